@@ -0,0 +1,42 @@
+// History database benchmarks. Run with `cargo bench --bench history`; compare against a
+// saved baseline (`-- --save-baseline main`, then `-- --baseline main`) before a release.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use wingman_lib::dev_tools::{export, first_page, list_all, memory_database, search, seed, PageCache, SEARCH_QUERIES};
+
+const ENTRIES: u32 = 10_000;
+
+fn bench_insert(c: &mut Criterion) {
+    c.bench_function("insert 1k entries", |b| {
+        b.iter_batched(
+            || memory_database().unwrap(),
+            |conn| seed(&conn, 1_000, 42).unwrap(),
+            BatchSize::PerIteration,
+        )
+    });
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let conn = memory_database().unwrap();
+    seed(&conn, ENTRIES, 42).unwrap();
+
+    c.bench_function("list 10k entries", |b| b.iter(|| list_all(black_box(&conn)).unwrap()));
+    c.bench_function("first page", |b| b.iter(|| first_page(black_box(&conn), None).unwrap()));
+    let cache = PageCache::new();
+    c.bench_function("first page (cached)", |b| {
+        b.iter(|| first_page(black_box(&conn), Some(&cache)).unwrap())
+    });
+    let mut group = c.benchmark_group("search 10k entries");
+    for query in SEARCH_QUERIES {
+        group.bench_function(query, |b| b.iter(|| search(black_box(&conn), query).unwrap()));
+    }
+    group.finish();
+    c.bench_function("export 10k entries", |b| b.iter(|| export(black_box(&conn)).unwrap()));
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_insert, bench_queries
+}
+criterion_main!(benches);
@@ -0,0 +1,158 @@
+// System accessibility preferences, so the UI can adapt: Reduce Motion turns slides into
+// fades (here for the native panel, in CSS for the frontend), and Increase Contrast /
+// Reduce Transparency are reasons to drop the vibrancy. A background thread polls the
+// OS and emits `accessibility-changed` whenever something flips, since neither macOS
+// nor Windows settings changes reach a menubar app without an observer per setting.
+//
+// Sources: NSWorkspace on macOS, SystemParametersInfo on Windows, and the GNOME
+// interface settings on Linux (left at the defaults elsewhere).
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Last polled preferences; None until the first read
+static CURRENT: Mutex<Option<AccessibilityPrefs>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AccessibilityPrefs {
+    pub reduce_motion: bool,
+    pub increase_contrast: bool,
+    pub reduce_transparency: bool,
+    /// VoiceOver, Narrator/NVDA or Orca is running
+    pub screen_reader: bool,
+}
+
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+fn read_prefs() -> AccessibilityPrefs {
+    use cocoa::base::{id, BOOL, NO};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace.is_null() {
+            return AccessibilityPrefs::default();
+        }
+        let reduce_motion: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+        let increase_contrast: BOOL = msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+        let reduce_transparency: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceTransparency];
+        let voice_over: BOOL = msg_send![workspace, isVoiceOverEnabled];
+        AccessibilityPrefs {
+            reduce_motion: reduce_motion != NO,
+            increase_contrast: increase_contrast != NO,
+            reduce_transparency: reduce_transparency != NO,
+            screen_reader: voice_over != NO,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn read_prefs() -> AccessibilityPrefs {
+    use windows::core::BOOL;
+    use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST, SPI_GETSCREENREADER,
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    fn flag(action: windows::Win32::UI::WindowsAndMessaging::SYSTEM_PARAMETERS_INFO_ACTION) -> Option<bool> {
+        let mut value = BOOL::default();
+        unsafe {
+            SystemParametersInfoW(
+                action,
+                0,
+                Some(&mut value as *mut BOOL as *mut _),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+        }
+        .ok()
+        .map(|_| value.as_bool())
+    }
+
+    let mut contrast = HIGHCONTRASTW {
+        cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+        ..Default::default()
+    };
+    let high_contrast = unsafe {
+        SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            contrast.cbSize,
+            Some(&mut contrast as *mut HIGHCONTRASTW as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .is_ok()
+        && contrast.dwFlags.contains(HCF_HIGHCONTRASTON);
+
+    AccessibilityPrefs {
+        // "Show animations in Windows" off
+        reduce_motion: flag(SPI_GETCLIENTAREAANIMATION).map(|on| !on).unwrap_or(false),
+        increase_contrast: high_contrast,
+        // Windows has no separate switch; high contrast themes are opaque
+        reduce_transparency: high_contrast,
+        screen_reader: flag(SPI_GETSCREENREADER).unwrap_or(false),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_prefs() -> AccessibilityPrefs {
+    fn gsettings(schema: &str, key: &str) -> Option<String> {
+        let output = std::process::Command::new("gsettings").args(["get", schema, key]).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    let high_contrast = gsettings("org.gnome.desktop.a11y.interface", "high-contrast").as_deref() == Some("true");
+    AccessibilityPrefs {
+        reduce_motion: gsettings("org.gnome.desktop.interface", "enable-animations").as_deref() == Some("false"),
+        increase_contrast: high_contrast,
+        reduce_transparency: high_contrast,
+        screen_reader: gsettings("org.gnome.desktop.a11y.applications", "screen-reader-enabled").as_deref()
+            == Some("true"),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn read_prefs() -> AccessibilityPrefs {
+    AccessibilityPrefs::default()
+}
+
+/// The current preferences, from the poller when it's running
+pub fn prefs() -> AccessibilityPrefs {
+    if let Some(prefs) = CURRENT.lock().ok().and_then(|current| *current) {
+        return prefs;
+    }
+    let prefs = read_prefs();
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = Some(prefs);
+    }
+    prefs
+}
+
+/// Poll the system preferences and emit `accessibility-changed` with the new ones
+pub fn start_monitor(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let prefs = read_prefs();
+        let previous = CURRENT.lock().ok().and_then(|mut current| current.replace(prefs));
+        if previous.is_some_and(|previous| previous != prefs) {
+            log::info!("Accessibility preferences changed: {:?}", prefs);
+            app.emit("accessibility-changed", prefs).ok();
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_accessibility_prefs() -> AccessibilityPrefs {
+    prefs()
+}
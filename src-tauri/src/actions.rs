@@ -0,0 +1,403 @@
+// The action registry behind the quick actions palette: every built-in tool the palette
+// offers, with its title, keywords for search and the Pro feature it needs, served by
+// list_actions and run by execute_action. Keeping the list here means the palette can
+// only offer what the backend can actually run.
+//
+// Ids: "transform:<name>" for transform_text_cmd, the command name for single-command
+// tools ("encode_base64", "generate_uuid"), "lorem:<paragraphs>", "window:<command>",
+// and "snippet:<id>" for one entry per saved snippet. Custom transformations and chains
+// run JavaScript in the webview, so the frontend adds those itself.
+//
+// The registry also feeds the editor's action bar (suggestions.rs): an action marked
+// with `suggest` is offered for text of the given content kinds. EDITOR_TOOLS are tools
+// with their own UI in the editor (the JWT viewer, a JSONPath prompt, ...) that the bar
+// suggests but the palette can't run, so list_actions leaves them out.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::clipboard::TransformParams;
+use crate::error::AppError;
+use crate::license::{has_pro_license, is_feature_enabled, ProFeature};
+use crate::storage::load_snippets;
+use crate::window::MAIN_WINDOW_LABEL;
+use ActionOutput::{Append, Replace, SideEffect};
+
+/// What the palette does with an action's text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionOutput {
+    /// Replace the selection (or the whole editor without one)
+    Replace,
+    /// Add to the end of the editor
+    Append,
+    /// Nothing to insert; the action acts on the app
+    SideEffect,
+}
+
+pub struct ActionDef {
+    pub id: &'static str,
+    pub title: &'static str,
+    description: &'static str,
+    section: &'static str,
+    keywords: &'static [&'static str],
+    pub pro: Option<ProFeature>,
+    output: ActionOutput,
+    /// Content kinds (see suggestions::content_kinds) the action bar offers this for
+    pub kinds: &'static [&'static str],
+    /// How useful the action is for text of those kinds, 0-1
+    pub relevance: f64,
+}
+
+const fn action(
+    id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    section: &'static str,
+    keywords: &'static [&'static str],
+    pro: Option<ProFeature>,
+    output: ActionOutput,
+) -> ActionDef {
+    ActionDef {
+        id,
+        title,
+        description,
+        section,
+        keywords,
+        pro,
+        output,
+        kinds: &[],
+        relevance: 0.0,
+    }
+}
+
+/// An editor tool for EDITOR_TOOLS: suggested, never listed in the palette
+const fn tool(id: &'static str, title: &'static str, pro: Option<ProFeature>) -> ActionDef {
+    action(id, title, "", "Editor", &[], pro, SideEffect)
+}
+
+impl ActionDef {
+    /// Offer the action in the editor's action bar for text of `kinds`
+    const fn suggest(self, kinds: &'static [&'static str], relevance: f64) -> Self {
+        ActionDef {
+            kinds,
+            relevance,
+            ..self
+        }
+    }
+}
+
+const FORMAT_LANGUAGES: &[&str] = &[
+    "json",
+    "xml",
+    "html",
+    "css",
+    "python",
+    "react",
+    "jsx",
+    "tsx",
+    "javascript",
+    "typescript",
+    "sql",
+    "graphql",
+    "go",
+    "rust",
+    "java",
+    "php",
+    "ruby",
+    "swift",
+    "kotlin",
+    "csharp",
+    "bash",
+    "c",
+    "cpp",
+    "markdown",
+];
+const MINIFY_LANGUAGES: &[&str] = &[
+    "json",
+    "css",
+    "react",
+    "jsx",
+    "tsx",
+    "javascript",
+    "typescript",
+    "html",
+    "xml",
+];
+
+const ENCODE: Option<ProFeature> = Some(ProFeature::EncodeDecode);
+
+const BUILTIN: &[ActionDef] = &[
+    action("transform:uppercase", "UPPERCASE", "Convert to upper case", "Text", &["upper", "caps"], None, Replace),
+    action("transform:lowercase", "lowercase", "Convert to lower case", "Text", &["lower"], None, Replace),
+    action("transform:titlecase", "Title Case", "Capitalize every word", "Text", &["title", "capitalize"], None, Replace),
+    action("transform:sentencecase", "Sentence case", "Capitalize the first word of each sentence", "Text", &["sentence"], None, Replace),
+    action("transform:trim", "Trim Whitespace", "Remove leading and trailing whitespace", "Text", &["strip", "spaces"], None, Replace),
+    action("transform:camelcase", "camelCase", "Convert to camelCase", "Case Conversion", &["camel", "variable"], None, Replace),
+    action("transform:snakecase", "snake_case", "Convert to snake_case", "Case Conversion", &["snake", "underscore"], None, Replace),
+    action("transform:kebabcase", "kebab-case", "Convert to kebab-case", "Case Conversion", &["kebab", "dash", "slug"], None, Replace),
+    action("transform:sort", "Sort Lines", "Sort lines alphabetically", "Lines", &["order", "alphabetical"], None, Replace)
+        .suggest(&["lines"], 0.3),
+    action("transform:deduplicate", "Remove Duplicate Lines", "Keep the first of each repeated line", "Lines", &["unique", "dedupe"], None, Replace)
+        .suggest(&["lines"], 0.3),
+    action("transform:reverse", "Reverse Lines", "Reverse the order of lines", "Lines", &["flip"], None, Replace),
+    action("transform:bulletlist", "Bullet List", "Prefix each line with a bullet", "Lines", &["list", "markdown"], None, Replace)
+        .suggest(&["lines"], 0.2),
+    action("transform:numberlines", "Number Lines", "Prefix each line with 1., 2., ...", "Lines", &["list", "numbered", "ordered"], None, Replace)
+        .suggest(&["lines"], 0.2),
+    action("transform:joinlines", "Join Lines", "Join lines with a comma", "Lines", &["merge", "comma", "csv"], None, Replace),
+    action("transform:splitlines", "Split to Lines", "Put each comma-separated item on its own line", "Lines", &["separate", "comma", "explode"], None, Replace),
+    action("transform:shufflelines", "Shuffle Lines", "Put lines in random order", "Lines", &["random", "shuffle"], None, Replace),
+    action("unix_to_human", "Unix → Human", "Convert Unix timestamp to readable date", "Timestamps", &["epoch", "date", "time"], None, Replace)
+        .suggest(&["timestamp"], 0.9),
+    action("human_to_unix", "Human → Unix", "Convert datetime to Unix timestamp", "Timestamps", &["epoch", "date", "time"], None, Replace),
+    action("get_current_timestamp", "Current Timestamp", "Insert current Unix timestamp", "Timestamps", &["now", "epoch"], None, Append),
+    action("encode_base64", "Base64 Encode", "Encode text to Base64", "Encode/Decode", &["b64"], ENCODE, Replace),
+    action("decode_base64", "Base64 Decode", "Decode Base64 to text", "Encode/Decode", &["b64"], ENCODE, Replace)
+        .suggest(&["base64"], 0.9),
+    action("encode_url", "URL Encode", "Percent-encode for URLs", "Encode/Decode", &["percent", "escape"], ENCODE, Replace),
+    action("decode_url", "URL Decode", "Decode percent-encoded text", "Encode/Decode", &["percent", "unescape"], ENCODE, Replace)
+        .suggest(&["url"], 0.5),
+    action("encode_html", "HTML Encode", "Escape HTML entities", "Encode/Decode", &["entities", "escape"], ENCODE, Replace),
+    action("decode_html", "HTML Decode", "Unescape HTML entities", "Encode/Decode", &["entities", "unescape"], ENCODE, Replace),
+    action("generate_uuid", "UUID v4", "Random UUID (standard)", "ID Generators", &["guid", "random"], None, Append),
+    action("generate_uuid_v7", "UUID v7", "Time-sortable UUID", "ID Generators", &["guid", "sortable"], None, Append),
+    action("generate_nanoid", "NanoID", "Compact URL-safe ID (21 chars)", "ID Generators", &["id", "random"], None, Append),
+    action("generate_short_hash", "Short Hash", "8-char random hash", "ID Generators", &["id", "random"], None, Append),
+    action("generate_prefixed_id", "Prefixed ID (usr_)", "ID with usr_ prefix", "ID Generators", &["id", "stripe"], None, Append),
+    action("generate_bulk_uuid", "Bulk UUIDs (10)", "Generate 10 UUIDs at once", "ID Generators", &["guid", "many"], None, Append),
+    action("lorem:1", "1 Paragraph", "Generate 1 paragraph", "Lorem Ipsum", &["placeholder", "dummy"], None, Append),
+    action("lorem:3", "3 Paragraphs", "Generate 3 paragraphs", "Lorem Ipsum", &["placeholder", "dummy"], None, Append),
+    action("lorem:5", "5 Paragraphs", "Generate 5 paragraphs", "Lorem Ipsum", &["placeholder", "dummy"], None, Append),
+    action("generate_md5", "MD5 Hash", "Generate MD5 hash of text", "Hash Generators", &["digest", "checksum"], None, Replace),
+    action("generate_sha1", "SHA-1 Hash", "Generate SHA-1 hash of text", "Hash Generators", &["digest", "checksum"], None, Replace),
+    action("generate_sha256", "SHA-256 Hash", "Generate SHA-256 hash of text", "Hash Generators", &["digest", "checksum"], None, Replace),
+    action("generate_sha512", "SHA-512 Hash", "Generate SHA-512 hash of text", "Hash Generators", &["digest", "checksum"], None, Replace),
+    action("window:hide_window", "Hide Window", "Hide Wingman", "Window", &["close", "dismiss"], None, SideEffect),
+    action("window:toggle_fullscreen", "Toggle Fullscreen", "Enter or leave fullscreen", "Window", &["maximize"], None, SideEffect),
+    action("window:toggle_focus_mode", "Toggle Focus Mode", "Fill the screen without distractions", "Window", &["zen", "maximize"], None, SideEffect),
+    action("window:cycle_window_preset", "Next Window Preset", "Move to the next saved window size and position", "Window", &["layout", "resize"], None, SideEffect),
+];
+
+const FORMATTING: Option<ProFeature> = Some(ProFeature::JsonXmlFormatting);
+
+/// Ids are the command that runs the tool, or the frontend detector action for tools
+/// that live there ("decode-jwt", "csv-to-json")
+const EDITOR_TOOLS: &[ActionDef] = &[
+    tool("format_code", "Format", FORMATTING).suggest(FORMAT_LANGUAGES, 0.9),
+    tool("minify_code", "Minify", FORMATTING).suggest(MINIFY_LANGUAGES, 0.6),
+    tool("decode-jwt", "Decode JWT", None).suggest(&["jwt"], 1.0),
+    tool("query_json", "Query JSON", None).suggest(&["json"], 0.5),
+    tool("sort_json_keys", "Sort Keys", None).suggest(&["json"], 0.4),
+    tool("json_to_typescript_interface", "To TypeScript", None).suggest(&["json"], 0.4),
+    tool("infer_json_schema", "Infer Schema", None).suggest(&["json"], 0.3),
+    tool("csv-to-json", "CSV to JSON", None).suggest(&["csv"], 0.9),
+    tool("csv-pretty-table", "Pretty Table", None).suggest(&["csv"], 0.6),
+    tool("parse_url", "Parse URL", None).suggest(&["url"], 0.7),
+    tool("url_to_markdown_link", "Markdown Link", None).suggest(&["url"], 0.4),
+    tool("format_stack_trace", "Clean Up Trace", None).suggest(&["stack_trace"], 0.9),
+    tool("extract_sql_params", "SQL Parameters", None).suggest(&["sql"], 0.4),
+];
+
+/// Every action the registry knows, palette actions first
+pub fn registry() -> impl Iterator<Item = &'static ActionDef> {
+    BUILTIN.iter().chain(EDITOR_TOOLS)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionInfo {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub section: String,
+    pub keywords: Vec<String>,
+    pub pro_feature: Option<ProFeature>,
+    /// Needs a Pro license the user doesn't have
+    pub locked: bool,
+    /// Runs on the editor text, so it's disabled while the editor is empty
+    pub needs_input: bool,
+    pub output: ActionOutput,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ActionPayload {
+    /// The selection, or the whole editor without one
+    pub text: String,
+    /// Separator, prefix and suffix for the parameterized transforms
+    pub params: Option<TransformParams>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionResult {
+    pub output: ActionOutput,
+    /// None for side-effect actions
+    pub text: Option<String>,
+}
+
+impl ActionDef {
+    fn info(&self, is_unlocked: &impl Fn(ProFeature) -> bool) -> ActionInfo {
+        ActionInfo {
+            id: self.id.to_string(),
+            title: self.title.to_string(),
+            description: self.description.to_string(),
+            section: self.section.to_string(),
+            keywords: self.keywords.iter().map(|k| k.to_string()).collect(),
+            pro_feature: self.pro,
+            locked: self.pro.is_some_and(|feature| !is_unlocked(feature)),
+            needs_input: self.output == Replace,
+            output: self.output,
+        }
+    }
+}
+
+/// Built-in actions followed by one per snippet, in palette order
+pub fn list(is_unlocked: impl Fn(ProFeature) -> bool) -> Vec<ActionInfo> {
+    let mut actions: Vec<ActionInfo> = BUILTIN.iter().map(|def| def.info(&is_unlocked)).collect();
+
+    match load_snippets() {
+        Ok(data) => {
+            let locked = !is_unlocked(ProFeature::Snippets);
+            actions.extend(data.snippets.into_iter().map(|snippet| ActionInfo {
+                id: format!("snippet:{}", snippet.id),
+                description: snippet.content.lines().next().unwrap_or_default().chars().take(80).collect(),
+                title: snippet.name,
+                section: "Snippets".to_string(),
+                keywords: snippet.tags,
+                pro_feature: Some(ProFeature::Snippets),
+                locked,
+                needs_input: false,
+                output: Append,
+            }));
+        }
+        Err(e) => log::warn!("Failed to load snippets for the action list: {}", e),
+    }
+    actions
+}
+
+fn text_result(output: ActionOutput, text: String) -> Result<ActionResult, AppError> {
+    Ok(ActionResult { output, text: Some(text) })
+}
+
+async fn run_window_action(app: &AppHandle, command: &str) -> Result<(), AppError> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .ok_or_else(|| AppError::not_found("Main window not found"))?;
+    match command {
+        "hide_window" => crate::hide_window(window.as_ref().window()).await,
+        "toggle_fullscreen" => crate::toggle_fullscreen(window.as_ref().window()).await,
+        "toggle_focus_mode" => crate::toggle_focus_mode(window).await.map(|_| ()),
+        "cycle_window_preset" => crate::window_presets::cycle_window_preset(app.clone()).map(|_| ()),
+        _ => Err(AppError::not_found(format!("Unknown window action: {}", command))),
+    }
+}
+
+/// Run a listed action on `payload`; Pro actions are refused without a license
+pub async fn execute(app: &AppHandle, id: &str, payload: ActionPayload) -> Result<ActionResult, AppError> {
+    if let Some(snippet_id) = id.strip_prefix("snippet:") {
+        if !is_feature_enabled(ProFeature::Snippets) {
+            return Err(AppError::pro_required());
+        }
+        let snippet = load_snippets()?
+            .snippets
+            .into_iter()
+            .find(|snippet| snippet.id == snippet_id)
+            .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", snippet_id)))?;
+        return text_result(ActionOutput::Append, snippet.content);
+    }
+
+    let def = BUILTIN
+        .iter()
+        .find(|def| def.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Unknown action: {}", id)))?;
+    if def.pro.is_some_and(|feature| !is_feature_enabled(feature)) {
+        return Err(AppError::pro_required());
+    }
+    if def.output == Replace && payload.text.trim().is_empty() {
+        return Err(AppError::invalid_input(format!("{} needs some text", def.title)));
+    }
+
+    let text = payload.text;
+    let output = def.output;
+    if let Some(name) = id.strip_prefix("transform:") {
+        return text_result(output, crate::transform_text_cmd(text, name.to_string(), payload.params)?);
+    }
+    if let Some(paragraphs) = id.strip_prefix("lorem:") {
+        let paragraphs = paragraphs.parse().unwrap_or(1);
+        return text_result(output, crate::generate_lorem_ipsum(paragraphs, "plain".to_string()));
+    }
+    if let Some(command) = id.strip_prefix("window:") {
+        run_window_action(app, command).await?;
+        return Ok(ActionResult { output, text: None });
+    }
+
+    let result = match id {
+        "unix_to_human" => crate::unix_to_human(text)?,
+        "human_to_unix" => crate::human_to_unix(text)?,
+        "get_current_timestamp" => crate::get_current_timestamp(Some(false)),
+        "encode_base64" => crate::encode_base64(text)?,
+        "decode_base64" => crate::decode_base64(text)?,
+        "encode_url" => crate::encode_url(text)?,
+        "decode_url" => crate::decode_url(text)?,
+        "encode_html" => crate::encode_html(text)?,
+        "decode_html" => crate::decode_html(text)?,
+        "generate_uuid" => crate::generate_uuid(),
+        "generate_uuid_v7" => crate::generate_uuid_v7(),
+        "generate_nanoid" => crate::generate_nanoid(Some(21)),
+        "generate_short_hash" => crate::generate_short_hash(Some(8)),
+        "generate_prefixed_id" => crate::generate_prefixed_id("usr_".to_string(), Some("nanoid".to_string()))?,
+        "generate_bulk_uuid" => crate::generate_bulk("uuid".to_string(), 10, None, None, None)?,
+        "generate_md5" => crate::generate_md5(text),
+        "generate_sha1" => crate::generate_sha1(text),
+        "generate_sha256" => crate::generate_sha256(text),
+        "generate_sha512" => crate::generate_sha512(text),
+        _ => return Err(AppError::not_found(format!("Action {} has no handler", id))),
+    };
+    text_result(output, result)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Everything the quick actions palette can offer
+#[tauri::command]
+pub fn list_actions() -> Vec<ActionInfo> {
+    let licensed = has_pro_license();
+    list(|_| licensed)
+}
+
+#[tauri::command]
+pub async fn execute_action(
+    app: AppHandle,
+    id: String,
+    payload: Option<ActionPayload>,
+) -> Result<ActionResult, AppError> {
+    execute(&app, &id, payload.unwrap_or_default()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_registry_is_consistent() {
+        let mut ids = HashSet::new();
+        for def in registry() {
+            assert!(ids.insert(def.id), "duplicate action id {}", def.id);
+            if let Some(name) = def.id.strip_prefix("transform:") {
+                assert!(crate::clipboard::TextTransform::from_name(name).is_some(), "{}", def.id);
+            }
+        }
+
+        let locked = list(|_| false);
+        let base64 = locked.iter().find(|a| a.id == "decode_base64").unwrap();
+        assert!(base64.locked);
+        assert_eq!(base64.pro_feature, Some(ProFeature::EncodeDecode));
+        assert!(!locked.iter().find(|a| a.id == "generate_uuid").unwrap().locked);
+        // Editor tools are suggested, not offered in the palette
+        assert!(!locked.iter().any(|a| a.id == "decode-jwt"));
+        assert!(registry().any(|def| def.id == "decode-jwt" && def.kinds == ["jwt"]));
+    }
+}
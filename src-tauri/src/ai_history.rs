@@ -0,0 +1,353 @@
+// Opt-in log of AI calls (prompt, preset, response, tokens) so a good rewrite isn't lost
+// when the panel closes before it's copied. premium::call_ai_feature records every call,
+// whichever feature made it. Rows live next to history in the profile database and are
+// pruned by the ai_history_retention_days setting.
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, State};
+use thiserror::Error;
+
+use crate::command_middleware::track;
+use crate::error::{AppError, ErrorCode};
+use crate::license::get_cached_license_key;
+use crate::links::{link_from_matching_entry, ItemRef, RELATION_AI_RESPONSE};
+use crate::premium::{load_ai_presets, request_ai_feature, AIResponse};
+use crate::storage::{load_custom_ai_prompts, load_settings};
+use crate::AppState;
+
+/// AI calls are made from places without app state (summaries, translation), so
+/// recording finds the database through this
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+#[derive(Error, Debug)]
+pub enum AIHistoryError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("AI interaction {0} not found")]
+    NotFound(i64),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AIInteraction {
+    pub id: i64,
+    /// Feature name sent to the proxy; the preset id for preset calls
+    pub feature: String,
+    pub preset_name: Option<String>,
+    pub prompt: String,
+    pub system_instructions: Option<String>,
+    pub response: String,
+    pub tokens_used: i64,
+    /// Interaction this one re-ran, if any
+    pub rerun_of: Option<i64>,
+    pub created_at: String,
+}
+
+/// Fields of a new row; id and created_at are assigned on insert
+pub struct NewInteraction<'a> {
+    pub feature: &'a str,
+    pub preset_name: Option<&'a str>,
+    pub prompt: &'a str,
+    pub system_instructions: Option<&'a str>,
+    pub response: &'a str,
+    pub tokens_used: i64,
+    pub rerun_of: Option<i64>,
+}
+
+pub fn init_ai_history_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ai_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            feature TEXT NOT NULL,
+            preset_name TEXT,
+            prompt TEXT NOT NULL,
+            system_instructions TEXT,
+            response TEXT NOT NULL,
+            tokens_used INTEGER NOT NULL DEFAULT 0,
+            rerun_of INTEGER,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_ai_history_created_at ON ai_history(created_at DESC)",
+        [],
+    )?;
+    Ok(())
+}
+
+const INTERACTION_COLUMNS: &str =
+    "id, feature, preset_name, prompt, system_instructions, response, tokens_used, rerun_of, created_at";
+
+fn map_interaction_row(row: &rusqlite::Row) -> rusqlite::Result<AIInteraction> {
+    Ok(AIInteraction {
+        id: row.get(0)?,
+        feature: row.get(1)?,
+        preset_name: row.get(2)?,
+        prompt: row.get(3)?,
+        system_instructions: row.get(4)?,
+        response: row.get(5)?,
+        tokens_used: row.get(6)?,
+        rerun_of: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+pub fn add_interaction(conn: &Connection, interaction: &NewInteraction) -> Result<i64, AIHistoryError> {
+    conn.execute(
+        "INSERT INTO ai_history (feature, preset_name, prompt, system_instructions, response, tokens_used, rerun_of)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            interaction.feature,
+            interaction.preset_name,
+            interaction.prompt,
+            interaction.system_instructions,
+            interaction.response,
+            interaction.tokens_used,
+            interaction.rerun_of
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn get_interaction(conn: &Connection, id: i64) -> Result<Option<AIInteraction>, AIHistoryError> {
+    let sql = format!("SELECT {} FROM ai_history WHERE id = ?1", INTERACTION_COLUMNS);
+    Ok(conn.query_row(&sql, [id], map_interaction_row).optional()?)
+}
+
+pub fn get_interactions(conn: &Connection, limit: u32, offset: u32) -> Result<Vec<AIInteraction>, AIHistoryError> {
+    let sql = format!(
+        "SELECT {} FROM ai_history ORDER BY created_at DESC, id DESC LIMIT ?1 OFFSET ?2",
+        INTERACTION_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let interactions = stmt
+        .query_map(rusqlite::params![limit, offset], map_interaction_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(interactions)
+}
+
+/// Substring match over prompts, responses and preset names
+pub fn search_interactions(conn: &Connection, query: &str, limit: u32) -> Result<Vec<AIInteraction>, AIHistoryError> {
+    let pattern = format!("%{}%", query);
+    let sql = format!(
+        "SELECT {} FROM ai_history
+         WHERE prompt LIKE ?1 OR response LIKE ?1 OR preset_name LIKE ?1
+         ORDER BY created_at DESC, id DESC LIMIT ?2",
+        INTERACTION_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let interactions = stmt
+        .query_map(rusqlite::params![pattern, limit], map_interaction_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(interactions)
+}
+
+pub fn delete_interaction(conn: &Connection, id: i64) -> Result<(), AIHistoryError> {
+    if conn.execute("DELETE FROM ai_history WHERE id = ?1", [id])? == 0 {
+        return Err(AIHistoryError::NotFound(id));
+    }
+    Ok(())
+}
+
+/// Delete interactions older than `retention_days`; 0 keeps everything.
+/// Returns how many rows were removed.
+pub fn apply_retention(conn: &Connection, retention_days: u32) -> Result<u32, AIHistoryError> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+    let removed = conn.execute(
+        "DELETE FROM ai_history WHERE created_at < datetime('now', ?1)",
+        [format!("-{} days", retention_days)],
+    )?;
+    Ok(removed as u32)
+}
+
+/// Name of the preset or custom prompt a feature id refers to
+fn preset_name(feature: &str) -> Option<String> {
+    let preset = load_ai_presets()
+        .ok()
+        .and_then(|config| config.presets.into_iter().find(|p| p.id == feature))
+        .map(|p| p.name);
+    preset.or_else(|| {
+        load_custom_ai_prompts()
+            .ok()?
+            .prompts
+            .into_iter()
+            .find(|p| p.id == feature)
+            .map(|p| p.name)
+    })
+}
+
+/// Called from setup so calls can be recorded
+pub fn init(app: &AppHandle) {
+    APP.get_or_init(|| app.clone());
+}
+
+/// Save a completed call when AI history is turned on. Failures are logged, never
+/// surfaced: the response has already been produced and must still reach the caller.
+pub fn record_if_enabled(
+    feature: &str,
+    prompt: &str,
+    system_instructions: Option<&str>,
+    response: &AIResponse,
+    rerun_of: Option<i64>,
+) -> Option<i64> {
+    let settings = load_settings().ok()?;
+    if !settings.ai_history_enabled {
+        return None;
+    }
+    let preset_name = preset_name(feature);
+    let interaction = NewInteraction {
+        feature,
+        preset_name: preset_name.as_deref(),
+        prompt,
+        system_instructions,
+        response: &response.result,
+        tokens_used: response.tokens_used_this_request,
+        rerun_of,
+    };
+
+    let state = APP.get()?.state::<AppState>();
+    let conn = state.db.lock().ok()?;
+    let result = add_interaction(&conn, &interaction)
+        .and_then(|id| apply_retention(&conn, settings.ai_history_retention_days).map(|_| id));
+    match result {
+        Ok(id) => {
+            // Provenance: the history entry the prompt was taken from
+            if let Err(e) = link_from_matching_entry(&conn, prompt, &ItemRef::ai(id), RELATION_AI_RESPONSE) {
+                log::warn!("Failed to link AI interaction {}: {}", id, e);
+            }
+            Some(id)
+        }
+        Err(e) => {
+            log::warn!("Failed to record AI interaction: {}", e);
+            None
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn list_ai_history(state: State<AppState>, limit: u32, offset: u32) -> Result<Vec<AIInteraction>, AppError> {
+    let conn = state.db.lock()?;
+    get_interactions(&conn, limit, offset).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn search_ai_history(state: State<AppState>, query: String, limit: u32) -> Result<Vec<AIInteraction>, AppError> {
+    let conn = state.db.lock()?;
+    search_interactions(&conn, &query, limit).map_err(AppError::from)
+}
+
+/// Send a past interaction's prompt and instructions again. The new response is
+/// recorded as its own interaction, linked back through `rerun_of`.
+#[tauri::command]
+pub async fn rerun_ai_interaction(state: State<'_, AppState>, id: i64) -> Result<AIResponse, AppError> {
+    track("rerun_ai_interaction", async move {
+        let interaction = {
+            let conn = state.db.lock()?;
+            get_interaction(&conn, id)?
+                .ok_or(AIHistoryError::NotFound(id))?
+        };
+        let license_key = get_cached_license_key().ok_or_else(|| AppError::new(ErrorCode::LicenseInvalid, "No active license key found"))?;
+
+        let response = request_ai_feature(
+            &license_key,
+            &interaction.prompt,
+            &interaction.feature,
+            interaction.system_instructions.as_deref(),
+        )
+        .await?;
+
+        record_if_enabled(
+            &interaction.feature,
+            &interaction.prompt,
+            interaction.system_instructions.as_deref(),
+            &response,
+            Some(interaction.id),
+        );
+        Ok(response)
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn delete_ai_interaction(state: State<AppState>, id: i64) -> Result<(), AppError> {
+    let conn = state.db.lock()?;
+    delete_interaction(&conn, id).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn clear_ai_history(state: State<AppState>) -> Result<(), AppError> {
+    let conn = state.db.lock()?;
+    conn.execute("DELETE FROM ai_history", [])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_ai_history_table(&conn).unwrap();
+        conn
+    }
+
+    fn add(conn: &Connection, prompt: &str, response: &str) -> i64 {
+        add_interaction(
+            conn,
+            &NewInteraction {
+                feature: "preset-formal",
+                preset_name: Some("Formal"),
+                prompt,
+                system_instructions: Some("Rewrite formally"),
+                response,
+                tokens_used: 42,
+                rerun_of: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_add_list_search_delete() {
+        let conn = test_conn();
+        let first = add(&conn, "hey whats up", "Good afternoon.");
+        add(&conn, "ship it", "Please proceed with the release.");
+
+        assert_eq!(get_interactions(&conn, 10, 0).unwrap().len(), 2);
+        let found = search_interactions(&conn, "afternoon", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, first);
+        assert_eq!(found[0].preset_name.as_deref(), Some("Formal"));
+        assert_eq!(found[0].tokens_used, 42);
+
+        delete_interaction(&conn, first).unwrap();
+        assert!(get_interaction(&conn, first).unwrap().is_none());
+        assert!(matches!(delete_interaction(&conn, first), Err(AIHistoryError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_retention() {
+        let conn = test_conn();
+        let old = add(&conn, "old", "old");
+        let recent = add(&conn, "recent", "recent");
+        conn.execute(
+            "UPDATE ai_history SET created_at = datetime('now', '-40 days') WHERE id = ?1",
+            [old],
+        )
+        .unwrap();
+
+        assert_eq!(apply_retention(&conn, 0).unwrap(), 0);
+        assert_eq!(apply_retention(&conn, 30).unwrap(), 1);
+        let remaining = get_interactions(&conn, 10, 0).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, recent);
+    }
+}
@@ -0,0 +1,242 @@
+// Runs AI workflows (see AIWorkflow in storage.rs): each step's output is the next
+// step's input. AI steps go through the Claude proxy like single-shot presets;
+// transform steps run locally. A run can be cancelled between or during steps.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+use crate::clipboard::{transform_text, TextTransform};
+use crate::command_middleware::track;
+use crate::error::{AppError, ErrorCode};
+use crate::license::get_cached_license_key;
+use crate::premium::{call_ai_feature, load_ai_presets};
+use crate::storage::{load_ai_workflows, load_custom_ai_prompts, AIWorkflowStep};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowStepResult {
+    pub step_id: String,
+    pub name: String,
+    pub output: String,
+    /// 0 for local transform steps
+    pub tokens_used: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowRunResult {
+    pub workflow_id: String,
+    pub output: String,
+    pub steps: Vec<WorkflowStepResult>,
+    pub total_tokens: i64,
+    /// From the last AI step; `None` if the workflow had none
+    pub tokens_remaining: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WorkflowProgress<'a> {
+    workflow_id: &'a str,
+    step_index: usize,
+    step_count: usize,
+    result: &'a WorkflowStepResult,
+}
+
+struct RunHandle {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl RunHandle {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        // Stores a permit if the run is between awaits, so the next AI call sees it
+        self.notify.notify_one();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// In-flight runs by workflow id; a workflow runs at most once at a time
+static RUNS: Mutex<Option<HashMap<String, Arc<RunHandle>>>> = Mutex::new(None);
+
+/// Removes the run from `RUNS` however it ends
+struct RunGuard {
+    workflow_id: String,
+    handle: Arc<RunHandle>,
+}
+
+impl RunGuard {
+    fn start(workflow_id: &str) -> Result<Self, String> {
+        let mut runs = RUNS.lock().map_err(|e| e.to_string())?;
+        let runs = runs.get_or_insert_with(HashMap::new);
+        if runs.contains_key(workflow_id) {
+            return Err("This workflow is already running".to_string());
+        }
+        let handle = Arc::new(RunHandle {
+            cancelled: AtomicBool::new(false),
+            notify: Notify::new(),
+        });
+        runs.insert(workflow_id.to_string(), handle.clone());
+        Ok(RunGuard {
+            workflow_id: workflow_id.to_string(),
+            handle,
+        })
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        if let Ok(mut runs) = RUNS.lock() {
+            if let Some(runs) = runs.as_mut() {
+                runs.remove(&self.workflow_id);
+            }
+        }
+    }
+}
+
+pub fn parse_transform(name: &str) -> Result<TextTransform, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| format!("Unknown transform: {}", name))
+}
+
+/// System prompt for an AI step: a saved preset, then a custom AI prompt, then the
+/// snapshot stored on the step
+fn resolve_system_prompt(step: &AIWorkflowStep) -> Result<String, String> {
+    if let Ok(config) = load_ai_presets() {
+        if let Some(preset) = config.presets.iter().find(|p| p.id == step.target_id) {
+            return Ok(preset.system_prompt.clone());
+        }
+    }
+    if let Ok(data) = load_custom_ai_prompts() {
+        if let Some(prompt) = data.prompts.iter().find(|p| p.id == step.target_id) {
+            return Ok(crate::custom_prompts::system_prompt_with_defaults(prompt));
+        }
+    }
+    step.system_prompt
+        .clone()
+        .ok_or_else(|| format!("AI preset not found for step \"{}\"", step.name))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Run a workflow's steps in order. Emits `ai-workflow-step` after each step so the
+/// UI can show intermediate results.
+#[tauri::command]
+pub async fn run_ai_workflow(app: AppHandle, workflow_id: String, text: String) -> Result<WorkflowRunResult, AppError> {
+    track("run_ai_workflow", async move {
+        let workflow = load_ai_workflows()?
+            .workflows
+            .into_iter()
+            .find(|w| w.id == workflow_id)
+            .ok_or_else(|| AppError::not_found(format!("Workflow not found: {}", workflow_id)))?;
+        if workflow.steps.is_empty() {
+            return Err(AppError::invalid_input("Workflow has no steps"));
+        }
+
+        let needs_ai = workflow.steps.iter().any(|s| s.step_type == "ai");
+        let license_key = if needs_ai {
+            Some(get_cached_license_key().ok_or_else(|| AppError::new(ErrorCode::LicenseInvalid, "No active license key found"))?)
+        } else {
+            None
+        };
+
+        let guard = RunGuard::start(&workflow.id)?;
+        let handle = guard.handle.clone();
+
+        let mut current = text;
+        let mut steps = Vec::with_capacity(workflow.steps.len());
+        let mut total_tokens = 0;
+        let mut tokens_remaining = None;
+
+        for (index, step) in workflow.steps.iter().enumerate() {
+            if handle.is_cancelled() {
+                return Err(AppError::new(ErrorCode::Cancelled, "Workflow cancelled"));
+            }
+
+            let (output, tokens_used) = match step.step_type.as_str() {
+                "transform" => (transform_text(&current, parse_transform(&step.target_id)?), 0),
+                "ai" => {
+                    let system_prompt = resolve_system_prompt(step)?;
+                    let license_key = license_key.as_deref().unwrap_or_default();
+                    let response = tokio::select! {
+                        response = call_ai_feature(license_key, &current, &step.target_id, Some(&system_prompt)) => {
+                            response.map_err(|e| AppError::from(e).with_context(format!("Step \"{}\" failed", step.name)))?
+                        }
+                        _ = handle.notify.notified() => return Err(AppError::new(ErrorCode::Cancelled, "Workflow cancelled")),
+                    };
+                    tokens_remaining = Some(response.tokens_remaining);
+                    (response.result, response.tokens_used_this_request)
+                }
+                other => return Err(AppError::invalid_input(format!("Unknown workflow step type: {}", other))),
+            };
+
+            total_tokens += tokens_used;
+            let result = WorkflowStepResult {
+                step_id: step.id.clone(),
+                name: step.name.clone(),
+                output: output.clone(),
+                tokens_used,
+            };
+            app.emit(
+                "ai-workflow-step",
+                WorkflowProgress {
+                    workflow_id: &workflow.id,
+                    step_index: index,
+                    step_count: workflow.steps.len(),
+                    result: &result,
+                },
+            )
+            .ok();
+            steps.push(result);
+            current = output;
+        }
+
+        Ok(WorkflowRunResult {
+            workflow_id: workflow.id,
+            output: current,
+            steps,
+            total_tokens,
+            tokens_remaining,
+        })
+    })
+    .await
+}
+
+/// Stop a running workflow. The AI request in flight is abandoned; tokens it already
+/// used are still counted by the proxy.
+#[tauri::command]
+pub fn cancel_ai_workflow(workflow_id: String) -> Result<bool, AppError> {
+    let runs = RUNS.lock()?;
+    match runs.as_ref().and_then(|runs| runs.get(&workflow_id)) {
+        Some(handle) => {
+            handle.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transform() {
+        assert!(matches!(parse_transform("BulletList"), Ok(TextTransform::BulletList)));
+        assert!(parse_transform("Shout").is_err());
+    }
+
+    #[test]
+    fn test_transform_steps_chain() {
+        let text = "b\na\nb";
+        let out = transform_text(text, parse_transform("RemoveDuplicateLines").unwrap());
+        let out = transform_text(&out, parse_transform("SortLines").unwrap());
+        assert_eq!(transform_text(&out, parse_transform("Uppercase").unwrap()), "A\nB");
+    }
+}
@@ -0,0 +1,194 @@
+//! Content-addressed storage for history attachments.
+//!
+//! Attachments arrive from the frontend as a JSON array of objects carrying a
+//! base64 data URL in `data`. Before they hit SQLite the payload is written to
+//! `attachments/<sha256>` under the app data dir and replaced by a `hash`
+//! field, so the `images` column only holds metadata. Reading reverses this so
+//! the frontend keeps receiving data URLs.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::storage::get_profile_data_dir;
+
+#[derive(Error, Debug)]
+pub enum AttachmentError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("Invalid attachment data: {0}")]
+    InvalidData(String),
+}
+
+pub fn get_attachments_dir() -> Result<PathBuf, AttachmentError> {
+    let dir = get_profile_data_dir()?.join("attachments");
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+    }
+    Ok(dir)
+}
+
+fn is_valid_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Split a `data:<mime>;base64,<payload>` URL into its mime type and payload
+fn parse_data_url(data: &str) -> Option<(&str, &str)> {
+    let rest = data.strip_prefix("data:")?;
+    let (header, payload) = rest.split_once(',')?;
+    let mime = header.strip_suffix(";base64")?;
+    Some((mime, payload))
+}
+
+/// Write attachment bytes to disk, returning their content hash.
+/// Identical attachments share a single file.
+pub fn store_attachment(bytes: &[u8]) -> Result<String, AttachmentError> {
+    let hash = format!("{:x}", Sha256::digest(bytes));
+    let path = get_attachments_dir()?.join(&hash);
+    if !path.exists() {
+        // Write to a temp name first so a crash can't leave a truncated file behind a valid hash
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &path)?;
+    }
+    Ok(hash)
+}
+
+pub fn load_attachment(hash: &str) -> Result<Vec<u8>, AttachmentError> {
+    if !is_valid_hash(hash) {
+        return Err(AttachmentError::InvalidData(format!("bad attachment hash: {}", hash)));
+    }
+    Ok(fs::read(get_attachments_dir()?.join(hash))?)
+}
+
+/// Move inline data URLs out of an attachments JSON array, returning the
+/// metadata-only JSON to store in SQLite. Entries that are already stored
+/// (or aren't base64 data URLs) are left untouched.
+pub fn externalize_attachments(json: &str) -> Result<String, AttachmentError> {
+    let mut items: Vec<Value> = serde_json::from_str(json)?;
+
+    for item in items.iter_mut() {
+        let Some(obj) = item.as_object_mut() else {
+            continue;
+        };
+        let Some(data) = obj.get("data").and_then(|d| d.as_str()) else {
+            continue;
+        };
+        let Some((mime, payload)) = parse_data_url(data) else {
+            continue;
+        };
+
+        let bytes = STANDARD
+            .decode(payload)
+            .map_err(|e| AttachmentError::InvalidData(e.to_string()))?;
+        let hash = store_attachment(&bytes)?;
+
+        if !obj.contains_key("mimeType") {
+            obj.insert("mimeType".to_string(), Value::String(mime.to_string()));
+        }
+        obj.remove("data");
+        obj.insert("hash".to_string(), Value::String(hash));
+    }
+
+    Ok(serde_json::to_string(&items)?)
+}
+
+/// Inverse of `externalize_attachments`: rebuild the `data` URL for each
+/// stored attachment. Missing files are skipped rather than failing the whole
+/// entry, so a deleted attachment only drops that one item.
+pub fn hydrate_attachments(json: &str) -> Result<String, AttachmentError> {
+    let items: Vec<Value> = serde_json::from_str(json)?;
+    let mut hydrated = Vec::with_capacity(items.len());
+
+    for mut item in items {
+        if let Some(obj) = item.as_object_mut() {
+            if let Some(hash) = obj.get("hash").and_then(|h| h.as_str()).map(|h| h.to_string()) {
+                let Ok(bytes) = load_attachment(&hash) else {
+                    continue;
+                };
+                let mime = obj
+                    .get("mimeType")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+                obj.remove("hash");
+                obj.insert(
+                    "data".to_string(),
+                    Value::String(format!("data:{};base64,{}", mime, STANDARD.encode(bytes))),
+                );
+            }
+        }
+        hydrated.push(item);
+    }
+
+    Ok(serde_json::to_string(&hydrated)?)
+}
+
+/// Hashes referenced by a metadata-only attachments JSON array
+pub fn referenced_hashes(json: &str) -> Vec<String> {
+    serde_json::from_str::<Vec<Value>>(json)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| item.get("hash").and_then(|h| h.as_str()))
+        .map(|h| h.to_string())
+        .collect()
+}
+
+/// Delete attachment files not present in `referenced`. Returns the number of files removed.
+pub fn remove_unreferenced(referenced: &HashSet<String>) -> Result<u32, AttachmentError> {
+    let dir = get_attachments_dir()?;
+    let mut removed = 0;
+
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Leftover temp files from an interrupted write are always garbage
+        let orphaned = if is_valid_hash(&name) {
+            !referenced.contains(&name)
+        } else {
+            name.ends_with(".tmp")
+        };
+        if orphaned && fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_data_url() {
+        assert_eq!(
+            parse_data_url("data:image/png;base64,iVBORw0K"),
+            Some(("image/png", "iVBORw0K"))
+        );
+        assert_eq!(parse_data_url("data:text/plain,hello"), None);
+        assert_eq!(parse_data_url("https://example.com/a.png"), None);
+    }
+
+    #[test]
+    fn test_is_valid_hash() {
+        assert!(is_valid_hash(&format!("{:x}", Sha256::digest(b"abc"))));
+        assert!(!is_valid_hash("../history.db"));
+        assert!(!is_valid_hash("abc"));
+    }
+
+    #[test]
+    fn test_referenced_hashes() {
+        let json = r#"[{"id":1,"hash":"aa"},{"id":2,"data":"data:text/plain;base64,aGk="}]"#;
+        assert_eq!(referenced_hashes(json), vec!["aa".to_string()]);
+        assert!(referenced_hashes("not json").is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+// Touch ID / Windows Hello confirmation in front of sensitive actions. The
+// authentication_gate settings pick which actions need it: revealing a stored credential,
+// opening history entries classify.rs flagged as sensitive, and turning privacy mode off.
+// Switching any of those off again needs a prompt too, so the gate can't be dropped
+// through update_settings.
+// With the sensitive-entries gate on, listed entries of that kind come back masked and
+// marked truncated, so the frontend fetches them through get_entry_content, which asks
+// first. A successful prompt covers further gated actions for a short grace period.
+// On macOS the device owner policy is used, so Macs without Touch ID fall back to the
+// login password.
+
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::classify::SmartTag;
+use crate::error::AppError;
+use crate::history::HistoryEntry;
+use crate::storage::{load_settings, AuthenticationGate};
+
+/// How long one successful prompt covers further gated actions
+const GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Shown instead of the content of masked entries
+const MASK: &str = "••••••••";
+
+static LAST_SUCCESS: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatedAction {
+    RevealCredential,
+    ViewSensitiveEntry,
+    DisablePrivacyMode,
+    /// Turning off one of the other gates
+    LowerGate,
+}
+
+impl GatedAction {
+    fn is_gated(self, gate: &AuthenticationGate) -> bool {
+        match self {
+            Self::RevealCredential => gate.reveal_credentials,
+            Self::ViewSensitiveEntry => gate.sensitive_entries,
+            Self::DisablePrivacyMode => gate.disable_privacy_mode,
+            Self::LowerGate => gate.reveal_credentials || gate.sensitive_entries || gate.disable_privacy_mode,
+        }
+    }
+
+    /// Shown in the system prompt after "Wingman is trying to"
+    fn reason(self) -> &'static str {
+        match self {
+            Self::RevealCredential => "show a stored credential",
+            Self::ViewSensitiveEntry => "show a sensitive history entry",
+            Self::DisablePrivacyMode => "turn off privacy mode",
+            Self::LowerGate => "stop asking for authentication",
+        }
+    }
+}
+
+pub fn is_gated(action: GatedAction) -> bool {
+    load_settings()
+        .map(|settings| action.is_gated(&settings.authentication_gate))
+        .unwrap_or(false)
+}
+
+/// Whether `next` turns off a gate that's on in `previous`
+pub fn lowers(previous: &AuthenticationGate, next: &AuthenticationGate) -> bool {
+    (previous.reveal_credentials && !next.reveal_credentials)
+        || (previous.sensitive_entries && !next.sensitive_entries)
+        || (previous.disable_privacy_mode && !next.disable_privacy_mode)
+}
+
+/// Whether comma-separated smart tags include a sensitive kind
+pub fn has_sensitive_tag(smart_tags: Option<&str>) -> bool {
+    smart_tags
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|tag| SmartTag::parse(tag.trim()))
+        .any(|tag| tag.is_sensitive())
+}
+
+pub fn entry_is_sensitive(conn: &Connection, id: i64) -> rusqlite::Result<bool> {
+    let smart_tags: Option<Option<String>> = conn
+        .query_row("SELECT smart_tags FROM history WHERE id = ?1", [id], |row| row.get(0))
+        .optional()?;
+    Ok(has_sensitive_tag(smart_tags.flatten().as_deref()))
+}
+
+/// Mask the content of sensitive entries; they're marked truncated so the frontend
+/// loads the real content through get_entry_content
+pub fn mask_entries(entries: &mut [HistoryEntry]) {
+    for entry in entries.iter_mut().filter(|entry| has_sensitive_tag(entry.smart_tags.as_deref())) {
+        entry.content = MASK.to_string();
+        entry.html = None;
+        entry.summary = None;
+        entry.content_truncated = true;
+    }
+}
+
+/// Mask sensitive entries if the gate for them is on
+pub fn mask_if_gated(mut entries: Vec<HistoryEntry>) -> Vec<HistoryEntry> {
+    if is_gated(GatedAction::ViewSensitiveEntry) {
+        mask_entries(&mut entries);
+    }
+    entries
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "LocalAuthentication", kind = "framework")]
+extern "C" {}
+
+/// Show the system prompt and block until it's answered
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+fn prompt(reason: &str) -> Result<(), AppError> {
+    use block::ConcreteBlock;
+    use cocoa::base::{id, nil, BOOL, YES};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    use crate::error::ErrorCode;
+
+    /// LAPolicyDeviceOwnerAuthentication: biometrics, then the login password
+    const POLICY: i64 = 2;
+    /// LAErrorUserCancel, LAErrorSystemCancel, LAErrorAppCancel
+    const CANCEL_CODES: [i64; 3] = [-2, -4, -9];
+
+    unsafe {
+        let context: id = msg_send![class!(LAContext), new];
+        if context.is_null() {
+            return Err(AppError::unsupported("LocalAuthentication isn't available"));
+        }
+        let mut error: id = nil;
+        let available: BOOL = msg_send![context, canEvaluatePolicy: POLICY error: &mut error];
+        if available != YES {
+            let () = msg_send![context, release];
+            return Err(AppError::unsupported("Touch ID and the login password can't be used to authenticate"));
+        }
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let reply = ConcreteBlock::new(move |success: BOOL, error: id| {
+            let code: i64 = if error.is_null() { 0 } else { msg_send![error, code] };
+            sender.send((success == YES, code)).ok();
+        });
+        let reply = reply.copy();
+        let reason = NSString::alloc(nil).init_str(reason);
+        let () = msg_send![context, evaluatePolicy: POLICY localizedReason: reason reply: &*reply];
+        let answer = receiver.recv();
+        let () = msg_send![reason, release];
+        let () = msg_send![context, release];
+
+        match answer {
+            Ok((true, _)) => Ok(()),
+            Ok((false, code)) if CANCEL_CODES.contains(&code) => {
+                Err(AppError::new(ErrorCode::Cancelled, "Authentication was cancelled"))
+            }
+            _ => Err(AppError::new(ErrorCode::PermissionDenied, "Authentication failed")),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn prompt(reason: &str) -> Result<(), AppError> {
+    use windows::core::HSTRING;
+    use windows::Security::Credentials::UI::{UserConsentVerificationResult, UserConsentVerifier};
+
+    use crate::error::ErrorCode;
+
+    let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(reason))
+        .and_then(|operation| operation.join())
+        .map_err(|e| AppError::internal(format!("Windows Hello failed: {}", e)))?;
+    match result {
+        UserConsentVerificationResult::Verified => Ok(()),
+        UserConsentVerificationResult::Canceled => {
+            Err(AppError::new(ErrorCode::Cancelled, "Authentication was cancelled"))
+        }
+        UserConsentVerificationResult::DeviceNotPresent
+        | UserConsentVerificationResult::NotConfiguredForUser
+        | UserConsentVerificationResult::DisabledByPolicy => {
+            Err(AppError::unsupported("Windows Hello isn't set up on this device"))
+        }
+        _ => Err(AppError::new(ErrorCode::PermissionDenied, "Authentication failed")),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn prompt(_reason: &str) -> Result<(), AppError> {
+    Err(AppError::unsupported("System authentication isn't available on this platform"))
+}
+
+/// Prompt off the async runtime's worker threads; the call blocks until answered
+pub async fn authenticate(reason: String) -> Result<(), AppError> {
+    tauri::async_runtime::spawn_blocking(move || prompt(&reason))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))??;
+    if let Ok(mut last) = LAST_SUCCESS.lock() {
+        *last = Some(Instant::now());
+    }
+    Ok(())
+}
+
+/// Ask for authentication if `action` is gated and no prompt succeeded recently
+pub async fn ensure(action: GatedAction) -> Result<(), AppError> {
+    if !is_gated(action) {
+        return Ok(());
+    }
+    let recent = LAST_SUCCESS
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .is_some_and(|at| at.elapsed() < GRACE_PERIOD);
+    if recent {
+        return Ok(());
+    }
+    authenticate(action.reason().to_string()).await
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Ask the user to confirm it's them with Touch ID (or the login password) on macOS and
+/// Windows Hello on Windows. Fails with `cancelled`, `permission_denied` or `unsupported`.
+#[tauri::command]
+pub async fn require_authentication(reason: String) -> Result<(), AppError> {
+    if reason.trim().is_empty() {
+        return Err(AppError::invalid_input("A reason is required"));
+    }
+    authenticate(reason).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turning_a_gate_off_is_lowering() {
+        let on = AuthenticationGate {
+            reveal_credentials: true,
+            sensitive_entries: true,
+            disable_privacy_mode: false,
+        };
+        let mut off = on;
+        off.sensitive_entries = false;
+        assert!(lowers(&on, &off));
+        assert!(!lowers(&off, &on));
+        assert!(!lowers(&on, &on));
+        assert!(!lowers(&AuthenticationGate::default(), &AuthenticationGate::default()));
+    }
+
+    #[test]
+    fn test_only_sensitive_entries_are_masked() {
+        assert!(has_sensitive_tag(Some("url, credit_card")));
+        assert!(!has_sensitive_tag(Some("url,email")));
+        assert!(!has_sensitive_tag(None));
+
+        let conn = Connection::open_in_memory().unwrap();
+        crate::history::create_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO history (id, content, character_count, word_count, line_count, smart_tags)
+             VALUES (1, '4111 1111 1111 1111', 19, 4, 1, 'credit_card'), (2, 'hello', 5, 1, 1, NULL)",
+            [],
+        )
+        .unwrap();
+        assert!(entry_is_sensitive(&conn, 1).unwrap());
+        assert!(!entry_is_sensitive(&conn, 2).unwrap());
+        assert!(!entry_is_sensitive(&conn, 3).unwrap());
+
+        let mut entries = crate::history::get_entries(&conn, 10, 0).unwrap();
+        mask_entries(&mut entries);
+        let card = entries.iter().find(|e| e.id == 1).unwrap();
+        assert_eq!(card.content, MASK);
+        assert!(card.content_truncated);
+        assert_eq!(entries.iter().find(|e| e.id == 2).unwrap().content, "hello");
+    }
+}
@@ -0,0 +1,269 @@
+// Launch at login, registered with the OS directly: SMAppService on macOS, the
+// per-user Run key on Windows and an XDG autostart entry on Linux. The system is
+// the source of truth; AppSettings.launch_at_login mirrors it for the UI.
+
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::storage::{load_settings, save_settings};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchAtLoginStatus {
+    pub enabled: bool,
+    /// Registered, but the user still has to allow it in System Settings > Login Items
+    pub requires_approval: bool,
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::LaunchAtLoginStatus;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    // SMAppServiceStatus
+    const STATUS_ENABLED: i64 = 1;
+    const STATUS_REQUIRES_APPROVAL: i64 = 2;
+
+    /// `[SMAppService mainAppService]`, loading ServiceManagement on first use
+    #[allow(deprecated)]
+    unsafe fn main_app_service() -> Result<id, String> {
+        let framework_path = NSString::alloc(nil).init_str("/System/Library/Frameworks/ServiceManagement.framework");
+        let bundle: id = msg_send![class!(NSBundle), bundleWithPath: framework_path];
+        if bundle != nil {
+            let _: bool = msg_send![bundle, load];
+        }
+        let class = objc::runtime::Class::get("SMAppService")
+            .ok_or_else(|| "Launch at login requires macOS 13 or later".to_string())?;
+        let service: id = msg_send![class, mainAppService];
+        if service == nil {
+            return Err("Failed to get the app's login item service".to_string());
+        }
+        Ok(service)
+    }
+
+    #[allow(deprecated)]
+    unsafe fn error_message(error: id) -> String {
+        if error == nil {
+            return "Unknown error".to_string();
+        }
+        let description: id = msg_send![error, localizedDescription];
+        let c_str: *const std::os::raw::c_char = msg_send![description, UTF8String];
+        if c_str.is_null() {
+            return "Unknown error".to_string();
+        }
+        std::ffi::CStr::from_ptr(c_str).to_string_lossy().into_owned()
+    }
+
+    #[allow(deprecated)]
+    pub fn status() -> Result<LaunchAtLoginStatus, String> {
+        unsafe {
+            let service = main_app_service()?;
+            let status: i64 = msg_send![service, status];
+            Ok(LaunchAtLoginStatus {
+                enabled: status == STATUS_ENABLED || status == STATUS_REQUIRES_APPROVAL,
+                requires_approval: status == STATUS_REQUIRES_APPROVAL,
+            })
+        }
+    }
+
+    #[allow(deprecated)]
+    pub fn set_enabled(enabled: bool) -> Result<(), String> {
+        unsafe {
+            let service = main_app_service()?;
+            let mut error: id = nil;
+            let ok: bool = if enabled {
+                msg_send![service, registerAndReturnError: &mut error]
+            } else {
+                msg_send![service, unregisterAndReturnError: &mut error]
+            };
+            if ok {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Failed to {} login item: {}",
+                    if enabled { "register" } else { "unregister" },
+                    error_message(error)
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::LaunchAtLoginStatus;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    const VALUE_NAME: &str = "Wingman";
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    fn reg(args: &[&str]) -> Result<std::process::Output, String> {
+        Command::new("reg")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| format!("Failed to run reg: {}", e))
+    }
+
+    pub fn status() -> Result<LaunchAtLoginStatus, String> {
+        let output = reg(&["query", RUN_KEY, "/v", VALUE_NAME])?;
+        Ok(LaunchAtLoginStatus {
+            enabled: output.status.success(),
+            requires_approval: false,
+        })
+    }
+
+    pub fn set_enabled(enabled: bool) -> Result<(), String> {
+        let output = if enabled {
+            let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+            let command = format!("\"{}\"", exe.display());
+            reg(&["add", RUN_KEY, "/v", VALUE_NAME, "/t", "REG_SZ", "/d", &command, "/f"])?
+        } else {
+            if !status()?.enabled {
+                return Ok(());
+            }
+            reg(&["delete", RUN_KEY, "/v", VALUE_NAME, "/f"])?
+        };
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Failed to update the Run key: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::LaunchAtLoginStatus;
+    use std::path::PathBuf;
+
+    fn desktop_file_path() -> Result<PathBuf, String> {
+        dirs::config_dir()
+            .map(|dir| dir.join("autostart").join("wingman.desktop"))
+            .ok_or_else(|| "Could not find the config directory".to_string())
+    }
+
+    /// The AppImage itself rather than the binary in its temporary mount
+    fn executable_path() -> Result<String, String> {
+        if let Ok(appimage) = std::env::var("APPIMAGE") {
+            return Ok(appimage);
+        }
+        std::env::current_exe()
+            .map(|p| p.to_string_lossy().to_string())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn status() -> Result<LaunchAtLoginStatus, String> {
+        Ok(LaunchAtLoginStatus {
+            enabled: desktop_file_path()?.exists(),
+            requires_approval: false,
+        })
+    }
+
+    pub fn set_enabled(enabled: bool) -> Result<(), String> {
+        let path = desktop_file_path()?;
+        if enabled {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(&path, super::desktop_entry(&executable_path()?)).map_err(|e| e.to_string())
+        } else if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| e.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// XDG autostart entry. Exec arguments are quoted per the Desktop Entry spec.
+#[cfg_attr(any(target_os = "macos", target_os = "windows"), allow(dead_code))]
+fn desktop_entry(exec_path: &str) -> String {
+    let mut quoted = String::with_capacity(exec_path.len() + 2);
+    quoted.push('"');
+    for c in exec_path.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Wingman\n\
+         Exec={}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        quoted
+    )
+}
+
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    platform::set_enabled(enabled)?;
+    log::info!("Launch at login {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// Bring the stored setting in line with the system, e.g. after the user removed
+/// the login item in System Settings. Called once at startup.
+pub fn sync_setting() {
+    let status = match platform::status() {
+        Ok(status) => status,
+        Err(e) => {
+            log::debug!("Launch at login status unavailable: {}", e);
+            return;
+        }
+    };
+    if let Ok(mut settings) = load_settings() {
+        if settings.launch_at_login != status.enabled {
+            settings.launch_at_login = status.enabled;
+            if let Err(e) = save_settings(&settings) {
+                log::warn!("Failed to sync launch at login setting: {}", e);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn set_launch_at_login(enabled: bool) -> Result<LaunchAtLoginStatus, AppError> {
+    set_enabled(enabled)?;
+    let status = platform::status()?;
+
+    let mut settings = load_settings()?;
+    settings.launch_at_login = status.enabled;
+    save_settings(&settings)?;
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn get_launch_at_login() -> Result<LaunchAtLoginStatus, AppError> {
+    platform::status().map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry_quotes_exec() {
+        let entry = desktop_entry("/opt/My Apps/wingman$1%");
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Exec=\"/opt/My Apps/wingman\\$1%%\"\n"));
+    }
+}
@@ -0,0 +1,792 @@
+// Schema-less decoding of binary wire payloads copied from logs: MessagePack, CBOR and
+// protobuf, into a JSON tree for inspection. The payload is pasted as base64 (standard or
+// URL-safe) or hex.
+//
+// Without a hint every format is tried and the first one that accounts for every byte
+// wins; any other format that also decodes cleanly is listed as an alternative, since
+// short payloads are often valid in more than one.
+//
+// Values JSON can't hold directly are spelled out:
+//
+// - Byte strings become "0x..." hex.
+// - Non-string map keys are rendered as text.
+// - CBOR tags, MessagePack extensions and CBOR simple values become objects keyed
+//   "$tag", "$ext" and "$simple".
+// - Non-finite floats become strings.
+//
+// Protobuf has no field names or types on the wire, so messages are objects keyed by field
+// number, repeated fields are arrays, and a length-delimited field is shown as a nested
+// message if it parses as one, else as text if it's UTF-8, else as bytes (what
+// `protoc --decode_raw` does). A varint length prefix or a gRPC frame header around the
+// message is detected and stripped.
+//
+// Also here: hex dumps (an offset / hex / ASCII view like `hexdump -C`, and reading one
+// back) and hex <-> base64 conversion.
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Number, Value};
+
+use crate::error::AppError;
+
+/// Nesting deeper than this is treated as garbage rather than recursed into
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryFormat {
+    #[serde(alias = "messagepack")]
+    Msgpack,
+    Cbor,
+    #[serde(alias = "proto")]
+    Protobuf,
+}
+
+const FORMATS: [BinaryFormat; 3] = [BinaryFormat::Msgpack, BinaryFormat::Cbor, BinaryFormat::Protobuf];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryDecodeResult {
+    pub format: BinaryFormat,
+    pub value: Value,
+    /// Pretty-printed `value` for the editor
+    pub output: String,
+    pub bytes: usize,
+    /// Framing stripped before decoding: "length_prefix" or "grpc"
+    pub framing: Option<String>,
+    /// Other formats that also decode the whole payload
+    pub alternatives: Vec<BinaryFormat>,
+}
+
+/// Decoding failed: (what went wrong, at which byte offset)
+type DecodeError = (String, usize);
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn fail<T>(&self, message: impl Into<String>) -> Result<T, DecodeError> {
+        Err((message.into(), self.pos))
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if n > self.bytes.len() - self.pos {
+            return self.fail(format!("needs {} more bytes, {} left", n, self.bytes.len() - self.pos));
+        }
+        let slice = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn uint(&mut self, n: usize) -> Result<u64, DecodeError> {
+        Ok(self.take(n)?.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        self.fail("varint longer than 10 bytes")
+    }
+
+    /// A length read from the payload, checked against what's left so a corrupt length
+    /// can't trigger a huge allocation
+    fn length(&self, length: u64) -> Result<usize, DecodeError> {
+        match usize::try_from(length) {
+            Ok(length) if length <= self.bytes.len() - self.pos => Ok(length),
+            _ => self.fail(format!("length {} runs past the end", length)),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", digits)
+}
+
+fn float(value: f64) -> Value {
+    Number::from_f64(value)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+/// JSON object key for a decoded map key
+fn key_text(key: Value) -> String {
+    match key {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+fn check_depth(reader: &Reader, depth: usize) -> Result<(), DecodeError> {
+    if depth > MAX_DEPTH {
+        return reader.fail(format!("nested more than {} levels", MAX_DEPTH));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// MessagePack
+// ============================================================================
+
+fn msgpack_value(reader: &mut Reader, depth: usize) -> Result<Value, DecodeError> {
+    check_depth(reader, depth)?;
+    let marker = reader.byte()?;
+    let value = match marker {
+        0x00..=0x7f => json!(marker),
+        0x80..=0x8f => msgpack_map(reader, (marker & 0x0f) as u64, depth)?,
+        0x90..=0x9f => msgpack_array(reader, (marker & 0x0f) as u64, depth)?,
+        0xa0..=0xbf => msgpack_str(reader, (marker & 0x1f) as u64)?,
+        0xc0 => Value::Null,
+        0xc2 => json!(false),
+        0xc3 => json!(true),
+        0xc4..=0xc6 => {
+            let n = reader.uint(1 << (marker - 0xc4))?;
+            let length = reader.length(n)?;
+            json!(hex(reader.take(length)?))
+        }
+        0xc7..=0xc9 => {
+            let n = reader.uint(1 << (marker - 0xc7))?;
+            msgpack_ext(reader, n)?
+        }
+        0xca => float(f32::from_bits(reader.uint(4)? as u32) as f64),
+        0xcb => float(f64::from_bits(reader.uint(8)?)),
+        0xcc..=0xcf => json!(reader.uint(1 << (marker - 0xcc))?),
+        0xd0 => json!(reader.uint(1)? as u8 as i8),
+        0xd1 => json!(reader.uint(2)? as u16 as i16),
+        0xd2 => json!(reader.uint(4)? as u32 as i32),
+        0xd3 => json!(reader.uint(8)? as i64),
+        0xd4..=0xd8 => msgpack_ext(reader, 1 << (marker - 0xd4))?,
+        0xd9..=0xdb => {
+            let n = reader.uint(1 << (marker - 0xd9))?;
+            msgpack_str(reader, n)?
+        }
+        0xdc | 0xdd => {
+            let n = reader.uint(if marker == 0xdc { 2 } else { 4 })?;
+            msgpack_array(reader, n, depth)?
+        }
+        0xde | 0xdf => {
+            let n = reader.uint(if marker == 0xde { 2 } else { 4 })?;
+            msgpack_map(reader, n, depth)?
+        }
+        0xe0..=0xff => json!(marker as i8),
+        0xc1 => {
+            reader.pos -= 1;
+            return reader.fail("0xc1 is never used in MessagePack");
+        }
+    };
+    Ok(value)
+}
+
+fn msgpack_str(reader: &mut Reader, length: u64) -> Result<Value, DecodeError> {
+    let length = reader.length(length)?;
+    let bytes = reader.take(length)?;
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(json!(text)),
+        Err(_) => reader.fail("string is not UTF-8"),
+    }
+}
+
+fn msgpack_array(reader: &mut Reader, count: u64, depth: usize) -> Result<Value, DecodeError> {
+    // Every element takes at least a byte
+    let count = reader.length(count)?;
+    (0..count)
+        .map(|_| msgpack_value(reader, depth + 1))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+fn msgpack_map(reader: &mut Reader, count: u64, depth: usize) -> Result<Value, DecodeError> {
+    let count = reader.length(count)?;
+    let mut map = Map::new();
+    for _ in 0..count {
+        let key = key_text(msgpack_value(reader, depth + 1)?);
+        map.insert(key, msgpack_value(reader, depth + 1)?);
+    }
+    Ok(Value::Object(map))
+}
+
+fn msgpack_ext(reader: &mut Reader, length: u64) -> Result<Value, DecodeError> {
+    let ext_type = reader.byte()? as i8;
+    let length = reader.length(length)?;
+    let data = reader.take(length)?;
+    // Type -1 is the standard timestamp extension
+    if ext_type == -1 {
+        let (seconds, nanos) = match data.len() {
+            4 => (u32::from_be_bytes(data.try_into().unwrap_or_default()) as i64, 0),
+            8 => {
+                let raw = u64::from_be_bytes(data.try_into().unwrap_or_default());
+                ((raw & 0x3_ffff_ffff) as i64, (raw >> 34) as u32)
+            }
+            12 => (
+                i64::from_be_bytes(data[4..].try_into().unwrap_or_default()),
+                u32::from_be_bytes(data[..4].try_into().unwrap_or_default()),
+            ),
+            _ => return reader.fail("timestamp extension must be 4, 8 or 12 bytes"),
+        };
+        if let Some(time) = chrono::DateTime::from_timestamp(seconds, nanos) {
+            return Ok(json!(time.to_rfc3339()));
+        }
+    }
+    Ok(json!({ "$ext": ext_type, "data": hex(data) }))
+}
+
+// ============================================================================
+// CBOR
+// ============================================================================
+
+/// Argument of a CBOR head; None for indefinite length
+fn cbor_argument(reader: &mut Reader, info: u8) -> Result<Option<u64>, DecodeError> {
+    match info {
+        0..=23 => Ok(Some(info as u64)),
+        24..=27 => Ok(Some(reader.uint(1 << (info - 24))?)),
+        31 => Ok(None),
+        _ => reader.fail(format!("reserved additional info {}", info)),
+    }
+}
+
+fn half_float(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f64;
+    let magnitude = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent as i32 - 15),
+    };
+    if bits & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Whether the next byte is the "break" that ends an indefinite-length item
+fn cbor_break(reader: &mut Reader) -> Result<bool, DecodeError> {
+    if reader.bytes.get(reader.pos) == Some(&0xff) {
+        reader.pos += 1;
+        return Ok(true);
+    }
+    if reader.at_end() {
+        return reader.fail("indefinite-length item is missing its break");
+    }
+    Ok(false)
+}
+
+/// Bytes of a byte or text string, joining the chunks of an indefinite-length one
+fn cbor_string(reader: &mut Reader, major: u8, length: Option<u64>) -> Result<Vec<u8>, DecodeError> {
+    match length {
+        Some(length) => {
+            let length = reader.length(length)?;
+            Ok(reader.take(length)?.to_vec())
+        }
+        None => {
+            let mut bytes = Vec::new();
+            while !cbor_break(reader)? {
+                let head = reader.byte()?;
+                if head >> 5 != major || head & 0x1f == 31 {
+                    return reader.fail("bad chunk in indefinite-length string");
+                }
+                let length = cbor_argument(reader, head & 0x1f)?;
+                bytes.extend(cbor_string(reader, major, length)?);
+            }
+            Ok(bytes)
+        }
+    }
+}
+
+fn cbor_value(reader: &mut Reader, depth: usize) -> Result<Value, DecodeError> {
+    check_depth(reader, depth)?;
+    let head = reader.byte()?;
+    let (major, info) = (head >> 5, head & 0x1f);
+    let argument = cbor_argument(reader, info)?;
+    let definite = |reader: &Reader| match argument {
+        Some(n) => Ok(n),
+        None => reader.fail("indefinite length not allowed here"),
+    };
+
+    let value = match major {
+        0 => json!(definite(reader)?),
+        1 => {
+            let n = definite(reader)?;
+            match i64::try_from(n) {
+                Ok(n) => json!(-1 - n),
+                // Below i64::MIN; exact as text
+                Err(_) => json!(format!("-{}", n as u128 + 1)),
+            }
+        }
+        2 => json!(hex(&cbor_string(reader, major, argument)?)),
+        3 => match String::from_utf8(cbor_string(reader, major, argument)?) {
+            Ok(text) => json!(text),
+            Err(_) => return reader.fail("text string is not UTF-8"),
+        },
+        4 => {
+            let mut items = Vec::new();
+            match argument {
+                Some(count) => {
+                    for _ in 0..reader.length(count)? {
+                        items.push(cbor_value(reader, depth + 1)?);
+                    }
+                }
+                None => {
+                    while !cbor_break(reader)? {
+                        items.push(cbor_value(reader, depth + 1)?);
+                    }
+                }
+            }
+            Value::Array(items)
+        }
+        5 => {
+            let mut map = Map::new();
+            let mut entry = |reader: &mut Reader| -> Result<(), DecodeError> {
+                let key = key_text(cbor_value(reader, depth + 1)?);
+                map.insert(key, cbor_value(reader, depth + 1)?);
+                Ok(())
+            };
+            match argument {
+                Some(count) => {
+                    for _ in 0..reader.length(count)? {
+                        entry(reader)?;
+                    }
+                }
+                None => {
+                    while !cbor_break(reader)? {
+                        entry(reader)?;
+                    }
+                }
+            }
+            Value::Object(map)
+        }
+        6 => json!({ "$tag": definite(reader)?, "value": cbor_value(reader, depth + 1)? }),
+        _ => match (info, argument) {
+            (20, _) => json!(false),
+            (21, _) => json!(true),
+            (22, _) | (23, _) => Value::Null,
+            (25, Some(bits)) => float(half_float(bits as u16)),
+            (26, Some(bits)) => float(f32::from_bits(bits as u32) as f64),
+            (27, Some(bits)) => float(f64::from_bits(bits)),
+            (31, _) => return reader.fail("unexpected break"),
+            (_, Some(simple)) => json!({ "$simple": simple }),
+            (_, None) => return reader.fail("bad simple value"),
+        },
+    };
+    Ok(value)
+}
+
+// ============================================================================
+// Protobuf
+// ============================================================================
+
+fn protobuf_message(reader: &mut Reader, depth: usize) -> Result<Value, DecodeError> {
+    check_depth(reader, depth)?;
+    let mut fields: Map<String, Value> = Map::new();
+    while !reader.at_end() {
+        let key = reader.varint()?;
+        let (field, wire_type) = (key >> 3, key & 0x07);
+        if field == 0 || field > 0x1fff_ffff {
+            return reader.fail(format!("invalid field number {}", field));
+        }
+        let value = match wire_type {
+            0 => json!(reader.varint()?),
+            1 => json!(reader.uint(8).map(u64::swap_bytes)?),
+            2 => {
+                let length = reader.varint()?;
+                let length = reader.length(length)?;
+                protobuf_bytes(reader.take(length)?, depth)
+            }
+            5 => json!(reader.uint(4).map(|n| (n as u32).swap_bytes())?),
+            _ => return reader.fail(format!("unsupported wire type {}", wire_type)),
+        };
+        // Repeated fields collect into an array
+        match fields.get_mut(&field.to_string()) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                fields.insert(field.to_string(), value);
+            }
+        }
+    }
+    Ok(Value::Object(fields))
+}
+
+/// A length-delimited field: nested message, else text, else bytes
+fn protobuf_bytes(bytes: &[u8], depth: usize) -> Value {
+    if !bytes.is_empty() {
+        if let Ok(message) = protobuf_message(&mut Reader::new(bytes), depth + 1) {
+            // Short printable strings often parse as messages by accident
+            let printable = std::str::from_utf8(bytes)
+                .is_ok_and(|text| text.chars().all(|c| !c.is_control() || c.is_whitespace()));
+            if !printable {
+                return message;
+            }
+        }
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => json!(text),
+        Err(_) => json!(hex(bytes)),
+    }
+}
+
+/// Strip a gRPC frame header or a varint length prefix around a protobuf message
+fn strip_framing(bytes: &[u8]) -> (&[u8], Option<&'static str>) {
+    if bytes.len() >= 5 && bytes[0] <= 1 {
+        let length = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
+        if length == bytes.len() - 5 && length > 0 {
+            return (&bytes[5..], Some("grpc"));
+        }
+    }
+    let mut reader = Reader::new(bytes);
+    if let Ok(length) = reader.varint() {
+        if length > 0 && length as usize == bytes.len() - reader.pos {
+            return (&bytes[reader.pos..], Some("length_prefix"));
+        }
+    }
+    (bytes, None)
+}
+
+// ============================================================================
+// Hex dumps
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HexDumpInput {
+    /// The text's UTF-8 bytes
+    #[default]
+    Text,
+    Base64,
+    Hex,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HexDumpOptions {
+    pub input: HexDumpInput,
+    pub bytes_per_line: usize,
+    /// Bytes per space-separated group: 1 like `hexdump -C`, 2 like `xxd`
+    pub group_size: usize,
+    pub uppercase: bool,
+    /// Show the |ASCII| column
+    pub ascii: bool,
+}
+
+impl Default for HexDumpOptions {
+    fn default() -> Self {
+        Self {
+            input: HexDumpInput::Text,
+            bytes_per_line: 16,
+            group_size: 1,
+            uppercase: false,
+            ascii: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedBytes {
+    /// The bytes as UTF-8, when they are
+    pub text: Option<String>,
+    pub base64: String,
+    pub hex: String,
+    pub bytes: usize,
+}
+
+impl DecodedBytes {
+    fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            base64: STANDARD.encode(&bytes),
+            hex: bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+            bytes: bytes.len(),
+            text: String::from_utf8(bytes).ok(),
+        }
+    }
+}
+
+fn hex_dump_lines(bytes: &[u8], options: &HexDumpOptions) -> String {
+    let per_line = options.bytes_per_line.clamp(1, 64);
+    let group = options.group_size.clamp(1, per_line);
+    // Width of a full line's hex column, so the ASCII column lines up on the last one
+    let hex_width = per_line * 2 + per_line.div_ceil(group) - 1;
+
+    let mut out = String::new();
+    for (i, line) in bytes.chunks(per_line).enumerate() {
+        let hex: Vec<String> = line
+            .chunks(group)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|b| if options.uppercase { format!("{:02X}", b) } else { format!("{:02x}", b) })
+                    .collect()
+            })
+            .collect();
+        out.push_str(&format!("{:08x}  {:<width$}", i * per_line, hex.join(" "), width = hex_width));
+        if options.ascii {
+            let ascii: String = line
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            out.push_str(&format!("  |{}|", ascii));
+        }
+        out.push('\n');
+    }
+    // Closing offset, as hexdump prints it
+    out.push_str(&format!("{:08x}\n", bytes.len()));
+    out
+}
+
+/// Bytes from a dump by this command, `hexdump -C`, `xxd`, or plain hex lines
+fn parse_hex_dump(text: &str) -> Result<Vec<u8>, AppError> {
+    let mut bytes = Vec::new();
+    let mut saw_offsets = false;
+    for (number, line) in text.lines().enumerate() {
+        // The ASCII column: "|...|" for hexdump -C, after a double space for xxd
+        let line = line.split('|').next().unwrap_or_default();
+        let mut rest = line.trim();
+        let first = rest.split_whitespace().next().unwrap_or_default();
+        if let Some(offset) = first.strip_suffix(':') {
+            if offset.chars().all(|c| c.is_ascii_hexdigit()) {
+                saw_offsets = true;
+                rest = rest[first.len()..].trim_start();
+                rest = rest.split("  ").next().unwrap_or_default();
+            }
+        } else if first.len() >= 6 && usize::from_str_radix(first, 16).ok() == Some(bytes.len()) {
+            // An offset only if it counts the bytes read so far, so plain hex words aren't eaten
+            if rest.split_whitespace().count() > 1 {
+                saw_offsets = true;
+                rest = rest[first.len()..].trim_start();
+            } else if saw_offsets {
+                // hexdump's closing offset line
+                continue;
+            }
+        }
+
+        for token in rest.split_whitespace() {
+            let token = token.strip_prefix("0x").unwrap_or(token);
+            // A collapsed run of repeated lines
+            if token == "*" {
+                return Err(AppError::invalid_input(format!(
+                    "Line {}: \"*\" hides repeated lines; dump with `hexdump -v` instead",
+                    number + 1
+                )));
+            }
+            let chunk = parse_hex(token).ok_or_else(|| {
+                AppError::invalid_input(format!("Line {}: \"{}\" is not hex bytes", number + 1, token))
+            })?;
+            bytes.extend(chunk);
+        }
+    }
+    if bytes.is_empty() {
+        return Err(AppError::invalid_input("No hex bytes found"));
+    }
+    Ok(bytes)
+}
+
+// ============================================================================
+// Input and dispatch
+// ============================================================================
+
+/// Bytes of a hex string; whitespace, ":" separators and a "0x" prefix are allowed
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace() && *c != ':').collect();
+    let digits = compact
+        .strip_prefix("0x")
+        .or_else(|| compact.strip_prefix("0X"))
+        .unwrap_or(&compact);
+    if digits.is_empty() || digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Bytes of base64 in any of the common alphabets, padded or not
+fn parse_base64(text: &str) -> Option<Vec<u8>> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(&compact).ok())
+}
+
+fn payload_bytes(text: &str) -> Result<Vec<u8>, AppError> {
+    parse_hex(text)
+        .or_else(|| parse_base64(text))
+        .ok_or_else(|| AppError::invalid_input("Expected base64 or hex"))
+}
+
+fn decode_as(format: BinaryFormat, bytes: &[u8]) -> Result<(Value, Option<&'static str>), DecodeError> {
+    let (bytes, framing) = match format {
+        BinaryFormat::Protobuf => strip_framing(bytes),
+        _ => (bytes, None),
+    };
+    let mut reader = Reader::new(bytes);
+    let value = match format {
+        BinaryFormat::Msgpack => msgpack_value(&mut reader, 0)?,
+        BinaryFormat::Cbor => cbor_value(&mut reader, 0)?,
+        BinaryFormat::Protobuf => protobuf_message(&mut reader, 0)?,
+    };
+    if !reader.at_end() {
+        return reader.fail(format!("{} bytes left over", bytes.len() - reader.pos));
+    }
+    Ok((value, framing))
+}
+
+fn decode(bytes: &[u8], hint: Option<BinaryFormat>) -> Result<BinaryDecodeResult, AppError> {
+    if bytes.is_empty() {
+        return Err(AppError::invalid_input("The payload is empty"));
+    }
+    let candidates: Vec<BinaryFormat> = match hint {
+        Some(format) => vec![format],
+        None => FORMATS.to_vec(),
+    };
+
+    let mut decoded = None;
+    let mut alternatives = Vec::new();
+    let mut failures = Vec::new();
+    for format in candidates {
+        match decode_as(format, bytes) {
+            Ok(result) if decoded.is_none() => decoded = Some((format, result)),
+            Ok(_) => alternatives.push(format),
+            Err((message, offset)) => failures.push(format!("{:?}: {} at byte {}", format, message, offset)),
+        }
+    }
+
+    let (format, (value, framing)) = decoded.ok_or_else(|| {
+        AppError::invalid_input("Couldn't decode the payload").with_details(json!({ "attempts": failures }))
+    })?;
+    Ok(BinaryDecodeResult {
+        format,
+        output: serde_json::to_string_pretty(&value)?,
+        value,
+        bytes: bytes.len(),
+        framing: framing.map(str::to_string),
+        alternatives,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Decode a base64 or hex payload as MessagePack, CBOR or protobuf; tries each in turn
+/// unless `format_hint` names one
+#[tauri::command]
+pub fn decode_base64_binary(text: String, format_hint: Option<BinaryFormat>) -> Result<BinaryDecodeResult, AppError> {
+    decode(&payload_bytes(&text)?, format_hint)
+}
+
+/// Offset / hex / ASCII view of text, base64 or hex input
+#[tauri::command]
+pub fn hex_dump(text: String, options: Option<HexDumpOptions>) -> Result<String, AppError> {
+    let options = options.unwrap_or_default();
+    let bytes = match options.input {
+        HexDumpInput::Text => text.into_bytes(),
+        HexDumpInput::Base64 => parse_base64(&text).ok_or_else(|| AppError::invalid_input("Invalid base64"))?,
+        HexDumpInput::Hex => parse_hex(&text).ok_or_else(|| AppError::invalid_input("Invalid hex"))?,
+    };
+    if bytes.is_empty() {
+        return Err(AppError::invalid_input("Nothing to dump"));
+    }
+    Ok(hex_dump_lines(&bytes, &options))
+}
+
+/// Read a hex dump back into bytes
+#[tauri::command]
+pub fn from_hex_dump(text: String) -> Result<DecodedBytes, AppError> {
+    parse_hex_dump(&text).map(DecodedBytes::new)
+}
+
+#[tauri::command]
+pub fn hex_to_base64(text: String) -> Result<String, AppError> {
+    parse_hex(&text)
+        .map(|bytes| STANDARD.encode(bytes))
+        .ok_or_else(|| AppError::invalid_input("Invalid hex: expected pairs of hex digits"))
+}
+
+#[tauri::command]
+pub fn base64_to_hex(text: String, uppercase: Option<bool>) -> Result<String, AppError> {
+    let bytes = parse_base64(&text).ok_or_else(|| AppError::invalid_input("Invalid base64"))?;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(if uppercase.unwrap_or(false) { hex.to_uppercase() } else { hex })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_msgpack_and_cbor() {
+        // {"compact": true, "schema": 0, "ids": [1, -2, 300], "bin": <de ad>}
+        let msgpack = payload_bytes("hKdjb21wYWN0w6ZzY2hlbWEAo2lkc5MB/s0BLKNiaW7EAt6t").unwrap();
+        let result = decode(&msgpack, None).unwrap();
+        assert_eq!(result.format, BinaryFormat::Msgpack);
+        assert_eq!(
+            result.value,
+            json!({ "compact": true, "schema": 0, "ids": [1, -2, 300], "bin": "0xdead" })
+        );
+
+        // {"a": 1.5, "b": [_ "x", 1(1700000000)]} with an indefinite-length array and a tag
+        let cbor = payload_bytes("a2 61 61 f9 3e 00 61 62 9f 61 78 c1 1a 65 53 f1 00 ff").unwrap();
+        let result = decode(&cbor, None).unwrap();
+        assert_eq!(result.format, BinaryFormat::Cbor);
+        assert_eq!(
+            result.value,
+            json!({ "a": 1.5, "b": ["x", { "$tag": 1, "value": 1700000000 }] })
+        );
+    }
+
+    #[test]
+    fn test_protobuf_with_framing() {
+        // field 1 = 150, field 2 = "testing", field 3 = { field 1 = 1 } twice; gRPC framed
+        let message = "08 96 01 12 07 74 65 73 74 69 6e 67 1a 02 08 01 1a 02 08 01";
+        let framed = format!("00 00 00 00 14 {}", message);
+        let result = decode(&payload_bytes(&framed).unwrap(), Some(BinaryFormat::Protobuf)).unwrap();
+        assert_eq!(result.framing.as_deref(), Some("grpc"));
+        assert_eq!(
+            result.value,
+            json!({ "1": 150, "2": "testing", "3": [{ "1": 1 }, { "1": 1 }] })
+        );
+
+        let error = decode(&[0x0f, 0x01], Some(BinaryFormat::Protobuf)).unwrap_err();
+        assert!(error.details.unwrap()["attempts"][0].as_str().unwrap().contains("wire type 7"));
+    }
+
+    #[test]
+    fn test_hex_dump_round_trip() {
+        let options = HexDumpOptions::default();
+        let dump = hex_dump_lines(b"Hello, world!\n\x00\xff", &options);
+        assert_eq!(
+            dump,
+            "00000000  48 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 0a 00 ff  |Hello, world!...|\n00000010\n"
+        );
+        assert_eq!(parse_hex_dump(&dump).unwrap(), b"Hello, world!\n\x00\xff");
+
+        // xxd's layout, whose ASCII column isn't delimited
+        let xxd = "00000000: 4865 6c6c 6f0a                           Hello.\n";
+        assert_eq!(parse_hex_dump(xxd).unwrap(), b"Hello\n");
+        assert_eq!(DecodedBytes::new(parse_hex_dump("deadbeef cafe").unwrap()).hex, "deadbeefcafe");
+
+        assert_eq!(hex_to_base64("0xDEADBEEF".to_string()).unwrap(), "3q2+7w==");
+        assert_eq!(base64_to_hex("3q2-7w".to_string(), Some(true)).unwrap(), "DEADBEEF");
+    }
+}
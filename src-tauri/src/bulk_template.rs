@@ -0,0 +1,428 @@
+// Bulk generation from data: a Handlebars-style template is rendered once per row of a
+// pasted CSV/TSV or JSON array and the results are joined, e.g. one SQL INSERT or curl
+// command per row.
+//
+// Template syntax:
+//   {{name}}                   field value; {{user.email}} reaches into JSON objects
+//   {{helper name}}            upper, lower, trim, sql, json, shell, url
+//   {{@index}} / {{@number}}   0-based / 1-based row number
+//   {{#if name}}..{{else}}..{{/if}}, {{#unless name}}..{{/unless}}   non-empty test
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+
+const MAX_ROWS: usize = 10_000;
+const HELPERS: &[&str] = &["upper", "lower", "trim", "sql", "json", "shell", "url"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    /// JSON when the data starts with [ or {, otherwise delimited text
+    #[default]
+    Auto,
+    Csv,
+    Tsv,
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BulkTemplateOptions {
+    pub format: DataFormat,
+    /// Placed between rendered rows
+    pub separator: String,
+    /// First CSV row names the columns; without one they are {{1}}, {{2}}, ...
+    pub has_header: bool,
+}
+
+impl Default for BulkTemplateOptions {
+    fn default() -> Self {
+        Self {
+            format: DataFormat::Auto,
+            separator: "\n".to_string(),
+            has_header: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkTemplateResult {
+    pub output: String,
+    pub rows: usize,
+    pub columns: Vec<String>,
+    /// Placeholders that no row has a value for; they render empty
+    pub missing_fields: Vec<String>,
+}
+
+// ============================================================================
+// Template
+// ============================================================================
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    Text(String),
+    Field { helper: Option<String>, path: String },
+    If { path: String, negate: bool, then: Vec<Node>, otherwise: Vec<Node> },
+}
+
+/// Parse until one of `stops` ({{else}}, {{/if}}, ...); returns the nodes and the stop hit
+fn parse_nodes(rest: &mut &str, stops: &[&'static str]) -> Result<(Vec<Node>, Option<&'static str>), AppError> {
+    let mut nodes = Vec::new();
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            nodes.push(Node::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| AppError::invalid_input("Template has an unclosed {{"))?;
+        let tag = after[..end].trim();
+        *rest = &after[end + 2..];
+
+        if let Some(stop) = stops.iter().find(|stop| **stop == tag) {
+            return Ok((nodes, Some(*stop)));
+        }
+        let mut words = tag.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some(block @ ("#if" | "#unless")), Some(path), None) => {
+                let close = if block == "#if" { "/if" } else { "/unless" };
+                let (then, stop) = parse_nodes(rest, &["else", close])?;
+                let otherwise = match stop {
+                    Some("else") => parse_nodes(rest, &[close])?.0,
+                    Some(_) => Vec::new(),
+                    None => return Err(AppError::invalid_input(format!("Missing {{{{{}}}}}", close))),
+                };
+                nodes.push(Node::If {
+                    path: path.to_string(),
+                    negate: block == "#unless",
+                    then,
+                    otherwise,
+                });
+            }
+            (Some(helper), Some(path), None) if HELPERS.contains(&helper) => nodes.push(Node::Field {
+                helper: Some(helper.to_string()),
+                path: path.to_string(),
+            }),
+            (Some(path), None, None) if !path.starts_with(['#', '/']) && path != "else" => {
+                nodes.push(Node::Field { helper: None, path: path.to_string() })
+            }
+            _ => return Err(AppError::invalid_input(format!("Unsupported template tag {{{{{}}}}}", tag))),
+        }
+    }
+    if !rest.is_empty() {
+        nodes.push(Node::Text(rest.to_string()));
+        *rest = "";
+    }
+    Ok((nodes, None))
+}
+
+fn parse_template(template: &str) -> Result<Vec<Node>, AppError> {
+    let mut rest = template;
+    match parse_nodes(&mut rest, &[])? {
+        (nodes, None) => Ok(nodes),
+        (_, Some(stop)) => Err(AppError::invalid_input(format!("Unexpected {{{{{}}}}}", stop))),
+    }
+}
+
+/// A field's value: "@index"/"@number", a column, or a dotted path into JSON
+fn lookup(row: &Map<String, Value>, index: usize, path: &str) -> Option<Value> {
+    match path {
+        "@index" => return Some(Value::from(index)),
+        "@number" => return Some(Value::from(index + 1)),
+        _ => {}
+    }
+    if let Some(value) = row.get(path) {
+        return Some(value.clone());
+    }
+    let mut parts = path.split('.');
+    let mut value = row.get(parts.next()?)?;
+    for part in parts {
+        value = match value {
+            Value::Object(map) => map.get(part)?,
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value.clone())
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn is_truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(_) => true,
+    }
+}
+
+fn apply_helper(helper: &str, value: Option<&Value>) -> String {
+    let text = value.map(value_text).unwrap_or_default();
+    match helper {
+        "upper" => text.to_uppercase(),
+        "lower" => text.to_lowercase(),
+        "trim" => text.trim().to_string(),
+        // Quoted literal, NULL for missing/empty; numbers and booleans stay bare
+        "sql" => match value {
+            None | Some(Value::Null) => "NULL".to_string(),
+            Some(Value::String(s)) if s.is_empty() => "NULL".to_string(),
+            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::Bool(b)) => b.to_string().to_uppercase(),
+            _ => format!("'{}'", text.replace('\'', "''")),
+        },
+        "json" => match value {
+            Some(Value::String(s)) => Value::String(s.clone()).to_string(),
+            Some(other) => other.to_string(),
+            None => "null".to_string(),
+        },
+        "shell" => format!("'{}'", text.replace('\'', r"'\''")),
+        "url" => urlencoding::encode(&text).into_owned(),
+        _ => text,
+    }
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    row: &Map<String, Value>,
+    index: usize,
+    out: &mut String,
+    missing: &mut Vec<String>,
+) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Field { helper, path } => {
+                let value = lookup(row, index, path);
+                if value.is_none() && !missing.contains(path) {
+                    missing.push(path.clone());
+                }
+                match helper {
+                    Some(helper) => out.push_str(&apply_helper(helper, value.as_ref())),
+                    None => out.push_str(&value.as_ref().map(value_text).unwrap_or_default()),
+                }
+            }
+            Node::If { path, negate, then, otherwise } => {
+                let branch = if is_truthy(lookup(row, index, path).as_ref()) != *negate { then } else { otherwise };
+                render_nodes(branch, row, index, out, missing);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Data
+// ============================================================================
+
+/// Parsed data: column names in first-seen order and one object per row
+struct Table {
+    columns: Vec<String>,
+    rows: Vec<Map<String, Value>>,
+}
+
+/// RFC 4180 records: quoted fields may contain the delimiter, "" and newlines
+fn parse_delimited(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, AppError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+        } else if c == '"' && field.is_empty() {
+            in_quotes = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            // Blank lines separate nothing
+            if record.len() > 1 || !record[0].is_empty() {
+                records.push(std::mem::take(&mut record));
+            }
+            record.clear();
+        } else {
+            field.push(c);
+        }
+    }
+    if in_quotes {
+        return Err(AppError::invalid_input("CSV has an unterminated quoted field"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Tab when the first line has tabs, then semicolon-separated European exports, else comma
+fn sniff_delimiter(text: &str) -> char {
+    let first = text.lines().next().unwrap_or_default();
+    if first.contains('\t') {
+        '\t'
+    } else if first.matches(';').count() > first.matches(',').count() {
+        ';'
+    } else {
+        ','
+    }
+}
+
+fn delimited_rows(
+    text: &str,
+    delimiter: char,
+    has_header: bool,
+) -> Result<Table, AppError> {
+    let mut records = parse_delimited(text, delimiter)?;
+    let columns: Vec<String> = if has_header && !records.is_empty() {
+        records.remove(0).into_iter().map(|name| name.trim().to_string()).collect()
+    } else {
+        (1..=records.iter().map(Vec::len).max().unwrap_or(0)).map(|n| n.to_string()).collect()
+    };
+    let rows = records
+        .into_iter()
+        .map(|record| {
+            columns
+                .iter()
+                .cloned()
+                .zip(record.into_iter().map(Value::String).chain(std::iter::repeat(Value::Null)))
+                .collect()
+        })
+        .collect();
+    Ok(Table { columns, rows })
+}
+
+fn json_rows(text: &str) -> Result<Table, AppError> {
+    let value: Value = serde_json::from_str(text).map_err(|e| AppError::invalid_input(format!("Invalid JSON: {}", e)))?;
+    let items = match value {
+        Value::Array(items) => items,
+        object @ Value::Object(_) => vec![object],
+        _ => return Err(AppError::invalid_input("JSON data must be an array of objects")),
+    };
+    let mut columns: Vec<String> = Vec::new();
+    let rows = items
+        .into_iter()
+        .map(|item| match item {
+            Value::Object(map) => {
+                for key in map.keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+                map
+            }
+            // Arrays of plain values are addressed as {{value}}
+            other => Map::from_iter([("value".to_string(), other)]),
+        })
+        .collect::<Vec<_>>();
+    if rows.iter().any(|row| row.contains_key("value")) && !columns.iter().any(|c| c == "value") {
+        columns.push("value".to_string());
+    }
+    Ok(Table { columns, rows })
+}
+
+pub fn render_bulk(template: &str, data: &str, options: &BulkTemplateOptions) -> Result<BulkTemplateResult, AppError> {
+    let nodes = parse_template(template)?;
+    let data = data.trim_start_matches('\u{feff}');
+    let format = match options.format {
+        DataFormat::Auto if data.trim_start().starts_with(['[', '{']) => DataFormat::Json,
+        other => other,
+    };
+    let Table { columns, rows } = match format {
+        DataFormat::Json => json_rows(data)?,
+        DataFormat::Tsv => delimited_rows(data, '\t', options.has_header)?,
+        DataFormat::Csv => delimited_rows(data, ',', options.has_header)?,
+        DataFormat::Auto => delimited_rows(data, sniff_delimiter(data), options.has_header)?,
+    };
+    if rows.is_empty() {
+        return Err(AppError::invalid_input("No data rows"));
+    }
+    if rows.len() > MAX_ROWS {
+        return Err(AppError::invalid_input(format!("At most {} rows can be rendered at once", MAX_ROWS)));
+    }
+
+    let mut missing = Vec::new();
+    let rendered: Vec<String> = rows
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let mut out = String::new();
+            let mut row_missing = Vec::new();
+            render_nodes(&nodes, row, index, &mut out, &mut row_missing);
+            missing.push(row_missing);
+            out
+        })
+        .collect();
+    // Only fields absent from every row; sparse JSON objects are expected
+    let mut missing_fields: Vec<String> = missing.first().cloned().unwrap_or_default();
+    missing_fields.retain(|field| missing.iter().all(|row| row.contains(field)));
+
+    Ok(BulkTemplateResult {
+        output: rendered.join(&options.separator),
+        rows: rows.len(),
+        columns,
+        missing_fields,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Render `template` once per row of CSV/TSV or JSON `data` and join the results
+#[tauri::command]
+pub fn render_bulk_template(
+    template: String,
+    data: String,
+    options: Option<BulkTemplateOptions>,
+) -> Result<BulkTemplateResult, AppError> {
+    render_bulk(&template, &data, &options.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_to_sql() {
+        let data = "id,name,email\n1,O'Brien,ob@example.com\n2,\"Smith, Jo\",\n";
+        let template = "INSERT INTO users (id, name, email) VALUES ({{sql id}}, {{sql name}}, {{sql email}});";
+        let result = render_bulk(template, data, &BulkTemplateOptions::default()).unwrap();
+        assert_eq!(
+            result.output,
+            "INSERT INTO users (id, name, email) VALUES ('1', 'O''Brien', 'ob@example.com');\n\
+             INSERT INTO users (id, name, email) VALUES ('2', 'Smith, Jo', NULL);"
+        );
+        assert_eq!(result.columns, vec!["id", "name", "email"]);
+        assert!(result.missing_fields.is_empty());
+    }
+
+    #[test]
+    fn test_json_with_blocks_and_paths() {
+        let data = r#"[{"user": {"name": "ada"}, "admin": true}, {"user": {"name": "bob"}}]"#;
+        let template = "{{@number}}. {{upper user.name}}{{#if admin}} (admin){{else}}{{/if}}{{nope}}";
+        let options = BulkTemplateOptions { separator: " | ".to_string(), ..Default::default() };
+        let result = render_bulk(template, data, &options).unwrap();
+        assert_eq!(result.output, "1. ADA (admin) | 2. BOB");
+        assert_eq!(result.missing_fields, vec!["nope"]);
+
+        assert!(render_bulk("{{#if a}}x", data, &options).is_err());
+    }
+}
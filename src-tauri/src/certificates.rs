@@ -0,0 +1,449 @@
+// X.509 certificate and CSR decoding, for checking what a pasted cert is for and when it
+// expires without reaching for `openssl x509 -text`.
+//
+// Input is PEM (a whole chain is fine; every CERTIFICATE block is decoded in order) or a
+// single DER structure pasted as base64 or hex. Nothing is verified: signatures aren't
+// checked and no trust chain is built.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::traits::PublicKeyParts;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use x509_cert::der::asn1::ObjectIdentifier;
+use x509_cert::der::oid::db::DB;
+use x509_cert::der::{Decode, Encode};
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::pkix::{BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAltName};
+use x509_cert::ext::Extension;
+use x509_cert::name::Name;
+use x509_cert::request::{CertReq, ExtensionReq};
+use x509_cert::spki::SubjectPublicKeyInfoOwned;
+use x509_cert::time::Time;
+use x509_cert::Certificate;
+
+use crate::error::AppError;
+
+const CERTIFICATE_LABELS: [&str; 2] = ["CERTIFICATE", "X509 CERTIFICATE"];
+const CSR_LABELS: [&str; 2] = ["CERTIFICATE REQUEST", "NEW CERTIFICATE REQUEST"];
+
+const OID_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+const OID_EC: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+const OID_ED25519: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+const OID_ED448: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.113");
+const OID_DSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10040.4.1");
+const OID_COMMON_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.4.3");
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicKeySummary {
+    /// "RSA", "EC", "Ed25519", ... or the algorithm OID when unknown
+    pub algorithm: String,
+    pub bits: Option<u32>,
+    /// Named curve for EC keys, e.g. "secp256r1"
+    pub curve: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateInfo {
+    /// RFC 4514 form, most specific attribute first ("CN=example.com,O=Example")
+    pub subject: String,
+    pub subject_common_name: Option<String>,
+    pub issuer: String,
+    pub issuer_common_name: Option<String>,
+    pub self_signed: bool,
+    pub version: u8,
+    /// Colon-separated hex
+    pub serial_number: String,
+    /// Subject alternative names, prefixed with their kind: "DNS:", "IP:", "email:", "URI:"
+    pub subject_alt_names: Vec<String>,
+    /// RFC 3339
+    pub not_before: String,
+    pub not_after: String,
+    /// Seconds until not_after; negative once expired
+    pub expires_in_seconds: i64,
+    /// Whole days until not_after, rounded down; negative once expired
+    pub days_remaining: i64,
+    pub expired: bool,
+    pub not_yet_valid: bool,
+    pub public_key: PublicKeySummary,
+    pub signature_algorithm: String,
+    pub is_ca: bool,
+    pub key_usage: Vec<String>,
+    pub extended_key_usage: Vec<String>,
+    /// Colon-separated uppercase hex of the DER, as `openssl x509 -fingerprint` prints it
+    pub sha256_fingerprint: String,
+    pub sha1_fingerprint: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CsrInfo {
+    pub subject: String,
+    pub subject_common_name: Option<String>,
+    /// From the extensionRequest attribute
+    pub subject_alt_names: Vec<String>,
+    pub public_key: PublicKeySummary,
+    pub signature_algorithm: String,
+    pub key_usage: Vec<String>,
+    pub extended_key_usage: Vec<String>,
+}
+
+fn invalid(what: &str, error: impl std::fmt::Display) -> AppError {
+    AppError::invalid_input(format!("Not a valid {}: {}", what, error))
+}
+
+/// Colon-separated hex of `bytes`, as certificate tools print serials and fingerprints
+fn colon_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// DER bodies of the PEM blocks with one of `labels`, or the whole input decoded from
+/// base64 or hex when it isn't PEM
+fn der_blocks(text: &str, labels: &[&str], what: &str) -> Result<Vec<Vec<u8>>, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::invalid_input(format!("Paste a {} to decode", what)));
+    }
+
+    if !text.contains("-----BEGIN ") {
+        let compact: String = text.chars().filter(|c| !c.is_whitespace() && *c != ':').collect();
+        let bytes = if compact.chars().all(|c| c.is_ascii_hexdigit()) {
+            decode_hex(&compact)
+        } else {
+            STANDARD.decode(&compact).ok()
+        };
+        return bytes
+            .map(|bytes| vec![bytes])
+            .ok_or_else(|| AppError::invalid_input(format!("Expected a PEM, base64 or hex {}", what)));
+    }
+
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("-----BEGIN ") {
+        let block = &rest[start..];
+        let label_end = block["-----BEGIN ".len()..]
+            .find("-----")
+            .ok_or_else(|| invalid(what, "unterminated PEM header"))?;
+        let label = &block["-----BEGIN ".len()..][..label_end];
+        let footer = format!("-----END {}-----", label);
+        let end = block
+            .find(&footer)
+            .ok_or_else(|| invalid(what, format!("missing \"{}\"", footer)))?
+            + footer.len();
+        if labels.contains(&label) {
+            let (_, der) =
+                x509_cert::der::pem::decode_vec(&block.as_bytes()[..end]).map_err(|e| invalid(what, e))?;
+            blocks.push(der);
+        }
+        rest = &block[end..];
+    }
+
+    if blocks.is_empty() {
+        return Err(AppError::invalid_input(format!(
+            "No {} found; expected a \"-----BEGIN {}-----\" block",
+            what, labels[0]
+        )));
+    }
+    Ok(blocks)
+}
+
+fn common_name(name: &Name) -> Option<String> {
+    name.0
+        .iter()
+        .flat_map(|rdn| rdn.0.iter())
+        .rfind(|atv| atv.oid == OID_COMMON_NAME)
+        .map(|atv| {
+            // Any string type; fall back to the RFC 4514 rendering, which escapes it
+            std::str::from_utf8(atv.value.value())
+                .map(str::to_string)
+                .unwrap_or_else(|_| atv.to_string())
+        })
+}
+
+fn oid_name(oid: &ObjectIdentifier) -> String {
+    DB.by_oid(oid)
+        .map(str::to_string)
+        .unwrap_or_else(|| oid.to_string())
+}
+
+fn public_key_summary(spki: &SubjectPublicKeyInfoOwned) -> PublicKeySummary {
+    let oid = spki.algorithm.oid;
+    let key_bytes = spki.subject_public_key.raw_bytes();
+    let (algorithm, bits, curve) = if oid == OID_RSA {
+        let bits = rsa::RsaPublicKey::from_pkcs1_der(key_bytes)
+            .ok()
+            .map(|key| key.n().bits() as u32);
+        ("RSA".to_string(), bits, None)
+    } else if oid == OID_EC {
+        let curve = spki
+            .algorithm
+            .parameters
+            .as_ref()
+            .and_then(|params| params.decode_as::<ObjectIdentifier>().ok());
+        let bits = curve.as_ref().and_then(|curve| match curve.to_string().as_str() {
+            "1.2.840.10045.3.1.7" => Some(256),
+            "1.3.132.0.34" => Some(384),
+            "1.3.132.0.35" => Some(521),
+            "1.3.132.0.10" => Some(256),
+            _ => None,
+        });
+        ("EC".to_string(), bits, curve.as_ref().map(oid_name))
+    } else if oid == OID_ED25519 {
+        ("Ed25519".to_string(), Some(256), None)
+    } else if oid == OID_ED448 {
+        ("Ed448".to_string(), Some(456), None)
+    } else if oid == OID_DSA {
+        ("DSA".to_string(), None, None)
+    } else {
+        (oid_name(&oid), None, None)
+    };
+    PublicKeySummary { algorithm, bits, curve }
+}
+
+fn general_name(name: &GeneralName) -> String {
+    match name {
+        GeneralName::DnsName(dns) => format!("DNS:{}", dns.as_str()),
+        GeneralName::Rfc822Name(email) => format!("email:{}", email.as_str()),
+        GeneralName::UniformResourceIdentifier(uri) => format!("URI:{}", uri.as_str()),
+        GeneralName::IpAddress(ip) => {
+            let bytes = ip.as_bytes();
+            let addr = match bytes.len() {
+                4 => <[u8; 4]>::try_from(bytes).map(|b| std::net::IpAddr::from(b).to_string()).ok(),
+                16 => <[u8; 16]>::try_from(bytes).map(|b| std::net::IpAddr::from(b).to_string()).ok(),
+                _ => None,
+            };
+            format!("IP:{}", addr.unwrap_or_else(|| colon_hex(bytes)))
+        }
+        GeneralName::DirectoryName(dn) => format!("DirName:{}", dn),
+        GeneralName::RegisteredId(oid) => format!("RID:{}", oid),
+        GeneralName::OtherName(other) => format!("othername:{}", oid_name(&other.type_id)),
+        GeneralName::EdiPartyName(_) => "EdiPartyName".to_string(),
+    }
+}
+
+/// What the extensions say, for the parts shared by certificates and CSRs
+#[derive(Default)]
+struct ExtensionSummary {
+    subject_alt_names: Vec<String>,
+    is_ca: bool,
+    key_usage: Vec<String>,
+    extended_key_usage: Vec<String>,
+}
+
+fn summarize_extensions(extensions: &[Extension]) -> ExtensionSummary {
+    use x509_cert::der::oid::AssociatedOid;
+
+    let mut summary = ExtensionSummary::default();
+    for extension in extensions {
+        let value = extension.extn_value.as_bytes();
+        // Extensions that don't decode are skipped rather than failing the whole cert
+        if extension.extn_id == SubjectAltName::OID {
+            if let Ok(san) = SubjectAltName::from_der(value) {
+                summary.subject_alt_names = san.0.iter().map(general_name).collect();
+            }
+        } else if extension.extn_id == BasicConstraints::OID {
+            if let Ok(constraints) = BasicConstraints::from_der(value) {
+                summary.is_ca = constraints.ca;
+            }
+        } else if extension.extn_id == KeyUsage::OID {
+            if let Ok(usage) = KeyUsage::from_der(value) {
+                summary.key_usage = usage.0.into_iter().map(|flag| format!("{:?}", flag)).collect();
+            }
+        } else if extension.extn_id == ExtendedKeyUsage::OID {
+            if let Ok(usage) = ExtendedKeyUsage::from_der(value) {
+                summary.extended_key_usage = usage
+                    .0
+                    .iter()
+                    .map(|oid| {
+                        let name = oid_name(oid);
+                        name.strip_prefix("id-kp-").map(str::to_string).unwrap_or(name)
+                    })
+                    .collect();
+            }
+        }
+    }
+    summary
+}
+
+fn to_datetime(time: &Time) -> DateTime<Utc> {
+    DateTime::from_timestamp(time.to_unix_duration().as_secs() as i64, 0).unwrap_or_default()
+}
+
+fn certificate_info(der: &[u8], now: DateTime<Utc>) -> Result<CertificateInfo, AppError> {
+    let certificate = Certificate::from_der(der).map_err(|e| invalid("certificate", e))?;
+    let tbs = &certificate.tbs_certificate;
+    let extensions = summarize_extensions(tbs.extensions.as_deref().unwrap_or(&[]));
+
+    let not_before = to_datetime(&tbs.validity.not_before);
+    let not_after = to_datetime(&tbs.validity.not_after);
+    let expires_in_seconds = (not_after - now).num_seconds();
+
+    Ok(CertificateInfo {
+        subject: tbs.subject.to_string(),
+        subject_common_name: common_name(&tbs.subject),
+        issuer: tbs.issuer.to_string(),
+        issuer_common_name: common_name(&tbs.issuer),
+        self_signed: tbs.subject == tbs.issuer,
+        version: tbs.version as u8 + 1,
+        serial_number: colon_hex(tbs.serial_number.as_bytes()),
+        subject_alt_names: extensions.subject_alt_names,
+        not_before: not_before.to_rfc3339(),
+        not_after: not_after.to_rfc3339(),
+        expires_in_seconds,
+        days_remaining: expires_in_seconds.div_euclid(86_400),
+        expired: now > not_after,
+        not_yet_valid: now < not_before,
+        public_key: public_key_summary(&tbs.subject_public_key_info),
+        signature_algorithm: oid_name(&certificate.signature_algorithm.oid),
+        is_ca: extensions.is_ca,
+        key_usage: extensions.key_usage,
+        extended_key_usage: extensions.extended_key_usage,
+        sha256_fingerprint: colon_hex(&Sha256::digest(der)),
+        sha1_fingerprint: colon_hex(&Sha1::digest(der)),
+    })
+}
+
+fn csr_info(der: &[u8]) -> Result<CsrInfo, AppError> {
+    let request = CertReq::from_der(der).map_err(|e| invalid("certificate request", e))?;
+    let info = &request.info;
+
+    let mut requested = Vec::new();
+    for attribute in info.attributes.iter() {
+        if attribute.oid != <ExtensionReq as x509_cert::der::oid::AssociatedOid>::OID {
+            continue;
+        }
+        for value in attribute.values.iter() {
+            let extensions = value
+                .to_der()
+                .and_then(|der| ExtensionReq::from_der(&der))
+                .map_err(|e| invalid("certificate request", e))?;
+            requested.extend(extensions.0);
+        }
+    }
+    let extensions = summarize_extensions(&requested);
+
+    Ok(CsrInfo {
+        subject: info.subject.to_string(),
+        subject_common_name: common_name(&info.subject),
+        subject_alt_names: extensions.subject_alt_names,
+        public_key: public_key_summary(&info.public_key),
+        signature_algorithm: oid_name(&request.algorithm.oid),
+        key_usage: extensions.key_usage,
+        extended_key_usage: extensions.extended_key_usage,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Decode a certificate, or each certificate of a PEM chain in order
+#[tauri::command]
+pub fn decode_certificate(text: String) -> Result<Vec<CertificateInfo>, AppError> {
+    let now = Utc::now();
+    der_blocks(&text, &CERTIFICATE_LABELS, "certificate")?
+        .iter()
+        .map(|der| certificate_info(der, now))
+        .collect()
+}
+
+/// Decode a certificate signing request (PKCS#10)
+#[tauri::command]
+pub fn decode_csr(text: String) -> Result<CsrInfo, AppError> {
+    let blocks = der_blocks(&text, &CSR_LABELS, "certificate request")?;
+    csr_info(&blocks[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:P-256 -subj "/O=Wingman Test/CN=wingman.test"
+    //   -addext "subjectAltName=DNS:wingman.test,DNS:*.wingman.test,IP:127.0.0.1" ...
+    const CERT: &str = "-----BEGIN CERTIFICATE-----
+MIICCDCCAa2gAwIBAgIUYlbOOJASGa6hk4gZR8nkqHT8bdgwCgYIKoZIzj0EAwIw
+LjEVMBMGA1UECgwMV2luZ21hbiBUZXN0MRUwEwYDVQQDDAx3aW5nbWFuLnRlc3Qw
+HhcNMjYxMDE2MTkyOTAxWhcNMzYxMDEzMTkyOTAxWjAuMRUwEwYDVQQKDAxXaW5n
+bWFuIFRlc3QxFTATBgNVBAMMDHdpbmdtYW4udGVzdDBZMBMGByqGSM49AgEGCCqG
+SM49AwEHA0IABEEM6dxu8WYS/455qL2s0jKHM/rHYwBd3W+svXdBnyiWR90UvOMS
+EEN6eS+y+6xGP9+u3GB3iVALragPssR4JxOjgagwgaUwHQYDVR0OBBYEFKbEjzD5
+Qk+V6EW2qooJUhInG0M/MB8GA1UdIwQYMBaAFKbEjzD5Qk+V6EW2qooJUhInG0M/
+MA8GA1UdEwEB/wQFMAMBAf8wLQYDVR0RBCYwJIIMd2luZ21hbi50ZXN0gg4qLndp
+bmdtYW4udGVzdIcEfwAAATATBgNVHSUEDDAKBggrBgEFBQcDATAOBgNVHQ8BAf8E
+BAMCB4AwCgYIKoZIzj0EAwIDSQAwRgIhAJ1VF+0mFpLOA5imfaL1ib59FTxsC7EC
+jKn0G7+VKsuZAiEArxgB2askJBufrYo0n7mdBrmzWzpWpudiPFDLBZK+DjI=
+-----END CERTIFICATE-----";
+
+    const CSR: &str = "-----BEGIN CERTIFICATE REQUEST-----
+MIIBBDCBqwIBADAbMRkwFwYDVQQDDBBjc3Iud2luZ21hbi50ZXN0MFkwEwYHKoZI
+zj0CAQYIKoZIzj0DAQcDQgAEQQzp3G7xZhL/jnmovazSMocz+sdjAF3db6y9d0Gf
+KJZH3RS84xIQQ3p5L7L7rEY/367cYHeJUAutqA+yxHgnE6AuMCwGCSqGSIb3DQEJ
+DjEfMB0wGwYDVR0RBBQwEoIQY3NyLndpbmdtYW4udGVzdDAKBggqhkjOPQQDAgNI
+ADBFAiB8cr50dU2Pq+P3oNqFlPf/pRJJdJhAJ1vzBhs6Ool4cwIhAP6xjgxS3/6y
+/8gmU3oQsdQlvyM+hhul+9PSdP4y8HlP
+-----END CERTIFICATE REQUEST-----";
+
+    #[test]
+    fn test_decode_certificate() {
+        let chain = format!("leaf:\n{}\n\nagain:\n{}\n", CERT, CERT);
+        let certs = decode_certificate(chain).unwrap();
+        assert_eq!(certs.len(), 2);
+
+        let cert = &certs[0];
+        assert_eq!(cert.subject, "CN=wingman.test,O=Wingman Test");
+        assert_eq!(cert.subject_common_name.as_deref(), Some("wingman.test"));
+        assert!(cert.self_signed && cert.is_ca);
+        assert_eq!(cert.version, 3);
+        assert_eq!(cert.serial_number, "62:56:CE:38:90:12:19:AE:A1:93:88:19:47:C9:E4:A8:74:FC:6D:D8");
+        assert_eq!(
+            cert.subject_alt_names,
+            ["DNS:wingman.test", "DNS:*.wingman.test", "IP:127.0.0.1"]
+        );
+        assert_eq!(cert.not_after, "2036-10-13T19:29:01+00:00");
+        assert_eq!(cert.public_key.algorithm, "EC");
+        assert_eq!(cert.public_key.curve.as_deref(), Some("secp256r1"));
+        assert_eq!(cert.signature_algorithm, "ecdsa-with-SHA256");
+        assert_eq!(cert.key_usage, ["DigitalSignature"]);
+        assert_eq!(cert.extended_key_usage, ["serverAuth"]);
+        assert_eq!(
+            cert.sha256_fingerprint,
+            "C6:73:25:B3:78:64:3F:4A:F1:74:2C:09:C7:04:6A:4C:3B:92:43:C3:09:C1:4C:C8:EB:DA:D4:19:93:10:D5:1E"
+        );
+    }
+
+    #[test]
+    fn test_expiry_countdown_and_bare_base64() {
+        let body: String = CERT.lines().filter(|line| !line.starts_with("-----")).collect();
+        let der = &der_blocks(&body, &CERTIFICATE_LABELS, "certificate").unwrap()[0];
+
+        let now = DateTime::parse_from_rfc3339("2036-10-23T19:29:01Z").unwrap().to_utc();
+        let cert = certificate_info(der, now).unwrap();
+        assert!(cert.expired && !cert.not_yet_valid);
+        assert_eq!(cert.days_remaining, -10);
+
+        assert!(decode_certificate(CSR.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_decode_csr() {
+        let csr = decode_csr(CSR.to_string()).unwrap();
+        assert_eq!(csr.subject, "CN=csr.wingman.test");
+        assert_eq!(csr.subject_alt_names, ["DNS:csr.wingman.test"]);
+        assert_eq!(csr.public_key.bits, Some(256));
+    }
+}
@@ -0,0 +1,275 @@
+// Emoji and special character picker. Emoji come from the CLDR data in the emojis crate
+// (name, group, GitHub shortcodes); special characters are the Unicode blocks people
+// reach for when writing (arrows, math, currency, accented letters, box drawing, ...)
+// named from the Unicode character database. Both are embedded, so search works
+// offline and is built once on first use. Recently used characters live in
+// characters.json.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+use crate::storage::{ensure_app_data_dir, StorageError};
+
+/// Results returned by one search; the picker shows a scrolling grid
+const MAX_RESULTS: usize = 200;
+const MAX_RECENT: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CharacterCategory {
+    // Emoji groups
+    SmileysAndEmotion,
+    PeopleAndBody,
+    AnimalsAndNature,
+    FoodAndDrink,
+    TravelAndPlaces,
+    Activities,
+    Objects,
+    Symbols,
+    Flags,
+    // Special characters
+    Arrows,
+    Math,
+    Currency,
+    Punctuation,
+    Latin,
+    Greek,
+    Technical,
+    Shapes,
+    BoxDrawing,
+}
+
+impl CharacterCategory {
+    fn is_emoji(self) -> bool {
+        matches!(
+            self,
+            Self::SmileysAndEmotion
+                | Self::PeopleAndBody
+                | Self::AnimalsAndNature
+                | Self::FoodAndDrink
+                | Self::TravelAndPlaces
+                | Self::Activities
+                | Self::Objects
+                | Self::Symbols
+                | Self::Flags
+        )
+    }
+}
+
+impl From<emojis::Group> for CharacterCategory {
+    fn from(group: emojis::Group) -> Self {
+        match group {
+            emojis::Group::SmileysAndEmotion => Self::SmileysAndEmotion,
+            emojis::Group::PeopleAndBody => Self::PeopleAndBody,
+            emojis::Group::AnimalsAndNature => Self::AnimalsAndNature,
+            emojis::Group::FoodAndDrink => Self::FoodAndDrink,
+            emojis::Group::TravelAndPlaces => Self::TravelAndPlaces,
+            emojis::Group::Activities => Self::Activities,
+            emojis::Group::Objects => Self::Objects,
+            emojis::Group::Symbols => Self::Symbols,
+            emojis::Group::Flags => Self::Flags,
+        }
+    }
+}
+
+/// Code point ranges of the special character categories
+const SPECIAL_RANGES: &[(CharacterCategory, u32, u32)] = &[
+    (CharacterCategory::Punctuation, 0x00A1, 0x00BF),
+    (CharacterCategory::Punctuation, 0x2010, 0x205E),
+    (CharacterCategory::Latin, 0x00C0, 0x024F),
+    (CharacterCategory::Greek, 0x0391, 0x03C9),
+    (CharacterCategory::Currency, 0x20A0, 0x20C0),
+    (CharacterCategory::Technical, 0x2100, 0x214F),
+    (CharacterCategory::Arrows, 0x2190, 0x21FF),
+    (CharacterCategory::Arrows, 0x27F0, 0x27FF),
+    (CharacterCategory::Math, 0x2150, 0x218B),
+    (CharacterCategory::Math, 0x2200, 0x22FF),
+    (CharacterCategory::Technical, 0x2300, 0x23FF),
+    (CharacterCategory::BoxDrawing, 0x2500, 0x259F),
+    (CharacterCategory::Shapes, 0x25A0, 0x25FF),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterEntry {
+    pub character: String,
+    /// Lowercase Unicode/CLDR name, e.g. "rightwards arrow"
+    pub name: String,
+    pub category: CharacterCategory,
+    /// Shortcodes for emoji ("thumbsup"), empty for special characters
+    pub keywords: Vec<String>,
+    /// "U+1F44D"; sequences (flags, ZWJ emoji) have several
+    pub codepoints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CharactersData {
+    /// Most recent first
+    pub recent: Vec<String>,
+}
+
+pub fn load_characters_data() -> Result<CharactersData, StorageError> {
+    let path = ensure_app_data_dir()?.join("characters.json");
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        Ok(CharactersData::default())
+    }
+}
+
+pub fn save_characters_data(data: &CharactersData) -> Result<(), StorageError> {
+    let path = ensure_app_data_dir()?.join("characters.json");
+    fs::write(path, serde_json::to_string_pretty(data)?)?;
+    Ok(())
+}
+
+fn codepoints(text: &str) -> Vec<String> {
+    text.chars()
+        // Variation selectors only pick emoji vs text presentation
+        .filter(|c| !matches!(*c as u32, 0xFE0E | 0xFE0F))
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect()
+}
+
+fn characters() -> &'static [CharacterEntry] {
+    static CHARACTERS: OnceLock<Vec<CharacterEntry>> = OnceLock::new();
+    CHARACTERS.get_or_init(|| {
+        let emoji = emojis::iter().map(|emoji| CharacterEntry {
+            character: emoji.as_str().to_string(),
+            name: emoji.name().to_string(),
+            category: emoji.group().into(),
+            keywords: emoji.shortcodes().map(str::to_string).collect(),
+            codepoints: codepoints(emoji.as_str()),
+        });
+        let special = SPECIAL_RANGES.iter().flat_map(|&(category, start, end)| {
+            (start..=end).filter_map(move |code| {
+                let c = char::from_u32(code)?;
+                let name = unicode_names2::name(c)?.to_string().to_lowercase();
+                Some(CharacterEntry {
+                    character: c.to_string(),
+                    name,
+                    category,
+                    keywords: Vec::new(),
+                    codepoints: vec![format!("U+{:04X}", code)],
+                })
+            })
+        });
+        emoji.chain(special).collect()
+    })
+}
+
+/// Lower is better; None when the entry doesn't match every query word
+fn match_rank(entry: &CharacterEntry, query: &str, words: &[&str]) -> Option<u8> {
+    if entry.character == query
+        || entry.name == query
+        || entry.keywords.iter().any(|k| k == query)
+        || entry.codepoints.iter().any(|cp| cp.eq_ignore_ascii_case(query))
+    {
+        return Some(0);
+    }
+    if entry.name.starts_with(query) || entry.keywords.iter().any(|k| k.starts_with(query)) {
+        return Some(1);
+    }
+    let name_words: Vec<&str> = entry.name.split([' ', '-']).collect();
+    let matches_word = |word: &str| {
+        name_words.iter().any(|w| w.starts_with(word))
+            || entry.keywords.iter().any(|k| k.split('_').any(|part| part.starts_with(word)))
+    };
+    if words.iter().all(|word| matches_word(word)) {
+        return Some(2);
+    }
+    words.iter().all(|word| entry.name.contains(word)).then_some(3)
+}
+
+pub fn search(query: &str, category: Option<CharacterCategory>, recent: &[String]) -> Vec<CharacterEntry> {
+    let query = query.trim().trim_matches(':').to_lowercase();
+    let in_category = |entry: &&CharacterEntry| category.map_or(true, |c| entry.category == c);
+
+    if query.is_empty() {
+        // Recently used first, then the category in its natural order
+        let all = characters();
+        let recent_entries = recent
+            .iter()
+            .filter_map(|character| all.iter().find(|entry| &entry.character == character))
+            .filter(in_category);
+        let rest = all
+            .iter()
+            .filter(in_category)
+            .filter(|entry| category.is_some() || entry.category.is_emoji())
+            .filter(|entry| !recent.contains(&entry.character));
+        return recent_entries.chain(rest).take(MAX_RESULTS).cloned().collect();
+    }
+
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let mut ranked: Vec<(u8, bool, usize, &CharacterEntry)> = characters()
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| in_category(entry))
+        .filter_map(|(index, entry)| {
+            let rank = match_rank(entry, &query, &words)?;
+            Some((rank, !recent.contains(&entry.character), index, entry))
+        })
+        .collect();
+    ranked.sort_by_key(|&(rank, not_recent, index, _)| (rank, not_recent, index));
+    ranked.into_iter().take(MAX_RESULTS).map(|(.., entry)| entry.clone()).collect()
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Search emoji and special characters by name, shortcode, code point ("U+2192") or the
+/// character itself. An empty query lists the category, recently used first.
+#[tauri::command]
+pub fn search_characters(query: String, category: Option<CharacterCategory>) -> Result<Vec<CharacterEntry>, AppError> {
+    let data = load_characters_data()?;
+    Ok(search(&query, category, &data.recent))
+}
+
+#[tauri::command]
+pub fn get_recent_characters() -> Result<Vec<String>, AppError> {
+    Ok(load_characters_data()?.recent)
+}
+
+/// Move a picked character to the front of the recently used list
+#[tauri::command]
+pub fn record_character_use(character: String) -> Result<Vec<String>, AppError> {
+    if character.is_empty() {
+        return Err(AppError::invalid_input("Character is empty"));
+    }
+    let mut data = load_characters_data()?;
+    data.recent.retain(|c| c != &character);
+    data.recent.insert(0, character);
+    data.recent.truncate(MAX_RECENT);
+    save_characters_data(&data)?;
+    Ok(data.recent)
+}
+
+#[tauri::command]
+pub fn clear_recent_characters() -> Result<(), AppError> {
+    save_characters_data(&CharactersData::default()).map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_characters() {
+        let results = search("thumbsup", None, &[]);
+        assert_eq!(results[0].character, "👍");
+        assert_eq!(results[0].category, CharacterCategory::PeopleAndBody);
+
+        let arrows = search("right arrow", Some(CharacterCategory::Arrows), &[]);
+        assert!(arrows.iter().any(|e| e.character == "→"));
+        assert!(arrows.iter().all(|e| e.category == CharacterCategory::Arrows));
+
+        assert_eq!(search("U+20AC", None, &[])[0].character, "€");
+        assert_eq!(search("e with acute", None, &[])[0].name, "latin capital letter e with acute");
+
+        let recent = vec!["🎉".to_string()];
+        assert_eq!(search("", None, &recent)[0].character, "🎉");
+    }
+}
@@ -45,6 +45,101 @@ pub enum TextTransform {
     RemoveDuplicateLines,
     ReverseLines,
     BulletList,
+    /// "1. " before each non-blank line
+    NumberLines,
+    /// Non-blank lines, trimmed, joined with the separator
+    JoinLines { separator: String },
+    /// One line per separated piece, trimmed; empty pieces are dropped
+    SplitBySeparatorToLines { separator: String },
+    /// Wrap each non-blank line, e.g. in quotes or a function call
+    AddPrefixSuffix { prefix: String, suffix: String },
+    ShuffleLines,
+}
+
+/// Arguments of the parameterized transforms, as transform_text_cmd receives them.
+/// A typed `\n` or `\t` (backslash and letter) means a newline or a tab, and `\\` a
+/// backslash.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransformParams {
+    pub separator: Option<String>,
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+}
+
+fn unescape_param(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('\\') => unescaped.push('\\'),
+            // Anything else keeps its backslash
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+impl TextTransform {
+    /// The name transform_text_cmd and the action registry use ("camelcase", "sort", ...).
+    /// Parameterized transforms get their defaults; see with_params.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::with_params(name, &TransformParams::default()).ok()
+    }
+
+    /// The named transform with its arguments. Joining defaults to ", " and splitting
+    /// to ","; prefixsuffix needs a prefix or a suffix.
+    pub fn with_params(name: &str, params: &TransformParams) -> Result<Self, String> {
+        let separator = |default: &str| {
+            let separator = params.separator.as_deref().map(unescape_param).unwrap_or_else(|| default.to_string());
+            if separator.is_empty() {
+                Err("The separator can't be empty".to_string())
+            } else {
+                Ok(separator)
+            }
+        };
+        Ok(match name {
+            "uppercase" => TextTransform::Uppercase,
+            "lowercase" => TextTransform::Lowercase,
+            "titlecase" => TextTransform::TitleCase,
+            "sentencecase" => TextTransform::SentenceCase,
+            "camelcase" => TextTransform::CamelCase,
+            "snakecase" => TextTransform::SnakeCase,
+            "kebabcase" => TextTransform::KebabCase,
+            "trim" => TextTransform::TrimWhitespace,
+            "sort" => TextTransform::SortLines,
+            "deduplicate" => TextTransform::RemoveDuplicateLines,
+            "reverse" => TextTransform::ReverseLines,
+            "bulletlist" => TextTransform::BulletList,
+            "numberlines" => TextTransform::NumberLines,
+            "joinlines" => TextTransform::JoinLines {
+                separator: separator(", ")?,
+            },
+            "splitlines" => TextTransform::SplitBySeparatorToLines {
+                separator: separator(",")?,
+            },
+            "prefixsuffix" => {
+                let prefix = params.prefix.as_deref().map(unescape_param).unwrap_or_default();
+                let suffix = params.suffix.as_deref().map(unescape_param).unwrap_or_default();
+                if prefix.is_empty() && suffix.is_empty() {
+                    return Err("Add a prefix, a suffix or both".to_string());
+                }
+                TextTransform::AddPrefixSuffix { prefix, suffix }
+            }
+            "shufflelines" => TextTransform::ShuffleLines,
+            _ => return Err(format!("Unknown transform: {}", name)),
+        })
+    }
 }
 
 pub fn transform_text(text: &str, transform: TextTransform) -> String {
@@ -110,6 +205,49 @@ pub fn transform_text(text: &str, transform: TextTransform) -> String {
                     .join("\n")
             }
         }
+        TextTransform::NumberLines => {
+            let mut number = 0;
+            text.lines()
+                .map(|line| {
+                    if line.trim().is_empty() {
+                        line.to_string()
+                    } else {
+                        number += 1;
+                        format!("{}. {}", number, line)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        TextTransform::JoinLines { separator } => text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(&separator),
+        TextTransform::SplitBySeparatorToLines { separator } => text
+            .split(separator.as_str())
+            .map(|piece| piece.trim())
+            .filter(|piece| !piece.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        TextTransform::AddPrefixSuffix { prefix, suffix } => text
+            .lines()
+            .map(|line| {
+                if line.trim().is_empty() {
+                    line.to_string()
+                } else {
+                    format!("{}{}{}", prefix, line, suffix)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        TextTransform::ShuffleLines => {
+            use rand::seq::SliceRandom;
+            let mut lines: Vec<&str> = text.lines().collect();
+            lines.shuffle(&mut rand::thread_rng());
+            lines.join("\n")
+        }
     }
 }
 
@@ -209,3 +347,115 @@ pub fn count_occurrences(text: &str, pattern: &str) -> usize {
     }
     text.matches(pattern).count()
 }
+
+/// Clipboard representations offered by "Copy as..."
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CopyFormat {
+    Markdown,
+    Rtf,
+    Plain,
+    Html,
+}
+
+/// The pasteboard flavors to write for one copy. Plain text is always present
+/// so apps without rich text support still get something sensible.
+#[derive(Debug, Clone)]
+pub struct ClipboardPayload {
+    pub text: String,
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+/// Wrap content in a Markdown code fence. The fence is made longer than any
+/// backtick run inside the content so embedded fences don't terminate it early.
+pub fn markdown_code_fence(content: &str, language: Option<&str>) -> String {
+    let longest_run = content
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let language = language.filter(|l| *l != "plaintext").unwrap_or("");
+    let body = content.strip_suffix('\n').unwrap_or(content);
+    format!("{fence}{language}\n{body}\n{fence}")
+}
+
+pub fn convert_for_copy(
+    content: &str,
+    format: CopyFormat,
+    language: Option<&str>,
+) -> Result<ClipboardPayload, String> {
+    let payload = match format {
+        CopyFormat::Markdown => ClipboardPayload {
+            text: markdown_code_fence(content, language),
+            html: None,
+            rtf: None,
+        },
+        CopyFormat::Plain => ClipboardPayload {
+            text: content.replace("\r\n", "\n"),
+            html: None,
+            rtf: None,
+        },
+        CopyFormat::Html => ClipboardPayload {
+            text: content.to_string(),
+            html: Some(crate::highlight::to_html(content, language)?),
+            rtf: None,
+        },
+        // Word prefers RTF while Google Docs only reads HTML, so offer both
+        CopyFormat::Rtf => ClipboardPayload {
+            text: content.to_string(),
+            html: Some(crate::highlight::to_html(content, language)?),
+            rtf: Some(crate::highlight::to_rtf(content, language)?),
+        },
+    };
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(separator: Option<&str>, prefix: Option<&str>, suffix: Option<&str>) -> TransformParams {
+        TransformParams {
+            separator: separator.map(str::to_string),
+            prefix: prefix.map(str::to_string),
+            suffix: suffix.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_list_transforms() {
+        assert_eq!(transform_text("a\n\nb", TextTransform::NumberLines), "1. a\n\n2. b");
+
+        let join = TextTransform::with_params("joinlines", &params(Some(" | "), None, None)).unwrap();
+        assert_eq!(transform_text(" a \n\nb\nc", join), "a | b | c");
+        let join = TextTransform::from_name("joinlines").unwrap();
+        assert_eq!(transform_text("a\nb", join), "a, b");
+
+        let split = TextTransform::with_params("splitlines", &params(Some("\\t"), None, None)).unwrap();
+        assert_eq!(transform_text("a\t b\t\tc", split), "a\nb\nc");
+
+        let wrap = TextTransform::with_params("prefixsuffix", &params(None, Some("'"), Some("',"))).unwrap();
+        assert_eq!(transform_text("x\n\ny", wrap), "'x',\n\n'y',");
+
+        let shuffled = transform_text("1\n2\n3\n4", TextTransform::ShuffleLines);
+        let mut lines: Vec<&str> = shuffled.lines().collect();
+        lines.sort();
+        assert_eq!(lines, vec!["1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn test_transform_params_are_validated() {
+        assert!(TextTransform::from_name("prefixsuffix").is_none());
+        assert!(TextTransform::with_params("splitlines", &params(Some(""), None, None)).is_err());
+        assert!(TextTransform::with_params("nope", &TransformParams::default()).is_err());
+        assert!(TextTransform::from_name("uppercase").is_some());
+    }
+
+    #[test]
+    fn test_unescape_param() {
+        assert_eq!(unescape_param("a\\nb\\tc"), "a\nb\tc");
+        assert_eq!(unescape_param("C:\\\\new"), "C:\\new");
+        assert_eq!(unescape_param("\\d\\"), "\\d\\");
+    }
+}
@@ -0,0 +1,444 @@
+// What's actually on the clipboard: every type of every pasteboard item with its size
+// and a preview (a text excerpt, image dimensions, file names), for debugging pastes
+// that come out differently in different apps.
+//
+// The platform code only lists the raw types and bytes (pasteboardItems on macOS, the
+// xclip targets on Linux); describing them is shared. When a password manager marks the
+// clipboard concealed (org.nspasteboard.ConcealedType), sizes are listed but no
+// previews. Windows isn't supported yet, like read_clipboard_rich.
+
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::privacy::is_privacy_mode_active;
+
+const PREVIEW_CHARS: usize = 200;
+const CONCEALED_TYPE: &str = "org.nspasteboard.ConcealedType";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlavorKind {
+    Text,
+    Image,
+    Files,
+    Data,
+}
+
+/// One type of one pasteboard item
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardFlavor {
+    /// Index of the pasteboard item; Linux always has one
+    pub item: usize,
+    /// UTI on macOS, MIME type or atom name on Linux
+    pub type_id: String,
+    pub size: usize,
+    pub kind: FlavorKind,
+    /// Excerpt of text types, whitespace collapsed
+    pub preview: Option<String>,
+    pub image_width: Option<u32>,
+    pub image_height: Option<u32>,
+    pub file_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardInventory {
+    pub items: usize,
+    pub flavors: Vec<ClipboardFlavor>,
+    pub total_size: usize,
+    /// Marked concealed by a password manager; previews are withheld
+    pub concealed: bool,
+}
+
+struct RawFlavor {
+    item: usize,
+    type_id: String,
+    bytes: Vec<u8>,
+}
+
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+mod platform {
+    use super::RawFlavor;
+    use cocoa::base::{id, nil};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe fn ns_string(string: id) -> String {
+        if string == nil {
+            return String::new();
+        }
+        let ptr: *const std::os::raw::c_char = msg_send![string, UTF8String];
+        if ptr.is_null() {
+            return String::new();
+        }
+        std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+
+    unsafe fn ns_data(data: id) -> Vec<u8> {
+        if data == nil {
+            return Vec::new();
+        }
+        let len: usize = msg_send![data, length];
+        let bytes: *const u8 = msg_send![data, bytes];
+        if bytes.is_null() || len == 0 {
+            return Vec::new();
+        }
+        std::slice::from_raw_parts(bytes, len).to_vec()
+    }
+
+    pub fn read_flavors() -> Result<Vec<RawFlavor>, String> {
+        let mut flavors = Vec::new();
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            if pasteboard == nil {
+                return Err("Failed to get the pasteboard".to_string());
+            }
+            let items: id = msg_send![pasteboard, pasteboardItems];
+            if items == nil {
+                return Ok(flavors);
+            }
+            let item_count: usize = msg_send![items, count];
+            for index in 0..item_count {
+                let item: id = msg_send![items, objectAtIndex: index];
+                let types: id = msg_send![item, types];
+                let type_count: usize = msg_send![types, count];
+                for type_index in 0..type_count {
+                    let uti: id = msg_send![types, objectAtIndex: type_index];
+                    let data: id = msg_send![item, dataForType: uti];
+                    flavors.push(RawFlavor {
+                        item: index,
+                        type_id: ns_string(uti),
+                        bytes: ns_data(data),
+                    });
+                }
+            }
+        }
+        Ok(flavors)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::RawFlavor;
+
+    /// Targets describing the selection rather than holding content
+    const META_TARGETS: &[&str] = &["TARGETS", "TIMESTAMP", "MULTIPLE", "SAVE_TARGETS", "DELETE"];
+
+    fn xclip_read(target: &str) -> Option<Vec<u8>> {
+        let output = std::process::Command::new("xclip")
+            .args(["-selection", "clipboard", "-o", "-t", target])
+            .output()
+            .ok()?;
+        output.status.success().then_some(output.stdout)
+    }
+
+    pub fn read_flavors() -> Result<Vec<RawFlavor>, String> {
+        let targets = xclip_read("TARGETS")
+            .ok_or_else(|| "Failed to read clipboard targets (is xclip installed?)".to_string())?;
+        Ok(String::from_utf8_lossy(&targets)
+            .lines()
+            .map(str::trim)
+            .filter(|target| !target.is_empty() && !META_TARGETS.contains(target))
+            .map(|target| RawFlavor {
+                item: 0,
+                type_id: target.to_string(),
+                bytes: xclip_read(target).unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+mod platform {
+    use super::RawFlavor;
+
+    pub fn read_flavors() -> Result<Vec<RawFlavor>, String> {
+        Err("Reading the clipboard inventory is not supported on this platform yet".to_string())
+    }
+}
+
+fn is_image_type(type_id: &str) -> bool {
+    type_id.starts_with("image/")
+        || matches!(
+            type_id,
+            "public.png"
+                | "public.jpeg"
+                | "public.tiff"
+                | "public.heic"
+                | "public.webp"
+                | "com.compuserve.gif"
+                | "com.microsoft.bmp"
+                | "NSTIFFPboardType"
+                | "Apple PNG pasteboard type"
+        )
+}
+
+fn is_file_type(type_id: &str) -> bool {
+    matches!(
+        type_id,
+        "public.file-url" | "NSFilenamesPboardType" | "text/uri-list" | "x-special/gnome-copied-files"
+    )
+}
+
+fn is_text_type(type_id: &str) -> bool {
+    let lower = type_id.to_ascii_lowercase();
+    // RTFD is a flattened bundle, not text
+    !lower.contains("rtfd")
+        && ["text", "string", "html", "rtf", "url", "json", "xml"]
+            .iter()
+            .any(|marker| lower.contains(marker))
+}
+
+fn is_utf16_type(type_id: &str) -> bool {
+    type_id.starts_with("public.utf16")
+}
+
+fn decode_text(type_id: &str, bytes: &[u8]) -> String {
+    if is_utf16_type(type_id) {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// First PREVIEW_CHARS characters with whitespace runs collapsed
+fn excerpt(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match collapsed.char_indices().nth(PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}…", &collapsed[..end]),
+        None => collapsed,
+    }
+}
+
+fn be_u16(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32)
+}
+
+fn le_u16(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32)
+}
+
+fn be_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Width and height of the first TIFF image, from the ImageWidth/ImageLength tags
+fn tiff_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let little = bytes.starts_with(b"II*\0");
+    if !little && !bytes.starts_with(b"MM\0*") {
+        return None;
+    }
+    let u16_at = |at| if little { le_u16(bytes, at) } else { be_u16(bytes, at) };
+    let u32_at = |at| if little { le_u32(bytes, at) } else { be_u32(bytes, at) };
+
+    let ifd = u32_at(4)? as usize;
+    let entries = u16_at(ifd)? as usize;
+    let (mut width, mut height) = (None, None);
+    for index in 0..entries {
+        let entry = ifd + 2 + index * 12;
+        let tag = u16_at(entry)?;
+        // SHORT values sit in the first two bytes of the value field, LONG in all four
+        let value = match u16_at(entry + 2)? {
+            3 => u16_at(entry + 8)?,
+            4 => u32_at(entry + 8)?,
+            _ => continue,
+        };
+        match tag {
+            256 => width = Some(value),
+            257 => height = Some(value),
+            _ => {}
+        }
+    }
+    Some((width?, height?))
+}
+
+/// Pixel dimensions from the image header: PNG, GIF, BMP, JPEG and TIFF
+fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be_u32(bytes, 16)?, be_u32(bytes, 20)?));
+    }
+    if bytes.starts_with(b"GIF8") {
+        return Some((le_u16(bytes, 6)?, le_u16(bytes, 8)?));
+    }
+    if bytes.starts_with(b"BM") {
+        // Height is negative for top-down bitmaps
+        return Some((le_u32(bytes, 18)?, (le_u32(bytes, 22)? as i32).unsigned_abs()));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        let mut at = 2;
+        while at + 9 < bytes.len() {
+            if bytes[at] != 0xFF {
+                return None;
+            }
+            let marker = bytes[at + 1];
+            // SOF0..SOF15 except DHT (C4), JPG (C8) and DAC (CC)
+            if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+                return Some((be_u16(bytes, at + 7)?, be_u16(bytes, at + 5)?));
+            }
+            at += 2 + be_u16(bytes, at + 2)? as usize;
+        }
+        return None;
+    }
+    tiff_dimensions(bytes)
+}
+
+/// File names from a file URL, a uri-list or an NSFilenamesPboardType property list
+fn file_names(text: &str) -> Vec<String> {
+    let paths: Vec<String> = if text.trim_start().starts_with("<?xml") || text.contains("<plist") {
+        text.split("<string>")
+            .skip(1)
+            .filter_map(|rest| rest.split("</string>").next())
+            .map(str::to_string)
+            .collect()
+    } else {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && *line != "copy" && *line != "cut")
+            .map(|line| line.strip_prefix("file://").unwrap_or(line).to_string())
+            .collect()
+    };
+    paths
+        .iter()
+        .filter_map(|path| {
+            let decoded = urlencoding::decode(path).map(|d| d.into_owned()).unwrap_or_else(|_| path.clone());
+            std::path::Path::new(decoded.trim_end_matches('/'))
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .collect()
+}
+
+fn describe(raw: RawFlavor, concealed: bool) -> ClipboardFlavor {
+    let mut flavor = ClipboardFlavor {
+        item: raw.item,
+        size: raw.bytes.len(),
+        kind: FlavorKind::Data,
+        preview: None,
+        image_width: None,
+        image_height: None,
+        file_names: Vec::new(),
+        type_id: raw.type_id,
+    };
+    if is_image_type(&flavor.type_id) {
+        flavor.kind = FlavorKind::Image;
+        if let Some((width, height)) = image_dimensions(&raw.bytes) {
+            flavor.image_width = Some(width);
+            flavor.image_height = Some(height);
+        }
+    } else if is_file_type(&flavor.type_id) {
+        flavor.kind = FlavorKind::Files;
+        if !concealed {
+            flavor.file_names = file_names(&decode_text(&flavor.type_id, &raw.bytes));
+        }
+    } else if is_text_type(&flavor.type_id) {
+        flavor.kind = FlavorKind::Text;
+        if !concealed {
+            flavor.preview = Some(excerpt(&decode_text(&flavor.type_id, &raw.bytes)));
+        }
+    }
+    flavor
+}
+
+fn build_inventory(raw: Vec<RawFlavor>) -> ClipboardInventory {
+    let concealed = raw.iter().any(|flavor| flavor.type_id == CONCEALED_TYPE);
+    let items = raw.iter().map(|flavor| flavor.item + 1).max().unwrap_or(0);
+    let flavors: Vec<ClipboardFlavor> = raw.into_iter().map(|flavor| describe(flavor, concealed)).collect();
+    ClipboardInventory {
+        items,
+        total_size: flavors.iter().map(|flavor| flavor.size).sum(),
+        flavors,
+        concealed,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Every type on the clipboard with its size and a preview
+#[tauri::command]
+pub fn read_clipboard_inventory() -> Result<ClipboardInventory, AppError> {
+    if is_privacy_mode_active() {
+        return Err(AppError::privacy_mode("Clipboard capture is paused while privacy mode is on"));
+    }
+    let raw = platform::read_flavors().map_err(AppError::unsupported)?;
+    Ok(build_inventory(raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(item: usize, type_id: &str, bytes: &[u8]) -> RawFlavor {
+        RawFlavor {
+            item,
+            type_id: type_id.to_string(),
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_image_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        assert_eq!(image_dimensions(&png), Some((640, 480)));
+
+        let gif = b"GIF89a\x20\x03\x58\x02";
+        assert_eq!(image_dimensions(gif), Some((800, 600)));
+
+        // SOI, an APP0 segment, then SOF0 with height 2 and width 3
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x02, 0x00, 0x03,
+            0x01, 0x01, 0x11, 0x00,
+        ];
+        assert_eq!(image_dimensions(&jpeg), Some((3, 2)));
+
+        // Little-endian TIFF: one IFD with ImageWidth (SHORT) 5 and ImageLength (LONG) 7
+        let mut tiff = b"II*\0\x08\0\0\0\x02\0".to_vec();
+        tiff.extend_from_slice(&[0x00, 0x01, 0x03, 0x00, 0x01, 0, 0, 0, 0x05, 0x00, 0, 0]);
+        tiff.extend_from_slice(&[0x01, 0x01, 0x04, 0x00, 0x01, 0, 0, 0, 0x07, 0x00, 0, 0]);
+        assert_eq!(image_dimensions(&tiff), Some((5, 7)));
+
+        assert_eq!(image_dimensions(b"not an image"), None);
+    }
+
+    #[test]
+    fn test_file_names() {
+        assert_eq!(file_names("file:///Users/me/My%20Notes.txt"), vec!["My Notes.txt"]);
+        assert_eq!(file_names("copy\nfile:///tmp/a.png\nfile:///tmp/dir/\n"), vec!["a.png", "dir"]);
+        let plist = "<?xml version=\"1.0\"?><plist><array><string>/tmp/one.rs</string>\
+                     <string>/tmp/two.md</string></array></plist>";
+        assert_eq!(file_names(plist), vec!["one.rs", "two.md"]);
+    }
+
+    #[test]
+    fn test_inventory_describes_and_conceals() {
+        let utf16: Vec<u8> = "hé".encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let inventory = build_inventory(vec![
+            raw(0, "public.utf8-plain-text", b"  hello\n\n  world  "),
+            raw(0, "public.utf16-plain-text", &utf16),
+            raw(0, "com.apple.flat-rtfd", &[1, 2, 3]),
+            raw(1, "public.file-url", b"file:///tmp/report.pdf"),
+        ]);
+        assert_eq!(inventory.items, 2);
+        assert_eq!(inventory.total_size, 18 + 4 + 3 + 22);
+        assert_eq!(inventory.flavors[0].preview.as_deref(), Some("hello world"));
+        assert_eq!(inventory.flavors[1].preview.as_deref(), Some("hé"));
+        assert_eq!(inventory.flavors[2].kind, FlavorKind::Data);
+        assert_eq!(inventory.flavors[3].file_names, vec!["report.pdf"]);
+
+        let concealed = build_inventory(vec![
+            raw(0, "public.utf8-plain-text", b"hunter2"),
+            raw(0, CONCEALED_TYPE, b""),
+        ]);
+        assert!(concealed.concealed);
+        assert_eq!(concealed.flavors[0].kind, FlavorKind::Text);
+        assert!(concealed.flavors[0].preview.is_none());
+        assert_eq!(concealed.flavors[0].size, 7);
+    }
+}
@@ -0,0 +1,606 @@
+// Renders highlighted code as a framed PNG (padding, window chrome, drop shadow) for
+// sharing in chat. Glyphs are rasterized straight from a monospace system font's
+// outlines so no browser/webview round trip is needed. Ligatures need a shaping
+// engine, so ligature fonts (Fira Code, JetBrains Mono) render their plain glyphs.
+
+use base64::Engine;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::PathBuf;
+use syntect::highlighting::{Color, FontStyle};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use ttf_parser::{Face, OutlineBuilder};
+
+use crate::error::AppError;
+use crate::highlight::{get_theme, highlight_lines};
+
+/// Larger images get rejected rather than allocating hundreds of megabytes
+const MAX_DIMENSION: u32 = 8192;
+const TAB_WIDTH: usize = 4;
+/// Subsamples per pixel row when rasterizing glyphs
+const SAMPLES: usize = 5;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CodeImageOptions {
+    /// Space around the window, in points
+    pub padding: u32,
+    /// "#rrggbb" or "transparent"
+    pub background: String,
+    pub window_chrome: bool,
+    /// Shown centered in the window chrome
+    pub title: Option<String>,
+    pub line_numbers: bool,
+    pub font_size: f32,
+    /// TTF/OTF/TTC file; defaults to the platform's monospace font
+    pub font_path: Option<String>,
+    /// Pixels per point (2 gives crisp images on retina displays)
+    pub scale: f32,
+    pub copy_to_clipboard: bool,
+}
+
+impl Default for CodeImageOptions {
+    fn default() -> Self {
+        Self {
+            padding: 48,
+            background: "#abb8c3".to_string(),
+            window_chrome: true,
+            title: None,
+            line_numbers: false,
+            font_size: 14.0,
+            font_path: None,
+            scale: 2.0,
+            copy_to_clipboard: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeImage {
+    pub width: u32,
+    pub height: u32,
+    /// "data:image/png;base64,..." for previews
+    pub data_url: String,
+    pub copied: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rgba {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+}
+
+impl Rgba {
+    fn parse(value: &str) -> Result<Self, String> {
+        if value.eq_ignore_ascii_case("transparent") {
+            return Ok(Rgba { r: 0, g: 0, b: 0, a: 0 });
+        }
+        let hex = value.trim().trim_start_matches('#');
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2).unwrap_or(""), 16);
+        match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Ok(r), Ok(g), Ok(b)) => Ok(Rgba { r, g, b, a: 255 }),
+            _ => Err(format!("Invalid color: {}", value)),
+        }
+    }
+
+    fn from_syntect(color: Color) -> Self {
+        Rgba {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: color.a,
+        }
+    }
+}
+
+// ============================================================================
+// Canvas
+// ============================================================================
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, fill: Rgba) -> Self {
+        let pixels = [fill.r, fill.g, fill.b, fill.a].repeat((width * height) as usize);
+        Canvas { width, height, pixels }
+    }
+
+    /// Composite `color` over the pixel with the given coverage (0..=1)
+    fn blend(&mut self, x: i64, y: i64, color: Rgba, coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let alpha = coverage.clamp(0.0, 1.0) * color.a as f32 / 255.0;
+        if alpha <= 0.0 {
+            return;
+        }
+        let i = ((y as u32 * self.width + x as u32) * 4) as usize;
+        let dst_alpha = self.pixels[i + 3] as f32 / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+        for (c, src) in [color.r, color.g, color.b].into_iter().enumerate() {
+            let dst = self.pixels[i + c] as f32;
+            self.pixels[i + c] = ((src as f32 * alpha + dst * dst_alpha * (1.0 - alpha)) / out_alpha).round() as u8;
+        }
+        self.pixels[i + 3] = (out_alpha * 255.0).round() as u8;
+    }
+
+    fn fill_rounded_rect(&mut self, x: f32, y: f32, width: f32, height: f32, radius: f32, color: Rgba) {
+        let (x1, y1) = (x + width, y + height);
+        for py in y.floor() as i64..y1.ceil() as i64 {
+            for px in x.floor() as i64..x1.ceil() as i64 {
+                let (cx, cy) = (px as f32 + 0.5, py as f32 + 0.5);
+                // Distance into the corner arc; 0 along the straight edges
+                let dx = (x + radius - cx).max(cx - (x1 - radius)).max(0.0);
+                let dy = (y + radius - cy).max(cy - (y1 - radius)).max(0.0);
+                let edge = if dx > 0.0 && dy > 0.0 {
+                    radius - (dx * dx + dy * dy).sqrt()
+                } else {
+                    (cx - x).min(x1 - cx).min(cy - y).min(y1 - cy)
+                };
+                self.blend(px, py, color, edge + 0.5);
+            }
+        }
+    }
+
+    fn fill_circle(&mut self, cx: f32, cy: f32, radius: f32, color: Rgba) {
+        self.fill_rounded_rect(cx - radius, cy - radius, radius * 2.0, radius * 2.0, radius, color);
+    }
+
+    fn into_image(self) -> Result<RgbaImage, String> {
+        RgbaImage::from_raw(self.width, self.height, self.pixels).ok_or_else(|| "Failed to build image".to_string())
+    }
+}
+
+// ============================================================================
+// Glyph rasterization
+// ============================================================================
+
+/// Flattens a glyph outline into line segments in pixel space (y down)
+struct Flattener {
+    scale: f32,
+    edges: Vec<(f32, f32, f32, f32)>,
+    start: (f32, f32),
+    last: (f32, f32),
+}
+
+impl Flattener {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.scale, -y * self.scale)
+    }
+
+    fn push_line(&mut self, to: (f32, f32)) {
+        if to != self.last {
+            self.edges.push((self.last.0, self.last.1, to.0, to.1));
+        }
+        self.last = to;
+    }
+}
+
+impl OutlineBuilder for Flattener {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.point(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.push_line(to);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (p0, p1, p2) = (self.last, self.point(x1, y1), self.point(x, y));
+        for step in 1..=8 {
+            let t = step as f32 / 8.0;
+            let mt = 1.0 - t;
+            self.push_line((
+                mt * mt * p0.0 + 2.0 * mt * t * p1.0 + t * t * p2.0,
+                mt * mt * p0.1 + 2.0 * mt * t * p1.1 + t * t * p2.1,
+            ));
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (p0, p1, p2, p3) = (self.last, self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        for step in 1..=12 {
+            let t = step as f32 / 12.0;
+            let mt = 1.0 - t;
+            let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+            self.push_line((
+                a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+                a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+            ));
+        }
+    }
+
+    fn close(&mut self) {
+        let start = self.start;
+        self.push_line(start);
+    }
+}
+
+/// Coverage bitmap of one glyph, positioned relative to the pen on the baseline
+struct GlyphBitmap {
+    left: i64,
+    top: i64,
+    width: usize,
+    coverage: Vec<f32>,
+}
+
+fn add_span(row: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let (x0, x1) = (x0.max(0.0), x1.min(row.len() as f32));
+    if x1 <= x0 {
+        return;
+    }
+    for (px, cell) in row.iter_mut().enumerate().take(x1.ceil() as usize).skip(x0.floor() as usize) {
+        let overlap = ((px + 1) as f32).min(x1) - (px as f32).max(x0);
+        *cell += overlap.max(0.0) * weight;
+    }
+}
+
+/// Non-zero winding scanline fill with vertical supersampling and exact horizontal coverage
+fn rasterize_glyph(face: &Face, c: char, scale: f32) -> Option<GlyphBitmap> {
+    let glyph = face.glyph_index(c)?;
+    let mut flattener = Flattener {
+        scale,
+        edges: Vec::new(),
+        start: (0.0, 0.0),
+        last: (0.0, 0.0),
+    };
+    face.outline_glyph(glyph, &mut flattener)?;
+    let edges = flattener.edges;
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+    for &(x0, y0, x1, y1) in &edges {
+        min_x = min_x.min(x0).min(x1);
+        max_x = max_x.max(x0).max(x1);
+        min_y = min_y.min(y0).min(y1);
+        max_y = max_y.max(y0).max(y1);
+    }
+    let (left, top) = (min_x.floor() as i64, min_y.floor() as i64);
+    let width = (max_x.ceil() as i64 - left).max(1) as usize;
+    let height = (max_y.ceil() as i64 - top).max(1) as usize;
+
+    let mut coverage = vec![0.0f32; width * height];
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+    for row in 0..height {
+        for sample in 0..SAMPLES {
+            let y = top as f32 + row as f32 + (sample as f32 + 0.5) / SAMPLES as f32;
+            crossings.clear();
+            for &(x0, y0, x1, y1) in &edges {
+                if (y0 <= y && y1 > y) || (y1 <= y && y0 > y) {
+                    let t = (y - y0) / (y1 - y0);
+                    crossings.push((x0 + t * (x1 - x0) - left as f32, if y1 > y0 { 1 } else { -1 }));
+                }
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let row_coverage = &mut coverage[row * width..(row + 1) * width];
+            let mut winding = 0;
+            let mut span_start = 0.0;
+            for &(x, direction) in &crossings {
+                let before = winding;
+                winding += direction;
+                if before == 0 && winding != 0 {
+                    span_start = x;
+                } else if before != 0 && winding == 0 {
+                    add_span(row_coverage, span_start, x, 1.0 / SAMPLES as f32);
+                }
+            }
+        }
+    }
+
+    Some(GlyphBitmap {
+        left,
+        top,
+        width,
+        coverage,
+    })
+}
+
+struct TextRenderer<'a> {
+    face: Face<'a>,
+    scale: f32,
+    advance: f32,
+    cache: HashMap<char, Option<GlyphBitmap>>,
+}
+
+impl<'a> TextRenderer<'a> {
+    fn new(face: Face<'a>, pixel_size: f32) -> Self {
+        let scale = pixel_size / face.units_per_em() as f32;
+        let advance = face
+            .glyph_index('0')
+            .and_then(|g| face.glyph_hor_advance(g))
+            .unwrap_or(face.units_per_em() / 2) as f32
+            * scale;
+        TextRenderer {
+            face,
+            scale,
+            advance,
+            cache: HashMap::new(),
+        }
+    }
+
+    fn draw_char(&mut self, canvas: &mut Canvas, c: char, x: f32, baseline: f32, color: Rgba) {
+        let (face, scale) = (&self.face, self.scale);
+        let glyph = self.cache.entry(c).or_insert_with(|| {
+            rasterize_glyph(face, c, scale).or_else(|| rasterize_glyph(face, '?', scale))
+        });
+        let Some(glyph) = glyph else {
+            return;
+        };
+        let (origin_x, origin_y) = (x.round() as i64 + glyph.left, baseline.round() as i64 + glyph.top);
+        for (i, &value) in glyph.coverage.iter().enumerate() {
+            if value > 0.0 {
+                let (gx, gy) = ((i % glyph.width) as i64, (i / glyph.width) as i64);
+                canvas.blend(origin_x + gx, origin_y + gy, color, value);
+            }
+        }
+    }
+
+    /// Draws `text` from `x`, returning the column count used
+    fn draw_text(&mut self, canvas: &mut Canvas, text: &str, x: f32, baseline: f32, color: Rgba) -> usize {
+        let mut columns = 0;
+        for c in text.chars() {
+            if !c.is_whitespace() && !c.is_control() {
+                self.draw_char(canvas, c, x + columns as f32 * self.advance, baseline, color);
+            }
+            columns += 1;
+        }
+        columns
+    }
+}
+
+// ============================================================================
+// Fonts
+// ============================================================================
+
+#[cfg(target_os = "macos")]
+fn home_font(name: &str) -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Library/Fonts").join(name))
+}
+
+/// Monospace fonts to try, ligature fonts the user installed first
+fn default_font_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    #[cfg(target_os = "macos")]
+    {
+        for name in ["FiraCode-Regular.ttf", "JetBrainsMono-Regular.ttf"] {
+            paths.extend(home_font(name));
+            paths.push(PathBuf::from("/Library/Fonts").join(name));
+        }
+        paths.extend(
+            ["/System/Library/Fonts/SFNSMono.ttf", "/System/Library/Fonts/Menlo.ttc", "/System/Library/Fonts/Monaco.ttf"]
+                .map(PathBuf::from),
+        );
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let fonts = std::env::var("WINDIR").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("C:\\Windows")).join("Fonts");
+        paths.extend(["CascadiaCode.ttf", "consola.ttf", "cour.ttf"].map(|name| fonts.join(name)));
+    }
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            paths.push(home.join(".local/share/fonts/FiraCode-Regular.ttf"));
+            paths.push(home.join(".local/share/fonts/JetBrainsMono-Regular.ttf"));
+        }
+        paths.extend(
+            [
+                "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+                "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+                "/usr/share/fonts/dejavu/DejaVuSansMono.ttf",
+                "/usr/share/fonts/truetype/liberation/LiberationMono-Regular.ttf",
+                "/usr/share/fonts/liberation-mono/LiberationMono-Regular.ttf",
+                "/usr/share/fonts/truetype/noto/NotoSansMono-Regular.ttf",
+            ]
+            .map(PathBuf::from),
+        );
+    }
+    paths
+}
+
+fn load_font(path: Option<&str>) -> Result<Vec<u8>, String> {
+    if let Some(path) = path.filter(|p| !p.is_empty()) {
+        return std::fs::read(path).map_err(|e| format!("Failed to read font {}: {}", path, e));
+    }
+    default_font_paths()
+        .into_iter()
+        .find_map(|path| std::fs::read(path).ok())
+        .ok_or_else(|| "No monospace font found; set a font file in the options".to_string())
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+pub fn render(text: &str, language: Option<&str>, theme: Option<&str>, options: &CodeImageOptions) -> Result<RgbaImage, String> {
+    let font_data = load_font(options.font_path.as_deref())?;
+    let face = Face::parse(&font_data, 0).map_err(|e| format!("Invalid font: {}", e))?;
+    render_with_face(text, language, theme, options, face)
+}
+
+fn render_with_face(
+    text: &str,
+    language: Option<&str>,
+    theme: Option<&str>,
+    options: &CodeImageOptions,
+    face: Face,
+) -> Result<RgbaImage, String> {
+    let text = text.replace('\t', &" ".repeat(TAB_WIDTH));
+    let text = text.trim_end_matches('\n');
+    let theme = get_theme(theme);
+    let lines = highlight_lines(text, language, theme)?;
+
+    let scale = options.scale.clamp(1.0, 4.0);
+    let font_px = options.font_size.clamp(6.0, 72.0) * scale;
+    let ascender = face.ascender() as f32 * font_px / face.units_per_em() as f32;
+    let descender = face.descender() as f32 * font_px / face.units_per_em() as f32;
+    let mut renderer = TextRenderer::new(face, font_px);
+
+    let foreground = theme.settings.foreground.map(Rgba::from_syntect).unwrap_or(Rgba { r: 36, g: 41, b: 46, a: 255 });
+    let window_background = theme.settings.background.map(Rgba::from_syntect).unwrap_or(Rgba { r: 255, g: 255, b: 255, a: 255 });
+    let dim = Rgba { a: 110, ..foreground };
+
+    let line_count = lines.len().max(1);
+    let gutter_columns = if options.line_numbers { line_count.to_string().len() + 2 } else { 0 };
+    let max_columns = text.lines().map(|l| l.chars().count()).max().unwrap_or(0).max(1);
+
+    let line_height = (font_px * 1.5).round();
+    let inner = (20.0 * scale).round();
+    let chrome = if options.window_chrome { (36.0 * scale).round() } else { 0.0 };
+    let padding = options.padding.min(256) as f32 * scale;
+    let window_width = ((gutter_columns + max_columns) as f32 * renderer.advance + inner * 2.0).ceil();
+    let window_height = (chrome + line_count as f32 * line_height + inner * 2.0).ceil();
+    let width = (window_width + padding * 2.0).ceil() as u32;
+    let height = (window_height + padding * 2.0).ceil() as u32;
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return Err("Snippet is too large to render as an image".to_string());
+    }
+
+    let mut canvas = Canvas::new(width, height, Rgba::parse(&options.background)?);
+    let radius = 8.0 * scale;
+
+    // Soft drop shadow: stacked translucent rects growing outwards
+    if padding >= 8.0 * scale {
+        let shadow = Rgba { r: 0, g: 0, b: 0, a: 6 };
+        for i in (1..=12).rev() {
+            let spread = i as f32 * scale;
+            canvas.fill_rounded_rect(
+                padding - spread,
+                padding - spread + 6.0 * scale,
+                window_width + spread * 2.0,
+                window_height + spread * 2.0,
+                radius + spread,
+                shadow,
+            );
+        }
+    }
+    canvas.fill_rounded_rect(padding, padding, window_width, window_height, radius, window_background);
+
+    if options.window_chrome {
+        let cy = padding + chrome / 2.0 + 4.0 * scale;
+        let lights = [
+            Rgba { r: 255, g: 95, b: 86, a: 255 },
+            Rgba { r: 255, g: 189, b: 46, a: 255 },
+            Rgba { r: 39, g: 201, b: 63, a: 255 },
+        ];
+        for (i, color) in lights.into_iter().enumerate() {
+            canvas.fill_circle(padding + inner + 6.0 * scale + i as f32 * 20.0 * scale, cy, 6.0 * scale, color);
+        }
+        if let Some(title) = options.title.as_deref().filter(|t| !t.trim().is_empty()) {
+            let title_width = title.chars().count() as f32 * renderer.advance;
+            let x = padding + (window_width - title_width) / 2.0;
+            renderer.draw_text(&mut canvas, title, x, cy + (ascender + descender) / 2.0, dim);
+        }
+    }
+
+    let text_left = padding + inner;
+    let baseline_offset = (line_height + ascender + descender) / 2.0;
+    for (index, spans) in lines.iter().enumerate() {
+        let baseline = padding + chrome + inner + index as f32 * line_height + baseline_offset;
+        if options.line_numbers {
+            let number = format!("{:>width$}", index + 1, width = gutter_columns - 2);
+            renderer.draw_text(&mut canvas, &number, text_left, baseline, dim);
+        }
+        let mut x = text_left + gutter_columns as f32 * renderer.advance;
+        for (style, segment) in spans {
+            let segment = segment.trim_end_matches(['\n', '\r']);
+            let color = Rgba::from_syntect(style.foreground);
+            let columns = renderer.draw_text(&mut canvas, segment, x, baseline, color);
+            if style.font_style.contains(FontStyle::UNDERLINE) {
+                let y = baseline + 2.0 * scale;
+                for px in x as i64..(x + columns as f32 * renderer.advance) as i64 {
+                    canvas.blend(px, y as i64, color, 1.0);
+                }
+            }
+            x += columns as f32 * renderer.advance;
+        }
+    }
+
+    canvas.into_image()
+}
+
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(png)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Render `text` as a framed, highlighted PNG and (by default) copy it to the clipboard
+#[tauri::command]
+pub async fn render_code_image(
+    app: AppHandle,
+    text: String,
+    language: Option<String>,
+    theme: Option<String>,
+    options: Option<CodeImageOptions>,
+) -> Result<CodeImage, AppError> {
+    if text.trim().is_empty() {
+        return Err(AppError::invalid_input("Nothing to render"));
+    }
+    let options = options.unwrap_or_default();
+    let render_options = options.clone();
+    let image = tokio::task::spawn_blocking(move || render(&text, language.as_deref(), theme.as_deref(), &render_options))
+        .await??;
+
+    let png = encode_png(&image)?;
+    let (width, height) = image.dimensions();
+    if options.copy_to_clipboard {
+        app.clipboard()
+            .write_image(&tauri::image::Image::new_owned(image.into_raw(), width, height))
+            .map_err(|e| format!("Failed to copy image: {}", e))?;
+    }
+
+    Ok(CodeImage {
+        width,
+        height,
+        data_url: format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(&png)
+        ),
+        copied: options.copy_to_clipboard,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert!(matches!(Rgba::parse("#ff8000"), Ok(Rgba { r: 255, g: 128, b: 0, a: 255 })));
+        assert_eq!(Rgba::parse("transparent").unwrap().a, 0);
+        assert!(Rgba::parse("#fff").is_err());
+    }
+
+    #[test]
+    fn test_add_span_partial_coverage() {
+        let mut row = vec![0.0; 4];
+        add_span(&mut row, 0.5, 2.25, 1.0);
+        assert_eq!(row, vec![0.5, 1.0, 0.25, 0.0]);
+    }
+
+    #[test]
+    fn test_rounded_rect_corners_are_transparent() {
+        let mut canvas = Canvas::new(20, 20, Rgba { r: 0, g: 0, b: 0, a: 0 });
+        canvas.fill_rounded_rect(0.0, 0.0, 20.0, 20.0, 8.0, Rgba { r: 255, g: 0, b: 0, a: 255 });
+        assert_eq!(canvas.pixels[3], 0);
+        let center = ((10 * 20 + 10) * 4) as usize;
+        assert_eq!(&canvas.pixels[center..center + 4], &[255, 0, 0, 255]);
+    }
+}
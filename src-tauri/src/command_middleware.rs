@@ -0,0 +1,343 @@
+// Middleware around the invoke handler. Every command from the webview passes through
+// `handle`, which refuses network commands in offline mode (see offline.rs) and
+// all but the allowed ones in presentation mode (see presentation.rs), turns away
+// expensive commands (AI, gists, the HTTP runner) once their group has used up its
+// per-minute budget, and counts invocations for the diagnostics view. With the audit
+// log switched on in settings, each invocation is also appended to command_audit.log as
+// a JSON line (name, duration, success; never arguments), rotated at AUDIT_LOG_MAX_BYTES.
+//
+// Tauri doesn't hand the handler a command's result, only whether it was dispatched, and
+// async commands return to it as soon as they're spawned. So for most commands a record
+// covers dispatch: the time spent inline and whether a command by that name accepted the
+// call. The rate-limited commands, where timings matter, report their real duration and
+// outcome through `track` instead.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::error::{AppError, ErrorCode};
+use crate::offline;
+use crate::presentation;
+use crate::storage::ensure_app_data_dir;
+
+const WINDOW: Duration = Duration::from_secs(60);
+const AUDIT_LOG_FILE: &str = "command_audit.log";
+const AUDIT_LOG_MAX_BYTES: u64 = 1024 * 1024;
+/// Rotated files kept next to the live one (command_audit.log.1, .2, ...)
+const AUDIT_LOG_BACKUPS: u32 = 3;
+
+struct Limit {
+    group: &'static str,
+    per_minute: usize,
+    commands: &'static [&'static str],
+}
+
+const LIMITS: [Limit; 3] = [
+    Limit {
+        group: "ai",
+        per_minute: 20,
+        commands: &[
+            "call_ai_feature_cmd",
+            "run_ai_workflow",
+            "rerun_ai_interaction",
+            "summarize_text",
+            "translate_text",
+        ],
+    },
+    Limit {
+        group: "gist",
+        per_minute: 30,
+        commands: &[
+            "create_github_gist",
+            "list_wingman_gists",
+            "update_github_gist",
+            "delete_github_gist",
+            "sync_all_gist_snippets",
+            "resolve_gist_conflict",
+        ],
+    },
+    Limit {
+        group: "http",
+        per_minute: 60,
+        commands: &["run_http_request"],
+    },
+];
+
+fn limit_for(command: &str) -> Option<&'static Limit> {
+    LIMITS.iter().find(|limit| limit.commands.contains(&command))
+}
+
+/// Sliding one-minute windows of call times, per group
+#[derive(Default)]
+struct RateLimiter {
+    calls: HashMap<&'static str, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    /// Count a call, or return how long until the group has budget again
+    fn check(&mut self, limit: &Limit, now: Instant) -> Result<(), Duration> {
+        let calls = self.calls.entry(limit.group).or_default();
+        while calls.front().is_some_and(|&at| now.duration_since(at) >= WINDOW) {
+            calls.pop_front();
+        }
+        if calls.len() >= limit.per_minute {
+            let oldest = calls.front().copied().unwrap_or(now);
+            return Err(WINDOW.saturating_sub(now.duration_since(oldest)));
+        }
+        calls.push_back(now);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandStat {
+    pub command: String,
+    pub calls: u64,
+    pub failures: u64,
+    /// Calls turned away by the rate limiter (also counted as failures)
+    pub rate_limited: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+    pub last_called_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandStats {
+    /// Counts cover the current session, starting here
+    pub since: DateTime<Utc>,
+    /// Set while the audit log is on
+    pub audit_log_path: Option<String>,
+    /// Busiest first
+    pub commands: Vec<CommandStat>,
+}
+
+struct Middleware {
+    since: DateTime<Utc>,
+    limiter: RateLimiter,
+    stats: HashMap<String, CommandStat>,
+    audit_log: Option<PathBuf>,
+}
+
+impl Middleware {
+    fn new() -> Self {
+        Self {
+            since: Utc::now(),
+            limiter: RateLimiter::default(),
+            stats: HashMap::new(),
+            audit_log: None,
+        }
+    }
+
+    fn record(&mut self, command: &str, duration: Duration, success: bool, rate_limited: bool) {
+        let now = Utc::now();
+        let ms = duration.as_secs_f64() * 1000.0;
+        let stat = self
+            .stats
+            .entry(command.to_string())
+            .or_insert_with(|| CommandStat {
+                command: command.to_string(),
+                calls: 0,
+                failures: 0,
+                rate_limited: 0,
+                total_ms: 0.0,
+                max_ms: 0.0,
+                last_called_at: now,
+            });
+        stat.calls += 1;
+        stat.failures += u64::from(!success);
+        stat.rate_limited += u64::from(rate_limited);
+        stat.total_ms += ms;
+        stat.max_ms = stat.max_ms.max(ms);
+        stat.last_called_at = now;
+
+        if let Some(path) = &self.audit_log {
+            let line = json!({
+                "at": now.to_rfc3339(),
+                "command": command,
+                "duration_ms": ms,
+                "success": success,
+            });
+            if let Err(e) = append_line(path, &line.to_string()) {
+                log::warn!("Failed to write the command audit log: {}", e);
+            }
+        }
+    }
+}
+
+static MIDDLEWARE: Mutex<Option<Middleware>> = Mutex::new(None);
+
+fn with_middleware<T>(f: impl FnOnce(&mut Middleware) -> T) -> T {
+    let mut guard = MIDDLEWARE.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(Middleware::new))
+}
+
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if fs::metadata(path).is_ok_and(|meta| meta.len() >= AUDIT_LOG_MAX_BYTES) {
+        for index in (1..AUDIT_LOG_BACKUPS).rev() {
+            let from = rotated_path(path, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(path, index + 1))?;
+            }
+        }
+        fs::rename(path, rotated_path(path, 1))?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Turn the audit log on or off to match settings
+pub fn configure(audit_log_enabled: bool) -> Result<(), String> {
+    let path = if audit_log_enabled {
+        let dir = ensure_app_data_dir().map_err(|e| e.to_string())?;
+        Some(dir.join(AUDIT_LOG_FILE))
+    } else {
+        None
+    };
+    with_middleware(|middleware| middleware.audit_log = path);
+    Ok(())
+}
+
+/// Put the middleware in front of a generate_handler! closure
+pub fn wrap<R, F>(inner: F) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| handle(invoke, &inner)
+}
+
+fn handle<R: Runtime>(invoke: Invoke<R>, inner: &impl Fn(Invoke<R>) -> bool) -> bool {
+    let command = invoke.message.command().to_string();
+
+    if offline::blocks(&command) {
+        with_middleware(|middleware| middleware.record(&command, Duration::ZERO, false, false));
+        invoke.resolver.reject(AppError::offline());
+        return true;
+    }
+
+    if presentation::blocks(&command) {
+        with_middleware(|middleware| middleware.record(&command, Duration::ZERO, false, false));
+        invoke.resolver.reject(presentation::blocked_error());
+        return true;
+    }
+
+    if let Some(limit) = limit_for(&command) {
+        let verdict = with_middleware(|middleware| {
+            let verdict = middleware.limiter.check(limit, Instant::now());
+            if verdict.is_err() {
+                middleware.record(&command, Duration::ZERO, false, true);
+            }
+            verdict
+        });
+        if let Err(retry_after) = verdict {
+            let seconds = retry_after.as_secs().max(1);
+            invoke.resolver.reject(
+                AppError::new(
+                    ErrorCode::QuotaExceeded,
+                    format!("Too many requests, try again in {} seconds", seconds),
+                )
+                .with_details(json!({
+                    "group": limit.group,
+                    "limit_per_minute": limit.per_minute,
+                    "retry_after_secs": seconds,
+                })),
+            );
+            return true;
+        }
+        // Reported by `track` when the command finishes
+        return inner(invoke);
+    }
+
+    let started = Instant::now();
+    let handled = inner(invoke);
+    with_middleware(|middleware| middleware.record(&command, started.elapsed(), handled, false));
+    handled
+}
+
+/// Time an async command to completion and record its outcome
+pub async fn track<T>(
+    command: &'static str,
+    task: impl Future<Output = Result<T, AppError>>,
+) -> Result<T, AppError> {
+    let started = Instant::now();
+    let result = task.await;
+    with_middleware(|middleware| middleware.record(command, started.elapsed(), result.is_ok(), false));
+    result
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Per-command call counts, failures and timings for this session
+#[tauri::command]
+pub fn get_command_stats() -> CommandStats {
+    with_middleware(|middleware| {
+        let mut commands: Vec<CommandStat> = middleware.stats.values().cloned().collect();
+        commands.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.command.cmp(&b.command)));
+        CommandStats {
+            since: middleware.since,
+            audit_log_path: middleware
+                .audit_log
+                .as_ref()
+                .map(|path| path.to_string_lossy().to_string()),
+            commands,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_window() {
+        let limit = Limit {
+            group: "test",
+            per_minute: 2,
+            commands: &[],
+        };
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        assert!(limiter.check(&limit, start).is_ok());
+        assert!(limiter.check(&limit, start + Duration::from_secs(10)).is_ok());
+
+        let retry_after = limiter.check(&limit, start + Duration::from_secs(20)).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(40));
+
+        // The first call has aged out of the window
+        assert!(limiter.check(&limit, start + Duration::from_secs(60)).is_ok());
+        assert_eq!(limit_for("run_http_request").map(|l| l.group), Some("http"));
+        assert!(limit_for("get_settings").is_none());
+    }
+
+    #[test]
+    fn test_audit_log_rotation() {
+        let dir = std::env::temp_dir().join(format!("wingman-audit-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(AUDIT_LOG_FILE);
+
+        fs::write(&path, vec![b'x'; AUDIT_LOG_MAX_BYTES as usize]).unwrap();
+        append_line(&path, "{\"command\":\"a\"}").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"command\":\"a\"}\n");
+        assert_eq!(fs::metadata(rotated_path(&path, 1)).unwrap().len(), AUDIT_LOG_MAX_BYTES);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,322 @@
+// Runs command snippets (SnippetKind::Command): the snippet body is handed to a shell in
+// a child process and its output captured, so a snippet that's really a terminal command
+// can be run from Wingman and its output lands in the editor and history.
+//
+// The child is kept on a short leash, per CommandRunnerSettings:
+//
+// - The environment is cleared and only allowlisted variables are passed through, so
+//   tokens in the app's environment don't leak into commands.
+// - It runs in a scratch directory (app data `command_runs/`) unless one is configured,
+//   with stdin closed.
+// - It's killed at the timeout, keeping the output read so far. Only the shell itself
+//   is killed; processes it has started in the background are left alone.
+// - Output past MAX_OUTPUT_BYTES per stream is read and dropped, never buffered.
+// - By default the user confirms each run in a native dialog showing the command.
+//   Snippets whose content comes from a gist or URL always ask, since it can change
+//   without the user editing it, and can't be made command snippets in the first place.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+use crate::error::{AppError, ErrorCode};
+use crate::history::{add_entry, get_entry};
+use crate::storage::{ensure_app_data_dir, load_settings, load_snippets, CommandRunnerSettings, SnippetKind};
+use crate::AppState;
+
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+const MAX_TIMEOUT_SECS: u64 = 600;
+const SCRATCH_DIR: &str = "command_runs";
+const CONFIRM_PREVIEW_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandRunResult {
+    pub stdout: String,
+    pub stderr: String,
+    /// None when the command was killed (timeout or signal)
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// Some output was dropped past the size limit
+    pub truncated: bool,
+    pub duration_ms: u64,
+    /// History entry holding the output, when saved
+    pub history_id: Option<i64>,
+}
+
+/// The shell program and the flag that makes it run the next argument as a command
+fn shell_invocation(configured: Option<&str>) -> (String, &'static str) {
+    let shell = configured
+        .map(str::trim)
+        .filter(|shell| !shell.is_empty())
+        .map(str::to_string)
+        .or_else(|| if cfg!(windows) { None } else { std::env::var("SHELL").ok() })
+        .unwrap_or_else(|| if cfg!(windows) { "cmd".to_string() } else { "/bin/sh".to_string() });
+
+    // Either separator, so Windows paths are recognized in settings synced from elsewhere
+    let file_name = shell.rsplit(['/', '\\']).next().unwrap_or_default().to_lowercase();
+    let program = file_name.strip_suffix(".exe").unwrap_or(&file_name);
+    let flag = match program {
+        "cmd" => "/C",
+        "powershell" | "pwsh" => "-Command",
+        _ => "-c",
+    };
+    (shell, flag)
+}
+
+/// Read up to MAX_OUTPUT_BYTES into `buffer`, then drain the rest so the child never
+/// stalls on a full pipe. Returns whether anything was dropped. If the future is
+/// dropped, `buffer` keeps what was read so far.
+async fn read_capped(reader: &mut (impl AsyncRead + Unpin), buffer: &mut Vec<u8>) -> std::io::Result<bool> {
+    (&mut *reader).take(MAX_OUTPUT_BYTES as u64).read_to_end(buffer).await?;
+    let dropped = tokio::io::copy(reader, &mut tokio::io::sink()).await?;
+    Ok(dropped > 0)
+}
+
+fn working_dir(settings: &CommandRunnerSettings) -> Result<PathBuf, AppError> {
+    if let Some(dir) = settings.working_dir.as_deref().filter(|dir| !dir.trim().is_empty()) {
+        let path = PathBuf::from(dir);
+        if !path.is_dir() {
+            return Err(AppError::invalid_input(format!("{} is not a directory", dir)));
+        }
+        return Ok(path);
+    }
+    let dir = ensure_app_data_dir()?.join(SCRATCH_DIR);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// The command as shown in the confirm dialog, marked when it's been cut short
+fn confirm_preview(command: &str) -> String {
+    let total = command.chars().count();
+    if total <= CONFIRM_PREVIEW_CHARS {
+        return command.to_string();
+    }
+    let preview: String = command.chars().take(CONFIRM_PREVIEW_CHARS).collect();
+    format!(
+        "{}…\n\n(truncated, {} more characters not shown)",
+        preview,
+        total - CONFIRM_PREVIEW_CHARS
+    )
+}
+
+async fn confirm_run(name: &str, command: &str) -> bool {
+    use rfd::{AsyncMessageDialog, MessageButtons, MessageDialogResult, MessageLevel};
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::window::DIALOG_OPEN.store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    let preview = confirm_preview(command);
+    let result = AsyncMessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title(format!("Run \"{}\"?", name))
+        .set_description(preview)
+        .set_buttons(MessageButtons::OkCancelCustom("Run".to_string(), "Cancel".to_string()))
+        .show()
+        .await;
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::window::DIALOG_OPEN.store(false, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    match result {
+        MessageDialogResult::Ok | MessageDialogResult::Yes => true,
+        MessageDialogResult::Custom(label) => label == "Run",
+        _ => false,
+    }
+}
+
+async fn run(command: &str, settings: &CommandRunnerSettings) -> Result<CommandRunResult, AppError> {
+    let (shell, flag) = shell_invocation(settings.shell.as_deref());
+    let mut child = Command::new(&shell);
+    child
+        .arg(flag)
+        .arg(command)
+        .current_dir(working_dir(settings)?)
+        .env_clear()
+        .envs(
+            std::env::vars().filter(|(name, _)| {
+                settings
+                    .env_allowlist
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(name))
+            }),
+        )
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let started = Instant::now();
+    let mut child = child
+        .spawn()
+        .map_err(|e| AppError::internal(format!("Failed to start {}: {}", shell, e)))?;
+    let timeout = Duration::from_secs(settings.timeout_secs.clamp(1, MAX_TIMEOUT_SECS));
+    let (Some(mut stdout_pipe), Some(mut stderr_pipe)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err(AppError::internal("Command output isn't piped"));
+    };
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let finished = tokio::time::timeout(timeout, async {
+        let (stdout_dropped, stderr_dropped, status) = tokio::join!(
+            read_capped(&mut stdout_pipe, &mut stdout),
+            read_capped(&mut stderr_pipe, &mut stderr),
+            child.wait()
+        );
+        let (stdout_dropped, stderr_dropped) = (stdout_dropped?, stderr_dropped?);
+        Ok::<_, std::io::Error>((stdout_dropped || stderr_dropped, status?))
+    })
+    .await;
+
+    let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).to_string();
+    match finished {
+        Ok(result) => {
+            let (truncated, status) = result?;
+            Ok(CommandRunResult {
+                stdout: text(&stdout),
+                stderr: text(&stderr),
+                exit_code: status.code(),
+                timed_out: false,
+                truncated,
+                duration_ms: started.elapsed().as_millis() as u64,
+                history_id: None,
+            })
+        }
+        Err(_) => {
+            // kill_on_drop would get it too, but not before this returns
+            child.start_kill().ok();
+            let truncated = stdout.len() >= MAX_OUTPUT_BYTES || stderr.len() >= MAX_OUTPUT_BYTES;
+            let mut stderr = text(&stderr);
+            if !stderr.is_empty() && !stderr.ends_with('\n') {
+                stderr.push('\n');
+            }
+            stderr.push_str(&format!("Timed out after {} seconds", timeout.as_secs()));
+            Ok(CommandRunResult {
+                stdout: text(&stdout),
+                stderr,
+                exit_code: None,
+                timed_out: true,
+                truncated,
+                duration_ms: started.elapsed().as_millis() as u64,
+                history_id: None,
+            })
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Run a command snippet and return its output; also saved to history if enabled
+#[tauri::command]
+pub async fn run_snippet_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<CommandRunResult, AppError> {
+    let snippet = load_snippets()?
+        .snippets
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", id)))?;
+    if snippet.kind != SnippetKind::Command {
+        return Err(AppError::invalid_input(format!("\"{}\" is not a command snippet", snippet.name)));
+    }
+    if snippet.content.trim().is_empty() {
+        return Err(AppError::invalid_input("The command is empty"));
+    }
+
+    let settings = load_settings().unwrap_or_default().command_runner;
+    let confirm = settings.confirm_before_run || snippet.is_remote();
+    if confirm && !confirm_run(&snippet.name, &snippet.content).await {
+        return Err(AppError::new(ErrorCode::Cancelled, "Command cancelled"));
+    }
+
+    let mut result = run(&snippet.content, &settings).await?;
+
+    if settings.save_output_to_history
+        && !result.stdout.trim().is_empty()
+        && !crate::privacy::is_privacy_mode_active()
+    {
+        let conn = state.db.lock()?;
+        let id = add_entry(&conn, &result.stdout, None, None, None, None)?;
+        if let Ok(Some(entry)) = get_entry(&conn, id) {
+            app.emit("history-entry-added", &entry).ok();
+        }
+        drop(conn);
+        crate::tray::rebuild(&app);
+        result.history_id = Some(id);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_invocation() {
+        assert_eq!(shell_invocation(Some("/bin/zsh")), ("/bin/zsh".to_string(), "-c"));
+        assert_eq!(shell_invocation(Some("C:\\Windows\\System32\\cmd.exe")).1, "/C");
+        assert_eq!(shell_invocation(Some("pwsh")).1, "-Command");
+    }
+
+    #[test]
+    fn test_confirm_preview_marks_truncation() {
+        assert_eq!(confirm_preview("echo hi"), "echo hi");
+        let long = "x".repeat(CONFIRM_PREVIEW_CHARS + 20);
+        let preview = confirm_preview(&long);
+        assert!(preview.starts_with(&format!("{}…", "x".repeat(CONFIRM_PREVIEW_CHARS))));
+        assert!(preview.ends_with("(truncated, 20 more characters not shown)"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_clears_environment_and_times_out() {
+        std::env::set_var("WINGMAN_TEST_SECRET", "hunter2");
+        let settings = CommandRunnerSettings {
+            shell: Some("/bin/sh".to_string()),
+            working_dir: Some(std::env::temp_dir().to_string_lossy().to_string()),
+            timeout_secs: 1,
+            ..CommandRunnerSettings::default()
+        };
+
+        let result = run("echo \"[$WINGMAN_TEST_SECRET]\"; echo oops >&2; exit 3", &settings)
+            .await
+            .unwrap();
+        assert_eq!(result.stdout, "[]\n");
+        assert_eq!(result.stderr, "oops\n");
+        assert_eq!(result.exit_code, Some(3));
+
+        let result = run("echo started; sleep 5", &settings).await.unwrap();
+        assert!(result.timed_out);
+        assert_eq!(result.stdout, "started\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_drops_output_past_the_limit() {
+        let settings = CommandRunnerSettings {
+            shell: Some("/bin/sh".to_string()),
+            working_dir: Some(std::env::temp_dir().to_string_lossy().to_string()),
+            timeout_secs: 10,
+            ..CommandRunnerSettings::default()
+        };
+        let bytes = MAX_OUTPUT_BYTES + 4096;
+        let result = run(&format!("head -c {} /dev/zero | tr '\\0' x", bytes), &settings)
+            .await
+            .unwrap();
+        assert!(result.truncated);
+        assert_eq!(result.stdout.len(), MAX_OUTPUT_BYTES);
+        assert_eq!(result.exit_code, Some(0));
+    }
+}
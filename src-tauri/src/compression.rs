@@ -0,0 +1,169 @@
+// Transparent zstd compression for very large history entries. Content over
+// COMPRESS_THRESHOLD_BYTES is stored compressed in history.content_zstd with the
+// `compressed` flag set, while `content` keeps the first HEAD_BYTES as plain text. The
+// head is as long as what an overflowed entry keeps with the default size limit, so
+// LIKE searches, link lookups, classification and the indexer see the same text they
+// would on an uncompressed row; everything below the threshold stays whole in `content`.
+// get_entry and get_entry_content decompress the full text. New entries are compressed
+// as they're saved; compact_database compresses rows saved before this, or before they
+// grew past the threshold, and VACUUMs to hand the space back.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::io;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::history::{get_db_path, HistoryError};
+use crate::oversize::format_bytes;
+use crate::AppState;
+
+/// Content at least this long is compressed
+const COMPRESS_THRESHOLD_BYTES: usize = 2 * 1024 * 1024;
+/// Plain-text prefix kept in `content`; the default HistoryLimits::max_entry_bytes, so
+/// search covers as much of a compressed entry as of any other
+const HEAD_BYTES: usize = 1024 * 1024;
+const ZSTD_LEVEL: i32 = 3;
+
+/// How a large entry is stored
+#[derive(Debug, PartialEq)]
+pub struct Packed {
+    pub head: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompactReport {
+    pub compressed_entries: u32,
+    pub size_before: u64,
+    pub size_after: u64,
+    /// Human-readable space saved, e.g. "12.4 MB"
+    pub saved: String,
+}
+
+fn head(content: &str) -> &str {
+    let mut end = HEAD_BYTES.min(content.len());
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    &content[..end]
+}
+
+/// Compress `content` if it's over the threshold and compresses well enough to be worth it
+pub fn pack(content: &str) -> Result<Option<Packed>, HistoryError> {
+    if content.len() < COMPRESS_THRESHOLD_BYTES {
+        return Ok(None);
+    }
+    let data = zstd::encode_all(content.as_bytes(), ZSTD_LEVEL).map_err(HistoryError::Compression)?;
+    let head = head(content);
+    // Already-compressed payloads (base64 blobs and the like) barely shrink
+    if head.len() + data.len() > content.len() * 9 / 10 {
+        return Ok(None);
+    }
+    Ok(Some(Packed {
+        head: head.to_string(),
+        data,
+    }))
+}
+
+pub fn unpack(data: &[u8]) -> Result<String, HistoryError> {
+    let bytes = zstd::decode_all(data).map_err(HistoryError::Compression)?;
+    String::from_utf8(bytes)
+        .map_err(|e| HistoryError::Compression(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+/// Compress every large entry that isn't yet, returning how many were
+pub fn compress_existing(conn: &Connection) -> Result<u32, HistoryError> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, content FROM history
+             WHERE compressed = 0 AND content_hash IS NULL AND length(CAST(content AS BLOB)) >= ?1",
+        )?;
+        let rows = stmt
+            .query_map([COMPRESS_THRESHOLD_BYTES as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    let mut compressed = 0;
+    for (id, content) in rows {
+        if let Some(packed) = pack(&content)? {
+            tx.execute(
+                "UPDATE history SET content = ?2, content_zstd = ?3, compressed = 1 WHERE id = ?1",
+                rusqlite::params![id, packed.head, packed.data],
+            )?;
+            compressed += 1;
+        }
+    }
+    tx.commit()?;
+    Ok(compressed)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Compress large entries saved uncompressed, then VACUUM the database
+#[tauri::command]
+pub fn compact_database(state: State<AppState>) -> Result<CompactReport, AppError> {
+    let path = get_db_path()?;
+    let file_size = || std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let size_before = file_size();
+
+    let conn = state.db.lock()?;
+    let compressed_entries = compress_existing(&conn)?;
+    conn.execute("VACUUM", [])?;
+    drop(conn);
+
+    let size_after = file_size();
+    log::info!(
+        "Compacted the history database: {} entries compressed, {} -> {}",
+        compressed_entries,
+        format_bytes(size_before),
+        format_bytes(size_after)
+    );
+    Ok(CompactReport {
+        compressed_entries,
+        size_before,
+        size_after,
+        saved: format_bytes(size_before.saturating_sub(size_after)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_entries_round_trip() {
+        assert!(pack("short").unwrap().is_none());
+        // Entries search should see in full aren't compressed
+        assert!(pack(&"a".repeat(HEAD_BYTES * 3 / 2)).unwrap().is_none());
+
+        let content = "SELECT * FROM orders WHERE total > 100;\n".repeat(60_000);
+        let packed = pack(&content).unwrap().unwrap();
+        assert!(content.starts_with(&packed.head));
+        assert!(packed.data.len() < content.len() / 10);
+        assert_eq!(unpack(&packed.data).unwrap(), content);
+    }
+
+    #[test]
+    fn test_compress_existing_keeps_full_content() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::history::create_schema(&conn).unwrap();
+        let content = "{\"level\":\"info\",\"msg\":\"request served\"}\n".repeat(60_000);
+        conn.execute(
+            "INSERT INTO history (id, content, character_count, word_count, line_count) VALUES (1, ?1, 0, 0, 0)",
+            [&content],
+        )
+        .unwrap();
+
+        assert_eq!(compress_existing(&conn).unwrap(), 1);
+        assert_eq!(compress_existing(&conn).unwrap(), 0);
+        let entries = crate::history::get_entries(&conn, 10, 0).unwrap();
+        assert!(entries[0].content_truncated);
+        assert!(content.starts_with(&entries[0].content));
+        assert_eq!(crate::history::get_entry_content(&conn, 1).unwrap(), content);
+    }
+}
@@ -4,34 +4,222 @@
 //! - macOS: Keychain
 //! - Windows: Credential Manager
 //! - Linux: Secret Service (libsecret)
+//!
+//! Every secret is an account under one keychain service. A registry file next to the
+//! settings records which feature owns each account, a label and when it was created or
+//! last rotated, so stored credentials can be listed without reading any secret.
 
 use keyring::Entry;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Mutex;
+
+use crate::error::{AppError, ErrorCode};
+use crate::storage::{ensure_app_data_dir, StorageError};
 
 const SERVICE_NAME: &str = "com.wingman.app";
 
+/// Registry services; GitHub tokens are rotated and revoked through github.rs, per profile
+pub const SERVICE_GITHUB: &str = "github";
+const SERVICE_OTHER: &str = "other";
+
+/// Accounts with a fixed name, registered at startup if a secret already exists
+const KNOWN_ACCOUNTS: &[(&str, &str, &str)] = &[
+    ("deepl_api_key", "translate", "DeepL API key"),
+    ("google_translate_api_key", "translate", "Google Translate API key"),
+    ("languagetool_api_key", "grammar", "LanguageTool API key"),
+    ("local_api_token", "local_api", "Local API token"),
+    ("proxy_password", "proxy", "Proxy password"),
+];
+
+/// Serializes read-modify-write of the registry file
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+/// What Wingman knows about a stored credential; never the secret itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CredentialRecord {
+    /// Feature that owns the credential: "github", "translate", "lan_sync", ...
+    pub service: String,
+    /// Keychain account under com.wingman.app
+    pub account: String,
+    pub label: String,
+    /// RFC 3339
+    pub created_at: String,
+    pub rotated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialRegistry {
+    pub credentials: Vec<CredentialRecord>,
+    /// Set once secrets stored before the registry existed have been registered
+    #[serde(default)]
+    pub migrated: bool,
+}
+
+impl CredentialRegistry {
+    /// Record that `account` was written at `now`: new accounts are added, existing
+    /// ones are marked rotated
+    pub fn record_write(&mut self, account: &str, now: &str) {
+        match self.credentials.iter_mut().find(|c| c.account == account) {
+            Some(existing) => existing.rotated_at = Some(now.to_string()),
+            None => {
+                let (service, label) = describe(account);
+                self.credentials.push(CredentialRecord {
+                    service,
+                    account: account.to_string(),
+                    label,
+                    created_at: now.to_string(),
+                    rotated_at: None,
+                });
+            }
+        }
+    }
+
+    pub fn remove(&mut self, account: &str) -> bool {
+        let before = self.credentials.len();
+        self.credentials.retain(|c| c.account != account);
+        self.credentials.len() != before
+    }
+}
+
+/// The owning service and a label for a keychain account
+pub fn describe(account: &str) -> (String, String) {
+    if let Some((_, service, label)) = KNOWN_ACCOUNTS.iter().find(|(known, _, _)| *known == account) {
+        return (service.to_string(), label.to_string());
+    }
+    if let Some(rest) = account.strip_prefix(crate::github::KEYRING_GITHUB_ACCOUNT) {
+        let label = match rest.strip_prefix(':') {
+            Some(profile) => format!("GitHub access token (profile {})", profile),
+            None => "GitHub access token".to_string(),
+        };
+        return (SERVICE_GITHUB.to_string(), label);
+    }
+    if let Some(peer) = account.strip_prefix("lan_peer:") {
+        return ("lan_sync".to_string(), format!("LAN sync key for {}", peer));
+    }
+    (SERVICE_OTHER.to_string(), account.to_string())
+}
+
+pub fn load_registry() -> Result<CredentialRegistry, StorageError> {
+    let path = ensure_app_data_dir()?.join("credential_registry.json");
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        Ok(CredentialRegistry::default())
+    }
+}
+
+pub fn save_registry(registry: &CredentialRegistry) -> Result<(), StorageError> {
+    let path = ensure_app_data_dir()?.join("credential_registry.json");
+    fs::write(path, serde_json::to_string_pretty(registry)?)?;
+    Ok(())
+}
+
+fn update_registry(change: impl FnOnce(&mut CredentialRegistry)) -> Result<(), StorageError> {
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut registry = load_registry()?;
+    change(&mut registry);
+    save_registry(&registry)
+}
+
+/// Register secrets stored before the registry existed: the GitHub token of every
+/// profile and the fixed-name accounts. Runs once; reads the keychain, so call it off
+/// the main thread.
+pub fn migrate_registry() {
+    if load_registry().map(|r| r.migrated).unwrap_or(true) {
+        return;
+    }
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut existing: Vec<String> = KNOWN_ACCOUNTS
+        .iter()
+        .map(|(account, _, _)| account.to_string())
+        .filter(|account| matches!(get_credential(account.clone()), Ok(Some(_))))
+        .collect();
+    // load_token_for also moves a file-only token into the keychain
+    let profiles = crate::profiles::load_profiles().map(|data| data.profiles).unwrap_or_default();
+    existing.extend(
+        profiles
+            .iter()
+            .map(|profile| profile.id.as_str())
+            .chain([crate::profiles::DEFAULT_PROFILE_ID])
+            .filter(|id| crate::github::load_token_for(id).is_ok())
+            .map(crate::github::keyring_account_for),
+    );
+    existing.sort();
+    existing.dedup();
+    let result = update_registry(|registry| {
+        for account in &existing {
+            if !registry.credentials.iter().any(|c| &c.account == account) {
+                registry.record_write(account, &now);
+            }
+        }
+        registry.migrated = true;
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to migrate the credential registry: {}", e);
+    }
+}
+
+/// Every keychain account Wingman may have written: the registered ones, the fixed-name
+/// accounts and the GitHub token of each profile, registered or not
+pub fn all_accounts() -> Vec<String> {
+    let mut accounts: Vec<String> = load_registry()
+        .map(|registry| registry.credentials.into_iter().map(|c| c.account).collect())
+        .unwrap_or_default();
+    accounts.extend(KNOWN_ACCOUNTS.iter().map(|(account, _, _)| account.to_string()));
+    accounts.push(crate::github::KEYRING_GITHUB_ACCOUNT.to_string());
+    if let Ok(data) = crate::profiles::load_profiles() {
+        accounts.extend(
+            data.profiles
+                .iter()
+                .filter(|p| p.id != crate::profiles::DEFAULT_PROFILE_ID)
+                .map(|p| format!("{}:{}", crate::github::KEYRING_GITHUB_ACCOUNT, p.id)),
+        );
+    }
+    accounts.sort();
+    accounts.dedup();
+    accounts
+}
+
+/// Delete a keychain entry without touching the registry. False if there was none.
+pub fn erase(account: &str) -> Result<bool, AppError> {
+    let entry = Entry::new(SERVICE_NAME, account)
+        .map_err(|e| AppError::new(ErrorCode::Storage, format!("Failed to create keyring entry: {}", e)))?;
+    match entry.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(AppError::new(ErrorCode::Storage, format!("Failed to delete credential: {}", e))),
+    }
+}
+
 /// Store a credential securely in the OS keychain
 #[tauri::command]
-pub fn store_credential(key: String, value: String) -> Result<(), String> {
+pub fn store_credential(key: String, value: String) -> Result<(), AppError> {
     log::info!("store_credential called for key: {}", key);
     let entry = Entry::new(SERVICE_NAME, &key).map_err(|e| {
         log::error!("Failed to create keyring entry for {}: {}", key, e);
-        format!("Failed to create keyring entry: {}", e)
+        AppError::new(ErrorCode::Storage, format!("Failed to create keyring entry: {}", e))
     })?;
     entry.set_password(&value).map_err(|e| {
         log::error!("Failed to store credential {}: {}", key, e);
-        format!("Failed to store credential: {}", e)
+        AppError::new(ErrorCode::Storage, format!("Failed to store credential: {}", e))
     })?;
     log::info!("Successfully stored credential: {}", key);
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Err(e) = update_registry(|registry| registry.record_write(&key, &now)) {
+        log::warn!("Failed to register credential {}: {}", key, e);
+    }
     Ok(())
 }
 
-/// Retrieve a credential from the OS keychain
-#[tauri::command]
-pub fn get_credential(key: String) -> Result<Option<String>, String> {
+/// Retrieve a credential from the OS keychain. Not a command: the webview goes through
+/// reveal_credential, which is behind the authentication gate.
+pub fn get_credential(key: String) -> Result<Option<String>, AppError> {
     log::info!("get_credential called for key: {}", key);
     let entry = Entry::new(SERVICE_NAME, &key).map_err(|e| {
         log::error!("Failed to create keyring entry for {}: {}", key, e);
-        format!("Failed to create keyring entry: {}", e)
+        AppError::new(ErrorCode::Storage, format!("Failed to create keyring entry: {}", e))
     })?;
     match entry.get_password() {
         Ok(password) => {
@@ -44,39 +232,140 @@ pub fn get_credential(key: String) -> Result<Option<String>, String> {
         }
         Err(e) => {
             log::error!("Failed to retrieve credential {}: {}", key, e);
-            Err(format!("Failed to retrieve credential: {}", e))
+            Err(AppError::new(ErrorCode::Storage, format!("Failed to retrieve credential: {}", e)))
         }
     }
 }
 
+/// Show a stored secret in the credentials manager. Asks for Touch ID / Windows Hello
+/// first when the reveal_credentials gate is on.
+#[tauri::command]
+pub async fn reveal_credential(account: String) -> Result<Option<String>, AppError> {
+    crate::auth_gate::ensure(crate::auth_gate::GatedAction::RevealCredential).await?;
+    get_credential(account)
+}
+
 /// Delete a credential from the OS keychain
 #[tauri::command]
-pub fn delete_credential(key: String) -> Result<(), String> {
+pub fn delete_credential(key: String) -> Result<(), AppError> {
     log::info!("delete_credential called for key: {}", key);
     let entry = Entry::new(SERVICE_NAME, &key).map_err(|e| {
         log::error!("Failed to create keyring entry for {}: {}", key, e);
-        format!("Failed to create keyring entry: {}", e)
+        AppError::new(ErrorCode::Storage, format!("Failed to create keyring entry: {}", e))
     })?;
     match entry.delete_credential() {
         Ok(()) => {
             log::info!("Successfully deleted credential: {}", key);
-            Ok(())
         }
         Err(keyring::Error::NoEntry) => {
             log::info!("Credential already deleted (no entry): {}", key);
-            Ok(()) // Already deleted, that's fine
+            // Already deleted, that's fine
         }
         Err(e) => {
             log::error!("Failed to delete credential {}: {}", key, e);
-            Err(format!("Failed to delete credential: {}", e))
+            return Err(AppError::new(ErrorCode::Storage, format!("Failed to delete credential: {}", e)));
         }
     }
+    if let Err(e) = update_registry(|registry| {
+        registry.remove(&key);
+    }) {
+        log::warn!("Failed to unregister credential {}: {}", key, e);
+    }
+    Ok(())
+}
+
+/// Credentials Wingman has stored, grouped by service. Secrets aren't read.
+#[tauri::command]
+pub fn list_credentials() -> Result<Vec<CredentialRecord>, AppError> {
+    let mut credentials = load_registry()?.credentials;
+    credentials.sort_by(|a, b| a.service.cmp(&b.service).then_with(|| a.label.cmp(&b.label)));
+    Ok(credentials)
+}
+
+/// The profile whose GitHub token `record` is, if it's one
+fn github_profile(record: &CredentialRecord) -> Option<String> {
+    if record.service != SERVICE_GITHUB {
+        return None;
+    }
+    crate::github::profile_of_account(&record.account)
+}
+
+/// Replace the secret of a registered credential
+#[tauri::command]
+pub fn rotate_credential(account: String, value: String) -> Result<CredentialRecord, AppError> {
+    if value.trim().is_empty() {
+        return Err(AppError::invalid_input("The new value can't be empty"));
+    }
+    let record = load_registry()?
+        .credentials
+        .into_iter()
+        .find(|c| c.account == account)
+        .ok_or_else(|| AppError::not_found(format!("No stored credential named {}", account)))?;
+    if let Some(profile) = github_profile(&record) {
+        // Keeps that profile's fallback token file in step with the keychain
+        crate::github::save_token_for(&profile, &value)?;
+    } else {
+        store_credential(account.clone(), value)?;
+    }
+    load_registry()?
+        .credentials
+        .into_iter()
+        .find(|c| c.account == account)
+        .ok_or_else(|| AppError::internal("Credential vanished from the registry while rotating"))
+}
+
+/// Delete a registered credential and forget it. Revoking the GitHub token signs out of
+/// GitHub; the token itself stays valid on github.com until revoked there.
+#[tauri::command]
+pub fn revoke_credential(account: String) -> Result<(), AppError> {
+    let record = load_registry()?
+        .credentials
+        .into_iter()
+        .find(|c| c.account == account)
+        .ok_or_else(|| AppError::not_found(format!("No stored credential named {}", account)))?;
+    if let Some(profile) = github_profile(&record) {
+        crate::github::delete_token_for(&profile)?;
+    } else {
+        delete_credential(account)?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_github_records_name_their_profile() {
+        let mut registry = CredentialRegistry::default();
+        registry.record_write("github_access_token", "2026-01-01T00:00:00Z");
+        registry.record_write("github_access_token:work", "2026-01-01T00:00:00Z");
+        registry.record_write("deepl_api_key", "2026-01-01T00:00:00Z");
+        let profiles: Vec<Option<String>> = registry.credentials.iter().map(github_profile).collect();
+        assert_eq!(profiles, vec![Some("default".to_string()), Some("work".to_string()), None]);
+        assert_eq!(crate::github::keyring_account_for("work"), "github_access_token:work");
+        assert_eq!(crate::github::profile_of_account("github_access_tokenx"), None);
+    }
+
+    #[test]
+    fn test_registry_records_writes() {
+        let mut registry = CredentialRegistry::default();
+        registry.record_write("deepl_api_key", "2026-01-01T00:00:00Z");
+        registry.record_write("github_access_token:work", "2026-01-02T00:00:00Z");
+        registry.record_write("deepl_api_key", "2026-02-01T00:00:00Z");
+
+        assert_eq!(registry.credentials.len(), 2);
+        let deepl = &registry.credentials[0];
+        assert_eq!((deepl.service.as_str(), deepl.label.as_str()), ("translate", "DeepL API key"));
+        assert_eq!(deepl.created_at, "2026-01-01T00:00:00Z");
+        assert_eq!(deepl.rotated_at.as_deref(), Some("2026-02-01T00:00:00Z"));
+        assert_eq!(registry.credentials[1].label, "GitHub access token (profile work)");
+        assert_eq!(describe("lan_peer:abc").0, "lan_sync");
+
+        assert!(registry.remove("deepl_api_key"));
+        assert!(!registry.remove("deepl_api_key"));
+    }
+
     #[test]
     fn test_credential_roundtrip() {
         let key = "test_key_wingman".to_string();
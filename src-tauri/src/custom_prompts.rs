@@ -0,0 +1,276 @@
+// Parameterized custom AI prompts. A prompt declares variables (name, label, type,
+// default) and uses them as `{{name}}` in its system and user prompts; values come
+// from a form in the UI and are validated and substituted here, not in the frontend.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use tauri::AppHandle;
+use thiserror::Error;
+
+use crate::error::{AppError, ErrorCode};
+use crate::license::get_cached_license_key;
+use crate::notifications::{self, NotificationCategory};
+use crate::premium::{call_ai_feature, AIResponse};
+use crate::storage::{load_custom_ai_prompts, CustomAIPrompt, PromptVariable, PromptVariableType};
+
+/// Placeholder for the input text in user prompt templates
+const TEXT_VARIABLE: &str = "text";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PromptError {
+    #[error("Invalid variable name \"{0}\": use letters, digits and underscores")]
+    InvalidName(String),
+    #[error("\"{0}\" is reserved for the input text")]
+    ReservedName(String),
+    #[error("Variable \"{0}\" is declared more than once")]
+    Duplicate(String),
+    #[error("Variable \"{0}\" is a select with no options")]
+    NoOptions(String),
+    #[error("\"{label}\" is required")]
+    Missing { label: String },
+    #[error("\"{label}\" must be {expected}, got \"{value}\"")]
+    InvalidValue {
+        label: String,
+        expected: String,
+        value: String,
+    },
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Normalize a value for its type, or explain why it doesn't fit
+fn check_value(variable: &PromptVariable, value: &str) -> Result<String, PromptError> {
+    let invalid = |expected: &str| PromptError::InvalidValue {
+        label: variable.label.clone(),
+        expected: expected.to_string(),
+        value: value.to_string(),
+    };
+    match variable.var_type {
+        PromptVariableType::Text => Ok(value.trim().to_string()),
+        PromptVariableType::Multiline => Ok(value.to_string()),
+        PromptVariableType::Number => value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(|_| value.trim().to_string())
+            .ok_or_else(|| invalid("a number")),
+        PromptVariableType::Boolean => match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok("true".to_string()),
+            "false" | "no" | "0" => Ok("false".to_string()),
+            _ => Err(invalid("true or false")),
+        },
+        PromptVariableType::Select => variable
+            .options
+            .iter()
+            .find(|option| option.as_str() == value)
+            .cloned()
+            .ok_or_else(|| invalid(&format!("one of {}", variable.options.join(", ")))),
+    }
+}
+
+/// Check variable declarations when a prompt is saved
+pub fn validate_variables(variables: &[PromptVariable]) -> Result<(), PromptError> {
+    let mut seen = HashSet::new();
+    for variable in variables {
+        if variable.name == TEXT_VARIABLE {
+            return Err(PromptError::ReservedName(variable.name.clone()));
+        }
+        if !is_valid_name(&variable.name) {
+            return Err(PromptError::InvalidName(variable.name.clone()));
+        }
+        if !seen.insert(variable.name.as_str()) {
+            return Err(PromptError::Duplicate(variable.name.clone()));
+        }
+        if variable.var_type == PromptVariableType::Select && variable.options.is_empty() {
+            return Err(PromptError::NoOptions(variable.name.clone()));
+        }
+        if let Some(default) = &variable.default {
+            check_value(variable, default)?;
+        }
+    }
+    Ok(())
+}
+
+/// Values for every declared variable: the caller's, else the default. JSON numbers and
+/// booleans from the form are accepted as well as strings.
+pub fn resolve_values(
+    variables: &[PromptVariable],
+    provided: &HashMap<String, serde_json::Value>,
+) -> Result<HashMap<String, String>, PromptError> {
+    let mut values = HashMap::new();
+    for variable in variables {
+        let raw = match provided.get(&variable.name) {
+            Some(serde_json::Value::String(s)) => Some(s.clone()),
+            Some(serde_json::Value::Null) | None => None,
+            Some(other) => Some(other.to_string()),
+        };
+        let raw = raw
+            .filter(|v| !v.trim().is_empty())
+            .or_else(|| variable.default.clone());
+        let value = match raw {
+            Some(raw) => check_value(variable, &raw)?,
+            None if variable.required => {
+                return Err(PromptError::Missing {
+                    label: variable.label.clone(),
+                })
+            }
+            None => String::new(),
+        };
+        values.insert(variable.name.clone(), value);
+    }
+    Ok(values)
+}
+
+/// Replace `{{name}}` (whitespace inside the braces allowed) in one pass, so
+/// substituted values are never expanded again. Unknown names are left as written.
+pub fn substitute(template: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => match values.get(after[..end].trim()) {
+                Some(value) => {
+                    out.push_str(value);
+                    rest = &after[end + 2..];
+                }
+                None => {
+                    out.push_str("{{");
+                    rest = after;
+                }
+            },
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// System and user messages for a prompt run on `text`
+pub fn render_prompt(
+    prompt: &CustomAIPrompt,
+    text: &str,
+    provided: &HashMap<String, serde_json::Value>,
+) -> Result<(String, String), PromptError> {
+    let mut values = resolve_values(&prompt.variables, provided)?;
+    let system = substitute(&prompt.system_prompt, &values);
+    values.insert(TEXT_VARIABLE.to_string(), text.to_string());
+    let user = match &prompt.user_prompt {
+        Some(template) if !template.trim().is_empty() => substitute(template, &values),
+        _ => text.to_string(),
+    };
+    Ok((system, user))
+}
+
+/// System prompt with defaults filled in, for callers without a form (AI workflows)
+pub fn system_prompt_with_defaults(prompt: &CustomAIPrompt) -> String {
+    match resolve_values(&prompt.variables, &HashMap::new()) {
+        Ok(values) => substitute(&prompt.system_prompt, &values),
+        Err(_) => prompt.system_prompt.clone(),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Run a custom prompt on `text` with the values from its variable form
+#[tauri::command]
+pub async fn run_custom_prompt(
+    app: AppHandle,
+    id: String,
+    text: String,
+    variables: Option<HashMap<String, serde_json::Value>>,
+) -> Result<AIResponse, AppError> {
+    let prompt = load_custom_ai_prompts()?
+        .prompts
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Custom prompt not found: {}", id)))?;
+    let (system, user) = render_prompt(&prompt, &text, &variables.unwrap_or_default())?;
+
+    let license_key = get_cached_license_key().ok_or_else(|| AppError::new(ErrorCode::LicenseInvalid, "No active license key found"))?;
+    let started = Instant::now();
+    let result = call_ai_feature(&license_key, &user, &prompt.id, Some(&system)).await;
+    notifications::report(&app, NotificationCategory::Ai, &prompt.name, started, &result);
+    Ok(result?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(name: &str, var_type: PromptVariableType, default: Option<&str>) -> PromptVariable {
+        PromptVariable {
+            name: name.to_string(),
+            label: name.to_string(),
+            var_type,
+            default: default.map(str::to_string),
+            required: false,
+            options: vec!["formal".to_string(), "casual".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_substitute_is_single_pass() {
+        let values = HashMap::from([
+            ("tone".to_string(), "{{text}}".to_string()),
+            ("text".to_string(), "hello".to_string()),
+        ]);
+        assert_eq!(substitute("Be {{ tone }}: {{text}}", &values), "Be {{text}}: hello");
+        assert_eq!(substitute("{{unknown}} and {{", &values), "{{unknown}} and {{");
+    }
+
+    #[test]
+    fn test_render_with_defaults_and_types() {
+        let prompt = CustomAIPrompt {
+            id: "p".to_string(),
+            name: "Rewrite".to_string(),
+            description: String::new(),
+            system_prompt: "Rewrite in a {{tone}} tone, at most {{words}} words.".to_string(),
+            enabled: true,
+            created_at: String::new(),
+            updated_at: String::new(),
+            user_prompt: Some("Text:\n{{text}}".to_string()),
+            variables: vec![
+                variable("tone", PromptVariableType::Select, Some("formal")),
+                variable("words", PromptVariableType::Number, None),
+            ],
+        };
+        let provided = HashMap::from([("words".to_string(), serde_json::json!(50))]);
+        let (system, user) = render_prompt(&prompt, "hi there", &provided).unwrap();
+        assert_eq!(system, "Rewrite in a formal tone, at most 50 words.");
+        assert_eq!(user, "Text:\nhi there");
+
+        let bad = HashMap::from([("tone".to_string(), serde_json::json!("angry"))]);
+        assert!(matches!(
+            render_prompt(&prompt, "x", &bad),
+            Err(PromptError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_required_and_declarations() {
+        let mut required = variable("audience", PromptVariableType::Text, None);
+        required.required = true;
+        assert!(matches!(
+            resolve_values(&[required.clone()], &HashMap::new()),
+            Err(PromptError::Missing { .. })
+        ));
+
+        assert!(validate_variables(&[required.clone()]).is_ok());
+        assert!(validate_variables(&[required.clone(), required]).is_err());
+        assert!(validate_variables(&[variable("text", PromptVariableType::Text, None)]).is_err());
+        assert!(validate_variables(&[variable("2x", PromptVariableType::Text, None)]).is_err());
+        assert!(validate_variables(&[variable("n", PromptVariableType::Number, Some("many"))]).is_err());
+    }
+}
@@ -0,0 +1,1039 @@
+// Everything bundle for moving to a new machine: one passphrase-encrypted file with the
+// history databases, snippets, settings, chains, prompts and the other config files of
+// every profile, and optionally the keychain secrets.
+//
+// The bundle is a zip - manifest.json, the Wingman data dir under "wingman/", the
+// legacy com.wingman.app dir (AI and Obsidian configs) under "app/", and secrets.json -
+// compressed with zstd and sealed with AES-256-GCM under a key derived from the
+// passphrase with PBKDF2-SHA256. Databases go in as VACUUM INTO snapshots, so a live
+// database is copied consistently. The device-bound license cache never travels.
+//
+// Nothing the size of the data is held in memory: the zip is staged in a temp file and
+// streamed through zstd into the cipher, which seals it in CHUNK_LEN pieces (each nonce
+// carries a counter and a last-chunk flag, so reordered or truncated bundles fail).
+// Import decrypts to a temp file the same way. Bundles from before the chunked format
+// ("WNGBNDL1", one AES-GCM message) are still read.
+//
+// Import either replaces local data (the open database is rewritten table by table) or
+// merges: history entries and snippets that aren't here yet are added (see
+// merge_tables for what follows the entries) and config files are only written where
+// none exist. Import runs off the main thread and holds the database lock only while
+// the open database itself is being imported.
+//
+// Both directions respect the authentication gate: exporting secrets counts as revealing
+// credentials, and a replace import whose settings.json would turn a gate off asks for
+// LowerGate before anything is written.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::auth_gate::{self, GatedAction};
+use crate::error::AppError;
+use crate::history::get_db_path;
+use crate::storage::{get_app_data_dir, load_settings, AppSettings};
+use crate::AppState;
+
+/// Single-message bundles, read but no longer written
+const MAGIC: &[u8; 8] = b"WNGBNDL1";
+const STREAM_MAGIC: &[u8; 8] = b"WNGBNDL2";
+const BUNDLE_VERSION: u32 = 1;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN + NONCE_LEN;
+/// Plaintext per sealed chunk of a streamed bundle
+const CHUNK_LEN: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+/// Random part of each chunk nonce; the counter and last flag take the rest
+const NONCE_PREFIX_LEN: usize = NONCE_LEN - 5;
+const ZSTD_LEVEL: i32 = 3;
+const MIN_PASSPHRASE_CHARS: usize = 8;
+/// Bundles asking for more are damaged or hostile
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+/// Files holding tokens; bundled only with include_secrets
+const SECRET_FILES: &[&str] = &["github_token.json"];
+/// Never bundled: device-bound, rebuilt locally, or database side files
+const SKIPPED_EXTENSIONS: &[&str] = &["enc", "log", "db-wal", "db-shm", "db-journal", "tmp"];
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("Archive error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("History error: {0}")]
+    History(#[from] crate::history::HistoryError),
+    #[error("Wrong passphrase or damaged bundle")]
+    Decrypt,
+    #[error("Not a Wingman data bundle")]
+    NotABundle,
+    #[error("Bundle version {0} is newer than this version of Wingman supports")]
+    UnsupportedVersion(u32),
+    #[error("Passphrase must be at least 8 characters")]
+    WeakPassphrase,
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    app_version: String,
+    created_at: String,
+    include_secrets: bool,
+    /// Archive paths, databases included
+    files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub path: String,
+    pub files: usize,
+    pub databases: usize,
+    pub secrets: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub files_written: usize,
+    /// Left alone in merge mode because a local copy exists
+    pub files_kept: usize,
+    /// History entries restored (replace) or added (merge)
+    pub history_entries: usize,
+    pub snippets_added: usize,
+    pub secrets_restored: usize,
+    /// Settings and hotkeys are read at startup, so a full restore takes a restart
+    pub restart_recommended: bool,
+}
+
+/// The legacy app dir the AI and Obsidian configs still live in
+fn legacy_app_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("com.wingman.app"))
+}
+
+fn is_bundled(path: &Path, include_secrets: bool) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let skipped = SKIPPED_EXTENSIONS
+        .iter()
+        .any(|skip| extension == *skip || name.ends_with(&format!(".{}", skip)));
+    !skipped && (include_secrets || !SECRET_FILES.contains(&name))
+}
+
+/// Files under `root`, relative, with `/` separators
+fn collect_files(root: &Path, include_secrets: bool) -> Result<Vec<String>, BundleError> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if is_bundled(&path, include_secrets) {
+                if let Ok(relative) = path.strip_prefix(root) {
+                    let parts: Vec<String> = relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy().to_string())
+                        .collect();
+                    files.push(parts.join("/"));
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn temp_path(label: &str, extension: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("wingman-bundle-{}-{}.{}", label, uuid::Uuid::new_v4(), extension))
+}
+
+/// A consistent copy of the database at `path` in a temp file, safe while Wingman has it
+/// open. The caller removes the copy.
+fn snapshot_database(path: &Path) -> Result<PathBuf, BundleError> {
+    let conn = Connection::open(path)?;
+    let target = temp_path("export", "db");
+    if let Err(e) = conn.execute("VACUUM INTO ?1", [target.to_string_lossy()]) {
+        let _ = fs::remove_file(&target);
+        return Err(e.into());
+    }
+    Ok(target)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+fn check_iterations(iterations: u32) -> Result<u32, BundleError> {
+    if iterations == 0 || iterations > MAX_PBKDF2_ITERATIONS {
+        return Err(BundleError::NotABundle);
+    }
+    Ok(iterations)
+}
+
+/// Chunk nonce: the stream's random prefix, the chunk counter, and whether it's the last
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32, last: bool) -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..NONCE_LEN - 1].copy_from_slice(&counter.to_be_bytes());
+    nonce[NONCE_LEN - 1] = last as u8;
+    nonce
+}
+
+/// Encrypts what's written to it in CHUNK_LEN pieces. `finish` seals the last piece,
+/// which is marked as such so a truncated bundle doesn't decrypt.
+struct SealWriter<W: Write> {
+    inner: W,
+    cipher: Aes256Gcm,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> SealWriter<W> {
+    /// Write the header to `inner` and start the stream
+    fn new(mut inner: W, passphrase: &str, iterations: u32) -> Result<Self, BundleError> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut prefix = [0u8; NONCE_PREFIX_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut prefix);
+        let cipher =
+            Aes256Gcm::new_from_slice(&derive_key(passphrase, &salt, iterations)).map_err(|_| BundleError::Decrypt)?;
+
+        inner.write_all(STREAM_MAGIC)?;
+        inner.write_all(&iterations.to_be_bytes())?;
+        inner.write_all(&salt)?;
+        inner.write_all(&prefix)?;
+        Ok(SealWriter {
+            inner,
+            cipher,
+            prefix,
+            counter: 0,
+            buffer: Vec::with_capacity(CHUNK_LEN),
+        })
+    }
+
+    fn seal_chunk(&mut self, len: usize, last: bool) -> std::io::Result<()> {
+        let nonce = chunk_nonce(&self.prefix, self.counter, last);
+        let sealed = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), &self.buffer[..len])
+            .map_err(|_| std::io::Error::other("encryption failed"))?;
+        self.inner.write_all(&sealed)?;
+        self.buffer.drain(..len);
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| std::io::Error::other("bundle too large"))?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> std::io::Result<W> {
+        self.seal_chunk(self.buffer.len(), true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for SealWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        // Hold back a full chunk: only finish knows which one is last
+        while self.buffer.len() > CHUNK_LEN {
+            self.seal_chunk(CHUNK_LEN, false)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Read until `buf` is full or the input ends; returns how much was read
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Decrypt a bundle stream (after its magic) into `out`
+fn open_stream(reader: &mut impl BufRead, passphrase: &str, out: &mut impl Write) -> Result<(), BundleError> {
+    let mut header = [0u8; 4 + SALT_LEN + NONCE_PREFIX_LEN];
+    if read_full(reader, &mut header)? < header.len() {
+        return Err(BundleError::NotABundle);
+    }
+    let iterations = check_iterations(u32::from_be_bytes(header[..4].try_into().expect("4 bytes")))?;
+    let salt = &header[4..4 + SALT_LEN];
+    let prefix: [u8; NONCE_PREFIX_LEN] = header[4 + SALT_LEN..].try_into().expect("prefix length");
+    let cipher =
+        Aes256Gcm::new_from_slice(&derive_key(passphrase, salt, iterations)).map_err(|_| BundleError::Decrypt)?;
+
+    let mut chunk = vec![0u8; CHUNK_LEN + TAG_LEN];
+    let mut counter = 0u32;
+    loop {
+        let len = read_full(reader, &mut chunk)?;
+        let last = len < chunk.len() || reader.fill_buf()?.is_empty();
+        let plain = cipher
+            .decrypt(Nonce::from_slice(&chunk_nonce(&prefix, counter, last)), &chunk[..len])
+            .map_err(|_| BundleError::Decrypt)?;
+        out.write_all(&plain)?;
+        if last {
+            return Ok(());
+        }
+        counter = counter.checked_add(1).ok_or(BundleError::Decrypt)?;
+    }
+}
+
+/// Bundles from before the streaming format: one AES-GCM message over the whole archive
+fn open_sealed(data: &[u8], passphrase: &str) -> Result<Vec<u8>, BundleError> {
+    if data.len() < HEADER_LEN || &data[..MAGIC.len()] != MAGIC {
+        return Err(BundleError::NotABundle);
+    }
+    let mut offset = MAGIC.len();
+    let iterations = check_iterations(u32::from_be_bytes(data[offset..offset + 4].try_into().expect("4 bytes")))?;
+    offset += 4;
+    let salt = &data[offset..offset + SALT_LEN];
+    offset += SALT_LEN;
+    let nonce = &data[offset..offset + NONCE_LEN];
+    offset += NONCE_LEN;
+
+    let cipher =
+        Aes256Gcm::new_from_slice(&derive_key(passphrase, salt, iterations)).map_err(|_| BundleError::Decrypt)?;
+    let compressed = cipher
+        .decrypt(Nonce::from_slice(nonce), &data[offset..])
+        .map_err(|_| BundleError::Decrypt)?;
+    Ok(zstd::decode_all(compressed.as_slice())?)
+}
+
+/// Decrypt and decompress the bundle at `path` into the zip archive at `archive`
+fn unseal_to_file(path: &Path, passphrase: &str, archive: &Path) -> Result<(), BundleError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 8];
+    if read_full(&mut reader, &mut magic)? < magic.len() {
+        return Err(BundleError::NotABundle);
+    }
+    if &magic == MAGIC {
+        fs::write(archive, open_sealed(&fs::read(path)?, passphrase)?)?;
+        return Ok(());
+    }
+    if &magic != STREAM_MAGIC {
+        return Err(BundleError::NotABundle);
+    }
+    let mut decoder = zstd::stream::write::Decoder::new(BufWriter::new(File::create(archive)?))?;
+    open_stream(&mut reader, passphrase, &mut decoder)?;
+    decoder.flush()?;
+    decoder.into_inner().flush()?;
+    Ok(())
+}
+
+/// Keychain secrets of every registered credential
+fn read_secrets() -> BTreeMap<String, String> {
+    let accounts = crate::credentials::load_registry()
+        .map(|registry| registry.credentials)
+        .unwrap_or_default();
+    accounts
+        .into_iter()
+        .filter_map(
+            |record| match crate::credentials::get_credential(record.account.clone()) {
+                Ok(Some(secret)) => Some((record.account, secret)),
+                Ok(None) => None,
+                Err(e) => {
+                    log::warn!(
+                        "Skipping credential {} in the data bundle: {}",
+                        record.account,
+                        e.message
+                    );
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Add the files under `roots` (and the secrets) to `zip`, databases as snapshots
+fn write_archive(
+    zip: &mut ZipWriter<File>,
+    roots: &[(&str, PathBuf)],
+    include_secrets: bool,
+    report: &mut ExportReport,
+) -> Result<Vec<String>, BundleError> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut names = Vec::new();
+
+    for (prefix, root) in roots {
+        if !root.is_dir() {
+            continue;
+        }
+        for relative in collect_files(root, include_secrets)? {
+            let path = root.join(&relative);
+            let name = format!("{}/{}", prefix, relative);
+            zip.start_file(name.as_str(), options)?;
+            if relative.ends_with(".db") {
+                report.databases += 1;
+                let snapshot = snapshot_database(&path)?;
+                let copied = File::open(&snapshot).and_then(|mut file| std::io::copy(&mut file, zip));
+                let _ = fs::remove_file(&snapshot);
+                copied?;
+            } else {
+                report.files += 1;
+                std::io::copy(&mut File::open(&path)?, zip)?;
+            }
+            names.push(name);
+        }
+    }
+
+    if include_secrets {
+        let secrets = read_secrets();
+        report.secrets = secrets.len();
+        zip.start_file("secrets.json", options)?;
+        zip.write_all(&serde_json::to_vec(&secrets)?)?;
+    }
+
+    let manifest = Manifest {
+        version: BUNDLE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        include_secrets,
+        files: names.clone(),
+    };
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    Ok(names)
+}
+
+/// Build the bundle into `out`. The archive is staged in a temp file and streamed through
+/// zstd and the cipher, so nothing the size of the data is held in memory. `iterations`
+/// is only lowered by tests.
+fn build_bundle(
+    roots: &[(&str, PathBuf)],
+    out: impl Write,
+    passphrase: &str,
+    include_secrets: bool,
+    iterations: u32,
+) -> Result<ExportReport, BundleError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(BundleError::WeakPassphrase);
+    }
+    let mut report = ExportReport {
+        path: String::new(),
+        files: 0,
+        databases: 0,
+        secrets: 0,
+        bytes: 0,
+    };
+
+    let staged = temp_path("export", "zip");
+    let result = (|| -> Result<(), BundleError> {
+        let mut zip = ZipWriter::new(File::create(&staged)?);
+        write_archive(&mut zip, roots, include_secrets, &mut report)?;
+        let mut archive = zip.finish()?;
+        archive.seek(SeekFrom::Start(0))?;
+
+        let mut encoder = zstd::stream::write::Encoder::new(SealWriter::new(out, passphrase, iterations)?, ZSTD_LEVEL)?;
+        std::io::copy(&mut archive, &mut encoder)?;
+        encoder.finish()?.finish()?;
+        Ok(())
+    })();
+    let _ = fs::remove_file(&staged);
+    result.map(|()| report)
+}
+
+fn bundle_roots() -> Result<Vec<(&'static str, PathBuf)>, BundleError> {
+    let mut roots = vec![("wingman", get_app_data_dir()?)];
+    if let Some(legacy) = legacy_app_dir() {
+        roots.push(("app", legacy));
+    }
+    Ok(roots)
+}
+
+/// Write the bundle next to `path` and move it into place once it's complete, so a
+/// failed export never leaves a cut-off file or clobbers an earlier bundle
+pub fn export_bundle(path: &Path, passphrase: &str, include_secrets: bool) -> Result<ExportReport, BundleError> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    let result = File::create(&partial).map_err(BundleError::from).and_then(|file| {
+        let roots = bundle_roots()?;
+        build_bundle(&roots, BufWriter::new(file), passphrase, include_secrets, PBKDF2_ITERATIONS)
+    });
+    let mut report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    fs::rename(&partial, path)?;
+    report.bytes = fs::metadata(path)?.len();
+    report.path = path.to_string_lossy().to_string();
+    Ok(report)
+}
+
+fn columns(conn: &Connection, schema: &str, table: &str) -> Result<Vec<String>, BundleError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info(\"{}\")", schema, table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names)
+}
+
+/// Columns both copies of `table` have, quoted for SQL; empty if either lacks the table
+fn shared_columns(conn: &Connection, table: &str) -> Result<Vec<String>, BundleError> {
+    let local = columns(conn, "main", table)?;
+    Ok(columns(conn, "bundle", table)?
+        .into_iter()
+        .filter(|column| local.contains(column))
+        .map(|column| format!("\"{}\"", column))
+        .collect())
+}
+
+/// Make every table the bundle has match the bundle's copy
+fn replace_tables(conn: &Connection) -> Result<usize, BundleError> {
+    let tables: Vec<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT b.name FROM bundle.sqlite_master b
+             JOIN main.sqlite_master m ON m.name = b.name AND m.type = 'table'
+             WHERE b.type = 'table' AND b.name NOT LIKE 'sqlite_%'",
+        )?;
+        let names = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+        names
+    };
+    conn.execute_batch("BEGIN")?;
+    let result = (|| {
+        for table in &tables {
+            let columns = shared_columns(conn, table)?.join(", ");
+            conn.execute(&format!("DELETE FROM main.\"{}\"", table), [])?;
+            conn.execute(
+                &format!(
+                    "INSERT INTO main.\"{0}\" ({1}) SELECT {1} FROM bundle.\"{0}\"",
+                    table, columns
+                ),
+                [],
+            )?;
+        }
+        conn.query_row("SELECT COUNT(*) FROM main.history", [], |row| row.get::<_, i64>(0))
+            .map_err(BundleError::from)
+    })();
+    match result {
+        Ok(count) => {
+            conn.execute_batch("COMMIT")?;
+            Ok(count as usize)
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    }
+}
+
+/// Copy rows of `table` the bundle has into main, renaming `id_column` (a history id)
+/// through temp.merged_ids; rows whose entry wasn't added are skipped
+fn merge_entry_rows(conn: &Connection, table: &str, id_column: &str) -> Result<(), BundleError> {
+    let quoted = format!("\"{}\"", id_column);
+    let columns: Vec<String> = shared_columns(conn, table)?
+        .into_iter()
+        .filter(|column| column != "\"id\"" && *column != quoted)
+        .collect();
+    if columns.is_empty() {
+        return Ok(());
+    }
+    let select: Vec<String> = columns.iter().map(|column| format!("b.{}", column)).collect();
+    conn.execute(
+        &format!(
+            "INSERT OR IGNORE INTO main.\"{table}\" ({quoted}, {list})
+             SELECT m.new_id, {select} FROM bundle.\"{table}\" b
+             JOIN temp.merged_ids m ON m.old_id = b.{quoted}",
+            table = table,
+            quoted = quoted,
+            list = columns.join(", "),
+            select = select.join(", ")
+        ),
+        [],
+    )?;
+    Ok(())
+}
+
+/// Merge-mode import of one database, in a transaction. History entries this database
+/// doesn't have are added under new ids, and what refers to them follows under the new
+/// ids: their versions and links. They're queued for semantic indexing rather than
+/// copying vectors. Snippet versions are keyed by snippet id and added as they are.
+/// Trash, rules, drafts and the other tables are left as they are here.
+fn merge_tables(conn: &Connection) -> Result<usize, BundleError> {
+    let columns: Vec<String> = shared_columns(conn, "history")?
+        .into_iter()
+        .filter(|column| column != "\"id\"")
+        .collect();
+    let list = columns.join(", ");
+    let select: Vec<String> = columns.iter().map(|column| format!("b.{}", column)).collect();
+
+    conn.execute_batch(
+        "DROP TABLE IF EXISTS temp.merged_ids;
+         CREATE TEMP TABLE merged_ids (old_id INTEGER PRIMARY KEY, new_id INTEGER NOT NULL);",
+    )?;
+    let missing: Vec<i64> = {
+        let mut stmt = conn.prepare(
+            "SELECT b.id FROM bundle.history b
+             WHERE NOT EXISTS (
+                SELECT 1 FROM main.history h WHERE h.created_at = b.created_at AND h.content = b.content
+             )
+             ORDER BY b.id",
+        )?;
+        let ids = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+        ids
+    };
+    {
+        let mut insert = conn.prepare(&format!(
+            "INSERT INTO main.history ({}) SELECT {} FROM bundle.history b WHERE b.id = ?1",
+            list,
+            select.join(", ")
+        ))?;
+        let mut map = conn.prepare("INSERT INTO temp.merged_ids (old_id, new_id) VALUES (?1, ?2)")?;
+        for old_id in &missing {
+            insert.execute([old_id])?;
+            map.execute([*old_id, conn.last_insert_rowid()])?;
+        }
+    }
+
+    merge_entry_rows(conn, "history_versions", "entry_id")?;
+    // Links between two merged entries, or a merged entry and a snippet
+    if !shared_columns(conn, "item_links")?.is_empty() {
+        conn.execute(
+            "INSERT OR IGNORE INTO main.item_links (source_kind, source_id, target_kind, target_id, relation, created_at)
+             SELECT b.source_kind,
+                    CASE WHEN b.source_kind = 'history' THEN CAST(s.new_id AS TEXT) ELSE b.source_id END,
+                    b.target_kind,
+                    CASE WHEN b.target_kind = 'history' THEN CAST(t.new_id AS TEXT) ELSE b.target_id END,
+                    b.relation, b.created_at
+             FROM bundle.item_links b
+             LEFT JOIN temp.merged_ids s ON b.source_kind = 'history' AND s.old_id = CAST(b.source_id AS INTEGER)
+             LEFT JOIN temp.merged_ids t ON b.target_kind = 'history' AND t.old_id = CAST(b.target_id AS INTEGER)
+             WHERE (s.new_id IS NOT NULL OR t.new_id IS NOT NULL)
+               AND (b.source_kind != 'history' OR s.new_id IS NOT NULL)
+               AND (b.target_kind != 'history' OR t.new_id IS NOT NULL)",
+            [],
+        )?;
+    }
+    if !columns(conn, "main", "index_queue")?.is_empty() {
+        conn.execute(
+            "INSERT OR REPLACE INTO main.index_queue (entry_id) SELECT new_id FROM temp.merged_ids",
+            [],
+        )?;
+    }
+    let version_columns: Vec<String> = shared_columns(conn, "snippet_versions")?
+        .into_iter()
+        .filter(|column| column != "\"id\"")
+        .collect();
+    if !version_columns.is_empty() {
+        conn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO main.snippet_versions ({0}) SELECT {0} FROM bundle.snippet_versions",
+                version_columns.join(", ")
+            ),
+            [],
+        )?;
+    }
+    conn.execute_batch("DROP TABLE temp.merged_ids")?;
+    Ok(missing.len())
+}
+
+/// Load the bundled database at `source` into `conn`, replacing or merging its tables
+fn import_database(conn: &Connection, source: &Path, merge: bool) -> Result<usize, BundleError> {
+    conn.execute("ATTACH DATABASE ?1 AS bundle", [source.to_string_lossy()])?;
+    let result = if merge {
+        conn.execute_batch("BEGIN")?;
+        match merge_tables(conn) {
+            Ok(added) => conn.execute_batch("COMMIT").map(|_| added).map_err(BundleError::from),
+            Err(e) => {
+                let _ = conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    } else {
+        replace_tables(conn)
+    };
+    let _ = conn.execute_batch("DETACH DATABASE bundle");
+    result
+}
+
+/// Snippets from the bundle that aren't here, or were edited there since; returns how
+/// many were added. Snippets linked to a URL here are left alone, since their content
+/// only comes from the URL.
+fn merge_snippets(local: &Path, bundled: &[u8]) -> Result<usize, BundleError> {
+    let mut data: serde_json::Value = serde_json::from_str(&fs::read_to_string(local)?)?;
+    let incoming: serde_json::Value = serde_json::from_slice(bundled)?;
+    let Some(snippets) = data.get_mut("snippets").and_then(|s| s.as_array_mut()) else {
+        return Ok(0);
+    };
+    let mut added = 0;
+    for snippet in incoming
+        .get("snippets")
+        .and_then(|s| s.as_array())
+        .cloned()
+        .unwrap_or_default()
+    {
+        let id = snippet.get("id").cloned();
+        match snippets.iter_mut().find(|existing| existing.get("id") == id.as_ref()) {
+            Some(existing) => {
+                let linked = existing.get("source").is_some_and(|source| !source.is_null());
+                let newer = snippet.get("updated_at").and_then(|v| v.as_str())
+                    > existing.get("updated_at").and_then(|v| v.as_str());
+                if newer && !linked {
+                    *existing = snippet;
+                }
+            }
+            None => {
+                snippets.push(snippet);
+                added += 1;
+            }
+        }
+    }
+    fs::write(local, serde_json::to_string_pretty(&data)?)?;
+    Ok(added)
+}
+
+fn remove_side_files(db: &Path) {
+    for suffix in ["-wal", "-shm"] {
+        let mut side = db.as_os_str().to_owned();
+        side.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(side));
+    }
+}
+
+/// Whether importing `archive` would turn off an authentication gate that's on here.
+/// Merge imports never overwrite an existing settings.json.
+fn lowers_auth_gate(archive: &Path, merge: bool) -> Result<bool, BundleError> {
+    if merge {
+        return Ok(false);
+    }
+    let mut archive = ZipArchive::new(BufReader::new(File::open(archive)?))?;
+    let incoming: AppSettings = match archive.by_name("wingman/settings.json") {
+        Ok(mut file) => {
+            let mut text = String::new();
+            file.read_to_string(&mut text)?;
+            serde_json::from_str(&text)?
+        }
+        Err(_) => return Ok(false),
+    };
+    let current = load_settings()?.authentication_gate;
+    Ok(auth_gate::lowers(&current, &incoming.authentication_gate))
+}
+
+fn import_archive(db: &Mutex<Connection>, archive: &Path, merge: bool) -> Result<ImportReport, BundleError> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(archive)?))?;
+
+    let manifest: Manifest = {
+        let mut file = archive.by_name("manifest.json").map_err(|_| BundleError::NotABundle)?;
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        serde_json::from_str(&text)?
+    };
+    if manifest.version > BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(manifest.version));
+    }
+
+    let roots: BTreeMap<&str, PathBuf> = bundle_roots()?.into_iter().collect();
+    let active_db = get_db_path()?;
+    let mut report = ImportReport {
+        restart_recommended: !merge,
+        ..Default::default()
+    };
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        // enclosed_name rejects absolute paths and `..`
+        let Some(name) = file.enclosed_name() else {
+            continue;
+        };
+        let mut parts = name.components();
+        let Some(root) = parts
+            .next()
+            .and_then(|c| c.as_os_str().to_str())
+            .and_then(|prefix| roots.get(prefix))
+        else {
+            continue;
+        };
+        let dest = root.join(parts.as_path());
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if dest.extension().is_some_and(|e| e == "db") {
+            let source = temp_path("import", "db");
+            let result = File::create(&source)
+                .and_then(|mut out| std::io::copy(&mut file, &mut out))
+                .map_err(BundleError::from)
+                .and_then(|_| {
+                    if dest == active_db {
+                        let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+                        import_database(&conn, &source, merge)
+                    } else if merge && dest.exists() {
+                        import_database(&Connection::open(&dest)?, &source, true)
+                    } else {
+                        remove_side_files(&dest);
+                        fs::copy(&source, &dest)?;
+                        Ok(0)
+                    }
+                });
+            let _ = fs::remove_file(&source);
+            report.history_entries += result?;
+            report.files_written += 1;
+        } else if !dest.exists() || !merge {
+            std::io::copy(&mut file, &mut File::create(&dest)?)?;
+            report.files_written += 1;
+        } else if dest.file_name().is_some_and(|n| n == "snippets.json") {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            report.snippets_added += merge_snippets(&dest, &bytes)?;
+            report.files_written += 1;
+        } else {
+            report.files_kept += 1;
+        }
+    }
+
+    if manifest.include_secrets {
+        if let Ok(mut file) = archive.by_name("secrets.json") {
+            let mut text = String::new();
+            file.read_to_string(&mut text)?;
+            let secrets: BTreeMap<String, String> = serde_json::from_str(&text)?;
+            for (account, secret) in secrets {
+                let exists = crate::credentials::get_credential(account.clone())
+                    .ok()
+                    .flatten()
+                    .is_some();
+                if merge && exists {
+                    continue;
+                }
+                crate::credentials::store_credential(account, secret).map_err(|e| BundleError::Keychain(e.message))?;
+                report.secrets_restored += 1;
+            }
+        }
+    }
+
+    crate::history::clear_cache();
+    crate::saved_searches::mark_counts_stale();
+    Ok(report)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Write history, snippets, settings and configs of every profile to one encrypted file.
+/// Keychain secrets and token files are only included with `include_secrets`, which
+/// goes through the credential gate first.
+#[tauri::command]
+pub async fn export_all_data(
+    path: String,
+    passphrase: String,
+    include_secrets: bool,
+) -> Result<ExportReport, AppError> {
+    if include_secrets {
+        auth_gate::ensure(GatedAction::RevealCredential).await?;
+    }
+    let report =
+        tokio::task::spawn_blocking(move || export_bundle(Path::new(&path), &passphrase, include_secrets)).await??;
+    log::info!(
+        "Exported data bundle: {} files, {} databases, {} secrets",
+        report.files,
+        report.databases,
+        report.secrets
+    );
+    Ok(report)
+}
+
+/// Restore the bundle at `path`. The archive is decrypted to `archive` and read entry by
+/// entry; the open database is locked only while its own copy is imported. A replace
+/// import that lowers the authentication gate asks first, before anything is written.
+async fn import_gated(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+    merge: bool,
+    archive: PathBuf,
+) -> Result<ImportReport, AppError> {
+    let staged = archive.clone();
+    let lowers = tokio::task::spawn_blocking(move || {
+        unseal_to_file(Path::new(&path), &passphrase, &staged)?;
+        lowers_auth_gate(&staged, merge)
+    })
+    .await??;
+    if lowers {
+        auth_gate::ensure(GatedAction::LowerGate).await?;
+    }
+    let report = tokio::task::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        import_archive(&state.db, &archive, merge)
+    })
+    .await??;
+    Ok(report)
+}
+
+/// Restore a bundle from export_all_data; `merge` keeps local data and adds what's missing
+#[tauri::command]
+pub async fn import_all_data(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+    merge: bool,
+) -> Result<ImportReport, AppError> {
+    crate::privacy::ensure_history_writable()?;
+    let archive = temp_path("import", "zip");
+    let result = import_gated(app, path, passphrase, merge, archive.clone()).await;
+    let _ = fs::remove_file(&archive);
+    let report = result?;
+    if merge && report.history_entries > 0 {
+        crate::indexer::wake();
+    }
+    log::info!(
+        "Imported data bundle ({}): {:?}",
+        if merge { "merge" } else { "replace" },
+        report
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unseal(sealed: &[u8], passphrase: &str) -> Result<Vec<u8>, BundleError> {
+        let mut reader = &sealed[STREAM_MAGIC.len()..];
+        let mut plain = Vec::new();
+        open_stream(&mut reader, passphrase, &mut plain)?;
+        Ok(plain)
+    }
+
+    #[test]
+    fn test_seal_round_trip() {
+        let data: Vec<u8> = (0..CHUNK_LEN * 2 + 100).map(|i| (i % 251) as u8).collect();
+        for plain in [&b""[..], b"hello bundle", &data[..CHUNK_LEN], &data] {
+            let mut writer = SealWriter::new(Vec::new(), "correct horse", 1_000).unwrap();
+            writer.write_all(plain).unwrap();
+            let sealed = writer.finish().unwrap();
+            assert!(sealed.starts_with(STREAM_MAGIC));
+            assert_eq!(unseal(&sealed, "correct horse").unwrap(), plain);
+            assert!(matches!(unseal(&sealed, "wrong horse"), Err(BundleError::Decrypt)));
+        }
+        assert!(matches!(
+            open_sealed(b"not a bundle", "x"),
+            Err(BundleError::NotABundle)
+        ));
+    }
+
+    #[test]
+    fn test_truncated_stream_fails() {
+        let data = vec![7u8; CHUNK_LEN * 3];
+        let mut writer = SealWriter::new(Vec::new(), "correct horse", 1_000).unwrap();
+        writer.write_all(&data).unwrap();
+        let sealed = writer.finish().unwrap();
+        // Dropping the last chunk leaves a stream whose final chunk isn't marked last
+        let cut = sealed.len() - (CHUNK_LEN + TAG_LEN);
+        assert!(matches!(unseal(&sealed[..cut], "correct horse"), Err(BundleError::Decrypt)));
+    }
+
+    #[test]
+    fn test_bundle_skips_secrets_and_side_files() {
+        let root = std::env::temp_dir().join(format!("wingman-bundle-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("profiles/work")).unwrap();
+        fs::write(root.join("settings.json"), "{}").unwrap();
+        fs::write(root.join("github_token.json"), "{}").unwrap();
+        fs::write(root.join("history.db-wal"), "").unwrap();
+        fs::write(root.join("profiles/work/snippets.json"), "{\"snippets\":[]}").unwrap();
+
+        assert_eq!(
+            collect_files(&root, false).unwrap(),
+            vec!["profiles/work/snippets.json", "settings.json"]
+        );
+        assert!(collect_files(&root, true)
+            .unwrap()
+            .contains(&"github_token.json".to_string()));
+        assert!(matches!(
+            build_bundle(&[("wingman", root.clone())], Vec::new(), "short", false, 1_000),
+            Err(BundleError::WeakPassphrase)
+        ));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_merge_history_skips_existing() {
+        let dir = std::env::temp_dir();
+        let bundled = dir.join(format!("wingman-bundle-merge-{}.db", uuid::Uuid::new_v4()));
+        {
+            let source = Connection::open(&bundled).unwrap();
+            crate::history::create_schema(&source).unwrap();
+            source
+                .execute_batch(
+                    "INSERT INTO history (content, character_count, word_count, line_count, created_at)
+                     VALUES ('shared', 6, 1, 1, '2026-01-01 00:00:00'), ('new', 3, 1, 1, '2026-01-02 00:00:00');
+                     INSERT INTO history_versions (entry_id, version, content) VALUES (2, 1, 'older');
+                     INSERT INTO item_links (source_kind, source_id, target_kind, target_id, relation)
+                     VALUES ('history', '2', 'snippet', 'abc', 'saved_as');",
+                )
+                .unwrap();
+        }
+        let conn = Connection::open_in_memory().unwrap();
+        crate::history::create_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO history (content, character_count, word_count, line_count, created_at)
+             VALUES ('shared', 6, 1, 1, '2026-01-01 00:00:00'), ('local', 5, 1, 1, '2026-01-01 12:00:00')",
+            [],
+        )
+        .unwrap();
+
+        let added = import_database(&conn, &bundled, true).unwrap();
+        assert_eq!(added, 1);
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 3);
+
+        // What refers to the merged entry follows it to its new id
+        let new_id: i64 = conn
+            .query_row("SELECT id FROM history WHERE content = 'new'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(new_id, 3);
+        let version_entry: i64 = conn
+            .query_row("SELECT entry_id FROM history_versions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version_entry, new_id);
+        let link_source: String = conn
+            .query_row("SELECT source_id FROM item_links", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(link_source, new_id.to_string());
+        let queued: i64 = conn
+            .query_row("SELECT entry_id FROM index_queue", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(queued, new_id);
+        fs::remove_file(&bundled).unwrap();
+    }
+}
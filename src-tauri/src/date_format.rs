@@ -0,0 +1,296 @@
+// Timestamp display that follows settings.date_time: the user's date pattern, 12 or
+// 24-hour clock and display time zone. Patterns use ICU letters ("dd.MM.yyyy",
+// "EEE d MMM") since that's what people know from other apps; they're translated to
+// chrono's strftime format here. Named styles ("date", "time", "datetime", "iso") are
+// built from the settings, anything else is taken as a pattern.
+//
+// The display time zone is an IANA name ("Europe/Berlin") or an abbreviation, both
+// resolved through chrono-tz so daylight saving time is applied: "PST" means US Pacific
+// time and shows as PDT in summer. Fixed offsets ("+05:30") are taken as they are.
+
+use chrono::{DateTime, FixedOffset, Local, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::error::AppError;
+use crate::storage::{load_settings, DateTimeSettings};
+
+/// Unix timestamps above this are taken as milliseconds (year 2286 in seconds)
+const MILLIS_THRESHOLD: i64 = 10_000_000_000;
+
+/// A Unix timestamp in seconds or milliseconds, told apart by size
+pub fn from_unix(timestamp: i64) -> Option<DateTime<Utc>> {
+    if timestamp > MILLIS_THRESHOLD {
+        DateTime::from_timestamp(timestamp / 1000, ((timestamp % 1000) * 1_000_000) as u32)
+    } else {
+        DateTime::from_timestamp(timestamp, 0)
+    }
+}
+
+/// A Unix timestamp, RFC 3339, or a UTC "YYYY-MM-DD HH:MM:SS" like history's created_at
+pub fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(timestamp) = text.parse::<i64>() {
+        return from_unix(timestamp);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|naive| Utc.from_utc_datetime(&naive))
+}
+
+/// Parse timezone offsets (e.g., "+05:30", "-08:00", "UTC", "Z") and common abbreviations
+pub fn parse_offset(tz: &str) -> Result<FixedOffset, String> {
+    match tz.to_uppercase().as_str() {
+        "UTC" | "Z" => Ok(FixedOffset::east_opt(0).unwrap()),
+        "EST" => Ok(FixedOffset::west_opt(5 * 3600).unwrap()),
+        "EDT" => Ok(FixedOffset::west_opt(4 * 3600).unwrap()),
+        "CST" => Ok(FixedOffset::west_opt(6 * 3600).unwrap()),
+        "CDT" => Ok(FixedOffset::west_opt(5 * 3600).unwrap()),
+        "MST" => Ok(FixedOffset::west_opt(7 * 3600).unwrap()),
+        "MDT" => Ok(FixedOffset::west_opt(6 * 3600).unwrap()),
+        "PST" => Ok(FixedOffset::west_opt(8 * 3600).unwrap()),
+        "PDT" => Ok(FixedOffset::west_opt(7 * 3600).unwrap()),
+        "IST" => Ok(FixedOffset::east_opt(5 * 3600 + 1800).unwrap()),
+        "JST" => Ok(FixedOffset::east_opt(9 * 3600).unwrap()),
+        "CET" => Ok(FixedOffset::east_opt(3600).unwrap()),
+        "CEST" => Ok(FixedOffset::east_opt(2 * 3600).unwrap()),
+        "GMT" => Ok(FixedOffset::east_opt(0).unwrap()),
+        "AEST" => Ok(FixedOffset::east_opt(10 * 3600).unwrap()),
+        "AEDT" => Ok(FixedOffset::east_opt(11 * 3600).unwrap()),
+        s if s.starts_with('+') || s.starts_with('-') => {
+            let sign = if s.starts_with('-') { -1 } else { 1 };
+            let parts: Vec<&str> = s[1..].split(':').collect();
+            let hours: i32 = parts.first().and_then(|h| h.parse().ok()).unwrap_or(0);
+            let mins: i32 = parts.get(1).and_then(|m| m.parse().ok()).unwrap_or(0);
+            FixedOffset::east_opt(sign * (hours * 3600 + mins * 60)).ok_or_else(|| format!("Invalid offset: {}", s))
+        }
+        _ => Err(format!(
+            "Unknown timezone: {}. Use UTC, EST, PST, or offset like +05:30",
+            tz
+        )),
+    }
+}
+
+/// Abbreviations of zones that observe DST, either half of the pair naming the zone.
+/// Checked before the tz database, whose "EST" and "MST" are fixed offsets.
+fn zone_for_abbreviation(abbreviation: &str) -> Option<Tz> {
+    match abbreviation {
+        "EST" | "EDT" => Some(Tz::America__New_York),
+        "CST" | "CDT" => Some(Tz::America__Chicago),
+        "MST" | "MDT" => Some(Tz::America__Denver),
+        "PST" | "PDT" => Some(Tz::America__Los_Angeles),
+        "CET" | "CEST" => Some(Tz::Europe__Berlin),
+        "AEST" | "AEDT" => Some(Tz::Australia__Sydney),
+        _ => None,
+    }
+}
+
+/// The offset to show `instant` in, and the zone's name when it has one ("UTC", "PDT")
+fn display_zone(timezone: &str, instant: &DateTime<Utc>) -> Result<(FixedOffset, Option<String>), String> {
+    let timezone = timezone.trim();
+    if timezone.is_empty() || timezone.eq_ignore_ascii_case("local") {
+        return Ok((Local.offset_from_utc_datetime(&instant.naive_utc()).fix(), None));
+    }
+    let zone = zone_for_abbreviation(&timezone.to_uppercase()).or_else(|| timezone.parse::<Tz>().ok());
+    if let Some(zone) = zone {
+        let local = instant.with_timezone(&zone);
+        return Ok((local.offset().fix(), Some(local.format("%Z").to_string())));
+    }
+    let offset = parse_offset(timezone).map_err(|_| {
+        format!(
+            "Unknown timezone: {}. Use an IANA zone like America/New_York, UTC, PST, or an offset like +05:30",
+            timezone
+        )
+    })?;
+    let name = match timezone.to_uppercase() {
+        name if name.starts_with('+') || name.starts_with('-') => None,
+        name if name == "Z" => Some("UTC".to_string()),
+        name => Some(name),
+    };
+    Ok((offset, name))
+}
+
+/// Translate an ICU-style pattern to a chrono format string
+pub fn icu_to_strftime(pattern: &str) -> Result<String, String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            // Quoted literal; '' is a single quote
+            if chars.get(i + 1) == Some(&'\'') {
+                out.push('\'');
+                i += 2;
+                continue;
+            }
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\'' {
+                    if chars.get(i + 1) == Some(&'\'') {
+                        out.push('\'');
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                push_literal(&mut out, chars[i]);
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+        if !c.is_ascii_alphabetic() {
+            push_literal(&mut out, c);
+            i += 1;
+            continue;
+        }
+
+        let run = chars[i..].iter().take_while(|&&next| next == c).count();
+        let field = match (c, run) {
+            ('y', 2) => "%y",
+            ('y', _) => "%Y",
+            ('M', 1) => "%-m",
+            ('M', 2) => "%m",
+            ('M', 3) => "%b",
+            ('M', _) => "%B",
+            ('d', 1) => "%-d",
+            ('d', _) => "%d",
+            ('D', _) => "%j",
+            ('E', 1..=3) => "%a",
+            ('E', _) => "%A",
+            ('H', 1) => "%-H",
+            ('H', _) => "%H",
+            ('h', 1) => "%-I",
+            ('h', _) => "%I",
+            ('m', 1) => "%-M",
+            ('m', _) => "%M",
+            ('s', 1) => "%-S",
+            ('s', _) => "%S",
+            ('S', 1..=3) => "%3f",
+            ('S', 4..=6) => "%6f",
+            ('S', _) => "%9f",
+            ('a', _) => "%p",
+            ('Z', _) | ('X', 1..=2) | ('x', 1..=2) => "%z",
+            ('X', _) | ('x', _) | ('z', _) => "%:z",
+            _ => return Err(format!("Unsupported pattern letter '{}' in \"{}\"", c, pattern)),
+        };
+        out.push_str(field);
+        i += run;
+    }
+    Ok(out)
+}
+
+fn push_literal(out: &mut String, c: char) {
+    if c == '%' {
+        out.push_str("%%");
+    } else {
+        out.push(c);
+    }
+}
+
+/// Format `instant` in the display time zone. `style` is "date", "time", "datetime" (with
+/// the zone), "iso" (RFC 3339), or an ICU-style pattern.
+pub fn format_with(instant: &DateTime<Utc>, style: &str, settings: &DateTimeSettings) -> Result<String, String> {
+    let (offset, zone_name) = display_zone(&settings.timezone, instant)?;
+    let local = instant.with_timezone(&offset);
+    let time_pattern = if settings.clock_24h { "HH:mm:ss" } else { "h:mm:ss a" };
+
+    let pattern = match style {
+        "iso" => return Ok(local.to_rfc3339()),
+        "date" => settings.date_pattern.clone(),
+        "time" => time_pattern.to_string(),
+        "datetime" => {
+            let formatted = local
+                .format(&icu_to_strftime(&format!("{} {}", settings.date_pattern, time_pattern))?)
+                .to_string();
+            let zone = zone_name.unwrap_or_else(|| local.format("%:z").to_string());
+            return Ok(format!("{} {}", formatted, zone));
+        }
+        pattern => pattern.to_string(),
+    };
+    Ok(local.format(&icu_to_strftime(&pattern)?).to_string())
+}
+
+/// `format_with` using the saved settings
+pub fn format(instant: &DateTime<Utc>, style: &str) -> Result<String, String> {
+    format_with(instant, style, &load_settings().unwrap_or_default().date_time)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Show a Unix timestamp (seconds or ms), RFC 3339 time or history `created_at` the way
+/// the user's date and time settings ask for; `style` defaults to "datetime"
+#[tauri::command]
+pub fn format_timestamp(timestamp: String, style: Option<String>) -> Result<String, AppError> {
+    let instant = parse_timestamp(&timestamp)
+        .ok_or_else(|| AppError::invalid_input(format!("Not a timestamp: {}", timestamp)))?;
+    format(&instant, style.as_deref().unwrap_or("datetime")).map_err(AppError::invalid_input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(date_pattern: &str, clock_24h: bool, timezone: &str) -> DateTimeSettings {
+        DateTimeSettings {
+            date_pattern: date_pattern.to_string(),
+            clock_24h,
+            timezone: timezone.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_icu_patterns() {
+        assert_eq!(icu_to_strftime("yyyy-MM-dd HH:mm").unwrap(), "%Y-%m-%d %H:%M");
+        assert_eq!(icu_to_strftime("EEE d MMM ''yy").unwrap(), "%a %-d %b '%y");
+        assert_eq!(icu_to_strftime("'at' h a, 100%").unwrap(), "at %-I %p, 100%%");
+        assert!(icu_to_strftime("yyyy-QQ").is_err());
+    }
+
+    #[test]
+    fn test_styles() {
+        let instant = from_unix(1_704_067_200).unwrap();
+        // The defaults keep the old fixed UTC format
+        assert_eq!(
+            format_with(&instant, "datetime", &DateTimeSettings::default()).unwrap(),
+            "2024-01-01 00:00:00 UTC"
+        );
+
+        let us = settings("MM/dd/yyyy", false, "-05:00");
+        assert_eq!(format_with(&instant, "datetime", &us).unwrap(), "12/31/2023 7:00:00 PM -05:00");
+        assert_eq!(format_with(&instant, "date", &us).unwrap(), "12/31/2023");
+        assert_eq!(format_with(&instant, "iso", &us).unwrap(), "2023-12-31T19:00:00-05:00");
+        assert_eq!(
+            format_with(&instant, "EEEE d MMMM", &settings("dd.MM.yyyy", true, "JST")).unwrap(),
+            "Monday 1 January"
+        );
+    }
+
+    #[test]
+    fn test_zones_follow_dst() {
+        let winter = from_unix(1_704_067_200).unwrap();
+        let summer = from_unix(1_719_792_000).unwrap(); // 2024-07-01 00:00 UTC
+        let pacific = settings("yyyy-MM-dd", true, "PST");
+        assert_eq!(format_with(&winter, "datetime", &pacific).unwrap(), "2023-12-31 16:00:00 PST");
+        assert_eq!(format_with(&summer, "datetime", &pacific).unwrap(), "2024-06-30 17:00:00 PDT");
+        assert_eq!(
+            format_with(&summer, "iso", &settings("yyyy-MM-dd", true, "Europe/Berlin")).unwrap(),
+            "2024-07-01T02:00:00+02:00"
+        );
+        assert!(format_with(&summer, "date", &settings("yyyy-MM-dd", true, "Mars/Olympus")).is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = from_unix(1_704_067_200);
+        assert_eq!(parse_timestamp("1704067200000"), expected);
+        assert_eq!(parse_timestamp("2024-01-01T01:00:00+01:00"), expected);
+        assert_eq!(parse_timestamp("2024-01-01 00:00:00"), expected);
+        assert_eq!(parse_timestamp("soon"), None);
+    }
+}
@@ -0,0 +1,279 @@
+// Stress testing for the history database: realistic fake entries and timings for
+// the hot paths (insert, list, first page with and without the page cache, search,
+// export). benches/history.rs runs the same functions under criterion. The commands
+// only work in debug builds or with the `dev-tools` feature, since seeding writes junk
+// into the user's real history.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::error::AppError;
+use crate::history::{
+    add_entry, create_schema, export_history, get_entries, get_entries_after, search_entries, HistoryError,
+    FIRST_PAGE_SIZE,
+};
+pub use crate::history::PageCache;
+use crate::AppState;
+
+pub const DEFAULT_BENCHMARK_ENTRIES: u32 = 5_000;
+const MAX_ENTRIES: u32 = 100_000;
+const PAGE_SIZE: u32 = 100;
+/// Cached first-page reads averaged for `first_page_cached_ms`
+const CACHED_READS: u32 = 100;
+pub const SEARCH_QUERIES: [&str; 4] = ["error", "SELECT", "https://", "config"];
+
+const WORDS: [&str; 24] = [
+    "deploy", "config", "review", "meeting", "invoice", "release", "customer", "migration",
+    "the", "a", "with", "before", "after", "staging", "production", "error", "timeout",
+    "cache", "token", "draft", "notes", "follow", "up", "tomorrow",
+];
+const HOSTS: [&str; 5] = ["github.com", "docs.rs", "example.com", "stackoverflow.com", "linear.app"];
+const TABLES: [&str; 4] = ["users", "orders", "invoices", "sessions"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryBenchmark {
+    pub entries: u32,
+    pub insert_ms: f64,
+    /// Paging through every entry, PAGE_SIZE at a time
+    pub list_ms: f64,
+    /// The page the panel opens with, read from SQLite
+    pub first_page_ms: f64,
+    /// The same page from a warm page cache
+    pub first_page_cached_ms: f64,
+    /// Average over SEARCH_QUERIES
+    pub search_ms: f64,
+    pub export_ms: f64,
+}
+
+fn words(rng: &mut StdRng, count: usize) -> String {
+    (0..count)
+        .map(|_| *WORDS.choose(rng).unwrap_or(&"text"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A clip like the ones people copy: prose, code, JSON, URLs, SQL or stack traces
+pub fn fake_entry(rng: &mut StdRng) -> (String, Option<&'static str>) {
+    match rng.gen_range(0..6) {
+        0 => {
+            let sentences = rng.gen_range(1..6);
+            let text = (0..sentences)
+                .map(|_| {
+                    let n = rng.gen_range(6..18);
+                    format!("{}.", words(rng, n))
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            (text, Some("plaintext"))
+        }
+        1 => {
+            let name = WORDS.choose(rng).unwrap_or(&"run");
+            let code = format!(
+                "fn {}_{}(input: &str) -> Result<String, Error> {{\n    let value = input.trim();\n    if value.is_empty() {{\n        return Err(Error::Empty);\n    }}\n    Ok(value.to_string())\n}}",
+                name,
+                rng.gen_range(0..1000)
+            );
+            (code, Some("rust"))
+        }
+        2 => {
+            let json = format!(
+                "{{\n  \"id\": {},\n  \"name\": \"{}\",\n  \"enabled\": {},\n  \"tags\": [\"{}\", \"{}\"]\n}}",
+                rng.gen_range(1..100_000),
+                words(rng, 2),
+                rng.gen_bool(0.5),
+                WORDS.choose(rng).unwrap_or(&"a"),
+                WORDS.choose(rng).unwrap_or(&"b")
+            );
+            (json, Some("json"))
+        }
+        3 => {
+            let url = format!(
+                "https://{}/{}/{}?page={}",
+                HOSTS.choose(rng).unwrap_or(&"example.com"),
+                WORDS.choose(rng).unwrap_or(&"docs"),
+                rng.gen_range(1..10_000),
+                rng.gen_range(1..20)
+            );
+            (url, None)
+        }
+        4 => {
+            let sql = format!(
+                "SELECT id, created_at FROM {} WHERE status = '{}' ORDER BY created_at DESC LIMIT {};",
+                TABLES.choose(rng).unwrap_or(&"users"),
+                WORDS.choose(rng).unwrap_or(&"active"),
+                rng.gen_range(10..500)
+            );
+            (sql, Some("sql"))
+        }
+        _ => {
+            let depth = rng.gen_range(3..12);
+            let frames = (0..depth)
+                .map(|i| format!("    at {}.handle (src/{}.ts:{}:{})", WORDS[i % WORDS.len()], WORDS[(i * 7) % WORDS.len()], rng.gen_range(1..400), rng.gen_range(1..80)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            (format!("TypeError: Cannot read properties of undefined (reading '{}')\n{}", words(rng, 1), frames), None)
+        }
+    }
+}
+
+/// An empty history database in memory
+pub fn memory_database() -> Result<Connection, HistoryError> {
+    let conn = Connection::open_in_memory()?;
+    create_schema(&conn)?;
+    Ok(conn)
+}
+
+/// Add `count` fake entries in one transaction. The same seed gives the same entries.
+pub fn seed(conn: &Connection, count: u32, seed: u64) -> Result<u32, HistoryError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let tx = conn.unchecked_transaction()?;
+    for _ in 0..count {
+        let (content, language) = fake_entry(&mut rng);
+        add_entry(&tx, &content, language, None, None, None)?;
+    }
+    tx.commit()?;
+    Ok(count)
+}
+
+/// Page through every entry the way the history panel does; returns how many were read
+pub fn list_all(conn: &Connection) -> Result<usize, HistoryError> {
+    let mut cursor = None;
+    let mut read = 0;
+    loop {
+        let page = get_entries_after(conn, cursor, PAGE_SIZE)?;
+        let Some(last) = page.last() else {
+            return Ok(read);
+        };
+        cursor = Some(last.id);
+        read += page.len();
+    }
+}
+
+/// Load the panel's first page, through `cache` when given; returns how many were read
+pub fn first_page(conn: &Connection, cache: Option<&PageCache>) -> Result<usize, HistoryError> {
+    let entries = match cache {
+        Some(cache) => cache.get_entries(conn, FIRST_PAGE_SIZE, 0)?,
+        None => get_entries(conn, FIRST_PAGE_SIZE, 0)?,
+    };
+    Ok(entries.len())
+}
+
+pub fn search(conn: &Connection, query: &str) -> Result<usize, HistoryError> {
+    Ok(search_entries(conn, query, PAGE_SIZE)?.len())
+}
+
+pub fn export(conn: &Connection) -> Result<usize, HistoryError> {
+    Ok(export_history(conn)?.len())
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Time the history operations against a fresh in-memory database of `entries` entries
+pub fn benchmark(entries: u32) -> Result<HistoryBenchmark, HistoryError> {
+    let conn = memory_database()?;
+
+    let started = Instant::now();
+    seed(&conn, entries, 42)?;
+    let insert_ms = millis(started.elapsed());
+
+    let started = Instant::now();
+    list_all(&conn)?;
+    let list_ms = millis(started.elapsed());
+
+    let started = Instant::now();
+    first_page(&conn, None)?;
+    let first_page_ms = millis(started.elapsed());
+
+    let cache = PageCache::new();
+    first_page(&conn, Some(&cache))?;
+    let started = Instant::now();
+    for _ in 0..CACHED_READS {
+        first_page(&conn, Some(&cache))?;
+    }
+    let first_page_cached_ms = millis(started.elapsed()) / CACHED_READS as f64;
+
+    let started = Instant::now();
+    for query in SEARCH_QUERIES {
+        search(&conn, query)?;
+    }
+    let search_ms = millis(started.elapsed()) / SEARCH_QUERIES.len() as f64;
+
+    let started = Instant::now();
+    export(&conn)?;
+    let export_ms = millis(started.elapsed());
+
+    Ok(HistoryBenchmark {
+        entries,
+        insert_ms,
+        list_ms,
+        first_page_ms,
+        first_page_cached_ms,
+        search_ms,
+        export_ms,
+    })
+}
+
+fn check_enabled() -> Result<(), String> {
+    if cfg!(any(debug_assertions, feature = "dev-tools")) {
+        Ok(())
+    } else {
+        Err("Developer tools aren't available in this build".to_string())
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Add `count` fake entries to the real history, for stress testing the UI
+#[tauri::command]
+pub async fn seed_fake_history(app: AppHandle, count: u32) -> Result<u32, AppError> {
+    check_enabled()?;
+    let count = count.min(MAX_ENTRIES);
+    let handle = app.clone();
+    let seeded = tauri::async_runtime::spawn_blocking(move || {
+        let state = handle.state::<AppState>();
+        let conn = state.db.lock()?;
+        seed(&conn, count, rand::random()).map_err(AppError::from)
+    })
+    .await??;
+    crate::tray::rebuild(&app);
+    Ok(seeded)
+}
+
+/// Time insert, list, first page, search and export on a scratch database; real history is untouched
+#[tauri::command]
+pub async fn benchmark_history_ops(entries: Option<u32>) -> Result<HistoryBenchmark, AppError> {
+    check_enabled()?;
+    let entries = entries.unwrap_or(DEFAULT_BENCHMARK_ENTRIES).clamp(1, MAX_ENTRIES);
+    tauri::async_runtime::spawn_blocking(move || benchmark(entries).map_err(AppError::from))
+        .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_and_benchmark() {
+        let conn = memory_database().unwrap();
+        assert_eq!(seed(&conn, 200, 7).unwrap(), 200);
+        assert_eq!(list_all(&conn).unwrap(), 200);
+        assert_eq!(export(&conn).unwrap(), 200);
+
+        // Seeding is deterministic
+        let mut a = StdRng::seed_from_u64(3);
+        let mut b = StdRng::seed_from_u64(3);
+        assert_eq!(fake_entry(&mut a), fake_entry(&mut b));
+
+        let report = benchmark(50).unwrap();
+        assert_eq!(report.entries, 50);
+    }
+}
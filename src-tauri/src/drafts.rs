@@ -0,0 +1,280 @@
+// Editor autosave. The frontend saves the panel's content periodically and on hide;
+// each app session writes to a single draft row, so a crash or quit loses at most
+// the last few seconds of typing. The latest draft is offered back on the first show.
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+use thiserror::Error;
+
+use crate::error::AppError;
+use crate::storage::load_settings;
+use crate::AppState;
+
+#[derive(Error, Debug)]
+pub enum DraftError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("Invalid draft metadata: {0}")]
+    Metadata(#[from] serde_json::Error),
+    #[error("Draft {0} not found")]
+    NotFound(i64),
+}
+
+/// Older drafts beyond this are pruned on save
+const MAX_DRAFTS: i64 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Draft {
+    pub id: i64,
+    pub content: String,
+    /// Editor state saved alongside the text (language, cursor position, ...)
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Draft row this session is writing to
+static SESSION_DRAFT: Mutex<Option<i64>> = Mutex::new(None);
+/// The latest draft is only offered back once per launch
+static STARTUP_RESTORE_DONE: AtomicBool = AtomicBool::new(false);
+
+pub fn init_drafts_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS drafts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            content TEXT NOT NULL,
+            metadata TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_drafts_updated_at ON drafts(updated_at DESC)",
+        [],
+    )?;
+    Ok(())
+}
+
+const DRAFT_COLUMNS: &str = "id, content, metadata, created_at, updated_at";
+
+fn map_draft_row(row: &rusqlite::Row) -> rusqlite::Result<Draft> {
+    let metadata: Option<String> = row.get(2)?;
+    Ok(Draft {
+        id: row.get(0)?,
+        content: row.get(1)?,
+        metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+pub fn get_draft(conn: &Connection, id: i64) -> Result<Option<Draft>, DraftError> {
+    let sql = format!("SELECT {} FROM drafts WHERE id = ?1", DRAFT_COLUMNS);
+    Ok(conn.query_row(&sql, [id], map_draft_row).optional()?)
+}
+
+pub fn get_drafts(conn: &Connection) -> Result<Vec<Draft>, DraftError> {
+    let sql = format!(
+        "SELECT {} FROM drafts ORDER BY updated_at DESC, id DESC",
+        DRAFT_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let drafts = stmt
+        .query_map([], map_draft_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(drafts)
+}
+
+pub fn latest_draft(conn: &Connection) -> Result<Option<Draft>, DraftError> {
+    let sql = format!(
+        "SELECT {} FROM drafts ORDER BY updated_at DESC, id DESC LIMIT 1",
+        DRAFT_COLUMNS
+    );
+    Ok(conn.query_row(&sql, [], map_draft_row).optional()?)
+}
+
+/// Write `content` to the draft row `current` (or a new one). Empty content means the
+/// editor was cleared, so the draft is removed. Returns the id now holding the draft.
+pub fn upsert_draft(
+    conn: &Connection,
+    current: Option<i64>,
+    content: &str,
+    metadata: Option<&serde_json::Value>,
+) -> Result<Option<i64>, DraftError> {
+    if content.trim().is_empty() {
+        if let Some(id) = current {
+            conn.execute("DELETE FROM drafts WHERE id = ?1", [id])?;
+        }
+        return Ok(None);
+    }
+
+    let metadata = metadata.map(serde_json::to_string).transpose()?;
+    let updated = match current {
+        Some(id) => conn.execute(
+            "UPDATE drafts SET content = ?2, metadata = ?3, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            rusqlite::params![id, content, metadata],
+        )?,
+        None => 0,
+    };
+
+    let id = match current {
+        Some(id) if updated > 0 => id,
+        // No session draft yet, or it was deleted from the drafts list
+        _ => {
+            conn.execute(
+                "INSERT INTO drafts (content, metadata) VALUES (?1, ?2)",
+                rusqlite::params![content, metadata],
+            )?;
+            conn.last_insert_rowid()
+        }
+    };
+
+    conn.execute(
+        "DELETE FROM drafts WHERE id NOT IN (
+            SELECT id FROM drafts ORDER BY updated_at DESC, id DESC LIMIT ?1
+        )",
+        [MAX_DRAFTS],
+    )?;
+
+    Ok(Some(id))
+}
+
+pub fn delete_draft(conn: &Connection, id: i64) -> Result<(), DraftError> {
+    conn.execute("DELETE FROM drafts WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Forget the session draft, e.g. after switching to another profile's database
+pub fn reset_session() {
+    if let Ok(mut session) = SESSION_DRAFT.lock() {
+        *session = None;
+    }
+}
+
+fn drafts_enabled() -> bool {
+    load_settings().map(|s| s.auto_save_drafts).unwrap_or(true)
+}
+
+/// On the first show after launch, hand the latest draft to the frontend so the
+/// editor picks up where it left off. Later edits keep writing to that draft.
+pub fn restore_latest_on_first_show(app: &AppHandle, state: &AppState) {
+    if STARTUP_RESTORE_DONE.swap(true, Ordering::SeqCst) || !drafts_enabled() {
+        return;
+    }
+    let draft = match state.db.lock() {
+        Ok(conn) => latest_draft(&conn),
+        Err(_) => return,
+    };
+    match draft {
+        Ok(Some(draft)) => {
+            if let Ok(mut session) = SESSION_DRAFT.lock() {
+                *session = Some(draft.id);
+            }
+            log::info!("Restoring draft {} from the previous session", draft.id);
+            app.emit("draft-restored", draft).ok();
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to load latest draft: {}", e),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Autosave the editor. Returns the saved draft, or `None` if the editor is empty
+/// or drafts are turned off in settings.
+#[tauri::command]
+pub fn save_draft(
+    state: State<AppState>,
+    content: String,
+    metadata: Option<serde_json::Value>,
+) -> Result<Option<Draft>, AppError> {
+    if !drafts_enabled() {
+        return Ok(None);
+    }
+    let conn = state.db.lock()?;
+    let mut session = SESSION_DRAFT.lock()?;
+
+    *session = upsert_draft(&conn, *session, &content, metadata.as_ref())?;
+    match *session {
+        Some(id) => get_draft(&conn, id).map_err(AppError::from),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub fn list_drafts(state: State<AppState>) -> Result<Vec<Draft>, AppError> {
+    let conn = state.db.lock()?;
+    get_drafts(&conn).map_err(AppError::from)
+}
+
+/// Load a draft into the editor; subsequent autosaves update it instead of the
+/// session's previous draft.
+#[tauri::command]
+pub fn restore_draft(state: State<AppState>, id: i64) -> Result<Draft, AppError> {
+    let conn = state.db.lock()?;
+    let draft = get_draft(&conn, id)?
+        .ok_or(DraftError::NotFound(id))?;
+    *SESSION_DRAFT.lock()? = Some(draft.id);
+    Ok(draft)
+}
+
+#[tauri::command]
+pub fn discard_draft(state: State<AppState>, id: i64) -> Result<(), AppError> {
+    let conn = state.db.lock()?;
+    delete_draft(&conn, id)?;
+    let mut session = SESSION_DRAFT.lock()?;
+    if *session == Some(id) {
+        *session = None;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_drafts_table(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_upsert_updates_session_draft() {
+        let conn = test_conn();
+        let meta = serde_json::json!({ "language": "rust" });
+        let id = upsert_draft(&conn, None, "fn main", Some(&meta)).unwrap().unwrap();
+        let same = upsert_draft(&conn, Some(id), "fn main() {}", None).unwrap().unwrap();
+        assert_eq!(id, same);
+
+        let drafts = get_drafts(&conn).unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].content, "fn main() {}");
+        assert!(drafts[0].metadata.is_none());
+    }
+
+    #[test]
+    fn test_empty_content_removes_draft() {
+        let conn = test_conn();
+        let id = upsert_draft(&conn, None, "hello", None).unwrap();
+        assert_eq!(upsert_draft(&conn, id, "  \n", None).unwrap(), None);
+        assert!(latest_draft(&conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_drafts_are_pruned() {
+        let conn = test_conn();
+        for i in 0..(MAX_DRAFTS + 5) {
+            upsert_draft(&conn, None, &format!("draft {}", i), None).unwrap();
+        }
+        let drafts = get_drafts(&conn).unwrap();
+        assert_eq!(drafts.len() as i64, MAX_DRAFTS);
+        assert_eq!(drafts[0].content, format!("draft {}", MAX_DRAFTS + 4));
+    }
+}
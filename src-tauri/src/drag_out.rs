@@ -0,0 +1,266 @@
+// Drag a history entry out of Wingman as a file. The entry is written to a temp file
+// named after its title with the extension for its language, then on macOS an
+// AppKit dragging session is started on the main thread so the file can be dropped into
+// Finder, Slack, Mail and so on. The command runs after the webview's mousedown has been
+// handled, when NSApp's currentEvent is nil or something else, so a local event monitor
+// keeps the last left mouse down/dragged event and the drag starts from that while the
+// button is still held. Other platforms only get the path.
+
+use tauri::{AppHandle, State};
+
+use crate::error::AppError;
+use crate::history::{get_entry, HistoryEntry, HistoryError};
+use crate::AppState;
+
+/// File name for a dragged entry: its title or first line, else "clip-<id>"
+fn drag_file_name(entry: &HistoryEntry) -> String {
+    let (_, extensions) = crate::get_file_filter(entry.language.as_deref().unwrap_or("plaintext"));
+    let source = entry
+        .title
+        .clone()
+        .unwrap_or_else(|| entry.content.lines().find(|l| !l.trim().is_empty()).unwrap_or_default().to_string());
+    let stem: String = source
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect::<String>()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .take(60)
+        .collect();
+    let stem = if stem.is_empty() { format!("clip-{}", entry.id) } else { stem };
+    format!("{}.{}", stem, extensions[0])
+}
+
+/// Write the entry into its own temp directory so the file keeps a clean name
+fn write_drag_file(entry: &HistoryEntry) -> Result<std::path::PathBuf, String> {
+    let dir = crate::temp_files::subdir(&format!("drag_{}", entry.id))?;
+    let path = dir.join(drag_file_name(entry));
+    crate::temp_files::write_private(&path, &entry.content)?;
+    Ok(path)
+}
+
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+mod macos {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::{NSArray, NSPoint, NSRect, NSSize, NSString};
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Class, Object, Protocol, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::sync::Once;
+
+    const SOURCE_CLASS: &str = "WingmanDragSource";
+    /// NSDragOperationCopy
+    const DRAG_OPERATION_COPY: u64 = 1;
+    const ICON_SIZE: f64 = 48.0;
+    /// NSEventTypeLeftMouseDown, NSEventTypeLeftMouseDragged
+    const LEFT_MOUSE_DOWN: u64 = 1;
+    const LEFT_MOUSE_DRAGGED: u64 = 6;
+    const MOUSE_EVENT_MASK: u64 = (1 << LEFT_MOUSE_DOWN) | (1 << LEFT_MOUSE_DRAGGED);
+
+    /// The last left mouse down/dragged event, retained; stored as an address since `id`
+    /// isn't Send. Only touched on the main thread.
+    static LAST_MOUSE_EVENT: std::sync::Mutex<usize> = std::sync::Mutex::new(0);
+
+    /// Keep the last left mouse event for begin_drag. Call once on the main thread.
+    pub(crate) fn install_mouse_monitor() {
+        use block::ConcreteBlock;
+
+        let handler = ConcreteBlock::new(|event: id| -> id {
+            if event != nil {
+                unsafe {
+                    let _: id = msg_send![event, retain];
+                    let mut last = LAST_MOUSE_EVENT.lock().unwrap_or_else(|e| e.into_inner());
+                    let previous = std::mem::replace(&mut *last, event as usize);
+                    if previous != 0 {
+                        let _: () = msg_send![previous as id, release];
+                    }
+                }
+            }
+            event
+        });
+        let handler = handler.copy();
+        unsafe {
+            let _: id = msg_send![class!(NSEvent), addLocalMonitorForEventsMatchingMask:MOUSE_EVENT_MASK handler:&*handler];
+        }
+        // The monitor lives as long as the app
+        std::mem::forget(handler);
+    }
+
+    /// The mouse event to drag from: the monitored one while the left button is still
+    /// held, else NSApp's current event if it's a left mouse event
+    unsafe fn drag_event(ns_app: id) -> Option<id> {
+        let pressed: u64 = msg_send![class!(NSEvent), pressedMouseButtons];
+        if pressed & 1 == 0 {
+            return None;
+        }
+        let last = *LAST_MOUSE_EVENT.lock().unwrap_or_else(|e| e.into_inner());
+        if last != 0 {
+            return Some(last as id);
+        }
+        let current: id = msg_send![ns_app, currentEvent];
+        if current == nil {
+            return None;
+        }
+        let kind: u64 = msg_send![current, type];
+        matches!(kind, LEFT_MOUSE_DOWN | LEFT_MOUSE_DRAGGED).then_some(current)
+    }
+
+    extern "C" fn operation_mask(_this: &Object, _cmd: Sel, _session: id, _context: i64) -> u64 {
+        DRAG_OPERATION_COPY
+    }
+
+    fn source_class() -> Option<&'static Class> {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(|| {
+            if let Some(mut decl) = ClassDecl::new(SOURCE_CLASS, class!(NSObject)) {
+                if let Some(protocol) = Protocol::get("NSDraggingSource") {
+                    decl.add_protocol(protocol);
+                }
+                unsafe {
+                    decl.add_method(
+                        sel!(draggingSession:sourceOperationMaskForDraggingContext:),
+                        operation_mask as extern "C" fn(&Object, Sel, id, i64) -> u64,
+                    );
+                }
+                decl.register();
+            }
+        });
+        Class::get(SOURCE_CLASS)
+    }
+
+    /// Start dragging the file at `path` from the triggering mouse event, using its
+    /// Finder icon as the drag image. Main thread only.
+    pub(super) unsafe fn begin_drag(ns_window: id, path: &str) -> Result<(), String> {
+        let ns_app: id = msg_send![class!(NSApplication), sharedApplication];
+        let Some(event) = drag_event(ns_app) else {
+            return Err("No mouse event to start the drag from; the button was released".to_string());
+        };
+        let view: id = msg_send![ns_window, contentView];
+        if view == nil {
+            return Err("Window has no content view".to_string());
+        }
+        let source_class = source_class().ok_or("Couldn't register the drag source")?;
+
+        let ns_path = NSString::alloc(nil).init_str(path);
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+        let item: id = msg_send![class!(NSDraggingItem), alloc];
+        let item: id = msg_send![item, initWithPasteboardWriter: url];
+
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let icon: id = msg_send![workspace, iconForFile: ns_path];
+        let location: NSPoint = msg_send![event, locationInWindow];
+        let location: NSPoint = msg_send![view, convertPoint:location fromView:nil];
+        let frame = NSRect::new(
+            NSPoint::new(location.x - ICON_SIZE / 2.0, location.y - ICON_SIZE / 2.0),
+            NSSize::new(ICON_SIZE, ICON_SIZE),
+        );
+        let _: () = msg_send![item, setDraggingFrame:frame contents:icon];
+
+        // The session keeps its source alive for as long as the drag lasts
+        let source: id = msg_send![source_class, new];
+        let items = NSArray::arrayWithObjects(nil, &[item]);
+        let session: id = msg_send![view, beginDraggingSessionWithItems:items event:event source:source];
+        if session == nil {
+            return Err("The drag couldn't be started".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Start keeping the mouse events drags begin from; call once on the main thread
+#[cfg(target_os = "macos")]
+pub fn install_mouse_monitor() {
+    macos::install_mouse_monitor();
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn install_mouse_monitor() {}
+
+/// Write a history entry to a temp file and, on macOS, start dragging it. Call this
+/// from the frontend's mousedown/dragstart handler so there's a mouse event to drag
+/// from. Returns the file path.
+#[tauri::command]
+pub async fn prepare_drag_file(app: AppHandle, state: State<'_, AppState>, entry_id: i64) -> Result<String, AppError> {
+    let entry = {
+        let conn = state.db.lock()?;
+        get_entry(&conn, entry_id)?
+            .ok_or(HistoryError::EntryNotFound(entry_id))?
+    };
+    let path = write_drag_file(&entry)?.to_string_lossy().to_string();
+
+    #[cfg(target_os = "macos")]
+    {
+        use crate::window::MAIN_WINDOW_LABEL;
+        use std::sync::mpsc;
+        use std::time::Duration;
+        use tauri::Manager;
+
+        // Must run on main thread to avoid Cocoa exceptions
+        let (tx, rx) = mpsc::channel();
+        let app_inner = app.clone();
+        let path_for_drag = path.clone();
+
+        app.run_on_main_thread(move || {
+            let result = match app_inner.get_webview_window(MAIN_WINDOW_LABEL) {
+                Some(window) => match window.ns_window() {
+                    Ok(ns_window) => unsafe { macos::begin_drag(ns_window as cocoa::base::id, &path_for_drag) },
+                    Err(e) => Err(e.to_string()),
+                },
+                None => Err("Window not found".to_string()),
+            };
+            let _ = tx.send(result);
+        })?;
+
+        rx.recv_timeout(Duration::from_secs(5))
+            .map_err(|e| format!("Timeout waiting for drag: {}", e))??;
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(content: &str, title: Option<&str>, language: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            id: 7,
+            content: content.to_string(),
+            created_at: String::new(),
+            character_count: 0,
+            word_count: 0,
+            line_count: 0,
+            language: language.map(str::to_string),
+            tags: None,
+            images: None,
+            html: None,
+            paste_service: None,
+            paste_url: None,
+            paste_published_at: None,
+            expires_at: None,
+            summary: None,
+            title: title.map(str::to_string),
+            smart_tags: None,
+            reminder_at: None,
+            reminder_note: None,
+            content_truncated: false,
+            source_app: None,
+            source_bundle_id: None,
+            source_window_title: None,
+        }
+    }
+
+    #[test]
+    fn test_drag_file_name() {
+        assert_eq!(drag_file_name(&entry("fn main() {}", None, Some("rust"))), "fn_main.rs");
+        assert_eq!(drag_file_name(&entry("x", Some("Login fix: v2"), Some("python"))), "Login_fix_v2.py");
+        assert_eq!(drag_file_name(&entry("\n  \n", None, None)), "clip-7.txt");
+    }
+}
@@ -0,0 +1,558 @@
+// The error type returned by Tauri commands. It serializes to
+// `{"code": "pro_required", "message": "...", "details": ...}` so the frontend can act
+// on the code (open the license screen, offer a retry, highlight the bad field) instead
+// of pattern-matching message text. Module errors convert into it with `?`; each
+// conversion below decides which code a variant deserves.
+//
+// Helpers that still fail with a plain String convert to `internal`, so commands can be
+// moved over without rewriting everything they call.
+
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The feature needs Wingman Pro
+    ProRequired,
+    /// The license key is missing, invalid or expired
+    LicenseInvalid,
+    SubscriptionExpired,
+    /// A usage or rate limit was hit (AI tokens, GitHub API)
+    QuotaExceeded,
+    /// The request never got an answer: no connection, DNS, timeout
+    Network,
+    /// Refused because offline mode is on
+    Offline,
+    /// A remote service answered with an error
+    ExternalService,
+    /// Not signed in to a service, or the sign-in was revoked
+    Unauthenticated,
+    PermissionDenied,
+    /// Refused because privacy mode is on
+    PrivacyMode,
+    /// The feature is switched off or not set up in settings
+    Disabled,
+    /// Not available on this platform or for this kind of content
+    Unsupported,
+    /// The user cancelled the operation; usually not worth showing
+    Cancelled,
+    InvalidInput,
+    NotFound,
+    /// Would clobber something that already exists
+    Conflict,
+    /// Reading or writing local files or the database failed
+    Storage,
+    Internal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Extra context for the UI, e.g. `{"field": "label"}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Prefix the message ("Step \"Summarize\" failed: ...") without losing the code
+    pub fn with_context(mut self, context: impl fmt::Display) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn privacy_mode(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::PrivacyMode, message)
+    }
+
+    pub fn offline() -> Self {
+        Self::new(ErrorCode::Offline, "Wingman is in offline mode")
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unsupported, message)
+    }
+
+    pub fn pro_required() -> Self {
+        Self::new(ErrorCode::ProRequired, "This feature requires a Pro license")
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// ============================================================================
+// Compatibility with String errors
+// ============================================================================
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+/// For callers that still expect a String, such as the local API
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message
+    }
+}
+
+// ============================================================================
+// Library errors
+// ============================================================================
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(error: std::sync::PoisonError<T>) -> Self {
+        Self::internal(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        let code = match error.kind() {
+            std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            std::io::ErrorKind::AlreadyExists => ErrorCode::Conflict,
+            _ => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::new(ErrorCode::Storage, error.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        match error {
+            rusqlite::Error::QueryReturnedNoRows => Self::not_found("Not found"),
+            error => Self::new(ErrorCode::Storage, error.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(error: reqwest::Error) -> Self {
+        let code = if error.is_status() {
+            ErrorCode::ExternalService
+        } else {
+            ErrorCode::Network
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(error: tauri::Error) -> Self {
+        Self::internal(error.to_string())
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        Self::internal(error.to_string())
+    }
+}
+
+// ============================================================================
+// Module errors
+// ============================================================================
+
+impl From<crate::storage::StorageError> for AppError {
+    fn from(error: crate::storage::StorageError) -> Self {
+        Self::new(ErrorCode::Storage, error.to_string())
+    }
+}
+
+impl From<crate::premium::PremiumError> for AppError {
+    fn from(error: crate::premium::PremiumError) -> Self {
+        use crate::premium::PremiumError::*;
+        let code = match &error {
+            NetworkError(_) => ErrorCode::Network,
+            InvalidLicense => ErrorCode::LicenseInvalid,
+            PremiumRequired => ErrorCode::ProRequired,
+            SubscriptionExpired => ErrorCode::SubscriptionExpired,
+            TokenLimitExceeded(_) => ErrorCode::QuotaExceeded,
+            StorageError(_) => ErrorCode::Storage,
+            AIServiceError(_) | ObsidianError(_) => ErrorCode::ExternalService,
+            ValidationError(_) => ErrorCode::InvalidInput,
+            PrivacyModeActive => ErrorCode::PrivacyMode,
+            Offline => ErrorCode::Offline,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::license::LicenseError> for AppError {
+    fn from(error: crate::license::LicenseError) -> Self {
+        use crate::license::LicenseError::*;
+        let code = match &error {
+            NetworkError(_) => ErrorCode::Network,
+            InvalidLicense | LicenseExpired => ErrorCode::LicenseInvalid,
+            DeviceLimitExceeded => ErrorCode::QuotaExceeded,
+            StorageError(_) => ErrorCode::Storage,
+            ValidationError(_) => ErrorCode::InvalidInput,
+            DeviceIdError(_) | EncryptionError(_) => ErrorCode::Internal,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::history::HistoryError> for AppError {
+    fn from(error: crate::history::HistoryError) -> Self {
+        use crate::history::HistoryError::*;
+        let code = match &error {
+            EntryNotFound(_) | VersionNotFound(..) => ErrorCode::NotFound,
+            Database(_) | Storage(_) | Attachment(_) | Compression(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::attachments::AttachmentError> for AppError {
+    fn from(error: crate::attachments::AttachmentError) -> Self {
+        use crate::attachments::AttachmentError::*;
+        let code = match &error {
+            InvalidData(_) => ErrorCode::InvalidInput,
+            Io(_) | Json(_) | Storage(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::ai_history::AIHistoryError> for AppError {
+    fn from(error: crate::ai_history::AIHistoryError) -> Self {
+        use crate::ai_history::AIHistoryError::*;
+        let code = match &error {
+            NotFound(_) => ErrorCode::NotFound,
+            Database(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::custom_prompts::PromptError> for AppError {
+    fn from(error: crate::custom_prompts::PromptError) -> Self {
+        use crate::custom_prompts::PromptError::*;
+        let field = match &error {
+            InvalidName(name) | ReservedName(name) | Duplicate(name) | NoOptions(name) => name,
+            Missing { label } | InvalidValue { label, .. } => label,
+        };
+        let details = serde_json::json!({ "field": field });
+        Self::invalid_input(error.to_string()).with_details(details)
+    }
+}
+
+impl From<crate::data_bundle::BundleError> for AppError {
+    fn from(error: crate::data_bundle::BundleError) -> Self {
+        use crate::data_bundle::BundleError::*;
+        match error {
+            History(error) => error.into(),
+            Decrypt | NotABundle | UnsupportedVersion(_) | WeakPassphrase => Self::invalid_input(error.to_string()),
+            Io(_) | Json(_) | Database(_) | Zip(_) | Storage(_) | Keychain(_) => {
+                Self::new(ErrorCode::Storage, error.to_string())
+            }
+        }
+    }
+}
+
+impl From<crate::drafts::DraftError> for AppError {
+    fn from(error: crate::drafts::DraftError) -> Self {
+        use crate::drafts::DraftError::*;
+        let code = match &error {
+            NotFound(_) => ErrorCode::NotFound,
+            Database(_) | Metadata(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::github::GitHubError> for AppError {
+    fn from(error: crate::github::GitHubError) -> Self {
+        use crate::github::GitHubError::*;
+        let code = match &error {
+            HttpClient(crate::http_client::HttpClientError::Offline) => ErrorCode::Offline,
+            Network(_) | HttpClient(_) => ErrorCode::Network,
+            NotAuthenticated | TokenRevoked | AuthTimeout | AuthPending => ErrorCode::Unauthenticated,
+            RateLimit | SlowDown(_) => ErrorCode::QuotaExceeded,
+            Forbidden(_) => ErrorCode::PermissionDenied,
+            NotConfigured => ErrorCode::Disabled,
+            InvalidContent(_) => ErrorCode::InvalidInput,
+            ApiError(_) => ErrorCode::ExternalService,
+            TokenSave(_) | TokenLoad(_) | Io(_) | Json(_) => ErrorCode::Storage,
+        };
+        let app_error = Self::new(code, error.to_string());
+        match error {
+            // The device flow modal polls again after this many seconds
+            SlowDown(interval) => app_error.with_details(serde_json::json!({ "interval": interval })),
+            _ => app_error,
+        }
+    }
+}
+
+impl From<crate::grammar::GrammarError> for AppError {
+    fn from(error: crate::grammar::GrammarError) -> Self {
+        use crate::grammar::GrammarError::*;
+        let code = match &error {
+            HttpClient(crate::http_client::HttpClientError::Offline) => ErrorCode::Offline,
+            Network(_) | HttpClient(_) => ErrorCode::Network,
+            Server(_) => ErrorCode::ExternalService,
+            InvalidUrl(_) => ErrorCode::InvalidInput,
+            PrivacyModeActive => ErrorCode::PrivacyMode,
+            Io(_) | Json(_) | Storage(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::http_client::HttpClientError> for AppError {
+    fn from(error: crate::http_client::HttpClientError) -> Self {
+        use crate::http_client::HttpClientError::*;
+        let code = match &error {
+            Offline => ErrorCode::Offline,
+            InvalidProxy(_) | InvalidPin(_) => ErrorCode::InvalidInput,
+            Keychain(_) | CaBundle { .. } => ErrorCode::Storage,
+            Tls(_) | Build(_) => ErrorCode::Internal,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::lan_sync::LanSyncError> for AppError {
+    fn from(error: crate::lan_sync::LanSyncError) -> Self {
+        use crate::lan_sync::LanSyncError::*;
+        let code = match &error {
+            Timeout(_) => ErrorCode::Network,
+            NotPaired(_) => ErrorCode::Unauthenticated,
+            PeerNotFound(_) => ErrorCode::NotFound,
+            Disabled => ErrorCode::Disabled,
+            Rejected(_) => ErrorCode::PermissionDenied,
+            Io(_) | Json(_) | Storage(_) | Keychain(_) => ErrorCode::Storage,
+            Crypto | Protocol(_) => ErrorCode::Internal,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::launcher::LauncherError> for AppError {
+    fn from(error: crate::launcher::LauncherError) -> Self {
+        use crate::launcher::LauncherError::*;
+        let code = match &error {
+            UnknownAction(_) => ErrorCode::InvalidInput,
+            NotFound(_) => ErrorCode::NotFound,
+            Storage(_) | History(_) => ErrorCode::Storage,
+            Lock => ErrorCode::Internal,
+            Denied(inner) => return inner.clone(),
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::local_api::LocalApiError> for AppError {
+    fn from(error: crate::local_api::LocalApiError) -> Self {
+        use crate::local_api::LocalApiError::*;
+        let code = match &error {
+            Timeout(_) => ErrorCode::Network,
+            BadRequest(_) => ErrorCode::InvalidInput,
+            Io(_) | Json(_) | Keychain(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::paste::PasteError> for AppError {
+    fn from(error: crate::paste::PasteError) -> Self {
+        use crate::paste::PasteError::*;
+        let code = match &error {
+            HttpClient(crate::http_client::HttpClientError::Offline) => ErrorCode::Offline,
+            Network(_) | HttpClient(_) => ErrorCode::Network,
+            ApiError(_) => ErrorCode::ExternalService,
+            UnknownService(_) => ErrorCode::InvalidInput,
+            PrivateBinNotConfigured => ErrorCode::Disabled,
+            Encryption => ErrorCode::Internal,
+            Io(_) | Json(_) | Storage(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::prompt_library::LibraryError> for AppError {
+    fn from(error: crate::prompt_library::LibraryError) -> Self {
+        use crate::prompt_library::LibraryError::*;
+        match error {
+            Premium(error) => error.into(),
+            WrongFormat(_) | UnsupportedVersion(_) | InvalidItem { .. } | Json(_) => {
+                Self::invalid_input(error.to_string())
+            }
+            Io(_) | Storage(_) => Self::new(ErrorCode::Storage, error.to_string()),
+        }
+    }
+}
+
+impl From<crate::quick_save::QuickSaveError> for AppError {
+    fn from(error: crate::quick_save::QuickSaveError) -> Self {
+        use crate::quick_save::QuickSaveError::*;
+        let code = match &error {
+            NoDirectory => ErrorCode::NotFound,
+            Exists(_) => ErrorCode::Conflict,
+            Io(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::rules::RuleError> for AppError {
+    fn from(error: crate::rules::RuleError) -> Self {
+        use crate::rules::RuleError::*;
+        let code = match &error {
+            NotFound(_) => ErrorCode::NotFound,
+            Invalid(_) => ErrorCode::InvalidInput,
+            Database(_) | Json(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::saved_searches::SavedSearchError> for AppError {
+    fn from(error: crate::saved_searches::SavedSearchError) -> Self {
+        use crate::saved_searches::SavedSearchError::*;
+        match error {
+            History(error) => error.into(),
+            NotFound(_) => Self::new(ErrorCode::NotFound, error.to_string()),
+            Invalid(_) => Self::invalid_input(error.to_string()),
+            Database(_) | Json(_) => Self::new(ErrorCode::Storage, error.to_string()),
+        }
+    }
+}
+
+impl From<crate::semantic::SemanticError> for AppError {
+    fn from(error: crate::semantic::SemanticError) -> Self {
+        use crate::semantic::SemanticError::*;
+        let code = match &error {
+            InvalidScope(_) => ErrorCode::InvalidInput,
+            Database(_) | Storage(_) | History(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::snippet_export::SnippetExportError> for AppError {
+    fn from(error: crate::snippet_export::SnippetExportError) -> Self {
+        use crate::snippet_export::SnippetExportError::*;
+        let code = match &error {
+            NothingToExport => ErrorCode::NotFound,
+            Io(_) | Json(_) | Storage(_) | Zip(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::ssh_keys::SshKeyError> for AppError {
+    fn from(error: crate::ssh_keys::SshKeyError) -> Self {
+        use crate::ssh_keys::SshKeyError::*;
+        let code = match &error {
+            InvalidKey(_) | InvalidInput(_) | PassphraseRequired | WrongPassphrase | Rsa(_) => ErrorCode::InvalidInput,
+            Unsupported(_) => ErrorCode::Unsupported,
+            Exists(_) => ErrorCode::Conflict,
+            Io(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::translate::TranslateError> for AppError {
+    fn from(error: crate::translate::TranslateError) -> Self {
+        use crate::translate::TranslateError::*;
+        let code = match &error {
+            HttpClient(crate::http_client::HttpClientError::Offline) => ErrorCode::Offline,
+            Network(_) | HttpClient(_) => ErrorCode::Network,
+            MissingApiKey(_) => ErrorCode::Unauthenticated,
+            UnsupportedLanguage(..) => ErrorCode::InvalidInput,
+            Service(_) => ErrorCode::ExternalService,
+            PrivacyModeActive => ErrorCode::PrivacyMode,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl From<crate::watch_folders::WatchFolderError> for AppError {
+    fn from(error: crate::watch_folders::WatchFolderError) -> Self {
+        use crate::watch_folders::WatchFolderError::*;
+        let code = match &error {
+            NotADirectory(_) | InvalidPattern(..) => ErrorCode::InvalidInput,
+            AlreadyWatched(_) => ErrorCode::Conflict,
+            NotFound(_) => ErrorCode::NotFound,
+            Io(_) | Json(_) | Storage(_) | Watcher(_) => ErrorCode::Storage,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialized_shape() {
+        let error: AppError = crate::premium::PremiumError::PremiumRequired.into();
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "pro_required");
+        assert_eq!(json["message"], "Premium subscription required");
+        assert!(json.get("details").is_none());
+
+        let legacy: AppError = "Something broke".to_string().into();
+        assert_eq!(legacy.code, ErrorCode::Internal);
+        assert_eq!(legacy.to_string(), "Something broke");
+    }
+
+    #[test]
+    fn test_prompt_errors_name_the_field() {
+        let error: AppError = crate::custom_prompts::PromptError::Missing {
+            label: "Audience".to_string(),
+        }
+        .into();
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+        assert_eq!(error.details.unwrap()["field"], "Audience");
+    }
+}
@@ -0,0 +1,427 @@
+// WebSocket event bridge for external dashboards. When turned on, Wingman listens on
+// 127.0.0.1 and pushes a JSON message to every connected client as things happen:
+//
+//   {"type": "entry_added", "at": "...", "id": 42, "language": "rust", "characters": 120, "lines": 6}
+//   {"type": "transform_run", "at": "...", "transform": "uppercase", "input_characters": 12, "output_characters": 12}
+//   {"type": "ai_call_completed", "at": "...", "feature": "summarize", "tokens_used": 310, "tokens_remaining": 99690}
+//
+// Messages describe activity, never content: no clip text, prompts or responses.
+// Clients authenticate with the local API token, either as `Authorization: Bearer` or,
+// for browsers that can't set headers on a WebSocket, as `?token=`. Each event type can
+// be switched off in settings. Clients never send anything but pings and closes.
+
+use base64::Engine;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::history::HistoryEntry;
+use crate::local_api::{error_body, is_authorized, is_local_host, read_request, token_matches, write_response, LocalApiError};
+use crate::storage::EventBridgeSettings;
+
+pub const DEFAULT_PORT: u16 = 47661;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Messages a slow client can fall behind by before it starts missing some
+const CHANNEL_CAPACITY: usize = 256;
+/// Clients only need to send pings and closes, which the spec caps at 125 bytes
+const MAX_CLIENT_FRAME: u64 = 4 * 1024;
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BridgeEvent {
+    EntryAdded {
+        id: i64,
+        language: Option<String>,
+        characters: i32,
+        lines: i32,
+    },
+    TransformRun {
+        transform: String,
+        input_characters: usize,
+        output_characters: usize,
+    },
+    AiCallCompleted {
+        feature: String,
+        tokens_used: i64,
+        tokens_remaining: i64,
+    },
+}
+
+impl BridgeEvent {
+    pub fn entry_added(entry: &HistoryEntry) -> Self {
+        BridgeEvent::EntryAdded {
+            id: entry.id,
+            language: entry.language.clone(),
+            characters: entry.character_count,
+            lines: entry.line_count,
+        }
+    }
+
+    fn allowed_by(&self, filter: &EventBridgeSettings) -> bool {
+        match self {
+            BridgeEvent::EntryAdded { .. } => filter.entry_added,
+            BridgeEvent::TransformRun { .. } => filter.transform_run,
+            BridgeEvent::AiCallCompleted { .. } => filter.ai_call_completed,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    at: String,
+    #[serde(flatten)]
+    event: &'a BridgeEvent,
+}
+
+struct Service {
+    task: tauri::async_runtime::JoinHandle<()>,
+    port: u16,
+    sender: broadcast::Sender<String>,
+    filter: EventBridgeSettings,
+}
+
+static SERVICE: Mutex<Option<Service>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventBridgeInfo {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// Connected dashboards
+    pub clients: usize,
+}
+
+/// Send an event to connected clients. Cheap when the bridge is off or the event type
+/// is filtered out.
+pub fn publish(event: BridgeEvent) {
+    let service = SERVICE.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(service) = service.as_ref() else {
+        return;
+    };
+    if service.sender.receiver_count() == 0 || !event.allowed_by(&service.filter) {
+        return;
+    }
+    let message = Message {
+        at: chrono::Utc::now().to_rfc3339(),
+        event: &event,
+    };
+    match serde_json::to_string(&message) {
+        // Fails only when every client disconnected in the meantime
+        Ok(json) => {
+            service.sender.send(json).ok();
+        }
+        Err(e) => log::warn!("Failed to serialize bridge event: {}", e),
+    }
+}
+
+// ============================================================================
+// WebSocket
+// ============================================================================
+
+/// The Sec-WebSocket-Accept value for a client's Sec-WebSocket-Key
+fn accept_key(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key.trim().as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+}
+
+/// A single unmasked, unfragmented server frame
+fn encode_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Read one client frame, returning its opcode and unmasked payload
+async fn read_frame(reader: &mut OwnedReadHalf) -> Result<(u8, Vec<u8>), LocalApiError> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header).await?;
+    let opcode = header[0] & 0x0F;
+    let masked = header[1] & 0x80 != 0;
+    let length = match header[1] & 0x7F {
+        126 => {
+            let mut bytes = [0u8; 2];
+            reader.read_exact(&mut bytes).await?;
+            u16::from_be_bytes(bytes) as u64
+        }
+        127 => {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes).await?;
+            u64::from_be_bytes(bytes)
+        }
+        length => length as u64,
+    };
+    if !masked {
+        return Err(LocalApiError::BadRequest("client frames must be masked".to_string()));
+    }
+    if length > MAX_CLIENT_FRAME {
+        return Err(LocalApiError::BadRequest("frame too large".to_string()));
+    }
+
+    let mut mask = [0u8; 4];
+    reader.read_exact(&mut mask).await?;
+    let mut payload = vec![0u8; length as usize];
+    reader.read_exact(&mut payload).await?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((opcode, payload))
+}
+
+/// Answer pings and closes; returns when the client goes away
+async fn read_client(mut reader: OwnedReadHalf, replies: mpsc::Sender<Vec<u8>>) {
+    loop {
+        match read_frame(&mut reader).await {
+            Ok((OPCODE_PING, payload)) => {
+                if replies.send(encode_frame(OPCODE_PONG, &payload)).await.is_err() {
+                    return;
+                }
+            }
+            Ok((OPCODE_CLOSE, payload)) => {
+                replies.send(encode_frame(OPCODE_CLOSE, &payload)).await.ok();
+                return;
+            }
+            Ok(_) => {}
+            Err(_) => return,
+        }
+    }
+}
+
+/// Validate the upgrade request and switch protocols
+async fn handshake(stream: &mut TcpStream) -> Result<bool, LocalApiError> {
+    let request = read_request(stream).await?;
+    let rejection = if !is_local_host(request.header("host")) {
+        Some((403, "Requests must be addressed to localhost"))
+    } else if !is_authorized(request.header("authorization"))
+        && !request.query.get("token").is_some_and(|t| token_matches(t))
+    {
+        Some((401, "Missing or invalid API token"))
+    } else if request.method != "GET"
+        || !request
+            .header("upgrade")
+            .is_some_and(|u| u.eq_ignore_ascii_case("websocket"))
+    {
+        Some((400, "Expected a WebSocket upgrade"))
+    } else {
+        None
+    };
+    if let Some((status, message)) = rejection {
+        write_response(stream, status, &error_body(message)).await?;
+        return Ok(false);
+    }
+    let Some(key) = request.header("sec-websocket-key") else {
+        write_response(stream, 400, &error_body("Missing Sec-WebSocket-Key")).await?;
+        return Ok(false);
+    };
+
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    );
+    stream.write_all(response.as_bytes()).await?;
+    Ok(true)
+}
+
+async fn handle_connection(mut stream: TcpStream, mut events: broadcast::Receiver<String>) -> Result<(), LocalApiError> {
+    if !tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake(&mut stream)).await?? {
+        return Ok(());
+    }
+
+    let (reader, mut writer) = stream.into_split();
+    let (reply_tx, mut replies) = mpsc::channel(8);
+    let reader = tauri::async_runtime::spawn(read_client(reader, reply_tx));
+    let result = forward_events(&mut writer, &mut events, &mut replies).await;
+    reader.abort();
+    result
+}
+
+async fn forward_events(
+    writer: &mut OwnedWriteHalf,
+    events: &mut broadcast::Receiver<String>,
+    replies: &mut mpsc::Receiver<Vec<u8>>,
+) -> Result<(), LocalApiError> {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(json) => writer.write_all(&encode_frame(OPCODE_TEXT, json.as_bytes())).await?,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::debug!("Event bridge client fell behind by {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            reply = replies.recv() => match reply {
+                Some(frame) => {
+                    writer.write_all(&frame).await?;
+                    if frame[0] & 0x0F == OPCODE_CLOSE {
+                        return Ok(());
+                    }
+                }
+                None => return Ok(()),
+            },
+        }
+    }
+}
+
+async fn run_listener(listener: std::net::TcpListener, sender: broadcast::Sender<String>) {
+    let listener = match TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Event bridge listener failed: {}", e);
+            return;
+        }
+    };
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let events = sender.subscribe();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = handle_connection(stream, events).await {
+                        log::debug!("Event bridge connection ended: {}", e);
+                    }
+                });
+            }
+            Err(e) => {
+                log::warn!("Event bridge accept failed: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Service
+// ============================================================================
+
+fn start(filter: &EventBridgeSettings) -> Result<(), LocalApiError> {
+    let mut service = SERVICE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(service) = service.as_mut() {
+        service.filter = filter.clone();
+        return Ok(());
+    }
+    // Create the token up front so a keychain prompt doesn't stall the first client
+    crate::local_api::api_token()?;
+
+    let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, DEFAULT_PORT))
+        .or_else(|_| std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)))?;
+    listener.set_nonblocking(true)?;
+    let port = listener.local_addr()?.port();
+    log::info!("Event bridge listening on 127.0.0.1:{}", port);
+
+    let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+    *service = Some(Service {
+        task: tauri::async_runtime::spawn(run_listener(listener, sender.clone())),
+        port,
+        sender,
+        filter: filter.clone(),
+    });
+    Ok(())
+}
+
+fn stop() {
+    if let Some(service) = SERVICE.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        // Dropping the sender closes every client's stream
+        service.task.abort();
+    }
+}
+
+/// Start, stop or refilter the bridge to match settings
+pub fn configure(settings: &EventBridgeSettings) -> Result<(), String> {
+    if settings.enabled {
+        start(settings).map_err(|e| format!("Failed to start the event bridge: {}", e))
+    } else {
+        stop();
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Whether the bridge is up, its port and how many clients are connected. Clients
+/// use the token from get_local_api_info.
+#[tauri::command]
+pub fn get_event_bridge_info() -> EventBridgeInfo {
+    let service = SERVICE.lock().unwrap_or_else(|e| e.into_inner());
+    EventBridgeInfo {
+        running: service.is_some(),
+        port: service.as_ref().map(|s| s.port),
+        clients: service.as_ref().map_or(0, |s| s.sender.receiver_count()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_key() {
+        // Example from RFC 6455, section 1.3
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn test_encode_frame_lengths() {
+        assert_eq!(encode_frame(OPCODE_TEXT, b"hi"), vec![0x81, 2, b'h', b'i']);
+        let medium = encode_frame(OPCODE_TEXT, &[0; 300]);
+        assert_eq!(&medium[..4], &[0x81, 126, 0x01, 0x2C]);
+        let large = encode_frame(OPCODE_TEXT, &[0; 70_000]);
+        assert_eq!(&large[..2], &[0x81, 127]);
+        assert_eq!(large.len(), 70_000 + 10);
+    }
+
+    #[test]
+    fn test_filter_and_message_shape() {
+        let filter = EventBridgeSettings {
+            enabled: true,
+            entry_added: true,
+            transform_run: false,
+            ai_call_completed: true,
+        };
+        let transform = BridgeEvent::TransformRun {
+            transform: "uppercase".to_string(),
+            input_characters: 3,
+            output_characters: 3,
+        };
+        assert!(!transform.allowed_by(&filter));
+
+        let ai = BridgeEvent::AiCallCompleted {
+            feature: "summarize".to_string(),
+            tokens_used: 10,
+            tokens_remaining: 90,
+        };
+        assert!(ai.allowed_by(&filter));
+        let json = serde_json::to_value(Message {
+            at: "now".to_string(),
+            event: &ai,
+        })
+        .unwrap();
+        assert_eq!(json["type"], "ai_call_completed");
+        assert_eq!(json["tokens_used"], 10);
+        assert_eq!(json["at"], "now");
+    }
+}
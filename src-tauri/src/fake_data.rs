@@ -0,0 +1,535 @@
+// Realistic fake data for generate_bulk: names, emails, addresses, phone numbers, IP
+// addresses, test credit card numbers, dates and JSON objects built from a small schema.
+// Values are drawn from per-locale word lists; phone numbers use ranges reserved for
+// fiction where the country has one, emails use the reserved example.* domains and card
+// numbers use the processors' published test prefixes, so generated contact details
+// don't belong to anyone. With a seed the output is reproducible.
+
+use chrono::{Duration, NaiveDate};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+
+/// Generators handled here rather than by the id generators in generate_bulk. Schema
+/// fields can also use "uuid", which is seeded like everything else.
+pub const FAKE_GENERATORS: &[&str] = &[
+    "name",
+    "first_name",
+    "last_name",
+    "email",
+    "username",
+    "phone",
+    "address",
+    "city",
+    "postcode",
+    "country",
+    "ipv4",
+    "ipv6",
+    "credit_card",
+    "date",
+    "int",
+    "float",
+    "bool",
+    "word",
+    "sentence",
+    "json",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en_US")]
+    EnUs,
+    #[serde(rename = "en_GB")]
+    EnGb,
+    #[serde(rename = "de_DE")]
+    DeDe,
+    #[serde(rename = "fr_FR")]
+    FrFr,
+    #[serde(rename = "es_ES")]
+    EsEs,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FakeDataOptions {
+    pub locale: Locale,
+    /// Same seed, same output
+    pub seed: Option<u64>,
+    /// Inclusive YYYY-MM-DD bounds for "date"; 2000-01-01 to 2029-12-31 by default
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    /// For "json": an object whose values name generators, e.g.
+    /// {"id": "uuid", "name": "name", "age": "int:18-90", "tags": ["word", 3]}
+    pub schema: Option<Value>,
+}
+
+struct LocaleData {
+    first_names: &'static [&'static str],
+    last_names: &'static [&'static str],
+    streets: &'static [&'static str],
+    /// (city, region) pairs
+    cities: &'static [(&'static str, &'static str)],
+    /// {number} {street} {city} {region} {postcode}
+    address_format: &'static str,
+    /// `#` is a digit, `?` an uppercase letter
+    postcode: &'static str,
+    phone: &'static str,
+    country: &'static str,
+}
+
+const EN_US: LocaleData = LocaleData {
+    first_names: &[
+        "James", "Mary", "Robert", "Patricia", "John", "Jennifer", "Michael", "Linda", "David", "Elizabeth",
+        "William", "Barbara", "Richard", "Susan", "Joseph", "Jessica", "Thomas", "Sarah", "Carlos", "Karen",
+        "Daniel", "Maria", "Matthew", "Emily", "Anthony", "Ashley", "Andrew", "Michelle", "Kevin", "Aisha",
+    ],
+    last_names: &[
+        "Smith", "Johnson", "Williams", "Brown", "Jones", "Garcia", "Miller", "Davis", "Rodriguez", "Martinez",
+        "Hernandez", "Lopez", "Wilson", "Anderson", "Thomas", "Taylor", "Moore", "Jackson", "Martin", "Lee",
+        "Thompson", "White", "Harris", "Clark", "Lewis", "Nguyen", "Walker", "Hall", "Allen", "Young",
+    ],
+    streets: &[
+        "Main Street", "Oak Avenue", "Maple Drive", "Cedar Lane", "Pine Street", "Elm Street", "Washington Avenue",
+        "Lake Road", "Hill Street", "Park Avenue", "Sunset Boulevard", "River Road", "Church Street", "Highland Drive",
+    ],
+    cities: &[
+        ("Springfield", "IL"), ("Portland", "OR"), ("Austin", "TX"), ("Denver", "CO"), ("Columbus", "OH"),
+        ("Raleigh", "NC"), ("Madison", "WI"), ("Boise", "ID"), ("Albany", "NY"), ("Tucson", "AZ"),
+    ],
+    address_format: "{number} {street}, {city}, {region} {postcode}",
+    postcode: "#####",
+    // 555-0100 to 555-0199 are set aside for fiction
+    phone: "(###) 555-01##",
+    country: "United States",
+};
+
+const EN_GB: LocaleData = LocaleData {
+    first_names: &[
+        "Oliver", "Amelia", "George", "Isla", "Harry", "Ava", "Jack", "Mia", "Charlie", "Grace", "Thomas",
+        "Sophie", "Oscar", "Lily", "William", "Emily", "James", "Poppy", "Henry", "Freya", "Arjun", "Priya",
+    ],
+    last_names: &[
+        "Smith", "Jones", "Taylor", "Brown", "Williams", "Wilson", "Johnson", "Davies", "Robinson", "Wright",
+        "Thompson", "Evans", "Walker", "White", "Roberts", "Green", "Hall", "Wood", "Jackson", "Clarke", "Patel",
+    ],
+    streets: &[
+        "High Street", "Station Road", "Church Lane", "Victoria Road", "Mill Lane", "Park Road", "Queens Road",
+        "London Road", "Kings Road", "The Green", "Manor Road", "New Street",
+    ],
+    cities: &[
+        ("Bristol", "Avon"), ("Leeds", "West Yorkshire"), ("York", "North Yorkshire"), ("Bath", "Somerset"),
+        ("Oxford", "Oxfordshire"), ("Norwich", "Norfolk"), ("Exeter", "Devon"), ("Chester", "Cheshire"),
+    ],
+    address_format: "{number} {street}, {city}, {postcode}",
+    postcode: "??# #??",
+    // Ofcom's drama range
+    phone: "07700 900###",
+    country: "United Kingdom",
+};
+
+const DE_DE: LocaleData = LocaleData {
+    first_names: &[
+        "Lukas", "Anna", "Leon", "Lea", "Finn", "Hannah", "Jonas", "Lena", "Paul", "Marie", "Felix", "Sophie",
+        "Maximilian", "Laura", "Jürgen", "Jörg", "Mehmet", "Katharina", "Tobias", "Julia",
+    ],
+    last_names: &[
+        "Müller", "Schmidt", "Schneider", "Fischer", "Weber", "Meyer", "Wagner", "Becker", "Schulz", "Hoffmann",
+        "Koch", "Richter", "Klein", "Wolf", "Schröder", "Neumann", "Schwarz", "Braun", "Yılmaz", "Krüger",
+    ],
+    streets: &[
+        "Hauptstraße", "Schulstraße", "Gartenstraße", "Bahnhofstraße", "Dorfstraße", "Bergstraße",
+        "Lindenstraße", "Kirchstraße", "Waldstraße", "Ringstraße", "Am Markt", "Goethestraße",
+    ],
+    cities: &[
+        ("Berlin", "Berlin"), ("Hamburg", "Hamburg"), ("München", "Bayern"), ("Köln", "Nordrhein-Westfalen"),
+        ("Leipzig", "Sachsen"), ("Freiburg", "Baden-Württemberg"), ("Bremen", "Bremen"), ("Kiel", "Schleswig-Holstein"),
+    ],
+    address_format: "{street} {number}, {postcode} {city}",
+    postcode: "#####",
+    phone: "+49 30 ########",
+    country: "Deutschland",
+};
+
+const FR_FR: LocaleData = LocaleData {
+    first_names: &[
+        "Louis", "Emma", "Gabriel", "Jade", "Léo", "Louise", "Raphaël", "Alice", "Arthur", "Chloé", "Hugo",
+        "Léa", "Jules", "Manon", "Lucas", "Inès", "Adam", "Camille", "Théo", "Zoé",
+    ],
+    last_names: &[
+        "Martin", "Bernard", "Dubois", "Thomas", "Robert", "Richard", "Petit", "Durand", "Leroy", "Moreau",
+        "Simon", "Laurent", "Lefèvre", "Michel", "Garcia", "David", "Bertrand", "Roux", "Fournier", "Girard",
+    ],
+    streets: &[
+        "rue de la Paix", "rue Victor Hugo", "avenue Jean Jaurès", "rue de la République", "boulevard Pasteur",
+        "rue des Écoles", "place de l'Église", "rue du Moulin", "avenue de la Gare", "rue Nationale",
+    ],
+    cities: &[
+        ("Paris", "Île-de-France"), ("Lyon", "Auvergne-Rhône-Alpes"), ("Nantes", "Pays de la Loire"),
+        ("Lille", "Hauts-de-France"), ("Bordeaux", "Nouvelle-Aquitaine"), ("Toulouse", "Occitanie"),
+    ],
+    address_format: "{number} {street}, {postcode} {city}",
+    postcode: "#####",
+    // 01 99 00 is reserved for fiction by ARCEP
+    phone: "01 99 00 ## ##",
+    country: "France",
+};
+
+const ES_ES: LocaleData = LocaleData {
+    first_names: &[
+        "Hugo", "Lucía", "Martín", "Sofía", "Pablo", "María", "Mateo", "Martina", "Lucas", "Paula", "Daniel",
+        "Julia", "Alejandro", "Valeria", "Javier", "Carmen", "Álvaro", "Elena", "Sergio", "Nuria",
+    ],
+    last_names: &[
+        "García", "Rodríguez", "González", "Fernández", "López", "Martínez", "Sánchez", "Pérez", "Gómez",
+        "Martín", "Jiménez", "Ruiz", "Hernández", "Díaz", "Moreno", "Muñoz", "Álvarez", "Romero", "Navarro",
+    ],
+    streets: &[
+        "Calle Mayor", "Calle Real", "Avenida de la Constitución", "Calle de la Iglesia", "Plaza de España",
+        "Calle del Sol", "Paseo de la Castellana", "Calle Nueva", "Avenida de Andalucía", "Calle Alcalá",
+    ],
+    cities: &[
+        ("Madrid", "Madrid"), ("Barcelona", "Barcelona"), ("Valencia", "Valencia"), ("Sevilla", "Sevilla"),
+        ("Zaragoza", "Zaragoza"), ("Bilbao", "Vizcaya"), ("Málaga", "Málaga"), ("Granada", "Granada"),
+    ],
+    address_format: "{street}, {number}, {postcode} {city}",
+    postcode: "#####",
+    phone: "+34 6## ### ###",
+    country: "España",
+};
+
+const WORDS: &[&str] = &[
+    "alpha", "bridge", "canvas", "delta", "ember", "forest", "garden", "harbor", "island", "jungle", "kettle",
+    "lantern", "meadow", "nectar", "orbit", "pepper", "quartz", "river", "saddle", "timber", "umbrella",
+    "velvet", "willow", "yonder", "zephyr", "amber", "basil", "copper", "dune", "falcon", "granite", "hazel",
+];
+
+/// (brand prefix, length): the card processors' documented test number ranges
+const CARD_PREFIXES: &[(&str, usize)] = &[("424242", 16), ("400005", 16), ("555555", 16), ("222300", 16), ("378282", 15), ("601111", 16)];
+
+impl Locale {
+    fn data(self) -> &'static LocaleData {
+        match self {
+            Self::EnUs => &EN_US,
+            Self::EnGb => &EN_GB,
+            Self::DeDe => &DE_DE,
+            Self::FrFr => &FR_FR,
+            Self::EsEs => &ES_ES,
+        }
+    }
+}
+
+struct Faker {
+    rng: StdRng,
+    locale: &'static LocaleData,
+    date_from: NaiveDate,
+    date_to: NaiveDate,
+}
+
+fn parse_date(value: Option<&str>, default: NaiveDate) -> Result<NaiveDate, AppError> {
+    match value {
+        Some(text) => NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+            .map_err(|_| AppError::invalid_input(format!("Invalid date \"{}\": expected YYYY-MM-DD", text))),
+        None => Ok(default),
+    }
+}
+
+/// Names without accents for email addresses and usernames
+fn ascii_fold(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            'ä' | 'Ä' => out.push_str("ae"),
+            'ö' | 'Ö' => out.push_str("oe"),
+            'ü' | 'Ü' => out.push_str("ue"),
+            'ß' => out.push_str("ss"),
+            'á' | 'à' | 'â' | 'Á' => out.push('a'),
+            'é' | 'è' | 'ê' | 'ë' | 'É' => out.push('e'),
+            'í' | 'ï' | 'ı' | 'Í' => out.push('i'),
+            'ó' | 'ô' | 'Ó' => out.push('o'),
+            'ú' | 'û' | 'Ú' => out.push('u'),
+            'ñ' => out.push('n'),
+            'ç' => out.push('c'),
+            c if c.is_ascii_alphanumeric() => out.push(c.to_ascii_lowercase()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Luhn check digit for `digits`
+fn luhn_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            let d = d as u32;
+            if i % 2 == 0 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+impl Faker {
+    fn new(options: &FakeDataOptions) -> Result<Self, AppError> {
+        let date_from = parse_date(options.date_from.as_deref(), NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or_default())?;
+        let date_to = parse_date(options.date_to.as_deref(), NaiveDate::from_ymd_opt(2029, 12, 31).unwrap_or_default())?;
+        if date_from > date_to {
+            return Err(AppError::invalid_input("date_from is after date_to"));
+        }
+        Ok(Self {
+            rng: match options.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            },
+            locale: options.locale.data(),
+            date_from,
+            date_to,
+        })
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.rng.gen_range(0..items.len())]
+    }
+
+    fn pattern(&mut self, pattern: &str) -> String {
+        pattern
+            .chars()
+            .map(|c| match c {
+                '#' => char::from(b'0' + self.rng.gen_range(0..10)),
+                '?' => char::from(b'A' + self.rng.gen_range(0..26)),
+                other => other,
+            })
+            .collect()
+    }
+
+    fn address(&mut self) -> String {
+        let (city, region) = self.pick(self.locale.cities);
+        self.locale
+            .address_format
+            .replace("{number}", &self.rng.gen_range(1..=999).to_string())
+            .replace("{street}", self.pick(self.locale.streets))
+            .replace("{city}", city)
+            .replace("{region}", region)
+            .replace("{postcode}", &self.pattern(self.locale.postcode))
+    }
+
+    fn email(&mut self) -> String {
+        let first = ascii_fold(self.pick(self.locale.first_names));
+        let last = ascii_fold(self.pick(self.locale.last_names));
+        let domain = self.pick(&["example.com", "example.net", "example.org"]);
+        match self.rng.gen_range(0..3) {
+            0 => format!("{}.{}@{}", first, last, domain),
+            1 => format!("{}{}@{}", &first[..1], last, domain),
+            _ => format!("{}.{}{}@{}", first, last, self.rng.gen_range(1..100), domain),
+        }
+    }
+
+    fn ipv4(&mut self) -> String {
+        // Skip 0/8, 10/8, 127/8 and multicast and above
+        let first = loop {
+            let octet = self.rng.gen_range(1..224u8);
+            if octet != 10 && octet != 127 {
+                break octet;
+            }
+        };
+        format!("{}.{}.{}.{}", first, self.rng.gen::<u8>(), self.rng.gen::<u8>(), self.rng.gen_range(1..255u8))
+    }
+
+    fn ipv6(&mut self) -> String {
+        // Global unicast, 2000::/3
+        let mut groups: [u16; 8] = self.rng.gen();
+        groups[0] = 0x2000 | (groups[0] & 0x1fff);
+        std::net::Ipv6Addr::from(groups).to_string()
+    }
+
+    fn credit_card(&mut self) -> String {
+        let (prefix, length) = self.pick(CARD_PREFIXES);
+        let mut digits: Vec<u8> = prefix.bytes().map(|b| b - b'0').collect();
+        while digits.len() < length - 1 {
+            digits.push(self.rng.gen_range(0..10));
+        }
+        digits.push(luhn_check_digit(&digits));
+        // Amex groups 4-6-5, the rest 4-4-4-4
+        let groups: &[usize] = if length == 15 { &[4, 6, 5] } else { &[4, 4, 4, 4] };
+        let mut out = String::new();
+        let mut start = 0;
+        for size in groups {
+            if start > 0 {
+                out.push(' ');
+            }
+            out.extend(digits[start..start + size].iter().map(|d| char::from(b'0' + d)));
+            start += size;
+        }
+        out
+    }
+
+    fn date(&mut self) -> String {
+        let days = (self.date_to - self.date_from).num_days();
+        (self.date_from + Duration::days(self.rng.gen_range(0..=days))).format("%Y-%m-%d").to_string()
+    }
+
+    fn sentence(&mut self) -> String {
+        let count = self.rng.gen_range(4..10);
+        let words: Vec<&str> = (0..count).map(|_| self.pick(WORDS)).collect();
+        let sentence = words.join(" ");
+        format!("{}{}.", sentence[..1].to_uppercase(), &sentence[1..])
+    }
+
+    /// Bounds from "int:18-90" / "float:0-1"
+    fn range(spec: Option<&str>, default: (f64, f64)) -> Result<(f64, f64), AppError> {
+        let Some(spec) = spec else { return Ok(default) };
+        let invalid = || AppError::invalid_input(format!("Invalid range \"{}\": expected min-max", spec));
+        // Split at the separating '-', not a leading minus sign
+        let split = spec.char_indices().skip(1).find(|&(_, c)| c == '-').map(|(i, _)| i).ok_or_else(invalid)?;
+        let min: f64 = spec[..split].trim().parse().map_err(|_| invalid())?;
+        let max: f64 = spec[split + 1..].trim().parse().map_err(|_| invalid())?;
+        if min > max {
+            return Err(invalid());
+        }
+        Ok((min, max))
+    }
+
+    /// One value for a generator name such as "email" or "int:1-10"
+    fn value(&mut self, generator: &str) -> Result<Value, AppError> {
+        let (name, args) = match generator.split_once(':') {
+            Some((name, args)) => (name.trim(), Some(args)),
+            None => (generator.trim(), None),
+        };
+        let value = match name {
+            "first_name" => Value::from(self.pick(self.locale.first_names)),
+            "last_name" => Value::from(self.pick(self.locale.last_names)),
+            "name" => {
+                let first = self.pick(self.locale.first_names);
+                Value::from(format!("{} {}", first, self.pick(self.locale.last_names)))
+            }
+            "email" => Value::from(self.email()),
+            "username" => {
+                let first = ascii_fold(self.pick(self.locale.first_names));
+                Value::from(format!("{}{}", first, self.rng.gen_range(1..1000)))
+            }
+            "phone" => Value::from(self.pattern(self.locale.phone)),
+            "address" => Value::from(self.address()),
+            "city" => Value::from(self.pick(self.locale.cities).0),
+            "postcode" => Value::from(self.pattern(self.locale.postcode)),
+            "country" => Value::from(self.locale.country),
+            "ipv4" => Value::from(self.ipv4()),
+            "ipv6" => Value::from(self.ipv6()),
+            "credit_card" => Value::from(self.credit_card()),
+            "date" => Value::from(self.date()),
+            "uuid" => Value::from(uuid::Builder::from_random_bytes(self.rng.gen()).into_uuid().to_string()),
+            "int" => {
+                let (min, max) = Self::range(args, (0.0, 1000.0))?;
+                Value::from(self.rng.gen_range(min as i64..=max as i64))
+            }
+            "float" => {
+                let (min, max) = Self::range(args, (0.0, 1.0))?;
+                let value = if min == max { min } else { self.rng.gen_range(min..max) };
+                Value::from((value * 100.0).round() / 100.0)
+            }
+            "bool" => Value::from(self.rng.gen::<bool>()),
+            "word" => Value::from(self.pick(WORDS)),
+            "sentence" => Value::from(self.sentence()),
+            _ => return Err(AppError::invalid_input(format!("Unknown generator \"{}\"", generator))),
+        };
+        Ok(value)
+    }
+
+    /// A value shaped like `schema`: strings name generators, objects recurse, `[spec]`
+    /// is one to three items and `[spec, n]` exactly n; anything else is copied as is
+    fn shaped_like(&mut self, schema: &Value) -> Result<Value, AppError> {
+        match schema {
+            Value::String(generator) => self.value(generator),
+            Value::Object(fields) => fields
+                .iter()
+                .map(|(key, spec)| Ok((key.clone(), self.shaped_like(spec)?)))
+                .collect::<Result<Map<_, _>, AppError>>()
+                .map(Value::Object),
+            Value::Array(items) if !items.is_empty() => {
+                let count = match items.get(1).and_then(Value::as_u64) {
+                    Some(count) => count.min(100) as usize,
+                    None => self.rng.gen_range(1..=3),
+                };
+                (0..count).map(|_| self.shaped_like(&items[0])).collect::<Result<Vec<_>, _>>().map(Value::Array)
+            }
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+pub fn is_fake_generator(generator: &str) -> bool {
+    let name = generator.split(':').next().unwrap_or_default();
+    FAKE_GENERATORS.contains(&name)
+}
+
+/// `count` values, one per line; "json" gives a pretty-printed array of schema objects
+pub fn generate(generator: &str, count: usize, options: &FakeDataOptions) -> Result<String, AppError> {
+    let mut faker = Faker::new(options)?;
+    if generator == "json" {
+        let schema = options
+            .schema
+            .as_ref()
+            .ok_or_else(|| AppError::invalid_input("The json generator needs a schema"))?;
+        let items = (0..count).map(|_| faker.shaped_like(schema)).collect::<Result<Vec<_>, _>>()?;
+        return serde_json::to_string_pretty(&items).map_err(|e| AppError::internal(e.to_string()));
+    }
+    let values = (0..count)
+        .map(|_| faker.value(generator).map(|value| match value {
+            Value::String(s) => s,
+            other => other.to_string(),
+        }))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(values.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_output_is_reproducible() {
+        let options = FakeDataOptions {
+            locale: Locale::DeDe,
+            seed: Some(42),
+            schema: Some(serde_json::json!({"id": "uuid", "name": "name", "age": "int:18-90", "tags": ["word", 2]})),
+            ..Default::default()
+        };
+        let first = generate("json", 3, &options).unwrap();
+        assert_eq!(first, generate("json", 3, &options).unwrap());
+        let items: Vec<Value> = serde_json::from_str(&first).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0]["tags"].as_array().unwrap().len(), 2);
+        let age = items[0]["age"].as_i64().unwrap();
+        assert!((18..=90).contains(&age));
+
+        assert!(generate("email", 5, &options).unwrap().lines().all(|email| email.is_ascii() && email.contains("@example.")));
+    }
+
+    #[test]
+    fn test_cards_and_dates() {
+        let options = FakeDataOptions {
+            seed: Some(7),
+            date_from: Some("2024-02-28".to_string()),
+            date_to: Some("2024-03-01".to_string()),
+            ..Default::default()
+        };
+        for card in generate("credit_card", 20, &options).unwrap().lines() {
+            let digits: Vec<u8> = card.bytes().filter(u8::is_ascii_digit).map(|b| b - b'0').collect();
+            let (body, check) = digits.split_at(digits.len() - 1);
+            assert_eq!(luhn_check_digit(body), check[0], "{}", card);
+        }
+        for date in generate("date", 20, &options).unwrap().lines() {
+            assert!(["2024-02-28", "2024-02-29", "2024-03-01"].contains(&date));
+        }
+        assert_eq!(luhn_check_digit(&[4, 2, 4, 2, 4, 2, 4, 2, 4, 2, 4, 2, 4, 2, 4]), 2);
+    }
+}
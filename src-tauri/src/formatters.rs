@@ -836,3 +836,269 @@ pub fn minify_xml_code(text: String) -> Result<String, String> {
 
     Ok(result)
 }
+
+// GraphQL formatting. Note the parser drops comments, like most GraphQL printers.
+pub fn format_graphql_code(text: String) -> Result<String, String> {
+    match graphql_parser::parse_query::<String>(&text) {
+        Ok(document) => Ok(document.to_string()),
+        // SDL (type definitions) isn't a valid executable document; try it as a schema
+        Err(query_error) => graphql_parser::parse_schema::<String>(&text)
+            .map(|document| document.to_string())
+            .map_err(|_| format!("GraphQL parse error: {}", query_error)),
+    }
+}
+
+// SQL bound parameters
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlParamKind {
+    /// `?` or `%s`, bound in order
+    Positional,
+    /// `$1`, `$2`, ...
+    Numbered,
+    /// `:name`, `@name` or `%(name)s`
+    Named,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SqlParam {
+    pub placeholder: String,
+    pub kind: SqlParamKind,
+    /// Parameter name (named) or 1-based index (positional and numbered)
+    pub key: String,
+    /// Character offset of the placeholder in the query
+    pub offset: usize,
+}
+
+/// Find parameter placeholders, skipping string literals, quoted identifiers,
+/// comments and Postgres `::` casts / dollar-quoted strings.
+/// Returns the placeholders with their character ranges.
+fn scan_sql_params(query: &str) -> Vec<(usize, usize, SqlParam)> {
+    let chars: Vec<char> = query.chars().collect();
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut params = Vec::new();
+    let mut positional = 0;
+    let mut i = 0;
+
+    let skip_until = |from: usize, end: &[char]| -> usize {
+        let mut j = from;
+        while j < chars.len() {
+            if chars[j..].starts_with(end) {
+                return j + end.len();
+            }
+            j += 1;
+        }
+        chars.len()
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '\'' | '"' | '`' => {
+                // Quotes are escaped by doubling, which the scan handles as two literals
+                i = skip_until(i + 1, &[c]);
+                continue;
+            }
+            '-' if next == Some('-') => {
+                i = skip_until(i + 2, &['\n']);
+                continue;
+            }
+            '/' if next == Some('*') => {
+                i = skip_until(i + 2, &['*', '/']);
+                continue;
+            }
+            '?' => {
+                positional += 1;
+                params.push((i, i + 1, SqlParam {
+                    placeholder: "?".to_string(),
+                    kind: SqlParamKind::Positional,
+                    key: positional.to_string(),
+                    offset: i,
+                }));
+            }
+            '$' if next.map(|n| n.is_ascii_digit()).unwrap_or(false) => {
+                let end = (i + 1..chars.len()).find(|&j| !chars[j].is_ascii_digit()).unwrap_or(chars.len());
+                let digits: String = chars[i + 1..end].iter().collect();
+                params.push((i, end, SqlParam {
+                    placeholder: format!("${}", digits),
+                    kind: SqlParamKind::Numbered,
+                    key: digits,
+                    offset: i,
+                }));
+                i = end;
+                continue;
+            }
+            '$' => {
+                // Dollar-quoted string: $$...$$ or $tag$...$tag$
+                let tag_end = (i + 1..chars.len()).find(|&j| !is_ident(chars[j])).unwrap_or(chars.len());
+                if chars.get(tag_end) == Some(&'$') {
+                    let tag: Vec<char> = chars[i..=tag_end].to_vec();
+                    i = skip_until(tag_end + 1, &tag);
+                    continue;
+                }
+            }
+            ':' if next == Some(':') => {
+                i += 2; // Postgres cast, e.g. id::text
+                continue;
+            }
+            ':' | '@' if next.map(|n| n.is_alphabetic() || n == '_').unwrap_or(false)
+                && (i == 0 || !is_ident(chars[i - 1]) && chars[i - 1] != c) =>
+            {
+                let end = (i + 1..chars.len()).find(|&j| !is_ident(chars[j])).unwrap_or(chars.len());
+                let name: String = chars[i + 1..end].iter().collect();
+                params.push((i, end, SqlParam {
+                    placeholder: format!("{}{}", c, name),
+                    kind: SqlParamKind::Named,
+                    key: name,
+                    offset: i,
+                }));
+                i = end;
+                continue;
+            }
+            '@' if next == Some('@') => {
+                i += 2; // System variable, e.g. @@version
+                continue;
+            }
+            '%' if next == Some('s') => {
+                positional += 1;
+                params.push((i, i + 2, SqlParam {
+                    placeholder: "%s".to_string(),
+                    kind: SqlParamKind::Positional,
+                    key: positional.to_string(),
+                    offset: i,
+                }));
+                i += 2;
+                continue;
+            }
+            '%' if next == Some('(') => {
+                if let Some(close) = (i + 2..chars.len()).find(|&j| chars[j] == ')') {
+                    if chars.get(close + 1) == Some(&'s') {
+                        let name: String = chars[i + 2..close].iter().collect();
+                        params.push((i, close + 2, SqlParam {
+                            placeholder: format!("%({})s", name),
+                            kind: SqlParamKind::Named,
+                            key: name,
+                            offset: i,
+                        }));
+                        i = close + 2;
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    params
+}
+
+pub fn extract_sql_params(query: &str) -> Vec<SqlParam> {
+    scan_sql_params(query).into_iter().map(|(_, _, param)| param).collect()
+}
+
+fn sql_literal(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "NULL".to_string(),
+        serde_json::Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        // Arrays and objects are most likely JSON columns
+        other => format!("'{}'", other.to_string().replace('\'', "''")),
+    }
+}
+
+/// Substitute bound parameters into a query, e.g. to re-run a query copied from logs.
+/// `params_json` is an array (for `?`, `%s` and `$n`) or an object keyed by name.
+/// Named placeholders can also take an array, assigned in order of first appearance.
+pub fn inline_sql_params(query: &str, params_json: &str) -> Result<String, String> {
+    let params: serde_json::Value = serde_json::from_str(params_json)
+        .map_err(|e| format!("Invalid parameters JSON: {}", e))?;
+    let found = scan_sql_params(query);
+
+    let mut named_order: Vec<String> = Vec::new();
+    for (_, _, param) in &found {
+        if param.kind == SqlParamKind::Named && !named_order.contains(&param.key) {
+            named_order.push(param.key.clone());
+        }
+    }
+
+    let lookup = |param: &SqlParam| -> Option<&serde_json::Value> {
+        match (&params, &param.kind) {
+            (serde_json::Value::Array(values), SqlParamKind::Named) => {
+                let index = named_order.iter().position(|n| *n == param.key)?;
+                values.get(index)
+            }
+            (serde_json::Value::Array(values), _) => {
+                let index: usize = param.key.parse().ok()?;
+                values.get(index.checked_sub(1)?)
+            }
+            (serde_json::Value::Object(map), _) => map.get(&param.key),
+            _ => None,
+        }
+    };
+
+    let chars: Vec<char> = query.chars().collect();
+    let mut result = String::with_capacity(query.len());
+    let mut last = 0;
+    for (start, end, param) in &found {
+        let value = lookup(param)
+            .ok_or_else(|| format!("No value for parameter {}", param.placeholder))?;
+        result.extend(&chars[last..*start]);
+        result.push_str(&sql_literal(value));
+        last = *end;
+    }
+    result.extend(&chars[last..]);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_sql_params_skips_literals_and_casts() {
+        let params = extract_sql_params(
+            "SELECT id::text, ':nope', \"?col\" FROM t -- where x = ?\nWHERE a = :a AND b = $2 AND c = ? AND d = @@version /* :c */",
+        );
+        let placeholders: Vec<&str> = params.iter().map(|p| p.placeholder.as_str()).collect();
+        assert_eq!(placeholders, vec![":a", "$2", "?"]);
+        assert_eq!(params[2].key, "1");
+    }
+
+    #[test]
+    fn test_inline_positional_and_numbered() {
+        assert_eq!(
+            inline_sql_params("SELECT * FROM users WHERE name = ? AND age > ?", r#"["O'Brien", 30]"#).unwrap(),
+            "SELECT * FROM users WHERE name = 'O''Brien' AND age > 30"
+        );
+        assert_eq!(
+            inline_sql_params("UPDATE t SET a = $2 WHERE id = $1", "[7, null]").unwrap(),
+            "UPDATE t SET a = NULL WHERE id = 7"
+        );
+        assert!(inline_sql_params("SELECT ?", "[]").is_err());
+    }
+
+    #[test]
+    fn test_inline_named() {
+        assert_eq!(
+            inline_sql_params("SELECT * FROM t WHERE a = :a OR b = %(b)s", r#"{"a": true, "b": "x"}"#).unwrap(),
+            "SELECT * FROM t WHERE a = TRUE OR b = 'x'"
+        );
+        assert_eq!(
+            inline_sql_params("SELECT $tag$ :skip $tag$, @id", "[5]").unwrap(),
+            "SELECT $tag$ :skip $tag$, 5"
+        );
+    }
+
+    #[test]
+    fn test_format_graphql() {
+        let formatted = format_graphql_code("query { user(id: 1) { name } }".to_string()).unwrap();
+        assert_eq!(formatted, "query {\n  user(id: 1) {\n    name\n  }\n}\n");
+        assert!(format_graphql_code("type User { id: ID! }".to_string()).is_ok());
+        assert!(format_graphql_code("query {".to_string()).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+// The frontmost application: name, bundle id and window title. show_window records the
+// app Wingman was opened over (AppState::previous_app); hide_and_paste reactivates it and
+// paste profiles match against it. A watcher also notes the frontmost app each time the
+// pasteboard change count moves, which is the app the copy came from; new history
+// entries are stamped with that copy source, or with the previous app when the
+// clipboard changed without one being seen. Only tracked on macOS.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+#[cfg(target_os = "macos")]
+use std::time::Duration;
+
+use crate::AppState;
+
+/// How often the copy watcher checks the pasteboard change count
+#[cfg(target_os = "macos")]
+const COPY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Wingman's own bundle id; copies made from its windows have no outside source
+#[cfg(target_os = "macos")]
+const OWN_BUNDLE_ID: &str = "com.wingman.app";
+
+/// The app frontmost when the pasteboard reached this change count
+static COPY_SOURCE: Mutex<Option<(FrontmostApp, i64)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrontmostApp {
+    pub name: String,
+    /// "com.tinyspeck.slackmacgap"
+    pub bundle_id: Option<String>,
+    /// Needs Accessibility permission; None without it
+    pub window_title: Option<String>,
+}
+
+/// Ask System Events for the frontmost process. One osascript run (~50ms), so callers
+/// do this off the main thread.
+#[cfg(target_os = "macos")]
+pub fn frontmost_app() -> Option<FrontmostApp> {
+    const SCRIPT: &str = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set appName to name of frontApp
+    set bundleId to ""
+    try
+        set bundleId to bundle identifier of frontApp
+    end try
+    set windowTitle to ""
+    try
+        set windowTitle to name of front window of frontApp
+    end try
+end tell
+return appName & linefeed & bundleId & linefeed & windowTitle"#;
+
+    let output = std::process::Command::new("osascript").arg("-e").arg(SCRIPT).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.trim_end_matches('\n').splitn(3, '\n');
+    let non_empty = |value: Option<&str>| value.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    Some(FrontmostApp {
+        name: non_empty(lines.next())?,
+        bundle_id: non_empty(lines.next()),
+        window_title: non_empty(lines.next()),
+    })
+}
+
+/// The app Wingman was last opened over
+#[cfg(target_os = "macos")]
+pub fn previous_app(state: &AppState) -> Option<FrontmostApp> {
+    state.previous_app.lock().ok()?.as_ref().map(|(app, _)| app.clone())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn previous_app(_state: &AppState) -> Option<FrontmostApp> {
+    None
+}
+
+/// Note the frontmost app whenever the pasteboard changes. The change count is cheap to
+/// read; osascript only runs on a change.
+#[cfg(target_os = "macos")]
+pub fn start_copy_watch() {
+    std::thread::spawn(|| {
+        let mut last = crate::temp_files::change_count();
+        loop {
+            std::thread::sleep(COPY_POLL_INTERVAL);
+            let current = crate::temp_files::change_count();
+            if current == last {
+                continue;
+            }
+            last = current;
+            let Some(count) = current else {
+                continue;
+            };
+            let source = frontmost_app().filter(|app| app.bundle_id.as_deref() != Some(OWN_BUNDLE_ID));
+            let mut recorded = COPY_SOURCE.lock().unwrap_or_else(|e| e.into_inner());
+            *recorded = source.map(|app| (app, count));
+        }
+    });
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn start_copy_watch() {}
+
+/// The app the current clipboard content was copied from, if the watcher saw it
+pub fn copy_source() -> Option<FrontmostApp> {
+    let recorded = COPY_SOURCE.lock().ok()?;
+    let (app, count) = recorded.as_ref()?;
+    (crate::temp_files::change_count() == Some(*count)).then(|| app.clone())
+}
+
+/// Where a new history entry came from: the copy source, else the app Wingman was
+/// opened over
+pub fn entry_source(state: &AppState) -> Option<FrontmostApp> {
+    copy_source().or_else(|| previous_app(state))
+}
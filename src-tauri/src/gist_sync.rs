@@ -0,0 +1,389 @@
+// Two-way sync between snippets and the gists they're linked to. For each linked snippet
+// the local updated_at and the gist's updated_at are compared against github_synced_at:
+// whichever side changed since the last sync wins, and when both did the pair is handed
+// back to the UI as a conflict to resolve (keep local, keep remote, or duplicate).
+//
+// Network work happens on a snapshot of snippets.json; results are written back only to
+// snippets that weren't edited in the meantime, so a sync never clobbers a fresh edit.
+// Pulled content replaces the local copy as a new snippet version.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+use crate::command_middleware::track;
+use crate::error::AppError;
+use crate::github::{self, GistDetailResponse, GitHubError};
+use crate::notifications::{self, NotificationCategory};
+use crate::storage::{load_snippets, save_snippets, Snippet};
+use crate::{snippet_versions, AppState};
+
+/// set_snippet_github_info stamps updated_at a moment after github_synced_at, and the
+/// GitHub clock isn't ours, so differences below this don't count as changes
+const CLOCK_SLACK_SECS: i64 = 2;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GistSyncConflict {
+    pub snippet_id: String,
+    pub snippet_name: String,
+    pub gist_id: String,
+    pub local_content: String,
+    pub remote_content: String,
+    pub local_updated_at: String,
+    pub remote_updated_at: String,
+    pub synced_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GistSyncFailure {
+    pub snippet_id: String,
+    pub snippet_name: String,
+    pub gist_id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GistSyncReport {
+    /// Snippet ids whose local edits were uploaded
+    pub pushed: Vec<String>,
+    /// Snippet ids that took the gist's content
+    pub pulled: Vec<String>,
+    pub unchanged: usize,
+    pub conflicts: Vec<GistSyncConflict>,
+    pub failures: Vec<GistSyncFailure>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Overwrite the gist with the snippet
+    KeepLocal,
+    /// Overwrite the snippet with the gist
+    KeepRemote,
+    /// Save the local version as a new, unlinked snippet and take the gist's content
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Plan {
+    InSync,
+    Push,
+    Pull,
+    Conflict,
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn changed_since(updated_at: &str, synced_at: DateTime<Utc>) -> bool {
+    parse_time(updated_at)
+        .map_or(true, |updated| updated - synced_at > Duration::seconds(CLOCK_SLACK_SECS))
+}
+
+/// Decide what to do with one linked snippet. Gists are stored trimmed, so contents
+/// are compared the same way.
+fn plan(local: &Snippet, remote_content: &str, remote_updated_at: &str) -> Plan {
+    if local.content.trim() == remote_content.trim() {
+        return Plan::InSync;
+    }
+    let Some(synced_at) = local.github_synced_at.as_deref().and_then(parse_time) else {
+        return Plan::Conflict;
+    };
+    match (
+        changed_since(&local.updated_at, synced_at),
+        changed_since(remote_updated_at, synced_at),
+    ) {
+        (true, false) => Plan::Push,
+        (false, true) => Plan::Pull,
+        // Both sides moved, or the contents differ without either admitting to a change
+        _ => Plan::Conflict,
+    }
+}
+
+/// The gist file backing a snippet: the recorded filename, else the gist's only file
+fn remote_file(snippet: &Snippet, gist: &GistDetailResponse) -> (String, String) {
+    let file = snippet
+        .github_gist_filename
+        .as_ref()
+        .and_then(|name| gist.files.get_key_value(name))
+        .or_else(|| gist.files.iter().next());
+    match file {
+        Some((key, file)) => (
+            file.filename.clone().unwrap_or_else(|| key.clone()),
+            file.content.clone().unwrap_or_default(),
+        ),
+        None => (snippet.github_gist_filename.clone().unwrap_or_default(), String::new()),
+    }
+}
+
+/// Errors that would fail every other snippet the same way
+fn is_fatal(error: &GitHubError) -> bool {
+    matches!(
+        error,
+        GitHubError::NotAuthenticated
+            | GitHubError::TokenRevoked
+            | GitHubError::RateLimit
+            | GitHubError::Forbidden(_)
+            | GitHubError::TokenLoad(_)
+            | GitHubError::HttpClient(_)
+    )
+}
+
+/// What a finished sync writes back to one snippet
+struct Outcome {
+    snippet_id: String,
+    /// updated_at when the snapshot was taken; skip the snippet if it has moved on
+    seen_updated_at: String,
+    content: Option<String>,
+    gist_url: String,
+    gist_filename: String,
+}
+
+fn apply(app: &AppHandle, outcomes: Vec<Outcome>) -> Result<(), AppError> {
+    if outcomes.is_empty() {
+        return Ok(());
+    }
+    let mut data = load_snippets()?;
+    let now = Utc::now().to_rfc3339();
+    let by_id: HashMap<&str, &Outcome> = outcomes.iter().map(|o| (o.snippet_id.as_str(), o)).collect();
+    for snippet in data.snippets.iter_mut() {
+        let Some(outcome) = by_id.get(snippet.id.as_str()) else {
+            continue;
+        };
+        if snippet.updated_at != outcome.seen_updated_at {
+            continue;
+        }
+        if let Some(content) = outcome.content.as_ref().filter(|_| snippet.source.is_none()) {
+            snippet.content = content.clone();
+            snippet.updated_at = now.clone();
+            snippet.version += 1;
+        }
+        snippet.github_gist_url = Some(outcome.gist_url.clone());
+        snippet.github_gist_filename = Some(outcome.gist_filename.clone());
+        snippet.github_synced_at = Some(now.clone());
+    }
+    let state = app.state::<AppState>();
+    snippet_versions::save_snippets_versioned(&*state.db.lock()?, &mut data)
+}
+
+async fn push(snippet: &Snippet, gist_id: &str, filename: &str) -> Result<Outcome, GitHubError> {
+    let result = github::update_gist_internal(gist_id, &snippet.content, Some(filename), None).await?;
+    Ok(Outcome {
+        snippet_id: snippet.id.clone(),
+        seen_updated_at: snippet.updated_at.clone(),
+        content: None,
+        gist_url: result.html_url,
+        gist_filename: filename.to_string(),
+    })
+}
+
+fn pull(snippet: &Snippet, gist: &GistDetailResponse, filename: String, content: String) -> Outcome {
+    Outcome {
+        snippet_id: snippet.id.clone(),
+        seen_updated_at: snippet.updated_at.clone(),
+        content: Some(content),
+        gist_url: gist.html_url.clone(),
+        gist_filename: filename,
+    }
+}
+
+async fn sync_one(
+    snippet: &Snippet,
+    gist_id: &str,
+    report: &mut GistSyncReport,
+) -> Result<Option<Outcome>, GitHubError> {
+    let gist = github::get_gist_detail_internal(gist_id).await?;
+    let (filename, remote_content) = remote_file(snippet, &gist);
+
+    // A snippet linked to a URL takes its content only from there; the gist follows it
+    let plan = match plan(snippet, &remote_content, &gist.updated_at) {
+        Plan::Pull | Plan::Conflict if snippet.source.is_some() => Plan::Push,
+        plan => plan,
+    };
+    Ok(match plan {
+        Plan::InSync => {
+            report.unchanged += 1;
+            // Refresh github_synced_at so the next comparison starts from here
+            Some(Outcome {
+                content: None,
+                ..pull(snippet, &gist, filename, remote_content)
+            })
+        }
+        Plan::Push => {
+            report.pushed.push(snippet.id.clone());
+            Some(push(snippet, gist_id, &filename).await?)
+        }
+        Plan::Pull => {
+            report.pulled.push(snippet.id.clone());
+            Some(pull(snippet, &gist, filename, remote_content))
+        }
+        Plan::Conflict => {
+            report.conflicts.push(GistSyncConflict {
+                snippet_id: snippet.id.clone(),
+                snippet_name: snippet.name.clone(),
+                gist_id: gist_id.to_string(),
+                local_content: snippet.content.clone(),
+                remote_content,
+                local_updated_at: snippet.updated_at.clone(),
+                remote_updated_at: gist.updated_at,
+                synced_at: snippet.github_synced_at.clone(),
+            });
+            None
+        }
+    })
+}
+
+async fn sync_all(app: &AppHandle) -> Result<GistSyncReport, AppError> {
+    let snippets = load_snippets()?.snippets;
+    let mut report = GistSyncReport::default();
+    let mut outcomes = Vec::new();
+
+    for snippet in &snippets {
+        let Some(gist_id) = snippet.github_gist_id.as_deref() else {
+            continue;
+        };
+        match sync_one(snippet, gist_id, &mut report).await {
+            Ok(outcome) => outcomes.extend(outcome),
+            Err(e) if is_fatal(&e) => {
+                // Keep what already went through before giving up
+                apply(app, outcomes)?;
+                return Err(e.into());
+            }
+            Err(e) => report.failures.push(GistSyncFailure {
+                snippet_id: snippet.id.clone(),
+                snippet_name: snippet.name.clone(),
+                gist_id: gist_id.to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    apply(app, outcomes)?;
+    Ok(report)
+}
+
+async fn resolve(app: &AppHandle, snippet_id: &str, resolution: ConflictResolution) -> Result<Snippet, AppError> {
+    let snippet = load_snippets()?
+        .snippets
+        .into_iter()
+        .find(|s| s.id == snippet_id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", snippet_id)))?;
+    let gist_id = snippet
+        .github_gist_id
+        .clone()
+        .ok_or_else(|| AppError::invalid_input("Snippet is not linked to a gist"))?;
+
+    if resolution != ConflictResolution::KeepLocal {
+        if let Some(source) = &snippet.source {
+            return Err(AppError::invalid_input(format!(
+                "\"{}\" is kept in sync with {}; unlink it to take the gist's content",
+                snippet.name, source.url
+            )));
+        }
+    }
+
+    let gist = github::get_gist_detail_internal(&gist_id).await?;
+    let (filename, remote_content) = remote_file(&snippet, &gist);
+
+    let outcome = match resolution {
+        ConflictResolution::KeepLocal => push(&snippet, &gist_id, &filename).await?,
+        ConflictResolution::KeepRemote => pull(&snippet, &gist, filename, remote_content),
+        ConflictResolution::Duplicate => {
+            let mut data = load_snippets()?;
+            let now = Utc::now().to_rfc3339();
+            data.snippets.push(Snippet {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: format!("{} (local copy)", snippet.name),
+                content: snippet.content.clone(),
+                tags: snippet.tags.clone(),
+                created_at: now.clone(),
+                updated_at: now,
+                github_gist_id: None,
+                github_gist_url: None,
+                github_gist_filename: None,
+                github_synced_at: None,
+                github_source: None,
+                favorite: false,
+                kind: snippet.kind,
+                version: 0,
+                source: None,
+            });
+            save_snippets(&data)?;
+            pull(&snippet, &gist, filename, remote_content)
+        }
+    };
+    apply(app, vec![outcome])?;
+
+    load_snippets()?
+        .snippets
+        .into_iter()
+        .find(|s| s.id == snippet_id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", snippet_id)))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Push or pull every gist-linked snippet; conflicts are returned, not resolved
+#[tauri::command]
+pub async fn sync_all_gist_snippets(app: AppHandle) -> Result<GistSyncReport, AppError> {
+    track("sync_all_gist_snippets", async move {
+        let started = Instant::now();
+        let result = sync_all(&app).await;
+        notifications::report(&app, NotificationCategory::GistSync, "Gist sync", started, &result);
+        result
+    })
+    .await
+}
+
+/// Settle one conflict from sync_all_gist_snippets; returns the linked snippet afterwards
+#[tauri::command]
+pub async fn resolve_gist_conflict(
+    app: AppHandle,
+    snippet_id: String,
+    resolution: ConflictResolution,
+) -> Result<Snippet, AppError> {
+    track("resolve_gist_conflict", async move {
+        resolve(&app, &snippet_id, resolution).await
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(content: &str, updated_at: &str, synced_at: Option<&str>) -> Snippet {
+        Snippet {
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: updated_at.to_string(),
+            github_gist_id: Some("g1".to_string()),
+            github_synced_at: synced_at.map(str::to_string),
+            ..Snippet::for_test("s1", content)
+        }
+    }
+
+    #[test]
+    fn test_plan_compares_against_last_sync() {
+        let synced = Some("2024-01-01T10:00:00Z");
+        let before = "2024-01-01T09:00:00Z";
+        let after = "2024-01-01T11:00:00Z";
+
+        // Same content after trimming: nothing to do regardless of timestamps
+        assert_eq!(plan(&snippet("a\n", after, synced), "a", after), Plan::InSync);
+        assert_eq!(plan(&snippet("local", after, synced), "remote", before), Plan::Push);
+        assert_eq!(plan(&snippet("local", before, synced), "remote", after), Plan::Pull);
+        assert_eq!(plan(&snippet("local", after, synced), "remote", after), Plan::Conflict);
+        assert_eq!(plan(&snippet("local", after, None), "remote", before), Plan::Conflict);
+
+        // set_snippet_github_info writes updated_at just after github_synced_at
+        let linked = snippet("local", "2024-01-01T10:00:00.5Z", synced);
+        assert_eq!(plan(&linked, "remote", after), Plan::Pull);
+    }
+}
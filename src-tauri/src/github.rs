@@ -2,13 +2,17 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
-use keyring::Entry;
+
+use crate::command_middleware::track;
+use crate::credentials::{delete_credential, get_credential, store_credential};
+use crate::error::AppError;
+use crate::http_client::{self, HttpClientError};
+use crate::notifications::{self, NotificationCategory};
 
 // GitHub OAuth Client ID (from GitHub App registration)
 // TODO: Replace with your actual Client ID after registering the GitHub App
 const GITHUB_CLIENT_ID: &str = "Iv23liEWBm84xdG4FROh";
-const KEYRING_SERVICE_NAME: &str = "com.wingman.app";
-const KEYRING_GITHUB_ACCOUNT: &str = "github_access_token";
+pub const KEYRING_GITHUB_ACCOUNT: &str = "github_access_token";
 const WINGMAN_GIST_MARKER: &str = "[Wingman]";
 
 // ============================================================================
@@ -19,6 +23,8 @@ const WINGMAN_GIST_MARKER: &str = "[Wingman]";
 pub enum GitHubError {
     #[error("Network error: {0}")]
     Network(#[from] reqwest::Error),
+    #[error("{0}")]
+    HttpClient(#[from] HttpClientError),
 
     #[error("Failed to save token: {0}")]
     TokenSave(String),
@@ -35,6 +41,12 @@ pub enum GitHubError {
     #[error("Rate limit exceeded. Try again later.")]
     RateLimit,
 
+    #[error("Access forbidden. Your GitHub token may lack gist permissions: {0}")]
+    Forbidden(String),
+
+    #[error("GitHub Client ID not configured. Please register a GitHub OAuth App and update the Client ID in github.rs")]
+    NotConfigured,
+
     #[error("Invalid content: {0}")]
     InvalidContent(String),
 
@@ -47,6 +59,10 @@ pub enum GitHubError {
     #[error("Device flow authorization pending")]
     AuthPending,
 
+    /// GitHub wants the device flow polled less often; carries the new interval in seconds
+    #[error("Polling too fast, retry in {0}s")]
+    SlowDown(u64),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -122,36 +138,42 @@ struct GitHubToken {
 // Storage Paths
 // ============================================================================
 
+/// GitHub account and config belong to the active profile
 fn get_app_data_dir() -> Result<PathBuf, GitHubError> {
-    let app_data_dir = dirs::data_dir()
-        .ok_or_else(|| GitHubError::Io(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Could not find app data directory"
-        )))?
-        .join("Wingman");
-
-    // Create directory if it doesn't exist
-    if !app_data_dir.exists() {
-        fs::create_dir_all(&app_data_dir)?;
-    }
+    crate::storage::ensure_profile_data_dir().map_err(|e| {
+        GitHubError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()))
+    })
+}
 
-    Ok(app_data_dir)
+/// Data directory of any profile, whether or not it's active
+fn get_profile_dir(profile_id: &str) -> Result<PathBuf, GitHubError> {
+    let dir = crate::storage::get_app_data_dir().map_err(|e| {
+        GitHubError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()))
+    })?;
+    Ok(match crate::profiles::profile_subdir(profile_id) {
+        Some(subdir) => dir.join(subdir),
+        None => dir,
+    })
 }
 
-fn get_token_path() -> Result<PathBuf, GitHubError> {
-    Ok(get_app_data_dir()?.join("github_token.json"))
+fn get_token_path_for(profile_id: &str) -> Result<PathBuf, GitHubError> {
+    Ok(get_profile_dir(profile_id)?.join("github_token.json"))
 }
 
 fn get_config_path() -> Result<PathBuf, GitHubError> {
     Ok(get_app_data_dir()?.join("github_config.json"))
 }
 
+/// Only the default profile shares files with pre-profile builds
 fn get_legacy_app_data_dir() -> Option<PathBuf> {
-    dirs::config_dir().map(|p| p.join("com.wingman.app"))
+    legacy_dir_for(&crate::profiles::active_profile_id())
 }
 
-fn get_legacy_token_path() -> Option<PathBuf> {
-    get_legacy_app_data_dir().map(|dir| dir.join("github_token.json"))
+fn legacy_dir_for(profile_id: &str) -> Option<PathBuf> {
+    if profile_id != crate::profiles::DEFAULT_PROFILE_ID {
+        return None;
+    }
+    dirs::config_dir().map(|p| p.join("com.wingman.app"))
 }
 
 fn get_legacy_config_path() -> Option<PathBuf> {
@@ -162,23 +184,43 @@ fn get_legacy_config_path() -> Option<PathBuf> {
 // Token Storage
 // ============================================================================
 
-fn get_keyring_entry() -> Result<Entry, GitHubError> {
-    Entry::new(KEYRING_SERVICE_NAME, KEYRING_GITHUB_ACCOUNT)
-        .map_err(|e| GitHubError::TokenSave(format!("Failed to create keyring entry: {}", e)))
+/// Keychain account of the active profile's token, stored through credentials.rs so
+/// it shows up in the credential registry
+pub fn keyring_account() -> String {
+    keyring_account_for(&crate::profiles::active_profile_id())
+}
+
+pub fn keyring_account_for(profile_id: &str) -> String {
+    if profile_id == crate::profiles::DEFAULT_PROFILE_ID {
+        KEYRING_GITHUB_ACCOUNT.to_string()
+    } else {
+        format!("{}:{}", KEYRING_GITHUB_ACCOUNT, profile_id)
+    }
 }
 
-fn save_token_to_file(access_token: &str) -> Result<(), GitHubError> {
+/// The profile a GitHub token account belongs to, or `None` for other accounts
+pub fn profile_of_account(account: &str) -> Option<String> {
+    match account.strip_prefix(KEYRING_GITHUB_ACCOUNT)? {
+        "" => Some(crate::profiles::DEFAULT_PROFILE_ID.to_string()),
+        rest => rest.strip_prefix(':').filter(|id| !id.is_empty()).map(str::to_string),
+    }
+}
+
+fn save_token_to_file(profile_id: &str, access_token: &str) -> Result<(), GitHubError> {
     let token = GitHubToken {
         access_token: access_token.to_string(),
         created_at: chrono::Utc::now().to_rfc3339(),
     };
 
     let json = serde_json::to_string_pretty(&token)?;
-    let primary_path = get_token_path()?;
+    let primary_path = get_token_path_for(profile_id)?;
+    if let Some(dir) = primary_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
     fs::write(&primary_path, &json).map_err(|e| GitHubError::TokenSave(e.to_string()))?;
 
     // Best-effort write to legacy config directory for compatibility with older builds.
-    if let Some(legacy_dir) = get_legacy_app_data_dir() {
+    if let Some(legacy_dir) = legacy_dir_for(profile_id) {
         let _ = fs::create_dir_all(&legacy_dir);
         let _ = fs::write(legacy_dir.join("github_token.json"), &json);
     }
@@ -186,15 +228,18 @@ fn save_token_to_file(access_token: &str) -> Result<(), GitHubError> {
     Ok(())
 }
 
-fn load_token_from_file() -> Result<String, GitHubError> {
-    let mut candidate_paths = vec![get_token_path()?];
-    if let Some(legacy) = get_legacy_token_path() {
+fn token_file_candidates(profile_id: &str) -> Result<Vec<PathBuf>, GitHubError> {
+    let mut candidate_paths = vec![get_token_path_for(profile_id)?];
+    if let Some(legacy) = legacy_dir_for(profile_id).map(|dir| dir.join("github_token.json")) {
         if !candidate_paths.contains(&legacy) {
             candidate_paths.push(legacy);
         }
     }
+    Ok(candidate_paths)
+}
 
-    for token_path in candidate_paths {
+fn load_token_from_file(profile_id: &str) -> Result<String, GitHubError> {
+    for token_path in token_file_candidates(profile_id)? {
         if !token_path.exists() {
             continue;
         }
@@ -207,55 +252,59 @@ fn load_token_from_file() -> Result<String, GitHubError> {
 }
 
 pub fn save_token(access_token: &str) -> Result<(), GitHubError> {
+    save_token_for(&crate::profiles::active_profile_id(), access_token)
+}
+
+/// Store the token of any profile in the keychain and its fallback file
+pub fn save_token_for(profile_id: &str, access_token: &str) -> Result<(), GitHubError> {
     // Primary: OS keychain
-    if let Ok(entry) = get_keyring_entry() {
-        if entry.set_password(access_token).is_ok() {
-            // Also persist fallback file in case keychain access fails on future launches.
-            save_token_to_file(access_token)?;
-            return Ok(());
-        }
+    if store_credential(keyring_account_for(profile_id), access_token.to_string()).is_ok() {
+        // Also persist fallback file in case keychain access fails on future launches.
+        save_token_to_file(profile_id, access_token)?;
+        return Ok(());
     }
 
     // Fallback for environments where keyring is unavailable
-    save_token_to_file(access_token)
+    save_token_to_file(profile_id, access_token)
 }
 
 pub fn load_token() -> Result<String, GitHubError> {
+    load_token_for(&crate::profiles::active_profile_id())
+}
+
+pub fn load_token_for(profile_id: &str) -> Result<String, GitHubError> {
+    let account = keyring_account_for(profile_id);
     // Primary: OS keychain
-    if let Ok(entry) = get_keyring_entry() {
-        match entry.get_password() {
-            Ok(token) => return Ok(token),
-            Err(keyring::Error::NoEntry) => {}
-            Err(_) => {}
-        }
+    if let Ok(Some(token)) = get_credential(account.clone()) {
+        return Ok(token);
     }
 
     // Fallback: legacy plaintext file
-    let token = load_token_from_file()?;
+    let token = load_token_from_file(profile_id)?;
 
     // Best-effort migration back into keychain when possible
-    if let Ok(entry) = get_keyring_entry() {
-        let _ = entry.set_password(&token);
-    }
+    let _ = store_credential(account, token.clone());
 
     Ok(token)
 }
 
 pub fn delete_token() -> Result<(), GitHubError> {
-    // Best effort keychain cleanup
-    if let Ok(entry) = get_keyring_entry() {
-        match entry.delete_credential() {
-            Ok(()) | Err(keyring::Error::NoEntry) => {}
-            Err(e) => return Err(GitHubError::TokenSave(e.to_string())),
-        }
-    }
+    delete_token_for(&crate::profiles::active_profile_id())
+}
+
+/// Remove the token of any profile from the keychain and its fallback files
+pub fn delete_token_for(profile_id: &str) -> Result<(), GitHubError> {
+    // Keychain cleanup; a missing entry is fine
+    delete_credential(keyring_account_for(profile_id)).map_err(|e| GitHubError::TokenSave(e.message))?;
 
     // Remove legacy token file if present
-    let token_path = get_token_path()?;
-    if token_path.exists() {
-        fs::remove_file(&token_path)?;
+    let mut candidates = token_file_candidates(profile_id)?.into_iter();
+    if let Some(token_path) = candidates.next() {
+        if token_path.exists() {
+            fs::remove_file(&token_path)?;
+        }
     }
-    if let Some(legacy_token_path) = get_legacy_token_path() {
+    for legacy_token_path in candidates {
         if legacy_token_path.exists() {
             let _ = fs::remove_file(legacy_token_path);
         }
@@ -346,21 +395,21 @@ struct GistSummaryResponse {
 }
 
 #[derive(Debug, Deserialize)]
-struct GistDetailResponse {
+pub(crate) struct GistDetailResponse {
     id: String,
     url: String,
-    html_url: String,
+    pub(crate) html_url: String,
     description: Option<String>,
-    updated_at: String,
+    pub(crate) updated_at: String,
     #[serde(rename = "public")]
     is_public: bool,
-    files: HashMap<String, GistFileDetail>,
+    pub(crate) files: HashMap<String, GistFileDetail>,
 }
 
 #[derive(Debug, Deserialize)]
-struct GistFileDetail {
-    filename: Option<String>,
-    content: Option<String>,
+pub(crate) struct GistFileDetail {
+    pub(crate) filename: Option<String>,
+    pub(crate) content: Option<String>,
 }
 
 // ============================================================================
@@ -370,12 +419,10 @@ struct GistFileDetail {
 async fn start_device_flow_internal() -> Result<DeviceFlowStart, GitHubError> {
     // Validate Client ID is configured
     if GITHUB_CLIENT_ID == "YOUR_GITHUB_CLIENT_ID" || GITHUB_CLIENT_ID.is_empty() {
-        return Err(GitHubError::ApiError(
-            "GitHub Client ID not configured. Please register a GitHub OAuth App and update the Client ID in github.rs".to_string()
-        ));
+        return Err(GitHubError::NotConfigured);
     }
 
-    let client = reqwest::Client::builder()
+    let client = http_client::builder()?
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
 
@@ -391,16 +438,7 @@ async fn start_device_flow_internal() -> Result<DeviceFlowStart, GitHubError> {
         .header("User-Agent", "Wingman-Desktop")
         .form(&params)
         .send()
-        .await
-        .map_err(|e| {
-            if e.is_timeout() {
-                GitHubError::Network(e)
-            } else if e.is_connect() {
-                GitHubError::ApiError("Failed to connect to GitHub. Please check your internet connection.".to_string())
-            } else {
-                GitHubError::Network(e)
-            }
-        })?;
+        .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -428,7 +466,7 @@ async fn start_device_flow_internal() -> Result<DeviceFlowStart, GitHubError> {
 async fn poll_device_flow_internal(device_code: &str) -> Result<Option<GitHubAuthStatus>, GitHubError> {
     println!("[GitHub Poll] Polling with device_code: {}...", &device_code[..8.min(device_code.len())]);
 
-    let client = reqwest::Client::builder()
+    let client = http_client::builder()?
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
 
@@ -445,14 +483,7 @@ async fn poll_device_flow_internal(device_code: &str) -> Result<Option<GitHubAut
         .header("User-Agent", "Wingman-Desktop")
         .form(&params)
         .send()
-        .await
-        .map_err(|e| {
-            if e.is_timeout() || e.is_connect() {
-                GitHubError::ApiError("Connection timeout. Please check your internet connection.".to_string())
-            } else {
-                GitHubError::Network(e)
-            }
-        })?;
+        .await?;
 
     let status = response.status();
     let response_text = response.text().await.unwrap_or_default();
@@ -480,7 +511,7 @@ async fn poll_device_flow_internal(device_code: &str) -> Result<Option<GitHubAut
                 // Return the interval so the frontend can slow down appropriately
                 let interval = token_response.interval.unwrap_or(10);
                 println!("[GitHub Poll] Slow down requested, new interval: {}s", interval);
-                Err(GitHubError::ApiError(format!("slow_down:{}", interval)))
+                Err(GitHubError::SlowDown(interval))
             }
             "expired_token" => Err(GitHubError::AuthTimeout),
             _ => Err(GitHubError::ApiError(format!("Device flow error: {}", error))),
@@ -517,7 +548,7 @@ async fn poll_device_flow_internal(device_code: &str) -> Result<Option<GitHubAut
 }
 
 async fn get_username_from_token(token: &str) -> Result<String, GitHubError> {
-    let client = reqwest::Client::builder()
+    let client = http_client::builder()?
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
 
@@ -528,16 +559,7 @@ async fn get_username_from_token(token: &str) -> Result<String, GitHubError> {
         .header("User-Agent", "Wingman-Desktop")
         .header("X-GitHub-Api-Version", "2022-11-28")
         .send()
-        .await
-        .map_err(|e| {
-            if e.is_timeout() {
-                GitHubError::ApiError("Request timed out. Please check your internet connection.".to_string())
-            } else if e.is_connect() {
-                GitHubError::ApiError("Failed to connect to GitHub. Please check your internet connection.".to_string())
-            } else {
-                GitHubError::Network(e)
-            }
-        })?;
+        .await?;
 
     if !response.status().is_success() {
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
@@ -631,13 +653,13 @@ fn ensure_wingman_marker(description: &str) -> String {
 
 fn github_authed_client() -> Result<(reqwest::Client, String), GitHubError> {
     let token = load_token()?;
-    let client = reqwest::Client::builder()
+    let client = http_client::builder()?
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
     Ok((client, token))
 }
 
-async fn get_gist_detail_internal(gist_id: &str) -> Result<GistDetailResponse, GitHubError> {
+pub(crate) async fn get_gist_detail_internal(gist_id: &str) -> Result<GistDetailResponse, GitHubError> {
     let (client, token) = github_authed_client()?;
     let response = client
         .get(format!("https://api.github.com/gists/{}", gist_id))
@@ -673,26 +695,26 @@ async fn get_gist_detail_internal(gist_id: &str) -> Result<GistDetailResponse, G
 // ============================================================================
 
 #[tauri::command]
-pub async fn start_github_device_flow() -> Result<DeviceFlowStart, String> {
+pub async fn start_github_device_flow() -> Result<DeviceFlowStart, AppError> {
     start_device_flow_internal()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn poll_github_device_flow(device_code: String) -> Result<Option<GitHubAuthStatus>, String> {
+pub async fn poll_github_device_flow(device_code: String) -> Result<Option<GitHubAuthStatus>, AppError> {
     match poll_device_flow_internal(&device_code).await {
         Ok(result) => Ok(result),
         Err(GitHubError::AuthPending) => Ok(None), // Still pending
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(e.into()),
     }
 }
 
 #[tauri::command]
-pub async fn check_github_auth_status() -> Result<GitHubAuthStatus, String> {
+pub async fn check_github_auth_status() -> Result<GitHubAuthStatus, AppError> {
     check_auth_status_internal()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 async fn create_gist_internal(
@@ -746,7 +768,7 @@ async fn create_gist_internal(
     });
 
     // Send request with retry logic for transient failures
-    let client = reqwest::Client::builder()
+    let client = http_client::builder()?
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
 
@@ -790,10 +812,7 @@ async fn create_gist_internal(
             if text.contains("rate limit") || text.contains("API rate limit") {
                 return Err(GitHubError::RateLimit);
             }
-            return Err(GitHubError::ApiError(format!(
-                "Access forbidden. Your GitHub token may lack gist permissions: {}",
-                text
-            )));
+            return Err(GitHubError::Forbidden(text));
         } else if status == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
             let text = response.text().await.unwrap_or_default();
             return Err(GitHubError::InvalidContent(format!(
@@ -828,14 +847,19 @@ async fn create_gist_internal(
 
 #[tauri::command]
 pub async fn create_github_gist(
+    app: tauri::AppHandle,
     content: String,
     filename: String,
     description: String,
     is_public: bool,
-) -> Result<GistResult, String> {
-    create_gist_internal(&content, &filename, &description, is_public)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<GistResult, AppError> {
+    track("create_github_gist", async move {
+        let started = std::time::Instant::now();
+        let result = create_gist_internal(&content, &filename, &description, is_public).await;
+        notifications::report(&app, NotificationCategory::GistSync, "Gist upload", started, &result);
+        result.map_err(AppError::from)
+    })
+    .await
 }
 
 async fn list_wingman_gists_internal() -> Result<Vec<WingmanGist>, GitHubError> {
@@ -903,7 +927,7 @@ async fn list_wingman_gists_internal() -> Result<Vec<WingmanGist>, GitHubError>
     Ok(wingman_gists)
 }
 
-async fn update_gist_internal(
+pub(crate) async fn update_gist_internal(
     gist_id: &str,
     content: &str,
     filename: Option<&str>,
@@ -1008,55 +1032,67 @@ async fn delete_gist_internal(gist_id: &str) -> Result<(), GitHubError> {
 }
 
 #[tauri::command]
-pub async fn list_wingman_gists() -> Result<Vec<WingmanGist>, String> {
-    list_wingman_gists_internal()
-        .await
-        .map_err(|e| e.to_string())
+pub async fn list_wingman_gists() -> Result<Vec<WingmanGist>, AppError> {
+    track("list_wingman_gists", async move {
+        list_wingman_gists_internal()
+            .await
+            .map_err(AppError::from)
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn update_github_gist(
+    app: tauri::AppHandle,
     gist_id: String,
     content: String,
     filename: Option<String>,
     description: Option<String>,
-) -> Result<GistResult, String> {
-    update_gist_internal(
-        &gist_id,
-        &content,
-        filename.as_deref(),
-        description.as_deref(),
-    )
+) -> Result<GistResult, AppError> {
+    track("update_github_gist", async move {
+        let started = std::time::Instant::now();
+        let result = update_gist_internal(
+            &gist_id,
+            &content,
+            filename.as_deref(),
+            description.as_deref(),
+        )
+        .await;
+        notifications::report(&app, NotificationCategory::GistSync, "Gist sync", started, &result);
+        result.map_err(AppError::from)
+    })
     .await
-    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_github_gist(gist_id: String) -> Result<(), String> {
-    delete_gist_internal(&gist_id)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn delete_github_gist(gist_id: String) -> Result<(), AppError> {
+    track("delete_github_gist", async move {
+        delete_gist_internal(&gist_id)
+            .await
+            .map_err(AppError::from)
+    })
+    .await
 }
 
 #[tauri::command]
-pub async fn logout_github() -> Result<(), String> {
-    delete_token().map_err(|e| e.to_string())?;
+pub async fn logout_github() -> Result<(), AppError> {
+    delete_token()?;
 
     // Update config to mark as not authenticated
-    let mut config = load_config().map_err(|e| e.to_string())?;
+    let mut config = load_config()?;
     config.is_authenticated = false;
     config.username = None;
-    save_config(&config).map_err(|e| e.to_string())?;
+    save_config(&config)?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_github_config() -> Result<GitHubConfig, String> {
-    load_config().map_err(|e| e.to_string())
+pub async fn get_github_config() -> Result<GitHubConfig, AppError> {
+    load_config().map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn save_github_config(config: GitHubConfig) -> Result<(), String> {
-    save_config(&config).map_err(|e| e.to_string())
+pub async fn save_github_config(config: GitHubConfig) -> Result<(), AppError> {
+    save_config(&config).map_err(AppError::from)
 }
@@ -0,0 +1,340 @@
+// Grammar and spell checking through a LanguageTool server: the public API by
+// default, or a self-hosted instance for people who don't want text leaving their
+// network. Issues come back with offsets so the editor can underline them, since
+// WebKit's native spellcheck is disabled in the panel.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::credentials::get_credential;
+use crate::error::AppError;
+use crate::http_client::{self, HttpClientError};
+use crate::storage::ensure_app_data_dir;
+
+#[derive(Error, Debug)]
+pub enum GrammarError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("{0}")]
+    HttpClient(#[from] HttpClientError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("LanguageTool error: {0}")]
+    Server(String),
+    #[error("Invalid server URL: {0}")]
+    InvalidUrl(String),
+    #[error("Checking is paused while privacy mode is on (use a local LanguageTool server)")]
+    PrivacyModeActive,
+}
+
+pub const DEFAULT_SERVER_URL: &str = "https://api.languagetool.org";
+/// Keychain entry for LanguageTool Premium API keys
+pub const LANGUAGETOOL_API_KEY: &str = "languagetool_api_key";
+const MAX_REPLACEMENTS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrammarConfig {
+    pub server_url: String,
+    /// LanguageTool Premium account; the API key is kept in the keychain
+    pub username: Option<String>,
+    /// Native language, enables false-friend checks
+    pub mother_tongue: Option<String>,
+    pub disabled_rules: Vec<String>,
+    /// Use LanguageTool's stricter "picky" mode (more style suggestions)
+    pub picky: bool,
+}
+
+impl Default for GrammarConfig {
+    fn default() -> Self {
+        Self {
+            server_url: DEFAULT_SERVER_URL.to_string(),
+            username: None,
+            mother_tongue: None,
+            disabled_rules: Vec::new(),
+            picky: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    Spelling,
+    Grammar,
+    Style,
+    Punctuation,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GrammarIssue {
+    /// UTF-16 offset and length, matching JavaScript string indices
+    pub offset: usize,
+    pub length: usize,
+    pub message: String,
+    pub short_message: Option<String>,
+    pub replacements: Vec<String>,
+    pub kind: IssueKind,
+    pub rule_id: String,
+    pub category: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub issues: Vec<GrammarIssue>,
+    /// Language the server checked against (the detected one for "auto")
+    pub language: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LtResponse {
+    language: Option<LtLanguage>,
+    matches: Vec<LtMatch>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LtLanguage {
+    code: String,
+    detected_language: Option<LtDetected>,
+}
+
+#[derive(Deserialize)]
+struct LtDetected {
+    code: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LtMatch {
+    message: String,
+    short_message: Option<String>,
+    offset: usize,
+    length: usize,
+    replacements: Vec<LtReplacement>,
+    rule: LtRule,
+}
+
+#[derive(Deserialize)]
+struct LtReplacement {
+    value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LtRule {
+    id: String,
+    issue_type: Option<String>,
+    category: LtCategory,
+}
+
+#[derive(Deserialize)]
+struct LtCategory {
+    id: String,
+    name: String,
+}
+
+// ============================================================================
+// Config Storage
+// ============================================================================
+
+fn get_config_path() -> Result<PathBuf, GrammarError> {
+    Ok(ensure_app_data_dir()?.join("grammar_config.json"))
+}
+
+pub fn load_config() -> Result<GrammarConfig, GrammarError> {
+    let path = get_config_path()?;
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        Ok(GrammarConfig::default())
+    }
+}
+
+pub fn save_config(config: &GrammarConfig) -> Result<(), GrammarError> {
+    let content = serde_json::to_string_pretty(config)?;
+    fs::write(get_config_path()?, content)?;
+    Ok(())
+}
+
+// ============================================================================
+// Checking
+// ============================================================================
+
+fn issue_kind(rule: &LtRule) -> IssueKind {
+    match (rule.issue_type.as_deref(), rule.category.id.as_str()) {
+        (Some("misspelling"), _) | (_, "TYPOS") => IssueKind::Spelling,
+        (_, "PUNCTUATION") | (_, "TYPOGRAPHY") => IssueKind::Punctuation,
+        (Some("style"), _) | (_, "STYLE") | (_, "REDUNDANCY") | (_, "PLAIN_ENGLISH") => IssueKind::Style,
+        (Some("grammar"), _) | (_, "GRAMMAR") | (_, "CASING") | (_, "CONFUSED_WORDS") => IssueKind::Grammar,
+        _ => IssueKind::Other,
+    }
+}
+
+fn convert_response(response: LtResponse) -> CheckResult {
+    let language = response.language.map(|l| match l.detected_language {
+        Some(detected) => detected.code,
+        None => l.code,
+    });
+    let issues = response
+        .matches
+        .into_iter()
+        .map(|m| GrammarIssue {
+            offset: m.offset,
+            length: m.length,
+            kind: issue_kind(&m.rule),
+            message: m.message,
+            short_message: m.short_message.filter(|s| !s.is_empty()),
+            replacements: m
+                .replacements
+                .into_iter()
+                .take(MAX_REPLACEMENTS)
+                .map(|r| r.value)
+                .collect(),
+            rule_id: m.rule.id,
+            category: m.rule.category.name,
+        })
+        .collect();
+    CheckResult { issues, language }
+}
+
+fn is_local_server(url: &reqwest::Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    if host == "localhost" {
+        return true;
+    }
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<std::net::IpAddr>() {
+        Ok(std::net::IpAddr::V4(ip)) => ip.is_loopback() || ip.is_private(),
+        Ok(std::net::IpAddr::V6(ip)) => ip.is_loopback(),
+        Err(_) => false,
+    }
+}
+
+pub async fn check(text: &str, language: &str, config: &GrammarConfig) -> Result<CheckResult, GrammarError> {
+    let base = reqwest::Url::parse(config.server_url.trim_end_matches('/'))
+        .map_err(|e| GrammarError::InvalidUrl(e.to_string()))?;
+    if !matches!(base.scheme(), "http" | "https") {
+        return Err(GrammarError::InvalidUrl(config.server_url.clone()));
+    }
+    // A server on this machine or LAN is fine; sending text elsewhere is not
+    if crate::privacy::is_privacy_mode_active() && !is_local_server(&base) {
+        return Err(GrammarError::PrivacyModeActive);
+    }
+    let endpoint = format!("{}/v2/check", base.as_str().trim_end_matches('/'));
+
+    let mut params: Vec<(&str, String)> = vec![
+        ("text", text.to_string()),
+        ("language", if language.is_empty() { "auto".to_string() } else { language.to_string() }),
+    ];
+    if let Some(mother_tongue) = config.mother_tongue.as_ref().filter(|m| !m.is_empty()) {
+        params.push(("motherTongue", mother_tongue.clone()));
+    }
+    if !config.disabled_rules.is_empty() {
+        params.push(("disabledRules", config.disabled_rules.join(",")));
+    }
+    if config.picky {
+        params.push(("level", "picky".to_string()));
+    }
+    if let Some(username) = config.username.as_ref().filter(|u| !u.is_empty()) {
+        if let Ok(Some(api_key)) = get_credential(LANGUAGETOOL_API_KEY.to_string()) {
+            params.push(("username", username.clone()));
+            params.push(("apiKey", api_key));
+        }
+    }
+
+    let response = http_client::builder()?
+        .timeout(std::time::Duration::from_secs(20))
+        .build()?
+        .post(&endpoint)
+        .form(&params)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(GrammarError::Server(format!("{}: {}", status.as_u16(), body.trim())));
+    }
+    let parsed: LtResponse = response.json().await?;
+    Ok(convert_response(parsed))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check spelling and grammar. `language` is a LanguageTool code like "en-US", or
+/// "auto" (the default) to detect it.
+#[tauri::command]
+pub async fn check_text(text: String, language: Option<String>) -> Result<CheckResult, AppError> {
+    if text.trim().is_empty() {
+        return Ok(CheckResult {
+            issues: Vec::new(),
+            language: None,
+        });
+    }
+    let config = load_config()?;
+    check(&text, language.as_deref().unwrap_or("auto"), &config)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_grammar_config() -> Result<GrammarConfig, AppError> {
+    load_config().map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn save_grammar_config(config: GrammarConfig) -> Result<(), AppError> {
+    reqwest::Url::parse(&config.server_url).map_err(|e| GrammarError::InvalidUrl(e.to_string()))?;
+    save_config(&config).map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_languagetool_response() {
+        let json = r#"{
+            "language": {"code": "auto", "name": "Auto", "detectedLanguage": {"code": "en-US"}},
+            "matches": [{
+                "message": "Possible spelling mistake found.",
+                "shortMessage": "Spelling mistake",
+                "offset": 8, "length": 4,
+                "replacements": [{"value": "test"}, {"value": "text"}],
+                "rule": {"id": "MORFOLOGIK_RULE_EN_US", "issueType": "misspelling",
+                         "category": {"id": "TYPOS", "name": "Possible Typo"}}
+            }, {
+                "message": "Use a comma.", "shortMessage": "",
+                "offset": 0, "length": 2, "replacements": [],
+                "rule": {"id": "COMMA_RULE", "category": {"id": "PUNCTUATION", "name": "Punctuation"}}
+            }]
+        }"#;
+        let result = convert_response(serde_json::from_str(json).unwrap());
+        assert_eq!(result.language.as_deref(), Some("en-US"));
+        assert_eq!(result.issues.len(), 2);
+        assert_eq!(result.issues[0].kind, IssueKind::Spelling);
+        assert_eq!(result.issues[0].replacements, vec!["test", "text"]);
+        assert_eq!(result.issues[1].kind, IssueKind::Punctuation);
+        assert!(result.issues[1].short_message.is_none());
+    }
+
+    #[test]
+    fn test_is_local_server() {
+        let local = |u: &str| is_local_server(&reqwest::Url::parse(u).unwrap());
+        assert!(local("http://localhost:8081"));
+        assert!(local("http://192.168.1.20:8010"));
+        assert!(!local("https://api.languagetool.org"));
+    }
+}
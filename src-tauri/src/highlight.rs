@@ -0,0 +1,165 @@
+// Syntax highlighting via syntect, shared by exporters that need colored output
+// (rich clipboard formats, PDF, images). The editor itself highlights in the frontend.
+
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Color, FontStyle, Style, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Light theme so colors stay readable when pasted into documents with a white page
+pub const DEFAULT_THEME: &str = "InspiredGitHub";
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    THEME_SET.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Look up a bundled theme by name, falling back to `DEFAULT_THEME`
+pub fn get_theme(name: Option<&str>) -> &'static Theme {
+    let themes = &theme_set().themes;
+    name.and_then(|n| themes.get(n))
+        .unwrap_or_else(|| &themes[DEFAULT_THEME])
+}
+
+/// Map the editor's language ids onto syntect syntaxes. Languages syntect doesn't
+/// bundle fall back to a close relative, or plain text.
+fn find_syntax(language: Option<&str>) -> &'static SyntaxReference {
+    let ss = syntax_set();
+    let token = match language.unwrap_or("plaintext") {
+        "javascript" | "typescript" | "jsx" | "tsx" | "react" => "js",
+        "python" => "py",
+        "rust" => "rs",
+        "csharp" => "cs",
+        "cpp" => "cpp",
+        "ruby" => "rb",
+        "bash" | "shell" => "sh",
+        "markdown" => "md",
+        "yaml" => "yaml",
+        other => other,
+    };
+    ss.find_syntax_by_token(token)
+        .unwrap_or_else(|| ss.find_syntax_plain_text())
+}
+
+/// Highlight `content` line by line. Each line keeps its trailing newline.
+pub fn highlight_lines<'a>(
+    content: &'a str,
+    language: Option<&str>,
+    theme: &Theme,
+) -> Result<Vec<Vec<(Style, &'a str)>>, String> {
+    let ss = syntax_set();
+    let mut highlighter = HighlightLines::new(find_syntax(language), theme);
+    LinesWithEndings::from(content)
+        .map(|line| {
+            highlighter
+                .highlight_line(line, ss)
+                .map_err(|e| format!("Highlighting failed: {}", e))
+        })
+        .collect()
+}
+
+/// Self-contained HTML (`<pre>` with inline styles) for pasting into rich text editors
+pub fn to_html(content: &str, language: Option<&str>) -> Result<String, String> {
+    let ss = syntax_set();
+    syntect::html::highlighted_html_for_string(content, ss, find_syntax(language), get_theme(None))
+        .map_err(|e| format!("Highlighting failed: {}", e))
+}
+
+/// Escape text for inclusion in an RTF document
+fn escape_rtf(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '{' => out.push_str("\\{"),
+            '}' => out.push_str("\\}"),
+            '\t' => out.push_str("\\tab "),
+            '\n' => out.push_str("\\line\n"),
+            '\r' => {}
+            c if c.is_ascii() => out.push(c),
+            c => {
+                // RTF wants signed 16-bit code units, with a '?' fallback for old readers
+                let mut buf = [0u16; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    out.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// RTF document with syntax colors, suitable for `public.rtf`. Word and Pages keep
+/// the colors on paste, which they don't reliably do for HTML.
+pub fn to_rtf(content: &str, language: Option<&str>) -> Result<String, String> {
+    let lines = highlight_lines(content, language, get_theme(None))?;
+
+    // Collect the color table first; RTF color indexes are 1-based (0 is "auto")
+    let mut colors: Vec<Color> = Vec::new();
+    for (style, _) in lines.iter().flatten() {
+        if !colors.contains(&style.foreground) {
+            colors.push(style.foreground);
+        }
+    }
+
+    let mut rtf = String::from("{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fmodern Menlo;}}{\\colortbl;");
+    for color in &colors {
+        rtf.push_str(&format!("\\red{}\\green{}\\blue{};", color.r, color.g, color.b));
+    }
+    rtf.push_str("}\\f0\\fs24 ");
+
+    for (style, text) in lines.iter().flatten() {
+        let index = colors.iter().position(|c| *c == style.foreground).unwrap_or(0) + 1;
+        let bold = style.font_style.contains(FontStyle::BOLD);
+        let italic = style.font_style.contains(FontStyle::ITALIC);
+        rtf.push_str(&format!("{{\\cf{}", index));
+        if bold {
+            rtf.push_str("\\b");
+        }
+        if italic {
+            rtf.push_str("\\i");
+        }
+        rtf.push(' ');
+        rtf.push_str(&escape_rtf(text));
+        rtf.push('}');
+    }
+
+    rtf.push('}');
+    Ok(rtf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_rtf() {
+        assert_eq!(escape_rtf("a{b}\\c"), "a\\{b\\}\\\\c");
+        assert_eq!(escape_rtf("x\ty\n"), "x\\tab y\\line\n");
+        assert_eq!(escape_rtf("é"), "\\u233?");
+        // Astral characters become a surrogate pair
+        assert_eq!(escape_rtf("😀"), "\\u-10179?\\u-8704?");
+    }
+
+    #[test]
+    fn test_to_rtf_is_well_formed() {
+        let rtf = to_rtf("fn main() {\n    println!(\"hi\");\n}\n", Some("rust")).unwrap();
+        assert!(rtf.starts_with("{\\rtf1"));
+        assert!(rtf.contains("\\colortbl;"));
+        let opens = rtf.matches('{').count() - rtf.matches("\\{").count();
+        let closes = rtf.matches('}').count() - rtf.matches("\\}").count();
+        assert_eq!(opens, closes);
+    }
+
+    #[test]
+    fn test_unknown_language_falls_back_to_plain_text() {
+        let lines = highlight_lines("hello\nworld", Some("not-a-language"), get_theme(None)).unwrap();
+        assert_eq!(lines.len(), 2);
+    }
+}
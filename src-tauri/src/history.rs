@@ -1,9 +1,22 @@
-use rusqlite::Connection;
+use rusqlite::hooks::Action;
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use thiserror::Error;
 
-use crate::storage::get_app_data_dir;
+use crate::attachments::{
+    externalize_attachments, hydrate_attachments, referenced_hashes, remove_unreferenced,
+    AttachmentError,
+};
+use crate::classify::smart_tags_for;
+use crate::compression::{pack, unpack};
+use crate::frontmost::FrontmostApp;
+use crate::indexer::enqueue;
+use crate::oversize::{load_overflow, prepare, StoredContent};
+use crate::storage::{get_profile_data_dir, load_settings, profile_data_dir};
 
 #[derive(Error, Debug)]
 pub enum HistoryError {
@@ -11,8 +24,31 @@ pub enum HistoryError {
     Database(#[from] rusqlite::Error),
     #[error("Storage error: {0}")]
     Storage(#[from] crate::storage::StorageError),
+    #[error("Attachment error: {0}")]
+    Attachment(#[from] AttachmentError),
+    #[error("History entry {0} not found")]
+    EntryNotFound(i64),
+    #[error("Version {1} of history entry {0} not found")]
+    VersionNotFound(i64, i64),
+    #[error("Compression error: {0}")]
+    Compression(std::io::Error),
 }
 
+/// Maximum number of prior revisions kept per history entry
+const MAX_VERSIONS_PER_ENTRY: i64 = 50;
+
+/// Characters of content returned by list queries; the rest comes from get_entry_content
+const PREVIEW_CHARS: usize = 2000;
+
+/// Pages of get_entries kept in memory by `PageCache`
+const CACHED_PAGES: usize = 8;
+
+/// Page the panel loads when it opens, read ahead at startup by `warm_cache`
+pub const FIRST_PAGE_SIZE: u32 = 100;
+
+/// The app connection's page cache; `init_database` keeps it in step with writes
+static PAGE_CACHE: PageCache = PageCache::new();
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
     pub id: i64,
@@ -23,7 +59,36 @@ pub struct HistoryEntry {
     pub line_count: i32,
     pub language: Option<String>,
     pub tags: Option<String>,
-    pub images: Option<String>, // JSON array of image data
+    pub images: Option<String>, // JSON array of attachments (data URLs are stored as files, see attachments.rs)
+    pub html: Option<String>, // Rich HTML variant, when the content was captured with formatting
+    pub paste_service: Option<String>, // Set once the entry is published to a paste service
+    pub paste_url: Option<String>,
+    pub paste_published_at: Option<String>,
+    pub expires_at: Option<String>, // UTC "YYYY-MM-DD HH:MM:SS"; the entry is deleted once this passes
+    pub summary: Option<String>, // Generated by summarize_text (see summarize.rs)
+    pub title: Option<String>, // Generated by suggest_title; shown instead of the first line
+    pub smart_tags: Option<String>, // Comma-separated kinds detected by classify.rs ("url,email")
+    pub reminder_at: Option<String>, // UTC "YYYY-MM-DD HH:MM:SS"; a notification fires once this passes
+    pub reminder_note: Option<String>,
+    /// `content` is only a preview (list queries, oversized entries); get_entry_content has it all
+    #[serde(default)]
+    pub content_truncated: bool,
+    /// App Wingman was opened over when the entry was saved (see frontmost.rs)
+    #[serde(default)]
+    pub source_app: Option<String>,
+    #[serde(default)]
+    pub source_bundle_id: Option<String>,
+    #[serde(default)]
+    pub source_window_title: Option<String>,
+}
+
+/// A prior revision of a history entry, saved whenever the entry is edited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntryVersion {
+    pub entry_id: i64,
+    pub version: i64,
+    pub content: String,
+    pub created_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,21 +98,59 @@ pub struct HistoryStats {
     pub total_words: i64,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceAppCount {
+    pub name: String,
+    pub bundle_id: Option<String>,
+    pub count: i64,
+}
+
 pub fn get_db_path() -> Result<PathBuf, HistoryError> {
-    let dir = get_app_data_dir()?;
+    let dir = get_profile_data_dir()?;
+    Ok(dir.join("history.db"))
+}
+
+/// Path of a profile's history database, whether or not it is the active one
+pub fn get_profile_db_path(profile_id: &str) -> Result<PathBuf, HistoryError> {
+    let dir = profile_data_dir(profile_id)?;
     Ok(dir.join("history.db"))
 }
 
 pub fn init_database() -> Result<Connection, HistoryError> {
-    let path = get_db_path()?;
+    let conn = open_database(&get_db_path()?)?;
+    prepare_connection(&conn)?;
+    Ok(conn)
+}
 
+/// Open the database at `path` with an up to date schema. Touches nothing outside
+/// the file, so it can run before the profile it belongs to is made active.
+pub fn open_database(path: &Path) -> Result<Connection, HistoryError> {
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).ok();
     }
 
-    let conn = Connection::open(&path)?;
+    let conn = Connection::open(path)?;
+    create_schema(&conn)?;
+    Ok(conn)
+}
+
+/// Make a connection from `open_database` the app's: moves inline attachments into
+/// the active profile's store and keeps the caches in step with writes
+pub fn prepare_connection(conn: &Connection) -> Result<(), HistoryError> {
+    migrate_inline_attachments(conn)?;
 
+    on_history_write(conn, || {
+        clear_cache();
+        crate::saved_searches::mark_counts_stale();
+    });
+
+    Ok(())
+}
+
+/// Create the history tables (and those of modules sharing the database), applying
+/// column migrations to older databases
+pub fn create_schema(conn: &Connection) -> Result<(), HistoryError> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS history (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -58,13 +161,45 @@ pub fn init_database() -> Result<Connection, HistoryError> {
             line_count INTEGER,
             language TEXT,
             tags TEXT,
-            images TEXT
+            images TEXT,
+            html TEXT,
+            paste_service TEXT,
+            paste_url TEXT,
+            paste_published_at DATETIME,
+            expires_at DATETIME
         )",
         [],
     )?;
 
     // Migration: add images column if it doesn't exist
     let _ = conn.execute("ALTER TABLE history ADD COLUMN images TEXT", []);
+    // Migration: add html column if it doesn't exist
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN html TEXT", []);
+    // Migration: add paste publishing columns if they don't exist
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN paste_service TEXT", []);
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN paste_url TEXT", []);
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN paste_published_at DATETIME", []);
+    // Migration: add expires_at column if it doesn't exist
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN expires_at DATETIME", []);
+    // Migration: add generated summary/title columns if they don't exist
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN summary TEXT", []);
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN title TEXT", []);
+    // Migration: add smart_tags column if it doesn't exist (backfilled by reclassify_entries)
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN smart_tags TEXT", []);
+    // Migration: add reminder columns if they don't exist
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN reminder_at DATETIME", []);
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN reminder_note TEXT", []);
+    // Migration: add content_hash column if it doesn't exist (oversized content, see oversize.rs)
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN content_hash TEXT", []);
+    // Migration: add source app columns if they don't exist
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN source_app TEXT", []);
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN source_bundle_id TEXT", []);
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN source_window_title TEXT", []);
+    // Migration: add compression columns if they don't exist (see compression.rs)
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN compressed INTEGER NOT NULL DEFAULT 0", []);
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN content_zstd BLOB", []);
+    // Migration: add content_digest column if it doesn't exist (filled by fill_content_digests)
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN content_digest TEXT", []);
 
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_created_at ON history(created_at DESC)",
@@ -76,7 +211,146 @@ pub fn init_database() -> Result<Connection, HistoryError> {
         [],
     )?;
 
-    Ok(conn)
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_expires_at ON history(expires_at) WHERE expires_at IS NOT NULL",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_reminder_at ON history(reminder_at) WHERE reminder_at IS NOT NULL",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_source_app ON history(source_app) WHERE source_app IS NOT NULL",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_language ON history(language)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_content_digest ON history(content_digest)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS history_versions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entry_id INTEGER NOT NULL,
+            version INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(entry_id, version)
+        )",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE history_versions ADD COLUMN content_hash TEXT", []);
+
+    crate::drafts::init_drafts_table(conn)?;
+    crate::ai_history::init_ai_history_table(conn)?;
+    crate::rules::init_rules_tables(conn)?;
+    crate::semantic::init_vectors_table(conn)?;
+    crate::indexer::init_index_queue_table(conn)?;
+    crate::links::init_links_table(conn)?;
+    crate::trash::init_trash_table(conn)?;
+    crate::snippet_versions::init_snippet_versions_table(conn)?;
+    crate::saved_searches::init_saved_searches_table(conn)?;
+    crate::suggestions::init_action_usage_table(conn)?;
+
+    Ok(())
+}
+
+/// Migration: move base64 attachment payloads that predate file storage out of the DB.
+/// Rows that fail to convert are left inline (they still render) and retried next launch.
+fn migrate_inline_attachments(conn: &Connection) -> Result<(), HistoryError> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, images FROM history WHERE images LIKE '%\"data\":\"data:%'",
+        )?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for (id, images) in rows {
+        match externalize_attachments(&images) {
+            Ok(stored) => {
+                tx.execute(
+                    "UPDATE history SET images = ?2 WHERE id = ?1",
+                    rusqlite::params![id, stored],
+                )?;
+            }
+            Err(e) => log::warn!("Failed to migrate attachments for history entry {}: {}", id, e),
+        }
+    }
+    tx.commit()?;
+
+    // Reclaim the space the inline blobs occupied
+    conn.execute("VACUUM", [])?;
+
+    Ok(())
+}
+
+/// The `content` and `content_zstd` to store: large content is compressed with only its
+/// head kept as text. Overflowed content already lives in a file and is left alone.
+fn compress_stored(content: String, overflowed: bool) -> Result<(String, Option<Vec<u8>>), HistoryError> {
+    if overflowed {
+        return Ok((content, None));
+    }
+    Ok(match pack(&content)? {
+        Some(packed) => (packed.head, Some(packed.data)),
+        None => (content, None),
+    })
+}
+
+/// SHA-256 of an entry's full content, for finding entries by content without reading
+/// (or decompressing) every row
+pub fn content_digest(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Digest rows stored without one (written before the column existed, or straight into
+/// the table rather than through add_entry)
+pub fn fill_content_digests(conn: &Connection) -> Result<u32, HistoryError> {
+    let rows: Vec<(i64, String, Option<String>, Option<Vec<u8>>)> = conn
+        .prepare("SELECT id, content, content_hash, content_zstd FROM history WHERE content_digest IS NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let count = rows.len() as u32;
+    for (id, content, hash, content_zstd) in rows {
+        // A missing overflow file shouldn't block the rest; digest the stored head instead
+        let full = match (&hash, &content_zstd) {
+            (None, None) => content,
+            _ => full_content(content.clone(), hash, content_zstd).unwrap_or_else(|e| {
+                log::warn!("Failed to load full content of history entry {}: {}", id, e);
+                content
+            }),
+        };
+        conn.execute(
+            "UPDATE history SET content_digest = ?2 WHERE id = ?1",
+            rusqlite::params![id, content_digest(&full)],
+        )?;
+    }
+    Ok(count)
+}
+
+/// The full text of a row given its `content`, `content_hash` and `content_zstd`
+pub fn full_content(content: String, hash: Option<String>, content_zstd: Option<Vec<u8>>) -> Result<String, HistoryError> {
+    match (hash, content_zstd) {
+        (Some(hash), _) => Ok(load_overflow(&hash)?),
+        (None, Some(data)) => unpack(&data),
+        (None, None) => Ok(content),
+    }
 }
 
 pub fn add_entry(
@@ -85,94 +359,699 @@ pub fn add_entry(
     language: Option<&str>,
     tags: Option<&str>,
     images: Option<&str>,
+    html: Option<&str>,
 ) -> Result<i64, HistoryError> {
     let character_count = content.chars().count() as i32;
     let word_count = content.split_whitespace().count() as i32;
     let line_count = content.lines().count() as i32;
+    let images = images.map(externalize_attachments).transpose()?;
+    let digest = content_digest(content);
+    let stored = prepare(content, &load_settings().unwrap_or_default().history_limits)?;
+    let (content, content_zstd) = compress_stored(stored.content, stored.content_hash.is_some())?;
 
+    // Smart tags and the semantic vector are filled in by the background indexer
     conn.execute(
-        "INSERT INTO history (content, character_count, word_count, line_count, language, tags, images)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        rusqlite::params![content, character_count, word_count, line_count, language, tags, images],
+        "INSERT INTO history (content, character_count, word_count, line_count, language, tags, images, html, content_hash,
+                              compressed, content_zstd, content_digest)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        rusqlite::params![
+            content,
+            character_count,
+            word_count,
+            line_count,
+            language,
+            tags,
+            images,
+            html,
+            stored.content_hash,
+            content_zstd.is_some(),
+            content_zstd,
+            digest
+        ],
     )?;
+    let id = conn.last_insert_rowid();
+    enqueue(conn, id)?;
 
-    Ok(conn.last_insert_rowid())
+    Ok(id)
 }
 
+/// Columns selected for every `HistoryEntry` query, in the order `map_entry_row` expects
+const ENTRY_COLUMNS: &str =
+    "id, content, created_at, character_count, word_count, line_count, language, tags, images, html, \
+     paste_service, paste_url, paste_published_at, expires_at, summary, title, smart_tags, \
+     reminder_at, reminder_note, content_hash IS NOT NULL OR compressed, source_app, source_bundle_id, source_window_title";
+
+/// `ENTRY_COLUMNS` with the content cut to a preview, for list queries
+pub fn preview_columns() -> String {
+    format!(
+        "id, substr(content, 1, {n}), created_at, character_count, word_count, line_count, language, tags, images, html, \
+         paste_service, paste_url, paste_published_at, expires_at, summary, title, smart_tags, \
+         reminder_at, reminder_note, content_hash IS NOT NULL OR compressed OR length(content) > {n}, \
+         source_app, source_bundle_id, source_window_title",
+        n = PREVIEW_CHARS
+    )
+}
+
+pub fn map_entry_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        content: row.get(1)?,
+        created_at: row.get(2)?,
+        character_count: row.get(3)?,
+        word_count: row.get(4)?,
+        line_count: row.get(5)?,
+        language: row.get(6)?,
+        tags: row.get(7)?,
+        images: row
+            .get::<_, Option<String>>(8)?
+            .map(|json| hydrate_attachments(&json).unwrap_or(json)),
+        html: row.get(9)?,
+        paste_service: row.get(10)?,
+        paste_url: row.get(11)?,
+        paste_published_at: row.get(12)?,
+        expires_at: row.get(13)?,
+        summary: row.get(14)?,
+        title: row.get(15)?,
+        smart_tags: row.get(16)?,
+        reminder_at: row.get(17)?,
+        reminder_note: row.get(18)?,
+        content_truncated: row.get(19)?,
+        source_app: row.get(20)?,
+        source_bundle_id: row.get(21)?,
+        source_window_title: row.get(22)?,
+    })
+}
+
+/// Newest entries first. Like the other list queries it returns content previews.
 pub fn get_entries(
     conn: &Connection,
     limit: u32,
     offset: u32,
 ) -> Result<Vec<HistoryEntry>, HistoryError> {
-    let mut stmt = conn.prepare(
-        "SELECT id, content, created_at, character_count, word_count, line_count, language, tags, images
-         FROM history
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM history
          ORDER BY created_at DESC
          LIMIT ?1 OFFSET ?2",
-    )?;
+        preview_columns()
+    ))?;
 
     let entries = stmt
-        .query_map(rusqlite::params![limit, offset], |row| {
-            Ok(HistoryEntry {
-                id: row.get(0)?,
-                content: row.get(1)?,
-                created_at: row.get(2)?,
-                character_count: row.get(3)?,
-                word_count: row.get(4)?,
-                line_count: row.get(5)?,
-                language: row.get(6)?,
-                tags: row.get(7)?,
-                images: row.get(8)?,
-            })
-        })?
+        .query_map(rusqlite::params![limit, offset], map_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// Least recently used cache of get_entries pages. Callers hold the connection's mutex
+/// for both the query and the insert, so a write can't land in between and leave a stale
+/// page behind.
+pub struct PageCache {
+    /// Most recently used first, keyed by (limit, offset)
+    pages: Mutex<Vec<((u32, u32), Vec<HistoryEntry>)>>,
+}
+
+impl PageCache {
+    pub const fn new() -> Self {
+        Self {
+            pages: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut pages) = self.pages.lock() {
+            pages.clear();
+        }
+    }
+
+    pub fn get_entries(&self, conn: &Connection, limit: u32, offset: u32) -> Result<Vec<HistoryEntry>, HistoryError> {
+        let key = (limit, offset);
+        if let Ok(mut pages) = self.pages.lock() {
+            if let Some(index) = pages.iter().position(|(cached, _)| *cached == key) {
+                let page = pages.remove(index);
+                let entries = page.1.clone();
+                pages.insert(0, page);
+                return Ok(entries);
+            }
+        }
+        let entries = get_entries(conn, limit, offset)?;
+        if let Ok(mut pages) = self.pages.lock() {
+            pages.insert(0, (key, entries.clone()));
+            pages.truncate(CACHED_PAGES);
+        }
+        Ok(entries)
+    }
+}
+
+impl Default for PageCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Call `changed` whenever a history row is inserted, updated or deleted through `conn`
+pub fn on_history_write(conn: &Connection, mut changed: impl FnMut() + Send + 'static) {
+    conn.update_hook(Some(move |_: Action, _: &str, table: &str, _: i64| {
+        if table == "history" {
+            changed();
+        }
+    }));
+}
+
+/// Empty the app connection's page cache, e.g. after switching to another profile's database
+pub fn clear_cache() {
+    PAGE_CACHE.clear();
+}
+
+/// get_entries through the app connection's page cache
+pub fn get_entries_cached(conn: &Connection, limit: u32, offset: u32) -> Result<Vec<HistoryEntry>, HistoryError> {
+    PAGE_CACHE.get_entries(conn, limit, offset)
+}
+
+/// Load the panel's first page into the cache, so the first open doesn't wait on SQLite
+pub fn warm_cache(conn: &Connection) -> Result<(), HistoryError> {
+    get_entries_cached(conn, FIRST_PAGE_SIZE, 0).map(|_| ())
+}
+
+/// A single entry with its full content, read back from the overflow file or decompressed
+/// if needed
+pub fn get_entry(conn: &Connection, id: i64) -> Result<Option<HistoryEntry>, HistoryError> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM history WHERE id = ?1", ENTRY_COLUMNS))?;
+    let mut rows = stmt.query_map(rusqlite::params![id], map_entry_row)?;
+    let Some(mut entry) = rows.next().transpose()? else {
+        return Ok(None);
+    };
+    if entry.content_truncated {
+        entry.content = get_entry_content(conn, id)?;
+        entry.content_truncated = false;
+    }
+    Ok(Some(entry))
+}
+
+/// Full content of an entry, for entries listed with a preview
+pub fn get_entry_content(conn: &Connection, id: i64) -> Result<String, HistoryError> {
+    let (content, hash, content_zstd) = conn
+        .query_row(
+            "SELECT content, content_hash, content_zstd FROM history WHERE id = ?1",
+            rusqlite::params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+        .ok_or(HistoryError::EntryNotFound(id))?;
+    full_content(content, hash, content_zstd)
+}
+
+/// Keyset pagination: entries older than `cursor_id`, newest first.
+/// Passing `None` starts from the newest entry. Unlike LIMIT/OFFSET this stays
+/// stable while new entries are being inserted at the head of the list.
+pub fn get_entries_after(
+    conn: &Connection,
+    cursor_id: Option<i64>,
+    limit: u32,
+) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM history
+         WHERE ?1 IS NULL OR id < ?1
+         ORDER BY id DESC
+         LIMIT ?2",
+        preview_columns()
+    ))?;
+
+    let entries = stmt
+        .query_map(rusqlite::params![cursor_id, limit], map_entry_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(entries)
 }
 
+/// Keyset pagination: entries newer than `cursor_id`, returned newest first
+/// (the same order as `get_entries_after`) so the UI can prepend them directly.
+pub fn get_entries_before(
+    conn: &Connection,
+    cursor_id: i64,
+    limit: u32,
+) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM history
+         WHERE id > ?1
+         ORDER BY id ASC
+         LIMIT ?2",
+        preview_columns()
+    ))?;
+
+    let mut entries = stmt
+        .query_map(rusqlite::params![cursor_id, limit], map_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.reverse();
+
+    Ok(entries)
+}
+
 pub fn search_entries(
     conn: &Connection,
     query: &str,
     limit: u32,
 ) -> Result<Vec<HistoryEntry>, HistoryError> {
     let search_pattern = format!("%{}%", query);
-    let mut stmt = conn.prepare(
-        "SELECT id, content, created_at, character_count, word_count, line_count, language, tags, images
-         FROM history
-         WHERE content LIKE ?1 OR tags LIKE ?1
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM history
+         WHERE content LIKE ?1 OR tags LIKE ?1 OR source_app LIKE ?1 OR source_window_title LIKE ?1
          ORDER BY created_at DESC
          LIMIT ?2",
-    )?;
+        preview_columns()
+    ))?;
 
     let entries = stmt
-        .query_map(rusqlite::params![search_pattern, limit], |row| {
-            Ok(HistoryEntry {
-                id: row.get(0)?,
-                content: row.get(1)?,
-                created_at: row.get(2)?,
-                character_count: row.get(3)?,
-                word_count: row.get(4)?,
-                line_count: row.get(5)?,
-                language: row.get(6)?,
-                tags: row.get(7)?,
-                images: row.get(8)?,
+        .query_map(rusqlite::params![search_pattern, limit], map_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// Entries carrying a smart tag (see classify.rs), newest first
+pub fn get_entries_by_smart_tag(conn: &Connection, tag: &str, limit: u32) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM history
+         WHERE ',' || smart_tags || ',' LIKE ?1
+         ORDER BY created_at DESC
+         LIMIT ?2",
+        preview_columns()
+    ))?;
+
+    let entries = stmt
+        .query_map(rusqlite::params![format!("%,{},%", tag), limit], map_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// Entries saved while `app` (name or bundle id, case-insensitive) was frontmost, newest first
+pub fn get_entries_by_source_app(
+    conn: &Connection,
+    app: &str,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM history
+         WHERE source_app = ?1 COLLATE NOCASE OR source_bundle_id = ?1 COLLATE NOCASE
+         ORDER BY created_at DESC
+         LIMIT ?2 OFFSET ?3",
+        preview_columns()
+    ))?;
+
+    let entries = stmt
+        .query_map(rusqlite::params![app, limit, offset], map_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// Apps entries were saved from, most entries first, for the source filter
+pub fn get_source_apps(conn: &Connection) -> Result<Vec<SourceAppCount>, HistoryError> {
+    let mut stmt = conn.prepare(
+        "SELECT source_app, MAX(source_bundle_id), COUNT(*) FROM history
+         WHERE source_app IS NOT NULL
+         GROUP BY source_app
+         ORDER BY COUNT(*) DESC, source_app",
+    )?;
+    let apps = stmt
+        .query_map([], |row| {
+            Ok(SourceAppCount {
+                name: row.get(0)?,
+                bundle_id: row.get(1)?,
+                count: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(apps)
+}
+
+/// Re-run the classifiers over every entry (e.g. entries from before smart tags, or
+/// after the classifiers change). Returns how many entries' tags changed.
+pub fn reclassify_entries(conn: &Connection) -> Result<u32, HistoryError> {
+    let changed: Vec<(i64, Option<String>)> = {
+        let mut stmt = conn.prepare("SELECT id, content, smart_tags FROM history")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?;
+        let mut changed = Vec::new();
+        for row in rows {
+            let (id, content, current) = row?;
+            let tags = smart_tags_for(&content);
+            if tags != current {
+                changed.push((id, tags));
+            }
+        }
+        changed
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    for (id, tags) in &changed {
+        tx.execute(
+            "UPDATE history SET smart_tags = ?2 WHERE id = ?1",
+            rusqlite::params![id, tags],
+        )?;
+    }
+    tx.commit()?;
+    Ok(changed.len() as u32)
+}
+
+/// Replace an entry's content, keeping the previous content as a new revision
+pub fn update_entry(conn: &Connection, id: i64, content: &str) -> Result<(), HistoryError> {
+    let tx = conn.unchecked_transaction()?;
+
+    let (previous, previous_hash, previous_zstd): (String, Option<String>, Option<Vec<u8>>) = tx
+        .query_row(
+            "SELECT content, content_hash, content_zstd FROM history WHERE id = ?1",
+            rusqlite::params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+        .ok_or(HistoryError::EntryNotFound(id))?;
+    // Revisions are kept as plain text; only the overflow file is shared
+    let previous = match (&previous_hash, previous_zstd) {
+        (None, Some(data)) => unpack(&data)?,
+        _ => previous,
+    };
+
+    // Saving identical content shouldn't create a no-op revision
+    let unchanged = match &previous_hash {
+        Some(hash) => load_overflow(hash)? == content,
+        None => previous == content,
+    };
+    if unchanged {
+        return Ok(());
+    }
+
+    // Oversized revisions keep pointing at their overflow file
+    tx.execute(
+        "INSERT INTO history_versions (entry_id, version, content, content_hash)
+         VALUES (?1, (SELECT COALESCE(MAX(version), 0) + 1 FROM history_versions WHERE entry_id = ?1), ?2, ?3)",
+        rusqlite::params![id, previous, previous_hash],
+    )?;
+
+    let character_count = content.chars().count() as i32;
+    let word_count = content.split_whitespace().count() as i32;
+    let line_count = content.lines().count() as i32;
+    let digest = content_digest(content);
+    let StoredContent { content, content_hash } = prepare(content, &load_settings().unwrap_or_default().history_limits)?;
+    let (content, content_zstd) = compress_stored(content, content_hash.is_some())?;
+
+    tx.execute(
+        "UPDATE history SET content = ?2, character_count = ?3, word_count = ?4, line_count = ?5, content_hash = ?6,
+                            compressed = ?7, content_zstd = ?8, content_digest = ?9
+         WHERE id = ?1",
+        rusqlite::params![
+            id,
+            content,
+            character_count,
+            word_count,
+            line_count,
+            content_hash,
+            content_zstd.is_some(),
+            content_zstd,
+            digest
+        ],
+    )?;
+    enqueue(&tx, id)?;
+
+    // Drop the oldest revisions beyond the cap
+    tx.execute(
+        "DELETE FROM history_versions WHERE entry_id = ?1 AND version <= (
+            SELECT MAX(version) FROM history_versions WHERE entry_id = ?1
+         ) - ?2",
+        rusqlite::params![id, MAX_VERSIONS_PER_ENTRY],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Prior revisions of an entry, newest first
+pub fn list_versions(conn: &Connection, id: i64) -> Result<Vec<HistoryEntryVersion>, HistoryError> {
+    let mut stmt = conn.prepare(
+        "SELECT entry_id, version, content, created_at FROM history_versions
+         WHERE entry_id = ?1
+         ORDER BY version DESC",
+    )?;
+
+    let versions = stmt
+        .query_map(rusqlite::params![id], |row| {
+            Ok(HistoryEntryVersion {
+                entry_id: row.get(0)?,
+                version: row.get(1)?,
+                content: row.get(2)?,
+                created_at: row.get(3)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
+    Ok(versions)
+}
+
+/// Make a prior revision current again. The content being replaced is itself
+/// kept as a new revision, so restoring never loses anything.
+pub fn restore_version(conn: &Connection, id: i64, version: i64) -> Result<(), HistoryError> {
+    let (content, hash): (String, Option<String>) = conn
+        .query_row(
+            "SELECT content, content_hash FROM history_versions WHERE entry_id = ?1 AND version = ?2",
+            rusqlite::params![id, version],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or(HistoryError::VersionNotFound(id, version))?;
+    let content = match hash {
+        Some(hash) => load_overflow(&hash)?,
+        None => content,
+    };
+
+    update_entry(conn, id, &content)
+}
+
+/// Record where an entry was published (see paste.rs)
+pub fn set_entry_paste_info(conn: &Connection, id: i64, service: &str, url: &str) -> Result<(), HistoryError> {
+    let updated = conn.execute(
+        "UPDATE history SET paste_service = ?2, paste_url = ?3, paste_published_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        rusqlite::params![id, service, url],
+    )?;
+    if updated == 0 {
+        return Err(HistoryError::EntryNotFound(id));
+    }
+    Ok(())
+}
+
+/// Record the app an entry came from
+pub fn set_entry_source(conn: &Connection, id: i64, source: &FrontmostApp) -> Result<(), HistoryError> {
+    conn.execute(
+        "UPDATE history SET source_app = ?2, source_bundle_id = ?3, source_window_title = ?4 WHERE id = ?1",
+        rusqlite::params![id, source.name, source.bundle_id, source.window_title],
+    )?;
+    Ok(())
+}
+
+/// Attach (or clear with `None`) a generated summary
+pub fn set_entry_summary(conn: &Connection, id: i64, summary: Option<&str>) -> Result<(), HistoryError> {
+    let updated = conn.execute(
+        "UPDATE history SET summary = ?2 WHERE id = ?1",
+        rusqlite::params![id, summary],
+    )?;
+    if updated == 0 {
+        return Err(HistoryError::EntryNotFound(id));
+    }
+    Ok(())
+}
+
+/// Attach (or clear with `None`) a generated title
+pub fn set_entry_title(conn: &Connection, id: i64, title: Option<&str>) -> Result<(), HistoryError> {
+    let updated = conn.execute(
+        "UPDATE history SET title = ?2 WHERE id = ?1",
+        rusqlite::params![id, title],
+    )?;
+    if updated == 0 {
+        return Err(HistoryError::EntryNotFound(id));
+    }
+    Ok(())
+}
+
+/// Add tags to an entry's comma-separated `tags`, skipping ones it already has
+pub fn add_entry_tags(conn: &Connection, id: i64, new_tags: &[String]) -> Result<(), HistoryError> {
+    let current: Option<String> = conn
+        .query_row("SELECT tags FROM history WHERE id = ?1", [id], |row| row.get(0))
+        .optional()?
+        .ok_or(HistoryError::EntryNotFound(id))?;
+    let mut tags: Vec<String> = current
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+    for tag in new_tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    conn.execute(
+        "UPDATE history SET tags = ?2 WHERE id = ?1",
+        rusqlite::params![id, Some(tags.join(",")).filter(|t| !t.is_empty())],
+    )?;
+    Ok(())
+}
+
+/// Set or clear (`None`) when an entry self-destructs. Timestamps are stored in UTC in
+/// the same format as `created_at` so they compare directly against `datetime('now')`.
+pub fn set_entry_expiry(
+    conn: &Connection,
+    id: i64,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<(), HistoryError> {
+    let expires_at = expires_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+    let updated = conn.execute(
+        "UPDATE history SET expires_at = ?2 WHERE id = ?1",
+        rusqlite::params![id, expires_at],
+    )?;
+    if updated == 0 {
+        return Err(HistoryError::EntryNotFound(id));
+    }
+    Ok(())
+}
+
+/// Set or clear (`None`) an entry's reminder. Stored like `expires_at` so due reminders
+/// can be found with a plain comparison against `datetime('now')`.
+pub fn set_entry_reminder(
+    conn: &Connection,
+    id: i64,
+    remind_at: Option<chrono::DateTime<chrono::Utc>>,
+    note: Option<&str>,
+) -> Result<(), HistoryError> {
+    let remind_at = remind_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+    let note = remind_at.as_ref().and(note.map(str::trim).filter(|n| !n.is_empty()));
+    let updated = conn.execute(
+        "UPDATE history SET reminder_at = ?2, reminder_note = ?3 WHERE id = ?1",
+        rusqlite::params![id, remind_at, note],
+    )?;
+    if updated == 0 {
+        return Err(HistoryError::EntryNotFound(id));
+    }
+    Ok(())
+}
+
+/// Entries with a pending reminder, soonest first
+pub fn get_upcoming_reminders(conn: &Connection, limit: u32) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let sql = format!(
+        "SELECT {} FROM history WHERE reminder_at IS NOT NULL ORDER BY reminder_at ASC LIMIT ?1",
+        ENTRY_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let entries = stmt
+        .query_map([limit], map_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
     Ok(entries)
 }
 
+/// Entries whose reminder has passed, with the reminder cleared so it fires only once.
+/// The returned entries still carry the reminder time and note.
+pub fn take_due_reminders(conn: &Connection) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let sql = format!(
+        "SELECT {} FROM history WHERE reminder_at IS NOT NULL AND reminder_at <= datetime('now')
+         ORDER BY reminder_at ASC",
+        ENTRY_COLUMNS
+    );
+    let due = {
+        let mut stmt = conn.prepare(&sql)?;
+        let due = stmt
+            .query_map([], map_entry_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        due
+    };
+
+    if due.is_empty() {
+        return Ok(due);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for entry in &due {
+        tx.execute(
+            "UPDATE history SET reminder_at = NULL, reminder_note = NULL WHERE id = ?1",
+            rusqlite::params![entry.id],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(due)
+}
+
+/// Delete entries whose expiry has passed, along with their versions and attachments.
+/// Returns the ids that were removed.
+pub fn delete_expired_entries(conn: &Connection) -> Result<Vec<i64>, HistoryError> {
+    let ids: Vec<i64> = {
+        let mut stmt = conn.prepare(
+            "SELECT id FROM history WHERE expires_at IS NOT NULL AND expires_at <= datetime('now')",
+        )?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        ids
+    };
+
+    if ids.is_empty() {
+        return Ok(ids);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for id in &ids {
+        tx.execute("DELETE FROM history WHERE id = ?1", rusqlite::params![id])?;
+        tx.execute("DELETE FROM history_versions WHERE entry_id = ?1", rusqlite::params![id])?;
+    }
+    tx.commit()?;
+    collect_orphaned_attachments(conn)?;
+
+    Ok(ids)
+}
+
+/// Move an entry to the trash; its revisions are kept until the trash is purged
 pub fn delete_entry(conn: &Connection, id: i64) -> Result<(), HistoryError> {
-    conn.execute("DELETE FROM history WHERE id = ?1", rusqlite::params![id])?;
+    crate::trash::trash_entries(conn, Some(id))?;
     Ok(())
 }
 
+/// Move every entry to the trash
 pub fn clear_history(conn: &Connection) -> Result<(), HistoryError> {
-    conn.execute("DELETE FROM history", [])?;
+    crate::trash::trash_entries(conn, None)?;
     Ok(())
 }
 
+/// Delete attachment files no longer referenced by any history entry, trashed entry or revision
+pub fn collect_orphaned_attachments(conn: &Connection) -> Result<u32, HistoryError> {
+    let mut stmt = conn.prepare(
+        "SELECT images FROM history WHERE images IS NOT NULL
+         UNION ALL SELECT images FROM history_trash WHERE images IS NOT NULL",
+    )?;
+    let mut referenced: HashSet<String> = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .flat_map(|json| referenced_hashes(json))
+        .collect();
+
+    // Overflowed content lives in the same store
+    let mut stmt = conn.prepare(
+        "SELECT content_hash FROM history WHERE content_hash IS NOT NULL
+         UNION SELECT content_hash FROM history_trash WHERE content_hash IS NOT NULL
+         UNION SELECT content_hash FROM history_versions WHERE content_hash IS NOT NULL",
+    )?;
+    for hash in stmt.query_map([], |row| row.get::<_, String>(0))? {
+        referenced.insert(hash?);
+    }
+
+    Ok(remove_unreferenced(&referenced)?)
+}
+
 pub fn get_stats(conn: &Connection) -> Result<HistoryStats, HistoryError> {
     let mut stmt = conn.prepare(
         "SELECT COUNT(*), COALESCE(SUM(character_count), 0), COALESCE(SUM(word_count), 0) FROM history",
@@ -196,10 +1075,59 @@ pub fn cleanup_old_entries(conn: &Connection, max_entries: u32) -> Result<u32, H
         )",
         rusqlite::params![max_entries],
     )?;
+    conn.execute(
+        "DELETE FROM history_versions WHERE entry_id NOT IN (SELECT id FROM history UNION SELECT id FROM history_trash)",
+        [],
+    )?;
+    collect_orphaned_attachments(conn)?;
 
     Ok(result as u32)
 }
 
+/// Every entry with its full content
 pub fn export_history(conn: &Connection) -> Result<Vec<HistoryEntry>, HistoryError> {
-    get_entries(conn, u32::MAX, 0)
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM history ORDER BY created_at DESC", ENTRY_COLUMNS))?;
+    let mut entries = stmt
+        .query_map([], map_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    for entry in entries.iter_mut().filter(|e| e.content_truncated) {
+        entry.content = get_entry_content(conn, entry.id)?;
+        entry.content_truncated = false;
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_page_cache_is_cleared_by_writes() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        let cache = Arc::new(PageCache::new());
+        let hooked = Arc::clone(&cache);
+        on_history_write(&conn, move || hooked.clear());
+
+        let insert = |content: &str| {
+            conn.execute(
+                "INSERT INTO history (content, character_count, word_count, line_count) VALUES (?1, 0, 0, 0)",
+                [content],
+            )
+            .unwrap();
+        };
+        insert("first");
+        assert_eq!(cache.get_entries(&conn, 10, 0).unwrap().len(), 1);
+
+        // Served from the cache while nothing is written...
+        conn.execute("CREATE TABLE scratch (id INTEGER)", []).unwrap();
+        conn.execute("INSERT INTO scratch (id) VALUES (1)", []).unwrap();
+        assert_eq!(cache.pages.lock().unwrap().len(), 1);
+
+        // ...and read again after a history write
+        insert("second");
+        assert!(cache.pages.lock().unwrap().is_empty());
+        assert_eq!(cache.get_entries(&conn, 10, 0).unwrap().len(), 2);
+    }
 }
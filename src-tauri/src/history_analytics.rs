@@ -0,0 +1,246 @@
+// Dashboard numbers for history: entries over time, top languages and source apps,
+// busiest hours and weekdays, and totals. Everything is aggregated in SQL so the
+// frontend gets a few hundred numbers instead of every row. Times are bucketed in
+// local time; buckets without entries are left out of the timeline.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::history::HistoryError;
+use crate::AppState;
+
+/// Entries in the top languages / source apps lists
+const TOP_N: u32 = 10;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsRange {
+    Day,
+    Week,
+    #[default]
+    Month,
+    Quarter,
+    Year,
+    All,
+}
+
+impl AnalyticsRange {
+    /// SQLite datetime modifier for the start of the range
+    fn since_modifier(self) -> Option<&'static str> {
+        match self {
+            Self::Day => Some("-1 day"),
+            Self::Week => Some("-7 days"),
+            Self::Month => Some("-30 days"),
+            Self::Quarter => Some("-90 days"),
+            Self::Year => Some("-365 days"),
+            Self::All => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    Hour,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    /// strftime format of a bucket label: "2024-05-01 14:00", "2024-05-01", "2024-W18", "2024-05"
+    fn bucket_format(self) -> &'static str {
+        match self {
+            Self::Hour => "%Y-%m-%d %H:00",
+            Self::Day => "%Y-%m-%d",
+            Self::Week => "%Y-W%W",
+            Self::Month => "%Y-%m",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineBucket {
+    pub bucket: String,
+    pub entries: i64,
+    pub characters: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NamedCount {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryAnalytics {
+    pub range: AnalyticsRange,
+    pub granularity: Granularity,
+    pub total_entries: i64,
+    pub total_characters: i64,
+    pub total_words: i64,
+    /// Bytes of content held in the database; overflowed clips count their stored prefix
+    pub total_bytes: i64,
+    /// Entries whose full content lives in the attachment store (see oversize.rs)
+    pub overflowed_entries: i64,
+    pub timeline: Vec<TimelineBucket>,
+    pub top_languages: Vec<NamedCount>,
+    pub top_source_apps: Vec<NamedCount>,
+    /// Entries per local hour of day, 0-23
+    pub busiest_hours: Vec<i64>,
+    /// Entries per local weekday, Sunday first
+    pub busiest_weekdays: Vec<i64>,
+}
+
+/// `WHERE` clause limiting rows to the range; ?1 is the datetime modifier
+fn range_filter(range: AnalyticsRange) -> &'static str {
+    match range.since_modifier() {
+        Some(_) => "WHERE created_at >= datetime('now', ?1)",
+        None => "WHERE ?1 IS NULL",
+    }
+}
+
+fn top_counts(conn: &Connection, column: &str, range: AnalyticsRange) -> Result<Vec<NamedCount>, HistoryError> {
+    let sql = format!(
+        "SELECT {column}, COUNT(*) FROM history {filter} AND {column} IS NOT NULL AND {column} != ''
+         GROUP BY {column} ORDER BY COUNT(*) DESC, {column} LIMIT {limit}",
+        column = column,
+        filter = range_filter(range),
+        limit = TOP_N
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let counts = stmt
+        .query_map([range.since_modifier()], |row| {
+            Ok(NamedCount {
+                name: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(counts)
+}
+
+/// Counts per strftime value ("%H" or "%w") in local time, as a dense vector of `slots`
+fn distribution(conn: &Connection, format: &str, slots: usize, range: AnalyticsRange) -> Result<Vec<i64>, HistoryError> {
+    let sql = format!(
+        "SELECT CAST(strftime('{format}', created_at, 'localtime') AS INTEGER), COUNT(*) FROM history {filter}
+         GROUP BY 1",
+        format = format,
+        filter = range_filter(range)
+    );
+    let mut counts = vec![0; slots];
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([range.since_modifier()], |row| Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, i64>(1)?)))?;
+    for row in rows {
+        if let (Some(slot), count) = row? {
+            if let Some(total) = counts.get_mut(slot as usize) {
+                *total += count;
+            }
+        }
+    }
+    Ok(counts)
+}
+
+pub fn history_analytics(
+    conn: &Connection,
+    range: AnalyticsRange,
+    granularity: Granularity,
+) -> Result<HistoryAnalytics, HistoryError> {
+    let since = range.since_modifier();
+    let filter = range_filter(range);
+
+    let (total_entries, total_characters, total_words, total_bytes, overflowed_entries) = conn.query_row(
+        &format!(
+            "SELECT COUNT(*), COALESCE(SUM(character_count), 0), COALESCE(SUM(word_count), 0),
+                    COALESCE(SUM(length(CAST(content AS BLOB)) + COALESCE(length(content_zstd), 0)), 0), COUNT(content_hash)
+             FROM history {}",
+            filter
+        ),
+        [since],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT strftime('{format}', created_at, 'localtime') AS bucket, COUNT(*), COALESCE(SUM(character_count), 0)
+         FROM history {filter}
+         GROUP BY bucket ORDER BY bucket",
+        format = granularity.bucket_format(),
+        filter = filter
+    ))?;
+    let timeline = stmt
+        .query_map([since], |row| {
+            Ok(TimelineBucket {
+                bucket: row.get(0)?,
+                entries: row.get(1)?,
+                characters: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(HistoryAnalytics {
+        range,
+        granularity,
+        total_entries,
+        total_characters,
+        total_words,
+        total_bytes,
+        overflowed_entries,
+        timeline,
+        top_languages: top_counts(conn, "language", range)?,
+        top_source_apps: top_counts(conn, "source_app", range)?,
+        busiest_hours: distribution(conn, "%H", 24, range)?,
+        busiest_weekdays: distribution(conn, "%w", 7, range)?,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Aggregates for the history dashboard; the last 30 days by day unless specified
+#[tauri::command]
+pub fn get_history_analytics(
+    state: State<AppState>,
+    range: Option<AnalyticsRange>,
+    granularity: Option<Granularity>,
+) -> Result<HistoryAnalytics, AppError> {
+    let conn = state.db.lock()?;
+    history_analytics(&conn, range.unwrap_or_default(), granularity.unwrap_or_default()).map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_analytics() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::history::create_schema(&conn).unwrap();
+        let insert = "INSERT INTO history (content, created_at, character_count, word_count, language, source_app)
+                      VALUES (?1, ?2, ?3, 1, ?4, ?5)";
+        conn.execute(insert, rusqlite::params!["fn main() {}", "2020-01-01 10:00:00", 12, "rust", "Code"]).unwrap();
+        conn.execute(insert, rusqlite::params!["hello", "2020-01-01 11:00:00", 5, None::<String>, "Slack"]).unwrap();
+        conn.execute(insert, rusqlite::params!["let x = 1;", "2020-02-03 09:00:00", 10, "rust", "Code"]).unwrap();
+        conn.execute("INSERT INTO history (content, character_count, word_count) VALUES ('now', 3, 1)", []).unwrap();
+
+        let all = history_analytics(&conn, AnalyticsRange::All, Granularity::Month).unwrap();
+        assert_eq!(all.total_entries, 4);
+        assert_eq!(all.total_characters, 30);
+        assert_eq!(all.total_bytes, 30);
+        assert_eq!(all.timeline.len(), 3);
+        assert_eq!(all.top_languages[0].name, "rust");
+        assert_eq!(all.top_languages[0].count, 2);
+        assert_eq!(all.top_source_apps.len(), 2);
+        assert_eq!(all.busiest_hours.len(), 24);
+        assert_eq!(all.busiest_hours.iter().sum::<i64>(), 4);
+        assert_eq!(all.busiest_weekdays.iter().sum::<i64>(), 4);
+
+        // Only the entry added just now is within the last week
+        let week = history_analytics(&conn, AnalyticsRange::Week, Granularity::Day).unwrap();
+        assert_eq!(week.total_entries, 1);
+        assert!(week.top_languages.is_empty());
+    }
+}
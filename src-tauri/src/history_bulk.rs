@@ -0,0 +1,184 @@
+// Multi-select actions on history: delete, tag and export a set of entries at once. Each
+// runs in a single transaction, so a failure part way (e.g. an entry deleted in another
+// window) leaves every entry as it was instead of half the selection changed.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use crate::error::AppError;
+use crate::history::{self, HistoryEntry, HistoryError};
+use crate::{trash, tray, AppState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkExportFormat {
+    /// An array of full `HistoryEntry` objects
+    Json,
+    Csv,
+    /// One section per entry with the content in a fenced code block
+    Markdown,
+    /// Contents only, separated by blank lines
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkExportSummary {
+    pub path: String,
+    pub entries: usize,
+}
+
+/// Add `tags` to every entry; fails without changing anything if an id doesn't exist
+pub fn tag_entries(conn: &Connection, ids: &[i64], tags: &[String]) -> Result<u32, HistoryError> {
+    let tx = conn.unchecked_transaction()?;
+    for &id in ids {
+        history::add_entry_tags(&tx, id, tags)?;
+    }
+    tx.commit()?;
+    Ok(ids.len() as u32)
+}
+
+/// Full entries in the order of `ids`, read in one transaction so they're consistent
+pub fn load_entries(conn: &Connection, ids: &[i64]) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let tx = conn.unchecked_transaction()?;
+    let entries = ids
+        .iter()
+        .map(|&id| history::get_entry(&tx, id)?.ok_or(HistoryError::EntryNotFound(id)))
+        .collect::<Result<Vec<_>, _>>()?;
+    tx.commit()?;
+    Ok(entries)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A backtick fence longer than any run of backticks in the content
+fn fence_for(content: &str) -> String {
+    let longest = content
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+pub fn render(entries: &[HistoryEntry], format: BulkExportFormat) -> Result<String, serde_json::Error> {
+    Ok(match format {
+        BulkExportFormat::Json => serde_json::to_string_pretty(entries)?,
+        BulkExportFormat::Csv => {
+            let mut out = String::from("id,created_at,title,language,tags,source_app,content\n");
+            for entry in entries {
+                let fields = [
+                    entry.id.to_string(),
+                    entry.created_at.clone(),
+                    entry.title.clone().unwrap_or_default(),
+                    entry.language.clone().unwrap_or_default(),
+                    entry.tags.clone().unwrap_or_default(),
+                    entry.source_app.clone().unwrap_or_default(),
+                    entry.content.clone(),
+                ];
+                out.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+                out.push('\n');
+            }
+            out
+        }
+        BulkExportFormat::Markdown => entries
+            .iter()
+            .map(|entry| {
+                let fence = fence_for(&entry.content);
+                format!(
+                    "## {}\n\n{}{}\n{}\n{}\n",
+                    entry.title.as_deref().unwrap_or(&entry.created_at),
+                    fence,
+                    entry.language.as_deref().unwrap_or(""),
+                    entry.content,
+                    fence
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        BulkExportFormat::Text => entries.iter().map(|e| e.content.as_str()).collect::<Vec<_>>().join("\n\n"),
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Move the entries to the trash; returns how many were deleted
+#[tauri::command]
+pub fn bulk_delete_entries(app: AppHandle, state: State<AppState>, ids: Vec<i64>) -> Result<u32, AppError> {
+    let conn = state.db.lock()?;
+    let deleted = trash::trash_entry_ids(&conn, &ids)?;
+    drop(conn);
+    if deleted > 0 {
+        tray::rebuild(&app);
+    }
+    Ok(deleted)
+}
+
+/// Add tags to every entry, skipping tags an entry already has
+#[tauri::command]
+pub fn bulk_tag_entries(state: State<AppState>, ids: Vec<i64>, tags: Vec<String>) -> Result<u32, AppError> {
+    if tags.iter().all(|t| t.trim().is_empty()) {
+        return Err(AppError::invalid_input("At least one tag is required"));
+    }
+    let conn = state.db.lock()?;
+    tag_entries(&conn, &ids, &tags).map_err(AppError::from)
+}
+
+/// Write the entries, in the order given, to `path`
+#[tauri::command]
+pub fn bulk_export_entries(
+    state: State<AppState>,
+    ids: Vec<i64>,
+    format: BulkExportFormat,
+    path: String,
+) -> Result<BulkExportSummary, AppError> {
+    if ids.is_empty() {
+        return Err(AppError::invalid_input("No entries selected"));
+    }
+    let conn = state.db.lock()?;
+    // Sensitive entries are written masked while the gate is on, like they're listed
+    let entries = crate::for_display(load_entries(&conn, &ids)?);
+    drop(conn);
+    fs::write(Path::new(&path), render(&entries, format)?)?;
+    Ok(BulkExportSummary { path, entries: entries.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_tag_is_atomic_and_export() {
+        let conn = Connection::open_in_memory().unwrap();
+        history::create_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO history (id, content, character_count, word_count, line_count, tags, language)
+             VALUES (1, 'a, \"b\"', 6, 2, 1, 'work', NULL), (2, 'x ``` y', 7, 3, 1, NULL, 'rust')",
+            [],
+        )
+        .unwrap();
+
+        // Entry 3 doesn't exist, so nothing is tagged
+        assert!(tag_entries(&conn, &[1, 2, 3], &["sql".to_string()]).is_err());
+        assert_eq!(tag_entries(&conn, &[1, 2], &["sql".to_string(), "work".to_string()]).unwrap(), 2);
+        let entries = load_entries(&conn, &[2, 1]).unwrap();
+        assert_eq!(entries[0].tags.as_deref(), Some("sql,work"));
+        assert_eq!(entries[1].tags.as_deref(), Some("work,sql"));
+
+        let csv = render(&entries, BulkExportFormat::Csv).unwrap();
+        assert!(csv.ends_with(",\"work,sql\",,\"a, \"\"b\"\"\"\n"));
+        let markdown = render(&entries, BulkExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("````rust\nx ``` y\n````"));
+        assert_eq!(render(&entries, BulkExportFormat::Text).unwrap(), "x ``` y\n\na, \"b\"");
+    }
+}
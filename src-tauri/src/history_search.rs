@@ -0,0 +1,238 @@
+// Structured history search for the filter bar. A `HistoryFilter` becomes one WHERE
+// clause: every field that is set narrows the results, and unset fields are left out
+// of the query entirely so SQLite can use the created_at, language and source_app
+// indexes. Results page by id like get_entries_after.
+
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::history::{map_entry_row, preview_columns, HistoryEntry, HistoryError};
+use crate::AppState;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryFilter {
+    /// Matched like search_entries: content, tags, source app and window title
+    pub query: Option<String>,
+    /// Any of these languages
+    pub languages: Vec<String>,
+    /// All of these user tags
+    pub tags: Vec<String>,
+    /// Any of these smart tags ("url", "json", ...; see classify.rs)
+    pub smart_tags: Vec<String>,
+    /// Saved at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Saved before this time
+    pub to: Option<DateTime<Utc>>,
+    /// Only entries with (true) or without (false) images
+    pub has_images: Option<bool>,
+    /// Character counts, inclusive
+    pub min_length: Option<u32>,
+    pub max_length: Option<u32>,
+    /// App name or bundle id, case-insensitive
+    pub source_app: Option<String>,
+}
+
+/// Same format as `created_at`, so the two compare as strings
+fn db_timestamp(time: &DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+impl HistoryFilter {
+    /// The WHERE clause (without `WHERE`) and its parameters; "1" when nothing is set
+    pub fn to_sql(&self) -> (String, Vec<Value>) {
+        let mut clauses: Vec<String> = Vec::new();
+        let mut params: Vec<Value> = Vec::new();
+        let mut param = |value: Value| {
+            params.push(value);
+            format!("?{}", params.len())
+        };
+
+        if let Some(query) = self.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let p = param(Value::Text(format!("%{}%", query)));
+            clauses.push(format!(
+                "(content LIKE {p} OR tags LIKE {p} OR source_app LIKE {p} OR source_window_title LIKE {p})"
+            ));
+        }
+        if !self.languages.is_empty() {
+            let list: Vec<String> = self
+                .languages
+                .iter()
+                .map(|language| param(Value::Text(language.clone())))
+                .collect();
+            clauses.push(format!("language IN ({})", list.join(", ")));
+        }
+        for tag in self.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            let p = param(Value::Text(format!("%,{},%", tag)));
+            clauses.push(format!("',' || tags || ',' LIKE {}", p));
+        }
+        if !self.smart_tags.is_empty() {
+            let any: Vec<String> = self
+                .smart_tags
+                .iter()
+                .map(|tag| format!("',' || smart_tags || ',' LIKE {}", param(Value::Text(format!("%,{},%", tag)))))
+                .collect();
+            clauses.push(format!("({})", any.join(" OR ")));
+        }
+        if let Some(from) = &self.from {
+            clauses.push(format!("created_at >= {}", param(Value::Text(db_timestamp(from)))));
+        }
+        if let Some(to) = &self.to {
+            clauses.push(format!("created_at < {}", param(Value::Text(db_timestamp(to)))));
+        }
+        match self.has_images {
+            Some(true) => clauses.push("(images IS NOT NULL AND images NOT IN ('', '[]'))".to_string()),
+            Some(false) => clauses.push("(images IS NULL OR images IN ('', '[]'))".to_string()),
+            None => {}
+        }
+        if let Some(min) = self.min_length {
+            clauses.push(format!("character_count >= {}", param(Value::Integer(min.into()))));
+        }
+        if let Some(max) = self.max_length {
+            clauses.push(format!("character_count <= {}", param(Value::Integer(max.into()))));
+        }
+        if let Some(app) = self.source_app.as_deref().filter(|a| !a.is_empty()) {
+            let p = param(Value::Text(app.to_string()));
+            clauses.push(format!(
+                "(source_app = {p} COLLATE NOCASE OR source_bundle_id = {p} COLLATE NOCASE)"
+            ));
+        }
+
+        if clauses.is_empty() {
+            ("1".to_string(), params)
+        } else {
+            (clauses.join(" AND "), params)
+        }
+    }
+}
+
+/// Entries matching `filter` older than `cursor_id` (`None` for the newest), newest first
+pub fn search(
+    conn: &Connection,
+    filter: &HistoryFilter,
+    cursor_id: Option<i64>,
+    limit: u32,
+) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let (clause, mut params) = filter.to_sql();
+    params.push(Value::from(cursor_id));
+    let cursor = params.len();
+    params.push(Value::Integer(limit.into()));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM history
+         WHERE {} AND (?{cursor} IS NULL OR id < ?{cursor})
+         ORDER BY id DESC
+         LIMIT ?{}",
+        preview_columns(),
+        clause,
+        params.len()
+    ))?;
+
+    let entries = stmt
+        .query_map(rusqlite::params_from_iter(params), map_entry_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// How many entries match `filter`
+pub fn count(conn: &Connection, filter: &HistoryFilter) -> Result<i64, HistoryError> {
+    let (clause, params) = filter.to_sql();
+    let count = conn.query_row(
+        &format!("SELECT COUNT(*) FROM history WHERE {}", clause),
+        rusqlite::params_from_iter(params),
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Filtered history, `limit` at a time; pass the last id of a page as `cursor` for the next
+#[tauri::command]
+pub fn search_history_advanced(
+    state: State<AppState>,
+    filter: HistoryFilter,
+    limit: u32,
+    cursor: Option<i64>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    if let (Some(from), Some(to)) = (&filter.from, &filter.to) {
+        if from >= to {
+            return Err(AppError::invalid_input("The start of the date range must be before its end"));
+        }
+    }
+    let conn = state.db.lock()?;
+    Ok(crate::for_display(search(&conn, &filter, cursor, limit)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::create_schema;
+    use chrono::TimeZone;
+
+    fn insert(conn: &Connection, content: &str, language: &str, tags: &str, images: Option<&str>, created_at: &str) {
+        conn.execute(
+            "INSERT INTO history (content, character_count, word_count, line_count, language, tags, images, created_at)
+             VALUES (?1, ?2, 1, 1, ?3, ?4, ?5, ?6)",
+            rusqlite::params![content, content.len(), language, tags, images, created_at],
+        )
+        .unwrap();
+    }
+
+    fn ids(conn: &Connection, filter: &HistoryFilter) -> Vec<i64> {
+        search(conn, filter, None, 50).unwrap().iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn test_filters_combine() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        insert(&conn, "SELECT * FROM users", "sql", "work,db", None, "2026-10-01 09:00:00");
+        insert(&conn, "SELECT 1", "sql", "db", Some("[{\"hash\":\"a\"}]"), "2026-10-08 09:00:00");
+        insert(&conn, "fn main() {}", "rust", "work", Some("[]"), "2026-10-09 09:00:00");
+
+        assert_eq!(ids(&conn, &HistoryFilter::default()), vec![3, 2, 1]);
+
+        let sql = HistoryFilter {
+            languages: vec!["sql".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ids(&conn, &sql), vec![2, 1]);
+
+        let last_week = HistoryFilter {
+            from: Some(Utc.with_ymd_and_hms(2026, 10, 5, 0, 0, 0).unwrap()),
+            to: Some(Utc.with_ymd_and_hms(2026, 10, 12, 0, 0, 0).unwrap()),
+            ..sql.clone()
+        };
+        assert_eq!(ids(&conn, &last_week), vec![2]);
+
+        let tagged = HistoryFilter {
+            tags: vec!["work".to_string(), "db".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ids(&conn, &tagged), vec![1]);
+
+        let with_images = HistoryFilter {
+            has_images: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(ids(&conn, &with_images), vec![2]);
+
+        let long = HistoryFilter {
+            query: Some("select".to_string()),
+            min_length: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(ids(&conn, &long), vec![1]);
+
+        // Keyset paging continues after the cursor
+        assert_eq!(search(&conn, &HistoryFilter::default(), Some(3), 1).unwrap()[0].id, 2);
+        assert_eq!(count(&conn, &sql).unwrap(), 2);
+    }
+}
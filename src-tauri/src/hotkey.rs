@@ -1,4 +1,46 @@
+// Summon hotkeys. Plain shortcuts ("Command+Shift+Space") are registered by the
+// frontend with the global shortcut plugin. Two other forms are handled here:
+//
+// - "DoubleTap+Command": a modifier pressed and released twice on its own, Raycast
+//   style. The plugin only sees key combinations, so this needs a low-level listener:
+//   a CGEventTap on macOS (Input Monitoring permission) and a WH_KEYBOARD_LL hook on
+//   Windows. Other platforms can't use it.
+// - "Control+K Control+S": a two-step chord. The first step is a normal global
+//   shortcut; the second is only registered for CHORD_TIMEOUT after it, so it isn't
+//   taken away from other apps the rest of the time.
+//
+// Either way the backend emits `summon-hotkey` and the frontend toggles the window.
+
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::error::AppError;
+
+pub const SUMMON_EVENT: &str = "summon-hotkey";
+/// Longest a modifier can be held and still count as a tap
+const TAP_MAX_HOLD: Duration = Duration::from_millis(300);
+/// Longest gap between the two taps of a double tap
+const DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(400);
+/// How long the second step of a chord stays registered after the first
+const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Handle used by the key listener and chord handlers to emit SUMMON_EVENT
+static APP: OnceLock<AppHandle> = OnceLock::new();
+/// The double tap being watched for; the listener ignores keys while this is None
+static DOUBLE_TAP: Mutex<Option<DoubleTap>> = Mutex::new(None);
+static LISTENER_STARTED: AtomicBool = AtomicBool::new(false);
+/// The registered chord's two steps
+static CHORD: Mutex<Option<(String, String)>> = Mutex::new(None);
+/// Bumped whenever a chord is armed or finished, so a stale timeout doesn't disarm a newer one
+static CHORD_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// The chord's second step is currently registered by us, not by another Wingman hotkey
+static CHORD_ARMED: AtomicBool = AtomicBool::new(false);
+/// Whether this platform has a key listener for double taps
+const DOUBLE_TAP_SUPPORTED: bool = cfg!(any(target_os = "macos", target_os = "windows"));
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
@@ -35,7 +77,8 @@ pub fn get_default_hotkey() -> String {
     }
 }
 
-pub fn validate_hotkey(shortcut: &str) -> bool {
+/// A single key combination with at least one modifier
+pub fn validate_shortcut(shortcut: &str) -> bool {
     let parts: Vec<&str> = shortcut.split('+').collect();
 
     if parts.is_empty() {
@@ -72,3 +115,446 @@ pub fn validate_hotkey(shortcut: &str) -> bool {
     // Must have at least one modifier
     parts.len() > 1
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    /// Command on macOS, the Windows key elsewhere
+    Command,
+    Control,
+    Alt,
+    Shift,
+}
+
+impl Modifier {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "command" | "cmd" | "super" | "meta" => Some(Modifier::Command),
+            "control" | "ctrl" => Some(Modifier::Control),
+            "alt" | "option" => Some(Modifier::Alt),
+            "shift" => Some(Modifier::Shift),
+            _ => None,
+        }
+    }
+}
+
+/// What a hotkey string describes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyTrigger {
+    /// A regular shortcut for the global shortcut plugin
+    Shortcut(String),
+    /// "DoubleTap+Command"
+    DoubleTap(Modifier),
+    /// "Control+K Control+S": the second shortcut within CHORD_TIMEOUT of the first
+    Chord(String, String),
+}
+
+pub fn parse_hotkey(hotkey: &str) -> Option<HotkeyTrigger> {
+    let hotkey = hotkey.trim();
+    if let Some((prefix, modifier)) = hotkey.split_once('+') {
+        if prefix.eq_ignore_ascii_case("DoubleTap") {
+            return Modifier::parse(modifier).map(HotkeyTrigger::DoubleTap);
+        }
+    }
+    let steps: Vec<&str> = hotkey.split_whitespace().collect();
+    match steps.as_slice() {
+        [shortcut] if validate_shortcut(shortcut) => Some(HotkeyTrigger::Shortcut(shortcut.to_string())),
+        // Identical steps would need the first shortcut registered twice
+        [first, second] if validate_shortcut(first) && validate_shortcut(second) && !first.eq_ignore_ascii_case(second) => {
+            Some(HotkeyTrigger::Chord(first.to_string(), second.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// A plain shortcut, a double tap (where the platform supports it) or a two-step chord
+pub fn validate_hotkey(hotkey: &str) -> bool {
+    match parse_hotkey(hotkey) {
+        Some(HotkeyTrigger::DoubleTap(_)) => DOUBLE_TAP_SUPPORTED,
+        Some(_) => true,
+        None => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent {
+    ModifierDown(Modifier),
+    ModifierUp(Modifier),
+    /// Any other key going down
+    Key,
+}
+
+/// Recognizes a modifier tapped twice with nothing else pressed in between
+#[derive(Debug)]
+pub struct DoubleTap {
+    modifier: Modifier,
+    pressed_at: Option<Instant>,
+    /// Nothing else was pressed while the modifier was down
+    clean: bool,
+    last_tap: Option<Instant>,
+}
+
+impl DoubleTap {
+    pub fn new(modifier: Modifier) -> Self {
+        Self {
+            modifier,
+            pressed_at: None,
+            clean: false,
+            last_tap: None,
+        }
+    }
+
+    /// Returns true when `event` completes a double tap
+    pub fn feed(&mut self, event: KeyEvent, now: Instant) -> bool {
+        match event {
+            KeyEvent::ModifierDown(modifier) if modifier == self.modifier => {
+                // Key repeat sends more downs while held
+                if self.pressed_at.is_none() {
+                    self.pressed_at = Some(now);
+                    self.clean = true;
+                }
+                false
+            }
+            KeyEvent::ModifierUp(modifier) if modifier == self.modifier => {
+                let Some(pressed_at) = self.pressed_at.take() else {
+                    return false;
+                };
+                if !self.clean || now.saturating_duration_since(pressed_at) > TAP_MAX_HOLD {
+                    self.last_tap = None;
+                    return false;
+                }
+                match self.last_tap.take() {
+                    Some(last) if now.saturating_duration_since(last) <= DOUBLE_TAP_WINDOW => true,
+                    _ => {
+                        self.last_tap = Some(now);
+                        false
+                    }
+                }
+            }
+            _ => {
+                self.clean = false;
+                self.last_tap = None;
+                false
+            }
+        }
+    }
+}
+
+fn summon() {
+    if let Some(app) = APP.get() {
+        app.emit(SUMMON_EVENT, ()).ok();
+    }
+}
+
+/// Called by the platform key listener for every key event
+fn handle_key(event: KeyEvent) {
+    let fired = DOUBLE_TAP
+        .lock()
+        .ok()
+        .and_then(|mut double_tap| double_tap.as_mut().map(|d| d.feed(event, Instant::now())))
+        .unwrap_or(false);
+    if fired {
+        summon();
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn start_key_listener() -> Result<(), String> {
+    use core_foundation::base::TCFType;
+    use core_foundation::mach_port::CFMachPortRef;
+    use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop};
+    use core_graphics::event::{
+        CGEventFlags, CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventType,
+        EventField,
+    };
+    use std::ffi::c_void;
+    use std::sync::atomic::AtomicPtr;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventTapEnable(tap: CFMachPortRef, enable: bool);
+    }
+
+    /// The tap's port, so the callback can turn the tap back on
+    static TAP_PORT: AtomicPtr<c_void> = AtomicPtr::new(std::ptr::null_mut());
+
+    let (ready, result) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let tap = CGEventTap::new(
+            CGEventTapLocation::Session,
+            CGEventTapPlacement::HeadInsertEventTap,
+            CGEventTapOptions::ListenOnly,
+            vec![CGEventType::FlagsChanged, CGEventType::KeyDown],
+            |_proxy, event_type, event| {
+                match event_type {
+                    CGEventType::FlagsChanged => {
+                        let modifier = match event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE) {
+                            54 | 55 => Some((Modifier::Command, CGEventFlags::CGEventFlagCommand)),
+                            56 | 60 => Some((Modifier::Shift, CGEventFlags::CGEventFlagShift)),
+                            58 | 61 => Some((Modifier::Alt, CGEventFlags::CGEventFlagAlternate)),
+                            59 | 62 => Some((Modifier::Control, CGEventFlags::CGEventFlagControl)),
+                            // Caps Lock, Fn
+                            _ => None,
+                        };
+                        handle_key(match modifier {
+                            Some((modifier, flag)) if event.get_flags().contains(flag) => KeyEvent::ModifierDown(modifier),
+                            Some((modifier, _)) => KeyEvent::ModifierUp(modifier),
+                            None => KeyEvent::Key,
+                        });
+                    }
+                    // macOS turns the tap off when it thinks a callback is too slow
+                    CGEventType::TapDisabledByTimeout => {
+                        let port = TAP_PORT.load(Ordering::SeqCst);
+                        if !port.is_null() {
+                            log::warn!("Key listener was disabled by a timeout; re-enabling it");
+                            unsafe { CGEventTapEnable(port as CFMachPortRef, true) };
+                        }
+                    }
+                    CGEventType::TapDisabledByUserInput => {}
+                    _ => handle_key(KeyEvent::Key),
+                }
+                None
+            },
+        );
+        let tap = match tap {
+            Ok(tap) => tap,
+            Err(()) => {
+                ready
+                    .send(Err(
+                        "Double-tap hotkeys need Input Monitoring permission (System Settings > Privacy & Security)"
+                            .to_string(),
+                    ))
+                    .ok();
+                return;
+            }
+        };
+        let Ok(source) = tap.mach_port.create_runloop_source(0) else {
+            ready.send(Err("Failed to start the key listener".to_string())).ok();
+            return;
+        };
+        unsafe {
+            CFRunLoop::get_current().add_source(&source, kCFRunLoopCommonModes);
+        }
+        // The tap lives as long as this thread's run loop, so the port stays valid
+        TAP_PORT.store(tap.mach_port.as_concrete_TypeRef() as *mut c_void, Ordering::SeqCst);
+        tap.enable();
+        ready.send(Ok(())).ok();
+        CFRunLoop::run_current();
+    });
+    result.recv().map_err(|e| e.to_string())?
+}
+
+#[cfg(target_os = "windows")]
+fn start_key_listener() -> Result<(), String> {
+    use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetMessageW, SetWindowsHookExW, KBDLLHOOKSTRUCT, MSG, WH_KEYBOARD_LL, WM_KEYDOWN,
+        WM_SYSKEYDOWN,
+    };
+
+    unsafe extern "system" fn hook(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code >= 0 {
+            let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+            let down = matches!(wparam.0 as u32, WM_KEYDOWN | WM_SYSKEYDOWN);
+            let modifier = match info.vkCode {
+                0x5B | 0x5C => Some(Modifier::Command),
+                0xA0 | 0xA1 => Some(Modifier::Shift),
+                0xA2 | 0xA3 => Some(Modifier::Control),
+                0xA4 | 0xA5 => Some(Modifier::Alt),
+                _ => None,
+            };
+            match (modifier, down) {
+                (Some(modifier), true) => handle_key(KeyEvent::ModifierDown(modifier)),
+                (Some(modifier), false) => handle_key(KeyEvent::ModifierUp(modifier)),
+                (None, true) => handle_key(KeyEvent::Key),
+                (None, false) => {}
+            }
+        }
+        CallNextHookEx(None, code, wparam, lparam)
+    }
+
+    let (ready, result) = std::sync::mpsc::channel();
+    std::thread::spawn(move || unsafe {
+        // The hook is called on this thread, which has to keep pumping messages
+        match SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook), None, 0) {
+            Ok(_) => {
+                ready.send(Ok(())).ok();
+                let mut message = MSG::default();
+                while GetMessageW(&mut message, None, 0, 0).as_bool() {}
+            }
+            Err(e) => {
+                ready.send(Err(format!("Failed to start the key listener: {}", e))).ok();
+            }
+        }
+    });
+    result.recv().map_err(|e| e.to_string())?
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn start_key_listener() -> Result<(), String> {
+    Err("Double-tap hotkeys aren't supported on this platform".to_string())
+}
+
+/// Register the chord's second step for CHORD_TIMEOUT. Runs on its own thread:
+/// registering from inside a shortcut handler can deadlock the plugin. If another
+/// Wingman hotkey already holds the second step, that one is left alone.
+fn arm_chord(app: AppHandle, second: String) {
+    let generation = CHORD_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || {
+        let shortcuts = app.global_shortcut();
+        if CHORD_ARMED.swap(false, Ordering::SeqCst) {
+            shortcuts.unregister(second.as_str()).ok();
+        } else if shortcuts.is_registered(second.as_str()) {
+            log::warn!("Not arming the hotkey chord: {} is already a Wingman hotkey", second);
+            return;
+        }
+        let registered = shortcuts.on_shortcut(second.as_str(), |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                CHORD_GENERATION.fetch_add(1, Ordering::SeqCst);
+                summon();
+                disarm_chord(app.clone());
+            }
+        });
+        if let Err(e) = registered {
+            log::warn!("Failed to register the second step of the hotkey chord: {}", e);
+            return;
+        }
+        CHORD_ARMED.store(true, Ordering::SeqCst);
+        std::thread::sleep(CHORD_TIMEOUT);
+        if CHORD_GENERATION.load(Ordering::SeqCst) == generation && CHORD_ARMED.swap(false, Ordering::SeqCst) {
+            app.global_shortcut().unregister(second.as_str()).ok();
+        }
+    });
+}
+
+fn disarm_chord(app: AppHandle) {
+    std::thread::spawn(move || {
+        if !CHORD_ARMED.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let second = CHORD.lock().ok().and_then(|chord| chord.as_ref().map(|(_, second)| second.clone()));
+        if let Some(second) = second {
+            app.global_shortcut().unregister(second.as_str()).ok();
+        }
+    });
+}
+
+fn clear_advanced(app: &AppHandle) {
+    if let Ok(mut double_tap) = DOUBLE_TAP.lock() {
+        *double_tap = None;
+    }
+    let previous = CHORD.lock().ok().and_then(|mut chord| chord.take());
+    if let Some((first, second)) = previous {
+        CHORD_GENERATION.fetch_add(1, Ordering::SeqCst);
+        let shortcuts = app.global_shortcut();
+        shortcuts.unregister(first.as_str()).ok();
+        if CHORD_ARMED.swap(false, Ordering::SeqCst) {
+            shortcuts.unregister(second.as_str()).ok();
+        }
+    }
+}
+
+/// Watch for a double tap or chord summon hotkey. Returns false for plain shortcuts,
+/// which the caller registers itself; any previous double tap or chord is dropped.
+pub fn set_advanced_hotkey(app: &AppHandle, hotkey: Option<&str>) -> Result<bool, String> {
+    APP.get_or_init(|| app.clone());
+
+    // Checked before clearing so a rejected hotkey leaves the current one in place
+    let trigger = match hotkey.filter(|h| !h.trim().is_empty()) {
+        Some(hotkey) if !validate_hotkey(hotkey) => return Err(format!("Invalid hotkey: {}", hotkey)),
+        Some(hotkey) => parse_hotkey(hotkey),
+        None => None,
+    };
+    clear_advanced(app);
+
+    let Some(trigger) = trigger else {
+        return Ok(false);
+    };
+    match trigger {
+        HotkeyTrigger::Shortcut(_) => Ok(false),
+        HotkeyTrigger::DoubleTap(modifier) => {
+            if !LISTENER_STARTED.load(Ordering::SeqCst) {
+                start_key_listener()?;
+                LISTENER_STARTED.store(true, Ordering::SeqCst);
+            }
+            if let Ok(mut double_tap) = DOUBLE_TAP.lock() {
+                *double_tap = Some(DoubleTap::new(modifier));
+            }
+            Ok(true)
+        }
+        HotkeyTrigger::Chord(first, second) => {
+            let armed = second.clone();
+            app.global_shortcut()
+                .on_shortcut(first.as_str(), move |app, _shortcut, event| {
+                    if event.state == ShortcutState::Pressed {
+                        arm_chord(app.clone(), armed.clone());
+                    }
+                })
+                .map_err(|e| format!("Failed to register hotkey: {}", e))?;
+            if let Ok(mut chord) = CHORD.lock() {
+                *chord = Some((first, second));
+            }
+            Ok(true)
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Register `hotkey` if it's a double tap or chord (see the top of this file); returns
+/// false for a plain shortcut, which the frontend registers with the plugin
+#[tauri::command]
+pub fn set_summon_hotkey(app: AppHandle, hotkey: Option<String>) -> Result<bool, AppError> {
+    set_advanced_hotkey(&app, hotkey.as_deref()).map_err(AppError::invalid_input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hotkey() {
+        assert_eq!(
+            parse_hotkey("Command+Shift+Space"),
+            Some(HotkeyTrigger::Shortcut("Command+Shift+Space".to_string()))
+        );
+        assert_eq!(parse_hotkey("DoubleTap+Cmd"), Some(HotkeyTrigger::DoubleTap(Modifier::Command)));
+        assert_eq!(validate_hotkey("DoubleTap+Cmd"), DOUBLE_TAP_SUPPORTED);
+        assert_eq!(
+            parse_hotkey("Control+K Control+S"),
+            Some(HotkeyTrigger::Chord("Control+K".to_string(), "Control+S".to_string()))
+        );
+        assert!(!validate_hotkey("DoubleTap+K"));
+        assert!(!validate_hotkey("Control+K Control+K"));
+        assert!(!validate_hotkey("Control+K Control+S Control+D"));
+        assert!(!validate_hotkey("Space"));
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut tap = DoubleTap::new(Modifier::Command);
+        let down = KeyEvent::ModifierDown(Modifier::Command);
+        let up = KeyEvent::ModifierUp(Modifier::Command);
+
+        assert!(!tap.feed(down, at(0)));
+        assert!(!tap.feed(up, at(80)));
+        assert!(!tap.feed(down, at(200)));
+        assert!(tap.feed(up, at(260)));
+
+        // Too slow between taps
+        assert!(!tap.feed(down, at(1000)));
+        assert!(!tap.feed(up, at(1050)));
+        assert!(!tap.feed(down, at(1600)));
+        assert!(!tap.feed(up, at(1650)));
+
+        // Used as a modifier for another key in between
+        assert!(!tap.feed(down, at(1700)));
+        assert!(!tap.feed(KeyEvent::Key, at(1720)));
+        assert!(!tap.feed(up, at(1750)));
+        assert!(!tap.feed(down, at(1800)));
+        assert!(!tap.feed(up, at(1850)));
+    }
+}
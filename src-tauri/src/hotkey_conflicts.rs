@@ -0,0 +1,375 @@
+// Hotkey conflict detection for the settings UI, so a shortcut that's already taken is
+// flagged before it's saved. A shortcut is checked against:
+//
+// - well-known system shortcuts of the current platform and popular launchers, by name
+// - on macOS, the enabled shortcuts in System Settings > Keyboard Shortcuts, read with
+//   Carbon's CopySymbolicHotKeys (these catch remapped and less known ones, unnamed)
+// - Wingman's own hotkeys: the summon hotkey, rule hotkeys and the profile and window
+//   preset switchers
+// - a trial registration, which fails when another app holds the shortcut (reliable on
+//   Windows; macOS lets apps register the same shortcut)
+//
+// Shortcuts are compared as a set of modifiers plus a key, so "Cmd+Option+K" and
+// "Alt+Command+K" match.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+use crate::error::AppError;
+use crate::hotkey::{parse_hotkey, HotkeyTrigger};
+use crate::rules::{get_rules, Trigger};
+use crate::storage::load_settings;
+use crate::AppState;
+
+const COMMAND: u8 = 1;
+const CONTROL: u8 = 2;
+const ALT: u8 = 4;
+const SHIFT: u8 = 8;
+
+#[cfg(target_os = "macos")]
+const SYSTEM_SHORTCUTS: &[(&str, &str)] = &[
+    ("Command+Space", "Spotlight"),
+    ("Command+Alt+Space", "Finder search window"),
+    ("Control+Space", "Select the previous input source"),
+    ("Control+Alt+Space", "Select the next input source"),
+    ("Control+Command+Space", "Character Viewer (emoji)"),
+    ("Command+Tab", "App switcher"),
+    ("Command+Grave", "Switch windows of the same app"),
+    ("Command+Q", "Quit the frontmost app"),
+    ("Command+H", "Hide the frontmost app"),
+    ("Command+M", "Minimize the frontmost window"),
+    ("Command+W", "Close the frontmost window"),
+    ("Command+Alt+Escape", "Force Quit Applications"),
+    ("Control+Command+Q", "Lock Screen"),
+    ("Command+Shift+3", "Screenshot of the screen"),
+    ("Command+Shift+4", "Screenshot of a selection"),
+    ("Command+Shift+5", "Screenshot and recording options"),
+    ("Command+Alt+D", "Show or hide the Dock"),
+    ("Control+ArrowUp", "Mission Control"),
+    ("Control+ArrowDown", "Application windows"),
+    ("Control+ArrowLeft", "Move a space left"),
+    ("Control+ArrowRight", "Move a space right"),
+    ("Command+Shift+Slash", "Help menu search"),
+];
+
+#[cfg(target_os = "windows")]
+const SYSTEM_SHORTCUTS: &[(&str, &str)] = &[
+    ("Super+L", "Lock the PC"),
+    ("Super+D", "Show the desktop"),
+    ("Super+E", "File Explorer"),
+    ("Super+R", "Run dialog"),
+    ("Super+I", "Settings"),
+    ("Super+S", "Search"),
+    ("Super+A", "Quick Settings"),
+    ("Super+V", "Clipboard history"),
+    ("Super+Period", "Emoji panel"),
+    ("Super+Space", "Switch input language"),
+    ("Super+Tab", "Task View"),
+    ("Super+Shift+S", "Snipping Tool"),
+    ("Alt+Tab", "App switcher"),
+    ("Alt+F4", "Close the window"),
+    ("Control+Shift+Escape", "Task Manager"),
+    ("Control+Alt+Delete", "Security options"),
+];
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const SYSTEM_SHORTCUTS: &[(&str, &str)] = &[
+    ("Super+A", "Show applications"),
+    ("Super+L", "Lock screen"),
+    ("Super+Space", "Switch input source"),
+    ("Super+V", "Notification list"),
+    ("Alt+F2", "Run a command"),
+    ("Alt+Tab", "App switcher"),
+    ("Control+Alt+T", "Terminal"),
+    ("Control+Alt+Delete", "Log out"),
+];
+
+/// Default shortcuts of apps people commonly run alongside Wingman
+#[cfg(target_os = "macos")]
+const APP_SHORTCUTS: &[(&str, &str)] = &[
+    ("Alt+Space", "Alfred, Raycast or ChatGPT"),
+    ("Command+Shift+Space", "1Password Quick Access"),
+    ("Command+Shift+V", "Paste and Match Style in most apps"),
+    ("Control+Alt+Space", "Raycast AI"),
+    ("Command+Alt+Shift+V", "Paste without formatting"),
+];
+
+#[cfg(not(target_os = "macos"))]
+const APP_SHORTCUTS: &[(&str, &str)] = &[
+    ("Alt+Space", "PowerToys Run or ChatGPT"),
+    ("Control+Shift+Space", "1Password Quick Access"),
+    ("Control+Shift+V", "Paste without formatting in most apps"),
+    ("Super+Shift+C", "PowerToys Color Picker"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// A shortcut of the operating system
+    System,
+    /// The default of a popular app, which may or may not be installed
+    App,
+    /// Another Wingman hotkey
+    Wingman,
+    /// The trial registration failed: some running app holds it
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyConflict {
+    pub kind: ConflictKind,
+    /// Who uses the shortcut, e.g. "Spotlight"
+    pub owner: String,
+    /// Sentence for the settings UI, e.g. "Command+Space is taken by Spotlight"
+    pub message: String,
+}
+
+/// A shortcut reduced to what identifies it: a modifier bitmask and a key name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combo {
+    modifiers: u8,
+    key: String,
+}
+
+impl Combo {
+    pub fn parse(shortcut: &str) -> Option<Self> {
+        let parts: Vec<&str> = shortcut.split('+').map(str::trim).collect();
+        let (key, modifier_names) = parts.split_last()?;
+        let mut modifiers = 0;
+        for name in modifier_names {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "command" | "cmd" | "super" | "meta" => COMMAND,
+                "control" | "ctrl" => CONTROL,
+                "alt" | "option" => ALT,
+                "shift" => SHIFT,
+                _ => return None,
+            };
+        }
+        let key = match key.to_ascii_lowercase().as_str() {
+            "return" => "ENTER".to_string(),
+            "esc" => "ESCAPE".to_string(),
+            "up" => "ARROWUP".to_string(),
+            "down" => "ARROWDOWN".to_string(),
+            "left" => "ARROWLEFT".to_string(),
+            "right" => "ARROWRIGHT".to_string(),
+            "backquote" => "GRAVE".to_string(),
+            _ => key.to_ascii_uppercase(),
+        };
+        Some(Self { modifiers, key })
+    }
+}
+
+/// Enabled shortcuts from System Settings > Keyboard > Keyboard Shortcuts
+#[cfg(target_os = "macos")]
+fn symbolic_hotkeys() -> Vec<Combo> {
+    use core_foundation::array::{CFArray, CFArrayRef};
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn CopySymbolicHotKeys(hotkeys: *mut CFArrayRef) -> i32;
+    }
+
+    // Carbon modifier flags
+    const CMD_KEY: i64 = 0x100;
+    const SHIFT_KEY: i64 = 0x200;
+    const OPTION_KEY: i64 = 0x800;
+    const CONTROL_KEY: i64 = 0x1000;
+
+    let mut array: CFArrayRef = std::ptr::null();
+    if unsafe { CopySymbolicHotKeys(&mut array) } != 0 || array.is_null() {
+        return Vec::new();
+    }
+    let hotkeys: CFArray<CFDictionary<CFString, CFType>> = unsafe { CFArray::wrap_under_create_rule(array) };
+    let code_key = CFString::from_static_string("kHISymbolicHotKeyCode");
+    let modifiers_key = CFString::from_static_string("kHISymbolicHotKeyModifiers");
+    let enabled_key = CFString::from_static_string("kHISymbolicHotKeyEnabled");
+
+    hotkeys
+        .iter()
+        .filter_map(|hotkey| {
+            let enabled = hotkey.find(&enabled_key)?.downcast::<CFBoolean>().map(bool::from)?;
+            let code = hotkey.find(&code_key)?.downcast::<CFNumber>()?.to_i64()?;
+            let flags = hotkey.find(&modifiers_key)?.downcast::<CFNumber>()?.to_i64()?;
+            if !enabled {
+                return None;
+            }
+            let mut modifiers = 0;
+            for (flag, modifier) in [(CMD_KEY, COMMAND), (SHIFT_KEY, SHIFT), (OPTION_KEY, ALT), (CONTROL_KEY, CONTROL)] {
+                if flags & flag != 0 {
+                    modifiers |= modifier;
+                }
+            }
+            Some(Combo {
+                modifiers,
+                key: mac_key_name(code)?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn symbolic_hotkeys() -> Vec<Combo> {
+    Vec::new()
+}
+
+/// Key names (as Combo stores them) for macOS ANSI virtual key codes
+#[cfg(target_os = "macos")]
+fn mac_key_name(code: i64) -> Option<&'static str> {
+    Some(match code {
+        0 => "A", 1 => "S", 2 => "D", 3 => "F", 4 => "H", 5 => "G", 6 => "Z", 7 => "X",
+        8 => "C", 9 => "V", 11 => "B", 12 => "Q", 13 => "W", 14 => "E", 15 => "R", 16 => "Y",
+        17 => "T", 18 => "1", 19 => "2", 20 => "3", 21 => "4", 22 => "6", 23 => "5", 24 => "EQUAL",
+        25 => "9", 26 => "7", 27 => "MINUS", 28 => "8", 29 => "0", 30 => "BRACKETRIGHT", 31 => "O",
+        32 => "U", 33 => "BRACKETLEFT", 34 => "I", 35 => "P", 36 => "ENTER", 37 => "L", 38 => "J",
+        39 => "QUOTE", 40 => "K", 41 => "SEMICOLON", 42 => "BACKSLASH", 43 => "COMMA", 44 => "SLASH",
+        45 => "N", 46 => "M", 47 => "PERIOD", 48 => "TAB", 49 => "SPACE", 50 => "GRAVE",
+        51 => "BACKSPACE", 53 => "ESCAPE", 96 => "F5", 97 => "F6", 98 => "F7", 99 => "F3",
+        100 => "F8", 101 => "F9", 103 => "F11", 109 => "F10", 111 => "F12", 115 => "HOME",
+        116 => "PAGEUP", 117 => "DELETE", 118 => "F4", 119 => "END", 120 => "F2", 121 => "PAGEDOWN",
+        122 => "F1", 123 => "ARROWLEFT", 124 => "ARROWRIGHT", 125 => "ARROWDOWN", 126 => "ARROWUP",
+        _ => return None,
+    })
+}
+
+/// Wingman's hotkeys as (slot, shortcut, what it does). Slots are "summon",
+/// "profile_switch", "window_preset" and "rule:<id>".
+fn wingman_hotkeys(app: &AppHandle) -> Vec<(String, String, String)> {
+    let mut hotkeys = Vec::new();
+    if let Ok(settings) = load_settings() {
+        hotkeys.push(("summon".to_string(), settings.hotkey, "Wingman's summon hotkey".to_string()));
+        if let Some(hotkey) = settings.profile_switch_hotkey {
+            hotkeys.push(("profile_switch".to_string(), hotkey, "Wingman's profile switcher".to_string()));
+        }
+        if let Some(hotkey) = settings.window_preset_hotkey {
+            hotkeys.push(("window_preset".to_string(), hotkey, "Wingman's window preset cycler".to_string()));
+        }
+    }
+    let state = app.state::<AppState>();
+    if let Ok(conn) = state.db.lock() {
+        for rule in get_rules(&conn).unwrap_or_default() {
+            if let Trigger::Hotkey { shortcut } = rule.trigger {
+                hotkeys.push((format!("rule:{}", rule.id), shortcut, format!("the Wingman rule \"{}\"", rule.name)));
+            }
+        }
+    }
+    hotkeys
+}
+
+/// Conflicts of `shortcut` against the built-in tables and `taken` (shortcut, owner) pairs
+pub fn find_conflicts(shortcut: &str, taken: &[(String, String)], system: &[Combo]) -> Vec<HotkeyConflict> {
+    let Some(combo) = Combo::parse(shortcut) else {
+        return Vec::new();
+    };
+    let matches = |other: &str| Combo::parse(other).as_ref() == Some(&combo);
+    let conflict = |kind, owner: &str| HotkeyConflict {
+        kind,
+        owner: owner.to_string(),
+        message: format!("{} is taken by {}", shortcut, owner),
+    };
+
+    let mut conflicts: Vec<HotkeyConflict> = SYSTEM_SHORTCUTS
+        .iter()
+        .filter(|(other, _)| matches(other))
+        .map(|(_, owner)| conflict(ConflictKind::System, owner))
+        .collect();
+    if conflicts.is_empty() && system.contains(&combo) {
+        conflicts.push(conflict(ConflictKind::System, "a macOS keyboard shortcut"));
+    }
+    conflicts.extend(
+        APP_SHORTCUTS
+            .iter()
+            .filter(|(other, _)| matches(other))
+            .map(|(_, owner)| HotkeyConflict {
+                message: format!("{} is the default shortcut of {}", shortcut, owner),
+                ..conflict(ConflictKind::App, owner)
+            }),
+    );
+    conflicts.extend(
+        taken
+            .iter()
+            .filter(|(other, _)| matches(other))
+            .map(|(_, owner)| conflict(ConflictKind::Wingman, owner)),
+    );
+    conflicts
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Everything `shortcut` would clash with; empty when it's free. `editing` is the slot
+/// being changed (see wingman_hotkeys), whose current value isn't a conflict. For a chord
+/// the first step is checked, since the second is only held briefly; double taps can't clash.
+#[tauri::command]
+pub fn check_hotkey_conflicts(
+    app: AppHandle,
+    shortcut: String,
+    editing: Option<String>,
+) -> Result<Vec<HotkeyConflict>, AppError> {
+    let step = match parse_hotkey(&shortcut) {
+        Some(HotkeyTrigger::Shortcut(step)) | Some(HotkeyTrigger::Chord(step, _)) => step,
+        Some(HotkeyTrigger::DoubleTap(_)) => return Ok(Vec::new()),
+        None => return Err(AppError::invalid_input(format!("Invalid hotkey: {}", shortcut))),
+    };
+
+    let taken: Vec<(String, String)> = wingman_hotkeys(&app)
+        .into_iter()
+        .filter(|(slot, _, _)| Some(slot) != editing.as_ref())
+        .map(|(_, shortcut, owner)| (shortcut, owner))
+        .collect();
+    let mut conflicts = find_conflicts(&step, &taken, &symbolic_hotkeys());
+
+    // Ours if already registered; otherwise see whether another app holds it
+    let shortcuts = app.global_shortcut();
+    if !shortcuts.is_registered(step.as_str()) {
+        match shortcuts.register(step.as_str()) {
+            Ok(()) => {
+                shortcuts.unregister(step.as_str()).ok();
+            }
+            Err(e) => {
+                log::info!("Trial registration of {} failed: {}", step, e);
+                conflicts.push(HotkeyConflict {
+                    kind: ConflictKind::Unavailable,
+                    owner: "another app".to_string(),
+                    message: format!("{} is already registered by another app", step),
+                });
+            }
+        }
+    }
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combo_ignores_order_and_aliases() {
+        assert_eq!(Combo::parse("Cmd+Option+K"), Combo::parse("Alt+Command+k"));
+        assert_ne!(Combo::parse("Command+K"), Combo::parse("Command+Shift+K"));
+        assert_eq!(Combo::parse("Ctrl+Esc"), Combo::parse("Control+Escape"));
+        assert_eq!(Combo::parse("Hyper+K"), None);
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let taken = vec![("Alt+Shift+P".to_string(), "Wingman's profile switcher".to_string())];
+        let conflicts = find_conflicts("Shift+Option+P", &taken, &[]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::Wingman);
+        assert_eq!(conflicts[0].message, "Shift+Option+P is taken by Wingman's profile switcher");
+
+        let (system, _) = SYSTEM_SHORTCUTS[0];
+        assert_eq!(find_conflicts(system, &[], &[])[0].kind, ConflictKind::System);
+
+        let remapped = Combo::parse("Control+Alt+Shift+F12").unwrap();
+        assert_eq!(find_conflicts("Control+Alt+Shift+F12", &[], &[remapped]).len(), 1);
+        assert!(find_conflicts("Control+Alt+Shift+F11", &[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,434 @@
+// Shared factory for outgoing HTTP clients. Everything that talks to the network builds
+// its reqwest client here so the proxy and TLS settings apply everywhere:
+//
+// - Proxy: the system proxy (HTTP_PROXY/HTTPS_PROXY/NO_PROXY and the OS configuration)
+//   by default, a direct connection, or a manual proxy with optional basic auth. The
+//   proxy password is kept in the keychain, never in settings.json.
+// - Custom CA: a PEM bundle trusted on top of the built-in roots, for TLS-intercepting
+//   proxies.
+// - Pinning: SPKI SHA-256 pins for the Supabase endpoints (license, Pro, AI proxy).
+//   Supabase clients come from `supabase_builder`; once the chain has been verified as
+//   usual, some certificate in it must also carry a pinned key.
+//
+// In offline mode (see offline.rs) no client is built at all.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+use crate::credentials::{delete_credential, get_credential, store_credential};
+use crate::error::AppError;
+use crate::offline;
+use crate::storage::{load_settings, ProxyMode, ProxySettings, TlsSettings};
+
+const PASSWORD_CREDENTIAL: &str = "proxy_password";
+/// Start of the verifier's error, so `describe` can find it under reqwest's wrapping
+const PIN_MISMATCH: &str = "Certificate pinning failed";
+
+#[derive(Error, Debug)]
+pub enum HttpClientError {
+    #[error("Invalid proxy: {0}")]
+    InvalidProxy(String),
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+    #[error("Couldn't load the CA bundle at {path}: {reason}")]
+    CaBundle { path: String, reason: String },
+    #[error("Invalid certificate pin \"{0}\": expected sha256/ followed by a base64 SHA-256 hash")]
+    InvalidPin(String),
+    #[error("TLS setup failed: {0}")]
+    Tls(String),
+    #[error("Wingman is in offline mode")]
+    Offline,
+    #[error("Failed to create HTTP client: {0}")]
+    Build(#[from] reqwest::Error),
+}
+
+/// Settings with the keychain password, CA bundle and pins loaded, so building a client
+/// doesn't touch the keychain or the disk
+#[derive(Clone)]
+struct Resolved {
+    proxy: ProxySettings,
+    password: Option<String>,
+    ca_certs: Vec<CertificateDer<'static>>,
+    pins: Vec<[u8; 32]>,
+}
+
+static RESOLVED: Mutex<Option<Resolved>> = Mutex::new(None);
+
+fn load_ca_bundle(path: &str) -> Result<Vec<CertificateDer<'static>>, HttpClientError> {
+    let error = |reason: String| HttpClientError::CaBundle {
+        path: path.to_string(),
+        reason,
+    };
+    let pem = std::fs::read(path).map_err(|e| error(e.to_string()))?;
+    let certs = CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| error(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(error("no PEM certificates found".to_string()));
+    }
+    Ok(certs)
+}
+
+/// A pin as "sha256/<base64>" (the prefix is optional)
+fn parse_pin(pin: &str) -> Result<[u8; 32], HttpClientError> {
+    let trimmed = pin.trim();
+    let encoded = trimmed.strip_prefix("sha256/").unwrap_or(trimmed);
+    STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|hash| <[u8; 32]>::try_from(hash).ok())
+        .ok_or_else(|| HttpClientError::InvalidPin(pin.to_string()))
+}
+
+fn resolve(proxy: ProxySettings, tls: &TlsSettings) -> Result<Resolved, HttpClientError> {
+    let password = if proxy.mode == ProxyMode::Manual && !proxy.username.is_empty() {
+        get_credential(PASSWORD_CREDENTIAL.to_string()).map_err(|e| HttpClientError::Keychain(e.message))?
+    } else {
+        None
+    };
+    let ca_certs = match tls.ca_bundle_path.trim() {
+        "" => Vec::new(),
+        path => load_ca_bundle(path)?,
+    };
+    let pins = tls
+        .supabase_pins
+        .iter()
+        .filter(|pin| !pin.trim().is_empty())
+        .map(|pin| parse_pin(pin))
+        .collect::<Result<_, _>>()?;
+    Ok(Resolved {
+        proxy,
+        password,
+        ca_certs,
+        pins,
+    })
+}
+
+fn current() -> Result<Resolved, HttpClientError> {
+    if offline::is_enabled() {
+        return Err(HttpClientError::Offline);
+    }
+    let mut cached = RESOLVED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(resolved) = cached.as_ref() {
+        return Ok(resolved.clone());
+    }
+    let settings = load_settings().unwrap_or_default();
+    let resolved = resolve(settings.proxy, &settings.tls)?;
+    *cached = Some(resolved.clone());
+    Ok(resolved)
+}
+
+/// The proxy URL for a manual server, which may be given as host:port
+fn proxy_url(server: &str) -> Result<String, HttpClientError> {
+    let server = server.trim();
+    if server.is_empty() {
+        return Err(HttpClientError::InvalidProxy("No proxy server set".to_string()));
+    }
+    if server.contains("://") {
+        Ok(server.to_string())
+    } else {
+        Ok(format!("http://{}", server))
+    }
+}
+
+fn manual_proxy(settings: &ProxySettings, password: Option<&str>) -> Result<Proxy, HttpClientError> {
+    let url = proxy_url(&settings.server)?;
+    let mut proxy = Proxy::all(&url).map_err(|e| HttpClientError::InvalidProxy(format!("{}: {}", url, e)))?;
+    if !settings.username.is_empty() {
+        proxy = proxy.basic_auth(&settings.username, password.unwrap_or_default());
+    }
+    Ok(proxy.no_proxy(NoProxy::from_string(&settings.bypass)))
+}
+
+/// The proxy and CA settings as a function over a builder. Everything that can fail is
+/// done up front, so builders made elsewhere (the updater plugin's) can take them too.
+fn settings_for(
+    resolved: &Resolved,
+) -> Result<impl Fn(ClientBuilder) -> ClientBuilder + Send + Sync + 'static, HttpClientError> {
+    let certs = resolved
+        .ca_certs
+        .iter()
+        .map(|cert| reqwest::Certificate::from_der(cert))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mode = resolved.proxy.mode;
+    let proxy = match mode {
+        ProxyMode::Manual => Some(manual_proxy(&resolved.proxy, resolved.password.as_deref())?),
+        ProxyMode::System | ProxyMode::Direct => None,
+    };
+    Ok(move |mut builder: ClientBuilder| {
+        for cert in &certs {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        match (mode, &proxy) {
+            (ProxyMode::Direct, _) => builder.no_proxy(),
+            (_, Some(proxy)) => builder.proxy(proxy.clone()),
+            _ => builder,
+        }
+    })
+}
+
+fn apply(builder: ClientBuilder, resolved: &Resolved) -> Result<ClientBuilder, HttpClientError> {
+    Ok(settings_for(resolved)?(builder))
+}
+
+// ============================================================================
+// Certificate pinning
+// ============================================================================
+
+/// Split the DER element at the start of `input` into its contents and what follows
+fn der_element(input: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (&first, rest) = input.get(1..)?.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count].iter().fold(0usize, |len, &b| (len << 8) | b as usize);
+        (len, &rest[count..])
+    };
+    (rest.len() >= len).then(|| rest.split_at(len))
+}
+
+/// The DER SubjectPublicKeyInfo of a certificate, which is what pins hash
+fn spki(cert: &[u8]) -> Option<&[u8]> {
+    let (certificate, _) = der_element(cert)?;
+    let (tbs, _) = der_element(certificate)?;
+    let mut fields = tbs;
+    // The version is optional and tagged [0]
+    if fields.first() == Some(&0xA0) {
+        fields = der_element(fields)?.1;
+    }
+    // serialNumber, signature, issuer, validity, subject
+    for _ in 0..5 {
+        fields = der_element(fields)?.1;
+    }
+    let (_, rest) = der_element(fields)?;
+    Some(&fields[..fields.len() - rest.len()])
+}
+
+fn spki_hash(cert: &[u8]) -> Option<[u8; 32]> {
+    spki(cert).map(|spki| Sha256::digest(spki).into())
+}
+
+/// The usual WebPKI checks, then a pin check over the whole chain
+#[derive(Debug)]
+struct PinnedVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self
+            .inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+        let pinned = std::iter::once(end_entity)
+            .chain(intermediates)
+            .filter_map(|cert| spki_hash(cert))
+            .any(|hash| self.pins.contains(&hash));
+        if pinned {
+            Ok(verified)
+        } else {
+            Err(rustls::Error::General(format!(
+                "{} for {}: no certificate in the chain has a pinned public key. If a TLS-inspecting proxy is in the way, remove the pins in settings.",
+                PIN_MISMATCH,
+                server_name.to_str()
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+fn pinned_tls_config(resolved: &Resolved) -> Result<rustls::ClientConfig, HttpClientError> {
+    let tls_error = |e: &dyn std::fmt::Display| HttpClientError::Tls(e.to_string());
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    for cert in &resolved.ca_certs {
+        roots.add(cert.clone()).map_err(|e| tls_error(&e))?;
+    }
+    let inner = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+        .build()
+        .map_err(|e| tls_error(&e))?;
+
+    let mut config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| tls_error(&e))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedVerifier {
+            inner,
+            pins: resolved.pins.clone(),
+        }))
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+// ============================================================================
+// Clients
+// ============================================================================
+
+/// A client builder with the proxy and CA settings applied; callers add timeouts and the like
+pub fn builder() -> Result<ClientBuilder, HttpClientError> {
+    apply(reqwest::Client::builder(), &current()?)
+}
+
+/// The proxy and CA settings for a client builder this module doesn't create
+pub fn settings() -> Result<impl Fn(ClientBuilder) -> ClientBuilder + Send + Sync + 'static, HttpClientError> {
+    settings_for(&current()?)
+}
+
+/// Like `builder`, plus the Supabase pins when any are set
+pub fn supabase_builder() -> Result<ClientBuilder, HttpClientError> {
+    let resolved = current()?;
+    let builder = apply(reqwest::Client::builder(), &resolved)?;
+    if resolved.pins.is_empty() {
+        return Ok(builder);
+    }
+    Ok(builder.use_preconfigured_tls(pinned_tls_config(&resolved)?))
+}
+
+/// A Supabase client with reqwest's defaults otherwise
+pub fn supabase_client() -> Result<reqwest::Client, HttpClientError> {
+    Ok(supabase_builder()?.build()?)
+}
+
+/// A request error's message. reqwest reports a failed pin check only as "error sending
+/// request", so the verifier's explanation is dug out of the error chain.
+pub fn describe(error: &reqwest::Error) -> String {
+    let mut source = std::error::Error::source(error);
+    while let Some(inner) = source {
+        let message = inner.to_string();
+        if let Some(start) = message.find(PIN_MISMATCH) {
+            return message[start..].to_string();
+        }
+        source = inner.source();
+    }
+    error.to_string()
+}
+
+/// Pick up changed proxy or TLS settings. They're checked before they're accepted.
+pub fn configure(proxy: &ProxySettings, tls: &TlsSettings) -> Result<(), String> {
+    let resolved = resolve(proxy.clone(), tls).map_err(|e| e.to_string())?;
+    apply(reqwest::Client::builder(), &resolved)
+        .and_then(|builder| Ok(builder.build()?))
+        .map_err(|e| e.to_string())?;
+    if !resolved.pins.is_empty() {
+        pinned_tls_config(&resolved).map_err(|e| e.to_string())?;
+    }
+    *RESOLVED.lock().unwrap_or_else(|e| e.into_inner()) = Some(resolved);
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Store the manual proxy's password in the keychain, or remove it when empty
+#[tauri::command]
+pub fn set_proxy_password(password: Option<String>) -> Result<(), AppError> {
+    match password.filter(|p| !p.is_empty()) {
+        Some(password) => store_credential(PASSWORD_CREDENTIAL.to_string(), password)?,
+        None => delete_credential(PASSWORD_CREDENTIAL.to_string())?,
+    }
+    // Resolved again, with the new password, on the next request
+    RESOLVED.lock()?.take();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CERT: &str = "-----BEGIN CERTIFICATE-----
+MIIBejCCASGgAwIBAgIURo1Fi1Vf61fETmZhfp1AO5EjR+UwCgYIKoZIzj0EAwIw
+EzERMA8GA1UEAwwIcGluLnRlc3QwHhcNMjYxMDE2MTg1MjQwWhcNMzYxMDEzMTg1
+MjQwWjATMREwDwYDVQQDDAhwaW4udGVzdDBZMBMGByqGSM49AgEGCCqGSM49AwEH
+A0IABDmkc5ekadanngkzg96R0K6h/xt5jRpxLS9ydtlv9Ymj6tuZQPiXvAv+4eAu
+YENi9oOWDtC2Id/Sg7FjTcloXMajUzBRMB0GA1UdDgQWBBSh8Dygefd3g81OoOI/
+nJVxcRfqnjAfBgNVHSMEGDAWgBSh8Dygefd3g81OoOI/nJVxcRfqnjAPBgNVHRMB
+Af8EBTADAQH/MAoGCCqGSM49BAMCA0cAMEQCIGu3BGVWdeY0K2p2FrHT6F77ggQZ
+veSZ7EKbMfxMdCJEAiAn0KJ8w4+RJbdwE49mUcwMPcSwX8B4Rlx+46l3K//gOw==
+-----END CERTIFICATE-----
+";
+    /// openssl x509 -pubkey -noout | openssl pkey -pubin -outform der | openssl dgst -sha256 -binary | base64
+    const TEST_PIN: &str = "sha256/ui0ktZWOrNKQDj92EPqrLQg9+qVUVhD1JVsxc5JROcM=";
+
+    #[test]
+    fn test_proxy_url() {
+        assert_eq!(proxy_url("proxy.corp:8080").unwrap(), "http://proxy.corp:8080");
+        assert_eq!(proxy_url(" https://proxy.corp:443 ").unwrap(), "https://proxy.corp:443");
+        assert!(proxy_url("  ").is_err());
+
+        let resolved = Resolved {
+            proxy: ProxySettings {
+                mode: ProxyMode::Manual,
+                server: "proxy.corp:8080".to_string(),
+                username: "alice".to_string(),
+                bypass: "localhost,.internal".to_string(),
+            },
+            password: Some("secret".to_string()),
+            ca_certs: Vec::new(),
+            pins: Vec::new(),
+        };
+        assert!(apply(reqwest::Client::builder(), &resolved).unwrap().build().is_ok());
+    }
+
+    #[test]
+    fn test_spki_pin() {
+        let cert = CertificateDer::from_pem_slice(TEST_CERT.as_bytes()).unwrap();
+        assert_eq!(spki_hash(&cert), Some(parse_pin(TEST_PIN).unwrap()));
+        assert!(spki_hash(&cert[..40]).is_none());
+
+        assert!(parse_pin("sha256/not-base64").is_err());
+        assert!(parse_pin(&STANDARD.encode([0u8; 16])).is_err());
+
+        let resolved = Resolved {
+            proxy: ProxySettings::default(),
+            password: None,
+            ca_certs: vec![cert.into_owned()],
+            pins: vec![parse_pin(TEST_PIN).unwrap()],
+        };
+        assert!(pinned_tls_config(&resolved).is_ok());
+    }
+}
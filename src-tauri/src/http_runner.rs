@@ -0,0 +1,620 @@
+// Run HTTP requests pasted as curl commands.
+// parse_curl understands the options people actually paste (headers, data, auth,
+// method, redirects); anything that only affects curl's own output is ignored.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::command_middleware::track;
+use crate::error::AppError;
+use crate::http_client;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Hard ceiling, regardless of what the request asks for
+const MAX_TIMEOUT_SECS: u64 = 60;
+const MAX_RESPONSE_BYTES: usize = 5 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpRequestSpec {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    pub body: Option<String>,
+    /// Multipart text fields (curl -F). Mutually exclusive with `body`.
+    #[serde(default)]
+    pub form: Vec<KeyValue>,
+    #[serde(default)]
+    pub follow_redirects: bool,
+    /// Skip TLS certificate verification (curl -k)
+    #[serde(default)]
+    pub insecure: bool,
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponseResult {
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<KeyValue>,
+    /// Body as text, pretty-printed when it's JSON
+    pub body: String,
+    pub is_json: bool,
+    /// True if the body was cut off at the size cap
+    pub truncated: bool,
+    pub size_bytes: usize,
+    pub elapsed_ms: u64,
+    pub final_url: String,
+}
+
+// ============================================================================
+// curl parsing
+// ============================================================================
+
+/// Split a command line into words using POSIX shell quoting rules.
+/// Backslash-newline continuations (multi-line curl commands) are joined.
+fn shell_words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            // Bash's $'...' ANSI-C quoting, as produced by "Copy as cURL" in browsers
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some(c) => current.push(c),
+                            None => return Err("Unterminated quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(c) => {
+                    in_word = true;
+                    current.push(c);
+                }
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+fn split_header(header: &str) -> Option<KeyValue> {
+    let (name, value) = header.split_once(':')?;
+    Some(KeyValue {
+        name: name.trim().to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+/// `name=content` is sent with only the content encoded; a bare value is encoded whole
+fn encode_data_urlencode(value: &str) -> String {
+    match value.split_once('=') {
+        Some((name, content)) if !name.is_empty() => {
+            format!("{}={}", name, urlencoding::encode(content))
+        }
+        Some((_, content)) => urlencoding::encode(content).into_owned(),
+        None => urlencoding::encode(value).into_owned(),
+    }
+}
+
+/// Short flags that take no value, and so can be bundled (`-sSL`)
+const BUNDLEABLE_FLAGS: &str = "sSviLkIGfN";
+
+fn expand_short_flags(words: Vec<String>) -> Vec<String> {
+    words
+        .into_iter()
+        .flat_map(|word| {
+            let bundled = word.len() > 2
+                && word.starts_with('-')
+                && !word.starts_with("--")
+                && word[1..].chars().all(|c| BUNDLEABLE_FLAGS.contains(c));
+            if bundled {
+                word[1..].chars().map(|c| format!("-{}", c)).collect()
+            } else {
+                vec![word]
+            }
+        })
+        .collect()
+}
+
+/// Convert a curl command line into a request spec
+pub fn parse_curl_command(text: &str) -> Result<HttpRequestSpec, String> {
+    let words = expand_short_flags(shell_words(text.trim())?);
+    let mut args = words.into_iter();
+
+    match args.next() {
+        Some(first) if first == "curl" || first.ends_with("/curl") => {}
+        _ => return Err("Not a curl command".to_string()),
+    }
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers = Vec::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut form = Vec::new();
+    let mut follow_redirects = false;
+    let mut insecure = false;
+    let mut timeout_secs = None;
+    let mut head = false;
+    let mut data_as_query = false;
+
+    while let Some(arg) = args.next() {
+        // --opt=value is accepted for long options
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("Missing value for {}", name))
+        };
+
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(value(&flag)?.to_uppercase()),
+            "-H" | "--header" => {
+                let header = value(&flag)?;
+                if let Some(kv) = split_header(&header) {
+                    headers.push(kv);
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" => {
+                let body = value(&flag)?;
+                if body.starts_with('@') && flag != "--data-raw" {
+                    return Err("Reading request data from a file isn't supported".to_string());
+                }
+                data.push(body);
+            }
+            "--data-urlencode" => data.push(encode_data_urlencode(&value(&flag)?)),
+            "--json" => {
+                data.push(value(&flag)?);
+                headers.push(KeyValue {
+                    name: "Content-Type".to_string(),
+                    value: "application/json".to_string(),
+                });
+                headers.push(KeyValue {
+                    name: "Accept".to_string(),
+                    value: "application/json".to_string(),
+                });
+            }
+            "-F" | "--form" | "--form-string" => {
+                let field = value(&flag)?;
+                let (name, content) = field
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid form field: {}", field))?;
+                if flag != "--form-string" && (content.starts_with('@') || content.starts_with('<')) {
+                    return Err("File uploads in form fields aren't supported".to_string());
+                }
+                form.push(KeyValue {
+                    name: name.to_string(),
+                    value: content.to_string(),
+                });
+            }
+            "-u" | "--user" => {
+                let credentials = value(&flag)?;
+                use base64::{engine::general_purpose::STANDARD, Engine};
+                headers.push(KeyValue {
+                    name: "Authorization".to_string(),
+                    value: format!("Basic {}", STANDARD.encode(credentials)),
+                });
+            }
+            "-A" | "--user-agent" => headers.push(KeyValue {
+                name: "User-Agent".to_string(),
+                value: value(&flag)?,
+            }),
+            "-e" | "--referer" => headers.push(KeyValue {
+                name: "Referer".to_string(),
+                value: value(&flag)?,
+            }),
+            "-b" | "--cookie" => headers.push(KeyValue {
+                name: "Cookie".to_string(),
+                value: value(&flag)?,
+            }),
+            "-m" | "--max-time" => {
+                let secs = value(&flag)?;
+                timeout_secs = secs.parse::<f64>().ok().map(|s| s.ceil() as u64);
+            }
+            "--url" => url = Some(value(&flag)?),
+            "-L" | "--location" => follow_redirects = true,
+            "-k" | "--insecure" => insecure = true,
+            "-I" | "--head" => head = true,
+            "-G" | "--get" => data_as_query = true,
+            // Output/verbosity options that don't change the request
+            "-s" | "--silent" | "-S" | "--show-error" | "-v" | "--verbose" | "-i" | "--include"
+            | "--compressed" | "-f" | "--fail" | "-N" | "--no-buffer" => {}
+            // Options that take a value we don't use
+            "-o" | "--output" | "-w" | "--write-out" | "--connect-timeout" | "--retry" => {
+                value(&flag)?;
+            }
+            other if other.starts_with('-') && other.len() > 1 => {
+                log::debug!("Ignoring unsupported curl option {}", other);
+            }
+            _ => {
+                if url.is_none() {
+                    url = Some(arg);
+                }
+            }
+        }
+    }
+
+    let mut url = url.ok_or("No URL found in curl command")?;
+    if !url.contains("://") {
+        // curl defaults to http for bare hosts
+        url = format!("http://{}", url);
+    }
+
+    let mut body = if data.is_empty() { None } else { Some(data.join("&")) };
+    if data_as_query {
+        if let Some(query) = body.take() {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            url = format!("{}{}{}", url, separator, query);
+        }
+    }
+
+    let has_content_type = headers.iter().any(|h| h.name.eq_ignore_ascii_case("Content-Type"));
+    if body.is_some() && !has_content_type {
+        headers.push(KeyValue {
+            name: "Content-Type".to_string(),
+            value: "application/x-www-form-urlencoded".to_string(),
+        });
+    }
+
+    let method = method.unwrap_or_else(|| {
+        if head {
+            "HEAD".to_string()
+        } else if body.is_some() || !form.is_empty() {
+            "POST".to_string()
+        } else {
+            "GET".to_string()
+        }
+    });
+
+    Ok(HttpRequestSpec {
+        method,
+        url,
+        headers,
+        body,
+        form,
+        follow_redirects,
+        insecure,
+        timeout_secs,
+    })
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+fn pretty_body(bytes: &[u8], content_type: Option<&str>) -> (String, bool) {
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    let looks_json = content_type.map(|ct| ct.contains("json")).unwrap_or(false)
+        || matches!(text.trim_start().chars().next(), Some('{') | Some('['));
+    if looks_json {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) {
+            if let Ok(pretty) = serde_json::to_string_pretty(&value) {
+                return (pretty, true);
+            }
+        }
+    }
+    (text, false)
+}
+
+/// Ask before sending a request over plain HTTP, since credentials in pasted
+/// commands (tokens, cookies) would go over the wire unencrypted
+async fn confirm_plain_http(url: &str) -> bool {
+    use rfd::{AsyncMessageDialog, MessageButtons, MessageDialogResult, MessageLevel};
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::window::DIALOG_OPEN.store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    let result = AsyncMessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title("Send unencrypted request?")
+        .set_description(format!(
+            "{} uses plain HTTP. Headers and body, including any credentials, will be sent unencrypted.",
+            url
+        ))
+        .set_buttons(MessageButtons::OkCancelCustom("Send".to_string(), "Cancel".to_string()))
+        .show()
+        .await;
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::window::DIALOG_OPEN.store(false, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    match result {
+        MessageDialogResult::Ok | MessageDialogResult::Yes => true,
+        MessageDialogResult::Custom(label) => label == "Send",
+        _ => false,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum RedirectStep {
+    Follow,
+    /// Return the redirect response itself
+    Stop,
+    Refuse(&'static str),
+}
+
+/// Whether to follow a redirect from `from` to `to`. reqwest drops Authorization and
+/// cookies on cross-host hops but keeps other headers, so a request carrying its own
+/// headers stops at a redirect to another origin, like curl without --location-trusted.
+fn redirect_step(from: &reqwest::Url, to: &reqwest::Url, hops: usize, has_headers: bool) -> RedirectStep {
+    if hops > MAX_REDIRECTS {
+        return RedirectStep::Refuse("too many redirects");
+    }
+    if from.scheme() == "https" && to.scheme() != "https" {
+        return RedirectStep::Refuse("refusing to follow a redirect from HTTPS to HTTP");
+    }
+    if has_headers && from.origin() != to.origin() {
+        return RedirectStep::Stop;
+    }
+    RedirectStep::Follow
+}
+
+pub async fn execute_request(spec: &HttpRequestSpec) -> Result<HttpResponseResult, String> {
+    let url = reqwest::Url::parse(spec.url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    match url.scheme() {
+        "https" => {}
+        "http" => {
+            if !confirm_plain_http(url.as_str()).await {
+                return Err("Request cancelled".to_string());
+            }
+        }
+        other => return Err(format!("Unsupported URL scheme: {}", other)),
+    }
+
+    let method = reqwest::Method::from_bytes(spec.method.trim().to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", spec.method))?;
+    let timeout = spec
+        .timeout_secs
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(1, MAX_TIMEOUT_SECS);
+
+    let redirect = if spec.follow_redirects {
+        let has_headers = !spec.headers.is_empty();
+        reqwest::redirect::Policy::custom(move |attempt| {
+            let Some(from) = attempt.previous().last().cloned() else {
+                return attempt.follow();
+            };
+            match redirect_step(&from, attempt.url(), attempt.previous().len(), has_headers) {
+                RedirectStep::Follow => attempt.follow(),
+                RedirectStep::Stop => attempt.stop(),
+                RedirectStep::Refuse(reason) => attempt.error(reason),
+            }
+        })
+    } else {
+        reqwest::redirect::Policy::none()
+    };
+    let client = http_client::builder()
+        .map_err(|e| e.to_string())?
+        .timeout(Duration::from_secs(timeout))
+        .redirect(redirect)
+        .danger_accept_invalid_certs(spec.insecure)
+        .user_agent("Wingman-Desktop")
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut request = client.request(method, url);
+    for header in &spec.headers {
+        request = request.header(header.name.as_str(), header.value.as_str());
+    }
+    if !spec.form.is_empty() {
+        let form = spec
+            .form
+            .iter()
+            .fold(reqwest::multipart::Form::new(), |form, field| {
+                form.text(field.name.clone(), field.value.clone())
+            });
+        request = request.multipart(form);
+    } else if let Some(body) = &spec.body {
+        request = request.body(body.clone());
+    }
+
+    let started = Instant::now();
+    let mut response = request.send().await.map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    let final_url = response.url().to_string();
+    let headers: Vec<KeyValue> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| KeyValue {
+            name: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })
+        .collect();
+    let content_type = response
+        .headers()
+        .get("Content-Type")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_ascii_lowercase());
+
+    let mut bytes = Vec::new();
+    let mut size_bytes = 0;
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Failed to read response: {}", e))? {
+        size_bytes += chunk.len();
+        let remaining = MAX_RESPONSE_BYTES.saturating_sub(bytes.len());
+        if chunk.len() > remaining {
+            bytes.extend_from_slice(&chunk[..remaining]);
+            truncated = true;
+            break;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let (body, is_json) = if truncated {
+        (String::from_utf8_lossy(&bytes).into_owned(), false)
+    } else {
+        pretty_body(&bytes, content_type.as_deref())
+    };
+
+    Ok(HttpResponseResult {
+        status: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or("").to_string(),
+        headers,
+        body,
+        is_json,
+        truncated,
+        size_bytes,
+        elapsed_ms,
+        final_url,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn parse_curl(text: String) -> Result<HttpRequestSpec, AppError> {
+    parse_curl_command(&text).map_err(AppError::invalid_input)
+}
+
+#[tauri::command]
+pub async fn run_http_request(request_spec: HttpRequestSpec) -> Result<HttpResponseResult, AppError> {
+    track("run_http_request", async move {
+        execute_request(&request_spec).await.map_err(AppError::from)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_words() {
+        let words = shell_words("curl 'a b' \"c \\\"d\\\"\" e\\ f \\\n  g $'h\\ni'").unwrap();
+        assert_eq!(words, vec!["curl", "a b", "c \"d\"", "e f", "g", "h\ni"]);
+        assert!(shell_words("curl 'oops").is_err());
+    }
+
+    #[test]
+    fn test_parse_browser_curl() {
+        let spec = parse_curl_command(
+            "curl 'https://api.example.com/items?page=2' \\\n  -H 'Accept: application/json' \\\n  -H 'Authorization: Bearer abc' \\\n  --data-raw '{\"name\":\"x\"}' \\\n  --compressed",
+        )
+        .unwrap();
+        assert_eq!(spec.method, "POST");
+        assert_eq!(spec.url, "https://api.example.com/items?page=2");
+        assert_eq!(spec.headers[1].value, "Bearer abc");
+        assert_eq!(spec.body.as_deref(), Some("{\"name\":\"x\"}"));
+    }
+
+    #[test]
+    fn test_parse_get_with_query_data() {
+        let spec = parse_curl_command(
+            "curl -sSLG --data-urlencode 'q=a b' -d limit=5 -u user:pass example.com/search",
+        )
+        .unwrap();
+        assert_eq!(spec.method, "GET");
+        assert_eq!(spec.url, "http://example.com/search?q=a%20b&limit=5");
+        assert!(spec.body.is_none());
+        assert!(spec.follow_redirects);
+        assert_eq!(spec.headers[0].value, "Basic dXNlcjpwYXNz");
+    }
+
+    #[test]
+    fn test_parse_rejects_non_curl() {
+        assert!(parse_curl_command("wget https://example.com").is_err());
+        assert!(parse_curl_command("curl -s").is_err());
+    }
+
+    #[test]
+    fn test_redirects_stay_on_https_and_origin() {
+        let url = |u: &str| reqwest::Url::parse(u).unwrap();
+        let api = url("https://api.example.com/v1");
+        assert_eq!(redirect_step(&api, &url("https://api.example.com/v2"), 1, true), RedirectStep::Follow);
+        assert!(matches!(redirect_step(&api, &url("http://api.example.com/v2"), 1, false), RedirectStep::Refuse(_)));
+        assert_eq!(redirect_step(&api, &url("https://cdn.example.net/x"), 1, true), RedirectStep::Stop);
+        assert_eq!(redirect_step(&api, &url("https://cdn.example.net/x"), 1, false), RedirectStep::Follow);
+        assert_eq!(redirect_step(&url("http://a.test/"), &url("https://a.test/"), 1, false), RedirectStep::Follow);
+        assert!(matches!(redirect_step(&api, &api, MAX_REDIRECTS + 1, false), RedirectStep::Refuse(_)));
+    }
+
+    #[test]
+    fn test_pretty_body() {
+        let (body, is_json) = pretty_body(b"{\"a\":1}", Some("application/json"));
+        assert!(is_json);
+        assert_eq!(body, "{\n  \"a\": 1\n}");
+        let (body, is_json) = pretty_body(b"plain", None);
+        assert!(!is_json);
+        assert_eq!(body, "plain");
+    }
+}
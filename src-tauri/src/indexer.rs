@@ -0,0 +1,315 @@
+// Background indexing. Classifying and embedding a clip shouldn't hold up the insert,
+// so add_entry and update_entry only queue the entry in `index_queue` and a worker
+// thread does the rest: smart tags (classify.rs) and, while semantic search is on, the
+// entry's vector (semantic.rs). The queue lives in SQLite, so work queued before a
+// quit is picked up on the next launch. Each batch emits `index-progress`.
+//
+// An entry edited while the worker has it is queued again with a new queued_at, so the
+// worker only clears the row it took; the fresh one stays for the next batch.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::classify::smart_tags_for;
+use crate::error::AppError;
+use crate::history::{get_entry_content, HistoryError};
+use crate::storage::load_settings;
+use crate::AppState;
+
+const BATCH_SIZE: u32 = 25;
+/// Entries that failed this many times are left for rebuild_index
+const MAX_ATTEMPTS: u32 = 3;
+/// Fallback poll in case a wake-up is missed
+const IDLE_POLL: Duration = Duration::from_secs(30);
+/// queued_at for new rows, to the millisecond so a quick re-queue still changes it
+const QUEUED_NOW: &str = "strftime('%Y-%m-%d %H:%M:%f', 'now')";
+
+/// Set when work is queued; the worker waits on `WAKE` until it is
+static WORK_QUEUED: Mutex<bool> = Mutex::new(false);
+static WAKE: Condvar = Condvar::new();
+static BUSY: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatus {
+    /// Entries waiting to be indexed
+    pub queued: u32,
+    /// Entries that failed `MAX_ATTEMPTS` times
+    pub failed: u32,
+    /// Whether the worker is in the middle of a batch
+    pub indexing: bool,
+}
+
+/// A queue row as the worker took it
+#[derive(Debug, Clone, PartialEq)]
+struct Queued {
+    entry_id: i64,
+    queued_at: String,
+}
+
+/// Payload of `index-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexProgress {
+    pub indexed: u32,
+    pub remaining: u32,
+    pub failed: u32,
+}
+
+pub fn init_index_queue_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS index_queue (
+            entry_id INTEGER PRIMARY KEY,
+            queued_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Queue an entry for indexing; re-queuing resets its attempts
+pub fn enqueue(conn: &Connection, entry_id: i64) -> rusqlite::Result<()> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO index_queue (entry_id, queued_at, attempts, last_error)
+             VALUES (?1, {}, 0, NULL)",
+            QUEUED_NOW
+        ),
+        [entry_id],
+    )?;
+    wake();
+    Ok(())
+}
+
+/// Have the indexer look at the queue now, for rows added without `enqueue`
+pub fn wake() {
+    *WORK_QUEUED.lock().unwrap_or_else(|e| e.into_inner()) = true;
+    WAKE.notify_one();
+}
+
+fn next_batch(conn: &Connection) -> rusqlite::Result<Vec<Queued>> {
+    let mut stmt = conn.prepare(
+        "SELECT entry_id, queued_at FROM index_queue WHERE attempts < ?1 ORDER BY queued_at, entry_id LIMIT ?2",
+    )?;
+    let batch = stmt
+        .query_map(rusqlite::params![MAX_ATTEMPTS, BATCH_SIZE], |row| {
+            Ok(Queued {
+                entry_id: row.get(0)?,
+                queued_at: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(batch)
+}
+
+/// Clear the row the worker took, unless the entry was queued again since
+fn dequeue(conn: &Connection, queued: &Queued) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM index_queue WHERE entry_id = ?1 AND queued_at = ?2",
+        rusqlite::params![queued.entry_id, queued.queued_at],
+    )
+}
+
+fn status(conn: &Connection) -> rusqlite::Result<IndexStatus> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(attempts < ?1), 0), COALESCE(SUM(attempts >= ?1), 0) FROM index_queue",
+        [MAX_ATTEMPTS],
+        |row| {
+            Ok(IndexStatus {
+                queued: row.get(0)?,
+                failed: row.get(1)?,
+                indexing: BUSY.load(Ordering::SeqCst),
+            })
+        },
+    )
+}
+
+/// Index one entry. The content is read and the results written under the lock, but
+/// classifying and embedding happen without it so inserts aren't held up.
+fn index_entry(app: &AppHandle, queued: &Queued, embed: bool) -> Result<(), String> {
+    let entry_id = queued.entry_id;
+    let state = app.state::<AppState>();
+    // The full text, not the head compressed and overflowed rows keep in `content`
+    let content: Option<String> = {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        match get_entry_content(&conn, entry_id) {
+            Ok(content) => Some(content),
+            Err(HistoryError::EntryNotFound(_)) => None,
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    let Some(content) = content else {
+        // Deleted since it was queued
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        dequeue(&conn, queued).map_err(|e| e.to_string())?;
+        return Ok(());
+    };
+
+    let smart_tags = smart_tags_for(&content);
+    let vector = embed.then(|| crate::semantic::embed(&content));
+
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE history SET smart_tags = ?2 WHERE id = ?1",
+        rusqlite::params![entry_id, smart_tags],
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(vector) = vector {
+        crate::semantic::store_history_vector(&tx, entry_id, &content, &vector).map_err(|e| e.to_string())?;
+    }
+    dequeue(&tx, queued).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn record_failure(app: &AppHandle, queued: &Queued, error: &str) {
+    let state = app.state::<AppState>();
+    let Ok(conn) = state.db.lock() else {
+        return;
+    };
+    conn.execute(
+        "UPDATE index_queue SET attempts = attempts + 1, last_error = ?3 WHERE entry_id = ?1 AND queued_at = ?2",
+        rusqlite::params![queued.entry_id, queued.queued_at, error],
+    )
+    .ok();
+}
+
+/// Run one batch; returns how many entries it took from the queue
+fn run_batch(app: &AppHandle) -> Result<usize, String> {
+    let batch = {
+        let state = app.state::<AppState>();
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        next_batch(&conn).map_err(|e| e.to_string())?
+    };
+    if batch.is_empty() {
+        return Ok(0);
+    }
+
+    BUSY.store(true, Ordering::SeqCst);
+    let embed = load_settings().map(|s| s.semantic_search_enabled).unwrap_or(false);
+    let mut indexed = 0;
+    for queued in &batch {
+        match index_entry(app, queued, embed) {
+            Ok(()) => indexed += 1,
+            Err(e) => {
+                log::warn!("Failed to index history entry {}: {}", queued.entry_id, e);
+                record_failure(app, queued, &e);
+            }
+        }
+    }
+    BUSY.store(false, Ordering::SeqCst);
+
+    let state = app.state::<AppState>();
+    let counts = state
+        .db
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| status(&conn).map_err(|e| e.to_string()))?;
+    app.emit(
+        "index-progress",
+        IndexProgress {
+            indexed,
+            remaining: counts.queued,
+            failed: counts.failed,
+        },
+    )
+    .ok();
+    Ok(batch.len())
+}
+
+/// Start the worker thread. Entries queued in an earlier run are indexed first.
+pub fn start_indexer(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        match run_batch(&app) {
+            Ok(0) => {
+                let queued = WORK_QUEUED.lock().unwrap_or_else(|e| e.into_inner());
+                let (mut queued, _) = WAKE
+                    .wait_timeout_while(queued, IDLE_POLL, |queued| !*queued)
+                    .unwrap_or_else(|e| e.into_inner());
+                *queued = false;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("Indexer stopped: {}", e);
+                return;
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_index_status(state: State<AppState>) -> Result<IndexStatus, AppError> {
+    let conn = state.db.lock()?;
+    status(&conn).map_err(AppError::from)
+}
+
+/// Queue every history entry again, including ones that failed. Returns how many.
+#[tauri::command]
+pub fn rebuild_index(state: State<AppState>) -> Result<u32, AppError> {
+    let conn = state.db.lock()?;
+    let queued = conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO index_queue (entry_id, queued_at, attempts, last_error)
+             SELECT id, {}, 0, NULL FROM history",
+            QUEUED_NOW
+        ),
+        [],
+    )?;
+    wake();
+    Ok(queued as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_batches_and_status() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_index_queue_table(&conn).unwrap();
+        for id in 1..=3 {
+            enqueue(&conn, id).unwrap();
+        }
+        conn.execute("UPDATE index_queue SET attempts = ?1 WHERE entry_id = 2", [MAX_ATTEMPTS])
+            .unwrap();
+
+        let ids = |batch: Vec<Queued>| batch.into_iter().map(|q| q.entry_id).collect::<Vec<_>>();
+        assert_eq!(ids(next_batch(&conn).unwrap()), vec![1, 3]);
+        let counts = status(&conn).unwrap();
+        assert_eq!((counts.queued, counts.failed), (2, 1));
+
+        // Re-queuing gives a failed entry another go
+        enqueue(&conn, 2).unwrap();
+        let counts = status(&conn).unwrap();
+        assert_eq!((counts.queued, counts.failed), (3, 0));
+    }
+
+    #[test]
+    fn test_requeued_entry_survives_dequeue() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_index_queue_table(&conn).unwrap();
+        enqueue(&conn, 1).unwrap();
+        enqueue(&conn, 2).unwrap();
+        let batch = next_batch(&conn).unwrap();
+
+        // Entry 1 is edited while the worker indexes the old content
+        conn.execute(
+            "UPDATE index_queue SET queued_at = '2999-01-01 00:00:00.000' WHERE entry_id = 1",
+            [],
+        )
+        .unwrap();
+        assert_eq!(dequeue(&conn, &batch[0]).unwrap(), 0);
+        assert_eq!(dequeue(&conn, &batch[1]).unwrap(), 1);
+        let left: Vec<i64> = next_batch(&conn).unwrap().into_iter().map(|q| q.entry_id).collect();
+        assert_eq!(left, vec![1]);
+    }
+}
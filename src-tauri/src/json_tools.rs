@@ -0,0 +1,472 @@
+// Structured JSON tools beyond pretty-printing: JSONPath queries (RFC 9535), key sorting,
+// and generating a TypeScript interface or a JSON Schema from a sample document.
+//
+// Interfaces and schemas are inferred from the sample alone. Objects in the same array
+// are merged into one type, with keys missing from some of them marked optional, so
+// the more representative the sample the better the result.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use serde_json_path::JsonPath;
+
+use crate::error::AppError;
+use crate::license::{is_feature_enabled, ProFeature};
+
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonMatch {
+    /// Normalized path, e.g. `$['items'][0]['id']`
+    pub path: String,
+    /// The same location as a JSON Pointer, e.g. `/items/0/id`
+    pub pointer: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonQueryResult {
+    pub matches: Vec<JsonMatch>,
+    /// Pretty-printed result for the editor: the value for a single match, else an array
+    pub output: String,
+}
+
+fn parse(text: &str) -> Result<Value, AppError> {
+    serde_json::from_str(text).map_err(|e| AppError::invalid_input(format!("Invalid JSON: {}", e)))
+}
+
+fn require_pro() -> Result<(), AppError> {
+    if is_feature_enabled(ProFeature::JsonXmlFormatting) {
+        Ok(())
+    } else {
+        Err(AppError::pro_required())
+    }
+}
+
+fn query(value: &Value, path: &str) -> Result<JsonQueryResult, AppError> {
+    let path = JsonPath::parse(path.trim())
+        .map_err(|e| AppError::invalid_input(format!("Invalid JSONPath: {}", e)))?;
+    let matches: Vec<JsonMatch> = path
+        .query_located(value)
+        .iter()
+        .map(|node| JsonMatch {
+            path: node.location().to_string(),
+            pointer: node.location().to_json_pointer(),
+            value: node.node().clone(),
+        })
+        .collect();
+
+    let output = match matches.as_slice() {
+        [single] => serde_json::to_string_pretty(&single.value)?,
+        _ => serde_json::to_string_pretty(&matches.iter().map(|m| &m.value).collect::<Vec<_>>())?,
+    };
+    Ok(JsonQueryResult { matches, output })
+}
+
+pub fn sort_keys(value: Value, recursive: bool) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, if recursive { sort_keys(value, true) } else { value }))
+                    .collect(),
+            )
+        }
+        // Objects inside a top-level array count as the top level
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| sort_keys(item, recursive)).collect()),
+        other => other,
+    }
+}
+
+// ============================================================================
+// Shape inference
+// ============================================================================
+
+/// The type of every value seen at one position in the document
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    /// Only ever seen as an empty array's elements
+    Unknown,
+    Null,
+    Bool,
+    Integer,
+    Number,
+    String,
+    Array(Box<Shape>),
+    /// Keys in first-seen order, with whether every object had them
+    Object(Vec<(String, Shape, bool)>),
+    Union(Vec<Shape>),
+}
+
+impl Shape {
+    fn of(value: &Value) -> Shape {
+        match value {
+            Value::Null => Shape::Null,
+            Value::Bool(_) => Shape::Bool,
+            Value::Number(n) if n.is_i64() || n.is_u64() => Shape::Integer,
+            Value::Number(_) => Shape::Number,
+            Value::String(_) => Shape::String,
+            Value::Array(items) => Shape::Array(Box::new(
+                items.iter().map(Shape::of).fold(Shape::Unknown, Shape::merge),
+            )),
+            Value::Object(map) => Shape::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), Shape::of(value), true))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn merge(self, other: Shape) -> Shape {
+        match (self, other) {
+            (Shape::Unknown, shape) | (shape, Shape::Unknown) => shape,
+            (Shape::Integer, Shape::Number) | (Shape::Number, Shape::Integer) => Shape::Number,
+            (Shape::Array(a), Shape::Array(b)) => Shape::Array(Box::new(a.merge(*b))),
+            (Shape::Object(a), Shape::Object(b)) => {
+                let mut fields: Vec<(String, Shape, bool)> = Vec::with_capacity(a.len());
+                for (key, shape, required) in a {
+                    match b.iter().find(|(other, _, _)| *other == key) {
+                        Some((_, other, other_required)) => {
+                            fields.push((key, shape.merge(other.clone()), required && *other_required))
+                        }
+                        None => fields.push((key, shape, false)),
+                    }
+                }
+                for (key, shape, _) in b {
+                    if !fields.iter().any(|(existing, _, _)| *existing == key) {
+                        fields.push((key, shape, false));
+                    }
+                }
+                Shape::Object(fields)
+            }
+            (Shape::Union(members), shape) | (shape, Shape::Union(members)) => {
+                members.into_iter().fold(Shape::Union(Vec::new()), Shape::add_member).add_member(shape)
+            }
+            (a, b) if a == b => a,
+            (a, b) => Shape::Union(vec![a]).add_member(b),
+        }
+    }
+
+    /// Add `shape` to a union, merging it into a member of the same kind if there is one
+    fn add_member(self, shape: Shape) -> Shape {
+        if let Shape::Union(others) = shape {
+            return others.into_iter().fold(self, Shape::add_member);
+        }
+        let Shape::Union(mut members) = self else {
+            return self.merge(shape);
+        };
+        let same_kind = members.iter().position(|member| {
+            std::mem::discriminant(member) == std::mem::discriminant(&shape)
+                || matches!((member, &shape), (Shape::Integer, Shape::Number) | (Shape::Number, Shape::Integer))
+        });
+        match same_kind {
+            Some(i) => {
+                let member = members.remove(i);
+                members.insert(i, member.merge(shape));
+            }
+            None => members.push(shape),
+        }
+        if members.len() == 1 {
+            members.remove(0)
+        } else {
+            Shape::Union(members)
+        }
+    }
+}
+
+// ============================================================================
+// TypeScript
+// ============================================================================
+
+fn pascal_case(name: &str) -> String {
+    let pascal: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    match pascal.chars().next() {
+        None => "Item".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("T{}", pascal),
+        Some(_) => pascal,
+    }
+}
+
+/// Rough singular of an array's key, for naming its element type
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if name.ends_with("ss") {
+        name.to_string()
+    } else if let Some(stem) = name.strip_suffix('s') {
+        stem.to_string()
+    } else {
+        format!("{}Item", name)
+    }
+}
+
+fn property_name(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+/// Renders shapes as TypeScript, collecting one interface per object shape
+#[derive(Default)]
+struct TsWriter {
+    /// (position, name, body); position is where the object was first reached walking
+    /// the document, so parents come before the types they reference
+    interfaces: Vec<(usize, String, String)>,
+    reached: usize,
+}
+
+impl TsWriter {
+    fn type_of(&mut self, shape: &Shape, name: &str) -> String {
+        match shape {
+            Shape::Unknown => "unknown".to_string(),
+            Shape::Null => "null".to_string(),
+            Shape::Bool => "boolean".to_string(),
+            Shape::Integer | Shape::Number => "number".to_string(),
+            Shape::String => "string".to_string(),
+            Shape::Array(element) => {
+                let element = self.type_of(element, &singular(name));
+                if element.contains(' ') {
+                    format!("({})[]", element)
+                } else {
+                    format!("{}[]", element)
+                }
+            }
+            Shape::Union(members) => {
+                let mut types: Vec<String> = members.iter().map(|member| self.type_of(member, name)).collect();
+                types.dedup();
+                types.join(" | ")
+            }
+            Shape::Object(fields) => {
+                let position = self.reached;
+                self.reached += 1;
+                let body: String = fields
+                    .iter()
+                    .map(|(key, shape, required)| {
+                        format!(
+                            "  {}{}: {};\n",
+                            property_name(key),
+                            if *required { "" } else { "?" },
+                            self.type_of(shape, key)
+                        )
+                    })
+                    .collect();
+                // Identical objects in different places share one interface
+                if let Some((_, existing, _)) = self.interfaces.iter().find(|(_, _, other)| *other == body) {
+                    return existing.clone();
+                }
+                let base = pascal_case(name);
+                let mut interface = base.clone();
+                let mut n = 2;
+                while self.interfaces.iter().any(|(_, taken, _)| *taken == interface) {
+                    interface = format!("{}{}", base, n);
+                    n += 1;
+                }
+                self.interfaces.push((position, interface.clone(), body));
+                interface
+            }
+        }
+    }
+}
+
+fn typescript(value: &Value, root_name: &str) -> String {
+    let mut writer = TsWriter::default();
+    let root = pascal_case(root_name);
+    let root_type = writer.type_of(&Shape::of(value), &root);
+
+    let mut out = Vec::new();
+    if root_type != root {
+        out.push(format!("export type {} = {};", root, root_type));
+    }
+    writer.interfaces.sort_by_key(|(position, _, _)| *position);
+    for (_, name, body) in &writer.interfaces {
+        out.push(format!("export interface {} {{\n{}}}", name, body));
+    }
+    out.join("\n\n") + "\n"
+}
+
+// ============================================================================
+// JSON Schema
+// ============================================================================
+
+fn schema_of(shape: &Shape) -> Value {
+    match shape {
+        Shape::Unknown => json!({}),
+        Shape::Null => json!({ "type": "null" }),
+        Shape::Bool => json!({ "type": "boolean" }),
+        Shape::Integer => json!({ "type": "integer" }),
+        Shape::Number => json!({ "type": "number" }),
+        Shape::String => json!({ "type": "string" }),
+        Shape::Array(element) => {
+            let mut schema = json!({ "type": "array" });
+            if **element != Shape::Unknown {
+                schema["items"] = schema_of(element);
+            }
+            schema
+        }
+        Shape::Object(fields) => {
+            let properties: Map<String, Value> = fields
+                .iter()
+                .map(|(key, shape, _)| (key.clone(), schema_of(shape)))
+                .collect();
+            let required: Vec<&str> = fields
+                .iter()
+                .filter(|(_, _, required)| *required)
+                .map(|(key, _, _)| key.as_str())
+                .collect();
+            let mut schema = json!({ "type": "object", "properties": properties });
+            if !required.is_empty() {
+                schema["required"] = json!(required);
+            }
+            schema
+        }
+        Shape::Union(members) => {
+            let schemas: Vec<Value> = members.iter().map(schema_of).collect();
+            // Plain types collapse to `"type": [...]`; anything structured needs anyOf
+            let simple: Option<Vec<Value>> = schemas
+                .iter()
+                .map(|schema| match schema.as_object() {
+                    Some(map) if map.len() == 1 => map.get("type").cloned(),
+                    _ => None,
+                })
+                .collect();
+            match simple {
+                Some(types) => json!({ "type": types }),
+                None => json!({ "anyOf": schemas }),
+            }
+        }
+    }
+}
+
+fn schema(value: &Value) -> Value {
+    let mut schema = json!({ "$schema": SCHEMA_DIALECT });
+    if let (Some(target), Value::Object(inferred)) = (schema.as_object_mut(), schema_of(&Shape::of(value))) {
+        target.extend(inferred);
+    }
+    schema
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Run a JSONPath query (RFC 9535), e.g. `$.items[?@.price < 10].name`
+#[tauri::command]
+pub fn query_json(text: String, jsonpath: String) -> Result<JsonQueryResult, AppError> {
+    require_pro()?;
+    query(&parse(&text)?, &jsonpath)
+}
+
+/// Pretty-print with object keys sorted; only the top-level object's unless `recursive`
+#[tauri::command]
+pub fn sort_json_keys(text: String, recursive: bool) -> Result<String, AppError> {
+    require_pro()?;
+    Ok(serde_json::to_string_pretty(&sort_keys(parse(&text)?, recursive))?)
+}
+
+/// TypeScript interfaces describing a sample document, the root named `root_name`
+/// ("Root" by default)
+#[tauri::command]
+pub fn json_to_typescript_interface(text: String, root_name: Option<String>) -> Result<String, AppError> {
+    require_pro()?;
+    let root_name = root_name.filter(|name| !name.trim().is_empty());
+    Ok(typescript(&parse(&text)?, root_name.as_deref().unwrap_or("Root")))
+}
+
+/// A JSON Schema (draft 2020-12) that the sample document satisfies
+#[tauri::command]
+pub fn infer_json_schema(text: String) -> Result<String, AppError> {
+    require_pro()?;
+    Ok(serde_json::to_string_pretty(&schema(&parse(&text)?))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "id": 7,
+        "name": "Order",
+        "items": [
+            { "sku": "A-1", "price": 9.5, "tags": ["new"] },
+            { "sku": "B-2", "price": 12, "discount": null, "tags": [] }
+        ],
+        "shipping-address": { "city": "Oslo", "zip": "0150" }
+    }"#;
+
+    #[test]
+    fn test_query_and_sort() {
+        let value = parse(SAMPLE).unwrap();
+        let result = query(&value, "$.items[?@.price < 10].sku").unwrap();
+        assert_eq!(result.matches.len(), 1);
+        assert_eq!(result.matches[0].path, "$['items'][0]['sku']");
+        assert_eq!(result.matches[0].pointer, "/items/0/sku");
+        assert_eq!(result.output, "\"A-1\"");
+        assert!(query(&value, "$.items[").is_err());
+
+        let shallow = sort_keys(parse(r#"{"b": {"z": 1, "a": 2}, "a": 1}"#).unwrap(), false);
+        assert_eq!(shallow.to_string(), r#"{"a":1,"b":{"z":1,"a":2}}"#);
+        let deep = sort_keys(shallow, true);
+        assert_eq!(deep.to_string(), r#"{"a":1,"b":{"a":2,"z":1}}"#);
+    }
+
+    #[test]
+    fn test_typescript_interface() {
+        let ts = typescript(&parse(SAMPLE).unwrap(), "order");
+        assert_eq!(
+            ts,
+            "export interface Order {
+  id: number;
+  name: string;
+  items: Item[];
+  \"shipping-address\": ShippingAddress;
+}
+
+export interface Item {
+  sku: string;
+  price: number;
+  tags: string[];
+  discount?: null;
+}
+
+export interface ShippingAddress {
+  city: string;
+  zip: string;
+}
+"
+        );
+
+        let list = typescript(&parse(r#"[{"id": 1}, {"id": "x"}]"#).unwrap(), "Root");
+        assert!(list.starts_with("export type Root = RootItem[];"));
+        assert!(list.contains("id: number | string;"));
+    }
+
+    #[test]
+    fn test_infer_schema() {
+        let schema = schema(&parse(SAMPLE).unwrap());
+        assert_eq!(schema["$schema"], SCHEMA_DIALECT);
+        assert_eq!(schema["properties"]["id"]["type"], "integer");
+        let item = &schema["properties"]["items"]["items"];
+        assert_eq!(item["properties"]["price"]["type"], "number");
+        assert_eq!(item["required"], json!(["sku", "price", "tags"]));
+        assert_eq!(item["properties"]["tags"]["items"]["type"], "string");
+    }
+}
@@ -0,0 +1,1239 @@
+// LAN sync: Wingman instances on the same network find each other over mDNS, pair
+// once by comparing a six-digit code shown on both screens, then send clipboard items
+// to each other over TCP encrypted with AES-256-GCM under the key agreed (X25519 +
+// HKDF) during pairing. Keys live in the OS keychain; lan_peers.json lists who's paired.
+// Peers with auto sync on also get every new clip pushed into their history, and every
+// transfer either way is recorded in lan_transfers.json. A clip's expiry travels with
+// it, so a self-destructing clip also expires on the peer. Items older than a few
+// minutes are refused and nonces seen recently are remembered per peer, so captured
+// traffic can't be replayed.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hkdf::Hkdf;
+use rand::RngCore;
+use ring::agreement::{self, EphemeralPrivateKey, UnparsedPublicKey, X25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::oneshot;
+
+use crate::credentials::{delete_credential, get_credential, store_credential};
+use crate::error::AppError;
+use crate::history::{add_entry, get_entry, set_entry_expiry, HistoryEntry, HistoryError};
+use crate::license::{get_device_id, get_device_name};
+use crate::mdns::{self, Announcement, ServiceInfo, MDNS_ADDR, MDNS_PORT};
+use crate::storage::ensure_app_data_dir;
+use crate::AppState;
+
+const DEFAULT_PORT: u16 = 47653;
+const PROTOCOL_VERSION: u32 = 1;
+const MAX_FRAME_BYTES: usize = 32 * 1024 * 1024;
+const ANNOUNCE_TTL: u32 = 120;
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(30);
+const IO_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long both people have to compare codes
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(120);
+/// Items older than this are refused, which limits replaying captured traffic
+const MAX_ITEM_AGE_SECS: i64 = 300;
+const MAX_LOG_ENTRIES: usize = 500;
+
+#[derive(Error, Debug)]
+pub enum LanSyncError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("Timed out waiting for the other device")]
+    Timeout(#[from] tokio::time::error::Elapsed),
+    #[error("Encryption error")]
+    Crypto,
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+    #[error("Device {0} is not paired")]
+    NotPaired(String),
+    #[error("Device {0} was not found on the network")]
+    PeerNotFound(String),
+    #[error("LAN sync is turned off")]
+    Disabled,
+    #[error("Rejected by the other device: {0}")]
+    Rejected(String),
+}
+
+/// Per-device automatic sync. Each side applies its own settings: what it pushes to the
+/// peer and what it accepts from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerSyncSettings {
+    /// Push new clips to this device and accept its clips into history
+    #[serde(default)]
+    pub auto_sync: bool,
+    /// When off, only text is synced automatically
+    #[serde(default)]
+    pub include_images: bool,
+    /// Clips larger than this (text, HTML and images together) are not synced automatically
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: u64,
+}
+
+fn default_max_bytes() -> u64 {
+    1024 * 1024
+}
+
+impl Default for PeerSyncSettings {
+    fn default() -> Self {
+        Self {
+            auto_sync: false,
+            include_images: false,
+            max_bytes: default_max_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedPeer {
+    pub id: String,
+    pub name: String,
+    pub paired_at: String,
+    #[serde(default)]
+    pub sync: PeerSyncSettings,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PeersData {
+    peers: Vec<PairedPeer>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanPeer {
+    pub id: String,
+    pub name: String,
+    /// "ip:port" while the device is visible on the network
+    pub address: Option<String>,
+    pub paired: bool,
+    pub online: bool,
+    /// `None` for devices that aren't paired
+    pub sync: Option<PeerSyncSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanItem {
+    pub content: String,
+    pub html: Option<String>,
+    pub images: Option<String>,
+    pub language: Option<String>,
+    /// UTC "YYYY-MM-DD HH:MM:SS", as in history; the peer deletes the clip once it passes
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    /// Unix seconds on the sender
+    pub sent_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceivedItem {
+    pub peer_id: String,
+    pub peer_name: String,
+    pub item: LanItem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    Sent,
+    Received,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    Delivered,
+    /// Filtered out by the sync settings before sending or on arrival
+    Skipped,
+    /// Refused by the other device
+    Rejected,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRecord {
+    pub timestamp: String,
+    pub peer_id: String,
+    pub peer_name: String,
+    pub direction: TransferDirection,
+    /// Pushed by auto sync rather than sent by hand
+    pub automatic: bool,
+    pub bytes: u64,
+    pub has_images: bool,
+    pub status: TransferStatus,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TransferLog {
+    transfers: Vec<TransferRecord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PairingCode {
+    pub peer_id: String,
+    pub peer_name: String,
+    /// Shown on both devices; the user confirms they match
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    PairRequest {
+        version: u32,
+        device_id: String,
+        name: String,
+        public_key: String,
+    },
+    PairResponse {
+        device_id: String,
+        name: String,
+        public_key: String,
+    },
+    PairConfirm {
+        accepted: bool,
+    },
+    Item {
+        device_id: String,
+        nonce: String,
+        ciphertext: String,
+        #[serde(default)]
+        automatic: bool,
+    },
+    Ack {
+        ok: bool,
+        error: Option<String>,
+    },
+}
+
+struct Discovered {
+    name: String,
+    address: SocketAddr,
+    expires: Instant,
+}
+
+struct Service {
+    tasks: Vec<tauri::async_runtime::JoinHandle<()>>,
+    port: u16,
+}
+
+static SERVICE: Mutex<Option<Service>> = Mutex::new(None);
+
+/// Instances seen over mDNS, by LAN id
+static DISCOVERED: Mutex<Option<HashMap<String, Discovered>>> = Mutex::new(None);
+
+/// Pairings waiting for the local user to compare codes, by peer id
+static PENDING_PAIRINGS: Mutex<Option<HashMap<String, oneshot::Sender<bool>>>> = Mutex::new(None);
+
+/// Serializes read-modify-write of the transfer log
+static TRANSFER_LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Item nonces accepted recently, by peer id, with the Unix second they arrived
+static SEEN_NONCES: Mutex<Option<HashMap<String, HashMap<String, i64>>>> = Mutex::new(None);
+
+// ============================================================================
+// Identity and Storage
+// ============================================================================
+
+/// Id advertised on the LAN. Derived from the machine id rather than being it, so
+/// the hardware identifier never leaves the machine.
+pub fn local_device_id() -> &'static str {
+    static ID: OnceLock<String> = OnceLock::new();
+    ID.get_or_init(|| {
+        let machine = get_device_id().unwrap_or_else(|_| get_device_name());
+        let digest = Sha256::digest(format!("wingman-lan:{}", machine).as_bytes());
+        digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+    })
+}
+
+fn peers_path() -> Result<PathBuf, LanSyncError> {
+    Ok(ensure_app_data_dir()?.join("lan_peers.json"))
+}
+
+fn load_peers() -> Result<PeersData, LanSyncError> {
+    let path = peers_path()?;
+    if !path.exists() {
+        return Ok(PeersData::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_peers(data: &PeersData) -> Result<(), LanSyncError> {
+    fs::write(peers_path()?, serde_json::to_string_pretty(data)?)?;
+    Ok(())
+}
+
+fn key_credential(peer_id: &str) -> String {
+    format!("lan_peer:{}", peer_id)
+}
+
+fn load_peer_key(peer_id: &str) -> Result<[u8; 32], LanSyncError> {
+    let encoded = get_credential(key_credential(peer_id))
+        .map_err(|e| LanSyncError::Keychain(e.message))?
+        .ok_or_else(|| LanSyncError::NotPaired(peer_id.to_string()))?;
+    STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or(LanSyncError::Crypto)
+}
+
+fn save_paired_peer(peer_id: &str, name: &str, key: &[u8; 32]) -> Result<PairedPeer, LanSyncError> {
+    store_credential(key_credential(peer_id), STANDARD.encode(key)).map_err(|e| LanSyncError::Keychain(e.message))?;
+    let mut data = load_peers()?;
+    // Re-pairing keeps the sync settings chosen before
+    let sync = data
+        .peers
+        .iter()
+        .find(|p| p.id == peer_id)
+        .map(|p| p.sync.clone())
+        .unwrap_or_default();
+    data.peers.retain(|p| p.id != peer_id);
+    let peer = PairedPeer {
+        id: peer_id.to_string(),
+        name: name.to_string(),
+        paired_at: chrono::Utc::now().to_rfc3339(),
+        sync,
+    };
+    data.peers.push(peer.clone());
+    save_peers(&data)?;
+    Ok(peer)
+}
+
+fn find_peer(peer_id: &str) -> Result<PairedPeer, LanSyncError> {
+    load_peers()?
+        .peers
+        .into_iter()
+        .find(|p| p.id == peer_id)
+        .ok_or_else(|| LanSyncError::NotPaired(peer_id.to_string()))
+}
+
+fn transfer_log_path() -> Result<PathBuf, LanSyncError> {
+    Ok(ensure_app_data_dir()?.join("lan_transfers.json"))
+}
+
+fn load_transfer_log() -> Result<TransferLog, LanSyncError> {
+    let path = transfer_log_path()?;
+    if !path.exists() {
+        return Ok(TransferLog::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn log_transfer(
+    peer: &PairedPeer,
+    direction: TransferDirection,
+    automatic: bool,
+    item: &LanItem,
+    status: TransferStatus,
+    detail: Option<String>,
+) {
+    let record = TransferRecord {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        peer_id: peer.id.clone(),
+        peer_name: peer.name.clone(),
+        direction,
+        automatic,
+        bytes: item_size(item),
+        has_images: item.images.is_some(),
+        status,
+        detail,
+    };
+    let _guard = TRANSFER_LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let result = load_transfer_log().and_then(|mut log| {
+        log.transfers.push(record);
+        let overflow = log.transfers.len().saturating_sub(MAX_LOG_ENTRIES);
+        log.transfers.drain(..overflow);
+        fs::write(transfer_log_path()?, serde_json::to_string_pretty(&log)?)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to record LAN transfer: {}", e);
+    }
+}
+
+// ============================================================================
+// Auto Sync Filtering
+// ============================================================================
+
+fn item_size(item: &LanItem) -> u64 {
+    [Some(&item.content), item.html.as_ref(), item.images.as_ref()]
+        .into_iter()
+        .flatten()
+        .map(|part| part.len() as u64)
+        .sum()
+}
+
+/// Apply a peer's sync settings to an automatic transfer: images are dropped unless
+/// allowed, then the size limit is checked. `Err` explains why the item was skipped.
+fn filter_for_sync(item: &mut LanItem, settings: &PeerSyncSettings) -> Result<(), String> {
+    if !settings.include_images && item.images.is_some() {
+        item.images = None;
+        if item.content.trim().is_empty() {
+            return Err("Image-only clip and images are off".to_string());
+        }
+    }
+    let size = item_size(item);
+    if size > settings.max_bytes {
+        return Err(format!("{} bytes is over the {} byte limit", size, settings.max_bytes));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Crypto
+// ============================================================================
+
+fn generate_keypair() -> Result<(EphemeralPrivateKey, String), LanSyncError> {
+    let rng = ring::rand::SystemRandom::new();
+    let private = EphemeralPrivateKey::generate(&X25519, &rng).map_err(|_| LanSyncError::Crypto)?;
+    let public = private.compute_public_key().map_err(|_| LanSyncError::Crypto)?;
+    Ok((private, STANDARD.encode(public.as_ref())))
+}
+
+/// Session key and verification code from the X25519 secret. Both sides get the same
+/// result because the device ids are ordered before being mixed in.
+fn derive_keys(shared_secret: &[u8], local_id: &str, peer_id: &str) -> ([u8; 32], String) {
+    let (first, second) = if local_id < peer_id { (local_id, peer_id) } else { (peer_id, local_id) };
+    let salt = format!("{}:{}", first, second);
+    let hkdf = Hkdf::<Sha256>::new(Some(salt.as_bytes()), shared_secret);
+    let mut key = [0u8; 32];
+    let mut code = [0u8; 4];
+    // Output lengths are far below HKDF's limit, so expand can't fail
+    hkdf.expand(b"wingman-lan-sync key", &mut key).ok();
+    hkdf.expand(b"wingman-lan-sync code", &mut code).ok();
+    (key, format!("{:06}", u32::from_be_bytes(code) % 1_000_000))
+}
+
+fn agree(private: EphemeralPrivateKey, peer_public: &str, peer_id: &str) -> Result<([u8; 32], String), LanSyncError> {
+    let peer_public = STANDARD
+        .decode(peer_public)
+        .map_err(|_| LanSyncError::Protocol("Invalid public key".to_string()))?;
+    agreement::agree_ephemeral(private, &UnparsedPublicKey::new(&X25519, &peer_public), |secret| {
+        derive_keys(secret, local_device_id(), peer_id)
+    })
+    .map_err(|_| LanSyncError::Crypto)
+}
+
+/// Encrypt `item` for a peer; the sender id is authenticated alongside it
+fn encrypt_item(key: &[u8; 32], sender_id: &str, item: &LanItem) -> Result<(String, String), LanSyncError> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| LanSyncError::Crypto)?;
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let plaintext = serde_json::to_vec(item)?;
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &plaintext,
+                aad: sender_id.as_bytes(),
+            },
+        )
+        .map_err(|_| LanSyncError::Crypto)?;
+    Ok((STANDARD.encode(nonce), STANDARD.encode(ciphertext)))
+}
+
+fn decrypt_item(key: &[u8; 32], sender_id: &str, nonce: &str, ciphertext: &str) -> Result<LanItem, LanSyncError> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| LanSyncError::Crypto)?;
+    let nonce = STANDARD.decode(nonce).map_err(|_| LanSyncError::Crypto)?;
+    let ciphertext = STANDARD.decode(ciphertext).map_err(|_| LanSyncError::Crypto)?;
+    if nonce.len() != 12 {
+        return Err(LanSyncError::Crypto);
+    }
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: sender_id.as_bytes(),
+            },
+        )
+        .map_err(|_| LanSyncError::Crypto)?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+// ============================================================================
+// Wire Protocol
+// ============================================================================
+
+/// Messages are JSON, prefixed with their length as a big-endian u32
+async fn write_message(stream: &mut TcpStream, message: &Message) -> Result<(), LanSyncError> {
+    let body = serde_json::to_vec(message)?;
+    if body.len() > MAX_FRAME_BYTES {
+        return Err(LanSyncError::Protocol("Item is too large to send".to_string()));
+    }
+    stream.write_u32(body.len() as u32).await?;
+    stream.write_all(&body).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn read_message(stream: &mut TcpStream, timeout: Duration) -> Result<Message, LanSyncError> {
+    tokio::time::timeout(timeout, async {
+        let len = stream.read_u32().await? as usize;
+        if len > MAX_FRAME_BYTES {
+            return Err(LanSyncError::Protocol("Message too large".to_string()));
+        }
+        let mut body = vec![0u8; len];
+        stream.read_exact(&mut body).await?;
+        Ok(serde_json::from_slice(&body)?)
+    })
+    .await?
+}
+
+fn pending_pairings() -> std::sync::MutexGuard<'static, Option<HashMap<String, oneshot::Sender<bool>>>> {
+    PENDING_PAIRINGS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Wait for the local user's verdict, swap verdicts with the peer and store the key
+/// if both accepted. Runs on both sides of a pairing.
+async fn finish_pairing(
+    app: &AppHandle,
+    mut stream: TcpStream,
+    peer_id: String,
+    peer_name: String,
+    key: [u8; 32],
+) -> Result<bool, LanSyncError> {
+    let (sender, receiver) = oneshot::channel();
+    pending_pairings()
+        .get_or_insert_with(HashMap::new)
+        .insert(peer_id.clone(), sender);
+
+    let accepted_here = matches!(tokio::time::timeout(PAIRING_TIMEOUT, receiver).await, Ok(Ok(true)));
+    if let Some(pending) = pending_pairings().as_mut() {
+        pending.remove(&peer_id);
+    }
+
+    write_message(&mut stream, &Message::PairConfirm { accepted: accepted_here }).await?;
+    let accepted_there = matches!(
+        read_message(&mut stream, PAIRING_TIMEOUT).await,
+        Ok(Message::PairConfirm { accepted: true })
+    );
+
+    if accepted_here && accepted_there {
+        let peer = save_paired_peer(&peer_id, &peer_name, &key)?;
+        log::info!("Paired with LAN device {}", peer.id);
+        app.emit("lan-peer-paired", &peer).ok();
+        Ok(true)
+    } else {
+        app.emit("lan-pairing-failed", &peer_id).ok();
+        Ok(false)
+    }
+}
+
+async fn respond_to_pairing(
+    app: &AppHandle,
+    mut stream: TcpStream,
+    version: u32,
+    peer_id: String,
+    peer_name: String,
+    peer_public: String,
+) -> Result<(), LanSyncError> {
+    if version != PROTOCOL_VERSION {
+        return Err(LanSyncError::Protocol(format!("Unsupported protocol version {}", version)));
+    }
+    let (private, public_key) = generate_keypair()?;
+    write_message(
+        &mut stream,
+        &Message::PairResponse {
+            device_id: local_device_id().to_string(),
+            name: get_device_name(),
+            public_key,
+        },
+    )
+    .await?;
+    let (key, code) = agree(private, &peer_public, &peer_id)?;
+    app.emit(
+        "lan-pair-request",
+        PairingCode {
+            peer_id: peer_id.clone(),
+            peer_name: peer_name.clone(),
+            code,
+        },
+    )
+    .ok();
+    finish_pairing(app, stream, peer_id, peer_name, key).await.map(|_| ())
+}
+
+/// Add an automatically synced item to history. It goes straight in with `add_entry`
+/// rather than through `add_history_entry`, so it is never pushed on again.
+fn store_synced_item(app: &AppHandle, item: &LanItem) -> Result<(), String> {
+    if crate::privacy::is_privacy_mode_active() {
+        return Err("History is paused while privacy mode is on".to_string());
+    }
+    let expires_at = item
+        .expires_at
+        .as_deref()
+        .map(|t| crate::date_format::parse_timestamp(t).ok_or_else(|| format!("Invalid expiry: {}", t)))
+        .transpose()?;
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    let id = add_entry(
+        &conn,
+        &item.content,
+        item.language.as_deref(),
+        None,
+        item.images.as_deref(),
+        item.html.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+    if expires_at.is_some() {
+        set_entry_expiry(&conn, id, expires_at).map_err(|e| e.to_string())?;
+    }
+    if let Ok(Some(entry)) = get_entry(&conn, id) {
+        app.emit("history-entry-added", &entry).ok();
+    }
+    drop(conn);
+    crate::tray::rebuild(app);
+    Ok(())
+}
+
+/// Record `nonce` as seen from `sender_id`. False if it was already seen within the
+/// window `MAX_ITEM_AGE_SECS` allows, which means the item is a replay.
+fn remember_nonce(sender_id: &str, nonce: &str, now: i64) -> bool {
+    let mut seen = SEEN_NONCES.lock().unwrap_or_else(|e| e.into_inner());
+    let peers = seen.get_or_insert_with(HashMap::new);
+    // Anything older than twice the age limit would be refused as too old anyway
+    for nonces in peers.values_mut() {
+        nonces.retain(|_, seen_at| now - *seen_at <= MAX_ITEM_AGE_SECS * 2);
+    }
+    peers.retain(|_, nonces| !nonces.is_empty());
+    peers
+        .entry(sender_id.to_string())
+        .or_default()
+        .insert(nonce.to_string(), now)
+        .is_none()
+}
+
+fn receive_item(
+    app: &AppHandle,
+    sender_id: &str,
+    nonce: &str,
+    ciphertext: &str,
+    automatic: bool,
+) -> Result<(), LanSyncError> {
+    let peer = find_peer(sender_id)?;
+    let mut item = decrypt_item(&load_peer_key(sender_id)?, sender_id, nonce, ciphertext)?;
+    let now = chrono::Utc::now().timestamp();
+    if (now - item.sent_at).abs() > MAX_ITEM_AGE_SECS {
+        return Err(LanSyncError::Protocol("Item is too old; check both clocks".to_string()));
+    }
+    // Only authenticated items get here, so a stranger can't fill the cache
+    if !remember_nonce(sender_id, nonce, now) {
+        return Err(LanSyncError::Protocol("Item was already received".to_string()));
+    }
+
+    if automatic {
+        let refusal = if !peer.sync.auto_sync {
+            Some((TransferStatus::Rejected, "Auto sync is off for this device".to_string()))
+        } else {
+            match filter_for_sync(&mut item, &peer.sync) {
+                Ok(()) => store_synced_item(app, &item).err().map(|e| (TransferStatus::Failed, e)),
+                Err(reason) => Some((TransferStatus::Skipped, reason)),
+            }
+        };
+        let (status, detail) = match refusal {
+            Some((status, reason)) => (status, Some(reason)),
+            None => (TransferStatus::Delivered, None),
+        };
+        log_transfer(&peer, TransferDirection::Received, true, &item, status, detail.clone());
+        return match detail {
+            Some(reason) => Err(LanSyncError::Rejected(reason)),
+            None => Ok(()),
+        };
+    }
+
+    log_transfer(&peer, TransferDirection::Received, false, &item, TransferStatus::Delivered, None);
+    app.emit(
+        "lan-item-received",
+        ReceivedItem {
+            peer_id: peer.id,
+            peer_name: peer.name,
+            item,
+        },
+    )
+    .ok();
+    Ok(())
+}
+
+async fn handle_connection(app: &AppHandle, mut stream: TcpStream) -> Result<(), LanSyncError> {
+    match read_message(&mut stream, IO_TIMEOUT).await? {
+        Message::PairRequest {
+            version,
+            device_id,
+            name,
+            public_key,
+        } => respond_to_pairing(app, stream, version, device_id, name, public_key).await,
+        Message::Item {
+            device_id,
+            nonce,
+            ciphertext,
+            automatic,
+        } => {
+            let result = receive_item(app, &device_id, &nonce, &ciphertext, automatic);
+            let ack = Message::Ack {
+                ok: result.is_ok(),
+                error: result.as_ref().err().map(|e| match e {
+                    LanSyncError::Rejected(reason) => reason.clone(),
+                    other => other.to_string(),
+                }),
+            };
+            write_message(&mut stream, &ack).await?;
+            result
+        }
+        _ => Err(LanSyncError::Protocol("Unexpected message".to_string())),
+    }
+}
+
+// ============================================================================
+// Service
+// ============================================================================
+
+fn open_mdns_socket() -> std::io::Result<std::net::UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    // The system responder (mDNSResponder, Avahi) already listens on 5353
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, MDNS_PORT)).into())?;
+    socket.join_multicast_v4(&MDNS_ADDR, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_loop_v4(true)?;
+    socket.set_nonblocking(true)?;
+    Ok(socket.into())
+}
+
+/// Address of the interface multicast traffic leaves through
+fn local_ipv4() -> Option<Ipv4Addr> {
+    let socket = std::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((MDNS_ADDR, MDNS_PORT)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        std::net::IpAddr::V4(ip) if !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+fn local_service(port: u16) -> ServiceInfo {
+    ServiceInfo {
+        id: local_device_id().to_string(),
+        name: get_device_name(),
+        port,
+    }
+}
+
+fn record_announcement(announcement: Announcement, from: SocketAddr) {
+    let mut discovered = DISCOVERED.lock().unwrap_or_else(|e| e.into_inner());
+    let discovered = discovered.get_or_insert_with(HashMap::new);
+    if announcement.ttl == 0 {
+        discovered.remove(&announcement.info.id);
+        return;
+    }
+    discovered.insert(
+        announcement.info.id,
+        Discovered {
+            name: announcement.info.name,
+            address: SocketAddr::new(from.ip(), announcement.info.port),
+            expires: Instant::now() + Duration::from_secs(announcement.ttl as u64),
+        },
+    );
+}
+
+async fn run_mdns(socket: std::net::UdpSocket, port: u16) {
+    let socket = match UdpSocket::from_std(socket) {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::warn!("LAN sync discovery unavailable: {}", e);
+            return;
+        }
+    };
+    let group = SocketAddr::from((MDNS_ADDR, MDNS_PORT));
+    let info = local_service(port);
+    let mut interval = tokio::time::interval(DISCOVERY_INTERVAL);
+    let mut buffer = vec![0u8; 9000];
+
+    socket
+        .send_to(&mdns::build_announcement(&info, local_ipv4(), ANNOUNCE_TTL), group)
+        .await
+        .ok();
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                socket.send_to(&mdns::build_query(), group).await.ok();
+                if let Some(discovered) = DISCOVERED.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+                    discovered.retain(|_, peer| peer.expires > Instant::now());
+                }
+            }
+            received = socket.recv_from(&mut buffer) => {
+                let Ok((len, from)) = received else {
+                    continue;
+                };
+                let packet = &buffer[..len];
+                if mdns::is_service_query(packet) {
+                    let announcement = mdns::build_announcement(&info, local_ipv4(), ANNOUNCE_TTL);
+                    socket.send_to(&announcement, group).await.ok();
+                } else if let Some(announcement) = mdns::parse_announcement(packet) {
+                    if announcement.info.id != info.id {
+                        record_announcement(announcement, from);
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn run_listener(app: AppHandle, listener: std::net::TcpListener) {
+    let listener = match TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("LAN sync listener failed: {}", e);
+            return;
+        }
+    };
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = handle_connection(&app, stream).await {
+                        log::warn!("LAN sync connection from {} failed: {}", address, e);
+                    }
+                });
+            }
+            Err(e) => {
+                log::warn!("LAN sync accept failed: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+/// Start listening and advertising. Does nothing if already running.
+pub fn start(app: &AppHandle) -> Result<(), LanSyncError> {
+    let mut service = SERVICE.lock().unwrap_or_else(|e| e.into_inner());
+    if service.is_some() {
+        return Ok(());
+    }
+
+    let listener = std::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, DEFAULT_PORT))
+        .or_else(|_| std::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)))?;
+    listener.set_nonblocking(true)?;
+    let port = listener.local_addr()?.port();
+
+    let mut tasks = vec![tauri::async_runtime::spawn(run_listener(app.clone(), listener))];
+    match open_mdns_socket() {
+        Ok(socket) => tasks.push(tauri::async_runtime::spawn(run_mdns(socket, port))),
+        Err(e) => log::warn!("LAN sync discovery unavailable: {}", e),
+    }
+
+    log::info!("LAN sync listening on port {}", port);
+    *service = Some(Service { tasks, port });
+    Ok(())
+}
+
+/// Stop the service and tell the network we're gone
+pub fn stop() {
+    let Some(service) = SERVICE.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    for task in service.tasks {
+        task.abort();
+    }
+    if let Ok(socket) = std::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)) {
+        let goodbye = mdns::build_announcement(&local_service(service.port), None, 0);
+        socket.send_to(&goodbye, (MDNS_ADDR, MDNS_PORT)).ok();
+    }
+    if let Some(discovered) = DISCOVERED.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        discovered.clear();
+    }
+    log::info!("LAN sync stopped");
+}
+
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        start(app).map_err(|e| format!("Failed to start LAN sync: {}", e))
+    } else {
+        stop();
+        Ok(())
+    }
+}
+
+fn is_running() -> bool {
+    SERVICE.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+fn discovered_address(peer_id: &str) -> Result<(SocketAddr, String), LanSyncError> {
+    DISCOVERED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|d| d.get(peer_id))
+        .filter(|peer| peer.expires > Instant::now())
+        .map(|peer| (peer.address, peer.name.clone()))
+        .ok_or_else(|| LanSyncError::PeerNotFound(peer_id.to_string()))
+}
+
+async fn connect(address: SocketAddr) -> Result<TcpStream, LanSyncError> {
+    Ok(tokio::time::timeout(IO_TIMEOUT, TcpStream::connect(address)).await??)
+}
+
+async fn deliver(peer_id: &str, item: &LanItem, automatic: bool) -> Result<(), LanSyncError> {
+    let key = load_peer_key(peer_id)?;
+    let (address, _) = discovered_address(peer_id)?;
+    let (nonce, ciphertext) = encrypt_item(&key, local_device_id(), item)?;
+
+    let mut stream = connect(address).await?;
+    let message = Message::Item {
+        device_id: local_device_id().to_string(),
+        nonce,
+        ciphertext,
+        automatic,
+    };
+    write_message(&mut stream, &message).await?;
+    match read_message(&mut stream, IO_TIMEOUT).await? {
+        Message::Ack { ok: true, .. } => Ok(()),
+        Message::Ack { error, .. } => Err(LanSyncError::Rejected(
+            error.unwrap_or_else(|| "no reason given".to_string()),
+        )),
+        _ => Err(LanSyncError::Protocol("Unexpected reply from the other device".to_string())),
+    }
+}
+
+/// Send, then record the outcome in the transfer log
+async fn deliver_logged(peer: &PairedPeer, item: &LanItem, automatic: bool) -> Result<(), LanSyncError> {
+    let result = deliver(&peer.id, item, automatic).await;
+    let (status, detail) = match &result {
+        Ok(()) => (TransferStatus::Delivered, None),
+        Err(LanSyncError::Rejected(reason)) => (TransferStatus::Rejected, Some(reason.clone())),
+        Err(e) => (TransferStatus::Failed, Some(e.to_string())),
+    };
+    log_transfer(peer, TransferDirection::Sent, automatic, item, status, detail);
+    result
+}
+
+fn item_from_entry(entry: &HistoryEntry) -> LanItem {
+    LanItem {
+        content: entry.content.clone(),
+        html: entry.html.clone(),
+        images: entry.images.clone(),
+        language: entry.language.clone(),
+        expires_at: entry.expires_at.clone(),
+        sent_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+/// Push a newly captured clip to every paired device with auto sync on. Returns
+/// immediately; transfers run in the background and end up in the transfer log.
+/// Nothing is pushed in presentation mode, and sensitive entries aren't pushed while
+/// the authentication gate covers them.
+pub fn auto_send(entry: &HistoryEntry) {
+    if !is_running() || crate::presentation::is_active() {
+        return;
+    }
+    if crate::auth_gate::has_sensitive_tag(entry.smart_tags.as_deref())
+        && crate::auth_gate::is_gated(crate::auth_gate::GatedAction::ViewSensitiveEntry)
+    {
+        return;
+    }
+    let peers = match load_peers() {
+        Ok(data) => data.peers,
+        Err(e) => {
+            log::warn!("LAN auto sync skipped: {}", e);
+            return;
+        }
+    };
+    for peer in peers.into_iter().filter(|p| p.sync.auto_sync) {
+        let mut item = item_from_entry(entry);
+        if let Err(reason) = filter_for_sync(&mut item, &peer.sync) {
+            log_transfer(&peer, TransferDirection::Sent, true, &item, TransferStatus::Skipped, Some(reason));
+            continue;
+        }
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = deliver_logged(&peer, &item, true).await {
+                log::debug!("LAN auto sync to {} failed: {}", peer.id, e);
+            }
+        });
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Paired devices plus unpaired Wingman instances currently visible on the network
+#[tauri::command]
+pub fn list_peers() -> Result<Vec<LanPeer>, AppError> {
+    let paired = load_peers()?.peers;
+    let discovered = DISCOVERED.lock()?;
+    let now = Instant::now();
+    let online: HashMap<&String, &Discovered> = discovered
+        .iter()
+        .flatten()
+        .filter(|(_, peer)| peer.expires > now)
+        .collect();
+
+    let mut peers: Vec<LanPeer> = paired
+        .iter()
+        .map(|p| {
+            let seen = online.get(&p.id);
+            LanPeer {
+                id: p.id.clone(),
+                name: seen.map(|d| d.name.clone()).unwrap_or_else(|| p.name.clone()),
+                address: seen.map(|d| d.address.to_string()),
+                paired: true,
+                online: seen.is_some(),
+                sync: Some(p.sync.clone()),
+            }
+        })
+        .collect();
+    for (id, peer) in &online {
+        if !paired.iter().any(|p| &p.id == *id) {
+            peers.push(LanPeer {
+                id: (*id).clone(),
+                name: peer.name.clone(),
+                address: Some(peer.address.to_string()),
+                paired: false,
+                online: true,
+                sync: None,
+            });
+        }
+    }
+    Ok(peers)
+}
+
+/// Start pairing with a discovered device. Returns the code to compare; both users
+/// then call `confirm_pairing`. Emits `lan-peer-paired` or `lan-pairing-failed`.
+#[tauri::command]
+pub async fn pair_peer(app: AppHandle, peer_id: String) -> Result<PairingCode, AppError> {
+    if !is_running() {
+        return Err(LanSyncError::Disabled.into());
+    }
+    let (address, _) = discovered_address(&peer_id)?;
+    let mut stream = connect(address).await?;
+
+    let (private, public_key) = generate_keypair()?;
+    let request = Message::PairRequest {
+        version: PROTOCOL_VERSION,
+        device_id: local_device_id().to_string(),
+        name: get_device_name(),
+        public_key,
+    };
+    write_message(&mut stream, &request).await?;
+    let (device_id, name, peer_public) = match read_message(&mut stream, IO_TIMEOUT).await? {
+        Message::PairResponse {
+            device_id,
+            name,
+            public_key,
+        } => (device_id, name, public_key),
+        _ => return Err(AppError::internal("Unexpected reply to pairing request")),
+    };
+    if device_id != peer_id {
+        return Err(AppError::internal("A different device answered the pairing request"));
+    }
+    let (key, code) = agree(private, &peer_public, &peer_id)?;
+
+    let pairing = PairingCode {
+        peer_id: peer_id.clone(),
+        peer_name: name.clone(),
+        code,
+    };
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = finish_pairing(&app, stream, peer_id, name, key).await {
+            log::warn!("LAN pairing failed: {}", e);
+        }
+    });
+    Ok(pairing)
+}
+
+/// Accept or reject an in-progress pairing once the codes have been compared
+#[tauri::command]
+pub fn confirm_pairing(peer_id: String, accept: bool) -> Result<(), AppError> {
+    let sender = pending_pairings()
+        .as_mut()
+        .and_then(|pending| pending.remove(&peer_id))
+        .ok_or_else(|| AppError::not_found("No pairing in progress with this device"))?;
+    sender.send(accept).ok();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unpair_peer(peer_id: String) -> Result<(), AppError> {
+    let mut data = load_peers()?;
+    data.peers.retain(|p| p.id != peer_id);
+    save_peers(&data)?;
+    delete_credential(key_credential(&peer_id))
+}
+
+/// Send a history entry (text, HTML and images) to a paired device
+#[tauri::command]
+pub async fn send_to_peer(state: State<'_, AppState>, entry_id: i64, peer_id: String) -> Result<(), AppError> {
+    if crate::privacy::is_privacy_mode_active() {
+        return Err(AppError::privacy_mode("Sending is paused while privacy mode is on"));
+    }
+    if !is_running() {
+        return Err(LanSyncError::Disabled.into());
+    }
+    let entry = {
+        let conn = state.db.lock()?;
+        get_entry(&conn, entry_id)?
+            .ok_or(HistoryError::EntryNotFound(entry_id))?
+    };
+    let peer = find_peer(&peer_id)?;
+    deliver_logged(&peer, &item_from_entry(&entry), false)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Change how a paired device is synced automatically
+#[tauri::command]
+pub fn update_peer_settings(peer_id: String, settings: PeerSyncSettings) -> Result<PairedPeer, AppError> {
+    let mut data = load_peers()?;
+    let peer = data
+        .peers
+        .iter_mut()
+        .find(|p| p.id == peer_id)
+        .ok_or_else(|| LanSyncError::NotPaired(peer_id.clone()))?;
+    peer.sync = settings;
+    let updated = peer.clone();
+    save_peers(&data)?;
+    Ok(updated)
+}
+
+/// Recent transfers in both directions, newest first
+#[tauri::command]
+pub fn get_lan_transfer_log(peer_id: Option<String>, limit: Option<usize>) -> Result<Vec<TransferRecord>, AppError> {
+    let log = load_transfer_log()?;
+    Ok(log
+        .transfers
+        .into_iter()
+        .rev()
+        .filter(|t| peer_id.as_ref().map_or(true, |id| &t.peer_id == id))
+        .take(limit.unwrap_or(MAX_LOG_ENTRIES))
+        .collect())
+}
+
+#[tauri::command]
+pub fn clear_lan_transfer_log() -> Result<(), AppError> {
+    let _guard = TRANSFER_LOG_LOCK.lock()?;
+    let path = transfer_log_path()?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_agreement_matches_on_both_sides() {
+        let (alice_private, alice_public) = generate_keypair().unwrap();
+        let (bob_private, bob_public) = generate_keypair().unwrap();
+        let decode = |key: &str| STANDARD.decode(key).unwrap();
+
+        let alice = agreement::agree_ephemeral(alice_private, &UnparsedPublicKey::new(&X25519, decode(&bob_public)), |s| {
+            derive_keys(s, "alice", "bob")
+        })
+        .unwrap();
+        let bob = agreement::agree_ephemeral(bob_private, &UnparsedPublicKey::new(&X25519, decode(&alice_public)), |s| {
+            derive_keys(s, "bob", "alice")
+        })
+        .unwrap();
+        assert_eq!(alice, bob);
+        assert_eq!(alice.1.len(), 6);
+    }
+
+    #[test]
+    fn test_item_encryption_is_bound_to_sender() {
+        let key = [7u8; 32];
+        let item = LanItem {
+            content: "hello".to_string(),
+            html: None,
+            images: None,
+            language: Some("plaintext".to_string()),
+            expires_at: None,
+            sent_at: 1_700_000_000,
+        };
+        let (nonce, ciphertext) = encrypt_item(&key, "sender", &item).unwrap();
+        assert_eq!(decrypt_item(&key, "sender", &nonce, &ciphertext).unwrap().content, "hello");
+        assert!(decrypt_item(&key, "someone-else", &nonce, &ciphertext).is_err());
+        assert!(decrypt_item(&[8u8; 32], "sender", &nonce, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_sync_filtering() {
+        let item = LanItem {
+            content: "x".repeat(100),
+            html: None,
+            images: Some("[\"data:image/png;base64,AAAA\"]".to_string()),
+            language: None,
+            expires_at: None,
+            sent_at: 0,
+        };
+        let text_only = PeerSyncSettings {
+            auto_sync: true,
+            include_images: false,
+            max_bytes: 1024,
+        };
+        let mut filtered = item.clone();
+        filter_for_sync(&mut filtered, &text_only).unwrap();
+        assert!(filtered.images.is_none());
+
+        let with_images = PeerSyncSettings {
+            include_images: true,
+            ..text_only.clone()
+        };
+        let mut filtered = item.clone();
+        filter_for_sync(&mut filtered, &with_images).unwrap();
+        assert!(filtered.images.is_some());
+
+        let tiny = PeerSyncSettings {
+            max_bytes: 50,
+            ..text_only.clone()
+        };
+        assert!(filter_for_sync(&mut item.clone(), &tiny).is_err());
+
+        let mut image_only = LanItem {
+            content: String::new(),
+            ..item
+        };
+        assert!(filter_for_sync(&mut image_only, &text_only).is_err());
+    }
+
+    #[test]
+    fn test_replayed_nonces_are_refused() {
+        let now = 1_700_000_000;
+        assert!(remember_nonce("replay-peer", "nonce-a", now));
+        assert!(!remember_nonce("replay-peer", "nonce-a", now + 10));
+        // Nonces are tracked per peer
+        assert!(remember_nonce("replay-other-peer", "nonce-a", now + 10));
+        // Once the age check would refuse the item anyway, the nonce is forgotten
+        let later = now + MAX_ITEM_AGE_SECS * 2 + 20;
+        assert!(remember_nonce("replay-peer", "nonce-a", later));
+    }
+
+    #[test]
+    fn test_local_device_id_hides_machine_id() {
+        let id = local_device_id();
+        assert_eq!(id.len(), 16);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
@@ -0,0 +1,307 @@
+// Data provider for launcher extensions (Alfred, Raycast and the like). Queries return
+// compact items - title, subtitle, an icon hint and an action id - for snippets and
+// history, and an action id is later handed back to execute_launcher_action. The same
+// two operations are served over HTTP by local_api.rs.
+//
+// Action ids are "<verb>:<kind>:<id>": verbs are copy, paste and open, kinds are
+// snippet and history. Items carry the copy action; extensions can swap the verb.
+// Copying or pasting a sensitive history entry goes through the authentication gate
+// like get_entry_content does, since the local API can't prompt on its own.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use thiserror::Error;
+
+use crate::auth_gate::{self, GatedAction};
+use crate::error::AppError;
+use crate::history::{get_entries, get_entry, search_entries, HistoryEntry};
+use crate::storage::{load_snippets, Snippet};
+use crate::AppState;
+
+pub const DEFAULT_LIMIT: u32 = 20;
+pub const MAX_LIMIT: u32 = 100;
+const MAX_TITLE_CHARS: usize = 80;
+
+#[derive(Error, Debug)]
+pub enum LauncherError {
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("History error: {0}")]
+    History(#[from] crate::history::HistoryError),
+    #[error("Unknown launcher action: {0}")]
+    UnknownAction(String),
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("Database lock poisoned")]
+    Lock,
+    #[error("{0}")]
+    Denied(AppError),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LauncherItem {
+    pub title: String,
+    pub subtitle: String,
+    /// "snippet", "image", or the language of a history entry ("text" if unknown)
+    pub icon: String,
+    pub action_id: String,
+}
+
+/// Payload of `launcher-open`, emitted after the panel is shown for an open action
+#[derive(Debug, Clone, Serialize)]
+pub struct LauncherOpen {
+    pub kind: &'static str,
+    pub id: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum Verb {
+    Copy,
+    Paste,
+    Open,
+}
+
+#[derive(Debug, PartialEq)]
+enum Target {
+    Snippet(String),
+    History(i64),
+}
+
+fn parse_action(action_id: &str) -> Result<(Verb, Target), LauncherError> {
+    let unknown = || LauncherError::UnknownAction(action_id.to_string());
+    let mut parts = action_id.splitn(3, ':');
+    let verb = match parts.next() {
+        Some("copy") => Verb::Copy,
+        Some("paste") => Verb::Paste,
+        Some("open") => Verb::Open,
+        _ => return Err(unknown()),
+    };
+    let target = match (parts.next(), parts.next()) {
+        (Some("snippet"), Some(id)) if !id.is_empty() => Target::Snippet(id.to_string()),
+        (Some("history"), Some(id)) => Target::History(id.parse().map_err(|_| unknown())?),
+        _ => return Err(unknown()),
+    };
+    Ok((verb, target))
+}
+
+fn truncate(text: &str) -> String {
+    let mut chars = text.chars();
+    let head: String = chars.by_ref().take(MAX_TITLE_CHARS).collect();
+    if chars.next().is_some() {
+        format!("{}…", head.trim_end())
+    } else {
+        head
+    }
+}
+
+fn first_line(text: &str) -> Option<&str> {
+    text.lines().map(str::trim).find(|line| !line.is_empty())
+}
+
+/// How well a snippet matches a lowercased query: name prefix beats name, name beats
+/// tags, tags beat content. With no query favorites come first.
+fn snippet_score(snippet: &Snippet, query: &str) -> Option<u32> {
+    if query.is_empty() {
+        return Some(u32::from(snippet.favorite));
+    }
+    let name = snippet.name.to_lowercase();
+    if name.starts_with(query) {
+        Some(4)
+    } else if name.contains(query) {
+        Some(3)
+    } else if snippet.tags.iter().any(|tag| tag.to_lowercase().contains(query)) {
+        Some(2)
+    } else if snippet.content.to_lowercase().contains(query) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+fn snippet_item(snippet: &Snippet) -> LauncherItem {
+    let subtitle = if snippet.tags.is_empty() {
+        first_line(&snippet.content).map(truncate).unwrap_or_default()
+    } else {
+        snippet.tags.join(", ")
+    };
+    LauncherItem {
+        title: truncate(&snippet.name),
+        subtitle,
+        icon: "snippet".to_string(),
+        action_id: format!("copy:snippet:{}", snippet.id),
+    }
+}
+
+fn history_title(entry: &HistoryEntry) -> String {
+    match entry.title.as_deref().or_else(|| first_line(&entry.content)) {
+        Some(title) => truncate(title),
+        None if entry.images.is_some() => "Image".to_string(),
+        None => "(empty)".to_string(),
+    }
+}
+
+fn history_item(entry: &HistoryEntry) -> LauncherItem {
+    let icon = if entry.content.trim().is_empty() && entry.images.is_some() {
+        "image".to_string()
+    } else {
+        entry.language.clone().unwrap_or_else(|| "text".to_string())
+    };
+    let date = entry.created_at.split(['T', ' ']).next().unwrap_or_default();
+    LauncherItem {
+        title: history_title(entry),
+        subtitle: format!("{} lines · {}", entry.line_count, date),
+        icon,
+        action_id: format!("copy:history:{}", entry.id),
+    }
+}
+
+/// Matching snippets, best first, followed by matching history, newest first
+pub fn query(conn: &Connection, query: &str, limit: u32) -> Result<Vec<LauncherItem>, LauncherError> {
+    let limit = limit.clamp(1, MAX_LIMIT);
+    let needle = query.trim().to_lowercase();
+
+    let mut snippets: Vec<(u32, Snippet)> = load_snippets()?
+        .snippets
+        .into_iter()
+        .filter_map(|snippet| snippet_score(&snippet, &needle).map(|score| (score, snippet)))
+        .collect();
+    snippets.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.to_lowercase().cmp(&b.1.name.to_lowercase())));
+
+    let mut items: Vec<LauncherItem> = snippets
+        .iter()
+        .take(limit as usize)
+        .map(|(_, snippet)| snippet_item(snippet))
+        .collect();
+
+    let remaining = limit - items.len() as u32;
+    if remaining > 0 {
+        let entries = if needle.is_empty() {
+            get_entries(conn, remaining, 0)?
+        } else {
+            search_entries(conn, query.trim(), remaining)?
+        };
+        items.extend(crate::for_display(entries).iter().map(history_item));
+    }
+    Ok(items)
+}
+
+/// Run an action id returned by `query`
+pub async fn execute(app: &AppHandle, action_id: &str) -> Result<(), LauncherError> {
+    if crate::presentation::is_active() {
+        return Err(LauncherError::Denied(crate::presentation::blocked_error()));
+    }
+    let (verb, target) = parse_action(action_id)?;
+
+    if verb == Verb::Open {
+        let open = match target {
+            Target::Snippet(id) => LauncherOpen { kind: "snippet", id },
+            Target::History(id) => LauncherOpen {
+                kind: "history",
+                id: id.to_string(),
+            },
+        };
+        let handle = app.clone();
+        app.run_on_main_thread(move || {
+            crate::tray::show_panel(&handle, None);
+            handle.emit("launcher-open", &open).ok();
+        })
+        .ok();
+        return Ok(());
+    }
+
+    let (content, html) = match target {
+        Target::Snippet(id) => {
+            let snippet = load_snippets()?
+                .snippets
+                .into_iter()
+                .find(|s| s.id == id)
+                .ok_or_else(|| LauncherError::NotFound(format!("Snippet {}", id)))?;
+            (snippet.content, None)
+        }
+        Target::History(id) => {
+            let entry = {
+                let state = app.state::<AppState>();
+                let conn = state.db.lock().map_err(|_| LauncherError::Lock)?;
+                get_entry(&conn, id)?.ok_or_else(|| LauncherError::NotFound(format!("History entry {}", id)))?
+            };
+            if auth_gate::has_sensitive_tag(entry.smart_tags.as_deref()) {
+                auth_gate::ensure(GatedAction::ViewSensitiveEntry)
+                    .await
+                    .map_err(LauncherError::Denied)?;
+            }
+            (entry.content, entry.html)
+        }
+    };
+
+    match verb {
+        Verb::Paste => crate::tray::paste_content(app, &content, html.as_deref()),
+        _ => {
+            crate::tray::copy_content(app, &content, html.as_deref());
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn query_for_launcher(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<LauncherItem>, AppError> {
+    let conn = state.db.lock()?;
+    self::query(&conn, &query, limit.unwrap_or(DEFAULT_LIMIT)).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn execute_launcher_action(app: AppHandle, action_id: String) -> Result<(), AppError> {
+    execute(&app, &action_id).await.map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, tags: &[&str], content: &str) -> Snippet {
+        Snippet {
+            name: name.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Snippet::for_test("s1", content)
+        }
+    }
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(
+            parse_action("copy:snippet:abc-123").unwrap(),
+            (Verb::Copy, Target::Snippet("abc-123".to_string()))
+        );
+        assert_eq!(parse_action("open:history:42").unwrap(), (Verb::Open, Target::History(42)));
+        assert!(parse_action("paste:history:abc").is_err());
+        assert!(parse_action("delete:history:1").is_err());
+        assert!(parse_action("copy:snippet:").is_err());
+    }
+
+    #[test]
+    fn test_snippet_score() {
+        let s = snippet("Docker cleanup", &["shell"], "docker system prune -af");
+        assert_eq!(snippet_score(&s, "dock"), Some(4));
+        assert_eq!(snippet_score(&s, "clean"), Some(3));
+        assert_eq!(snippet_score(&s, "shell"), Some(2));
+        assert_eq!(snippet_score(&s, "prune"), Some(1));
+        assert_eq!(snippet_score(&s, "kubectl"), None);
+        assert_eq!(snippet_score(&s, ""), Some(0));
+    }
+
+    #[test]
+    fn test_truncate_and_subtitle() {
+        let long = "x".repeat(100);
+        assert_eq!(truncate(&long).chars().count(), MAX_TITLE_CHARS + 1);
+        assert_eq!(truncate("short"), "short");
+        assert_eq!(snippet_item(&snippet("a", &[], "\n  first line\nsecond")).subtitle, "first line");
+    }
+}
@@ -1,41 +1,164 @@
+mod accessibility;
+mod actions;
+mod ai_history;
+mod ai_workflows;
+mod attachments;
+mod auth_gate;
+mod autostart;
+mod binary_formats;
+mod bulk_template;
+mod certificates;
+mod characters;
+mod classify;
 mod clipboard;
+mod clipboard_inventory;
+mod code_image;
+mod command_middleware;
+mod command_runner;
+mod compression;
 mod credentials;
+mod custom_prompts;
+mod data_bundle;
+mod date_format;
+pub mod dev_tools;
+mod drafts;
+mod drag_out;
+mod error;
+mod event_bridge;
+mod fake_data;
 mod formatters;
+mod frontmost;
+mod gist_sync;
 mod github;
+mod grammar;
+mod highlight;
 mod history;
+mod history_analytics;
+mod history_bulk;
+mod history_search;
 mod hotkey;
+mod hotkey_conflicts;
+mod http_client;
+mod http_runner;
+mod indexer;
+mod json_tools;
+mod lan_sync;
+mod launcher;
 mod license;
+mod link_preview;
+mod links;
+mod local_api;
+mod log_parser;
+mod mdns;
 mod native_clipboard;
+mod net_tools;
+mod notifications;
+mod offline;
+mod oversize;
+mod panel_animation;
+mod paste;
+mod paste_profiles;
+mod pdf_export;
+mod permissions;
 mod premium;
+mod presentation;
+mod privacy;
+mod profiles;
+mod prompt_library;
+mod quick_save;
+mod rich_markdown;
+mod rules;
+mod saved_searches;
+mod semantic;
+#[cfg(target_os = "macos")]
+mod services;
+mod sharing;
+mod snippet_duplicates;
+mod snippet_export;
+mod snippet_sources;
+mod snippet_versions;
+mod ssh_keys;
+mod stack_trace;
+mod startup;
 mod storage;
+mod suggestions;
+mod summarize;
+mod temp_files;
+mod translate;
+mod trash;
+mod tray;
+mod uninstall;
 mod updater;
+mod url_tools;
+mod watch_folders;
 #[cfg(target_os = "macos")]
 mod window;
+mod window_presets;
 
 use rusqlite::Connection;
 use std::sync::Mutex;
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    AppHandle, Emitter, Manager, State,
-};
+use tauri::{AppHandle, Emitter, Manager, State};
 #[cfg(target_os = "macos")]
 use tauri_nspanel::ManagerExt;
 #[cfg(target_os = "macos")]
 use window::{start_workspace_monitor, set_window_blur, update_vibrancy_material, get_window_monitor_name, disable_webview_spellcheck, WebviewWindowExt, MAIN_WINDOW_LABEL};
 
-use clipboard::{calculate_text_stats, transform_text, TextStats, TextTransform};
-use credentials::{store_credential, get_credential, delete_credential};
+use accessibility::get_accessibility_prefs;
+use actions::{execute_action, list_actions};
+use ai_history::{clear_ai_history, delete_ai_interaction, list_ai_history, rerun_ai_interaction, search_ai_history};
+use ai_workflows::{cancel_ai_workflow, run_ai_workflow};
+use auth_gate::require_authentication;
+use autostart::{get_launch_at_login, set_launch_at_login};
+use binary_formats::{base64_to_hex, decode_base64_binary, from_hex_dump, hex_dump, hex_to_base64};
+use bulk_template::render_bulk_template;
+use certificates::{decode_certificate, decode_csr};
+use characters::{clear_recent_characters, get_recent_characters, record_character_use, search_characters};
+use clipboard::{
+    calculate_text_stats, convert_for_copy, transform_text, CopyFormat, TextStats, TextTransform, TransformParams,
+};
+use clipboard_inventory::read_clipboard_inventory;
+use code_image::render_code_image;
+use command_middleware::{get_command_stats, track};
+use command_runner::run_snippet_command;
+use compression::compact_database;
+use credentials::{store_credential, delete_credential, list_credentials, rotate_credential, revoke_credential, reveal_credential};
+use custom_prompts::run_custom_prompt;
+use data_bundle::{export_all_data, import_all_data};
+use date_format::format_timestamp;
+use dev_tools::{benchmark_history_ops, seed_fake_history};
+use drafts::{discard_draft, list_drafts, restore_draft, save_draft};
+use drag_out::prepare_drag_file;
+use error::{AppError, ErrorCode};
+use event_bridge::get_event_bridge_info;
+use gist_sync::{resolve_gist_conflict, sync_all_gist_snippets};
 use github::{
     check_github_auth_status, create_github_gist, delete_github_gist, get_github_config,
     list_wingman_gists, logout_github, poll_github_device_flow, save_github_config,
     start_github_device_flow, update_github_gist,
 };
+use grammar::{check_text, get_grammar_config, save_grammar_config};
 use history::{
-    add_entry, cleanup_old_entries, clear_history, delete_entry, export_history, get_entries,
-    get_stats, init_database, search_entries, HistoryEntry, HistoryStats,
+    add_entry, cleanup_old_entries, clear_history, delete_entry, export_history, get_entries_cached,
+    get_entries_after, get_entries_before, get_entries_by_smart_tag, get_entries_by_source_app, get_entry,
+    get_source_apps, get_stats, init_database, list_versions, reclassify_entries, restore_version, search_entries, update_entry,
+    HistoryEntry, HistoryEntryVersion, HistoryStats, SourceAppCount,
 };
-use hotkey::{get_default_hotkey, validate_hotkey};
+use history_analytics::get_history_analytics;
+use history_bulk::{bulk_delete_entries, bulk_export_entries, bulk_tag_entries};
+use history_search::search_history_advanced;
+use saved_searches::{delete_saved_search, list_saved_searches, run_saved_search, save_search};
+use hotkey::{get_default_hotkey, set_summon_hotkey, validate_hotkey};
+use hotkey_conflicts::check_hotkey_conflicts;
+use http_client::set_proxy_password;
+use http_runner::{parse_curl, run_http_request};
+use indexer::{get_index_status, rebuild_index};
+use startup::get_startup_metrics;
+use json_tools::{infer_json_schema, json_to_typescript_interface, query_json, sort_json_keys};
+use lan_sync::{
+    clear_lan_transfer_log, confirm_pairing, get_lan_transfer_log, list_peers, pair_peer, send_to_peer, unpair_peer,
+    update_peer_settings,
+};
+use launcher::{execute_launcher_action, query_for_launcher};
 use license::{
     check_license_status, is_feature_enabled, load_license_cache, refresh_license,
     validate_license_online, deactivate_license_online, clear_license_cache,
@@ -47,31 +170,120 @@ use premium::{
     load_ai_config, save_ai_config, load_ai_presets, save_ai_presets,
     SubscriptionStatus, UsageStats, AIResponse, ObsidianConfig, ObsidianResult, AIConfig, AIPresetsConfig,
 };
+use link_preview::{fetch_url_metadata, url_to_markdown_link};
+use links::{get_links, link_items, unlink_items};
+use local_api::{get_local_api_info, regenerate_local_api_token};
+use log_parser::{filter_log_lines, parse_log_lines};
+use net_tools::{analyze_ip, dns_lookup, expand_cidr};
+use notifications::NotificationCategory;
+use offline::is_online;
+use paste::{get_paste_config, publish_paste, save_paste_config};
+use paste_profiles::{get_active_paste_profile, get_paste_profiles, save_paste_profiles_cmd};
+use pdf_export::{export_to_pdf, print_content};
+use permissions::{check_permissions, request_permission};
+use presentation::{get_presentation_mode, set_presentation_mode};
+use privacy::{ensure_history_writable, get_privacy_mode, is_privacy_mode_active, set_privacy_mode};
+use profiles::{create_profile, get_active_profile, list_profiles, switch_profile};
+use prompt_library::{export_ai_prompts, import_ai_prompts};
+use quick_save::quick_save;
+use rich_markdown::{convert_rich_to_markdown, read_clipboard_capture};
+use rules::{
+    clear_rule_runs, create_rule, delete_rule, get_rule_runs, list_rules, set_rule_enabled, test_rule, update_rule,
+};
+use semantic::semantic_search;
+use sharing::{create_email_draft, list_share_services, share_content};
+use snippet_duplicates::{find_duplicate_snippets, merge_snippets};
+use snippet_export::export_snippets;
+use snippet_sources::{refresh_snippet_from_source, set_snippet_source};
+use snippet_versions::{compare_snippet_versions, get_snippet_versions};
+use ssh_keys::{convert_ssh_key, fingerprint_ssh_key, generate_ssh_key};
+use stack_trace::format_stack_trace;
 use storage::{
-    load_settings, load_snippets, save_settings, save_snippets, AppSettings, Snippet, SnippetsData,
+    load_settings, load_snippets, save_settings, save_snippets, AppSettings, Snippet, SnippetKind, SnippetsData,
     load_custom_transformations, save_custom_transformations, CustomTransformationsData,
     load_transformation_chains, save_transformation_chains, TransformationChainsData,
-    load_custom_ai_prompts, save_custom_ai_prompts, CustomAIPrompt, CustomAIPromptsData,
+    load_custom_ai_prompts, save_custom_ai_prompts, CustomAIPrompt, CustomAIPromptsData, PromptVariable,
+    load_ai_workflows, save_ai_workflows, AIWorkflowsData,
 };
-use updater::{check_for_updates, download_and_install_update as do_update, UpdateInfo};
+use suggestions::{get_suggested_actions, record_action_use};
+use summarize::{suggest_title, summarize_text};
+use temp_files::purge_temp_files;
+use translate::{list_supported_languages, translate_text};
+use trash::{empty_trash, list_trash, restore_deleted};
+use tray::rebuild_tray_menu;
+use uninstall::{prepare_remove_all_app_data, remove_all_app_data};
+use updater::{
+    check_for_updates, download_and_install_update as do_update, get_release_notes, install_ready_update,
+    DownloadState, ReleaseNotes, UpdateInfo,
+};
+use url_tools::{build_url, parse_url, parse_user_agent};
+use watch_folders::{add_watch_folder, list_watch_folders, remove_watch_folder};
+use window_presets::{apply_window_preset, cycle_window_preset};
 
 pub struct AppState {
     db: Mutex<Connection>,
     #[cfg(target_os = "macos")]
-    previous_app: Mutex<Option<(String, std::time::Instant)>>,
+    previous_app: Mutex<Option<(frontmost::FrontmostApp, std::time::Instant)>>,
     /// Track if window has been shown/positioned this session (don't re-center after first show)
     pub has_been_shown: std::sync::atomic::AtomicBool,
 }
 
 // Settings commands
 #[tauri::command]
-fn get_settings() -> Result<AppSettings, String> {
-    load_settings().map_err(|e| e.to_string())
+fn get_settings() -> Result<AppSettings, AppError> {
+    load_settings().map_err(AppError::from)
 }
 
+/// Turning off an authentication gate asks for Touch ID / Windows Hello first
 #[tauri::command]
-fn update_settings(settings: AppSettings) -> Result<(), String> {
-    save_settings(&settings).map_err(|e| e.to_string())
+async fn update_settings(app: AppHandle, mut settings: AppSettings) -> Result<(), AppError> {
+    let previous = load_settings()?;
+    if auth_gate::lowers(&previous.authentication_gate, &settings.authentication_gate) {
+        auth_gate::ensure(auth_gate::GatedAction::LowerGate).await?;
+    }
+    if previous.update_channel != settings.update_channel {
+        // Validates the channel and discards a download from the old one
+        updater::set_update_channel(&settings.update_channel).map_err(AppError::invalid_input)?;
+        settings.update_channel = load_settings()?.update_channel;
+    }
+    if previous.launch_at_login != settings.launch_at_login {
+        autostart::set_enabled(settings.launch_at_login)?;
+    }
+    if previous.profile_switch_hotkey != settings.profile_switch_hotkey {
+        profiles::register_switch_hotkey(&app, settings.profile_switch_hotkey.as_deref())?;
+    }
+    if previous.window_preset_hotkey != settings.window_preset_hotkey {
+        window_presets::register_hotkey(&app, settings.window_preset_hotkey.as_deref())?;
+    }
+    if previous.ai_history_retention_days != settings.ai_history_retention_days {
+        let state = app.state::<AppState>();
+        let conn = state.db.lock()?;
+        ai_history::apply_retention(&conn, settings.ai_history_retention_days)?;
+    }
+    if previous.trash_retention_days != settings.trash_retention_days {
+        let state = app.state::<AppState>();
+        let conn = state.db.lock()?;
+        trash::apply_retention(&conn, settings.trash_retention_days)?;
+    }
+    if previous.lan_sync_enabled != settings.lan_sync_enabled {
+        lan_sync::set_enabled(&app, settings.lan_sync_enabled)?;
+    }
+    if previous.local_api_enabled != settings.local_api_enabled {
+        local_api::set_enabled(&app, settings.local_api_enabled)?;
+    }
+    if previous.event_bridge != settings.event_bridge {
+        event_bridge::configure(&settings.event_bridge)?;
+    }
+    if previous.offline_mode != settings.offline_mode {
+        offline::set_enabled(settings.offline_mode);
+    }
+    if previous.proxy != settings.proxy || previous.tls != settings.tls {
+        http_client::configure(&settings.proxy, &settings.tls)?;
+    }
+    if previous.audit_log_enabled != settings.audit_log_enabled {
+        command_middleware::configure(settings.audit_log_enabled)?;
+    }
+    save_settings(&settings).map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -79,6 +291,7 @@ fn get_default_hotkey_cmd() -> String {
     get_default_hotkey()
 }
 
+/// Plain shortcuts, "DoubleTap+<modifier>" and two-step chords like "Control+K Control+S"
 #[tauri::command]
 fn validate_hotkey_cmd(shortcut: String) -> bool {
     validate_hotkey(&shortcut)
@@ -87,76 +300,255 @@ fn validate_hotkey_cmd(shortcut: String) -> bool {
 // History commands
 #[tauri::command]
 fn add_history_entry(
+    app: AppHandle,
     state: State<AppState>,
     content: String,
     language: Option<String>,
     tags: Option<String>,
     images: Option<String>,
-) -> Result<i64, String> {
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
-    add_entry(&conn, &content, language.as_deref(), tags.as_deref(), images.as_deref()).map_err(|e| e.to_string())
+    html: Option<String>,
+) -> Result<i64, AppError> {
+    ensure_history_writable()?;
+
+    let source = frontmost::entry_source(&state);
+    let conn = state.db.lock()?;
+    let id = add_entry(&conn, &content, language.as_deref(), tags.as_deref(), images.as_deref(), html.as_deref())?;
+    if let Some(source) = source {
+        history::set_entry_source(&conn, id, &source)?;
+    }
+    let added = get_entry(&conn, id).ok().flatten();
+    drop(conn);
+
+    if let Some(entry) = added {
+        // Let infinite-scroll views prepend the new entry without re-querying
+        app.emit("history-entry-added", &entry).ok();
+        event_bridge::publish(event_bridge::BridgeEvent::entry_added(&entry));
+        lan_sync::auto_send(&entry);
+        rules::on_entry_added(&app, entry);
+    }
+    tray::rebuild(&app);
+
+    Ok(id)
+}
+
+/// History as the frontend may show it: sensitive entries masked behind the
+/// authentication gate, everything redacted in presentation mode
+fn for_display(entries: Vec<HistoryEntry>) -> Vec<HistoryEntry> {
+    presentation::redact_if_active(auth_gate::mask_if_gated(entries))
+}
+
+#[tauri::command]
+fn get_history(state: State<AppState>, limit: u32, offset: u32) -> Result<Vec<HistoryEntry>, AppError> {
+    let conn = state.db.lock()?;
+    Ok(for_display(get_entries_cached(&conn, limit, offset)?))
+}
+
+/// Full content of an entry whose listed `content` is a preview (`content_truncated`).
+/// Sensitive entries ask for Touch ID / Windows Hello first when that gate is on.
+#[tauri::command]
+async fn get_entry_content(state: State<'_, AppState>, id: i64) -> Result<String, AppError> {
+    let sensitive = auth_gate::entry_is_sensitive(&*state.db.lock()?, id)?;
+    if sensitive {
+        auth_gate::ensure(auth_gate::GatedAction::ViewSensitiveEntry).await?;
+    }
+    let conn = state.db.lock()?;
+    history::get_entry_content(&conn, id).map_err(AppError::from)
+}
+
+/// Cursor-based paging towards older entries. Omit `cursor_id` for the first page.
+#[tauri::command]
+fn get_history_after(state: State<AppState>, cursor_id: Option<i64>, limit: u32) -> Result<Vec<HistoryEntry>, AppError> {
+    let conn = state.db.lock()?;
+    Ok(for_display(get_entries_after(&conn, cursor_id, limit)?))
+}
+
+/// Cursor-based paging towards newer entries (e.g. to catch up after scrolling back to the top)
+#[tauri::command]
+fn get_history_before(state: State<AppState>, cursor_id: i64, limit: u32) -> Result<Vec<HistoryEntry>, AppError> {
+    let conn = state.db.lock()?;
+    Ok(for_display(get_entries_before(&conn, cursor_id, limit)?))
+}
+
+#[tauri::command]
+fn search_history(state: State<AppState>, query: String, limit: u32) -> Result<Vec<HistoryEntry>, AppError> {
+    let conn = state.db.lock()?;
+    Ok(for_display(search_entries(&conn, &query, limit)?))
+}
+
+/// Entries of one kind of content ("url", "json", "stack_trace", ...; see classify.rs)
+#[tauri::command]
+fn get_history_by_smart_tag(state: State<AppState>, tag: String, limit: u32) -> Result<Vec<HistoryEntry>, AppError> {
+    let tag = classify::SmartTag::parse(&tag).ok_or_else(|| AppError::invalid_input(format!("Unknown smart tag: {}", tag)))?;
+    let conn = state.db.lock()?;
+    Ok(for_display(get_entries_by_smart_tag(&conn, tag.as_str(), limit)?))
+}
+
+/// Entries saved from one app (name or bundle id)
+#[tauri::command]
+fn get_history_by_source_app(
+    state: State<AppState>,
+    app: String,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    let conn = state.db.lock()?;
+    Ok(for_display(get_entries_by_source_app(&conn, &app, limit, offset)?))
+}
+
+#[tauri::command]
+fn list_history_source_apps(state: State<AppState>) -> Result<Vec<SourceAppCount>, AppError> {
+    let conn = state.db.lock()?;
+    get_source_apps(&conn).map_err(AppError::from)
 }
 
+/// Backfill smart tags for existing history. Returns how many entries changed.
 #[tauri::command]
-fn get_history(state: State<AppState>, limit: u32, offset: u32) -> Result<Vec<HistoryEntry>, String> {
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
-    get_entries(&conn, limit, offset).map_err(|e| e.to_string())
+fn reclassify_history(state: State<AppState>) -> Result<u32, AppError> {
+    let conn = state.db.lock()?;
+    reclassify_entries(&conn).map_err(AppError::from)
 }
 
+/// Edit a history entry in place; the previous content is kept as a revision
 #[tauri::command]
-fn search_history(state: State<AppState>, query: String, limit: u32) -> Result<Vec<HistoryEntry>, String> {
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
-    search_entries(&conn, &query, limit).map_err(|e| e.to_string())
+fn update_history_entry(app: AppHandle, state: State<AppState>, id: i64, content: String) -> Result<(), AppError> {
+    ensure_history_writable()?;
+    let conn = state.db.lock()?;
+    update_entry(&conn, id, &content)?;
+    drop(conn);
+    tray::rebuild(&app);
+    Ok(())
 }
 
+/// Earlier revisions hold the full content, so sensitive entries ask first like
+/// get_entry_content
 #[tauri::command]
-fn delete_history_entry(state: State<AppState>, id: i64) -> Result<(), String> {
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
-    delete_entry(&conn, id).map_err(|e| e.to_string())
+async fn get_entry_versions(state: State<'_, AppState>, id: i64) -> Result<Vec<HistoryEntryVersion>, AppError> {
+    let sensitive = auth_gate::entry_is_sensitive(&*state.db.lock()?, id)?;
+    if sensitive {
+        auth_gate::ensure(auth_gate::GatedAction::ViewSensitiveEntry).await?;
+    }
+    let conn = state.db.lock()?;
+    list_versions(&conn, id).map_err(AppError::from)
 }
 
 #[tauri::command]
-fn clear_all_history(state: State<AppState>) -> Result<(), String> {
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
-    clear_history(&conn).map_err(|e| e.to_string())
+fn restore_entry_version(app: AppHandle, state: State<AppState>, id: i64, version: i64) -> Result<(), AppError> {
+    ensure_history_writable()?;
+    let conn = state.db.lock()?;
+    restore_version(&conn, id, version)?;
+    drop(conn);
+    tray::rebuild(&app);
+    Ok(())
 }
 
 #[tauri::command]
-fn get_history_stats(state: State<AppState>) -> Result<HistoryStats, String> {
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
-    get_stats(&conn).map_err(|e| e.to_string())
+fn delete_history_entry(app: AppHandle, state: State<AppState>, id: i64) -> Result<(), AppError> {
+    let conn = state.db.lock()?;
+    delete_entry(&conn, id)?;
+    drop(conn);
+    tray::rebuild(&app);
+    Ok(())
 }
 
+/// Make an entry self-destruct at `timestamp` (RFC 3339), or keep it forever with `None`
 #[tauri::command]
-fn cleanup_history(state: State<AppState>, max_entries: u32) -> Result<u32, String> {
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
-    cleanup_old_entries(&conn, max_entries).map_err(|e| e.to_string())
+fn set_entry_expiry(state: State<AppState>, id: i64, timestamp: Option<String>) -> Result<(), AppError> {
+    let expires_at = timestamp
+        .map(|t| {
+            chrono::DateTime::parse_from_rfc3339(&t)
+                .map(|t| t.with_timezone(&chrono::Utc))
+                .map_err(|e| AppError::invalid_input(format!("Invalid timestamp: {}", e)))
+        })
+        .transpose()?;
+    let conn = state.db.lock()?;
+    history::set_entry_expiry(&conn, id, expires_at).map_err(AppError::from)
+}
+
+/// Remind about an entry at `timestamp` (RFC 3339) with an optional note, or cancel the
+/// reminder with `None`. The panel opens on the entry when it fires.
+#[tauri::command]
+fn set_entry_reminder(
+    state: State<AppState>,
+    id: i64,
+    timestamp: Option<String>,
+    note: Option<String>,
+) -> Result<(), AppError> {
+    let remind_at = timestamp
+        .map(|t| {
+            chrono::DateTime::parse_from_rfc3339(&t)
+                .map(|t| t.with_timezone(&chrono::Utc))
+                .map_err(|e| AppError::invalid_input(format!("Invalid timestamp: {}", e)))
+        })
+        .transpose()?;
+    let conn = state.db.lock()?;
+    history::set_entry_reminder(&conn, id, remind_at, note.as_deref()).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn list_upcoming_reminders(state: State<AppState>, limit: Option<u32>) -> Result<Vec<HistoryEntry>, AppError> {
+    let conn = state.db.lock()?;
+    Ok(for_display(history::get_upcoming_reminders(&conn, limit.unwrap_or(50))?))
+}
+
+#[tauri::command]
+fn clear_all_history(app: AppHandle, state: State<AppState>) -> Result<(), AppError> {
+    let conn = state.db.lock()?;
+    clear_history(&conn)?;
+    drop(conn);
+    tray::rebuild(&app);
+    Ok(())
 }
 
 #[tauri::command]
-fn export_all_history(state: State<AppState>) -> Result<Vec<HistoryEntry>, String> {
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
-    export_history(&conn).map_err(|e| e.to_string())
+fn get_history_stats(state: State<AppState>) -> Result<HistoryStats, AppError> {
+    let conn = state.db.lock()?;
+    get_stats(&conn).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn cleanup_history(app: AppHandle, state: State<AppState>, max_entries: u32) -> Result<u32, AppError> {
+    let conn = state.db.lock()?;
+    let removed = cleanup_old_entries(&conn, max_entries)?;
+    drop(conn);
+    if removed > 0 {
+        tray::rebuild(&app);
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+fn export_all_history(state: State<AppState>) -> Result<Vec<HistoryEntry>, AppError> {
+    let conn = state.db.lock()?;
+    Ok(for_display(export_history(&conn)?))
 }
 
 // Snippets commands
 #[tauri::command]
-fn get_snippets() -> Result<SnippetsData, String> {
-    load_snippets().map_err(|e| e.to_string())
+fn get_snippets() -> Result<SnippetsData, AppError> {
+    load_snippets().map_err(AppError::from)
 }
 
+/// Replace the snippet list, e.g. on import. Like update_snippet, a snippet changed from
+/// an older copy than the saved one is refused with a conflict.
 #[tauri::command]
-fn save_snippets_data(data: SnippetsData) -> Result<(), String> {
-    save_snippets(&data).map_err(|e| e.to_string())
+fn save_snippets_data(app: AppHandle, state: State<AppState>, mut data: SnippetsData) -> Result<(), AppError> {
+    let saved = load_snippets()?.snippets;
+    snippet_sources::keep_sources(&saved, &mut data.snippets)?;
+    snippet_versions::ensure_current(&saved, &data.snippets)?;
+    snippet_versions::save_snippets_versioned(&*state.db.lock()?, &mut data)?;
+    tray::rebuild(&app);
+    Ok(())
 }
 
 #[tauri::command]
 fn add_snippet(
+    state: State<'_, AppState>,
     name: String,
     content: String,
     tags: Vec<String>,
-) -> Result<Snippet, String> {
-    let mut data = load_snippets().map_err(|e| e.to_string())?;
+    source_entry_id: Option<i64>,
+) -> Result<Snippet, AppError> {
+    let mut data = load_snippets()?;
     let now = chrono::Utc::now().to_rfc3339();
     let snippet = Snippet {
         id: uuid_v4(),
@@ -170,34 +562,134 @@ fn add_snippet(
         github_gist_filename: None,
         github_synced_at: None,
         github_source: None,
+        favorite: false,
+        kind: SnippetKind::Text,
+        version: 0,
+        source: None,
     };
     data.snippets.push(snippet.clone());
-    save_snippets(&data).map_err(|e| e.to_string())?;
+    save_snippets(&data)?;
+
+    // Provenance: the history entry this snippet was saved from
+    let conn = state.db.lock()?;
+    let target = links::ItemRef::snippet(&snippet.id);
+    let linked = match source_entry_id {
+        Some(entry_id) => links::add_link(&conn, &links::ItemRef::history(entry_id), &target, links::RELATION_SAVED_AS).map(Some),
+        None => links::link_from_matching_entry(&conn, &snippet.content, &target, links::RELATION_SAVED_AS),
+    };
+    if let Err(e) = linked {
+        log::warn!("Failed to link snippet {}: {}", snippet.id, e);
+    }
     Ok(snippet)
 }
 
+fn edit_snippet(
+    app: &AppHandle,
+    state: &AppState,
+    id: &str,
+    name: String,
+    content: String,
+    tags: Vec<String>,
+    expected_version: Option<u64>,
+) -> Result<Snippet, AppError> {
+    let mut data = load_snippets()?;
+    let snippet = data
+        .snippets
+        .iter_mut()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", id)))?;
+    if expected_version.is_some_and(|version| version != snippet.version) {
+        return Err(snippet_versions::stale_edit(snippet));
+    }
+    snippet_sources::ensure_editable(snippet, &content)?;
+    snippet.name = name;
+    snippet.content = content;
+    snippet.tags = tags;
+    snippet.touch();
+    let updated = snippet.clone();
+    snippet_versions::save_snippets_versioned(&*state.db.lock()?, &mut data)?;
+    // Favorites are listed in the tray by name
+    tray::rebuild(app);
+    Ok(updated)
+}
+
+/// Save an edit made against `version` of the snippet. If it has changed since, nothing
+/// is saved and the conflict error's details carry the current copy as `current`.
 #[tauri::command]
 fn update_snippet(
+    app: AppHandle,
+    state: State<AppState>,
     id: String,
     name: String,
     content: String,
     tags: Vec<String>,
-) -> Result<(), String> {
-    let mut data = load_snippets().map_err(|e| e.to_string())?;
+    version: u64,
+) -> Result<Snippet, AppError> {
+    edit_snippet(&app, &state, &id, name, content, tags, Some(version))
+}
+
+/// update_snippet without the version check, for keeping this copy after a conflict
+#[tauri::command]
+fn force_update_snippet(
+    app: AppHandle,
+    state: State<AppState>,
+    id: String,
+    name: String,
+    content: String,
+    tags: Vec<String>,
+) -> Result<Snippet, AppError> {
+    edit_snippet(&app, &state, &id, name, content, tags, None)
+}
+
+/// Make a saved version of the snippet current again. The copy it replaces is saved as
+/// a version too, so rolling back never loses anything.
+#[tauri::command]
+fn rollback_snippet(app: AppHandle, state: State<AppState>, id: String, version: u64) -> Result<Snippet, AppError> {
+    let saved = snippet_versions::get_version(&*state.db.lock()?, &id, version)?
+        .ok_or_else(|| AppError::not_found(format!("Version {} of snippet {} not found", version, id)))?;
+    edit_snippet(&app, &state, &id, saved.name, saved.content, saved.tags, None)
+}
+
+#[tauri::command]
+fn delete_snippet(app: AppHandle, id: String) -> Result<(), AppError> {
+    let mut data = load_snippets()?;
+    let (removed, kept) = data.snippets.into_iter().partition(|s| s.id == id);
+    data.snippets = kept;
+    trash::trash_snippets(removed)?;
+    save_snippets(&data)?;
+    tray::rebuild(&app);
+    Ok(())
+}
+
+/// Favorite snippets are offered in the tray's Snippets submenu
+#[tauri::command]
+fn set_snippet_favorite(app: AppHandle, id: String, favorite: bool) -> Result<(), AppError> {
+    let mut data = load_snippets()?;
     if let Some(snippet) = data.snippets.iter_mut().find(|s| s.id == id) {
-        snippet.name = name;
-        snippet.content = content;
-        snippet.tags = tags;
+        snippet.favorite = favorite;
         snippet.updated_at = chrono::Utc::now().to_rfc3339();
     }
-    save_snippets(&data).map_err(|e| e.to_string())
+    save_snippets(&data)?;
+    tray::rebuild(&app);
+    Ok(())
 }
 
+/// Command snippets are run with run_snippet_command rather than pasted. Snippets synced
+/// from a gist or URL can't become commands, since their content changes remotely.
 #[tauri::command]
-fn delete_snippet(id: String) -> Result<(), String> {
-    let mut data = load_snippets().map_err(|e| e.to_string())?;
-    data.snippets.retain(|s| s.id != id);
-    save_snippets(&data).map_err(|e| e.to_string())
+fn set_snippet_kind(id: String, kind: SnippetKind) -> Result<(), AppError> {
+    let mut data = load_snippets()?;
+    if let Some(snippet) = data.snippets.iter_mut().find(|s| s.id == id) {
+        if kind == SnippetKind::Command && snippet.is_remote() {
+            return Err(AppError::invalid_input(format!(
+                "\"{}\" is synced from elsewhere and can't be a command snippet",
+                snippet.name
+            )));
+        }
+        snippet.kind = kind;
+        snippet.updated_at = chrono::Utc::now().to_rfc3339();
+    }
+    save_snippets(&data).map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -207,8 +699,8 @@ fn set_snippet_github_info(
     gist_url: String,
     gist_filename: Option<String>,
     github_source: Option<String>,
-) -> Result<(), String> {
-    let mut data = load_snippets().map_err(|e| e.to_string())?;
+) -> Result<(), AppError> {
+    let mut data = load_snippets()?;
     if let Some(snippet) = data.snippets.iter_mut().find(|s| s.id == id) {
         snippet.github_gist_id = Some(gist_id);
         snippet.github_gist_url = Some(gist_url);
@@ -217,12 +709,12 @@ fn set_snippet_github_info(
         snippet.github_source = github_source;
         snippet.updated_at = chrono::Utc::now().to_rfc3339();
     }
-    save_snippets(&data).map_err(|e| e.to_string())
+    save_snippets(&data).map_err(AppError::from)
 }
 
 #[tauri::command]
-fn clear_snippet_github_info(id: String) -> Result<(), String> {
-    let mut data = load_snippets().map_err(|e| e.to_string())?;
+fn clear_snippet_github_info(id: String) -> Result<(), AppError> {
+    let mut data = load_snippets()?;
     if let Some(snippet) = data.snippets.iter_mut().find(|s| s.id == id) {
         snippet.github_gist_id = None;
         snippet.github_gist_url = None;
@@ -231,18 +723,29 @@ fn clear_snippet_github_info(id: String) -> Result<(), String> {
         snippet.github_source = None;
         snippet.updated_at = chrono::Utc::now().to_rfc3339();
     }
-    save_snippets(&data).map_err(|e| e.to_string())
+    save_snippets(&data).map_err(AppError::from)
+}
+
+// AI workflow commands
+#[tauri::command]
+fn get_ai_workflows() -> Result<AIWorkflowsData, AppError> {
+    load_ai_workflows().map_err(AppError::from)
+}
+
+#[tauri::command]
+fn save_ai_workflows_data(data: AIWorkflowsData) -> Result<(), AppError> {
+    save_ai_workflows(&data).map_err(AppError::from)
 }
 
 // Custom AI Prompts commands
 #[tauri::command]
-fn get_custom_ai_prompts() -> Result<CustomAIPromptsData, String> {
-    load_custom_ai_prompts().map_err(|e| e.to_string())
+fn get_custom_ai_prompts() -> Result<CustomAIPromptsData, AppError> {
+    load_custom_ai_prompts().map_err(AppError::from)
 }
 
 #[tauri::command]
-fn save_custom_ai_prompts_data(data: CustomAIPromptsData) -> Result<(), String> {
-    save_custom_ai_prompts(&data).map_err(|e| e.to_string())
+fn save_custom_ai_prompts_data(data: CustomAIPromptsData) -> Result<(), AppError> {
+    save_custom_ai_prompts(&data).map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -250,8 +753,12 @@ fn add_custom_ai_prompt(
     name: String,
     description: String,
     system_prompt: String,
-) -> Result<CustomAIPrompt, String> {
-    let mut data = load_custom_ai_prompts().map_err(|e| e.to_string())?;
+    user_prompt: Option<String>,
+    variables: Option<Vec<PromptVariable>>,
+) -> Result<CustomAIPrompt, AppError> {
+    let variables = variables.unwrap_or_default();
+    custom_prompts::validate_variables(&variables)?;
+    let mut data = load_custom_ai_prompts()?;
     let now = chrono::Utc::now().to_rfc3339();
     let prompt = CustomAIPrompt {
         id: uuid_v4(),
@@ -261,12 +768,16 @@ fn add_custom_ai_prompt(
         enabled: true,
         created_at: now.clone(),
         updated_at: now,
+        user_prompt: user_prompt.filter(|p| !p.trim().is_empty()),
+        variables,
     };
     data.prompts.push(prompt.clone());
-    save_custom_ai_prompts(&data).map_err(|e| e.to_string())?;
+    save_custom_ai_prompts(&data)?;
     Ok(prompt)
 }
 
+/// `user_prompt` and `variables` are left unchanged when omitted; an empty
+/// `user_prompt` removes the template.
 #[tauri::command]
 fn update_custom_ai_prompt(
     id: String,
@@ -274,33 +785,44 @@ fn update_custom_ai_prompt(
     description: String,
     system_prompt: String,
     enabled: bool,
-) -> Result<(), String> {
-    let mut data = load_custom_ai_prompts().map_err(|e| e.to_string())?;
+    user_prompt: Option<String>,
+    variables: Option<Vec<PromptVariable>>,
+) -> Result<(), AppError> {
+    if let Some(variables) = &variables {
+        custom_prompts::validate_variables(variables)?;
+    }
+    let mut data = load_custom_ai_prompts()?;
     if let Some(prompt) = data.prompts.iter_mut().find(|p| p.id == id) {
         prompt.name = name;
         prompt.description = description;
         prompt.system_prompt = system_prompt;
         prompt.enabled = enabled;
+        if let Some(user_prompt) = user_prompt {
+            prompt.user_prompt = Some(user_prompt).filter(|p| !p.trim().is_empty());
+        }
+        if let Some(variables) = variables {
+            prompt.variables = variables;
+        }
         prompt.updated_at = chrono::Utc::now().to_rfc3339();
     }
-    save_custom_ai_prompts(&data).map_err(|e| e.to_string())
+    save_custom_ai_prompts(&data).map_err(AppError::from)
 }
 
 #[tauri::command]
-fn delete_custom_ai_prompt(id: String) -> Result<(), String> {
-    let mut data = load_custom_ai_prompts().map_err(|e| e.to_string())?;
+fn delete_custom_ai_prompt(id: String) -> Result<(), AppError> {
+    let mut data = load_custom_ai_prompts()?;
     data.prompts.retain(|p| p.id != id);
-    save_custom_ai_prompts(&data).map_err(|e| e.to_string())
+    save_custom_ai_prompts(&data).map_err(AppError::from)
 }
 
 #[tauri::command]
-fn toggle_custom_ai_prompt_enabled(id: String) -> Result<(), String> {
-    let mut data = load_custom_ai_prompts().map_err(|e| e.to_string())?;
+fn toggle_custom_ai_prompt_enabled(id: String) -> Result<(), AppError> {
+    let mut data = load_custom_ai_prompts()?;
     if let Some(prompt) = data.prompts.iter_mut().find(|p| p.id == id) {
         prompt.enabled = !prompt.enabled;
         prompt.updated_at = chrono::Utc::now().to_rfc3339();
     }
-    save_custom_ai_prompts(&data).map_err(|e| e.to_string())
+    save_custom_ai_prompts(&data).map_err(AppError::from)
 }
 
 // Text utility commands
@@ -309,24 +831,19 @@ fn get_text_stats(text: String) -> TextStats {
     calculate_text_stats(&text)
 }
 
+/// Run a built-in transform by name. `params` carries the separator, prefix and suffix
+/// of joinlines, splitlines and prefixsuffix.
 #[tauri::command]
-fn transform_text_cmd(text: String, transform: String) -> Result<String, String> {
-    let transform_type = match transform.as_str() {
-        "uppercase" => TextTransform::Uppercase,
-        "lowercase" => TextTransform::Lowercase,
-        "titlecase" => TextTransform::TitleCase,
-        "sentencecase" => TextTransform::SentenceCase,
-        "camelcase" => TextTransform::CamelCase,
-        "snakecase" => TextTransform::SnakeCase,
-        "kebabcase" => TextTransform::KebabCase,
-        "trim" => TextTransform::TrimWhitespace,
-        "sort" => TextTransform::SortLines,
-        "deduplicate" => TextTransform::RemoveDuplicateLines,
-        "reverse" => TextTransform::ReverseLines,
-        "bulletlist" => TextTransform::BulletList,
-        _ => return Err(format!("Unknown transform: {}", transform)),
-    };
-    Ok(transform_text(&text, transform_type))
+fn transform_text_cmd(text: String, transform: String, params: Option<TransformParams>) -> Result<String, AppError> {
+    let transform_type =
+        TextTransform::with_params(&transform, &params.unwrap_or_default()).map_err(AppError::invalid_input)?;
+    let result = transform_text(&text, transform_type);
+    event_bridge::publish(event_bridge::BridgeEvent::TransformRun {
+        transform,
+        input_characters: text.chars().count(),
+        output_characters: result.chars().count(),
+    });
+    Ok(result)
 }
 
 #[tauri::command]
@@ -341,34 +858,66 @@ fn write_native_clipboard(
     text: String,
     html: Option<String>,
     images: Vec<native_clipboard::ClipboardImage>,
-) -> Result<(), String> {
-    native_clipboard::write_to_clipboard(&text, html.as_deref(), &images)
+) -> Result<(), AppError> {
+    native_clipboard::write_to_clipboard(&text, html.as_deref(), &images).map_err(AppError::from)
+}
+
+/// Read plain text, HTML (RTF is converted) and image availability from the clipboard
+#[tauri::command]
+fn read_clipboard_rich() -> Result<native_clipboard::RichClipboardContent, AppError> {
+    if is_privacy_mode_active() {
+        return Err(AppError::privacy_mode("Clipboard capture is paused while privacy mode is on"));
+    }
+    native_clipboard::read_clipboard_rich().map_err(AppError::from)
+}
+
+/// Convert content to the requested representation and write it with matching pasteboard types.
+/// "auto" uses the paste profile for the previous app, or plain text without one.
+#[tauri::command]
+fn copy_as(state: State<AppState>, content: String, target_format: String, language: Option<String>) -> Result<(), AppError> {
+    let format = match target_format.as_str() {
+        "markdown" => CopyFormat::Markdown,
+        "rtf" => CopyFormat::Rtf,
+        "plain" => CopyFormat::Plain,
+        "html" => CopyFormat::Html,
+        "auto" => {
+            let payload = match paste_profiles::profile_for_previous_app(&state) {
+                Some(profile) => paste_profiles::apply_profile(&profile, &content, language.as_deref())?,
+                None => convert_for_copy(&content, CopyFormat::Plain, language.as_deref())?,
+            };
+            return native_clipboard::write_formats_to_clipboard(&payload.text, payload.html.as_deref(), payload.rtf.as_deref()).map_err(AppError::from);
+        }
+        _ => return Err(AppError::invalid_input(format!("Unknown copy format: {}", target_format))),
+    };
+    let payload = convert_for_copy(&content, format, language.as_deref())?;
+    native_clipboard::write_formats_to_clipboard(&payload.text, payload.html.as_deref(), payload.rtf.as_deref()).map_err(AppError::from)
 }
 
 /// Copy content as a file to the system clipboard
 #[tauri::command]
-fn copy_file_to_clipboard(content: String, language: String) -> Result<String, String> {
-    native_clipboard::copy_file_to_clipboard(&content, &language)
+fn copy_file_to_clipboard(content: String, language: String) -> Result<String, AppError> {
+    native_clipboard::copy_file_to_clipboard(&content, &language).map_err(AppError::from)
 }
 
 // JSON/XML formatting commands
+/// Keys come out sorted, as they always have; `preserve_order` keeps them as pasted
 #[tauri::command]
-fn format_json(text: String) -> Result<String, String> {
+fn format_json(text: String, preserve_order: Option<bool>) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::JsonXmlFormatting) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
     let parsed: serde_json::Value = serde_json::from_str(&text)
-        .map_err(|e| format!("Invalid JSON: {}", e))?;
-    serde_json::to_string_pretty(&parsed)
-        .map_err(|e| format!("Failed to format JSON: {}", e))
+        .map_err(|e| AppError::invalid_input(format!("Invalid JSON: {}", e)))?;
+    let parsed = if preserve_order.unwrap_or(false) { parsed } else { json_tools::sort_keys(parsed, true) };
+    Ok(serde_json::to_string_pretty(&parsed)?)
 }
 
 #[tauri::command]
-fn minify_json(text: String) -> Result<String, String> {
+fn minify_json(text: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::JsonXmlFormatting) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
-    formatters::minify_json_code(text)
+    formatters::minify_json_code(text).map_err(AppError::invalid_input)
 }
 
 #[tauri::command]
@@ -428,20 +977,22 @@ fn detect_language(text: String) -> String {
 }
 
 #[tauri::command]
-fn format_code(text: String, language: String) -> Result<String, String> {
+fn format_code(app: AppHandle, text: String, language: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::JsonXmlFormatting) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
 
-    match language.as_str() {
+    let started = std::time::Instant::now();
+    let result = match language.as_str() {
         "json" => formatters::format_json_code(text),
-        "xml" => format_xml(text),
+        "xml" => format_xml(text).map_err(|e| e.message),
         "html" => formatters::format_html_code(text),
         "css" => formatters::format_css_code(text),
         "python" => formatters::format_python_code(text),
         "react" | "jsx" | "tsx" => formatters::format_react_code(text),
         "javascript" | "typescript" => formatters::format_javascript_code(text),
         "sql" => formatters::format_sql_code(text),
+        "graphql" => formatters::format_graphql_code(text),
         "go" => formatters::format_go_code(text),
         "rust" => formatters::format_rust_code(text),
         "java" => formatters::format_java_code(text),
@@ -453,19 +1004,41 @@ fn format_code(text: String, language: String) -> Result<String, String> {
         "bash" => formatters::format_bash_code(text),
         "c" | "cpp" => formatters::format_c_cpp_code(text),
         "markdown" => formatters::format_markdown_code(text),
-        "yaml" => Err("YAML formatting is not recommended as YAML is whitespace-sensitive and formatting may change semantics.".to_string()),
-        "plaintext" => Err("Cannot format plain text. Please select a specific language mode first.".to_string()),
-        _ => Err(format!("Formatting not supported for {}", language))
+        "yaml" => return Err(AppError::unsupported("YAML formatting is not recommended as YAML is whitespace-sensitive and formatting may change semantics.")),
+        "plaintext" => return Err(AppError::unsupported("Cannot format plain text. Please select a specific language mode first.")),
+        _ => return Err(AppError::unsupported(format!("Formatting not supported for {}", language))),
+    }
+    .map_err(AppError::invalid_input);
+    notifications::report(&app, NotificationCategory::Formatting, "Formatting", started, &result);
+    result
+}
+
+#[tauri::command]
+fn format_graphql(text: String) -> Result<String, AppError> {
+    if !is_feature_enabled(ProFeature::JsonXmlFormatting) {
+        return Err(AppError::pro_required());
     }
+    formatters::format_graphql_code(text).map_err(AppError::invalid_input)
+}
+
+/// Substitute bound parameters (`?`, `$1`, `:name`, ...) into a query copied from logs
+#[tauri::command]
+fn inline_sql_params(query: String, params_json: String) -> Result<String, AppError> {
+    formatters::inline_sql_params(&query, &params_json).map_err(AppError::invalid_input)
+}
+
+#[tauri::command]
+fn extract_sql_params(query: String) -> Vec<formatters::SqlParam> {
+    formatters::extract_sql_params(&query)
 }
 
 #[tauri::command]
-fn minify_code(text: String, language: String) -> Result<String, String> {
+fn minify_code(text: String, language: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::JsonXmlFormatting) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
 
-    match language.as_str() {
+    let minified = match language.as_str() {
         // Supported minification
         "json" => formatters::minify_json_code(text),
         "css" => formatters::minify_css_code(text),
@@ -475,30 +1048,31 @@ fn minify_code(text: String, language: String) -> Result<String, String> {
         "xml" => formatters::minify_xml_code(text),
 
         // Languages that don't support minification
-        "sql" => Err("SQL minification is not recommended as it reduces readability without significant benefits.".to_string()),
-        "python" => Err("Python minification is not supported. Python relies on whitespace for syntax, making minification impractical.".to_string()),
-        "yaml" => Err("YAML minification is not supported. YAML is whitespace-sensitive and minification would break the format.".to_string()),
-        "markdown" => Err("Markdown minification is not supported. Markdown formatting is part of the content structure.".to_string()),
-        "bash" => Err("Bash/Shell script minification is not supported. Readability is more important for shell scripts.".to_string()),
-        "go" => Err("Go minification is not supported. Go code is compiled, so minification provides no runtime benefit.".to_string()),
-        "rust" => Err("Rust minification is not supported. Rust code is compiled, so minification provides no runtime benefit.".to_string()),
-        "java" => Err("Java minification is not supported. Java code is compiled, so minification provides no runtime benefit.".to_string()),
-        "php" => Err("PHP minification is not commonly used. Consider using opcode caching (OPcache) for performance instead.".to_string()),
-        "c" | "cpp" => Err("C/C++ minification is not supported. Code is compiled, so minification provides no runtime benefit.".to_string()),
-        "ruby" => Err("Ruby minification is not supported. Ruby emphasizes readability over minification.".to_string()),
-        "swift" => Err("Swift minification is not supported. Swift code is compiled, so minification provides no runtime benefit.".to_string()),
-        "kotlin" => Err("Kotlin minification is not supported. Kotlin code is compiled, so minification provides no runtime benefit.".to_string()),
-        "csharp" => Err("C# minification is not supported. C# code is compiled, so minification provides no runtime benefit.".to_string()),
-        "plaintext" => Err("Cannot minify plain text. Please select a specific language mode first.".to_string()),
-
-        _ => Err(format!("Minify not supported for {}", language))
-    }
+        "sql" => return Err(AppError::unsupported("SQL minification is not recommended as it reduces readability without significant benefits.")),
+        "python" => return Err(AppError::unsupported("Python minification is not supported. Python relies on whitespace for syntax, making minification impractical.")),
+        "yaml" => return Err(AppError::unsupported("YAML minification is not supported. YAML is whitespace-sensitive and minification would break the format.")),
+        "markdown" => return Err(AppError::unsupported("Markdown minification is not supported. Markdown formatting is part of the content structure.")),
+        "bash" => return Err(AppError::unsupported("Bash/Shell script minification is not supported. Readability is more important for shell scripts.")),
+        "go" => return Err(AppError::unsupported("Go minification is not supported. Go code is compiled, so minification provides no runtime benefit.")),
+        "rust" => return Err(AppError::unsupported("Rust minification is not supported. Rust code is compiled, so minification provides no runtime benefit.")),
+        "java" => return Err(AppError::unsupported("Java minification is not supported. Java code is compiled, so minification provides no runtime benefit.")),
+        "php" => return Err(AppError::unsupported("PHP minification is not commonly used. Consider using opcode caching (OPcache) for performance instead.")),
+        "c" | "cpp" => return Err(AppError::unsupported("C/C++ minification is not supported. Code is compiled, so minification provides no runtime benefit.")),
+        "ruby" => return Err(AppError::unsupported("Ruby minification is not supported. Ruby emphasizes readability over minification.")),
+        "swift" => return Err(AppError::unsupported("Swift minification is not supported. Swift code is compiled, so minification provides no runtime benefit.")),
+        "kotlin" => return Err(AppError::unsupported("Kotlin minification is not supported. Kotlin code is compiled, so minification provides no runtime benefit.")),
+        "csharp" => return Err(AppError::unsupported("C# minification is not supported. C# code is compiled, so minification provides no runtime benefit.")),
+        "plaintext" => return Err(AppError::unsupported("Cannot minify plain text. Please select a specific language mode first.")),
+
+        _ => return Err(AppError::unsupported(format!("Minify not supported for {}", language))),
+    };
+    minified.map_err(AppError::invalid_input)
 }
 
 #[tauri::command]
-fn format_xml(text: String) -> Result<String, String> {
+fn format_xml(text: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::JsonXmlFormatting) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
     // Simple XML formatter - add indentation
     let mut result = String::new();
@@ -552,48 +1126,48 @@ fn format_xml(text: String) -> Result<String, String> {
 
 // Encoding/decoding commands
 #[tauri::command]
-fn encode_base64(text: String) -> Result<String, String> {
+fn encode_base64(text: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::EncodeDecode) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
     use base64::{Engine as _, engine::general_purpose::STANDARD};
     Ok(STANDARD.encode(text.as_bytes()))
 }
 
 #[tauri::command]
-fn decode_base64(text: String) -> Result<String, String> {
+fn decode_base64(text: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::EncodeDecode) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
     use base64::{Engine as _, engine::general_purpose::STANDARD};
     let bytes = STANDARD.decode(text.trim())
-        .map_err(|e| format!("Invalid Base64: {}", e))?;
+        .map_err(|e| AppError::invalid_input(format!("Invalid Base64: {}", e)))?;
     String::from_utf8(bytes)
-        .map_err(|e| format!("Invalid UTF-8 in decoded data: {}", e))
+        .map_err(|e| AppError::invalid_input(format!("Invalid UTF-8 in decoded data: {}", e)))
 }
 
 #[tauri::command]
-fn encode_url(text: String) -> Result<String, String> {
+fn encode_url(text: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::EncodeDecode) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
     Ok(urlencoding::encode(&text).into_owned())
 }
 
 #[tauri::command]
-fn decode_url(text: String) -> Result<String, String> {
+fn decode_url(text: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::EncodeDecode) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
     urlencoding::decode(&text)
         .map(|s| s.into_owned())
-        .map_err(|e| format!("Invalid URL encoding: {}", e))
+        .map_err(|e| AppError::invalid_input(format!("Invalid URL encoding: {}", e)))
 }
 
 #[tauri::command]
-fn encode_html(text: String) -> Result<String, String> {
+fn encode_html(text: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::EncodeDecode) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
     Ok(text.chars()
         .map(|c| match c {
@@ -608,9 +1182,9 @@ fn encode_html(text: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-fn decode_html(text: String) -> Result<String, String> {
+fn decode_html(text: String) -> Result<String, AppError> {
     if !is_feature_enabled(ProFeature::EncodeDecode) {
-        return Err("This feature requires a Pro license".to_string());
+        return Err(AppError::pro_required());
     }
     Ok(text.replace("&amp;", "&")
         .replace("&lt;", "<")
@@ -663,10 +1237,10 @@ fn generate_short_hash(length: Option<usize>) -> String {
 }
 
 #[tauri::command]
-fn generate_prefixed_id(prefix: String, id_type: Option<String>) -> Result<String, String> {
+fn generate_prefixed_id(prefix: String, id_type: Option<String>) -> Result<String, AppError> {
     // Validate prefix (alphanumeric + underscore only)
     if !prefix.chars().all(|c| c.is_alphanumeric() || c == '_') {
-        return Err("Prefix must be alphanumeric with underscores only".to_string());
+        return Err(AppError::invalid_input("Prefix must be alphanumeric with underscores only"));
     }
 
     let id = match id_type.as_deref().unwrap_or("nanoid") {
@@ -686,11 +1260,17 @@ fn generate_bulk(
     count: u32,
     prefix: Option<String>,
     length: Option<usize>,
-) -> Result<String, String> {
+    options: Option<fake_data::FakeDataOptions>,
+) -> Result<String, AppError> {
     use sha2::{Digest, Sha256};
 
     let count = count.min(100) as usize; // Cap at 100
 
+    // Names, emails, addresses, ... with locale and seed options
+    if fake_data::is_fake_generator(&generator) {
+        return fake_data::generate(&generator, count, &options.unwrap_or_default());
+    }
+
     let results: Vec<String> = (0..count)
         .map(|i| match generator.as_str() {
             "uuid" | "uuid_v4" => uuid::Uuid::new_v4().to_string(),
@@ -756,28 +1336,20 @@ fn generate_sha512(text: String) -> String {
 }
 
 // Timestamp utility commands
+/// A Unix timestamp (seconds or ms) shown per the date and time settings (see date_format.rs)
 #[tauri::command]
-fn unix_to_human(text: String) -> Result<String, String> {
-    use chrono::DateTime;
-
+fn unix_to_human(text: String) -> Result<String, AppError> {
     let timestamp: i64 = text.trim().parse()
-        .map_err(|_| "Invalid timestamp. Enter a Unix timestamp (e.g., 1704067200)".to_string())?;
+        .map_err(|_| AppError::invalid_input("Invalid timestamp. Enter a Unix timestamp (e.g., 1704067200)"))?;
 
-    // Auto-detect seconds vs milliseconds (timestamps after year 2001 in ms are > 10^12)
-    let (secs, nsecs) = if timestamp > 10_000_000_000 {
-        (timestamp / 1000, ((timestamp % 1000) * 1_000_000) as u32)
-    } else {
-        (timestamp, 0)
-    };
+    let dt = date_format::from_unix(timestamp)
+        .ok_or_else(|| AppError::invalid_input("Invalid timestamp"))?;
 
-    let dt = DateTime::from_timestamp(secs, nsecs)
-        .ok_or_else(|| "Invalid timestamp".to_string())?;
-
-    Ok(dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+    date_format::format(&dt, "datetime").map_err(AppError::invalid_input)
 }
 
 #[tauri::command]
-fn human_to_unix(text: String) -> Result<String, String> {
+fn human_to_unix(text: String) -> Result<String, AppError> {
     use chrono::{NaiveDateTime, TimeZone, Utc, DateTime};
 
     let datetime = text.trim();
@@ -804,64 +1376,26 @@ fn human_to_unix(text: String) -> Result<String, String> {
         return Ok(dt.timestamp().to_string());
     }
 
-    Err("Could not parse datetime. Try formats like: 2024-01-15 14:30:00".to_string())
-}
-
-#[tauri::command]
-fn convert_timezone(datetime: String, from_tz: String, to_tz: String) -> Result<String, String> {
-    use chrono::{NaiveDateTime, TimeZone, FixedOffset};
-
-    // Parse timezone offsets (e.g., "+05:30", "-08:00", "UTC", "Z")
-    fn parse_offset(tz: &str) -> Result<FixedOffset, String> {
-        match tz.to_uppercase().as_str() {
-            "UTC" | "Z" => Ok(FixedOffset::east_opt(0).unwrap()),
-            "EST" => Ok(FixedOffset::west_opt(5 * 3600).unwrap()),
-            "EDT" => Ok(FixedOffset::west_opt(4 * 3600).unwrap()),
-            "CST" => Ok(FixedOffset::west_opt(6 * 3600).unwrap()),
-            "CDT" => Ok(FixedOffset::west_opt(5 * 3600).unwrap()),
-            "MST" => Ok(FixedOffset::west_opt(7 * 3600).unwrap()),
-            "MDT" => Ok(FixedOffset::west_opt(6 * 3600).unwrap()),
-            "PST" => Ok(FixedOffset::west_opt(8 * 3600).unwrap()),
-            "PDT" => Ok(FixedOffset::west_opt(7 * 3600).unwrap()),
-            "IST" => Ok(FixedOffset::east_opt(5 * 3600 + 1800).unwrap()),
-            "JST" => Ok(FixedOffset::east_opt(9 * 3600).unwrap()),
-            "CET" => Ok(FixedOffset::east_opt(1 * 3600).unwrap()),
-            "CEST" => Ok(FixedOffset::east_opt(2 * 3600).unwrap()),
-            "GMT" => Ok(FixedOffset::east_opt(0).unwrap()),
-            "AEST" => Ok(FixedOffset::east_opt(10 * 3600).unwrap()),
-            "AEDT" => Ok(FixedOffset::east_opt(11 * 3600).unwrap()),
-            s if s.starts_with('+') || s.starts_with('-') => {
-                let sign = if s.starts_with('-') { -1 } else { 1 };
-                let parts: Vec<&str> = s[1..].split(':').collect();
-                let hours: i32 = parts.get(0).and_then(|h| h.parse().ok()).unwrap_or(0);
-                let mins: i32 = parts.get(1).and_then(|m| m.parse().ok()).unwrap_or(0);
-                let offset_secs = sign * (hours * 3600 + mins * 60);
-                if sign > 0 {
-                    FixedOffset::east_opt(offset_secs)
-                } else {
-                    FixedOffset::west_opt(-offset_secs)
-                }
-                .ok_or_else(|| format!("Invalid offset: {}", s))
-            }
-            _ => Err(format!(
-                "Unknown timezone: {}. Use UTC, EST, PST, or offset like +05:30",
-                tz
-            )),
-        }
-    }
+    Err(AppError::invalid_input("Could not parse datetime. Try formats like: 2024-01-15 14:30:00"))
+}
+
+#[tauri::command]
+fn convert_timezone(datetime: String, from_tz: String, to_tz: String) -> Result<String, AppError> {
+    use chrono::{NaiveDateTime, TimeZone};
+    use date_format::parse_offset;
 
-    let from_offset = parse_offset(&from_tz)?;
-    let to_offset = parse_offset(&to_tz)?;
+    let from_offset = parse_offset(&from_tz).map_err(AppError::invalid_input)?;
+    let to_offset = parse_offset(&to_tz).map_err(AppError::invalid_input)?;
 
     // Parse the datetime
     let naive = NaiveDateTime::parse_from_str(&datetime, "%Y-%m-%d %H:%M:%S")
         .or_else(|_| NaiveDateTime::parse_from_str(&datetime, "%Y-%m-%d %H:%M"))
-        .map_err(|_| "Could not parse datetime. Use format: 2024-01-15 14:30:00")?;
+        .map_err(|_| AppError::invalid_input("Could not parse datetime. Use format: 2024-01-15 14:30:00"))?;
 
     let from_dt = from_offset
         .from_local_datetime(&naive)
         .single()
-        .ok_or_else(|| "Ambiguous datetime".to_string())?;
+        .ok_or_else(|| AppError::invalid_input("Ambiguous datetime"))?;
 
     let to_dt = from_dt.with_timezone(&to_offset);
 
@@ -944,7 +1478,7 @@ fn generate_lorem_ipsum(paragraphs: u32, format: String) -> String {
 
 // License commands
 #[tauri::command]
-async fn activate_license(license_key: String, email: String) -> Result<LicenseStatusInfo, String> {
+async fn activate_license(license_key: String, email: String) -> Result<LicenseStatusInfo, AppError> {
     validate_license_online(&license_key, &email)
         .await
         .map(|cache| LicenseStatusInfo {
@@ -955,15 +1489,15 @@ async fn activate_license(license_key: String, email: String) -> Result<LicenseS
             needs_revalidation: false,
             is_dev: cache.is_dev,
         })
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-async fn deactivate_license() -> Result<(), String> {
+async fn deactivate_license() -> Result<(), AppError> {
     log::info!("deactivate_license called");
     let cache = load_license_cache().map_err(|e| {
         log::error!("Failed to load license cache: {}", e);
-        e.to_string()
+        AppError::from(e)
     })?;
     log::info!("License cache loaded, license_key present: {}", cache.license_key.is_some());
     if let Some(license_key) = cache.license_key {
@@ -972,17 +1506,17 @@ async fn deactivate_license() -> Result<(), String> {
             .await
             .map_err(|e| {
                 log::error!("deactivate_license_online failed: {}", e);
-                e.to_string()
+                AppError::from(e)
             })
     } else {
         log::info!("No license key in cache, just clearing local cache");
-        clear_license_cache().map_err(|e| e.to_string())
+        clear_license_cache().map_err(AppError::from)
     }
 }
 
 #[tauri::command]
-fn get_license_status() -> Result<LicenseStatusInfo, String> {
-    check_license_status().map_err(|e| e.to_string())
+fn get_license_status() -> Result<LicenseStatusInfo, AppError> {
+    check_license_status().map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -991,7 +1525,7 @@ fn get_cached_license_key_cmd() -> Option<String> {
 }
 
 #[tauri::command]
-fn check_feature_enabled(feature: String) -> Result<bool, String> {
+fn check_feature_enabled(feature: String) -> Result<bool, AppError> {
     let pro_feature = match feature.as_str() {
         "history" => ProFeature::History,
         "syntax_highlighting" => ProFeature::SyntaxHighlighting,
@@ -1003,97 +1537,102 @@ fn check_feature_enabled(feature: String) -> Result<bool, String> {
         "json_xml_formatting" => ProFeature::JsonXmlFormatting,
         "encode_decode" => ProFeature::EncodeDecode,
         "image_attachments" => ProFeature::ImageAttachments,
-        _ => return Err(format!("Unknown feature: {}", feature)),
+        _ => return Err(AppError::invalid_input(format!("Unknown feature: {}", feature))),
     };
     Ok(is_feature_enabled(pro_feature))
 }
 
 #[tauri::command]
-async fn refresh_license_status() -> Result<LicenseStatusInfo, String> {
-    refresh_license().await.map_err(|e| e.to_string())
+async fn refresh_license_status() -> Result<LicenseStatusInfo, AppError> {
+    refresh_license().await.map_err(AppError::from)
 }
 
 // Premium commands
 #[tauri::command]
-async fn validate_premium_license_cmd(license_key: String) -> Result<SubscriptionStatus, String> {
+async fn validate_premium_license_cmd(license_key: String) -> Result<SubscriptionStatus, AppError> {
     validate_premium_license(&license_key)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-async fn get_ai_usage_cmd(license_key: String) -> Result<UsageStats, String> {
+async fn get_ai_usage_cmd(license_key: String) -> Result<UsageStats, AppError> {
     get_ai_usage(&license_key)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-async fn create_customer_portal_session_cmd() -> Result<String, String> {
-    let cache = load_license_cache().map_err(|e| e.to_string())?;
+async fn create_customer_portal_session_cmd() -> Result<String, AppError> {
+    let cache = load_license_cache()?;
     let license_key = cache
         .license_key
-        .ok_or_else(|| "No active license key found".to_string())?;
+        .ok_or_else(|| AppError::new(ErrorCode::LicenseInvalid, "No active license key found"))?;
     let email = cache
         .email
-        .ok_or_else(|| "No active license email found".to_string())?;
-    let device_id = get_device_id().map_err(|e| e.to_string())?;
+        .ok_or_else(|| AppError::new(ErrorCode::LicenseInvalid, "No active license email found"))?;
+    let device_id = get_device_id()?;
 
     create_customer_portal_session(&license_key, &email, &device_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
 async fn call_ai_feature_cmd(
+    app: AppHandle,
     license_key: String,
     prompt: String,
     feature: String,
     system_instructions: Option<String>,
-) -> Result<AIResponse, String> {
-    call_ai_feature(&license_key, &prompt, &feature, system_instructions.as_deref())
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<AIResponse, AppError> {
+    track("call_ai_feature_cmd", async move {
+        let started = std::time::Instant::now();
+        let result = call_ai_feature(&license_key, &prompt, &feature, system_instructions.as_deref()).await;
+        notifications::report(&app, NotificationCategory::Ai, "AI request", started, &result);
+        Ok(result?)
+    })
+    .await
 }
 
 // Obsidian commands
 #[tauri::command]
-fn get_obsidian_config() -> Result<ObsidianConfig, String> {
-    load_obsidian_config().map_err(|e| e.to_string())
+fn get_obsidian_config() -> Result<ObsidianConfig, AppError> {
+    load_obsidian_config().map_err(AppError::from)
 }
 
 #[tauri::command]
-fn configure_obsidian(config: ObsidianConfig) -> Result<(), String> {
+fn configure_obsidian(config: ObsidianConfig) -> Result<(), AppError> {
     // Validate vault path before saving
     if !config.vault_path.is_empty() {
-        validate_obsidian_vault(&config.vault_path).map_err(|e| e.to_string())?;
+        validate_obsidian_vault(&config.vault_path)?;
     }
-    save_obsidian_config(&config).map_err(|e| e.to_string())
+    save_obsidian_config(&config).map_err(AppError::from)
 }
 
 #[tauri::command]
-fn validate_obsidian_vault_cmd(vault_path: String) -> Result<bool, String> {
-    validate_obsidian_vault(&vault_path).map_err(|e| e.to_string())
+fn validate_obsidian_vault_cmd(vault_path: String) -> Result<bool, AppError> {
+    validate_obsidian_vault(&vault_path).map_err(AppError::from)
 }
 
 #[tauri::command]
-fn add_to_obsidian(content: String) -> Result<ObsidianResult, String> {
+fn add_to_obsidian(content: String) -> Result<ObsidianResult, AppError> {
     log::info!("add_to_obsidian called with {} chars", content.len());
 
     let config = load_obsidian_config().map_err(|e| {
         log::error!("Failed to load Obsidian config: {}", e);
-        e.to_string()
+        AppError::from(e)
     })?;
 
     log::info!("Obsidian config loaded: vault_path={}", config.vault_path);
 
     if config.vault_path.is_empty() {
-        return Err("Obsidian vault not configured. Please configure in Settings.".to_string());
+        return Err(AppError::new(ErrorCode::Disabled, "Obsidian vault not configured. Please configure in Settings."));
     }
 
     add_to_obsidian_vault(&content, &config).map_err(|e| {
         log::error!("Failed to add to Obsidian vault: {}", e);
-        e.to_string()
+        AppError::from(e)
     })
 }
 
@@ -1145,46 +1684,46 @@ fn is_allowed_https_host(url: &str, allowed_hosts: &[&str]) -> bool {
 }
 
 #[tauri::command]
-fn open_github_url(url: String) -> Result<(), String> {
+fn open_github_url(url: String) -> Result<(), AppError> {
     if !is_allowed_https_host(&url, &["github.com", "gist.github.com"]) {
-        return Err("Blocked URL host for GitHub open operation.".to_string());
+        return Err(AppError::invalid_input("Blocked URL host for GitHub open operation."));
     }
-    open_with_system_handler(&url)
+    open_with_system_handler(&url).map_err(AppError::from)
 }
 
 #[tauri::command]
-fn open_obsidian_url(url: String) -> Result<(), String> {
+fn open_obsidian_url(url: String) -> Result<(), AppError> {
     if !url.trim().to_ascii_lowercase().starts_with("obsidian://") {
-        return Err("Blocked URL scheme for Obsidian open operation.".to_string());
+        return Err(AppError::invalid_input("Blocked URL scheme for Obsidian open operation."));
     }
-    open_with_system_handler(&url)
+    open_with_system_handler(&url).map_err(AppError::from)
 }
 
 // AI commands
 #[tauri::command]
-fn get_ai_config() -> Result<AIConfig, String> {
-    load_ai_config().map_err(|e| e.to_string())
+fn get_ai_config() -> Result<AIConfig, AppError> {
+    load_ai_config().map_err(AppError::from)
 }
 
 #[tauri::command]
-fn configure_ai(config: AIConfig) -> Result<(), String> {
-    save_ai_config(&config).map_err(|e| e.to_string())
+fn configure_ai(config: AIConfig) -> Result<(), AppError> {
+    save_ai_config(&config).map_err(AppError::from)
 }
 
 // AI Presets commands
 #[tauri::command]
-fn get_ai_presets() -> Result<AIPresetsConfig, String> {
-    load_ai_presets().map_err(|e| e.to_string())
+fn get_ai_presets() -> Result<AIPresetsConfig, AppError> {
+    load_ai_presets().map_err(AppError::from)
 }
 
 #[tauri::command]
-fn save_ai_presets_cmd(config: AIPresetsConfig) -> Result<(), String> {
-    save_ai_presets(&config).map_err(|e| e.to_string())
+fn save_ai_presets_cmd(config: AIPresetsConfig) -> Result<(), AppError> {
+    save_ai_presets(&config).map_err(AppError::from)
 }
 
 // Folder picker command (uses rfd for native dialog)
 #[tauri::command]
-async fn pick_folder(title: Option<String>) -> Result<Option<String>, String> {
+async fn pick_folder(title: Option<String>) -> Result<Option<String>, AppError> {
     // Set flag to prevent panel from hiding when dialog takes focus
     #[cfg(target_os = "macos")]
     {
@@ -1207,13 +1746,50 @@ async fn pick_folder(title: Option<String>) -> Result<Option<String>, String> {
 
 // Update commands
 #[tauri::command]
-async fn check_for_app_updates(app: AppHandle) -> Result<UpdateInfo, String> {
-    check_for_updates(app).await
+async fn check_for_app_updates(app: AppHandle) -> Result<UpdateInfo, AppError> {
+    check_for_updates(app).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn download_and_install_update(app: AppHandle) -> Result<(), AppError> {
+    do_update(app).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_update_channel(channel: String) -> Result<(), AppError> {
+    updater::set_update_channel(&channel).map_err(AppError::invalid_input)
+}
+
+#[tauri::command]
+async fn get_update_release_notes(app: AppHandle) -> Result<Option<ReleaseNotes>, AppError> {
+    get_release_notes(app).await.map_err(AppError::from)
+}
+
+/// Restart into an update that auto-download already fetched
+#[tauri::command]
+fn restart_to_update(app: AppHandle) -> Result<(), AppError> {
+    install_ready_update(app).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn pause_update_download() -> Result<DownloadState, AppError> {
+    updater::set_download_paused(true).map_err(AppError::from)
 }
 
 #[tauri::command]
-async fn download_and_install_update(app: AppHandle) -> Result<(), String> {
-    do_update(app).await
+fn resume_update_download() -> Result<DownloadState, AppError> {
+    updater::set_download_paused(false).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn cancel_update_download() -> Result<(), AppError> {
+    updater::cancel_download().map_err(AppError::from)
+}
+
+/// Download the update now but install it silently on the next quit
+#[tauri::command]
+async fn defer_update_until_quit(app: AppHandle) -> Result<UpdateInfo, AppError> {
+    updater::defer_until_quit(app).await.map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -1223,9 +1799,9 @@ fn get_app_version() -> String {
 
 // Window commands
 #[tauri::command]
-async fn toggle_fullscreen(window: tauri::Window) -> Result<(), String> {
-    let is_fullscreen = window.is_fullscreen().map_err(|e| e.to_string())?;
-    window.set_fullscreen(!is_fullscreen).map_err(|e| e.to_string())?;
+async fn toggle_fullscreen(window: tauri::Window) -> Result<(), AppError> {
+    let is_fullscreen = window.is_fullscreen()?;
+    window.set_fullscreen(!is_fullscreen)?;
     Ok(())
 }
 
@@ -1240,7 +1816,7 @@ static FOCUS_MODE_ACTIVE: std::sync::atomic::AtomicBool = std::sync::atomic::Ato
 #[cfg(target_os = "macos")]
 #[tauri::command]
 #[allow(deprecated)]
-async fn toggle_focus_mode(window: tauri::WebviewWindow) -> Result<bool, String> {
+async fn toggle_focus_mode(window: tauri::WebviewWindow) -> Result<bool, AppError> {
     use cocoa::base::id;
     use cocoa::foundation::{NSPoint, NSRect, NSSize};
     use objc::{msg_send, sel, sel_impl};
@@ -1310,23 +1886,24 @@ async fn toggle_focus_mode(window: tauri::WebviewWindow) -> Result<bool, String>
                 }
             })();
             let _ = tx.send(result);
-        })
-        .map_err(|e| e.to_string())?;
+        })?;
 
-    rx.recv_timeout(Duration::from_secs(5))
-        .map_err(|e| format!("Timeout: {}", e))?
+    let result = rx
+        .recv_timeout(Duration::from_secs(5))
+        .map_err(|e| format!("Timeout: {}", e))?;
+    Ok(result?)
 }
 
 #[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn toggle_focus_mode(window: tauri::WebviewWindow) -> Result<bool, String> {
+async fn toggle_focus_mode(window: tauri::WebviewWindow) -> Result<bool, AppError> {
     // On non-macOS, use the standard maximize/unmaximize
-    let is_maximized = window.is_maximized().map_err(|e| e.to_string())?;
+    let is_maximized = window.is_maximized()?;
     if is_maximized {
-        window.unmaximize().map_err(|e| e.to_string())?;
+        window.unmaximize()?;
         Ok(false)
     } else {
-        window.maximize().map_err(|e| e.to_string())?;
+        window.maximize()?;
         Ok(true)
     }
 }
@@ -1334,7 +1911,7 @@ async fn toggle_focus_mode(window: tauri::WebviewWindow) -> Result<bool, String>
 /// Set window blur effect (vibrancy) - PRO feature
 #[cfg(target_os = "macos")]
 #[tauri::command]
-async fn set_window_blur_cmd(window: tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+async fn set_window_blur_cmd(window: tauri::WebviewWindow, enabled: bool) -> Result<(), AppError> {
     use std::sync::mpsc;
     use std::time::Duration;
 
@@ -1353,17 +1930,18 @@ async fn set_window_blur_cmd(window: tauri::WebviewWindow, enabled: bool) -> Res
                 Err("Window not found".to_string())
             };
             let _ = tx.send(result);
-        })
-        .map_err(|e| e.to_string())?;
+        })?;
 
     // Wait with timeout to avoid hanging
-    rx.recv_timeout(Duration::from_secs(5))
-        .map_err(|e| format!("Timeout waiting for blur operation: {}", e))?
+    let result = rx
+        .recv_timeout(Duration::from_secs(5))
+        .map_err(|e| format!("Timeout waiting for blur operation: {}", e))?;
+    Ok(result?)
 }
 
 #[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn set_window_blur_cmd(_window: tauri::WebviewWindow, _enabled: bool) -> Result<(), String> {
+async fn set_window_blur_cmd(_window: tauri::WebviewWindow, _enabled: bool) -> Result<(), AppError> {
     // Blur effect is only available on macOS
     Ok(())
 }
@@ -1371,7 +1949,7 @@ async fn set_window_blur_cmd(_window: tauri::WebviewWindow, _enabled: bool) -> R
 /// Update vibrancy material for light/dark theme
 #[cfg(target_os = "macos")]
 #[tauri::command]
-async fn set_vibrancy_mode(window: tauri::WebviewWindow, is_dark: bool) -> Result<(), String> {
+async fn set_vibrancy_mode(window: tauri::WebviewWindow, is_dark: bool) -> Result<(), AppError> {
     use std::sync::mpsc;
     use std::time::Duration;
 
@@ -1389,23 +1967,24 @@ async fn set_vibrancy_mode(window: tauri::WebviewWindow, is_dark: bool) -> Resul
                 Err("Window not found".to_string())
             };
             let _ = tx.send(result);
-        })
-        .map_err(|e| e.to_string())?;
+        })?;
 
-    rx.recv_timeout(Duration::from_secs(5))
-        .map_err(|e| format!("Timeout waiting for vibrancy update: {}", e))?
+    let result = rx
+        .recv_timeout(Duration::from_secs(5))
+        .map_err(|e| format!("Timeout waiting for vibrancy update: {}", e))?;
+    Ok(result?)
 }
 
 #[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn set_vibrancy_mode(_window: tauri::WebviewWindow, _is_dark: bool) -> Result<(), String> {
+async fn set_vibrancy_mode(_window: tauri::WebviewWindow, _is_dark: bool) -> Result<(), AppError> {
     // Vibrancy is only available on macOS
     Ok(())
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-async fn update_panel_behavior(window: tauri::Window, sticky_mode: bool) -> Result<(), String> {
+async fn update_panel_behavior(window: tauri::Window, sticky_mode: bool) -> Result<(), AppError> {
     log::info!("update_panel_behavior called with sticky_mode={}", sticky_mode);
 
     let app_handle = window.app_handle().clone();
@@ -1423,17 +2002,18 @@ async fn update_panel_behavior(window: tauri::Window, sticky_mode: bool) -> Resu
             } else {
                 log::error!("Failed to get webview window");
             }
-        })
-        .map_err(|e| e.to_string())?;
+        })?;
 
     Ok(())
 }
 
 #[tauri::command]
 #[allow(unused_variables)]
-async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Result<(), String> {
+async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Result<(), AppError> {
     log::info!("show_window called");
 
+    drafts::restore_latest_on_first_show(window.app_handle(), &state);
+
     // On macOS, use NSPanel for fullscreen overlay support
     // Show the panel IMMEDIATELY, then detect previous app in background
     #[cfg(target_os = "macos")]
@@ -1467,9 +2047,9 @@ async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Resul
                     }
                 };
 
-                // Always move to cursor's monitor, restoring saved position if available
-                if let Err(e) = webview_window.move_to_cursor_monitor() {
-                    log::warn!("Failed to move to cursor monitor: {}", e);
+                // Move to the cursor's (or active window's) monitor, restoring saved position if available
+                if let Err(e) = webview_window.move_to_placement_monitor() {
+                    log::warn!("Failed to move to placement monitor: {}", e);
                 }
 
                 // Re-apply macOS native text-service suppression after panel focus/show,
@@ -1478,7 +2058,7 @@ async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Resul
                     log::warn!("Failed to disable webview text services on show: {:?}", e);
                 }
 
-                panel.show_and_make_key();
+                window::show_panel_animated(&panel);
 
                 // Re-apply once again immediately after show. Some WebKit/AppKit
                 // text-service flags are only effective once the view is key.
@@ -1487,8 +2067,7 @@ async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Resul
                 }
 
                 log::info!("show_window (panel) completed successfully");
-            })
-            .map_err(|e| e.to_string())?;
+            })?;
 
         // Detect previous app in background (non-blocking)
         // Check if we have a recent cached value (within 500ms)
@@ -1503,21 +2082,12 @@ async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Resul
         if needs_detection {
             let app_handle_for_thread = window.app_handle().clone();
             std::thread::spawn(move || {
-                let output = std::process::Command::new("osascript")
-                    .arg("-e")
-                    .arg(r#"tell application "System Events" to get name of first process whose frontmost is true"#)
-                    .output()
-                    .ok();
-
-                if let Some(o) = output {
-                    if let Ok(app_name) = String::from_utf8(o.stdout) {
-                        let app_name = app_name.trim().to_string();
-                        log::info!("Previous app detected (async): {}", app_name);
-                        if !app_name.is_empty() && app_name != "Wingman" {
-                            let state = app_handle_for_thread.state::<AppState>();
-                            *state.previous_app.lock().unwrap() = Some((app_name.clone(), std::time::Instant::now()));
-                            log::info!("Stored previous app: {}", app_name);
-                        }
+                if let Some(app) = frontmost::frontmost_app() {
+                    log::info!("Previous app detected (async): {}", app.name);
+                    if app.name != "Wingman" {
+                        let state = app_handle_for_thread.state::<AppState>();
+                        log::info!("Stored previous app: {}", app.name);
+                        *state.previous_app.lock().unwrap() = Some((app, std::time::Instant::now()));
                     }
                 }
             });
@@ -1566,7 +2136,7 @@ async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Resul
         log::info!("Showing window...");
         window.show().map_err(|e| {
             log::error!("Failed to show window: {}", e);
-            e.to_string()
+            AppError::from(e)
         })?;
 
         // On Linux, request attention first then set focus
@@ -1581,8 +2151,9 @@ async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Resul
         log::info!("Setting focus...");
         window.set_focus().map_err(|e| {
             log::error!("Failed to set focus: {}", e);
-            e.to_string()
+            AppError::from(e)
         })?;
+        panel_animation::notify_shown(window.app_handle());
 
         log::info!("show_window completed successfully");
         Ok(())
@@ -1590,7 +2161,7 @@ async fn show_window(window: tauri::Window, state: State<'_, AppState>) -> Resul
 }
 
 #[tauri::command]
-async fn hide_window(window: tauri::Window) -> Result<(), String> {
+async fn hide_window(window: tauri::Window) -> Result<(), AppError> {
     #[cfg(target_os = "macos")]
     {
         let app_handle = window.app_handle().clone();
@@ -1612,26 +2183,39 @@ async fn hide_window(window: tauri::Window) -> Result<(), String> {
                             }
                         }
 
-                        panel.hide();
+                        window::hide_panel_animated(&app_handle_inner, &panel);
                     }
                 }
-            })
-            .map_err(|e| e.to_string())?;
+            })?;
         return Ok(());
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        window.hide().map_err(|e| e.to_string())
+        window.hide().map_err(AppError::from)
     }
 }
 
 #[tauri::command]
 #[allow(unused_variables)]
-async fn hide_and_paste(window: tauri::Window, state: State<'_, AppState>) -> Result<(), String> {
+async fn hide_and_paste(
+    window: tauri::Window,
+    state: State<'_, AppState>,
+    content: Option<String>,
+    language: Option<String>,
+) -> Result<(), AppError> {
+    // Rewrite the pasteboard the way the target app wants it. Callers that put
+    // attachments on the pasteboard leave `content` out so they aren't replaced.
+    if let Some(content) = content {
+        if let Some(profile) = paste_profiles::profile_for_previous_app(&state) {
+            let payload = paste_profiles::apply_profile(&profile, &content, language.as_deref())?;
+            native_clipboard::write_formats_to_clipboard(&payload.text, payload.html.as_deref(), payload.rtf.as_deref())?;
+        }
+    }
+
     // Get the stored previous app (extract just the name, ignore timestamp)
     #[cfg(target_os = "macos")]
-    let previous_app: Option<String> = state.previous_app.lock().unwrap().as_ref().map(|(name, _)| name.clone());
+    let previous_app: Option<String> = frontmost::previous_app(&state).map(|app| app.name);
 
     // Hide the window/panel - must run on main thread
     #[cfg(target_os = "macos")]
@@ -1652,16 +2236,15 @@ async fn hide_and_paste(window: tauri::Window, state: State<'_, AppState>) -> Re
                             }
                         }
 
-                        panel.hide();
+                        window::hide_panel_animated(&app_handle_inner, &panel);
                     }
                 }
-            })
-            .map_err(|e| e.to_string())?;
+            })?;
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        window.hide().map_err(|e| e.to_string())?;
+        window.hide()?;
     }
 
     // On macOS, activate the previous app
@@ -1690,24 +2273,24 @@ async fn hide_and_paste(window: tauri::Window, state: State<'_, AppState>) -> Re
 
 // Custom transformations commands
 #[tauri::command]
-fn get_custom_transformations() -> Result<CustomTransformationsData, String> {
-    load_custom_transformations().map_err(|e| e.to_string())
+fn get_custom_transformations() -> Result<CustomTransformationsData, AppError> {
+    load_custom_transformations().map_err(AppError::from)
 }
 
 #[tauri::command]
-fn save_custom_transformations_cmd(data: CustomTransformationsData) -> Result<(), String> {
-    save_custom_transformations(&data).map_err(|e| e.to_string())
+fn save_custom_transformations_cmd(data: CustomTransformationsData) -> Result<(), AppError> {
+    save_custom_transformations(&data).map_err(AppError::from)
 }
 
 // Transformation chains commands
 #[tauri::command]
-fn get_transformation_chains() -> Result<TransformationChainsData, String> {
-    load_transformation_chains().map_err(|e| e.to_string())
+fn get_transformation_chains() -> Result<TransformationChainsData, AppError> {
+    load_transformation_chains().map_err(AppError::from)
 }
 
 #[tauri::command]
-fn save_transformation_chains_cmd(data: TransformationChainsData) -> Result<(), String> {
-    save_transformation_chains(&data).map_err(|e| e.to_string())
+fn save_transformation_chains_cmd(data: TransformationChainsData) -> Result<(), AppError> {
+    save_transformation_chains(&data).map_err(AppError::from)
 }
 
 // Save file dialog command
@@ -1741,7 +2324,7 @@ fn get_file_filter(language: &str) -> (&'static str, &'static [&'static str]) {
 }
 
 #[tauri::command]
-async fn save_file_dialog(content: String, file_type: Option<String>) -> Result<Option<String>, String> {
+async fn save_file_dialog(content: String, file_type: Option<String>) -> Result<Option<String>, AppError> {
     use rfd::AsyncFileDialog;
 
     // Set flag to prevent panel from hiding when dialog takes focus
@@ -1766,13 +2349,93 @@ async fn save_file_dialog(content: String, file_type: Option<String>) -> Result<
 
     if let Some(handle) = file {
         let path = handle.path();
-        std::fs::write(path, content).map_err(|e| e.to_string())?;
+        std::fs::write(path, content)?;
         Ok(Some(path.to_string_lossy().to_string()))
     } else {
         Ok(None) // User cancelled
     }
 }
 
+/// How often expired history entries are swept
+const EXPIRY_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Delete expired history entries in the background and tell the UI which ones went
+fn start_expiry_sweeper(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let state = app.state::<AppState>();
+        let expired = match state.db.lock() {
+            Ok(conn) => history::delete_expired_entries(&conn),
+            Err(e) => {
+                log::error!("Expiry sweeper couldn't lock the database: {}", e);
+                std::thread::sleep(EXPIRY_SWEEP_INTERVAL);
+                continue;
+            }
+        };
+
+        match expired {
+            Ok(ids) if !ids.is_empty() => {
+                log::info!("Deleted {} expired history entries", ids.len());
+                app.emit("history-entries-expired", &ids).ok();
+                tray::rebuild(&app);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to delete expired history entries: {}", e),
+        }
+
+        std::thread::sleep(EXPIRY_SWEEP_INTERVAL);
+    });
+}
+
+/// How often pending reminders are checked
+const REMINDER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Fire due reminders: a system notification, then the panel opened on the entry.
+/// Reminders live in the database, so ones that came due while Wingman wasn't
+/// running fire on the first check after launch.
+fn start_reminder_scheduler(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let state = app.state::<AppState>();
+        let due = match state.db.lock() {
+            Ok(conn) => history::take_due_reminders(&conn),
+            Err(e) => {
+                log::error!("Reminder scheduler couldn't lock the database: {}", e);
+                std::thread::sleep(REMINDER_CHECK_INTERVAL);
+                continue;
+            }
+        };
+
+        match due {
+            Ok(entries) => {
+                for entry in entries {
+                    let preview = entry
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| entry.content.lines().next().unwrap_or_default().chars().take(80).collect());
+                    let body = match &entry.reminder_note {
+                        Some(note) => format!("{}\n{}", note, preview),
+                        None => preview,
+                    };
+                    notifications::send(
+                        &app,
+                        notifications::Notification::new(NotificationCategory::Reminder, "Wingman reminder", body)
+                            .for_entry(entry.id),
+                    );
+                    // Panels can only be shown from the main thread
+                    let handle = app.clone();
+                    app.run_on_main_thread(move || {
+                        tray::show_panel(&handle, None);
+                        handle.emit("reminder-fired", &entry).ok();
+                    })
+                    .ok();
+                }
+            }
+            Err(e) => log::warn!("Failed to check reminders: {}", e),
+        }
+
+        std::thread::sleep(REMINDER_CHECK_INTERVAL);
+    });
+}
+
 // Simple UUID v4 generator (using uuid crate)
 fn uuid_v4() -> String {
     uuid::Uuid::new_v4().to_string()
@@ -1780,23 +2443,17 @@ fn uuid_v4() -> String {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize database
-    let db = init_database().expect("Failed to initialize database");
-    #[cfg(target_os = "macos")]
-    let app_state = AppState {
-        db: Mutex::new(db),
-        previous_app: Mutex::new(None),
-        has_been_shown: std::sync::atomic::AtomicBool::new(false),
-    };
-    #[cfg(not(target_os = "macos"))]
-    let app_state = AppState {
-        db: Mutex::new(db),
-        has_been_shown: std::sync::atomic::AtomicBool::new(false),
-    };
+    startup::begin();
 
     #[allow(unused_mut)]
     let mut builder = tauri::Builder::default()
-        .manage(app_state)
+        // Registered first so a second launch exits before it touches the database or
+        // adds another tray icon; the running instance shows its panel instead
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            log::info!("Wingman was launched again, showing the running instance");
+            let handle = app.clone();
+            app.run_on_main_thread(move || tray::show_panel(&handle, None)).ok();
+        }))
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_shell::init())
@@ -1819,36 +2476,130 @@ pub fn run() {
     }
 
     builder
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(command_middleware::wrap(tauri::generate_handler![
             // Settings
             get_settings,
             update_settings,
             get_default_hotkey_cmd,
+            set_launch_at_login,
+            get_launch_at_login,
             validate_hotkey_cmd,
+            set_summon_hotkey,
+            check_hotkey_conflicts,
+            // Accessibility
+            get_accessibility_prefs,
+            // Profiles
+            list_profiles,
+            get_active_profile,
+            create_profile,
+            switch_profile,
+            // Semantic search
+            semantic_search,
+            // Summaries
+            summarize_text,
+            suggest_title,
+            // Translation
+            translate_text,
+            list_supported_languages,
+            // Grammar
+            check_text,
+            get_grammar_config,
+            save_grammar_config,
+            // Drafts
+            save_draft,
+            list_drafts,
+            restore_draft,
+            discard_draft,
             // History
             add_history_entry,
             get_history,
+            get_entry_content,
+            get_history_after,
+            get_history_before,
             search_history,
+            search_history_advanced,
+            save_search,
+            list_saved_searches,
+            run_saved_search,
+            delete_saved_search,
+            get_history_by_smart_tag,
+            get_history_by_source_app,
+            list_history_source_apps,
+            reclassify_history,
+            update_history_entry,
+            get_entry_versions,
+            restore_entry_version,
             delete_history_entry,
+            set_entry_expiry,
+            set_entry_reminder,
+            list_upcoming_reminders,
+            // Privacy mode
+            set_privacy_mode,
+            get_privacy_mode,
+            // Presentation mode
+            set_presentation_mode,
+            get_presentation_mode,
+            // Tray
+            rebuild_tray_menu,
+            // Permissions (onboarding)
+            check_permissions,
+            request_permission,
             clear_all_history,
             get_history_stats,
+            get_history_analytics,
             cleanup_history,
+            compact_database,
             export_all_history,
+            export_all_data,
+            import_all_data,
+            prepare_remove_all_app_data,
+            remove_all_app_data,
+            bulk_delete_entries,
+            bulk_tag_entries,
+            bulk_export_entries,
             // Snippets
             get_snippets,
             save_snippets_data,
             add_snippet,
             update_snippet,
+            force_update_snippet,
+            get_snippet_versions,
+            compare_snippet_versions,
+            rollback_snippet,
+            set_snippet_source,
+            refresh_snippet_from_source,
             delete_snippet,
+            set_snippet_favorite,
             set_snippet_github_info,
             clear_snippet_github_info,
+            set_snippet_kind,
+            run_snippet_command,
+            export_snippets,
+            find_duplicate_snippets,
+            merge_snippets,
+            // Item links
+            link_items,
+            get_links,
+            unlink_items,
+            // Trash
+            list_trash,
+            restore_deleted,
+            empty_trash,
             // Custom AI Prompts
             get_custom_ai_prompts,
             save_custom_ai_prompts_data,
+            // AI workflows
+            get_ai_workflows,
+            save_ai_workflows_data,
+            run_ai_workflow,
+            cancel_ai_workflow,
             add_custom_ai_prompt,
             update_custom_ai_prompt,
             delete_custom_ai_prompt,
             toggle_custom_ai_prompt_enabled,
+            run_custom_prompt,
+            export_ai_prompts,
+            import_ai_prompts,
             // Custom transformations
             get_custom_transformations,
             save_custom_transformations_cmd,
@@ -1857,26 +2608,128 @@ pub fn run() {
             save_transformation_chains_cmd,
             // Save file dialog
             save_file_dialog,
+            quick_save,
+            prepare_drag_file,
+            // Watch folders
+            list_watch_folders,
+            add_watch_folder,
+            remove_watch_folder,
+            // Background indexing
+            get_index_status,
+            get_startup_metrics,
+            rebuild_index,
+            // Paste profiles
+            get_paste_profiles,
+            save_paste_profiles_cmd,
+            get_active_paste_profile,
+            // Launcher extensions
+            query_for_launcher,
+            execute_launcher_action,
+            get_local_api_info,
+            regenerate_local_api_token,
+            get_event_bridge_info,
+            // Diagnostics
+            get_command_stats,
+            is_online,
+            // Dev tools
+            seed_fake_history,
+            benchmark_history_ops,
             // Text utilities
             get_text_stats,
             transform_text_cmd,
             count_pattern_occurrences,
+            // Action registry (quick actions palette)
+            list_actions,
+            execute_action,
+            // Suggested actions
+            get_suggested_actions,
+            record_action_use,
+            // Character picker
+            search_characters,
+            get_recent_characters,
+            record_character_use,
+            clear_recent_characters,
             // Native clipboard
             write_native_clipboard,
+            read_clipboard_rich,
+            read_clipboard_inventory,
+            convert_rich_to_markdown,
+            read_clipboard_capture,
+            copy_as,
             copy_file_to_clipboard,
+            purge_temp_files,
+            // Share sheet
+            share_content,
+            list_share_services,
+            create_email_draft,
+            // PDF export and printing
+            export_to_pdf,
+            print_content,
+            // Code images
+            render_code_image,
+            // LAN sync
+            list_peers,
+            pair_peer,
+            confirm_pairing,
+            unpair_peer,
+            send_to_peer,
+            update_peer_settings,
+            get_lan_transfer_log,
+            clear_lan_transfer_log,
+            // Paste services
+            publish_paste,
+            get_paste_config,
+            save_paste_config,
+            // Link previews
+            fetch_url_metadata,
+            url_to_markdown_link,
+            // HTTP request runner
+            parse_curl,
+            run_http_request,
+            // Proxy
+            set_proxy_password,
+            // Log parsing
+            parse_log_lines,
+            filter_log_lines,
+            // Automation rules
+            list_rules,
+            create_rule,
+            update_rule,
+            set_rule_enabled,
+            delete_rule,
+            test_rule,
+            get_rule_runs,
+            clear_rule_runs,
+            // Stack traces
+            format_stack_trace,
             // Code formatting
             detect_language,
             format_code,
+            format_graphql,
+            inline_sql_params,
+            extract_sql_params,
             minify_code,
             // JSON/XML formatting (legacy)
             format_json,
             minify_json,
+            query_json,
+            sort_json_keys,
+            json_to_typescript_interface,
+            infer_json_schema,
             format_xml,
             // Encoding/decoding
             encode_base64,
             decode_base64,
+            decode_base64_binary,
+            hex_dump,
+            from_hex_dump,
+            hex_to_base64,
+            base64_to_hex,
             encode_url,
             decode_url,
+            parse_url,
+            build_url,
+            parse_user_agent,
             encode_html,
             decode_html,
             // Generators
@@ -1886,9 +2739,11 @@ pub fn run() {
             generate_short_hash,
             generate_prefixed_id,
             generate_bulk,
+            render_bulk_template,
             generate_lorem_ipsum,
             // Timestamps
             unix_to_human,
+            format_timestamp,
             human_to_unix,
             convert_timezone,
             get_current_timestamp,
@@ -1897,6 +2752,14 @@ pub fn run() {
             generate_sha1,
             generate_sha256,
             generate_sha512,
+            generate_ssh_key,
+            convert_ssh_key,
+            fingerprint_ssh_key,
+            decode_certificate,
+            decode_csr,
+            analyze_ip,
+            expand_cidr,
+            dns_lookup,
             // License
             activate_license,
             deactivate_license,
@@ -1909,6 +2772,12 @@ pub fn run() {
             get_ai_usage_cmd,
             create_customer_portal_session_cmd,
             call_ai_feature_cmd,
+            // AI history
+            list_ai_history,
+            search_ai_history,
+            rerun_ai_interaction,
+            delete_ai_interaction,
+            clear_ai_history,
             // Obsidian
             get_obsidian_config,
             configure_obsidian,
@@ -1924,6 +2793,8 @@ pub fn run() {
             list_wingman_gists,
             update_github_gist,
             delete_github_gist,
+            sync_all_gist_snippets,
+            resolve_gist_conflict,
             logout_github,
             get_github_config,
             save_github_config,
@@ -1942,19 +2813,48 @@ pub fn run() {
             set_vibrancy_mode,
             #[cfg(target_os = "macos")]
             update_panel_behavior,
+            cycle_window_preset,
+            apply_window_preset,
             // Dialogs
             pick_folder,
             // Updates
             check_for_app_updates,
             download_and_install_update,
+            set_update_channel,
+            get_update_release_notes,
+            restart_to_update,
+            pause_update_download,
+            resume_update_download,
+            cancel_update_download,
+            defer_update_until_quit,
             get_app_version,
             // Secure credentials
             store_credential,
-            get_credential,
             delete_credential,
-        ])
+            list_credentials,
+            rotate_credential,
+            revoke_credential,
+            reveal_credential,
+            require_authentication,
+        ]))
         .setup(|app| {
-            let window = app.get_webview_window("main").unwrap();
+            // Initialize database
+            let db = startup::measure("database", || init_database().expect("Failed to initialize database"));
+            #[cfg(target_os = "macos")]
+            let app_state = AppState {
+                db: Mutex::new(db),
+                previous_app: Mutex::new(None),
+                has_been_shown: std::sync::atomic::AtomicBool::new(false),
+            };
+            #[cfg(not(target_os = "macos"))]
+            let app_state = AppState {
+                db: Mutex::new(db),
+                has_been_shown: std::sync::atomic::AtomicBool::new(false),
+            };
+            app.manage(app_state);
+            ai_history::init(app.handle());
+
+            let window = startup::measure("main window", || app.get_webview_window("main").unwrap());
 
             // Center the window on startup
             window.center().ok();
@@ -1964,15 +2864,18 @@ pub fn run() {
             {
                 app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
+                // "Send selection to Wingman" in the Services menu
+                services::register(app.handle());
+
                 // Pre-initialize the NSPanel so it's ready when hotkey is pressed
                 // This eliminates panel creation overhead on first show
-                if let Err(e) = window.to_wingman_panel() {
+                if let Err(e) = startup::measure("panel", || window.to_wingman_panel()) {
                     log::warn!("Failed to pre-initialize panel: {:?}", e);
                 } else {
                     log::info!("NSPanel pre-initialized successfully");
 
                     // Apply transparency at startup
-                    if let Err(e) = set_window_blur(&window, true) {
+                    if let Err(e) = startup::measure("blur", || set_window_blur(&window, true)) {
                         log::warn!("Failed to apply transparency on startup: {:?}", e);
                     } else {
                         log::info!("Transparency applied on startup");
@@ -1994,156 +2897,125 @@ pub fn run() {
                     }
 
                     // Start workspace monitor to refocus panel in sticky mode
-                    start_workspace_monitor(app.handle().clone());
-                    log::info!("Workspace monitor started");
+                    let handle = app.handle().clone();
+                    startup::defer("workspace monitor", move || {
+                        start_workspace_monitor(handle);
+                        log::info!("Workspace monitor started");
+                    });
                 }
             }
 
-            // Create system tray menu
-            let show_item = MenuItem::with_id(app, "show", "Show Wingman", true, None::<&str>)?;
-            let separator1 = tauri::menu::PredefinedMenuItem::separator(app)?;
-            let hotkeys_item = MenuItem::with_id(app, "hotkeys", "Hotkeys...", true, None::<&str>)?;
-            let settings_item = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
-            let separator2 = tauri::menu::PredefinedMenuItem::separator(app)?;
-            let updates_item = MenuItem::with_id(app, "check_updates", "Check for Updates...", true, None::<&str>)?;
-            let separator3 = tauri::menu::PredefinedMenuItem::separator(app)?;
-            let quit_item = MenuItem::with_id(app, "quit", "Quit Wingman", true, None::<&str>)?;
-
-            let menu = Menu::with_items(app, &[
-                &show_item,
-                &separator1,
-                &hotkeys_item,
-                &settings_item,
-                &separator2,
-                &updates_item,
-                &separator3,
-                &quit_item,
-            ])?;
-
-            // Build tray icon - menu shows on left-click
-            // Use custom tray icon (light version with colors for menu bar)
-            let tray_icon = {
-                let png_bytes = include_bytes!("../icons/tray/icon.png");
-                let img = image::load_from_memory(png_bytes).expect("Failed to decode tray icon");
-                let rgba = img.to_rgba8();
-                let (width, height) = rgba.dimensions();
-                tauri::image::Image::new_owned(rgba.into_raw(), width, height)
-            };
-            let _tray = TrayIconBuilder::new()
-                .icon(tray_icon)
-                .menu(&menu)
-                .show_menu_on_left_click(true)
-                .on_menu_event(|app, event| {
-                    match event.id.as_ref() {
-                        "show" => {
-                            #[cfg(target_os = "macos")]
-                            {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    let panel = app
-                                        .get_webview_panel(MAIN_WINDOW_LABEL)
-                                        .or_else(|_| window.to_wingman_panel());
-
-                                    if let Ok(panel) = panel {
-                                        // Always move to cursor's monitor with saved position
-                                        window.move_to_cursor_monitor().ok();
-                                        panel.show_and_make_key();
-                                    }
-                                }
-                            }
-                            #[cfg(not(target_os = "macos"))]
-                            {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    window.center().ok();
-                                    window.show().ok();
-                                    window.set_focus().ok();
-                                }
-                            }
+            let handle = app.handle().clone();
+            startup::defer("accessibility monitor", move || accessibility::start_monitor(handle));
+            startup::defer("temp file monitor", temp_files::start_monitor);
+            startup::defer("copy source watcher", frontmost::start_copy_watch);
+
+            // Self-destructing entries are cleaned up even while the panel is closed
+            start_expiry_sweeper(app.handle().clone());
+            start_reminder_scheduler(app.handle().clone());
+
+            // Nothing below is needed to open the panel, so it waits until setup is done
+            let handle = app.handle().clone();
+            startup::defer("indexer", move || indexer::start_indexer(handle));
+
+            startup::defer_on_main_thread(app.handle(), "drag mouse monitor", drag_out::install_mouse_monitor);
+
+            let handle = app.handle().clone();
+            startup::defer_on_main_thread(app.handle(), "tray", move || {
+                if let Err(e) = tray::create_tray(&handle) {
+                    log::error!("Failed to create the tray icon: {}", e);
+                }
+            });
+
+            // The login item can be removed outside Wingman
+            startup::defer("autostart sync", autostart::sync_setting);
+
+            // Register keychain secrets stored before the credential registry existed
+            startup::defer("credential registry", credentials::migrate_registry);
+
+            // Read the panel's first page ahead of the first open
+            let handle = app.handle().clone();
+            startup::defer("history cache", move || {
+                let state = handle.state::<AppState>();
+                let Ok(conn) = state.db.lock() else {
+                    return;
+                };
+                if let Err(e) = history::warm_cache(&conn) {
+                    log::warn!("Failed to warm the history cache: {}", e);
+                }
+            });
+
+            // Revalidate a license that's due, so the panel doesn't have to wait on it
+            startup::defer("license refresh", || {
+                if !check_license_status().map(|status| status.needs_revalidation).unwrap_or(false) {
+                    return;
+                }
+                if let Err(e) = tauri::async_runtime::block_on(refresh_license()) {
+                    log::warn!("Failed to refresh the license: {}", e);
+                }
+            });
+
+            updater::start_background_updates(app.handle().clone());
+
+            rules::register_hotkeys(app.handle());
+            rules::start_scheduler(app.handle().clone());
+            snippet_sources::start_scheduler(app.handle().clone());
+
+            if let Err(e) = watch_folders::restart(app.handle()) {
+                log::warn!("Failed to start watch folders: {}", e);
+            }
+
+            // Apply saved settings that start services or hotkeys
+            startup::measure("settings", || {
+                if let Ok(settings) = load_settings() {
+                    offline::set_enabled(settings.offline_mode);
+                    if let Err(e) = profiles::register_switch_hotkey(app.handle(), settings.profile_switch_hotkey.as_deref()) {
+                        log::warn!("{}", e);
+                    }
+                    if let Err(e) = window_presets::register_hotkey(app.handle(), settings.window_preset_hotkey.as_deref()) {
+                        log::warn!("{}", e);
+                    }
+                    let state = app.state::<AppState>();
+                    if let Ok(conn) = state.db.lock() {
+                        if let Err(e) = ai_history::apply_retention(&conn, settings.ai_history_retention_days) {
+                            log::warn!("Failed to prune AI history: {}", e);
                         }
-                        "hotkeys" => {
-                            #[cfg(target_os = "macos")]
-                            {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    let panel = app
-                                        .get_webview_panel(MAIN_WINDOW_LABEL)
-                                        .or_else(|_| window.to_wingman_panel());
-
-                                    if let Ok(panel) = panel {
-                                        window.move_to_cursor_monitor().ok();
-                                        panel.show_and_make_key();
-                                        window.emit("open-hotkeys", ()).ok();
-                                    }
-                                }
-                            }
-                            #[cfg(not(target_os = "macos"))]
-                            {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    window.center().ok();
-                                    window.show().ok();
-                                    window.set_focus().ok();
-                                    window.emit("open-hotkeys", ()).ok();
-                                }
-                            }
+                        if let Err(e) = trash::apply_retention(&conn, settings.trash_retention_days) {
+                            log::warn!("Failed to purge the trash: {}", e);
                         }
-                        "settings" => {
-                            #[cfg(target_os = "macos")]
-                            {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    let panel = app
-                                        .get_webview_panel(MAIN_WINDOW_LABEL)
-                                        .or_else(|_| window.to_wingman_panel());
-
-                                    if let Ok(panel) = panel {
-                                        window.move_to_cursor_monitor().ok();
-                                        panel.show_and_make_key();
-                                        window.emit("open-settings", ()).ok();
-                                    }
-                                }
-                            }
-                            #[cfg(not(target_os = "macos"))]
-                            {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    window.center().ok();
-                                    window.show().ok();
-                                    window.set_focus().ok();
-                                    window.emit("open-settings", ()).ok();
-                                }
-                            }
+                    }
+                    if settings.lan_sync_enabled {
+                        if let Err(e) = lan_sync::set_enabled(app.handle(), true) {
+                            log::warn!("{}", e);
                         }
-                        "check_updates" => {
-                            #[cfg(target_os = "macos")]
-                            {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    let panel = app
-                                        .get_webview_panel(MAIN_WINDOW_LABEL)
-                                        .or_else(|_| window.to_wingman_panel());
-
-                                    if let Ok(panel) = panel {
-                                        window.move_to_cursor_monitor().ok();
-                                        panel.show_and_make_key();
-                                        window.emit("check-updates", ()).ok();
-                                    }
-                                }
-                            }
-                            #[cfg(not(target_os = "macos"))]
-                            {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    window.center().ok();
-                                    window.show().ok();
-                                    window.set_focus().ok();
-                                    window.emit("check-updates", ()).ok();
-                                }
-                            }
+                    }
+                    if settings.local_api_enabled {
+                        if let Err(e) = local_api::set_enabled(app.handle(), true) {
+                            log::warn!("{}", e);
+                        }
+                    }
+                    if settings.event_bridge.enabled {
+                        if let Err(e) = event_bridge::configure(&settings.event_bridge) {
+                            log::warn!("{}", e);
                         }
-                        "quit" => {
-                            app.exit(0);
+                    }
+                    if settings.audit_log_enabled {
+                        if let Err(e) = command_middleware::configure(true) {
+                            log::warn!("{}", e);
                         }
-                        _ => {}
                     }
-                })
-                .build(app)?;
+                }
+            });
 
+            startup::ready();
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                temp_files::purge();
+                updater::install_on_quit();
+            }
+        });
 }
@@ -15,10 +15,12 @@ use std::fs;
 use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::http_client;
+
 // Supabase configuration - replace with your actual values
 // The publishable key (formerly "anon key") is safe to include in client code
 // as it respects Row Level Security policies
-const SUPABASE_URL: &str = "https://yhpetdqcmqpfwhdtbhat.supabase.co";
+pub(crate) const SUPABASE_URL: &str = "https://yhpetdqcmqpfwhdtbhat.supabase.co";
 const SUPABASE_PUBLISHABLE_KEY: &str = "sb_publishable_t4l4DUhI_I2rpT9pMU8dgg_Y2j55oJY";
 
 // Validation periods
@@ -301,7 +303,7 @@ pub async fn validate_license_online(
     let device_name = get_device_name();
     let os = get_os_name();
 
-    let client = reqwest::Client::new();
+    let client = http_client::supabase_client().map_err(|e| LicenseError::NetworkError(e.to_string()))?;
     let url = format!("{}/functions/v1/validate-license", SUPABASE_URL);
 
     let response = client
@@ -317,7 +319,7 @@ pub async fn validate_license_online(
         }))
         .send()
         .await
-        .map_err(|e| LicenseError::NetworkError(e.to_string()))?;
+        .map_err(|e| LicenseError::NetworkError(http_client::describe(&e)))?;
 
     let status = response.status();
     let body: ValidateLicenseResponse = response
@@ -370,7 +372,7 @@ pub async fn validate_license_online(
 pub async fn deactivate_license_online(license_key: &str) -> Result<(), LicenseError> {
     let device_id = get_device_id()?;
 
-    let client = reqwest::Client::new();
+    let client = http_client::supabase_client().map_err(|e| LicenseError::NetworkError(e.to_string()))?;
     let url = format!("{}/functions/v1/deactivate-device", SUPABASE_URL);
 
     let response = client
@@ -383,7 +385,7 @@ pub async fn deactivate_license_online(license_key: &str) -> Result<(), LicenseE
         }))
         .send()
         .await
-        .map_err(|e| LicenseError::NetworkError(e.to_string()))?;
+        .map_err(|e| LicenseError::NetworkError(http_client::describe(&e)))?;
 
     let body: DeactivateResponse = response
         .json()
@@ -484,6 +486,11 @@ pub fn check_license_status() -> Result<LicenseStatusInfo, LicenseError> {
 
 /// Refresh license validation online
 pub async fn refresh_license() -> Result<LicenseStatusInfo, LicenseError> {
+    // Offline mode answers from the cache, grace period and all
+    if crate::offline::is_enabled() {
+        return check_license_status();
+    }
+
     let cache = load_license_cache()?;
 
     let (license_key, email) = match (&cache.license_key, &cache.email) {
@@ -516,6 +523,12 @@ pub async fn refresh_license() -> Result<LicenseStatusInfo, LicenseError> {
 /// Note: feature param is for API consistency and future granular checks
 /// Premium tier also has access to all Pro features
 pub fn is_feature_enabled(_feature: ProFeature) -> bool {
+    has_pro_license()
+}
+
+/// Whether the license unlocks Pro features. One license unlocks all of them, so callers
+/// checking many features at once can read the status once with this.
+pub fn has_pro_license() -> bool {
     let status = match check_license_status() {
         Ok(s) => s,
         Err(_) => return false,
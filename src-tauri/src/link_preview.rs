@@ -0,0 +1,359 @@
+// Link previews for URLs copied to the clipboard.
+// Fetching arbitrary URLs from the desktop is an SSRF vector (routers, cloud metadata,
+// local dev servers), so every hop is resolved up front and must land on a public
+// address, the connection is pinned to the checked address, and reads are bounded.
+
+use regex::Regex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::http_client;
+
+const MAX_REDIRECTS: usize = 5;
+/// Metadata lives in <head>; there's no need to download whole pages
+const MAX_BODY_BYTES: usize = 512 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UrlMetadata {
+    /// Final URL after redirects
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub favicon_url: Option<String>,
+    pub content_type: Option<String>,
+    pub site_name: Option<String>,
+}
+
+// ============================================================================
+// Address checks
+// ============================================================================
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT
+        || (a == 192 && b == 0 && c == 0) // IETF protocol assignments
+        || (a == 198 && (18..20).contains(&b)) // benchmarking
+        || a >= 240) // reserved
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(v4);
+    }
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00 // unique local
+        || (first & 0xffc0) == 0xfe80 // link local
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8)) // documentation
+}
+
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_ipv4(v4),
+        IpAddr::V6(v6) => is_public_ipv6(v6),
+    }
+}
+
+/// Check the scheme and resolve the host, returning an address that's safe to connect to
+pub async fn resolve_public(url: &Url) -> Result<SocketAddr, String> {
+    if url.scheme() != "https" {
+        return Err("Only https URLs can be fetched".to_string());
+    }
+    let host = url.host_str().ok_or("URL has no host")?;
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    // Reject the whole host if any record is private, so a mixed answer can't be raced
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("Failed to resolve {}", host));
+    }
+    if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err("URL resolves to a private or reserved address".to_string());
+    }
+    Ok(addrs[0])
+}
+
+// ============================================================================
+// HTML parsing
+// ============================================================================
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn clean_text(text: &str) -> Option<String> {
+    let text = decode_entities(text).split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn tag_regex(pattern: &'static str, cell: &'static OnceLock<Regex>) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid regex"))
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    static ATTR: OnceLock<Regex> = OnceLock::new();
+    let re = tag_regex(r#"(?is)([a-z:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#, &ATTR);
+    re.captures_iter(tag)
+        .find(|c| c[1].eq_ignore_ascii_case(name))
+        .and_then(|c| c.get(2).or(c.get(3)).or(c.get(4)))
+        .map(|m| m.as_str())
+}
+
+/// Pull title, description, site name and favicon out of a page's HTML.
+/// Open Graph values win over the plain `<title>`/description tags.
+fn parse_html_metadata(html: &str, base: &Url) -> UrlMetadata {
+    static TITLE: OnceLock<Regex> = OnceLock::new();
+    static META: OnceLock<Regex> = OnceLock::new();
+    static LINK: OnceLock<Regex> = OnceLock::new();
+
+    let mut meta = UrlMetadata {
+        url: base.to_string(),
+        ..Default::default()
+    };
+
+    let mut title = None;
+    let mut description = None;
+    for tag in tag_regex(r"(?is)<meta\s[^>]*>", &META).find_iter(html) {
+        let tag = tag.as_str();
+        let key = attribute(tag, "property").or_else(|| attribute(tag, "name"));
+        let Some(content) = attribute(tag, "content").and_then(clean_text) else {
+            continue;
+        };
+        match key.map(|k| k.to_ascii_lowercase()).as_deref() {
+            Some("og:title") => meta.title = Some(content),
+            Some("twitter:title") => title = title.or(Some(content)),
+            Some("og:description") => meta.description = Some(content),
+            Some("description") | Some("twitter:description") => {
+                description = description.or(Some(content))
+            }
+            Some("og:site_name") => meta.site_name = Some(content),
+            _ => {}
+        }
+    }
+
+    if meta.title.is_none() {
+        meta.title = title.or_else(|| {
+            tag_regex(r"(?is)<title[^>]*>(.*?)</title>", &TITLE)
+                .captures(html)
+                .and_then(|c| clean_text(&c[1]))
+        });
+    }
+    meta.description = meta.description.or(description);
+
+    meta.favicon_url = tag_regex(r"(?is)<link\s[^>]*>", &LINK)
+        .find_iter(html)
+        .map(|m| m.as_str())
+        .find(|tag| {
+            attribute(tag, "rel")
+                .map(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("icon")))
+                .unwrap_or(false)
+        })
+        .and_then(|tag| attribute(tag, "href"))
+        .and_then(|href| base.join(&decode_entities(href)).ok())
+        .or_else(|| base.join("/favicon.ico").ok())
+        .map(|u| u.to_string());
+
+    meta
+}
+
+// ============================================================================
+// Fetching
+// ============================================================================
+
+/// Read at most `MAX_BODY_BYTES` of the response body
+async fn read_bounded(mut response: reqwest::Response) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        let remaining = MAX_BODY_BYTES - body.len();
+        body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+        if body.len() >= MAX_BODY_BYTES {
+            break;
+        }
+    }
+    Ok(body)
+}
+
+pub async fn fetch_metadata(url: &str) -> Result<UrlMetadata, String> {
+    let mut current = Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+
+    for _ in 0..=MAX_REDIRECTS {
+        let addr = resolve_public(&current).await?;
+        let host = current.host_str().unwrap_or_default().to_string();
+
+        // Pin the connection to the address we just checked, and follow redirects
+        // ourselves so every hop gets the same check. No proxy: it would resolve the
+        // host itself and could reach addresses the check refused.
+        let client = http_client::builder()
+            .map_err(|e| e.to_string())?
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .resolve(&host, addr)
+            .user_agent("Wingman-Desktop")
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        let response = client
+            .get(current.clone())
+            .header("Accept", "text/html,application/xhtml+xml;q=0.9,*/*;q=0.5")
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get("Location")
+                .and_then(|v| v.to_str().ok())
+                .ok_or("Redirect without a Location header")?;
+            current = current
+                .join(location)
+                .map_err(|e| format!("Invalid redirect: {}", e))?;
+            continue;
+        }
+
+        if !response.status().is_success() {
+            return Err(format!("Request failed with status {}", response.status().as_u16()));
+        }
+
+        let content_type = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or(v).trim().to_ascii_lowercase());
+
+        let is_html = content_type
+            .as_deref()
+            .map(|ct| ct == "text/html" || ct == "application/xhtml+xml")
+            .unwrap_or(false);
+
+        let mut meta = if is_html {
+            let body = read_bounded(response).await?;
+            parse_html_metadata(&String::from_utf8_lossy(&body), &current)
+        } else {
+            UrlMetadata {
+                url: current.to_string(),
+                ..Default::default()
+            }
+        };
+        meta.content_type = content_type;
+        return Ok(meta);
+    }
+
+    Err("Too many redirects".to_string())
+}
+
+/// `[title](url)`, escaping characters that would end the link text early
+pub fn markdown_link(url: &str, title: Option<&str>) -> String {
+    let title = title.filter(|t| !t.trim().is_empty()).unwrap_or(url);
+    let escaped = title.replace('\\', "\\\\").replace('[', "\\[").replace(']', "\\]");
+    let url = url.replace(' ', "%20").replace(')', "%29");
+    format!("[{}]({})", escaped, url)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn fetch_url_metadata(url: String) -> Result<UrlMetadata, AppError> {
+    fetch_metadata(&url).await.map_err(AppError::from)
+}
+
+/// Format a URL as a Markdown link titled with the page's title.
+/// Falls back to the bare URL as the link text if the page can't be fetched.
+#[tauri::command]
+pub async fn url_to_markdown_link(url: String) -> Result<String, AppError> {
+    let url = url.trim().to_string();
+    let title = match fetch_metadata(&url).await {
+        Ok(meta) => meta.title,
+        Err(e) => {
+            log::debug!("Link preview failed for {}: {}", url, e);
+            None
+        }
+    };
+    Ok(markdown_link(&url, title.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_addresses_rejected() {
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.5.4",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should be blocked", ip);
+        }
+        assert!(is_public_ip("93.184.216.34".parse().unwrap()));
+        assert!(is_public_ip("2606:4700::1111".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_html_metadata() {
+        let base = Url::parse("https://example.com/post/1").unwrap();
+        let html = r#"<html><head>
+            <title>  Plain &amp; Simple </title>
+            <meta name="description" content="Fallback description">
+            <meta property="og:description" content='Open Graph description'>
+            <meta property="og:site_name" content="Example">
+            <link rel="shortcut icon" href="/static/icon.png">
+        </head></html>"#;
+        let meta = parse_html_metadata(html, &base);
+        assert_eq!(meta.title.as_deref(), Some("Plain & Simple"));
+        assert_eq!(meta.description.as_deref(), Some("Open Graph description"));
+        assert_eq!(meta.site_name.as_deref(), Some("Example"));
+        assert_eq!(meta.favicon_url.as_deref(), Some("https://example.com/static/icon.png"));
+    }
+
+    #[test]
+    fn test_markdown_link() {
+        assert_eq!(
+            markdown_link("https://example.com/a b", Some("Title [draft]")),
+            "[Title \\[draft\\]](https://example.com/a%20b)"
+        );
+        assert_eq!(
+            markdown_link("https://example.com", None),
+            "[https://example.com](https://example.com)"
+        );
+    }
+}
@@ -0,0 +1,350 @@
+// Links between items: a history entry and the snippet it was saved as, an AI response
+// and the entry it was prompted with. Links are directed (source -> target) and typed by
+// a relation; get_links returns both directions so either side can show where it came
+// from. Saving a snippet and recording an AI call create links automatically when the
+// content matches a history entry. Rows live next to history in the profile database
+// and outlive the items they point at, so provenance survives deletes.
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::history::{content_digest, fill_content_digests};
+use crate::storage::load_snippets;
+use crate::AppState;
+
+/// History entry -> the snippet it was saved as
+pub const RELATION_SAVED_AS: &str = "saved_as";
+/// History entry -> the AI interaction it was the prompt of
+pub const RELATION_AI_RESPONSE: &str = "ai_response";
+
+/// Characters of content shown as a linked item's label
+const LABEL_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    History,
+    Snippet,
+    /// An AI history interaction
+    Ai,
+}
+
+impl ItemKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::History => "history",
+            Self::Snippet => "snippet",
+            Self::Ai => "ai",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "history" => Some(Self::History),
+            "snippet" => Some(Self::Snippet),
+            "ai" => Some(Self::Ai),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemRef {
+    pub kind: ItemKind,
+    /// History entry and AI interaction ids as strings, snippet ids as is
+    pub id: String,
+}
+
+impl ItemRef {
+    pub fn history(id: i64) -> Self {
+        Self { kind: ItemKind::History, id: id.to_string() }
+    }
+
+    pub fn snippet(id: &str) -> Self {
+        Self { kind: ItemKind::Snippet, id: id.to_string() }
+    }
+
+    pub fn ai(id: i64) -> Self {
+        Self { kind: ItemKind::Ai, id: id.to_string() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemLink {
+    pub id: i64,
+    pub source: ItemRef,
+    pub target: ItemRef,
+    pub relation: String,
+    pub created_at: String,
+}
+
+/// A link as seen from one of its ends
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkedItem {
+    pub link_id: i64,
+    pub relation: String,
+    /// "outgoing" when the queried item is the source
+    pub direction: &'static str,
+    pub item: ItemRef,
+    /// Snippet name or the start of the content; None once the item is deleted
+    pub label: Option<String>,
+    pub created_at: String,
+}
+
+pub fn init_links_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_links (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_kind TEXT NOT NULL,
+            source_id TEXT NOT NULL,
+            target_kind TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            relation TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (source_kind, source_id, target_kind, target_id, relation)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_item_links_target ON item_links(target_kind, target_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn map_link_row(row: &rusqlite::Row) -> rusqlite::Result<ItemLink> {
+    let kind = |index: usize| -> rusqlite::Result<ItemKind> {
+        let value: String = row.get(index)?;
+        ItemKind::parse(&value).ok_or_else(|| rusqlite::Error::InvalidColumnType(index, value, rusqlite::types::Type::Text))
+    };
+    Ok(ItemLink {
+        id: row.get(0)?,
+        source: ItemRef { kind: kind(1)?, id: row.get(2)? },
+        target: ItemRef { kind: kind(3)?, id: row.get(4)? },
+        relation: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+/// Link `source` to `target`; linking the same pair with the same relation again
+/// returns the existing link
+pub fn add_link(conn: &Connection, source: &ItemRef, target: &ItemRef, relation: &str) -> rusqlite::Result<ItemLink> {
+    conn.execute(
+        "INSERT OR IGNORE INTO item_links (source_kind, source_id, target_kind, target_id, relation)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![source.kind.as_str(), source.id, target.kind.as_str(), target.id, relation],
+    )?;
+    conn.query_row(
+        "SELECT id, source_kind, source_id, target_kind, target_id, relation, created_at FROM item_links
+         WHERE source_kind = ?1 AND source_id = ?2 AND target_kind = ?3 AND target_id = ?4 AND relation = ?5",
+        rusqlite::params![source.kind.as_str(), source.id, target.kind.as_str(), target.id, relation],
+        map_link_row,
+    )
+}
+
+/// Links with `item` at either end, newest first
+pub fn links_for(conn: &Connection, item: &ItemRef) -> rusqlite::Result<Vec<ItemLink>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, source_kind, source_id, target_kind, target_id, relation, created_at FROM item_links
+         WHERE (source_kind = ?1 AND source_id = ?2) OR (target_kind = ?1 AND target_id = ?2)
+         ORDER BY created_at DESC, id DESC",
+    )?;
+    let links = stmt
+        .query_map(rusqlite::params![item.kind.as_str(), item.id], map_link_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(links)
+}
+
+/// Link the newest history entry whose full content is exactly `content` to `target`,
+/// found by digest so compressed and overflowed entries match too. Used by the
+/// snippet-save and AI flows, which get content rather than an entry id.
+pub fn link_from_matching_entry(
+    conn: &Connection,
+    content: &str,
+    target: &ItemRef,
+    relation: &str,
+) -> rusqlite::Result<Option<ItemLink>> {
+    // Rows without a digest can't match; the rest are still worth linking
+    if let Err(e) = fill_content_digests(conn) {
+        log::warn!("Failed to digest history entries: {}", e);
+    }
+    let entry_id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM history WHERE content_digest = ?1 ORDER BY created_at DESC, id DESC LIMIT 1",
+            [content_digest(content)],
+            |row| row.get(0),
+        )
+        .optional()?;
+    entry_id
+        .map(|id| add_link(conn, &ItemRef::history(id), target, relation))
+        .transpose()
+}
+
+/// Point a snippet's links at another snippet, e.g. after merging duplicates. Links the
+/// other snippet already has are dropped rather than duplicated.
+pub fn retarget_snippet_links(conn: &Connection, from_id: &str, to_id: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE OR IGNORE item_links SET source_id = ?2 WHERE source_kind = 'snippet' AND source_id = ?1",
+        [from_id, to_id],
+    )?;
+    conn.execute(
+        "UPDATE OR IGNORE item_links SET target_id = ?2 WHERE target_kind = 'snippet' AND target_id = ?1",
+        [from_id, to_id],
+    )?;
+    conn.execute(
+        "DELETE FROM item_links WHERE (source_kind = 'snippet' AND source_id = ?1) OR (target_kind = 'snippet' AND target_id = ?1)",
+        [from_id],
+    )?;
+    Ok(())
+}
+
+fn first_chars(text: &str) -> String {
+    let line = text.lines().find(|line| !line.trim().is_empty()).unwrap_or_default().trim();
+    line.chars().take(LABEL_CHARS).collect()
+}
+
+fn label(conn: &Connection, item: &ItemRef) -> Option<String> {
+    match item.kind {
+        ItemKind::History => conn
+            .query_row(
+                "SELECT COALESCE(title, substr(content, 1, 500)) FROM history WHERE id = ?1",
+                [item.id.parse::<i64>().ok()?],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .ok()
+            .flatten()
+            .map(|text| first_chars(&text)),
+        ItemKind::Ai => conn
+            .query_row(
+                "SELECT COALESCE(preset_name, feature), substr(prompt, 1, 500) FROM ai_history WHERE id = ?1",
+                [item.id.parse::<i64>().ok()?],
+                |row| Ok(format!("{}: {}", row.get::<_, String>(0)?, first_chars(&row.get::<_, String>(1)?))),
+            )
+            .optional()
+            .ok()
+            .flatten(),
+        ItemKind::Snippet => load_snippets()
+            .ok()?
+            .snippets
+            .into_iter()
+            .find(|snippet| snippet.id == item.id)
+            .map(|snippet| snippet.name),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn link_items(
+    state: State<AppState>,
+    source: ItemRef,
+    target: ItemRef,
+    relation: String,
+) -> Result<ItemLink, AppError> {
+    let relation = relation.trim();
+    if relation.is_empty() {
+        return Err(AppError::invalid_input("Relation is required"));
+    }
+    if source == target {
+        return Err(AppError::invalid_input("An item can't be linked to itself"));
+    }
+    for item in [&source, &target] {
+        if item.kind != ItemKind::Snippet && item.id.parse::<i64>().is_err() {
+            return Err(AppError::invalid_input(format!("Invalid {} id: {}", item.kind.as_str(), item.id)));
+        }
+    }
+    let conn = state.db.lock()?;
+    Ok(add_link(&conn, &source, &target, relation)?)
+}
+
+/// Items linked to `item` in either direction, with labels for display
+#[tauri::command]
+pub fn get_links(state: State<AppState>, item: ItemRef) -> Result<Vec<LinkedItem>, AppError> {
+    let conn = state.db.lock()?;
+    let linked = links_for(&conn, &item)?
+        .into_iter()
+        .map(|link| {
+            let (direction, other) = if link.source == item { ("outgoing", link.target) } else { ("incoming", link.source) };
+            LinkedItem {
+                link_id: link.id,
+                relation: link.relation,
+                direction,
+                label: label(&conn, &other),
+                item: other,
+                created_at: link.created_at,
+            }
+        })
+        .collect();
+    Ok(linked)
+}
+
+#[tauri::command]
+pub fn unlink_items(state: State<AppState>, link_id: i64) -> Result<(), AppError> {
+    let conn = state.db.lock()?;
+    if conn.execute("DELETE FROM item_links WHERE id = ?1", [link_id])? == 0 {
+        return Err(AppError::not_found(format!("Link {} not found", link_id)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_in_both_directions() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_links_table(&conn).unwrap();
+        conn.execute(
+            "CREATE TABLE history (id INTEGER PRIMARY KEY, content TEXT, created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                                   content_hash TEXT, content_zstd BLOB, content_digest TEXT)",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO history (id, content) VALUES (7, 'SELECT 1')", []).unwrap();
+
+        let snippet = ItemRef::snippet("abc");
+        let link = link_from_matching_entry(&conn, "SELECT 1", &snippet, RELATION_SAVED_AS).unwrap().unwrap();
+        assert_eq!(link.source, ItemRef::history(7));
+        assert!(link_from_matching_entry(&conn, "other", &snippet, RELATION_SAVED_AS).unwrap().is_none());
+
+        // Linking again is a no-op
+        let again = add_link(&conn, &ItemRef::history(7), &snippet, RELATION_SAVED_AS).unwrap();
+        assert_eq!(again.id, link.id);
+        add_link(&conn, &ItemRef::history(7), &ItemRef::ai(3), RELATION_AI_RESPONSE).unwrap();
+
+        assert_eq!(links_for(&conn, &ItemRef::history(7)).unwrap().len(), 2);
+        let from_snippet = links_for(&conn, &snippet).unwrap();
+        assert_eq!(from_snippet.len(), 1);
+        assert_eq!(from_snippet[0].relation, "saved_as");
+    }
+
+    #[test]
+    fn test_matches_compressed_entries() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_links_table(&conn).unwrap();
+        conn.execute(
+            "CREATE TABLE history (id INTEGER PRIMARY KEY, content TEXT, created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                                   content_hash TEXT, content_zstd BLOB, content_digest TEXT)",
+            [],
+        )
+        .unwrap();
+        // Only the head is kept in `content`
+        let content = "SELECT 1;\n".repeat(300_000);
+        let packed = crate::compression::pack(&content).unwrap().unwrap();
+        conn.execute(
+            "INSERT INTO history (id, content, content_zstd) VALUES (9, ?1, ?2)",
+            rusqlite::params![packed.head, packed.data],
+        )
+        .unwrap();
+
+        let link = link_from_matching_entry(&conn, &content, &ItemRef::ai(1), RELATION_AI_RESPONSE).unwrap().unwrap();
+        assert_eq!(link.source, ItemRef::history(9));
+        assert!(link_from_matching_entry(&conn, &packed.head, &ItemRef::ai(2), RELATION_AI_RESPONSE).unwrap().is_none());
+    }
+}
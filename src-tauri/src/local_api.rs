@@ -0,0 +1,451 @@
+// Local HTTP API for launcher extensions. Listens on 127.0.0.1 only and expects
+// `Authorization: Bearer <token>` on every route but the health check. The token is
+// generated on first use and kept in the OS keychain; the settings screen shows it so
+// it can be pasted into the Alfred workflow or Raycast extension.
+//
+//   GET  /v1/health                          {"ok": true, "version": "..."}
+//   GET  /v1/launcher/query?q=...&limit=20   {"items": [LauncherItem, ...]}
+//   POST /v1/launcher/execute                {"action_id": "..."} -> {"ok": true}
+//
+// Requests whose Host isn't localhost are refused so a web page can't reach the API
+// through DNS rebinding.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::credentials::{get_credential, store_credential};
+use crate::error::AppError;
+use crate::launcher;
+use crate::AppState;
+
+pub const DEFAULT_PORT: u16 = 47660;
+const TOKEN_CREDENTIAL: &str = "local_api_token";
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug)]
+pub enum LocalApiError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Timed out reading the request")]
+    Timeout(#[from] tokio::time::error::Elapsed),
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+}
+
+struct Service {
+    task: tauri::async_runtime::JoinHandle<()>,
+    port: u16,
+}
+
+static SERVICE: Mutex<Option<Service>> = Mutex::new(None);
+
+/// The token, cached so requests don't each go to the keychain
+static TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalApiInfo {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub token: String,
+}
+
+// ============================================================================
+// Token
+// ============================================================================
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The API token, created and stored in the keychain the first time it's needed
+pub fn api_token() -> Result<String, LocalApiError> {
+    let mut cached = TOKEN.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(token) = cached.as_ref() {
+        return Ok(token.clone());
+    }
+    let token = match get_credential(TOKEN_CREDENTIAL.to_string()).map_err(|e| LocalApiError::Keychain(e.message))? {
+        Some(token) => token,
+        None => {
+            let token = generate_token();
+            store_credential(TOKEN_CREDENTIAL.to_string(), token.clone()).map_err(|e| LocalApiError::Keychain(e.message))?;
+            token
+        }
+    };
+    *cached = Some(token.clone());
+    Ok(token)
+}
+
+/// Compare without bailing at the first differing byte, so response timing doesn't
+/// reveal how much of a guess was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Whether an Authorization header value carries the current token
+pub fn is_authorized(header: Option<&str>) -> bool {
+    header
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .is_some_and(token_matches)
+}
+
+/// Whether `candidate` is the current token
+pub fn token_matches(candidate: &str) -> bool {
+    match api_token() {
+        Ok(token) => constant_time_eq(candidate.trim().as_bytes(), token.as_bytes()),
+        Err(e) => {
+            log::warn!("Local API token unavailable: {}", e);
+            false
+        }
+    }
+}
+
+/// Only loopback names are accepted; anything else came through a hostile DNS name
+pub(crate) fn is_local_host(host: Option<&str>) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    matches!(name, "127.0.0.1" | "localhost")
+}
+
+// ============================================================================
+// HTTP
+// ============================================================================
+
+#[derive(Debug)]
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+fn decode_component(s: &str) -> String {
+    let s = s.replace('+', " ");
+    urlencoding::decode(&s).map(|d| d.into_owned()).unwrap_or(s)
+}
+
+/// Parse the request line and headers. Header names are lowercased.
+fn parse_head(head: &str) -> Result<Request, LocalApiError> {
+    let bad = |msg: &str| LocalApiError::BadRequest(msg.to_string());
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (Some(method), Some(target), Some(version)) = (request_line.next(), request_line.next(), request_line.next())
+    else {
+        return Err(bad("malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(bad("unsupported HTTP version"));
+    }
+
+    let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+    let query = query_string
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode_component(key), decode_component(value))
+        })
+        .collect();
+
+    let mut headers = HashMap::new();
+    for line in lines.filter(|line| !line.is_empty()) {
+        let (name, value) = line.split_once(':').ok_or_else(|| bad("malformed header"))?;
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query,
+        headers,
+        body: Vec::new(),
+    })
+}
+
+pub(crate) async fn read_request(stream: &mut TcpStream) -> Result<Request, LocalApiError> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            return Err(LocalApiError::BadRequest("headers too large".to_string()));
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(LocalApiError::BadRequest("connection closed".to_string()));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let mut request = parse_head(&String::from_utf8_lossy(&buffer[..head_end]))?;
+    let length: usize = match request.header("content-length") {
+        Some(value) => value
+            .parse()
+            .map_err(|_| LocalApiError::BadRequest("invalid Content-Length".to_string()))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(LocalApiError::BadRequest("body too large".to_string()));
+    }
+    let mut body = buffer.split_off(head_end + 4);
+    if body.len() < length {
+        let start = body.len();
+        body.resize(length, 0);
+        stream.read_exact(&mut body[start..]).await?;
+    }
+    body.truncate(length);
+    request.body = body;
+    Ok(request)
+}
+
+pub(crate) async fn write_response(stream: &mut TcpStream, status: u16, body: &serde_json::Value) -> Result<(), LocalApiError> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    let body = serde_json::to_vec(body)?;
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+pub(crate) fn error_body(message: impl std::fmt::Display) -> serde_json::Value {
+    serde_json::json!({ "error": message.to_string() })
+}
+
+#[derive(Deserialize)]
+struct ExecuteBody {
+    action_id: String,
+}
+
+async fn route(app: &AppHandle, request: &Request) -> (u16, serde_json::Value) {
+    if !is_local_host(request.header("host")) {
+        return (403, error_body("Requests must be addressed to localhost"));
+    }
+    if request.path == "/v1/health" {
+        return (
+            200,
+            serde_json::json!({ "ok": true, "version": app.package_info().version.to_string() }),
+        );
+    }
+    if !is_authorized(request.header("authorization")) {
+        return (401, error_body("Missing or invalid API token"));
+    }
+    // The command middleware doesn't see these requests, so presentation mode is checked here
+    if crate::presentation::is_active() {
+        return (403, error_body(crate::presentation::blocked_error()));
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/launcher/query") => {
+            let query = request.query.get("q").map(String::as_str).unwrap_or_default();
+            let limit = request
+                .query
+                .get("limit")
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(launcher::DEFAULT_LIMIT);
+            let state = app.state::<AppState>();
+            let result = match state.db.lock() {
+                Ok(conn) => launcher::query(&conn, query, limit),
+                Err(_) => Err(launcher::LauncherError::Lock),
+            };
+            match result {
+                Ok(items) => (200, serde_json::json!({ "items": items })),
+                Err(e) => (500, error_body(e)),
+            }
+        }
+        ("POST", "/v1/launcher/execute") => {
+            let body: ExecuteBody = match serde_json::from_slice(&request.body) {
+                Ok(body) => body,
+                Err(e) => return (400, error_body(e)),
+            };
+            match launcher::execute(app, &body.action_id).await {
+                Ok(()) => (200, serde_json::json!({ "ok": true })),
+                Err(e @ launcher::LauncherError::UnknownAction(_)) => (400, error_body(e)),
+                Err(e @ launcher::LauncherError::NotFound(_)) => (404, error_body(e)),
+                Err(e @ launcher::LauncherError::Denied(_)) => (403, error_body(e)),
+                Err(e) => (500, error_body(e)),
+            }
+        }
+        (_, "/v1/launcher/query") | (_, "/v1/launcher/execute") => (405, error_body("Method not allowed")),
+        _ => (404, error_body("Not found")),
+    }
+}
+
+async fn handle_connection(app: &AppHandle, mut stream: TcpStream) -> Result<(), LocalApiError> {
+    let (status, body) = match tokio::time::timeout(IO_TIMEOUT, read_request(&mut stream)).await? {
+        Ok(request) => route(app, &request).await,
+        Err(e) => (400, error_body(e)),
+    };
+    write_response(&mut stream, status, &body).await
+}
+
+async fn run_listener(app: AppHandle, listener: std::net::TcpListener) {
+    let listener = match TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Local API listener failed: {}", e);
+            return;
+        }
+    };
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = handle_connection(&app, stream).await {
+                        log::debug!("Local API request failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => {
+                log::warn!("Local API accept failed: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Service
+// ============================================================================
+
+/// Start serving on localhost. Does nothing if already running.
+pub fn start(app: &AppHandle) -> Result<(), LocalApiError> {
+    let mut service = SERVICE.lock().unwrap_or_else(|e| e.into_inner());
+    if service.is_some() {
+        return Ok(());
+    }
+    // Create the token up front so a keychain prompt doesn't stall the first request
+    api_token()?;
+
+    let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, DEFAULT_PORT))
+        .or_else(|_| std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)))?;
+    listener.set_nonblocking(true)?;
+    let port = listener.local_addr()?.port();
+    log::info!("Local API listening on 127.0.0.1:{}", port);
+
+    *service = Some(Service {
+        task: tauri::async_runtime::spawn(run_listener(app.clone(), listener)),
+        port,
+    });
+    Ok(())
+}
+
+pub fn stop() {
+    if let Some(service) = SERVICE.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        service.task.abort();
+    }
+}
+
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        start(app).map_err(|e| format!("Failed to start the local API: {}", e))
+    } else {
+        stop();
+        Ok(())
+    }
+}
+
+fn running_port() -> Option<u16> {
+    SERVICE.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|s| s.port)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Whether the API is up, its port and the token extensions should send
+#[tauri::command]
+pub fn get_local_api_info() -> Result<LocalApiInfo, AppError> {
+    let port = running_port();
+    Ok(LocalApiInfo {
+        running: port.is_some(),
+        port,
+        token: api_token()?,
+    })
+}
+
+/// Replace the token; extensions using the old one stop working
+#[tauri::command]
+pub fn regenerate_local_api_token() -> Result<String, AppError> {
+    let token = generate_token();
+    store_credential(TOKEN_CREDENTIAL.to_string(), token.clone())?;
+    *TOKEN.lock().unwrap_or_else(|e| e.into_inner()) = Some(token.clone());
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let request = parse_head(
+            "GET /v1/launcher/query?q=docker+rm%21&limit=5 HTTP/1.1\r\nHost: 127.0.0.1:47660\r\nAuthorization: Bearer abc",
+        )
+        .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/v1/launcher/query");
+        assert_eq!(request.query["q"], "docker rm!");
+        assert_eq!(request.query["limit"], "5");
+        assert_eq!(request.header("authorization"), Some("Bearer abc"));
+
+        assert!(parse_head("GET /\r\n").is_err());
+        assert!(parse_head("GET / SPDY/3\r\n").is_err());
+    }
+
+    #[test]
+    fn test_host_and_token_checks() {
+        assert!(is_local_host(Some("127.0.0.1:47660")));
+        assert!(is_local_host(Some("localhost")));
+        assert!(!is_local_host(Some("evil.example:47660")));
+        assert!(!is_local_host(None));
+
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+        assert_eq!(generate_token().len(), 64);
+    }
+}
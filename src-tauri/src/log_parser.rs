@@ -0,0 +1,449 @@
+// Parsing of pasted log excerpts: JSON lines, logfmt and syslog, with a plain-text
+// fallback that still picks out levels and timestamps. Lines that don't parse in a
+// structured log (stack traces, wrapped messages) are attached to the record above.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json,
+    Logfmt,
+    Syslog,
+    Plain,
+}
+
+impl LogFormat {
+    fn from_hint(hint: &str) -> Option<Self> {
+        match hint.to_ascii_lowercase().as_str() {
+            "json" | "jsonl" | "ndjson" => Some(LogFormat::Json),
+            "logfmt" => Some(LogFormat::Logfmt),
+            "syslog" => Some(LogFormat::Syslog),
+            "plain" | "text" => Some(LogFormat::Plain),
+            _ => None,
+        }
+    }
+}
+
+/// Normalized severity, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "trace" | "trc" | "verbose" => Some(LogLevel::Trace),
+            "debug" | "dbg" | "d" => Some(LogLevel::Debug),
+            "info" | "inf" | "information" | "notice" | "i" => Some(LogLevel::Info),
+            "warn" | "warning" | "wrn" | "w" => Some(LogLevel::Warn),
+            "error" | "err" | "e" => Some(LogLevel::Error),
+            "fatal" | "critical" | "crit" | "panic" | "emerg" | "emergency" | "alert" | "f" => {
+                Some(LogLevel::Fatal)
+            }
+            _ => None,
+        }
+    }
+
+    /// pino/bunyan numeric levels
+    fn from_number(value: i64) -> Option<Self> {
+        match value {
+            ..=10 => Some(LogLevel::Trace),
+            11..=20 => Some(LogLevel::Debug),
+            21..=30 => Some(LogLevel::Info),
+            31..=40 => Some(LogLevel::Warn),
+            41..=50 => Some(LogLevel::Error),
+            _ => Some(LogLevel::Fatal),
+        }
+    }
+
+    /// Syslog severity (the low three bits of PRI)
+    fn from_syslog_severity(severity: u32) -> Self {
+        match severity {
+            0..=2 => LogLevel::Fatal,
+            3 => LogLevel::Error,
+            4 => LogLevel::Warn,
+            5 | 6 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    /// 1-based line the record starts on
+    pub line_number: usize,
+    /// Original text, including any continuation lines
+    pub raw: String,
+    pub timestamp: Option<String>,
+    pub level: Option<LogLevel>,
+    pub message: Option<String>,
+    /// Remaining structured fields (JSON/logfmt keys, syslog host and app)
+    pub fields: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedLog {
+    pub format: LogFormat,
+    pub records: Vec<LogRecord>,
+}
+
+const LEVEL_KEYS: &[&str] = &["level", "lvl", "severity", "levelname", "log.level", "loglevel"];
+const MESSAGE_KEYS: &[&str] = &["msg", "message", "@message", "event"];
+const TIME_KEYS: &[&str] = &["time", "timestamp", "ts", "@timestamp", "t", "datetime", "date"];
+
+fn regex(pattern: &'static str, cell: &'static OnceLock<Regex>) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid regex"))
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Move the well-known level/message/timestamp keys out of a field map
+fn take_known_fields(line_number: usize, raw: &str, mut fields: Map<String, Value>) -> LogRecord {
+    let mut take = |keys: &[&str]| keys.iter().find_map(|k| fields.remove(*k));
+
+    let level = take(LEVEL_KEYS).and_then(|v| match v {
+        Value::Number(n) => n.as_i64().and_then(LogLevel::from_number),
+        other => LogLevel::parse(&value_to_string(&other)),
+    });
+    let message = take(MESSAGE_KEYS).map(|v| value_to_string(&v));
+    let timestamp = take(TIME_KEYS).map(|v| value_to_string(&v));
+
+    LogRecord {
+        line_number,
+        raw: raw.to_string(),
+        timestamp,
+        level,
+        message,
+        fields,
+    }
+}
+
+// ============================================================================
+// Format parsers
+// ============================================================================
+
+fn parse_json_line(line_number: usize, line: &str) -> Option<LogRecord> {
+    match serde_json::from_str::<Value>(line.trim()).ok()? {
+        Value::Object(fields) => Some(take_known_fields(line_number, line, fields)),
+        _ => None,
+    }
+}
+
+/// Split a logfmt line into key/value pairs. Requires at least two pairs so
+/// prose that happens to contain an `=` isn't mistaken for logfmt.
+fn parse_logfmt_line(line_number: usize, line: &str) -> Option<LogRecord> {
+    let mut fields = Map::new();
+    let mut chars = line.trim().chars().peekable();
+
+    while chars.peek().is_some() {
+        while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+            chars.next();
+        }
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let value = if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => {
+                        if let Some(c) = chars.next() {
+                            value.push(c);
+                        }
+                    }
+                    Some('"') | None => break,
+                    Some(c) => value.push(c),
+                }
+            }
+            value
+        } else {
+            chars.by_ref().take_while(|c| !c.is_whitespace()).collect()
+        };
+        fields.insert(key, Value::String(value));
+    }
+
+    if fields.len() < 2 {
+        return None;
+    }
+    Some(take_known_fields(line_number, line, fields))
+}
+
+fn parse_syslog_line(line_number: usize, line: &str) -> Option<LogRecord> {
+    // RFC 5424: <PRI>1 TIMESTAMP HOST APP PROCID MSGID [SD] MSG
+    static RFC5424: OnceLock<Regex> = OnceLock::new();
+    // RFC 3164 (BSD): <PRI>Mmm dd hh:mm:ss HOST APP[PID]: MSG; PRI is optional in files
+    static RFC3164: OnceLock<Regex> = OnceLock::new();
+
+    let mut fields = Map::new();
+    let (pri, timestamp, message) = if let Some(c) = regex(
+        r"^<(\d{1,3})>1 (\S+) (\S+) (\S+) (\S+) (\S+) (-|\[.*?\]) ?(.*)$",
+        &RFC5424,
+    )
+    .captures(line)
+    {
+        fields.insert("host".to_string(), Value::String(c[3].to_string()));
+        fields.insert("app".to_string(), Value::String(c[4].to_string()));
+        if &c[5] != "-" {
+            fields.insert("pid".to_string(), Value::String(c[5].to_string()));
+        }
+        (c[1].parse::<u32>().ok(), c[2].to_string(), c[8].to_string())
+    } else {
+        let c = regex(
+            r"^(?:<(\d{1,3})>)?([A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}) (\S+) ([^:\[\s]+)(?:\[(\d+)\])?: (.*)$",
+            &RFC3164,
+        )
+        .captures(line)?;
+        fields.insert("host".to_string(), Value::String(c[3].to_string()));
+        fields.insert("app".to_string(), Value::String(c[4].to_string()));
+        if let Some(pid) = c.get(5) {
+            fields.insert("pid".to_string(), Value::String(pid.as_str().to_string()));
+        }
+        (
+            c.get(1).and_then(|p| p.as_str().parse::<u32>().ok()),
+            c[2].to_string(),
+            c[6].to_string(),
+        )
+    };
+
+    // Without PRI, fall back to a level word in the message
+    let level = pri
+        .map(|p| LogLevel::from_syslog_severity(p % 8))
+        .or_else(|| detect_level(&message));
+
+    Some(LogRecord {
+        line_number,
+        raw: line.to_string(),
+        timestamp: Some(timestamp),
+        level,
+        message: Some(message),
+        fields,
+    })
+}
+
+fn detect_level(text: &str) -> Option<LogLevel> {
+    static LEVEL: OnceLock<Regex> = OnceLock::new();
+    regex(
+        r"(?i)\b(TRACE|DEBUG|INFO|NOTICE|WARN|WARNING|ERROR|ERR|FATAL|CRITICAL|PANIC)\b",
+        &LEVEL,
+    )
+    .captures(text)
+    .and_then(|c| LogLevel::parse(&c[1]))
+}
+
+fn parse_plain_line(line_number: usize, line: &str) -> LogRecord {
+    static TIMESTAMP: OnceLock<Regex> = OnceLock::new();
+    let timestamp = regex(
+        r"^\[?(\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\]?",
+        &TIMESTAMP,
+    )
+    .captures(line)
+    .map(|c| c[1].to_string());
+
+    LogRecord {
+        line_number,
+        raw: line.to_string(),
+        timestamp,
+        level: detect_level(line),
+        message: Some(line.to_string()),
+        fields: Map::new(),
+    }
+}
+
+fn parse_line(format: LogFormat, line_number: usize, line: &str) -> Option<LogRecord> {
+    match format {
+        LogFormat::Json => parse_json_line(line_number, line),
+        LogFormat::Logfmt => parse_logfmt_line(line_number, line),
+        LogFormat::Syslog => parse_syslog_line(line_number, line),
+        LogFormat::Plain => Some(parse_plain_line(line_number, line)),
+    }
+}
+
+/// Pick the structured format that parses the most of the first lines
+fn detect_format(text: &str) -> LogFormat {
+    let sample: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .take(20)
+        .collect();
+
+    [LogFormat::Json, LogFormat::Syslog, LogFormat::Logfmt]
+        .into_iter()
+        .map(|format| {
+            let hits = sample
+                .iter()
+                .filter(|(i, line)| parse_line(format, *i + 1, line).is_some())
+                .count();
+            (format, hits)
+        })
+        // Require a majority so a stray JSON line doesn't claim the whole log
+        .filter(|(_, hits)| *hits * 2 > sample.len())
+        .max_by_key(|(_, hits)| *hits)
+        .map(|(format, _)| format)
+        .unwrap_or(LogFormat::Plain)
+}
+
+pub fn parse_log(text: &str, format_hint: Option<&str>) -> ParsedLog {
+    let format = format_hint
+        .and_then(LogFormat::from_hint)
+        .unwrap_or_else(|| detect_format(text));
+
+    let mut records: Vec<LogRecord> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_line(format, index + 1, line) {
+            Some(record) => records.push(record),
+            None => match records.last_mut() {
+                Some(previous) => {
+                    previous.raw.push('\n');
+                    previous.raw.push_str(line);
+                    if let Some(message) = previous.message.as_mut() {
+                        message.push('\n');
+                        message.push_str(line);
+                    }
+                }
+                None => records.push(parse_plain_line(index + 1, line)),
+            },
+        }
+    }
+
+    // Plain logs: indented lines (stack frames) belong to the record above
+    if format == LogFormat::Plain {
+        let mut merged: Vec<LogRecord> = Vec::with_capacity(records.len());
+        for record in records {
+            let is_continuation = record.raw.starts_with(char::is_whitespace)
+                || record.raw.starts_with("Caused by");
+            match merged.last_mut() {
+                Some(previous) if is_continuation => {
+                    previous.raw.push('\n');
+                    previous.raw.push_str(&record.raw);
+                }
+                _ => merged.push(record),
+            }
+        }
+        records = merged;
+    }
+
+    ParsedLog { format, records }
+}
+
+/// Keep records at or above `min_level` whose text matches `pattern` (a regex).
+/// Records without a detectable level are dropped when a level is given.
+pub fn filter_log(text: &str, min_level: Option<&str>, pattern: Option<&str>) -> Result<String, String> {
+    let min_level = match min_level.filter(|l| !l.trim().is_empty()) {
+        Some(level) => Some(LogLevel::parse(level).ok_or_else(|| format!("Unknown log level: {}", level))?),
+        None => None,
+    };
+    let pattern = match pattern.filter(|p| !p.is_empty()) {
+        Some(p) => Some(Regex::new(p).map_err(|e| format!("Invalid pattern: {}", e))?),
+        None => None,
+    };
+
+    let kept: Vec<String> = parse_log(text, None)
+        .records
+        .into_iter()
+        .filter(|record| match min_level {
+            Some(min) => record.level.map(|level| level >= min).unwrap_or(false),
+            None => true,
+        })
+        .filter(|record| pattern.as_ref().map(|re| re.is_match(&record.raw)).unwrap_or(true))
+        .map(|record| record.raw)
+        .collect();
+
+    Ok(kept.join("\n"))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn parse_log_lines(text: String, format_hint: Option<String>) -> ParsedLog {
+    parse_log(&text, format_hint.as_deref())
+}
+
+#[tauri::command]
+pub fn filter_log_lines(text: String, level: Option<String>, pattern: Option<String>) -> Result<String, AppError> {
+    filter_log(&text, level.as_deref(), pattern.as_deref()).map_err(AppError::invalid_input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_lines() {
+        let text = r#"{"level":30,"time":1700000000,"msg":"started","port":8080}
+{"level":"error","timestamp":"2024-01-01T00:00:00Z","message":"boom"}
+    at handler (app.js:10)"#;
+        let parsed = parse_log(text, None);
+        assert_eq!(parsed.format, LogFormat::Json);
+        assert_eq!(parsed.records.len(), 2);
+        assert_eq!(parsed.records[0].level, Some(LogLevel::Info));
+        assert_eq!(parsed.records[0].fields["port"], 8080);
+        assert_eq!(parsed.records[1].level, Some(LogLevel::Error));
+        assert!(parsed.records[1].raw.ends_with("(app.js:10)"));
+    }
+
+    #[test]
+    fn test_logfmt() {
+        let parsed = parse_log(
+            "ts=2024-01-01T00:00:00Z level=warn msg=\"disk almost full\" pct=91\nts=2024-01-01T00:00:01Z level=info msg=ok",
+            None,
+        );
+        assert_eq!(parsed.format, LogFormat::Logfmt);
+        assert_eq!(parsed.records[0].message.as_deref(), Some("disk almost full"));
+        assert_eq!(parsed.records[0].level, Some(LogLevel::Warn));
+        assert_eq!(parsed.records[0].fields["pct"], "91");
+    }
+
+    #[test]
+    fn test_syslog() {
+        let parsed = parse_log(
+            "<11>Jan  5 10:00:00 web01 nginx[123]: upstream timed out\n<14>1 2024-01-05T10:00:00Z web01 app 99 - - hello",
+            None,
+        );
+        assert_eq!(parsed.format, LogFormat::Syslog);
+        assert_eq!(parsed.records[0].level, Some(LogLevel::Error));
+        assert_eq!(parsed.records[0].fields["app"], "nginx");
+        assert_eq!(parsed.records[1].level, Some(LogLevel::Info));
+        assert_eq!(parsed.records[1].message.as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn test_filter_plain_log() {
+        let text = "2024-01-01 10:00:00 INFO starting\n2024-01-01 10:00:01 ERROR failed to connect\n    at db.connect\n2024-01-01 10:00:02 WARN retrying";
+        assert_eq!(
+            filter_log(text, Some("warn"), None).unwrap(),
+            "2024-01-01 10:00:01 ERROR failed to connect\n    at db.connect\n2024-01-01 10:00:02 WARN retrying"
+        );
+        assert_eq!(
+            filter_log(text, None, Some("start")).unwrap(),
+            "2024-01-01 10:00:00 INFO starting"
+        );
+        assert!(filter_log(text, Some("loud"), None).is_err());
+    }
+}
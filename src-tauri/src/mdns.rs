@@ -0,0 +1,356 @@
+// Minimal mDNS (RFC 6762 / DNS-SD) for finding other Wingman instances on the LAN:
+// just enough DNS encoding to ask for and announce one service type. Not a general
+// resolver; records for other services are ignored.
+
+use std::net::Ipv4Addr;
+
+pub const SERVICE_TYPE: &str = "_wingman._tcp.local";
+pub const MDNS_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+pub const MDNS_PORT: u16 = 5353;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+/// Set on records we own so caches replace rather than merge them
+const CACHE_FLUSH: u16 = 0x8000;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_AUTHORITATIVE: u16 = 0x0400;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceInfo {
+    /// Stable LAN identifier, also used as the instance and host label
+    pub id: String,
+    pub name: String,
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    pub info: ServiceInfo,
+    /// 0 means the instance is going away
+    pub ttl: u32,
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+fn push_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_name(out: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        let bytes = &label.as_bytes()[..label.len().min(63)];
+        out.push(bytes.len() as u8);
+        out.extend_from_slice(bytes);
+    }
+    out.push(0);
+}
+
+fn push_record(out: &mut Vec<u8>, name: &str, record_type: u16, class: u16, ttl: u32, rdata: &[u8]) {
+    push_name(out, name);
+    push_u16(out, record_type);
+    push_u16(out, class);
+    out.extend_from_slice(&ttl.to_be_bytes());
+    push_u16(out, rdata.len() as u16);
+    out.extend_from_slice(rdata);
+}
+
+fn header(flags: u16, questions: u16, answers: u16, additional: u16) -> Vec<u8> {
+    let mut out = Vec::with_capacity(512);
+    push_u16(&mut out, 0); // mDNS ids are always 0
+    push_u16(&mut out, flags);
+    push_u16(&mut out, questions);
+    push_u16(&mut out, answers);
+    push_u16(&mut out, 0);
+    push_u16(&mut out, additional);
+    out
+}
+
+/// PTR query for the Wingman service type
+pub fn build_query() -> Vec<u8> {
+    let mut out = header(0, 1, 0, 0);
+    push_name(&mut out, SERVICE_TYPE);
+    push_u16(&mut out, TYPE_PTR);
+    push_u16(&mut out, CLASS_IN);
+    out
+}
+
+fn instance_name(id: &str) -> String {
+    format!("{}.{}", id, SERVICE_TYPE)
+}
+
+fn txt_entry(out: &mut Vec<u8>, key: &str, value: &str) {
+    let entry = format!("{}={}", key, value);
+    let mut end = entry.len().min(255);
+    while !entry.is_char_boundary(end) {
+        end -= 1;
+    }
+    out.push(end as u8);
+    out.extend_from_slice(&entry.as_bytes()[..end]);
+}
+
+/// Response advertising `info`: PTR answer plus SRV/TXT (and A, when the address is
+/// known) as additional records. A `ttl` of 0 is a goodbye.
+pub fn build_announcement(info: &ServiceInfo, address: Option<Ipv4Addr>, ttl: u32) -> Vec<u8> {
+    let instance = instance_name(&info.id);
+    let host = format!("{}.local", info.id);
+    let mut out = header(
+        FLAG_RESPONSE | FLAG_AUTHORITATIVE,
+        0,
+        1,
+        if address.is_some() { 3 } else { 2 },
+    );
+
+    let mut ptr = Vec::new();
+    push_name(&mut ptr, &instance);
+    push_record(&mut out, SERVICE_TYPE, TYPE_PTR, CLASS_IN, ttl, &ptr);
+
+    let mut srv = Vec::new();
+    push_u16(&mut srv, 0); // priority
+    push_u16(&mut srv, 0); // weight
+    push_u16(&mut srv, info.port);
+    push_name(&mut srv, &host);
+    push_record(&mut out, &instance, TYPE_SRV, CLASS_IN | CACHE_FLUSH, ttl, &srv);
+
+    let mut txt = Vec::new();
+    txt_entry(&mut txt, "id", &info.id);
+    txt_entry(&mut txt, "name", &info.name);
+    txt_entry(&mut txt, "v", "1");
+    push_record(&mut out, &instance, TYPE_TXT, CLASS_IN | CACHE_FLUSH, ttl, &txt);
+
+    if let Some(address) = address {
+        push_record(&mut out, &host, TYPE_A, CLASS_IN | CACHE_FLUSH, ttl, &address.octets());
+    }
+    out
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+struct Reader<'a> {
+    packet: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn u8(&mut self) -> Option<u8> {
+        let value = *self.packet.get(self.pos)?;
+        self.pos += 1;
+        Some(value)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.packet.get(self.pos..self.pos + 2)?;
+        self.pos += 2;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.packet.get(self.pos..self.pos + 4)?;
+        self.pos += 4;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.packet.get(self.pos..self.pos + len)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    /// Reads a possibly compressed name, leaving the cursor after it
+    fn name(&mut self) -> Option<String> {
+        let mut labels: Vec<String> = Vec::new();
+        let mut pos = self.pos;
+        let mut resume = None;
+        // Bounds pointer chains so a malicious packet can't loop forever
+        for _ in 0..128 {
+            let len = *self.packet.get(pos)? as usize;
+            if len == 0 {
+                self.pos = resume.unwrap_or(pos + 1);
+                return Some(labels.join("."));
+            }
+            if len & 0xC0 == 0xC0 {
+                let offset = ((len & 0x3F) << 8) | *self.packet.get(pos + 1)? as usize;
+                resume.get_or_insert(pos + 2);
+                pos = offset;
+                continue;
+            }
+            let label = self.packet.get(pos + 1..pos + 1 + len)?;
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            pos += 1 + len;
+        }
+        None
+    }
+}
+
+struct Record<'a> {
+    name: String,
+    record_type: u16,
+    ttl: u32,
+    rdata_start: usize,
+    rdata: &'a [u8],
+}
+
+struct Packet<'a> {
+    flags: u16,
+    /// (name, type) of each question
+    questions: Vec<(String, u16)>,
+    /// Answer, authority and additional records together
+    records: Vec<Record<'a>>,
+}
+
+fn parse_packet(packet: &[u8]) -> Option<Packet<'_>> {
+    let mut reader = Reader { packet, pos: 0 };
+    let _id = reader.u16()?;
+    let flags = reader.u16()?;
+    let counts = [reader.u16()?, reader.u16()?, reader.u16()?, reader.u16()?];
+
+    let mut questions = Vec::new();
+    for _ in 0..counts[0] {
+        let name = reader.name()?;
+        let question_type = reader.u16()?;
+        let _class = reader.u16()?;
+        questions.push((name, question_type));
+    }
+
+    let mut records = Vec::new();
+    for _ in 0..(counts[1] as usize + counts[2] as usize + counts[3] as usize) {
+        let name = reader.name()?;
+        let record_type = reader.u16()?;
+        let _class = reader.u16()?;
+        let ttl = reader.u32()?;
+        let len = reader.u16()? as usize;
+        let rdata_start = reader.pos;
+        let rdata = reader.bytes(len)?;
+        records.push(Record {
+            name,
+            record_type,
+            ttl,
+            rdata_start,
+            rdata,
+        });
+    }
+    Some(Packet {
+        flags,
+        questions,
+        records,
+    })
+}
+
+/// Whether `packet` is a query asking for Wingman instances
+pub fn is_service_query(packet: &[u8]) -> bool {
+    match parse_packet(packet) {
+        Some(parsed) => {
+            parsed.flags & FLAG_RESPONSE == 0
+                && parsed
+                    .questions
+                    .iter()
+                    .any(|(name, qtype)| name.eq_ignore_ascii_case(SERVICE_TYPE) && matches!(*qtype, TYPE_PTR | TYPE_ANY))
+        }
+        None => false,
+    }
+}
+
+/// The Wingman instance advertised in a response, if any
+pub fn parse_announcement(packet: &[u8]) -> Option<Announcement> {
+    let Packet { flags, records, .. } = parse_packet(packet)?;
+    if flags & FLAG_RESPONSE == 0 {
+        return None;
+    }
+    let suffix = format!(".{}", SERVICE_TYPE);
+    let is_ours = |name: &str| name.to_ascii_lowercase().ends_with(&suffix);
+
+    let mut port = None;
+    let mut ttl = None;
+    let (mut id, mut name) = (None, None);
+    for record in records.iter().filter(|r| is_ours(&r.name)) {
+        match record.record_type {
+            TYPE_SRV => {
+                let mut reader = Reader {
+                    packet,
+                    pos: record.rdata_start + 4,
+                };
+                port = reader.u16();
+                ttl = Some(record.ttl);
+            }
+            TYPE_TXT => {
+                let mut reader = Reader {
+                    packet: record.rdata,
+                    pos: 0,
+                };
+                while let Some(len) = reader.u8() {
+                    let Some(entry) = reader.bytes(len as usize) else {
+                        break;
+                    };
+                    let entry = String::from_utf8_lossy(entry);
+                    match entry.split_once('=') {
+                        Some(("id", value)) => id = Some(value.to_string()),
+                        Some(("name", value)) => name = Some(value.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let id = id?;
+    Some(Announcement {
+        info: ServiceInfo {
+            name: name.unwrap_or_else(|| id.clone()),
+            id,
+            port: port?,
+        },
+        ttl: ttl.unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_announcement_roundtrip() {
+        let info = ServiceInfo {
+            id: "0123456789abcdef".to_string(),
+            name: "Jo's MacBook".to_string(),
+            port: 47653,
+        };
+        let packet = build_announcement(&info, Some(Ipv4Addr::new(192, 168, 1, 20)), 120);
+        let parsed = parse_announcement(&packet).unwrap();
+        assert_eq!(parsed.info, info);
+        assert_eq!(parsed.ttl, 120);
+        assert!(!is_service_query(&packet));
+    }
+
+    #[test]
+    fn test_query_detection() {
+        assert!(is_service_query(&build_query()));
+        assert!(parse_announcement(&build_query()).is_none());
+        assert!(!is_service_query(&[0, 0, 0]));
+    }
+
+    #[test]
+    fn test_compressed_names() {
+        // Header, then "_wingman._tcp.local" at offset 12 and a pointer back to it
+        let mut packet = header(0, 2, 0, 0);
+        push_name(&mut packet, SERVICE_TYPE);
+        push_u16(&mut packet, TYPE_A);
+        push_u16(&mut packet, CLASS_IN);
+        packet.extend_from_slice(&[0xC0, 12]);
+        push_u16(&mut packet, TYPE_PTR);
+        push_u16(&mut packet, CLASS_IN);
+        assert!(is_service_query(&packet));
+
+        // A pointer to itself must not hang
+        let mut looping = header(0, 1, 0, 0);
+        looping.extend_from_slice(&[0xC0, 12, 0, 12, 0, 1]);
+        assert!(!is_service_query(&looping));
+    }
+}
@@ -10,7 +10,6 @@ use cocoa::foundation::{NSArray, NSString};
 #[cfg(target_os = "macos")]
 use objc::{class, msg_send, sel, sel_impl};
 
-use std::fs;
 use std::path::PathBuf;
 
 /// Represents an image to be written to the clipboard
@@ -24,33 +23,21 @@ pub struct ClipboardImage {
     pub name: String,
 }
 
-/// Get the temp directory for clipboard files
-fn get_clipboard_temp_dir() -> Result<PathBuf, String> {
-    let temp_dir = std::env::temp_dir().join("wingman_clipboard");
-    if !temp_dir.exists() {
-        fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
-    }
-    Ok(temp_dir)
-}
-
-/// Clean up old temp files (files older than 1 hour)
-fn cleanup_old_temp_files(temp_dir: &PathBuf) {
-    if let Ok(entries) = fs::read_dir(temp_dir) {
-        let one_hour_ago = std::time::SystemTime::now()
-            .checked_sub(std::time::Duration::from_secs(3600))
-            .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-
-        for entry in entries.flatten() {
-            if let Ok(metadata) = entry.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    if modified < one_hour_ago {
-                        let _ = fs::remove_file(entry.path());
-                    }
-                }
-            }
-        }
-    }
+/// Everything readable from the clipboard in one snapshot
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct RichClipboardContent {
+    /// Plain text representation, if any
+    pub text: Option<String>,
+    /// HTML representation; taken from public.html or converted from RTF
+    pub html: Option<String>,
+    /// True when `html` was produced by converting an RTF payload
+    pub html_from_rtf: bool,
+    /// Raw RTF source, when the clipboard has an RTF representation
+    pub rtf: Option<String>,
+    /// Whether image data is available on the clipboard
+    pub has_image: bool,
+    /// MIME type of the best available image representation
+    pub image_mime_type: Option<String>,
 }
 
 /// Get file extension from MIME type
@@ -88,9 +75,7 @@ pub fn write_to_clipboard(
 ) -> Result<(), String> {
     use base64::Engine;
 
-    // Get temp directory and clean up old files
-    let temp_dir = get_clipboard_temp_dir()?;
-    cleanup_old_temp_files(&temp_dir);
+    let temp_dir = crate::temp_files::dir()?;
 
     // Save images to temp files and collect paths
     let mut file_paths: Vec<String> = Vec::new();
@@ -100,17 +85,17 @@ pub fn write_to_clipboard(
             .decode(&image.data)
             .map_err(|e| format!("Failed to decode base64 image: {}", e))?;
 
-        // Use original filename or generate one
-        let filename = if image.name.is_empty() {
-            let ext = extension_from_mime(&image.mime_type);
-            format!("clipboard_{}.{}", chrono::Utc::now().timestamp_millis(), ext)
-        } else {
-            image.name.clone()
+        // Use original filename (never a path out of the temp dir) or generate one
+        let filename = match std::path::Path::new(&image.name).file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => {
+                let ext = extension_from_mime(&image.mime_type);
+                format!("clipboard_{}.{}", chrono::Utc::now().timestamp_millis(), ext)
+            }
         };
 
         let file_path = temp_dir.join(&filename);
-        fs::write(&file_path, &image_bytes)
-            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        crate::temp_files::write_private(&file_path, &image_bytes)?;
 
         file_paths.push(file_path.to_string_lossy().to_string());
     }
@@ -240,9 +225,242 @@ pub fn write_to_clipboard(
                 }
             }
         }
+    }
+
+    if !file_paths.is_empty() {
+        let paths: Vec<PathBuf> = file_paths.iter().map(PathBuf::from).collect();
+        crate::temp_files::track_pasteboard_files(&paths);
+    }
+    Ok(())
+}
+
+/// Write alternative representations of the same content (plain text, HTML, RTF)
+/// so the pasting app can pick the richest flavor it understands
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+pub fn write_formats_to_clipboard(text: &str, html: Option<&str>, rtf: Option<&str>) -> Result<(), String> {
+    unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let _: i64 = msg_send![pasteboard, clearContents];
+
+        // Richest first, so apps that take the first type they support get formatting
+        let mut types: Vec<id> = Vec::new();
+        if rtf.is_some() {
+            types.push(NSString::alloc(nil).init_str("public.rtf"));
+        }
+        if html.is_some() {
+            types.push(NSString::alloc(nil).init_str("public.html"));
+        }
+        types.push(NSString::alloc(nil).init_str("public.utf8-plain-text"));
+
+        let types_array = NSArray::arrayWithObjects(nil, &types);
+        let _: id = msg_send![pasteboard, declareTypes:types_array owner:nil];
+
+        if let Some(rtf) = rtf {
+            let rtf_type = NSString::alloc(nil).init_str("public.rtf");
+            let ns_data: id = msg_send![class!(NSData), dataWithBytes:rtf.as_ptr() length:rtf.len()];
+            let _: bool = msg_send![pasteboard, setData:ns_data forType:rtf_type];
+        }
+
+        if let Some(html) = html {
+            let html_type = NSString::alloc(nil).init_str("public.html");
+            let ns_html = NSString::alloc(nil).init_str(html);
+            let _: bool = msg_send![pasteboard, setString:ns_html forType:html_type];
+        }
+
+        let text_type = NSString::alloc(nil).init_str("public.utf8-plain-text");
+        let ns_text = NSString::alloc(nil).init_str(text);
+        let _: bool = msg_send![pasteboard, setString:ns_text forType:text_type];
+    }
 
-        Ok(())
+    Ok(())
+}
+
+/// X11 clipboard contents are served by their owner, so the clipboard is kept open for
+/// the life of the app rather than dropped (and emptied) after each write
+#[cfg(target_os = "linux")]
+static CLIPBOARD: std::sync::Mutex<Option<arboard::Clipboard>> = std::sync::Mutex::new(None);
+
+/// HTML is offered as text/html alongside UTF8_STRING and text/plain, so apps that only
+/// take plain text still paste something
+#[cfg(target_os = "linux")]
+pub fn write_formats_to_clipboard(text: &str, html: Option<&str>, _rtf: Option<&str>) -> Result<(), String> {
+    let mut guard = CLIPBOARD.lock().map_err(|e| e.to_string())?;
+    let clipboard = match &mut *guard {
+        Some(clipboard) => clipboard,
+        slot => slot.insert(arboard::Clipboard::new().map_err(|e| format!("Failed to open the clipboard: {}", e))?),
+    };
+
+    match html {
+        Some(html) => clipboard.set_html(html, Some(text)),
+        None => clipboard.set_text(text),
     }
+    .map_err(|e| format!("Failed to write to the clipboard: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+pub fn write_formats_to_clipboard(_text: &str, _html: Option<&str>, _rtf: Option<&str>) -> Result<(), String> {
+    Err("Rich clipboard formats are not supported on Windows yet".to_string())
+}
+
+/// Read a string for the given pasteboard type, if present
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+unsafe fn pasteboard_string(pasteboard: id, uti: &str) -> Option<String> {
+    let ns_type = NSString::alloc(nil).init_str(uti);
+    let ns_string: id = msg_send![pasteboard, stringForType: ns_type];
+    if ns_string == nil {
+        return None;
+    }
+    let ptr: *const i8 = msg_send![ns_string, UTF8String];
+    if ptr.is_null() {
+        return None;
+    }
+    Some(std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned())
+}
+
+/// Convert the pasteboard's RTF payload (if any) to HTML via NSAttributedString
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+unsafe fn pasteboard_rtf_as_html(pasteboard: id) -> Option<String> {
+    use cocoa::foundation::NSRange;
+
+    let rtf_type = NSString::alloc(nil).init_str("public.rtf");
+    let rtf_data: id = msg_send![pasteboard, dataForType: rtf_type];
+    if rtf_data == nil {
+        return None;
+    }
+
+    let attributed: id = msg_send![class!(NSAttributedString), alloc];
+    let attributed: id = msg_send![attributed, initWithRTF:rtf_data documentAttributes:nil];
+    if attributed == nil {
+        return None;
+    }
+
+    let length: u64 = msg_send![attributed, length];
+    let range = NSRange::new(0, length);
+    // NSDocumentTypeDocumentAttribute => NSHTMLTextDocumentType
+    let key = NSString::alloc(nil).init_str("DocumentType");
+    let value = NSString::alloc(nil).init_str("NSHTML");
+    let attributes: id = msg_send![class!(NSDictionary), dictionaryWithObject:value forKey:key];
+    let html_data: id = msg_send![attributed, dataFromRange:range documentAttributes:attributes error:nil];
+    let _: () = msg_send![attributed, release];
+    if html_data == nil {
+        return None;
+    }
+
+    let len: usize = msg_send![html_data, length];
+    let bytes: *const u8 = msg_send![html_data, bytes];
+    if bytes.is_null() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(std::slice::from_raw_parts(bytes, len)).into_owned())
+}
+
+/// Read text, HTML (or RTF converted to HTML) and image availability from the macOS clipboard
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+pub fn read_clipboard_rich() -> Result<RichClipboardContent, String> {
+    unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+
+        let text = pasteboard_string(pasteboard, "public.utf8-plain-text");
+        let mut html = pasteboard_string(pasteboard, "public.html");
+        let mut html_from_rtf = false;
+        if html.is_none() {
+            html = pasteboard_rtf_as_html(pasteboard);
+            html_from_rtf = html.is_some();
+        }
+
+        // Ordered by preference; the first type present wins
+        let image_types = [
+            ("public.png", "image/png"),
+            ("public.jpeg", "image/jpeg"),
+            ("com.compuserve.gif", "image/gif"),
+            ("public.webp", "image/webp"),
+            ("public.tiff", "image/tiff"),
+        ];
+        let ns_types: Vec<id> = image_types
+            .iter()
+            .map(|(uti, _)| NSString::alloc(nil).init_str(uti))
+            .collect();
+        let types_array = NSArray::arrayWithObjects(nil, &ns_types);
+        let available: id = msg_send![pasteboard, availableTypeFromArray: types_array];
+        let image_mime_type = if available == nil {
+            None
+        } else {
+            let ptr: *const i8 = msg_send![available, UTF8String];
+            let uti = std::ffi::CStr::from_ptr(ptr).to_string_lossy();
+            image_types
+                .iter()
+                .find(|(candidate, _)| *candidate == uti)
+                .map(|(_, mime)| mime.to_string())
+        };
+
+        Ok(RichClipboardContent {
+            text,
+            html,
+            html_from_rtf,
+            rtf: pasteboard_string(pasteboard, "public.rtf"),
+            has_image: image_mime_type.is_some(),
+            image_mime_type,
+        })
+    }
+}
+
+/// Read the clipboard via xclip, which exposes each target separately
+#[cfg(target_os = "linux")]
+pub fn read_clipboard_rich() -> Result<RichClipboardContent, String> {
+    fn xclip_read(target: &str) -> Option<Vec<u8>> {
+        let output = std::process::Command::new("xclip")
+            .args(["-selection", "clipboard", "-o", "-t", target])
+            .output()
+            .ok()?;
+        if output.status.success() && !output.stdout.is_empty() {
+            Some(output.stdout)
+        } else {
+            None
+        }
+    }
+
+    let targets = xclip_read("TARGETS")
+        .map(|t| String::from_utf8_lossy(&t).lines().map(|l| l.trim().to_string()).collect::<Vec<_>>())
+        .ok_or_else(|| "Failed to read clipboard targets (is xclip installed?)".to_string())?;
+    let has_target = |name: &str| targets.iter().any(|t| t == name);
+
+    let text = ["UTF8_STRING", "text/plain;charset=utf-8", "text/plain", "STRING"]
+        .iter()
+        .find(|t| has_target(t))
+        .and_then(|t| xclip_read(t))
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+    let html = if has_target("text/html") {
+        xclip_read("text/html").map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+    } else {
+        None
+    };
+    let rtf = ["text/rtf", "application/rtf"]
+        .iter()
+        .find(|t| has_target(t))
+        .and_then(|t| xclip_read(t))
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+    let image_mime_type = ["image/png", "image/jpeg", "image/gif", "image/webp", "image/bmp"]
+        .iter()
+        .find(|t| has_target(t))
+        .map(|t| t.to_string());
+
+    Ok(RichClipboardContent {
+        text,
+        html,
+        html_from_rtf: false,
+        rtf,
+        has_image: image_mime_type.is_some(),
+        image_mime_type,
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_clipboard_rich() -> Result<RichClipboardContent, String> {
+    Err("Rich clipboard reading is not supported on Windows yet".to_string())
 }
 
 #[cfg(not(target_os = "macos"))]
@@ -289,15 +507,13 @@ fn extension_from_language(language: &str) -> &str {
 #[cfg(target_os = "macos")]
 #[allow(deprecated)]
 pub fn copy_file_to_clipboard(content: &str, language: &str) -> Result<String, String> {
-    let temp_dir = get_clipboard_temp_dir()?;
-    cleanup_old_temp_files(&temp_dir);
+    let temp_dir = crate::temp_files::dir()?;
 
     let ext = extension_from_language(language);
     let filename = format!("wingman_{}.{}", chrono::Utc::now().timestamp_millis(), ext);
     let file_path = temp_dir.join(&filename);
 
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    crate::temp_files::write_private(&file_path, content)?;
 
     let file_path_str = file_path.to_string_lossy().to_string();
 
@@ -324,20 +540,19 @@ pub fn copy_file_to_clipboard(content: &str, language: &str) -> Result<String, S
         let _: bool = msg_send![pasteboard, setString:ns_url forType:url_type2];
     }
 
+    crate::temp_files::track_pasteboard_files(&[file_path]);
     Ok(file_path_str)
 }
 
 #[cfg(target_os = "linux")]
 pub fn copy_file_to_clipboard(content: &str, language: &str) -> Result<String, String> {
-    let temp_dir = get_clipboard_temp_dir()?;
-    cleanup_old_temp_files(&temp_dir);
+    let temp_dir = crate::temp_files::dir()?;
 
     let ext = extension_from_language(language);
     let filename = format!("wingman_{}.{}", chrono::Utc::now().timestamp_millis(), ext);
     let file_path = temp_dir.join(&filename);
 
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    crate::temp_files::write_private(&file_path, content)?;
 
     let file_path_str = file_path.to_string_lossy().to_string();
     let uri = format!("file://{}\n", file_path_str);
@@ -377,15 +592,13 @@ pub fn copy_file_to_clipboard(content: &str, language: &str) -> Result<String, S
 
 #[cfg(target_os = "windows")]
 pub fn copy_file_to_clipboard(content: &str, language: &str) -> Result<String, String> {
-    let temp_dir = get_clipboard_temp_dir()?;
-    cleanup_old_temp_files(&temp_dir);
+    let temp_dir = crate::temp_files::dir()?;
 
     let ext = extension_from_language(language);
     let filename = format!("wingman_{}.{}", chrono::Utc::now().timestamp_millis(), ext);
     let file_path = temp_dir.join(&filename);
 
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    crate::temp_files::write_private(&file_path, content)?;
 
     let file_path_str = file_path.to_string_lossy().to_string();
 
@@ -400,7 +613,10 @@ pub fn copy_file_to_clipboard(content: &str, language: &str) -> Result<String, S
         .output();
 
     match result {
-        Ok(output) if output.status.success() => Ok(file_path_str),
+        Ok(output) if output.status.success() => {
+            crate::temp_files::track_pasteboard_files(&[file_path]);
+            Ok(file_path_str)
+        }
         Ok(output) => Err(format!("PowerShell error: {}", String::from_utf8_lossy(&output.stderr))),
         Err(e) => Err(format!("Failed to run PowerShell: {}", e)),
     }
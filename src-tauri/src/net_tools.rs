@@ -0,0 +1,364 @@
+// Network inspection helpers for pasted addresses and hostnames: classify an IP, expand
+// a CIDR block, and look up DNS records. Lookups go through the system resolver
+// configuration (resolv.conf, or the adapter settings on Windows) and the hosts file;
+// no third-party lookup service is involved.
+//
+// dns_lookup is turned away in offline mode like other network commands; analyze_ip
+// still works offline but skips the reverse lookup.
+
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::proto::rr::RecordType;
+use hickory_resolver::TokioAsyncResolver;
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::error::{AppError, ErrorCode};
+
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+/// expand_cidr lists at most this many addresses
+const MAX_LISTED: u128 = 1024;
+/// Size of the IPv6 address space, which doesn't fit in a u128
+const ALL_IPV6: &str = "340282366920938463463374607431768211456";
+const RECORD_TYPES: [&str; 10] = ["A", "AAAA", "CNAME", "MX", "TXT", "NS", "SOA", "SRV", "CAA", "PTR"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IpAnalysis {
+    /// Canonical form (IPv6 compressed)
+    pub address: String,
+    pub version: u8,
+    /// "public", "private", "loopback", "link_local", "shared" (carrier-grade NAT),
+    /// "multicast", "broadcast", "documentation", "unspecified" or "reserved"
+    pub scope: String,
+    pub is_public: bool,
+    /// The IPv4 address inside an IPv4-mapped IPv6 address
+    pub ipv4_mapped: Option<String>,
+    /// Name queried for reverse DNS, e.g. "1.0.0.127.in-addr.arpa"
+    pub reverse_pointer: String,
+    /// None when there's no PTR record, the lookup failed, or offline mode is on
+    pub reverse_dns: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CidrInfo {
+    pub version: u8,
+    pub prefix_len: u8,
+    pub network: String,
+    /// IPv4 only
+    pub broadcast: Option<String>,
+    pub netmask: String,
+    /// Inverse of the netmask, as ACLs want it
+    pub wildcard: String,
+    pub first_host: String,
+    pub last_host: String,
+    /// Counts are strings because an IPv6 block can exceed what JSON numbers hold
+    pub total_addresses: String,
+    pub usable_hosts: String,
+    /// The input had bits set past the prefix; `network` is the block it belongs to
+    pub host_bits_set: bool,
+    /// From the start of the block, up to MAX_LISTED
+    pub addresses: Vec<String>,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: String,
+    pub ttl: u32,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsLookupResult {
+    pub host: String,
+    pub record_type: String,
+    /// Empty when the name exists without records of this type, or doesn't exist
+    pub records: Vec<DnsRecord>,
+    pub duration_ms: u64,
+}
+
+/// Accepts bare addresses plus the "[::1]" and "host:port" forms people paste
+fn parse_ip(text: &str) -> Result<IpAddr, AppError> {
+    let text = text.trim();
+    text.parse::<IpAddr>()
+        .or_else(|_| text.trim_start_matches('[').trim_end_matches(']').parse())
+        .or_else(|_| text.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .map_err(|_| AppError::invalid_input(format!("\"{}\" is not an IP address", text)))
+}
+
+fn ipv4_scope(ip: Ipv4Addr) -> &'static str {
+    let [a, b, c, _] = ip.octets();
+    match (a, b, c) {
+        _ if ip.is_unspecified() => "unspecified",
+        _ if ip.is_broadcast() => "broadcast",
+        (127, _, _) => "loopback",
+        (10, _, _) | (192, 168, _) => "private",
+        (172, 16..=31, _) => "private",
+        (100, 64..=127, _) => "shared",
+        (169, 254, _) => "link_local",
+        (192, 0, 2) | (198, 51, 100) | (203, 0, 113) => "documentation",
+        (224..=239, _, _) => "multicast",
+        (0, _, _) | (240..=255, _, _) => "reserved",
+        _ => "public",
+    }
+}
+
+fn ipv6_scope(ip: Ipv6Addr) -> &'static str {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return ipv4_scope(v4);
+    }
+    match ip.segments()[0] {
+        _ if ip.is_unspecified() => "unspecified",
+        _ if ip.is_loopback() => "loopback",
+        0xfc00..=0xfdff => "private",
+        0xfe80..=0xfebf => "link_local",
+        0xff00..=0xffff => "multicast",
+        0x2001 if ip.segments()[1] == 0x0db8 => "documentation",
+        0x2000..=0x3fff => "public",
+        _ => "reserved",
+    }
+}
+
+fn reverse_pointer(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        IpAddr::V6(v6) => {
+            let nibbles: Vec<String> = v6
+                .octets()
+                .iter()
+                .rev()
+                .flat_map(|byte| [byte & 0x0f, byte >> 4])
+                .map(|nibble| format!("{:x}", nibble))
+                .collect();
+            format!("{}.ip6.arpa", nibbles.join("."))
+        }
+    }
+}
+
+fn classify(ip: IpAddr) -> IpAnalysis {
+    let (version, scope, ipv4_mapped) = match ip {
+        IpAddr::V4(v4) => (4, ipv4_scope(v4), None),
+        IpAddr::V6(v6) => (6, ipv6_scope(v6), v6.to_ipv4_mapped().map(|v4| v4.to_string())),
+    };
+    IpAnalysis {
+        address: ip.to_string(),
+        version,
+        scope: scope.to_string(),
+        is_public: scope == "public",
+        ipv4_mapped,
+        reverse_pointer: reverse_pointer(ip),
+        reverse_dns: None,
+    }
+}
+
+fn to_ip(value: u128, version: u8) -> IpAddr {
+    if version == 4 {
+        IpAddr::V4(Ipv4Addr::from(value as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(value))
+    }
+}
+
+fn cidr_info(cidr: &str) -> Result<CidrInfo, AppError> {
+    let cidr = cidr.trim();
+    let (address, prefix) = cidr.split_once('/').unwrap_or((cidr, ""));
+    let ip = parse_ip(address)?;
+    let (version, bits, value) = match ip {
+        IpAddr::V4(v4) => (4u8, 32u32, u32::from(v4) as u128),
+        IpAddr::V6(v6) => (6u8, 128u32, u128::from(v6)),
+    };
+    let prefix_len = if prefix.is_empty() {
+        bits
+    } else {
+        prefix
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|len| *len <= bits)
+            .ok_or_else(|| AppError::invalid_input(format!("Prefix length must be 0-{}", bits)))?
+    };
+
+    // Masks within the address width; shifting by the full width isn't allowed
+    let all = if bits == 128 { u128::MAX } else { (1u128 << bits) - 1 };
+    let host_mask = if prefix_len == 0 { all } else { (1u128 << (bits - prefix_len)) - 1 };
+    let netmask = all & !host_mask;
+    let network = value & netmask;
+    let last = network | host_mask;
+    let total = host_mask.checked_add(1);
+
+    // IPv4 blocks lose the network and broadcast addresses, except /31 and /32
+    let (first_host, last_host, usable) = if version == 4 && prefix_len < 31 {
+        (network + 1, last - 1, total.map(|total| total - 2))
+    } else {
+        (network, last, total)
+    };
+
+    let listed = total.unwrap_or(u128::MAX).min(MAX_LISTED);
+    Ok(CidrInfo {
+        version,
+        prefix_len: prefix_len as u8,
+        network: to_ip(network, version).to_string(),
+        broadcast: (version == 4).then(|| to_ip(last, version).to_string()),
+        netmask: to_ip(netmask, version).to_string(),
+        wildcard: to_ip(host_mask, version).to_string(),
+        first_host: to_ip(first_host, version).to_string(),
+        last_host: to_ip(last_host, version).to_string(),
+        // Only a /0 IPv6 block overflows
+        total_addresses: total.map_or_else(|| ALL_IPV6.to_string(), |n| n.to_string()),
+        usable_hosts: usable.map_or_else(|| ALL_IPV6.to_string(), |n| n.to_string()),
+        host_bits_set: value != network,
+        addresses: (0..listed).map(|i| to_ip(network + i, version).to_string()).collect(),
+        truncated: total.map_or(true, |total| total > MAX_LISTED),
+    })
+}
+
+fn resolver() -> Result<TokioAsyncResolver, AppError> {
+    let (config, mut options) = hickory_resolver::system_conf::read_system_conf()
+        .map_err(|e| AppError::new(ErrorCode::Network, format!("Can't read the system DNS settings: {}", e)))?;
+    options.timeout = LOOKUP_TIMEOUT;
+    options.attempts = 1;
+    Ok(TokioAsyncResolver::tokio(config, options))
+}
+
+async fn reverse_dns(ip: IpAddr) -> Option<String> {
+    let lookup = tokio::time::timeout(LOOKUP_TIMEOUT, resolver().ok()?.reverse_lookup(ip))
+        .await
+        .ok()?
+        .ok()?;
+    let name = lookup.iter().next()?.to_string();
+    Some(name.trim_end_matches('.').to_string())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// IP version, scope (private/public/...) and reverse DNS of an address
+#[tauri::command]
+pub async fn analyze_ip(text: String) -> Result<IpAnalysis, AppError> {
+    let ip = parse_ip(&text)?;
+    let mut analysis = classify(ip);
+    if !crate::offline::is_enabled() {
+        analysis.reverse_dns = reverse_dns(ip).await;
+    }
+    Ok(analysis)
+}
+
+/// Network, netmask, host range and size of a CIDR block; a bare address is a /32 or /128
+#[tauri::command]
+pub fn expand_cidr(cidr: String) -> Result<CidrInfo, AppError> {
+    cidr_info(&cidr)
+}
+
+/// Look up `record_type` records (A when omitted) for a host. PTR accepts an IP address.
+#[tauri::command]
+pub async fn dns_lookup(host: String, record_type: Option<String>) -> Result<DnsLookupResult, AppError> {
+    let host = host.trim().trim_end_matches('.').to_string();
+    if host.is_empty() {
+        return Err(AppError::invalid_input("Enter a hostname"));
+    }
+    let record_type = record_type
+        .map(|t| t.trim().to_uppercase())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "A".to_string());
+    if !RECORD_TYPES.contains(&record_type.as_str()) {
+        return Err(AppError::invalid_input(format!(
+            "Unsupported record type {}; use one of {}",
+            record_type,
+            RECORD_TYPES.join(", ")
+        )));
+    }
+    let query_type = RecordType::from_str(&record_type).map_err(|e| AppError::invalid_input(e.to_string()))?;
+    // PTR lookups are usually asked for by address
+    let name = match (query_type, host.parse::<IpAddr>()) {
+        (RecordType::PTR, Ok(ip)) => reverse_pointer(ip),
+        _ => host.clone(),
+    };
+
+    let resolver = resolver()?;
+    let started = Instant::now();
+    let result = tokio::time::timeout(LOOKUP_TIMEOUT, resolver.lookup(name.as_str(), query_type))
+        .await
+        .map_err(|_| AppError::new(ErrorCode::Network, format!("DNS lookup for {} timed out", host)))?;
+
+    let records = match result {
+        Ok(lookup) => lookup
+            .record_iter()
+            .filter_map(|record| {
+                Some(DnsRecord {
+                    name: record.name().to_string().trim_end_matches('.').to_string(),
+                    record_type: record.record_type().to_string(),
+                    ttl: record.ttl(),
+                    value: record.data()?.to_string(),
+                })
+            })
+            .collect(),
+        Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Vec::new(),
+        Err(e) => {
+            return Err(AppError::new(
+                ErrorCode::Network,
+                format!("DNS lookup for {} failed: {}", host, e),
+            ))
+        }
+    };
+
+    Ok(DnsLookupResult {
+        host,
+        record_type,
+        records,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let scope = |text: &str| classify(parse_ip(text).unwrap()).scope;
+        assert_eq!(scope("10.1.2.3"), "private");
+        assert_eq!(scope("172.31.255.1"), "private");
+        assert_eq!(scope("100.100.0.1"), "shared");
+        assert_eq!(scope("8.8.8.8"), "public");
+        assert_eq!(scope("[::1]"), "loopback");
+        assert_eq!(scope("fd12::1"), "private");
+        assert_eq!(scope("2001:db8::1"), "documentation");
+        assert_eq!(scope("[2606:4700::1111]:443"), "public");
+
+        let mapped = classify(parse_ip("::ffff:192.168.1.1").unwrap());
+        assert_eq!(mapped.ipv4_mapped.as_deref(), Some("192.168.1.1"));
+        assert_eq!(mapped.scope, "private");
+        assert_eq!(reverse_pointer(parse_ip("127.0.0.1").unwrap()), "1.0.0.127.in-addr.arpa");
+        assert!(reverse_pointer(parse_ip("2001:db8::1").unwrap()).starts_with("1.0.0.0.0.0.0.0"));
+    }
+
+    #[test]
+    fn test_cidr_info() {
+        let info = cidr_info("192.168.10.77/26").unwrap();
+        assert_eq!(info.network, "192.168.10.64");
+        assert_eq!(info.broadcast.as_deref(), Some("192.168.10.127"));
+        assert_eq!(info.netmask, "255.255.255.192");
+        assert_eq!(info.wildcard, "0.0.0.63");
+        assert_eq!((info.first_host.as_str(), info.last_host.as_str()), ("192.168.10.65", "192.168.10.126"));
+        assert_eq!((info.total_addresses.as_str(), info.usable_hosts.as_str()), ("64", "62"));
+        assert!(info.host_bits_set && !info.truncated);
+        assert_eq!(info.addresses.len(), 64);
+
+        let point_to_point = cidr_info("10.0.0.0/31").unwrap();
+        assert_eq!(point_to_point.usable_hosts, "2");
+
+        let v6 = cidr_info("2001:db8::/32").unwrap();
+        assert_eq!(v6.last_host, "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff");
+        assert_eq!(v6.total_addresses, "79228162514264337593543950336");
+        assert!(v6.truncated && v6.broadcast.is_none());
+        assert_eq!(cidr_info("::/0").unwrap().total_addresses, ALL_IPV6);
+
+        assert!(cidr_info("10.0.0.0/33").is_err());
+    }
+}
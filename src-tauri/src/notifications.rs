@@ -0,0 +1,382 @@
+// Native system notifications, shown even while the panel is hidden. Long operations
+// (formatting, AI calls, gist sync, export) report completion or failure here, and
+// reminders fire through here too. Each category can be switched off in settings.
+//
+// Clicking a notification opens the panel and emits `notification-clicked` with the
+// notification, so the frontend can route to the entry or view it came from. That
+// needs a way back from the notification: NSUserNotificationCenter's delegate on
+// macOS and notify-send's `--wait` on Linux. Windows toasts are shown without it,
+// since activation needs a COM activator; showing them at all needs an app id, which
+// unpackaged builds register under HKCU on first use.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::storage::{load_settings, NotificationSettings};
+
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+const APP_NAME: &str = "Wingman";
+
+/// Operations quicker than this finish while the user is still watching, so they
+/// don't get a notification
+const LONG_OPERATION: Duration = Duration::from_secs(2);
+
+/// Notifications kept for click-through; older ones just open the panel
+const MAX_PENDING: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    Formatting,
+    Ai,
+    GistSync,
+    Export,
+    Reminder,
+}
+
+impl NotificationCategory {
+    fn enabled(self, settings: &NotificationSettings) -> bool {
+        match self {
+            Self::Formatting => settings.formatting,
+            Self::Ai => settings.ai,
+            Self::GistSync => settings.gist_sync,
+            Self::Export => settings.export,
+            Self::Reminder => settings.reminders,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub id: String,
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    /// History entry the notification is about, if any
+    pub entry_id: Option<i64>,
+}
+
+impl Notification {
+    pub fn new(category: NotificationCategory, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            category,
+            title: title.into(),
+            body: body.into(),
+            entry_id: None,
+        }
+    }
+
+    pub fn for_entry(mut self, entry_id: i64) -> Self {
+        self.entry_id = Some(entry_id);
+        self
+    }
+}
+
+struct Pending {
+    app: AppHandle,
+    notification: Notification,
+}
+
+/// Shown notifications awaiting a click, oldest first
+static PENDING: Mutex<Vec<Pending>> = Mutex::new(Vec::new());
+
+fn remember(app: &AppHandle, notification: &Notification) {
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    if pending.len() >= MAX_PENDING {
+        pending.remove(0);
+    }
+    pending.push(Pending {
+        app: app.clone(),
+        notification: notification.clone(),
+    });
+}
+
+/// Open the panel for a clicked notification
+fn clicked(id: &str) {
+    let pending = {
+        let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        match pending.iter().position(|p| p.notification.id == id) {
+            Some(index) => pending.remove(index),
+            None => return,
+        }
+    };
+    let app = pending.app.clone();
+    pending
+        .app
+        .run_on_main_thread(move || {
+            crate::tray::show_panel(&app, None);
+            app.emit("notification-clicked", &pending.notification).ok();
+        })
+        .ok();
+}
+
+fn panel_visible(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false)
+}
+
+/// Show a notification if its category is switched on. Failures are only logged
+/// since a missing notification shouldn't fail the operation it reports on.
+pub fn send(app: &AppHandle, notification: Notification) {
+    let settings = load_settings().unwrap_or_default().notifications;
+    if !notification.category.enabled(&settings) {
+        return;
+    }
+    remember(app, &notification);
+    platform::show(app, notification);
+}
+
+/// Report the outcome of an operation that started at `started`. Only operations
+/// that took a while are reported, and only while the panel is hidden unless the
+/// user asked for notifications regardless.
+pub fn report<T, E: std::fmt::Display>(
+    app: &AppHandle,
+    category: NotificationCategory,
+    operation: &str,
+    started: Instant,
+    result: &Result<T, E>,
+) {
+    if started.elapsed() < LONG_OPERATION {
+        return;
+    }
+    let settings = load_settings().unwrap_or_default().notifications;
+    if settings.only_when_hidden && panel_visible(app) {
+        return;
+    }
+    send(app, outcome(category, operation, started.elapsed(), result));
+}
+
+fn outcome<T, E: std::fmt::Display>(
+    category: NotificationCategory,
+    operation: &str,
+    elapsed: Duration,
+    result: &Result<T, E>,
+) -> Notification {
+    match result {
+        Ok(_) => Notification::new(
+            category,
+            format!("{} finished", operation),
+            format!("Done in {:.1}s", elapsed.as_secs_f32()),
+        ),
+        Err(e) => Notification::new(category, format!("{} failed", operation), e.to_string()),
+    }
+}
+
+/// Escape text for the toast XML on Windows
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Notification;
+    use cocoa::base::{id, nil, BOOL, YES};
+    use cocoa::foundation::NSString;
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Class, Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::sync::Once;
+    use tauri::AppHandle;
+
+    const DELEGATE_CLASS: &str = "WingmanNotificationDelegate";
+
+    extern "C" fn did_activate(_this: &Object, _cmd: Sel, _center: id, notification: id) {
+        unsafe {
+            let identifier: id = msg_send![notification, identifier];
+            if identifier == nil {
+                return;
+            }
+            let utf8: *const std::os::raw::c_char = msg_send![identifier, UTF8String];
+            if !utf8.is_null() {
+                super::clicked(&std::ffi::CStr::from_ptr(utf8).to_string_lossy());
+            }
+        }
+    }
+
+    /// Show notifications even when Wingman is the active app
+    extern "C" fn should_present(_this: &Object, _cmd: Sel, _center: id, _notification: id) -> BOOL {
+        YES
+    }
+
+    fn delegate_class() -> Option<&'static Class> {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(|| {
+            if let Some(mut decl) = ClassDecl::new(DELEGATE_CLASS, class!(NSObject)) {
+                unsafe {
+                    decl.add_method(
+                        sel!(userNotificationCenter:didActivateNotification:),
+                        did_activate as extern "C" fn(&Object, Sel, id, id),
+                    );
+                    decl.add_method(
+                        sel!(userNotificationCenter:shouldPresentNotification:),
+                        should_present as extern "C" fn(&Object, Sel, id, id) -> BOOL,
+                    );
+                }
+                decl.register();
+            }
+        });
+        Class::get(DELEGATE_CLASS)
+    }
+
+    /// NSUserNotificationCenter must be used from the main thread
+    #[allow(deprecated)]
+    pub fn show(app: &AppHandle, notification: Notification) {
+        let result = app.run_on_main_thread(move || unsafe {
+            let center: id = msg_send![class!(NSUserNotificationCenter), defaultUserNotificationCenter];
+            if center == nil {
+                log::warn!("Notification center is unavailable");
+                return;
+            }
+            let delegate: id = msg_send![center, delegate];
+            if delegate == nil {
+                if let Some(class) = delegate_class() {
+                    // The center holds its delegate weakly; this one lives for the whole run
+                    let delegate: id = msg_send![class, new];
+                    let _: () = msg_send![center, setDelegate: delegate];
+                }
+            }
+
+            let ns_notification: id = msg_send![class!(NSUserNotification), new];
+            let _: () = msg_send![ns_notification, setIdentifier: NSString::alloc(nil).init_str(&notification.id)];
+            let _: () = msg_send![ns_notification, setTitle: NSString::alloc(nil).init_str(&notification.title)];
+            let _: () = msg_send![ns_notification, setInformativeText: NSString::alloc(nil).init_str(&notification.body)];
+            let _: () = msg_send![center, deliverNotification: ns_notification];
+        });
+        if let Err(e) = result {
+            log::warn!("Couldn't show notification: {}", e);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Notification, APP_NAME};
+    use std::process::Command;
+    use tauri::AppHandle;
+
+    /// Action key notify-send prints when the notification body is clicked
+    const DEFAULT_ACTION: &str = "default";
+
+    pub fn show(_app: &AppHandle, notification: Notification) {
+        // `--wait` keeps notify-send running until the notification is dismissed
+        // or clicked, so it gets its own thread
+        std::thread::spawn(move || {
+            let output = Command::new("notify-send")
+                .arg(format!("--app-name={}", APP_NAME))
+                .arg(format!("--action={}=Open {}", DEFAULT_ACTION, APP_NAME))
+                .arg("--wait")
+                .arg(&notification.title)
+                .arg(&notification.body)
+                .output();
+            match output {
+                Ok(output) if output.status.success() => {
+                    if String::from_utf8_lossy(&output.stdout).trim() == DEFAULT_ACTION {
+                        super::clicked(&notification.id);
+                    }
+                }
+                Ok(output) => log::warn!(
+                    "Notification \"{}\" failed: {}",
+                    notification.title,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+                Err(e) => log::warn!("Couldn't show notification \"{}\": {}", notification.title, e),
+            }
+        });
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{xml_escape, Notification, APP_NAME};
+    use std::process::Command;
+    use std::sync::Once;
+    use tauri::AppHandle;
+
+    /// Toasts from an app id Windows doesn't know are dropped silently. Installers
+    /// register it with the Start menu shortcut; this covers unpackaged builds.
+    fn register_app_id(app_id: &str) {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(|| {
+            let key = format!("HKCU\\Software\\Classes\\AppUserModelId\\{}", app_id);
+            let result = Command::new("reg")
+                .args(["add", &key, "/v", "DisplayName", "/t", "REG_SZ", "/d", APP_NAME, "/f"])
+                .output();
+            match result {
+                Ok(output) if output.status.success() => {}
+                Ok(output) => log::warn!(
+                    "Couldn't register the notification app id: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+                Err(e) => log::warn!("Couldn't register the notification app id: {}", e),
+            }
+        });
+    }
+
+    pub fn show(app: &AppHandle, notification: Notification) {
+        let app_id = app.config().identifier.clone();
+        // The toast XML is passed through an environment variable so nothing in the
+        // title or body is ever interpreted by PowerShell
+        let xml = format!(
+            "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual></toast>",
+            xml_escape(&notification.title),
+            xml_escape(&notification.body)
+        );
+        let script = format!(
+            "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null; \
+             [Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime] | Out-Null; \
+             $xml = New-Object Windows.Data.Xml.Dom.XmlDocument; \
+             $xml.LoadXml($env:WINGMAN_TOAST); \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:WINGMAN_APP_ID).Show([Windows.UI.Notifications.ToastNotification]::new($xml))";
+        std::thread::spawn(move || {
+            register_app_id(&app_id);
+            let result = Command::new("powershell")
+                .env("WINGMAN_TOAST", xml)
+                .env("WINGMAN_APP_ID", &app_id)
+                .args(["-NoProfile", "-NonInteractive", "-WindowStyle", "Hidden", "-Command"])
+                .arg(script)
+                .output();
+            if let Err(e) = result {
+                log::warn!("Couldn't show notification \"{}\": {}", notification.title, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_and_categories() {
+        let ok: Result<(), String> = Ok(());
+        let done = outcome(NotificationCategory::Formatting, "Formatting", Duration::from_millis(3400), &ok);
+        assert_eq!(done.title, "Formatting finished");
+        assert_eq!(done.body, "Done in 3.4s");
+
+        let failed: Result<(), String> = Err("rustfmt not found".to_string());
+        let done = outcome(NotificationCategory::Formatting, "Formatting", Duration::from_secs(3), &failed);
+        assert_eq!(done.title, "Formatting failed");
+        assert_eq!(done.body, "rustfmt not found");
+
+        let settings = NotificationSettings {
+            ai: false,
+            ..NotificationSettings::default()
+        };
+        assert!(!NotificationCategory::Ai.enabled(&settings));
+        assert!(NotificationCategory::GistSync.enabled(&settings));
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(xml_escape("<a & 'b'>"), "&lt;a &amp; &apos;b&apos;&gt;");
+    }
+}
@@ -0,0 +1,108 @@
+// Offline mode: an explicit switch for air-gapped machines, so nothing tries the network
+// and then times out. While it's on:
+//
+// - Commands that only make sense online (AI, gists, license activation, updates, paste
+//   services, ...) are turned away by the command middleware with ErrorCode::Offline.
+// - The HTTP client factory refuses to build clients, which covers everything else that
+//   would touch the network, such as rule webhooks and the AI step of a workflow.
+// - License refreshes answer from the cache and the background updater doesn't check.
+//
+// LAN sync, the local API and the event bridge stay available: they never leave the
+// machine or the local network.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::http_client;
+use crate::license::SUPABASE_URL;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Commands that can't do anything useful offline
+const NETWORK_COMMANDS: [&str; 28] = [
+    // License and subscription
+    "activate_license",
+    "deactivate_license",
+    "validate_premium_license_cmd",
+    "get_ai_usage_cmd",
+    "create_customer_portal_session_cmd",
+    // AI, translation and grammar
+    "call_ai_feature_cmd",
+    "rerun_ai_interaction",
+    "translate_text",
+    "check_text",
+    // GitHub
+    "start_github_device_flow",
+    "poll_github_device_flow",
+    "check_github_auth_status",
+    "create_github_gist",
+    "list_wingman_gists",
+    "update_github_gist",
+    "delete_github_gist",
+    "sync_all_gist_snippets",
+    "resolve_gist_conflict",
+    // Paste services and link previews
+    "publish_paste",
+    "fetch_url_metadata",
+    "url_to_markdown_link",
+    // Snippet sources
+    "refresh_snippet_from_source",
+    // DNS
+    "dns_lookup",
+    // Updates
+    "check_for_app_updates",
+    "download_and_install_update",
+    "get_update_release_notes",
+    "resume_update_download",
+    "defer_update_until_quit",
+];
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    OFFLINE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Whether the middleware should turn `command` away
+pub fn blocks(command: &str) -> bool {
+    is_enabled() && NETWORK_COMMANDS.contains(&command)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Whether the license and AI backend is reachable through the configured proxy.
+/// Always false in offline mode, without probing.
+#[tauri::command]
+pub async fn is_online() -> Result<bool, AppError> {
+    if is_enabled() {
+        return Ok(false);
+    }
+    let client = http_client::builder()?.timeout(PROBE_TIMEOUT).build()?;
+    // Any HTTP answer, even an error status, means the network path works
+    Ok(client.head(SUPABASE_URL).send().await.is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_only_network_commands_when_offline() {
+        set_enabled(false);
+        assert!(!blocks("call_ai_feature_cmd"));
+
+        set_enabled(true);
+        assert!(blocks("call_ai_feature_cmd"));
+        assert!(blocks("create_github_gist"));
+        assert!(!blocks("get_history"));
+        assert!(!blocks("summarize_text"));
+        set_enabled(false);
+    }
+}
@@ -0,0 +1,123 @@
+// Guardrails for oversized clips. Pasting a 200MB log shouldn't put 200MB into a TEXT
+// column, so content over the configured limit is either truncated with a notice or
+// overflowed: the full text goes to the content-addressed attachment store and the
+// row keeps the first `max_entry_bytes` plus the file's hash in `content_hash`.
+// `get_entry` and `get_entry_content` read overflowed content back in full.
+
+use crate::attachments::{load_attachment, store_attachment, AttachmentError};
+use crate::storage::{HistoryLimits, OversizedPolicy};
+
+/// Never store less than this, whatever the setting says
+const MIN_ENTRY_BYTES: u64 = 4 * 1024;
+
+/// What goes into the history row for a piece of content
+#[derive(Debug, PartialEq)]
+pub struct StoredContent {
+    pub content: String,
+    /// Attachment hash of the full content, when it overflowed to a file
+    pub content_hash: Option<String>,
+}
+
+/// The longest prefix of `content` that fits in `max_bytes` without splitting a character
+fn prefix(content: &str, max_bytes: usize) -> &str {
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let mut end = max_bytes;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    &content[..end]
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn truncation_notice(total_bytes: usize, max_bytes: u64) -> String {
+    format!(
+        "\n\n[Truncated by Wingman: this {} clip is over the {} history limit]",
+        format_bytes(total_bytes as u64),
+        format_bytes(max_bytes)
+    )
+}
+
+/// Apply the size limit to content about to be stored
+pub fn prepare(content: &str, limits: &HistoryLimits) -> Result<StoredContent, AttachmentError> {
+    let max_bytes = limits.max_entry_bytes.max(MIN_ENTRY_BYTES);
+    if content.len() as u64 <= max_bytes {
+        return Ok(StoredContent {
+            content: content.to_string(),
+            content_hash: None,
+        });
+    }
+
+    let head = prefix(content, max_bytes as usize);
+    match limits.oversized {
+        OversizedPolicy::Truncate => {
+            log::info!("Truncated a {} history entry", format_bytes(content.len() as u64));
+            Ok(StoredContent {
+                content: format!("{}{}", head, truncation_notice(content.len(), max_bytes)),
+                content_hash: None,
+            })
+        }
+        OversizedPolicy::Overflow => Ok(StoredContent {
+            content: head.to_string(),
+            content_hash: Some(store_attachment(content.as_bytes())?),
+        }),
+    }
+}
+
+/// Full content of an overflowed entry
+pub fn load_overflow(hash: &str) -> Result<String, AttachmentError> {
+    String::from_utf8(load_attachment(hash)?)
+        .map_err(|_| AttachmentError::InvalidData(format!("overflowed content {} is not UTF-8", hash)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_respects_char_boundaries() {
+        assert_eq!(prefix("héllo", 2), "h");
+        assert_eq!(prefix("héllo", 3), "hé");
+        assert_eq!(prefix("hi", 10), "hi");
+    }
+
+    #[test]
+    fn test_truncate_policy() {
+        let limits = HistoryLimits {
+            max_entry_bytes: 0,
+            oversized: OversizedPolicy::Truncate,
+        };
+        let small = prepare("short", &limits).unwrap();
+        assert_eq!(small.content, "short");
+
+        let big = "x".repeat(10_000);
+        let stored = prepare(&big, &limits).unwrap();
+        assert!(stored.content_hash.is_none());
+        assert!(stored.content.starts_with(&"x".repeat(MIN_ENTRY_BYTES as usize)));
+        assert!(stored
+            .content
+            .ends_with("[Truncated by Wingman: this 9.8 KB clip is over the 4.0 KB history limit]"));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1024 * 1024), "1.0 MB");
+        assert_eq!(format_bytes(200 * 1024 * 1024), "200.0 MB");
+    }
+}
@@ -0,0 +1,129 @@
+// Show and hide animations for the panel. settings.panel_animation picks a fade or a
+// slide from the top for each direction; with reduce_motion on, or the system Reduce
+// Motion accessibility setting (see accessibility.rs), slides become fades. On macOS the
+// NSPanel itself is animated with NSAnimationContext (see window.rs). Other platforms
+// show and hide the window instantly and send the frontend a "panel-animation" event so
+// it can animate the page with CSS.
+
+use serde::Serialize;
+#[cfg(target_os = "macos")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Runtime};
+
+use crate::storage::{load_settings, PanelAnimation, PanelAnimationSettings};
+
+/// Longest animation accepted from settings
+const MAX_DURATION_MS: u32 = 1000;
+
+/// Bumped whenever the panel is shown, so a hide still waiting for its animation to
+/// finish doesn't hide the panel that was just brought back
+#[cfg(target_os = "macos")]
+static SHOW_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ResolvedAnimation {
+    pub style: PanelAnimation,
+    pub duration_ms: u32,
+}
+
+impl ResolvedAnimation {
+    #[cfg(target_os = "macos")]
+    pub fn duration(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.duration_ms as u64)
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.style == PanelAnimation::None || self.duration_ms == 0
+    }
+}
+
+/// The animation to actually run: slides become fades when motion should be reduced
+pub fn resolve(settings: &PanelAnimationSettings, style: PanelAnimation, system_reduce_motion: bool) -> ResolvedAnimation {
+    let style = match style {
+        PanelAnimation::SlideFromTop if settings.reduce_motion || system_reduce_motion => PanelAnimation::Fade,
+        style => style,
+    };
+    ResolvedAnimation {
+        style,
+        duration_ms: settings.duration_ms.min(MAX_DURATION_MS),
+    }
+}
+
+fn system_reduce_motion() -> bool {
+    crate::accessibility::prefs().reduce_motion
+}
+
+pub fn show_animation() -> ResolvedAnimation {
+    let settings = load_settings().unwrap_or_default().panel_animation;
+    resolve(&settings, settings.show, system_reduce_motion())
+}
+
+#[cfg(target_os = "macos")]
+pub fn hide_animation() -> ResolvedAnimation {
+    let settings = load_settings().unwrap_or_default().panel_animation;
+    resolve(&settings, settings.hide, system_reduce_motion())
+}
+
+/// Call when the panel is shown; cancels pending hides
+#[cfg(target_os = "macos")]
+pub fn begin_show() {
+    SHOW_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Run `hide` on the main thread once the hide animation has played, unless the panel
+/// was shown again in the meantime
+#[cfg(target_os = "macos")]
+pub fn hide_after<R: Runtime>(app: &AppHandle<R>, animation: ResolvedAnimation, hide: impl FnOnce() + Send + 'static) {
+    let generation = SHOW_GENERATION.load(Ordering::SeqCst);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(animation.duration());
+        let _ = app.run_on_main_thread(move || {
+            if SHOW_GENERATION.load(Ordering::SeqCst) == generation {
+                hide();
+            }
+        });
+    });
+}
+
+#[cfg(not(target_os = "macos"))]
+#[derive(Debug, Clone, Serialize)]
+struct AnimationEvent {
+    /// "show"
+    phase: &'static str,
+    #[serde(flatten)]
+    animation: ResolvedAnimation,
+}
+
+/// Tell the frontend to play the show animation in CSS, where the window itself can't be
+/// animated
+#[cfg(not(target_os = "macos"))]
+pub fn notify_shown<R: Runtime>(app: &AppHandle<R>) {
+    use tauri::Emitter;
+
+    let animation = show_animation();
+    if !animation.is_none() {
+        app.emit("panel-animation", AnimationEvent { phase: "show", animation }).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reduced_motion_turns_slides_into_fades() {
+        let mut settings = PanelAnimationSettings {
+            duration_ms: 5000,
+            ..Default::default()
+        };
+        let slide = resolve(&settings, PanelAnimation::SlideFromTop, false);
+        assert_eq!(slide.style, PanelAnimation::SlideFromTop);
+        assert_eq!(slide.duration_ms, MAX_DURATION_MS);
+        assert_eq!(resolve(&settings, PanelAnimation::SlideFromTop, true).style, PanelAnimation::Fade);
+
+        settings.reduce_motion = true;
+        assert_eq!(resolve(&settings, PanelAnimation::SlideFromTop, false).style, PanelAnimation::Fade);
+        assert!(resolve(&settings, PanelAnimation::None, false).is_none());
+    }
+}
@@ -0,0 +1,460 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::aes::Aes256;
+use aes_gcm::{AesGcm, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::AppError;
+use crate::history::set_entry_paste_info;
+use crate::http_client::{self, HttpClientError};
+use crate::storage::ensure_app_data_dir;
+use crate::AppState;
+
+const PASTE_RS_URL: &str = "https://paste.rs/";
+const ZERO_X0_URL: &str = "https://0x0.st";
+const USER_AGENT: &str = "Wingman-Desktop";
+
+// ============================================================================
+// Error Types
+// ============================================================================
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasteError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("{0}")]
+    HttpClient(#[from] HttpClientError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+
+    #[error("Unknown paste service: {0}")]
+    UnknownService(String),
+
+    #[error("PrivateBin is not configured. Set the instance URL in Settings.")]
+    PrivateBinNotConfigured,
+
+    #[error("Encryption failed")]
+    Encryption,
+
+    #[error("Paste service error: {0}")]
+    ApiError(String),
+}
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteService {
+    PasteRs,
+    ZeroX0,
+    PrivateBin,
+}
+
+impl PasteService {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PasteService::PasteRs => "paste_rs",
+            PasteService::ZeroX0 => "zero_x0",
+            PasteService::PrivateBin => "private_bin",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, PasteError> {
+        match value {
+            "paste_rs" => Ok(PasteService::PasteRs),
+            "zero_x0" => Ok(PasteService::ZeroX0),
+            "private_bin" => Ok(PasteService::PrivateBin),
+            other => Err(PasteError::UnknownService(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZeroX0Config {
+    /// Hours until the paste expires; `None` uses the server's size-based retention
+    pub expires_hours: Option<u32>,
+    /// Generate a hard-to-guess URL
+    pub secret: bool,
+}
+
+impl Default for ZeroX0Config {
+    fn default() -> Self {
+        Self {
+            expires_hours: Some(24),
+            secret: true, // Unlisted by default (safer)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateBinConfig {
+    /// Instance URL, e.g. https://privatebin.example.com/
+    pub url: Option<String>,
+    /// One of PrivateBin's expiry values: 5min, 10min, 1hour, 1day, 1week, 1month, 1year, never
+    pub expire: String,
+    pub burn_after_reading: bool,
+    pub open_discussion: bool,
+}
+
+impl Default for PrivateBinConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            expire: "1week".to_string(),
+            burn_after_reading: false,
+            open_discussion: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasteConfig {
+    pub default_service: PasteService,
+    #[serde(default)]
+    pub zero_x0: ZeroX0Config,
+    #[serde(default)]
+    pub private_bin: PrivateBinConfig,
+    #[serde(default = "default_auto_copy_url")]
+    pub auto_copy_url: bool,
+}
+
+fn default_auto_copy_url() -> bool {
+    true
+}
+
+impl Default for PasteConfig {
+    fn default() -> Self {
+        Self {
+            default_service: PasteService::PasteRs,
+            zero_x0: ZeroX0Config::default(),
+            private_bin: PrivateBinConfig::default(),
+            auto_copy_url: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasteResult {
+    pub service: PasteService,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PrivateBinResponse {
+    status: i32,
+    id: Option<String>,
+    message: Option<String>,
+}
+
+// ============================================================================
+// Config Storage
+// ============================================================================
+
+fn get_config_path() -> Result<PathBuf, PasteError> {
+    Ok(ensure_app_data_dir()?.join("paste_config.json"))
+}
+
+pub fn load_config() -> Result<PasteConfig, PasteError> {
+    let path = get_config_path()?;
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        Ok(PasteConfig::default())
+    }
+}
+
+pub fn save_config(config: &PasteConfig) -> Result<(), PasteError> {
+    let content = serde_json::to_string_pretty(config)?;
+    fs::write(get_config_path()?, content)?;
+    Ok(())
+}
+
+// ============================================================================
+// Services
+// ============================================================================
+
+fn paste_client() -> Result<reqwest::Client, PasteError> {
+    Ok(http_client::builder()?
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent(USER_AGENT)
+        .build()?)
+}
+
+async fn error_from_response(response: reqwest::Response) -> PasteError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    PasteError::ApiError(format!("{}: {}", status.as_u16(), body.trim()))
+}
+
+async fn publish_paste_rs(content: &str) -> Result<PasteResult, PasteError> {
+    let response = paste_client()?
+        .post(PASTE_RS_URL)
+        .body(content.to_string())
+        .send()
+        .await?;
+
+    // 206 means the paste was truncated to the size limit; still a usable URL
+    if !response.status().is_success() {
+        return Err(error_from_response(response).await);
+    }
+
+    Ok(PasteResult {
+        service: PasteService::PasteRs,
+        url: response.text().await?.trim().to_string(),
+    })
+}
+
+async fn publish_zero_x0(content: &str, config: &ZeroX0Config) -> Result<PasteResult, PasteError> {
+    let file = reqwest::multipart::Part::text(content.to_string()).file_name("paste.txt");
+    let mut form = reqwest::multipart::Form::new().part("file", file);
+    if let Some(hours) = config.expires_hours {
+        form = form.text("expires", hours.to_string());
+    }
+    if config.secret {
+        form = form.text("secret", "");
+    }
+
+    let response = paste_client()?.post(ZERO_X0_URL).multipart(form).send().await?;
+    if !response.status().is_success() {
+        return Err(error_from_response(response).await);
+    }
+
+    Ok(PasteResult {
+        service: PasteService::ZeroX0,
+        url: response.text().await?.trim().to_string(),
+    })
+}
+
+/// Encrypt a paste in PrivateBin's v2 format. Returns the request body and the
+/// key, which only ever travels in the URL fragment so the server can't read the paste.
+fn encrypt_private_bin(
+    content: &str,
+    formatter: &str,
+    config: &PrivateBinConfig,
+) -> Result<(serde_json::Value, [u8; 32]), PasteError> {
+    const ITERATIONS: u32 = 100_000;
+
+    let mut rng = rand::thread_rng();
+    let mut key = [0u8; 32];
+    let mut iv = [0u8; 16];
+    let mut salt = [0u8; 8];
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut iv);
+    rng.fill_bytes(&mut salt);
+
+    let mut derived = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(&key, &salt, ITERATIONS, &mut derived);
+
+    let adata = serde_json::json!([
+        [
+            STANDARD.encode(iv),
+            STANDARD.encode(salt),
+            ITERATIONS,
+            256,
+            128,
+            "aes",
+            "gcm",
+            "none"
+        ],
+        formatter,
+        config.open_discussion as u8,
+        config.burn_after_reading as u8
+    ]);
+    // The authenticated data is the exact JSON the client will re-serialize when decrypting
+    let aad = serde_json::to_string(&adata)?;
+    let plaintext = serde_json::to_string(&serde_json::json!({ "paste": content }))?;
+
+    // PrivateBin uses a 128-bit IV, not the usual 96-bit GCM nonce
+    let cipher = AesGcm::<Aes256, aes_gcm::aead::consts::U16>::new_from_slice(&derived)
+        .map_err(|_| PasteError::Encryption)?;
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::<aes_gcm::aead::consts::U16>::from_slice(&iv),
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| PasteError::Encryption)?;
+
+    let body = serde_json::json!({
+        "v": 2,
+        "adata": adata,
+        "ct": STANDARD.encode(ciphertext),
+        "meta": { "expire": config.expire },
+    });
+
+    Ok((body, key))
+}
+
+async fn publish_private_bin(
+    content: &str,
+    language: Option<&str>,
+    config: &PrivateBinConfig,
+) -> Result<PasteResult, PasteError> {
+    let base_url = config
+        .url
+        .as_deref()
+        .map(|u| u.trim())
+        .filter(|u| !u.is_empty())
+        .ok_or(PasteError::PrivateBinNotConfigured)?;
+    let base_url = if base_url.ends_with('/') {
+        base_url.to_string()
+    } else {
+        format!("{}/", base_url)
+    };
+    if !base_url.starts_with("https://") {
+        return Err(PasteError::ApiError("PrivateBin URL must use https".to_string()));
+    }
+
+    let formatter = match language {
+        Some("markdown") => "markdown",
+        Some("plaintext") | None => "plaintext",
+        Some(_) => "syntaxhighlighting",
+    };
+    let (body, key) = encrypt_private_bin(content, formatter, config)?;
+
+    let response = paste_client()?
+        .post(&base_url)
+        .header("X-Requested-With", "JSONHttpRequest")
+        .json(&body)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(error_from_response(response).await);
+    }
+
+    let result: PrivateBinResponse = response.json().await?;
+    if result.status != 0 {
+        return Err(PasteError::ApiError(
+            result.message.unwrap_or_else(|| "Unknown PrivateBin error".to_string()),
+        ));
+    }
+    let id = result
+        .id
+        .ok_or_else(|| PasteError::ApiError("PrivateBin response missing id".to_string()))?;
+
+    Ok(PasteResult {
+        service: PasteService::PrivateBin,
+        url: format!("{}?{}#{}", base_url, id, bs58::encode(key).into_string()),
+    })
+}
+
+async fn publish_paste_internal(
+    content: &str,
+    service: PasteService,
+    language: Option<&str>,
+    config: &PasteConfig,
+) -> Result<PasteResult, PasteError> {
+    if content.trim().is_empty() {
+        return Err(PasteError::ApiError("Nothing to publish".to_string()));
+    }
+
+    match service {
+        PasteService::PasteRs => publish_paste_rs(content).await,
+        PasteService::ZeroX0 => publish_zero_x0(content, &config.zero_x0).await,
+        PasteService::PrivateBin => publish_private_bin(content, language, &config.private_bin).await,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Publish content to a paste service (the configured default when `service` is omitted).
+/// When `history_entry_id` is given the resulting URL is recorded on that entry, and with
+/// `auto_copy_url` on it is copied to the clipboard.
+#[tauri::command]
+pub async fn publish_paste(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    content: String,
+    service: Option<String>,
+    language: Option<String>,
+    history_entry_id: Option<i64>,
+) -> Result<PasteResult, AppError> {
+    let config = load_config()?;
+    let service = match service.as_deref() {
+        Some(s) => PasteService::parse(s)?,
+        None => config.default_service,
+    };
+
+    let result = publish_paste_internal(&content, service, language.as_deref(), &config)
+        .await?;
+
+    if let Some(id) = history_entry_id {
+        let conn = state.db.lock()?;
+        set_entry_paste_info(&conn, id, service.as_str(), &result.url)?;
+    }
+    if config.auto_copy_url {
+        if let Err(e) = app.clipboard().write_text(result.url.clone()) {
+            log::warn!("Failed to copy paste URL: {}", e);
+        }
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn get_paste_config() -> Result<PasteConfig, AppError> {
+    load_config().map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn save_paste_config(config: PasteConfig) -> Result<(), AppError> {
+    save_config(&config).map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_bin_roundtrip() {
+        let config = PrivateBinConfig::default();
+        let (body, key) = encrypt_private_bin("hello", "plaintext", &config).unwrap();
+
+        let adata = &body["adata"];
+        let spec = adata[0].as_array().unwrap();
+        let iv = STANDARD.decode(spec[0].as_str().unwrap()).unwrap();
+        let salt = STANDARD.decode(spec[1].as_str().unwrap()).unwrap();
+        let ct = STANDARD.decode(body["ct"].as_str().unwrap()).unwrap();
+
+        let mut derived = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<sha2::Sha256>(&key, &salt, 100_000, &mut derived);
+        let cipher = AesGcm::<Aes256, aes_gcm::aead::consts::U16>::new_from_slice(&derived).unwrap();
+        let aad = serde_json::to_string(adata).unwrap();
+        let plain = cipher
+            .decrypt(
+                Nonce::<aes_gcm::aead::consts::U16>::from_slice(&iv),
+                Payload { msg: &ct, aad: aad.as_bytes() },
+            )
+            .unwrap();
+
+        assert_eq!(String::from_utf8(plain).unwrap(), r#"{"paste":"hello"}"#);
+        assert_eq!(body["meta"]["expire"], "1week");
+    }
+
+    #[test]
+    fn test_service_names_roundtrip() {
+        for service in [PasteService::PasteRs, PasteService::ZeroX0, PasteService::PrivateBin] {
+            assert_eq!(PasteService::parse(service.as_str()).unwrap(), service);
+        }
+        assert!(PasteService::parse("pastebin").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+// Per-application paste profiles: Slack wants code fences, Google Docs wants rich
+// text, terminals want plain text. A profile maps an app (bundle id or name) to a copy
+// format and a list of transforms, and hide_and_paste and copy_as("auto") apply the
+// profile for the app Wingman was opened over before writing the pasteboard.
+// Profiles live in paste_profiles.json.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+use crate::clipboard::{convert_for_copy, transform_text, ClipboardPayload, CopyFormat, TextTransform};
+use crate::error::AppError;
+use crate::storage::{ensure_app_data_dir, StorageError};
+use crate::AppState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasteProfile {
+    pub id: String,
+    /// Bundle id ("com.tinyspeck.slackmacgap") or app name ("Slack"), case-insensitive
+    pub app: String,
+    /// Pasteboard representation to write; plain text when unset
+    #[serde(default)]
+    pub format: Option<CopyFormat>,
+    /// Applied in order before the content is converted
+    #[serde(default)]
+    pub transforms: Vec<TextTransform>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PasteProfilesData {
+    pub profiles: Vec<PasteProfile>,
+}
+
+pub fn load_paste_profiles() -> Result<PasteProfilesData, StorageError> {
+    let path = ensure_app_data_dir()?.join("paste_profiles.json");
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        Ok(PasteProfilesData::default())
+    }
+}
+
+pub fn save_paste_profiles(data: &PasteProfilesData) -> Result<(), StorageError> {
+    let path = ensure_app_data_dir()?.join("paste_profiles.json");
+    fs::write(path, serde_json::to_string_pretty(data)?)?;
+    Ok(())
+}
+
+/// Bundle ids by app name; an app's id doesn't change while Wingman runs
+static BUNDLE_IDS: Mutex<Option<HashMap<String, Option<String>>>> = Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+fn lookup_bundle_id(app_name: &str) -> Option<String> {
+    let script = format!(r#"id of application "{}""#, app_name.replace('"', "\\\""));
+    let output = std::process::Command::new("osascript").arg("-e").arg(script).output().ok()?;
+    let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !id.is_empty()).then_some(id)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn lookup_bundle_id(_app_name: &str) -> Option<String> {
+    None
+}
+
+fn bundle_id(app_name: &str) -> Option<String> {
+    if let Some(cached) = BUNDLE_IDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|ids| ids.get(app_name))
+    {
+        return cached.clone();
+    }
+    let id = lookup_bundle_id(app_name);
+    BUNDLE_IDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .insert(app_name.to_string(), id.clone());
+    id
+}
+
+/// The first enabled profile naming the app by name or bundle id
+fn find_profile<'a>(profiles: &'a [PasteProfile], app_name: &str, bundle_id: Option<&str>) -> Option<&'a PasteProfile> {
+    profiles.iter().filter(|p| p.enabled).find(|p| {
+        let app = p.app.trim();
+        app.eq_ignore_ascii_case(app_name) || bundle_id.is_some_and(|id| app.eq_ignore_ascii_case(id))
+    })
+}
+
+/// Run a profile's transforms, then convert to its format
+pub fn apply_profile(profile: &PasteProfile, content: &str, language: Option<&str>) -> Result<ClipboardPayload, String> {
+    let transformed = profile
+        .transforms
+        .iter()
+        .fold(content.to_string(), |text, transform| transform_text(&text, transform.clone()));
+    convert_for_copy(&transformed, profile.format.unwrap_or(CopyFormat::Plain), language)
+}
+
+/// The profile for the app Wingman was opened over, if any. The bundle id is only
+/// looked up when some profile could need it.
+pub fn profile_for_previous_app(state: &AppState) -> Option<PasteProfile> {
+    let app = crate::frontmost::previous_app(state)?;
+    let profiles = load_paste_profiles().ok()?.profiles;
+    if !profiles.iter().any(|p| p.enabled) {
+        return None;
+    }
+    if let Some(profile) = find_profile(&profiles, &app.name, None) {
+        return Some(profile.clone());
+    }
+    let bundle_id = app.bundle_id.or_else(|| bundle_id(&app.name))?;
+    find_profile(&profiles, &app.name, Some(&bundle_id)).cloned()
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_paste_profiles() -> Result<PasteProfilesData, AppError> {
+    load_paste_profiles().map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn save_paste_profiles_cmd(data: PasteProfilesData) -> Result<(), AppError> {
+    save_paste_profiles(&data).map_err(AppError::from)
+}
+
+/// The profile that would apply if content were pasted now, for showing in the UI
+#[tauri::command]
+pub fn get_active_paste_profile(state: tauri::State<'_, AppState>) -> Option<PasteProfile> {
+    profile_for_previous_app(&state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(app: &str, format: Option<CopyFormat>, transforms: Vec<TextTransform>, enabled: bool) -> PasteProfile {
+        PasteProfile {
+            id: app.to_string(),
+            app: app.to_string(),
+            format,
+            transforms,
+            enabled,
+        }
+    }
+
+    #[test]
+    fn test_find_profile() {
+        let profiles = vec![
+            profile("Slack", Some(CopyFormat::Markdown), vec![], false),
+            profile("com.tinyspeck.slackmacgap", Some(CopyFormat::Markdown), vec![], true),
+            profile("terminal", None, vec![], true),
+        ];
+        assert_eq!(find_profile(&profiles, "Terminal", None).unwrap().id, "terminal");
+        assert!(find_profile(&profiles, "Slack", None).is_none());
+        assert_eq!(
+            find_profile(&profiles, "Slack", Some("com.tinyspeck.slackmacgap")).unwrap().id,
+            "com.tinyspeck.slackmacgap"
+        );
+    }
+
+    #[test]
+    fn test_apply_profile() {
+        let slack = profile(
+            "Slack",
+            Some(CopyFormat::Markdown),
+            vec![TextTransform::TrimWhitespace],
+            true,
+        );
+        let payload = apply_profile(&slack, "  let x = 1;  \n", Some("rust")).unwrap();
+        assert_eq!(payload.text, "```rust\nlet x = 1;\n```");
+        assert!(payload.html.is_none());
+
+        let terminal = profile("Terminal", None, vec![TextTransform::Uppercase], true);
+        assert_eq!(apply_profile(&terminal, "ls\r\n", None).unwrap().text, "LS\n");
+    }
+}
@@ -0,0 +1,428 @@
+// PDF export and printing of syntax-highlighted content.
+// Highlighting comes from highlight.rs; layout here is deliberately simple:
+// one monospaced font, fixed line height, long lines wrapped at the page width.
+
+use printpdf::{BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfLayerReference, Rect, Rgb};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use syntect::highlighting::{Color as SyntectColor, FontStyle};
+
+use crate::error::AppError;
+use crate::highlight::{get_theme, highlight_lines};
+use crate::notifications::{self, NotificationCategory};
+
+// A4 portrait
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+const MARGIN_MM: f32 = 15.0;
+const HEADER_HEIGHT_MM: f32 = 10.0;
+const FOOTER_HEIGHT_MM: f32 = 8.0;
+const FONT_SIZE_PT: f32 = 9.0;
+const HEADER_FONT_SIZE_PT: f32 = 10.0;
+const LINE_HEIGHT_MM: f32 = 4.2;
+/// Courier glyphs are 600/1000 em wide
+const CHAR_WIDTH_MM: f32 = FONT_SIZE_PT * 0.6 * 0.3528;
+const TAB_WIDTH: usize = 4;
+
+/// One rendered row: a source line, or a wrapped continuation of one (no number)
+#[derive(Debug, Clone, PartialEq)]
+struct PdfLine {
+    number: Option<usize>,
+    spans: Vec<PdfSpan>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PdfSpan {
+    text: String,
+    color: (u8, u8, u8),
+    bold: bool,
+}
+
+fn usable_width_mm() -> f32 {
+    PAGE_WIDTH_MM - 2.0 * MARGIN_MM
+}
+
+fn lines_per_page() -> usize {
+    let usable = PAGE_HEIGHT_MM - 2.0 * MARGIN_MM - HEADER_HEIGHT_MM - FOOTER_HEIGHT_MM;
+    (usable / LINE_HEIGHT_MM).floor() as usize
+}
+
+/// Split highlighted source lines into rows of at most `max_cols` characters
+fn layout_lines(
+    lines: &[Vec<(SyntectColor, bool, String)>],
+    max_cols: usize,
+) -> Vec<PdfLine> {
+    let max_cols = max_cols.max(1);
+    let mut rows = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let mut current = PdfLine {
+            number: Some(index + 1),
+            spans: Vec::new(),
+        };
+        let mut cols = 0;
+
+        for (color, bold, text) in line {
+            let text = text.trim_end_matches(['\n', '\r']).replace('\t', &" ".repeat(TAB_WIDTH));
+            let mut chars = text.chars().peekable();
+            while chars.peek().is_some() {
+                if cols == max_cols {
+                    rows.push(std::mem::replace(
+                        &mut current,
+                        PdfLine {
+                            number: None,
+                            spans: Vec::new(),
+                        },
+                    ));
+                    cols = 0;
+                }
+                let chunk: String = chars.by_ref().take(max_cols - cols).collect();
+                cols += chunk.chars().count();
+                current.spans.push(PdfSpan {
+                    text: chunk,
+                    color: (color.r, color.g, color.b),
+                    bold: *bold,
+                });
+            }
+        }
+
+        rows.push(current);
+    }
+
+    rows
+}
+
+fn rgb(color: (u8, u8, u8)) -> Color {
+    Color::Rgb(Rgb::new(
+        color.0 as f32 / 255.0,
+        color.1 as f32 / 255.0,
+        color.2 as f32 / 255.0,
+        None,
+    ))
+}
+
+/// Everything that stays the same from page to page
+struct PageContext<'a> {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    header: IndirectFontRef,
+    title: &'a str,
+    page_count: usize,
+    gutter_cols: usize,
+    background: (u8, u8, u8),
+    foreground: (u8, u8, u8),
+}
+
+fn draw_page(layer: &PdfLayerReference, ctx: &PageContext, rows: &[PdfLine], page: usize) {
+    let PageContext {
+        title,
+        page_count,
+        gutter_cols,
+        background,
+        foreground,
+        ..
+    } = *ctx;
+
+    // Dark themes get their background so the text colors stay legible
+    if background != (255, 255, 255) {
+        layer.set_fill_color(rgb(background));
+        layer.add_rect(Rect::new(Mm(0.0), Mm(0.0), Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM)));
+    }
+
+    let top = PAGE_HEIGHT_MM - MARGIN_MM;
+
+    layer.set_fill_color(rgb(foreground));
+    layer.use_text(title, HEADER_FONT_SIZE_PT, Mm(MARGIN_MM), Mm(top - 4.0), &ctx.header);
+    layer.use_text(
+        format!("Page {} of {}", page, page_count),
+        FONT_SIZE_PT,
+        Mm(PAGE_WIDTH_MM - MARGIN_MM - 25.0),
+        Mm(MARGIN_MM),
+        &ctx.regular,
+    );
+
+    let gutter = (gutter_cols + 1) as f32 * CHAR_WIDTH_MM;
+    let mut y = top - HEADER_HEIGHT_MM - LINE_HEIGHT_MM;
+
+    for row in rows {
+        if let Some(number) = row.number {
+            layer.set_fill_color(rgb((150, 150, 150)));
+            layer.use_text(
+                format!("{:>width$}", number, width = gutter_cols),
+                FONT_SIZE_PT,
+                Mm(MARGIN_MM),
+                Mm(y),
+                &ctx.regular,
+            );
+        }
+
+        layer.begin_text_section();
+        layer.set_text_cursor(Mm(MARGIN_MM + gutter), Mm(y));
+        for span in &row.spans {
+            let font = if span.bold { &ctx.bold } else { &ctx.regular };
+            layer.set_font(font, FONT_SIZE_PT);
+            layer.set_fill_color(rgb(span.color));
+            layer.write_text(span.text.clone(), font);
+        }
+        layer.end_text_section();
+
+        y -= LINE_HEIGHT_MM;
+    }
+}
+
+/// Render content to a paginated A4 PDF with a header, page numbers and line numbers
+pub fn export_pdf(
+    content: &str,
+    language: Option<&str>,
+    theme: Option<&str>,
+    title: &str,
+    path: &Path,
+) -> Result<(), String> {
+    let theme = get_theme(theme);
+    let highlighted: Vec<Vec<(SyntectColor, bool, String)>> = highlight_lines(content, language, theme)?
+        .into_iter()
+        .map(|line| {
+            line.into_iter()
+                .map(|(style, text)| {
+                    (style.foreground, style.font_style.contains(FontStyle::BOLD), text.to_string())
+                })
+                .collect()
+        })
+        .collect();
+
+    let line_count = highlighted.len().max(1);
+    let gutter_cols = line_count.to_string().len();
+    let max_cols = ((usable_width_mm() / CHAR_WIDTH_MM) as usize).saturating_sub(gutter_cols + 1);
+    let rows = layout_lines(&highlighted, max_cols);
+
+    let per_page = lines_per_page();
+    let pages: Vec<&[PdfLine]> = if rows.is_empty() {
+        vec![&[]]
+    } else {
+        rows.chunks(per_page).collect()
+    };
+
+    let background = theme
+        .settings
+        .background
+        .map(|c| (c.r, c.g, c.b))
+        .unwrap_or((255, 255, 255));
+    let foreground = theme
+        .settings
+        .foreground
+        .map(|c| (c.r, c.g, c.b))
+        .unwrap_or((0, 0, 0));
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new(title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Content");
+    let ctx = PageContext {
+        regular: doc.add_builtin_font(BuiltinFont::Courier).map_err(|e| e.to_string())?,
+        bold: doc.add_builtin_font(BuiltinFont::CourierBold).map_err(|e| e.to_string())?,
+        header: doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| e.to_string())?,
+        title,
+        page_count: pages.len(),
+        gutter_cols,
+        background,
+        foreground,
+    };
+
+    for (index, page_rows) in pages.iter().enumerate() {
+        let layer = if index == 0 {
+            doc.get_page(first_page).get_layer(first_layer)
+        } else {
+            let (page, layer) = doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Content");
+            doc.get_page(page).get_layer(layer)
+        };
+        draw_page(&layer, &ctx, page_rows, index + 1);
+    }
+
+    let file = File::create(path).map_err(|e| format!("Failed to create PDF: {}", e))?;
+    doc.save(&mut BufWriter::new(file))
+        .map_err(|e| format!("Failed to write PDF: {}", e))
+}
+
+/// Header line for exported documents
+pub fn default_title(language: Option<&str>) -> String {
+    let date = chrono::Local::now().format("%Y-%m-%d %H:%M");
+    match language.filter(|l| *l != "plaintext") {
+        Some(language) => format!("Wingman - {} - {}", language, date),
+        None => format!("Wingman - {}", date),
+    }
+}
+
+/// Export highlighted content to a PDF. Without a `path` a save dialog is shown;
+/// returns the written path, or `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_to_pdf(
+    app: tauri::AppHandle,
+    content: String,
+    language: Option<String>,
+    theme: Option<String>,
+    path: Option<String>,
+) -> Result<Option<String>, AppError> {
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            use rfd::AsyncFileDialog;
+
+            // Set flag to prevent panel from hiding when dialog takes focus
+            #[cfg(target_os = "macos")]
+            {
+                crate::window::DIALOG_OPEN.store(true, std::sync::atomic::Ordering::SeqCst);
+            }
+
+            let file = AsyncFileDialog::new()
+                .add_filter("PDF", &["pdf"])
+                .set_file_name("snippet.pdf")
+                .save_file()
+                .await;
+
+            #[cfg(target_os = "macos")]
+            {
+                crate::window::DIALOG_OPEN.store(false, std::sync::atomic::Ordering::SeqCst);
+            }
+
+            match file {
+                Some(handle) => handle.path().to_path_buf(),
+                None => return Ok(None), // User cancelled
+            }
+        }
+    };
+
+    let title = default_title(language.as_deref());
+    // Timed from here so the save dialog doesn't count as a long export
+    let started = std::time::Instant::now();
+    let result = export_pdf(&content, language.as_deref(), theme.as_deref(), &title, &path);
+    notifications::report(&app, NotificationCategory::Export, "PDF export", started, &result);
+    result?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Print the PDF at `path` through PDFKit's print operation, which shows the
+/// standard print dialog. Blocks (in a modal run loop) until the dialog closes.
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+unsafe fn run_print_operation(path: &Path) -> Result<(), String> {
+    use cocoa::base::{id, nil, NO, YES};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    // PDFKit isn't linked into the app; load it on first use
+    let framework_path = NSString::alloc(nil).init_str("/System/Library/Frameworks/PDFKit.framework");
+    let bundle: id = msg_send![class!(NSBundle), bundleWithPath: framework_path];
+    if bundle == nil {
+        return Err("PDFKit is not available".to_string());
+    }
+    let _: bool = msg_send![bundle, load];
+
+    let ns_path = NSString::alloc(nil).init_str(&path.to_string_lossy());
+    let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+    let document: id = msg_send![class!(PDFDocument), alloc];
+    let document: id = msg_send![document, initWithURL: url];
+    if document == nil {
+        return Err("Failed to load PDF for printing".to_string());
+    }
+
+    let print_info: id = msg_send![class!(NSPrintInfo), sharedPrintInfo];
+    // kPDFPrintPageScaleDownToFit
+    let operation: id = msg_send![document, printOperationForPrintInfo:print_info scalingMode:2i64 autoRotate:YES];
+    if operation == nil {
+        return Err("Failed to create print operation".to_string());
+    }
+    let _: () = msg_send![operation, setShowsPrintPanel: YES];
+    let _: () = msg_send![operation, setShowsProgressPanel: NO];
+
+    crate::window::DIALOG_OPEN.store(true, std::sync::atomic::Ordering::SeqCst);
+    let _: bool = msg_send![operation, runOperation];
+    crate::window::DIALOG_OPEN.store(false, std::sync::atomic::Ordering::SeqCst);
+
+    Ok(())
+}
+
+/// Show the native print dialog for highlighted content. The content is laid out
+/// exactly as `export_to_pdf` would, then handed to the system print panel. Where
+/// there's no native print hook the PDF is opened in the default viewer instead.
+#[tauri::command]
+pub async fn print_content(
+    app: tauri::AppHandle,
+    content: String,
+    language: Option<String>,
+    theme: Option<String>,
+) -> Result<(), AppError> {
+    let path = crate::temp_files::dir()?.join(format!("wingman_print_{}.pdf", chrono::Utc::now().timestamp_millis()));
+    let title = default_title(language.as_deref());
+    export_pdf(&content, language.as_deref(), theme.as_deref(), &title, &path)?;
+    crate::temp_files::restrict(&path)?;
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        // Must run on main thread to avoid Cocoa exceptions. The print panel is modal,
+        // so report back as soon as it's been set up rather than when it closes.
+        let (tx, rx) = mpsc::channel::<Result<(), String>>();
+        app.run_on_main_thread(move || {
+            let result = unsafe { run_print_operation(&path) };
+            let _ = tx.send(result);
+        })?;
+
+        // A timeout just means the dialog is still open
+        match rx.recv_timeout(Duration::from_secs(2)) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Ok(()),
+            Err(e) => Err(format!("Print operation failed: {}", e)),
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        crate::open_with_system_handler(&path.to_string_lossy()).map_err(AppError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str) -> (SyntectColor, bool, String) {
+        (SyntectColor::BLACK, false, text.to_string())
+    }
+
+    #[test]
+    fn test_layout_wraps_long_lines() {
+        let lines = vec![vec![span("abcdef"), span("ghij\n")], vec![span("xy")]];
+        let rows = layout_lines(&lines, 4);
+        let texts: Vec<(Option<usize>, String)> = rows
+            .iter()
+            .map(|r| (r.number, r.spans.iter().map(|s| s.text.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                (Some(1), "abcd".to_string()),
+                (None, "efgh".to_string()),
+                (None, "ij".to_string()),
+                (Some(2), "xy".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_expands_tabs() {
+        let rows = layout_lines(&[vec![span("\tx")]], 80);
+        assert_eq!(rows[0].spans[0].text, "    x");
+    }
+
+    #[test]
+    fn test_export_pdf_writes_file() {
+        let path = std::env::temp_dir().join("wingman_pdf_export_test.pdf");
+        let content = (0..200).map(|i| format!("let x{} = {};\n", i, i)).collect::<String>();
+        export_pdf(&content, Some("javascript"), None, "Test", &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
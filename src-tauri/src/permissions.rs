@@ -0,0 +1,260 @@
+// macOS privacy permissions Wingman depends on, for the onboarding checklist.
+// Checks never prompt; request_permission triggers the system prompt where one
+// exists and opens the matching System Settings pane.
+
+use serde::Serialize;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionKind {
+    /// Simulating paste keystrokes and reading the focused window
+    Accessibility,
+    /// Controlling System Events and the previous app via osascript
+    Automation,
+    /// Window titles of other apps
+    ScreenRecording,
+}
+
+impl PermissionKind {
+    const ALL: [PermissionKind; 3] = [
+        PermissionKind::Accessibility,
+        PermissionKind::Automation,
+        PermissionKind::ScreenRecording,
+    ];
+
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "accessibility" => Ok(PermissionKind::Accessibility),
+            "automation" => Ok(PermissionKind::Automation),
+            "screen_recording" => Ok(PermissionKind::ScreenRecording),
+            other => Err(format!("Unknown permission: {}", other)),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            PermissionKind::Accessibility => "Paste directly into other apps",
+            PermissionKind::Automation => "Return focus to the app you were using",
+            PermissionKind::ScreenRecording => "Record which window content was copied from",
+        }
+    }
+
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn settings_url(&self) -> &'static str {
+        match self {
+            PermissionKind::Accessibility => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+            }
+            PermissionKind::Automation => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Automation"
+            }
+            PermissionKind::ScreenRecording => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)] // Which states can occur depends on the platform
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// The user hasn't been asked yet
+    NotDetermined,
+    /// Couldn't be determined without prompting (e.g. the target app isn't running)
+    Unknown,
+    /// This platform doesn't gate the feature behind a permission
+    NotApplicable,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionStatus {
+    pub kind: PermissionKind,
+    pub state: PermissionState,
+    pub description: String,
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::PermissionState;
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct AEDesc {
+        descriptor_type: u32,
+        data_handle: *mut c_void,
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+        fn AXIsProcessTrustedWithOptions(options: *const c_void) -> bool;
+        static kAXTrustedCheckOptionPrompt: *const c_void;
+    }
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        fn AECreateDesc(type_code: u32, data: *const c_void, size: isize, result: *mut AEDesc) -> i16;
+        fn AEDisposeDesc(desc: *mut AEDesc) -> i16;
+        fn AEDeterminePermissionToAutomateTarget(
+            target: *const AEDesc,
+            event_class: u32,
+            event_id: u32,
+            ask_user_if_needed: bool,
+        ) -> i32;
+    }
+
+    const TYPE_APPLICATION_BUNDLE_ID: u32 = u32::from_be_bytes(*b"bund");
+    const TYPE_WILDCARD: u32 = u32::from_be_bytes(*b"****");
+    const ERR_AE_EVENT_NOT_PERMITTED: i32 = -1743;
+    const ERR_AE_EVENT_WOULD_REQUIRE_USER_CONSENT: i32 = -1744;
+
+    /// osascript keystrokes go through System Events
+    const AUTOMATION_TARGET: &str = "com.apple.systemevents";
+
+    pub fn accessibility() -> PermissionState {
+        if unsafe { AXIsProcessTrusted() } {
+            PermissionState::Granted
+        } else {
+            // There's no API to tell "denied" from "never asked"
+            PermissionState::NotDetermined
+        }
+    }
+
+    #[allow(deprecated)]
+    pub fn prompt_accessibility() -> bool {
+        use cocoa::base::{id, YES};
+        use objc::{class, msg_send, sel, sel_impl};
+
+        unsafe {
+            let yes: id = msg_send![class!(NSNumber), numberWithBool: YES];
+            let key = kAXTrustedCheckOptionPrompt as id;
+            let options: id = msg_send![class!(NSDictionary), dictionaryWithObject: yes forKey: key];
+            AXIsProcessTrustedWithOptions(options as *const c_void)
+        }
+    }
+
+    /// Blocks until the user answers when `ask` is set, so call it off the main thread
+    pub fn automation(ask: bool) -> PermissionState {
+        unsafe {
+            let mut target = AEDesc {
+                descriptor_type: 0,
+                data_handle: std::ptr::null_mut(),
+            };
+            let bundle_id = AUTOMATION_TARGET.as_bytes();
+            if AECreateDesc(
+                TYPE_APPLICATION_BUNDLE_ID,
+                bundle_id.as_ptr() as *const c_void,
+                bundle_id.len() as isize,
+                &mut target,
+            ) != 0
+            {
+                return PermissionState::Unknown;
+            }
+
+            let result = AEDeterminePermissionToAutomateTarget(&target, TYPE_WILDCARD, TYPE_WILDCARD, ask);
+            AEDisposeDesc(&mut target);
+
+            match result {
+                0 => PermissionState::Granted,
+                ERR_AE_EVENT_NOT_PERMITTED => PermissionState::Denied,
+                ERR_AE_EVENT_WOULD_REQUIRE_USER_CONSENT => PermissionState::NotDetermined,
+                // Includes procNotFound: System Events launches on demand, so it's
+                // often not running yet
+                _ => PermissionState::Unknown,
+            }
+        }
+    }
+
+    pub fn screen_recording() -> PermissionState {
+        if core_graphics::access::ScreenCaptureAccess.preflight() {
+            PermissionState::Granted
+        } else {
+            PermissionState::NotDetermined
+        }
+    }
+
+    pub fn prompt_screen_recording() -> bool {
+        core_graphics::access::ScreenCaptureAccess.request()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn check(kind: PermissionKind) -> PermissionState {
+    match kind {
+        PermissionKind::Accessibility => macos::accessibility(),
+        PermissionKind::Automation => macos::automation(false),
+        PermissionKind::ScreenRecording => macos::screen_recording(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn check(_kind: PermissionKind) -> PermissionState {
+    PermissionState::NotApplicable
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn check_permissions() -> Vec<PermissionStatus> {
+    PermissionKind::ALL
+        .iter()
+        .map(|kind| PermissionStatus {
+            kind: *kind,
+            state: check(*kind),
+            description: kind.description().to_string(),
+        })
+        .collect()
+}
+
+/// Ask for a permission: shows the system prompt if there is one, otherwise (or if
+/// it was already refused) opens the relevant System Settings pane. Returns the new state.
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn request_permission(kind: String) -> Result<PermissionState, AppError> {
+    let kind = PermissionKind::parse(&kind)?;
+
+    let state = match kind {
+        PermissionKind::Accessibility => {
+            // The prompt itself offers to open System Settings
+            if macos::prompt_accessibility() {
+                return Ok(PermissionState::Granted);
+            }
+            PermissionState::NotDetermined
+        }
+        PermissionKind::Automation => {
+            // Make sure System Events is running so the consent prompt can appear
+            let _ = std::process::Command::new("open")
+                .args(["-g", "-b", "com.apple.systemevents"])
+                .status();
+            let state = tokio::task::spawn_blocking(|| macos::automation(true))
+                .await?;
+            if state == PermissionState::Granted {
+                return Ok(state);
+            }
+            state
+        }
+        PermissionKind::ScreenRecording => {
+            if macos::prompt_screen_recording() {
+                return Ok(PermissionState::Granted);
+            }
+            PermissionState::NotDetermined
+        }
+    };
+
+    crate::open_with_system_handler(kind.settings_url())?;
+    Ok(state)
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn request_permission(kind: String) -> Result<PermissionState, AppError> {
+    PermissionKind::parse(&kind)?;
+    Err(AppError::unsupported("Permission requests are only supported on macOS"))
+}
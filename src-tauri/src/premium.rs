@@ -9,6 +9,8 @@ use std::fs;
 use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::http_client;
+
 // Supabase configuration
 const SUPABASE_URL: &str = "https://yhpetdqcmqpfwhdtbhat.supabase.co";
 const SUPABASE_PUBLISHABLE_KEY: &str = "sb_publishable_t4l4DUhI_I2rpT9pMU8dgg_Y2j55oJY";
@@ -36,6 +38,10 @@ pub enum PremiumError {
     ObsidianError(String),
     #[error("Validation error: {0}")]
     ValidationError(String),
+    #[error("AI features are paused while privacy mode is on")]
+    PrivacyModeActive,
+    #[error("AI features aren't available in offline mode")]
+    Offline,
 }
 
 /// Subscription status for a Premium user
@@ -191,6 +197,12 @@ struct ClaudeProxyResponse {
 
 /// Get the path to the Obsidian config file
 fn get_obsidian_config_path() -> Result<PathBuf, PremiumError> {
+    // Profiles other than the default keep their vault config in the profile directory
+    if !crate::profiles::is_default_profile() {
+        let dir = crate::storage::ensure_profile_data_dir()
+            .map_err(|e| PremiumError::StorageError(e.to_string()))?;
+        return Ok(dir.join("obsidian_config.json"));
+    }
     let data_dir = dirs::data_dir()
         .ok_or_else(|| PremiumError::StorageError("Cannot find data directory".to_string()))?;
     let app_dir = data_dir.join("com.wingman.app");
@@ -459,7 +471,7 @@ pub fn add_to_obsidian_vault(content: &str, config: &ObsidianConfig) -> Result<O
 
 /// Validate Premium subscription and get status
 pub async fn validate_premium_license(license_key: &str) -> Result<SubscriptionStatus, PremiumError> {
-    let client = reqwest::Client::new();
+    let client = http_client::supabase_client().map_err(|e| PremiumError::NetworkError(e.to_string()))?;
     let url = format!("{}/rest/v1/rpc/has_premium_access", SUPABASE_URL);
 
     let response = client
@@ -471,7 +483,7 @@ pub async fn validate_premium_license(license_key: &str) -> Result<SubscriptionS
         }))
         .send()
         .await
-        .map_err(|e| PremiumError::NetworkError(e.to_string()))?;
+        .map_err(|e| PremiumError::NetworkError(http_client::describe(&e)))?;
 
     if !response.status().is_success() {
         let error_text = response.text().await.unwrap_or_default();
@@ -525,20 +537,42 @@ pub async fn get_ai_usage(license_key: &str) -> Result<UsageStats, PremiumError>
     })
 }
 
-/// Call an AI feature through the Claude proxy
+/// Call an AI feature through the Claude proxy. Every call made this way is recorded in
+/// AI history when that's turned on.
 pub async fn call_ai_feature(
     license_key: &str,
     prompt: &str,
     feature: &str,
     system_instructions: Option<&str>,
 ) -> Result<AIResponse, PremiumError> {
+    let response = request_ai_feature(license_key, prompt, feature, system_instructions).await?;
+    crate::ai_history::record_if_enabled(feature, prompt, system_instructions, &response, None);
+    Ok(response)
+}
+
+/// The proxy call behind call_ai_feature, without recording it
+pub async fn request_ai_feature(
+    license_key: &str,
+    prompt: &str,
+    feature: &str,
+    system_instructions: Option<&str>,
+) -> Result<AIResponse, PremiumError> {
+    // Nothing leaves the machine while capture is paused
+    if crate::privacy::is_privacy_mode_active() {
+        return Err(PremiumError::PrivacyModeActive);
+    }
+    if crate::offline::is_enabled() {
+        return Err(PremiumError::Offline);
+    }
+
     // Validate the prompt isn't empty
     let trimmed_prompt = prompt.trim();
     if trimmed_prompt.is_empty() {
         return Err(PremiumError::ValidationError("Prompt cannot be empty".to_string()));
     }
 
-    let client = reqwest::Client::builder()
+    let client = http_client::supabase_builder()
+        .map_err(|e| PremiumError::NetworkError(e.to_string()))?
         .timeout(std::time::Duration::from_secs(60))
         .build()
         .map_err(|e| PremiumError::NetworkError(e.to_string()))?;
@@ -568,7 +602,7 @@ pub async fn call_ai_feature(
         .await
         .map_err(|e| {
             log::error!("AI request failed: {}", e);
-            PremiumError::NetworkError(e.to_string())
+            PremiumError::NetworkError(http_client::describe(&e))
         })?;
 
     let status = response.status();
@@ -598,11 +632,17 @@ pub async fn call_ai_feature(
         PremiumError::AIServiceError("No result in response".to_string())
     })?;
 
-    Ok(AIResponse {
+    let response = AIResponse {
         result,
         tokens_used_this_request: body.tokens_used_this_request.unwrap_or(0),
         tokens_remaining: body.tokens_remaining.unwrap_or(MONTHLY_TOKEN_LIMIT),
-    })
+    };
+    crate::event_bridge::publish(crate::event_bridge::BridgeEvent::AiCallCompleted {
+        feature: feature.to_string(),
+        tokens_used: response.tokens_used_this_request,
+        tokens_remaining: response.tokens_remaining,
+    });
+    Ok(response)
 }
 
 /// Response from create-portal-session edge function
@@ -620,7 +660,8 @@ pub async fn create_customer_portal_session(
     email: &str,
     device_id: &str,
 ) -> Result<String, PremiumError> {
-    let client = reqwest::Client::builder()
+    let client = http_client::supabase_builder()
+        .map_err(|e| PremiumError::NetworkError(e.to_string()))?
         .timeout(std::time::Duration::from_secs(30))
         .build()
         .map_err(|e| PremiumError::NetworkError(e.to_string()))?;
@@ -643,7 +684,7 @@ pub async fn create_customer_portal_session(
         .await
         .map_err(|e| {
             log::error!("Portal session request failed: {}", e);
-            PremiumError::NetworkError(e.to_string())
+            PremiumError::NetworkError(http_client::describe(&e))
         })?;
 
     let status = response.status();
@@ -0,0 +1,228 @@
+// Presentation mode: a read-only view for screen sharing. While it's on:
+//
+// - History lists come back with content, rich text, images and summaries stripped, so
+//   only titles, languages, tags and counts show. Full content can't be fetched: the
+//   local API refuses launcher queries and actions, and the tray menu only shows clip
+//   titles and snippet names and won't paste.
+// - The command middleware only lets through the commands in ALLOWED_COMMANDS and turns
+//   everything else away with ErrorCode::Disabled: anything returning stored content
+//   (snippets, exports, trash, AI history, credentials) or reaching outside (AI, gist
+//   sync, LAN, sharing). New commands are blocked until they're added here. Clips
+//   aren't pushed to LAN peers either.
+//
+// It's a runtime switch like privacy mode rather than a setting, so a restart always
+// comes back in the normal view.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{AppError, ErrorCode};
+use crate::history::HistoryEntry;
+
+/// Commands that still work while presenting: history lists that go through
+/// `for_display`, settings and window management, and tools that only see the text
+/// they're given
+const ALLOWED_COMMANDS: &[&str] = &[
+    // Settings, profiles and permissions
+    "get_settings",
+    "update_settings",
+    "get_default_hotkey_cmd",
+    "set_launch_at_login",
+    "get_launch_at_login",
+    "validate_hotkey_cmd",
+    "set_summon_hotkey",
+    "check_hotkey_conflicts",
+    "get_accessibility_prefs",
+    "list_profiles",
+    "get_active_profile",
+    "set_privacy_mode",
+    "get_privacy_mode",
+    "set_presentation_mode",
+    "get_presentation_mode",
+    "check_permissions",
+    "request_permission",
+    "get_license_status",
+    "check_feature_enabled",
+    "is_online",
+    // Window and app
+    "show_window",
+    "hide_window",
+    "hide_and_paste",
+    "toggle_fullscreen",
+    "toggle_focus_mode",
+    "set_window_blur_cmd",
+    "set_vibrancy_mode",
+    "update_panel_behavior",
+    "cycle_window_preset",
+    "apply_window_preset",
+    "rebuild_tray_menu",
+    "get_app_version",
+    "check_for_app_updates",
+    "get_update_release_notes",
+    "pause_update_download",
+    "resume_update_download",
+    "cancel_update_download",
+    "defer_update_until_quit",
+    "get_startup_metrics",
+    "get_index_status",
+    // History, redacted by for_display
+    "add_history_entry",
+    "get_history",
+    "get_history_after",
+    "get_history_before",
+    "search_history",
+    "search_history_advanced",
+    "list_saved_searches",
+    "run_saved_search",
+    "get_history_by_smart_tag",
+    "get_history_by_source_app",
+    "list_history_source_apps",
+    "list_upcoming_reminders",
+    "get_history_stats",
+    "get_history_analytics",
+    "delete_history_entry",
+    "bulk_delete_entries",
+    "bulk_tag_entries",
+    "set_entry_expiry",
+    "set_entry_reminder",
+    // Clipboard writes of what the editor already shows
+    "write_native_clipboard",
+    "copy_as",
+    // Text tools working on their input only
+    "get_text_stats",
+    "transform_text_cmd",
+    "count_pattern_occurrences",
+    "list_actions",
+    "search_characters",
+    "get_recent_characters",
+    "record_character_use",
+    "detect_language",
+    "format_code",
+    "format_graphql",
+    "format_json",
+    "minify_json",
+    "minify_code",
+    "sort_json_keys",
+    "format_xml",
+    "encode_base64",
+    "decode_base64",
+    "encode_url",
+    "decode_url",
+    "encode_html",
+    "decode_html",
+    "generate_uuid",
+    "generate_uuid_v7",
+    "generate_nanoid",
+    "generate_lorem_ipsum",
+    "unix_to_human",
+    "format_timestamp",
+    "human_to_unix",
+    "convert_timezone",
+    "get_current_timestamp",
+];
+
+static PRESENTING: AtomicBool = AtomicBool::new(false);
+
+pub fn is_active() -> bool {
+    PRESENTING.load(Ordering::Relaxed)
+}
+
+fn allowed(command: &str) -> bool {
+    ALLOWED_COMMANDS.contains(&command)
+}
+
+/// Whether the middleware should turn `command` away
+pub fn blocks(command: &str) -> bool {
+    is_active() && !allowed(command)
+}
+
+pub fn blocked_error() -> AppError {
+    AppError::new(ErrorCode::Disabled, "Not available in presentation mode")
+}
+
+/// Strip everything but the metadata from entries
+pub fn redact_entries(entries: &mut [HistoryEntry]) {
+    for entry in entries {
+        entry.content = String::new();
+        entry.content_truncated = false;
+        entry.html = None;
+        entry.images = None;
+        entry.summary = None;
+        entry.reminder_note = None;
+        entry.source_window_title = None;
+        entry.paste_url = None;
+    }
+}
+
+/// Redact entries if presentation mode is on
+pub fn redact_if_active(mut entries: Vec<HistoryEntry>) -> Vec<HistoryEntry> {
+    if is_active() {
+        redact_entries(&mut entries);
+    }
+    entries
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn set_presentation_mode(app: AppHandle, enabled: bool) -> bool {
+    PRESENTING.store(enabled, Ordering::Relaxed);
+    log::info!("Presentation mode {}", if enabled { "on" } else { "off" });
+    crate::tray::rebuild(&app);
+    app.emit("presentation-mode-changed", enabled).ok();
+    enabled
+}
+
+#[tauri::command]
+pub fn get_presentation_mode() -> bool {
+    is_active()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_commands_are_not_allowed() {
+        for command in [
+            "get_entry_content",
+            "export_all_history",
+            "export_all_data",
+            "bulk_export_entries",
+            "list_trash",
+            "query_for_launcher",
+            "get_snippets",
+            "get_snippet_versions",
+            "compare_snippet_versions",
+            "reveal_credential",
+            "run_custom_prompt",
+            "some_future_command",
+        ] {
+            assert!(!allowed(command), "{}", command);
+        }
+        assert!(allowed("get_history"));
+        assert!(allowed("set_presentation_mode"));
+    }
+
+    #[test]
+    fn test_redaction_keeps_metadata() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::history::create_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO history (id, content, character_count, word_count, line_count, language, title, html)
+             VALUES (1, 'let token = \"abc\";', 18, 4, 1, 'rust', 'Token setup', '<b>token</b>')",
+            [],
+        )
+        .unwrap();
+
+        let mut entries = crate::history::get_entries(&conn, 10, 0).unwrap();
+        redact_entries(&mut entries);
+        assert_eq!(entries[0].content, "");
+        assert!(entries[0].html.is_none());
+        assert_eq!(entries[0].title.as_deref(), Some("Token setup"));
+        assert_eq!(entries[0].language.as_deref(), Some("rust"));
+        assert_eq!(entries[0].character_count, 18);
+    }
+}
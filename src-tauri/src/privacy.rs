@@ -0,0 +1,169 @@
+// Privacy mode: pauses clipboard capture, history writes and AI calls, either until
+// toggled off or for a fixed period. The resume timer lives here rather than in the
+// frontend so capture comes back even if the panel is never reopened.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::auth_gate::{self, GatedAction};
+use crate::error::AppError;
+
+struct PrivacyState {
+    enabled: bool,
+    resumes_at: Option<DateTime<Utc>>,
+    /// Bumped on every change so a stale resume timer doesn't undo a newer setting
+    generation: u64,
+}
+
+static PRIVACY: Mutex<PrivacyState> = Mutex::new(PrivacyState {
+    enabled: false,
+    resumes_at: None,
+    generation: 0,
+});
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrivacyStatus {
+    pub enabled: bool,
+    /// RFC 3339; `None` when paused indefinitely or not paused
+    pub resumes_at: Option<String>,
+}
+
+fn status_of(state: &PrivacyState) -> PrivacyStatus {
+    let enabled = state.enabled && state.resumes_at.map(|t| Utc::now() < t).unwrap_or(true);
+    PrivacyStatus {
+        enabled,
+        // A deadline that has passed (e.g. while asleep) isn't a pending resume
+        resumes_at: state.resumes_at.filter(|_| enabled).map(|t| t.to_rfc3339()),
+    }
+}
+
+/// Whether capture, history writes and AI calls are currently paused.
+/// Also checks the deadline directly: timers don't fire while the machine sleeps.
+pub fn is_privacy_mode_active() -> bool {
+    match PRIVACY.lock() {
+        Ok(state) => status_of(&state).enabled,
+        // Fail closed
+        Err(_) => true,
+    }
+}
+
+/// For commands that write history content: fails while privacy mode is on
+pub fn ensure_history_writable() -> Result<(), AppError> {
+    if is_privacy_mode_active() {
+        return Err(AppError::privacy_mode("History is paused while privacy mode is on"));
+    }
+    Ok(())
+}
+
+pub fn get_status() -> PrivacyStatus {
+    match PRIVACY.lock() {
+        Ok(state) => status_of(&state),
+        Err(_) => PrivacyStatus {
+            enabled: true,
+            resumes_at: None,
+        },
+    }
+}
+
+/// Reflect the current state in the tray and tell the frontend
+fn apply(app: &AppHandle, status: &PrivacyStatus) {
+    if let Some(tray) = app.tray_by_id(crate::tray::TRAY_ID) {
+        tray.set_icon(Some(crate::tray::tray_icon(status.enabled))).ok();
+        let tooltip = if status.enabled {
+            "Wingman (capture paused)"
+        } else {
+            "Wingman"
+        };
+        tray.set_tooltip(Some(tooltip)).ok();
+    }
+    // The menu's check mark is read from the state on rebuild
+    crate::tray::rebuild(app);
+    app.emit("privacy-mode-changed", status).ok();
+}
+
+/// Turn privacy mode on or off. With a duration, it turns itself off afterwards.
+pub fn set_privacy(app: &AppHandle, enabled: bool, duration: Option<Duration>) -> Result<PrivacyStatus, String> {
+    let (status, generation) = {
+        let mut state = PRIVACY.lock().map_err(|e| e.to_string())?;
+        state.enabled = enabled;
+        state.resumes_at = match duration {
+            Some(d) if enabled => Some(Utc::now() + chrono::Duration::from_std(d).map_err(|e| e.to_string())?),
+            _ => None,
+        };
+        state.generation += 1;
+        (status_of(&state), state.generation)
+    };
+
+    log::info!(
+        "Privacy mode {}{}",
+        if enabled { "enabled" } else { "disabled" },
+        status.resumes_at.as_deref().map(|t| format!(" until {}", t)).unwrap_or_default()
+    );
+    apply(app, &status);
+
+    if let (true, Some(duration)) = (enabled, duration) {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let resumed = match PRIVACY.lock() {
+                Ok(mut state) if state.generation == generation => {
+                    state.enabled = false;
+                    state.resumes_at = None;
+                    state.generation += 1;
+                    Some(status_of(&state))
+                }
+                _ => None,
+            };
+            if let Some(status) = resumed {
+                log::info!("Privacy mode period ended; capture resumed");
+                apply(&app, &status);
+            }
+        });
+    }
+
+    Ok(status)
+}
+
+/// Tray toggle: pauses indefinitely, or resumes once authenticated if that's gated
+pub fn toggle_privacy(app: &AppHandle) {
+    let enabled = !is_privacy_mode_active();
+    if !enabled && auth_gate::is_gated(GatedAction::DisablePrivacyMode) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match auth_gate::ensure(GatedAction::DisablePrivacyMode).await {
+                Ok(()) => {
+                    if let Err(e) = set_privacy(&app, false, None) {
+                        log::error!("Failed to toggle privacy mode: {}", e);
+                    }
+                }
+                Err(e) => log::info!("Privacy mode left on: {}", e),
+            }
+        });
+        return;
+    }
+    if let Err(e) = set_privacy(app, enabled, None) {
+        log::error!("Failed to toggle privacy mode: {}", e);
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Pause (or resume) capture. `duration_secs` resumes automatically after that long.
+/// Resuming asks for Touch ID / Windows Hello first when the disable_privacy_mode gate is on.
+#[tauri::command]
+pub async fn set_privacy_mode(app: AppHandle, enabled: bool, duration_secs: Option<u64>) -> Result<PrivacyStatus, AppError> {
+    if !enabled && is_privacy_mode_active() {
+        auth_gate::ensure(GatedAction::DisablePrivacyMode).await?;
+    }
+    set_privacy(&app, enabled, duration_secs.map(Duration::from_secs)).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn get_privacy_mode() -> PrivacyStatus {
+    get_status()
+}
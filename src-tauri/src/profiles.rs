@@ -0,0 +1,306 @@
+// Profiles partition history, snippets, custom AI prompts and the GitHub/Obsidian
+// configs (e.g. work vs personal). Each profile gets its own data directory under
+// profiles/<id>; the default profile keeps using the app data dir itself, so data
+// from before profiles existed belongs to it. Settings and the license stay global.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::error::{AppError, ErrorCode};
+use crate::history::{get_profile_db_path, open_database, prepare_connection};
+use crate::storage::{ensure_app_data_dir, StorageError};
+use crate::AppState;
+
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfilesData {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for ProfilesData {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: "Default".to_string(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+            }],
+        }
+    }
+}
+
+/// Active profile id, cached so storage paths don't re-read profiles.json each time
+static ACTIVE_PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Shortcut currently registered for cycling profiles
+static SWITCH_HOTKEY: Mutex<Option<String>> = Mutex::new(None);
+
+fn profiles_path() -> Result<PathBuf, StorageError> {
+    Ok(ensure_app_data_dir()?.join("profiles.json"))
+}
+
+pub fn load_profiles() -> Result<ProfilesData, StorageError> {
+    let path = profiles_path()?;
+    if !path.exists() {
+        return Ok(ProfilesData::default());
+    }
+    let content = fs::read_to_string(path)?;
+    let mut data: ProfilesData = serde_json::from_str(&content)?;
+    if !data.profiles.iter().any(|p| p.id == DEFAULT_PROFILE_ID) {
+        data.profiles.insert(0, ProfilesData::default().profiles.remove(0));
+    }
+    if !data.profiles.iter().any(|p| p.id == data.active) {
+        data.active = DEFAULT_PROFILE_ID.to_string();
+    }
+    Ok(data)
+}
+
+fn save_profiles(data: &ProfilesData) -> Result<(), StorageError> {
+    let content = serde_json::to_string_pretty(data)?;
+    fs::write(profiles_path()?, content)?;
+    Ok(())
+}
+
+pub fn active_profile_id() -> String {
+    if let Ok(cached) = ACTIVE_PROFILE.lock() {
+        if let Some(id) = cached.as_ref() {
+            return id.clone();
+        }
+    }
+    let id = load_profiles()
+        .map(|data| data.active)
+        .unwrap_or_else(|_| DEFAULT_PROFILE_ID.to_string());
+    if let Ok(mut cached) = ACTIVE_PROFILE.lock() {
+        *cached = Some(id.clone());
+    }
+    id
+}
+
+pub fn is_default_profile() -> bool {
+    active_profile_id() == DEFAULT_PROFILE_ID
+}
+
+/// Path of the active profile's data directory relative to the app data dir,
+/// or `None` for the default profile
+pub fn active_profile_subdir() -> Option<PathBuf> {
+    profile_subdir(&active_profile_id())
+}
+
+/// Path of a profile's data directory relative to the app data dir, or `None` for the
+/// default profile
+pub fn profile_subdir(id: &str) -> Option<PathBuf> {
+    if id == DEFAULT_PROFILE_ID {
+        None
+    } else {
+        Some(PathBuf::from("profiles").join(id))
+    }
+}
+
+/// Directory-safe id from a display name
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "profile".to_string()
+    } else {
+        slug
+    }
+}
+
+fn unique_id(name: &str, existing: &[Profile]) -> String {
+    let base = slugify(name);
+    let taken = |id: &str| existing.iter().any(|p| p.id == id);
+    if !taken(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|id| !taken(id))
+        .unwrap_or(base)
+}
+
+fn active_profile(data: &ProfilesData) -> Option<&Profile> {
+    data.profiles.iter().find(|p| p.id == data.active)
+}
+
+/// Make `id` the active profile: reopens the history database from the profile's
+/// directory and refreshes everything that shows profile data.
+pub fn switch_to(app: &AppHandle, id: &str) -> Result<Profile, String> {
+    let mut data = load_profiles().map_err(|e| e.to_string())?;
+    let profile = data
+        .profiles
+        .iter()
+        .find(|p| p.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Profile not found: {}", id))?;
+
+    if data.active != profile.id {
+        // Hold the connection for the whole switch, so nothing reads the new profile's
+        // files against the old profile's database or the other way round
+        let state = app.state::<AppState>();
+        let mut db = state.db.lock().map_err(|e| e.to_string())?;
+        let conn = get_profile_db_path(&profile.id)
+            .and_then(|path| open_database(&path))
+            .map_err(|e| format!("Failed to open profile database: {}", e))?;
+
+        let previous = data.active.clone();
+        data.active = profile.id.clone();
+        save_profiles(&data).map_err(|e| e.to_string())?;
+        *ACTIVE_PROFILE.lock().map_err(|e| e.to_string())? = Some(profile.id.clone());
+        if let Err(e) = prepare_connection(&conn) {
+            // Stay on the previous profile rather than mixing data
+            data.active = previous.clone();
+            save_profiles(&data).ok();
+            if let Ok(mut cached) = ACTIVE_PROFILE.lock() {
+                *cached = Some(previous);
+            }
+            return Err(format!("Failed to open profile database: {}", e));
+        }
+        *db = conn;
+        drop(db);
+        crate::history::clear_cache();
+        crate::saved_searches::mark_counts_stale();
+        crate::drafts::reset_session();
+        crate::rules::register_hotkeys(app);
+        log::info!("Switched to profile {}", profile.id);
+    }
+
+    crate::tray::rebuild(app);
+    app.emit("profile-switched", &profile).ok();
+    Ok(profile)
+}
+
+/// Switch to the profile after the active one, wrapping around
+pub fn cycle_profile(app: &AppHandle) {
+    let Ok(data) = load_profiles() else {
+        return;
+    };
+    if data.profiles.len() < 2 {
+        return;
+    }
+    let index = data.profiles.iter().position(|p| p.id == data.active).unwrap_or(0);
+    let next = &data.profiles[(index + 1) % data.profiles.len()];
+    if let Err(e) = switch_to(app, &next.id) {
+        log::error!("Failed to switch profile: {}", e);
+    }
+}
+
+/// (Re-)register the global shortcut that cycles profiles; `None` removes it
+pub fn register_switch_hotkey(app: &AppHandle, shortcut: Option<&str>) -> Result<(), String> {
+    let mut current = SWITCH_HOTKEY.lock().map_err(|e| e.to_string())?;
+    if current.as_deref() == shortcut {
+        return Ok(());
+    }
+    if let Some(previous) = current.take() {
+        app.global_shortcut().unregister(previous.as_str()).ok();
+    }
+    if let Some(shortcut) = shortcut.filter(|s| !s.trim().is_empty()) {
+        app.global_shortcut()
+            .on_shortcut(shortcut, |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    cycle_profile(app);
+                }
+            })
+            .map_err(|e| format!("Failed to register profile hotkey: {}", e))?;
+        *current = Some(shortcut.to_string());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn list_profiles() -> Result<ProfilesData, AppError> {
+    load_profiles().map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn get_active_profile() -> Result<Profile, AppError> {
+    let data = load_profiles()?;
+    active_profile(&data)
+        .cloned()
+        .ok_or_else(|| AppError::not_found("No active profile"))
+}
+
+/// Create an empty profile. Doesn't switch to it.
+#[tauri::command]
+pub fn create_profile(name: String) -> Result<Profile, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_input("Profile name cannot be empty"));
+    }
+    let mut data = load_profiles()?;
+    if data.profiles.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+        return Err(AppError::new(ErrorCode::Conflict, format!("A profile named \"{}\" already exists", name)));
+    }
+
+    let profile = Profile {
+        id: unique_id(name, &data.profiles),
+        name: name.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let dir = ensure_app_data_dir()?
+        .join("profiles")
+        .join(&profile.id);
+    fs::create_dir_all(&dir)?;
+
+    data.profiles.push(profile.clone());
+    save_profiles(&data)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, id: String) -> Result<Profile, AppError> {
+    switch_to(&app, &id).map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(id: &str) -> Profile {
+        Profile {
+            id: id.to_string(),
+            name: id.to_string(),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Work"), "work");
+        assert_eq!(slugify("  Client: ACME / 2024 "), "client-acme-2024");
+        assert_eq!(slugify("../.."), "profile");
+    }
+
+    #[test]
+    fn test_unique_id() {
+        let existing = vec![profile("default"), profile("work"), profile("work-2")];
+        assert_eq!(unique_id("Personal", &existing), "personal");
+        assert_eq!(unique_id("Work", &existing), "work-3");
+        assert_eq!(unique_id("Default", &existing), "default-2");
+    }
+}
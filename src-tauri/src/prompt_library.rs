@@ -0,0 +1,560 @@
+// Shareable prompt library files: AI presets, custom AI prompts, custom transformations,
+// transformation chains and AI workflows in one JSON document, so a team can pass a
+// curated toolbox around.
+//
+// Format (version 1):
+//
+//   {
+//     "format": "wingman-prompt-library",
+//     "version": 1,
+//     "exported_at": "2026-01-01T00:00:00Z",
+//     "ai_presets": [AIPreset],
+//     "custom_prompts": [CustomAIPrompt],
+//     "transformations": [CustomTransformation],
+//     "chains": [TransformationChain],
+//     "workflows": [AIWorkflow]
+//   }
+//
+// Items have the same shape as in their own JSON files; any list may be omitted.
+// Imports skip items whose content hash (what the item does, ignoring ids, names and
+// timestamps) matches one already installed, and re-point chain and workflow steps at
+// whichever id the referenced item ends up with. Custom transformations run code, so
+// they arrive disabled whatever the file says, until the user has looked them over.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::custom_prompts::validate_variables;
+use crate::error::AppError;
+use crate::premium::{load_ai_presets, save_ai_presets, AIPreset};
+use crate::storage::{
+    load_ai_workflows, load_custom_ai_prompts, load_custom_transformations, load_transformation_chains,
+    save_ai_workflows, save_custom_ai_prompts, save_custom_transformations, save_transformation_chains, AIWorkflow,
+    CustomAIPrompt, CustomTransformation, TransformationChain,
+};
+
+pub const LIBRARY_FORMAT: &str = "wingman-prompt-library";
+pub const LIBRARY_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum LibraryError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid library file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("Premium storage error: {0}")]
+    Premium(#[from] crate::premium::PremiumError),
+    #[error("Not a Wingman prompt library (format \"{0}\")")]
+    WrongFormat(String),
+    #[error("Library version {0} is newer than this version of Wingman supports")]
+    UnsupportedVersion(u32),
+    #[error("Invalid {kind} \"{name}\": {reason}")]
+    InvalidItem {
+        kind: &'static str,
+        name: String,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptLibrary {
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    #[serde(default)]
+    pub ai_presets: Vec<AIPreset>,
+    #[serde(default)]
+    pub custom_prompts: Vec<CustomAIPrompt>,
+    #[serde(default)]
+    pub transformations: Vec<CustomTransformation>,
+    #[serde(default)]
+    pub chains: Vec<TransformationChain>,
+    #[serde(default)]
+    pub workflows: Vec<AIWorkflow>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KindCounts {
+    pub added: u32,
+    pub duplicates: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    pub ai_presets: KindCounts,
+    pub custom_prompts: KindCounts,
+    pub transformations: KindCounts,
+    pub chains: KindCounts,
+    pub workflows: KindCounts,
+}
+
+// ============================================================================
+// Content Hashing
+// ============================================================================
+
+fn hash_parts(kind: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(kind.as_bytes());
+    for part in parts {
+        // Length-prefixed so ("ab", "c") and ("a", "bc") differ
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn preset_hash(preset: &AIPreset) -> String {
+    hash_parts("preset", &[preset.system_prompt.trim()])
+}
+
+fn prompt_hash(prompt: &CustomAIPrompt) -> String {
+    let variables = serde_json::to_string(&prompt.variables).unwrap_or_default();
+    hash_parts(
+        "prompt",
+        &[
+            prompt.system_prompt.trim(),
+            prompt.user_prompt.as_deref().unwrap_or("").trim(),
+            &variables,
+        ],
+    )
+}
+
+fn transformation_hash(transformation: &CustomTransformation) -> String {
+    hash_parts("transformation", &[&transformation.language, transformation.code.trim()])
+}
+
+/// Chains and workflows are hashed after their step references are remapped, so two
+/// copies pointing at the same (deduplicated) transforms compare equal
+fn chain_hash(chain: &TransformationChain) -> String {
+    let steps: Vec<String> = chain
+        .steps
+        .iter()
+        .map(|s| format!("{}:{}", s.step_type, s.transform_id))
+        .collect();
+    hash_parts("chain", &steps.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+fn workflow_hash(workflow: &AIWorkflow) -> String {
+    let steps: Vec<String> = workflow
+        .steps
+        .iter()
+        .map(|s| format!("{}:{}:{}", s.step_type, s.target_id, s.system_prompt.as_deref().unwrap_or("")))
+        .collect();
+    hash_parts("workflow", &steps.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+fn invalid(kind: &'static str, name: &str, reason: impl Into<String>) -> LibraryError {
+    LibraryError::InvalidItem {
+        kind,
+        name: name.to_string(),
+        reason: reason.into(),
+    }
+}
+
+fn require(kind: &'static str, name: &str, field: &str, value: &str) -> Result<(), LibraryError> {
+    if value.trim().is_empty() {
+        return Err(invalid(kind, name, format!("{} is empty", field)));
+    }
+    Ok(())
+}
+
+pub fn validate_library(library: &PromptLibrary) -> Result<(), LibraryError> {
+    if library.format != LIBRARY_FORMAT {
+        return Err(LibraryError::WrongFormat(library.format.clone()));
+    }
+    if library.version == 0 || library.version > LIBRARY_VERSION {
+        return Err(LibraryError::UnsupportedVersion(library.version));
+    }
+    for preset in &library.ai_presets {
+        require("AI preset", &preset.name, "id", &preset.id)?;
+        require("AI preset", &preset.name, "name", &preset.name)?;
+        require("AI preset", &preset.name, "system prompt", &preset.system_prompt)?;
+    }
+    for prompt in &library.custom_prompts {
+        require("custom prompt", &prompt.name, "id", &prompt.id)?;
+        require("custom prompt", &prompt.name, "name", &prompt.name)?;
+        require("custom prompt", &prompt.name, "system prompt", &prompt.system_prompt)?;
+        validate_variables(&prompt.variables).map_err(|e| invalid("custom prompt", &prompt.name, e.to_string()))?;
+    }
+    for transformation in &library.transformations {
+        require("transformation", &transformation.name, "id", &transformation.id)?;
+        require("transformation", &transformation.name, "name", &transformation.name)?;
+        require("transformation", &transformation.name, "language", &transformation.language)?;
+        require("transformation", &transformation.name, "code", &transformation.code)?;
+    }
+    for chain in &library.chains {
+        require("chain", &chain.name, "id", &chain.id)?;
+        if chain.steps.is_empty() {
+            return Err(invalid("chain", &chain.name, "it has no steps"));
+        }
+    }
+    for workflow in &library.workflows {
+        require("workflow", &workflow.name, "id", &workflow.id)?;
+        if workflow.steps.is_empty() {
+            return Err(invalid("workflow", &workflow.name, "it has no steps"));
+        }
+        if let Some(step) = workflow.steps.iter().find(|s| s.step_type != "ai" && s.step_type != "transform") {
+            return Err(invalid("workflow", &workflow.name, format!("unknown step type \"{}\"", step.step_type)));
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Merging
+// ============================================================================
+
+/// Add `incoming` items to `existing`, skipping content duplicates. Ids that clash with a
+/// different existing item are replaced; every incoming id ends up in `remap`.
+fn merge_items<T>(
+    existing: &mut Vec<T>,
+    incoming: Vec<T>,
+    hash: impl Fn(&T) -> String,
+    id_of: impl Fn(&mut T) -> &mut String,
+    remap: &mut HashMap<String, String>,
+) -> KindCounts {
+    let mut counts = KindCounts::default();
+    let mut by_hash: HashMap<String, String> = HashMap::new();
+    let mut ids: HashSet<String> = HashSet::new();
+    for item in existing.iter_mut() {
+        let id = id_of(item).clone();
+        by_hash.entry(hash(item)).or_insert_with(|| id.clone());
+        ids.insert(id);
+    }
+
+    for mut item in incoming {
+        let incoming_id = id_of(&mut item).clone();
+        let item_hash = hash(&item);
+        if let Some(existing_id) = by_hash.get(&item_hash) {
+            remap.insert(incoming_id, existing_id.clone());
+            counts.duplicates += 1;
+            continue;
+        }
+        let id = if ids.contains(&incoming_id) {
+            uuid::Uuid::new_v4().to_string()
+        } else {
+            incoming_id.clone()
+        };
+        *id_of(&mut item) = id.clone();
+        remap.insert(incoming_id, id.clone());
+        by_hash.insert(item_hash, id.clone());
+        ids.insert(id);
+        existing.push(item);
+        counts.added += 1;
+    }
+    counts
+}
+
+fn remapped(remap: &HashMap<String, String>, id: &str) -> String {
+    remap.get(id).cloned().unwrap_or_else(|| id.to_string())
+}
+
+/// Installed items to merge into: the current ones, or none when replacing
+struct Installed {
+    ai_presets: Vec<AIPreset>,
+    custom_prompts: Vec<CustomAIPrompt>,
+    transformations: Vec<CustomTransformation>,
+    chains: Vec<TransformationChain>,
+    workflows: Vec<AIWorkflow>,
+}
+
+fn merge_library(installed: &mut Installed, library: PromptLibrary) -> ImportSummary {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut summary = ImportSummary::default();
+    let mut transform_ids = HashMap::new();
+    let mut ai_ids = HashMap::new();
+
+    summary.ai_presets = merge_items(
+        &mut installed.ai_presets,
+        library.ai_presets,
+        preset_hash,
+        |p| &mut p.id,
+        &mut ai_ids,
+    );
+    let prompts = library
+        .custom_prompts
+        .into_iter()
+        .map(|mut p| {
+            p.created_at = now.clone();
+            p.updated_at = now.clone();
+            p
+        })
+        .collect();
+    summary.custom_prompts = merge_items(
+        &mut installed.custom_prompts,
+        prompts,
+        prompt_hash,
+        |p| &mut p.id,
+        &mut ai_ids,
+    );
+    let transformations = library
+        .transformations
+        .into_iter()
+        .map(|mut t| {
+            t.enabled = false;
+            t.created_at = now.clone();
+            t.updated_at = now.clone();
+            t
+        })
+        .collect();
+    summary.transformations = merge_items(
+        &mut installed.transformations,
+        transformations,
+        transformation_hash,
+        |t| &mut t.id,
+        &mut transform_ids,
+    );
+
+    let chains = library
+        .chains
+        .into_iter()
+        .map(|mut chain| {
+            for step in &mut chain.steps {
+                step.transform_id = remapped(&transform_ids, &step.transform_id);
+            }
+            chain.created_at = now.clone();
+            chain.updated_at = now.clone();
+            chain
+        })
+        .collect();
+    summary.chains = merge_items(&mut installed.chains, chains, chain_hash, |c| &mut c.id, &mut HashMap::new());
+
+    let workflows = library
+        .workflows
+        .into_iter()
+        .map(|mut workflow| {
+            for step in &mut workflow.steps {
+                if step.step_type == "ai" {
+                    step.target_id = remapped(&ai_ids, &step.target_id);
+                }
+            }
+            workflow.created_at = now.clone();
+            workflow.updated_at = now.clone();
+            workflow
+        })
+        .collect();
+    summary.workflows = merge_items(
+        &mut installed.workflows,
+        workflows,
+        workflow_hash,
+        |w| &mut w.id,
+        &mut HashMap::new(),
+    );
+    summary
+}
+
+// ============================================================================
+// Export / Import
+// ============================================================================
+
+pub fn export_library(path: &Path) -> Result<PromptLibrary, LibraryError> {
+    let library = PromptLibrary {
+        format: LIBRARY_FORMAT.to_string(),
+        version: LIBRARY_VERSION,
+        exported_at: Some(chrono::Utc::now().to_rfc3339()),
+        ai_presets: load_ai_presets()?.presets,
+        custom_prompts: load_custom_ai_prompts()?.prompts,
+        transformations: load_custom_transformations()?.transformations,
+        chains: load_transformation_chains()?.chains,
+        workflows: load_ai_workflows()?.workflows,
+    };
+    fs::write(path, serde_json::to_string_pretty(&library)?)?;
+    Ok(library)
+}
+
+fn take_unless<T>(items: &mut Vec<T>, replace: bool) -> Vec<T> {
+    if replace {
+        Vec::new()
+    } else {
+        std::mem::take(items)
+    }
+}
+
+/// Import a library file. With `merge` the items are added to what's installed;
+/// without it, each kind present in the file replaces the installed items of that
+/// kind (kinds the file leaves empty are untouched).
+pub fn import_library(path: &Path, merge: bool) -> Result<ImportSummary, LibraryError> {
+    let library: PromptLibrary = serde_json::from_str(&fs::read_to_string(path)?)?;
+    validate_library(&library)?;
+
+    let mut presets = load_ai_presets()?;
+    let mut prompts = load_custom_ai_prompts()?;
+    let mut transformations = load_custom_transformations()?;
+    let mut chains = load_transformation_chains()?;
+    let mut workflows = load_ai_workflows()?;
+
+    // When replacing, a kind the file carries starts from nothing
+    let mut installed = Installed {
+        ai_presets: take_unless(&mut presets.presets, !merge && !library.ai_presets.is_empty()),
+        custom_prompts: take_unless(&mut prompts.prompts, !merge && !library.custom_prompts.is_empty()),
+        transformations: take_unless(
+            &mut transformations.transformations,
+            !merge && !library.transformations.is_empty(),
+        ),
+        chains: take_unless(&mut chains.chains, !merge && !library.chains.is_empty()),
+        workflows: take_unless(&mut workflows.workflows, !merge && !library.workflows.is_empty()),
+    };
+    let summary = merge_library(&mut installed, library);
+
+    presets.presets = installed.ai_presets;
+    prompts.prompts = installed.custom_prompts;
+    transformations.transformations = installed.transformations;
+    chains.chains = installed.chains;
+    workflows.workflows = installed.workflows;
+    save_ai_presets(&presets)?;
+    save_custom_ai_prompts(&prompts)?;
+    save_custom_transformations(&transformations)?;
+    save_transformation_chains(&chains)?;
+    save_ai_workflows(&workflows)?;
+    Ok(summary)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Write all prompts, transformations, chains and workflows to `path`. Returns how
+/// many items were exported.
+#[tauri::command]
+pub fn export_ai_prompts(path: String) -> Result<usize, AppError> {
+    let library = export_library(Path::new(&path))?;
+    Ok(library.ai_presets.len()
+        + library.custom_prompts.len()
+        + library.transformations.len()
+        + library.chains.len()
+        + library.workflows.len())
+}
+
+#[tauri::command]
+pub fn import_ai_prompts(path: String, merge: bool) -> Result<ImportSummary, AppError> {
+    import_library(Path::new(&path), merge).map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ChainStep;
+
+    fn transformation(id: &str, code: &str) -> CustomTransformation {
+        CustomTransformation {
+            id: id.to_string(),
+            name: format!("Transform {}", id),
+            description: String::new(),
+            code: code.to_string(),
+            language: "javascript".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            enabled: true,
+            icon: None,
+            pinned_to_toolbar: None,
+        }
+    }
+
+    fn chain(id: &str, transform_id: &str) -> TransformationChain {
+        TransformationChain {
+            id: id.to_string(),
+            name: format!("Chain {}", id),
+            description: String::new(),
+            steps: vec![ChainStep {
+                id: "s1".to_string(),
+                step_type: "custom".to_string(),
+                transform_id: transform_id.to_string(),
+                name: "step".to_string(),
+                params: None,
+            }],
+            created_at: String::new(),
+            updated_at: String::new(),
+            enabled: true,
+        }
+    }
+
+    fn library() -> PromptLibrary {
+        PromptLibrary {
+            format: LIBRARY_FORMAT.to_string(),
+            version: LIBRARY_VERSION,
+            exported_at: None,
+            ai_presets: vec![],
+            custom_prompts: vec![],
+            transformations: vec![],
+            chains: vec![],
+            workflows: vec![],
+        }
+    }
+
+    fn installed() -> Installed {
+        Installed {
+            ai_presets: vec![],
+            custom_prompts: vec![],
+            transformations: vec![],
+            chains: vec![],
+            workflows: vec![],
+        }
+    }
+
+    #[test]
+    fn test_duplicates_are_skipped_and_references_remapped() {
+        let mut current = installed();
+        current.transformations.push(transformation("local-upper", "return text.toUpperCase()"));
+
+        let mut incoming = library();
+        // Same code under another id and name: a duplicate of local-upper
+        incoming.transformations.push(transformation("shared-upper", "return text.toUpperCase() "));
+        // Different code whose id clashes with the installed one
+        incoming.transformations.push(transformation("local-upper", "return text.trim()"));
+        incoming.chains.push(chain("c1", "shared-upper"));
+
+        let summary = merge_library(&mut current, incoming);
+        assert_eq!(summary.transformations.added, 1);
+        assert_eq!(summary.transformations.duplicates, 1);
+        assert_eq!(current.transformations.len(), 2);
+        assert_ne!(current.transformations[1].id, "local-upper");
+        assert!(!current.transformations[1].enabled);
+        // The chain now points at the transformation that was already installed
+        assert_eq!(current.chains[0].steps[0].transform_id, "local-upper");
+
+        // Importing the same chain again is a duplicate
+        let mut again = library();
+        again.chains.push(chain("c2", "local-upper"));
+        assert_eq!(merge_library(&mut current, again).chains.duplicates, 1);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(validate_library(&library()).is_ok());
+
+        let mut wrong_format = library();
+        wrong_format.format = "something-else".to_string();
+        assert!(matches!(validate_library(&wrong_format), Err(LibraryError::WrongFormat(_))));
+
+        let mut future = library();
+        future.version = LIBRARY_VERSION + 1;
+        assert!(matches!(validate_library(&future), Err(LibraryError::UnsupportedVersion(_))));
+
+        let mut empty_code = library();
+        empty_code.transformations.push(transformation("t", "  "));
+        assert!(matches!(validate_library(&empty_code), Err(LibraryError::InvalidItem { .. })));
+    }
+
+    #[test]
+    fn test_minimal_file_parses() {
+        let library: PromptLibrary =
+            serde_json::from_str(r#"{"format": "wingman-prompt-library", "version": 1}"#).unwrap();
+        assert!(validate_library(&library).is_ok());
+        assert!(library.custom_prompts.is_empty());
+    }
+}
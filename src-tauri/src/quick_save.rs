@@ -0,0 +1,167 @@
+// Quick save: write content straight to a configured folder, named from a template
+// like `{{date}}-{{language}}.{{ext}}`, so exporting a snippet doesn't go through
+// the save dialog. What happens when the name is taken follows the overwrite policy.
+
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::custom_prompts::substitute;
+use crate::error::AppError;
+use crate::storage::{load_settings, OverwritePolicy};
+
+/// Highest "name (n).ext" tried before giving up on renaming
+const MAX_RENAME_ATTEMPTS: u32 = 1000;
+
+#[derive(Error, Debug)]
+pub enum QuickSaveError {
+    #[error("No quick save folder is configured and no Downloads folder was found")]
+    NoDirectory,
+    #[error("{0} already exists")]
+    Exists(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Fill in a filename template. Placeholders: `{{date}}` (2024-05-01), `{{time}}`
+/// (153012), `{{datetime}}`, `{{language}}` and `{{ext}}`.
+pub fn render_filename(template: &str, language: &str, now: DateTime<Local>) -> String {
+    let (_, extensions) = crate::get_file_filter(language);
+    let values = HashMap::from([
+        ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+        ("time".to_string(), now.format("%H%M%S").to_string()),
+        ("datetime".to_string(), now.format("%Y-%m-%d-%H%M%S").to_string()),
+        ("language".to_string(), language.to_string()),
+        ("ext".to_string(), extensions[0].to_string()),
+    ]);
+    let name = sanitize(&substitute(template, &values));
+    if name.is_empty() {
+        format!("snippet.{}", extensions[0])
+    } else {
+        name
+    }
+}
+
+/// Drop characters that aren't allowed in filenames on any platform, so a template
+/// can never point outside the quick save folder
+fn sanitize(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .collect();
+    cleaned.trim().trim_start_matches('.').trim().to_string()
+}
+
+/// `name (n).ext` for the n-th rename of `name.ext`
+fn numbered(filename: &str, n: u32) -> String {
+    let path = Path::new(filename);
+    let stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    match path.extension() {
+        Some(ext) => format!("{} ({}).{}", stem, n, ext.to_string_lossy()),
+        None => format!("{} ({})", stem, n),
+    }
+}
+
+fn quick_save_dir(configured: Option<&str>) -> Result<PathBuf, QuickSaveError> {
+    match configured.map(str::trim).filter(|d| !d.is_empty()) {
+        Some(dir) => match dir.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)).ok_or(QuickSaveError::NoDirectory),
+            None => Ok(PathBuf::from(dir)),
+        },
+        None => dirs::download_dir().ok_or(QuickSaveError::NoDirectory),
+    }
+}
+
+/// Write `content` as `filename` in `dir` and return the path actually written.
+/// New files are created exclusively so a concurrent save can't be clobbered.
+pub fn write_file(dir: &Path, filename: &str, content: &str, policy: OverwritePolicy) -> Result<PathBuf, QuickSaveError> {
+    std::fs::create_dir_all(dir)?;
+    let create_new = |path: &Path| OpenOptions::new().write(true).create_new(true).open(path);
+
+    let path = dir.join(filename);
+    match policy {
+        OverwritePolicy::Overwrite => {
+            std::fs::write(&path, content)?;
+            Ok(path)
+        }
+        OverwritePolicy::Fail => match create_new(&path) {
+            Ok(mut file) => {
+                file.write_all(content.as_bytes())?;
+                Ok(path)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                Err(QuickSaveError::Exists(path.to_string_lossy().to_string()))
+            }
+            Err(e) => Err(e.into()),
+        },
+        OverwritePolicy::Rename => {
+            for n in 1..=MAX_RENAME_ATTEMPTS {
+                let candidate = if n == 1 { path.clone() } else { dir.join(numbered(filename, n)) };
+                match create_new(&candidate) {
+                    Ok(mut file) => {
+                        file.write_all(content.as_bytes())?;
+                        return Ok(candidate);
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            Err(QuickSaveError::Exists(path.to_string_lossy().to_string()))
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Save `content` to the quick save folder without a dialog; returns the written path
+#[tauri::command]
+pub fn quick_save(content: String, language: Option<String>) -> Result<String, AppError> {
+    let settings = load_settings()?.quick_save;
+    let dir = quick_save_dir(settings.directory.as_deref())?;
+    let filename = render_filename(
+        &settings.filename_template,
+        language.as_deref().unwrap_or("plaintext"),
+        Local::now(),
+    );
+    let path = write_file(&dir, &filename, &content, settings.overwrite)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_filename() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 15, 30, 12).unwrap();
+        assert_eq!(render_filename("{{date}}-{{language}}.{{ext}}", "rust", now), "2024-05-01-rust.rs");
+        assert_eq!(render_filename("{{datetime}}.{{ext}}", "unknown", now), "2024-05-01-153012.txt");
+        assert_eq!(render_filename("../../{{language}}.{{ext}}", "python", now), "python.py");
+        assert_eq!(render_filename("///", "json", now), "snippet.json");
+    }
+
+    #[test]
+    fn test_overwrite_policies() {
+        let dir = std::env::temp_dir().join(format!("wingman-quick-save-{}", uuid::Uuid::new_v4()));
+
+        let first = write_file(&dir, "a.txt", "one", OverwritePolicy::Rename).unwrap();
+        let second = write_file(&dir, "a.txt", "two", OverwritePolicy::Rename).unwrap();
+        assert_eq!(first.file_name().unwrap(), "a.txt");
+        assert_eq!(second.file_name().unwrap(), "a (2).txt");
+
+        assert!(matches!(
+            write_file(&dir, "a.txt", "three", OverwritePolicy::Fail),
+            Err(QuickSaveError::Exists(_))
+        ));
+        write_file(&dir, "a.txt", "four", OverwritePolicy::Overwrite).unwrap();
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "four");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
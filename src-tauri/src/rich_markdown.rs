@@ -0,0 +1,638 @@
+// Rich text to Markdown. Word, Pages and Google Docs only put HTML and RTF on the
+// pasteboard, so captures from them can be stored as Markdown instead of as a flat
+// plain-text fallback.
+//
+// HTML goes through htmd, after unwrapping the bold tag Google Docs puts around
+// everything. RTF is handled by a small parser here: bold and italic runs, paragraphs
+// and line breaks, \u and \'hh characters, list markers and HYPERLINK fields, with font,
+// color and style tables and other ignorable destinations skipped.
+//
+// rich_to_markdown in the settings turns conversion on for every capture or only for
+// the listed source apps. The clipboard monitor reads captures through
+// read_clipboard_capture, which matches the setting against the app the copy came from
+// (frontmost::copy_source) and leaves code alone: editors put highlighted HTML on the
+// pasteboard, and the plain text is what should be kept there.
+
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::error::AppError;
+use crate::frontmost::FrontmostApp;
+use crate::native_clipboard::RichClipboardContent;
+use crate::storage::RichToMarkdownSettings;
+
+/// Destinations whose text is never content
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "annotation",
+    "bkmkend",
+    "bkmkstart",
+    "colorschememapping",
+    "colortbl",
+    "datastore",
+    "filetbl",
+    "fonttbl",
+    "footer",
+    "footerl",
+    "footerr",
+    "footnote",
+    "generator",
+    "header",
+    "headerl",
+    "headerr",
+    "info",
+    "latentstyles",
+    "listoverridetable",
+    "listtable",
+    "mmathPr",
+    "nonshppict",
+    "object",
+    "pict",
+    "revtbl",
+    "rsidtbl",
+    "stylesheet",
+    "themedata",
+    "xmlnstbl",
+];
+
+/// Google Docs wraps the whole clipboard HTML in `<b style="font-weight:normal"
+/// id="docs-internal-guid-...">`, which would make everything bold
+fn docs_wrapper() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?i)<b\b[^>]*id="docs-internal-guid[^"]*"[^>]*>"#).unwrap())
+}
+
+fn unwrap_google_docs(html: &str) -> String {
+    let Some(wrapper) = docs_wrapper().find(html) else {
+        return html.to_string();
+    };
+    let mut unwrapped = format!("{}<div>{}", &html[..wrapper.start()], &html[wrapper.end()..]);
+    if let Some(close) = unwrapped.to_ascii_lowercase().rfind("</b>") {
+        unwrapped.replace_range(close..close + 4, "</div>");
+    }
+    unwrapped
+}
+
+pub fn html_to_markdown(html: &str) -> Result<String, String> {
+    let converter = htmd::HtmlToMarkdown::builder()
+        .skip_tags(vec!["head", "script", "style", "meta", "title"])
+        .build();
+    converter
+        .convert(&unwrap_google_docs(html))
+        .map(|markdown| tidy(&markdown))
+        .map_err(|e| format!("Failed to convert HTML: {}", e))
+}
+
+pub fn is_rtf(input: &str) -> bool {
+    input.trim_start().starts_with("{\\rtf")
+}
+
+/// Windows-1252, the code page \'hh escapes use in practice
+fn cp1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}', '\u{90}',
+        '\u{2018}', '\u{2019}', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Destination {
+    Text,
+    Skip,
+    /// \listtext or \pntext: the bullet or number of a list item
+    ListMarker,
+    FieldInstruction,
+    FieldResult,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Group {
+    bold: bool,
+    italic: bool,
+    /// Characters to skip after \u
+    uc: usize,
+    destination: Destination,
+    /// The group holds a \field
+    field: bool,
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Self {
+            bold: false,
+            italic: false,
+            uc: 1,
+            destination: Destination::Text,
+            field: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Run {
+    bold: bool,
+    italic: bool,
+    text: String,
+}
+
+#[derive(Default)]
+struct RtfParser {
+    stack: Vec<Group>,
+    current: Group,
+    runs: Vec<Run>,
+    list_marker: String,
+    field_instruction: String,
+    field_result: String,
+    /// Fallback characters left to skip after a \u
+    skip: usize,
+    /// A \* was just read: the group is ignorable unless its control word is known
+    ignorable: bool,
+    /// First half of a UTF-16 surrogate pair written as two \u
+    high_surrogate: Option<u32>,
+}
+
+impl RtfParser {
+    fn push_run(&mut self, text: &str) {
+        let (bold, italic) = (self.current.bold, self.current.italic);
+        match self.runs.last_mut() {
+            Some(run) if run.bold == bold && run.italic == italic => run.text.push_str(text),
+            _ => self.runs.push(Run {
+                bold,
+                italic,
+                text: text.to_string(),
+            }),
+        }
+    }
+
+    fn text(&mut self, c: char) {
+        if self.skip > 0 {
+            self.skip -= 1;
+            return;
+        }
+        // A high surrogate without its low half
+        if self.high_surrogate.take().is_some() {
+            self.emit('\u{FFFD}');
+        }
+        self.emit(c);
+    }
+
+    fn emit(&mut self, c: char) {
+        match self.current.destination {
+            Destination::Text => self.push_run(c.encode_utf8(&mut [0; 4])),
+            Destination::ListMarker => self.list_marker.push(c),
+            Destination::FieldInstruction => self.field_instruction.push(c),
+            Destination::FieldResult => self.field_result.push(c),
+            Destination::Skip => {}
+        }
+    }
+
+    /// Paragraph and line breaks; kept out of field results so links stay on one line
+    fn breaks(&mut self, text: &str) {
+        if self.current.destination == Destination::Text {
+            self.push_run(text);
+        }
+    }
+
+    fn open_group(&mut self) {
+        self.stack.push(self.current);
+        self.current.field = false;
+    }
+
+    fn close_group(&mut self) {
+        let closed = self.current;
+        self.current = self.stack.pop().unwrap_or_default();
+        if closed.destination == Destination::ListMarker && self.current.destination != Destination::ListMarker {
+            let marker = std::mem::take(&mut self.list_marker);
+            let marker = marker.trim();
+            let numbered = marker.starts_with(|c: char| c.is_ascii_digit()) && marker.ends_with(['.', ')']);
+            self.breaks(&if numbered { format!("{} ", marker) } else { "- ".to_string() });
+        }
+        if closed.field {
+            let instruction = std::mem::take(&mut self.field_instruction);
+            let result = std::mem::take(&mut self.field_result);
+            let url = instruction
+                .trim()
+                .strip_prefix("HYPERLINK")
+                .and_then(|rest| rest.split('"').nth(1))
+                .filter(|url| !url.is_empty());
+            let text = match url {
+                Some(url) if !result.trim().is_empty() => format!("[{}]({})", result.trim(), url),
+                _ => result,
+            };
+            self.breaks(&text);
+        }
+    }
+
+    /// One UTF-16 unit from \u; surrogate pairs come as two in a row
+    fn unicode(&mut self, unit: u32) {
+        let high = self.high_surrogate.take();
+        match unit {
+            0xD800..=0xDBFF => {
+                if high.is_some() {
+                    self.emit('\u{FFFD}');
+                }
+                self.high_surrogate = Some(unit);
+            }
+            0xDC00..=0xDFFF => {
+                let combined = high.and_then(|high| char::from_u32(0x10000 + ((high - 0xD800) << 10) + (unit - 0xDC00)));
+                self.emit(combined.unwrap_or('\u{FFFD}'));
+            }
+            _ => {
+                if high.is_some() {
+                    self.emit('\u{FFFD}');
+                }
+                self.emit(char::from_u32(unit).unwrap_or('\u{FFFD}'));
+            }
+        }
+    }
+
+    fn control_word(&mut self, word: &str, param: Option<i32>) {
+        let ignorable = std::mem::take(&mut self.ignorable);
+        let on = param != Some(0);
+        match word {
+            "par" | "sect" | "page" => self.breaks("\n\n"),
+            "line" => self.breaks("\n"),
+            "tab" => self.text('\t'),
+            "b" => self.current.bold = on,
+            "i" => self.current.italic = on,
+            "plain" => {
+                self.current.bold = false;
+                self.current.italic = false;
+            }
+            "uc" => self.current.uc = param.unwrap_or(1).max(0) as usize,
+            "u" => {
+                if let Some(param) = param {
+                    // Negative for code points above 32767
+                    let code = (if param < 0 { param + 65536 } else { param }) as u32;
+                    self.unicode(code);
+                    self.skip = self.current.uc;
+                }
+            }
+            "emdash" => self.text('—'),
+            "endash" => self.text('–'),
+            "bullet" => self.text('•'),
+            "lquote" => self.text('\u{2018}'),
+            "rquote" => self.text('\u{2019}'),
+            "ldblquote" => self.text('“'),
+            "rdblquote" => self.text('”'),
+            "listtext" | "pntext" => self.current.destination = Destination::ListMarker,
+            "field" => self.current.field = true,
+            "fldinst" => self.current.destination = Destination::FieldInstruction,
+            "fldrslt" => self.current.destination = Destination::FieldResult,
+            _ if ignorable || SKIPPED_DESTINATIONS.contains(&word) => self.current.destination = Destination::Skip,
+            _ => {}
+        }
+    }
+
+    fn parse(mut self, rtf: &str) -> Vec<Run> {
+        let bytes = rtf.as_bytes();
+        let mut at = 0;
+        while at < bytes.len() {
+            match bytes[at] {
+                b'{' => {
+                    self.open_group();
+                    at += 1;
+                }
+                b'}' => {
+                    self.close_group();
+                    at += 1;
+                }
+                b'\\' => at = self.escape(rtf, at + 1),
+                b'\r' | b'\n' => at += 1,
+                _ => {
+                    // Multi-byte UTF-8 shouldn't appear in RTF, but don't split it if it does
+                    let c = rtf[at..].chars().next().unwrap_or('\u{FFFD}');
+                    self.text(c);
+                    at += c.len_utf8();
+                }
+            }
+        }
+        self.runs
+    }
+
+    /// Handle the control word or symbol starting at `at` (after the backslash); returns
+    /// where parsing continues
+    fn escape(&mut self, rtf: &str, mut at: usize) -> usize {
+        let bytes = rtf.as_bytes();
+        let Some(&first) = bytes.get(at) else {
+            return at;
+        };
+        if first.is_ascii_alphabetic() {
+            let start = at;
+            while at < bytes.len() && bytes[at].is_ascii_alphabetic() {
+                at += 1;
+            }
+            let word = &rtf[start..at];
+            let param_start = at;
+            if at < bytes.len() && bytes[at] == b'-' {
+                at += 1;
+            }
+            while at < bytes.len() && bytes[at].is_ascii_digit() {
+                at += 1;
+            }
+            let param = rtf[param_start..at].parse::<i32>().ok();
+            // A single space delimits the control word and isn't text
+            if at < bytes.len() && bytes[at] == b' ' {
+                at += 1;
+            }
+            self.control_word(word, param);
+            return at;
+        }
+        match first {
+            b'\'' => {
+                if let Some(byte) = rtf.get(at + 1..at + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    self.text(cp1252(byte));
+                    return at + 3;
+                }
+                at + 1
+            }
+            b'*' => {
+                self.ignorable = true;
+                at + 1
+            }
+            b'~' => {
+                self.text('\u{a0}');
+                at + 1
+            }
+            b'_' => {
+                self.text('-');
+                at + 1
+            }
+            b'\r' | b'\n' => {
+                self.breaks("\n\n");
+                at + 1
+            }
+            // Optional hyphen and formula characters
+            b'-' | b'|' | b':' => at + 1,
+            _ => {
+                let c = rtf[at..].chars().next().unwrap_or('\u{FFFD}');
+                self.text(c);
+                at + c.len_utf8()
+            }
+        }
+    }
+}
+
+/// Emphasis markers go around the text of each line, outside its surrounding whitespace,
+/// since "** bold**" and markers across a blank line aren't emphasis
+fn render_runs(runs: &[Run]) -> String {
+    let mut out = String::new();
+    for run in runs {
+        let marker = match (run.bold, run.italic) {
+            (true, true) => "***",
+            (true, false) => "**",
+            (false, true) => "*",
+            (false, false) => "",
+        };
+        if marker.is_empty() {
+            out.push_str(&run.text);
+            continue;
+        }
+        for (index, line) in run.text.split('\n').enumerate() {
+            if index > 0 {
+                out.push('\n');
+            }
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                out.push_str(line);
+                continue;
+            }
+            let start = line.len() - line.trim_start().len();
+            let end = line.trim_end().len();
+            out.push_str(&line[..start]);
+            out.push_str(marker);
+            out.push_str(trimmed);
+            out.push_str(marker);
+            out.push_str(&line[end..]);
+        }
+    }
+    out
+}
+
+/// Trailing spaces off, at most one blank line in a row
+fn tidy(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut blank_lines = 0;
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+pub fn rtf_to_markdown(rtf: &str) -> String {
+    tidy(&render_runs(&RtfParser::default().parse(rtf)))
+}
+
+/// Markdown for RTF (starts with `{\rtf`) or HTML
+pub fn to_markdown(input: &str) -> Result<String, String> {
+    if is_rtf(input) {
+        Ok(rtf_to_markdown(input))
+    } else {
+        html_to_markdown(input)
+    }
+}
+
+/// Whether captures from this app are stored as Markdown
+pub fn applies_to(settings: &RichToMarkdownSettings, app_name: Option<&str>, bundle_id: Option<&str>) -> bool {
+    if !settings.enabled {
+        return false;
+    }
+    if settings.apps.iter().all(|app| app.trim().is_empty()) {
+        return true;
+    }
+    settings.apps.iter().map(|app| app.trim()).any(|app| {
+        app_name.is_some_and(|name| app.eq_ignore_ascii_case(name))
+            || bundle_id.is_some_and(|id| app.eq_ignore_ascii_case(id))
+    })
+}
+
+/// Markdown for what's on the clipboard, when the setting applies to the app it was
+/// copied from and the plain text isn't code. RTF goes through the parser here rather
+/// than the HTML macOS derives from it.
+pub fn capture_markdown(
+    settings: &RichToMarkdownSettings,
+    clipboard: &RichClipboardContent,
+    source: Option<&FrontmostApp>,
+) -> Option<String> {
+    let (name, bundle_id) = (source.map(|app| app.name.as_str()), source.and_then(|app| app.bundle_id.as_deref()));
+    if !applies_to(settings, name, bundle_id) {
+        return None;
+    }
+    let plain = clipboard.text.as_deref().unwrap_or_default();
+    if is_code(plain, clipboard.html.as_deref()) {
+        return None;
+    }
+    let markdown = match (&clipboard.html, &clipboard.rtf) {
+        (Some(html), _) if !clipboard.html_from_rtf => html_to_markdown(html),
+        (_, Some(rtf)) if is_rtf(rtf) => Ok(rtf_to_markdown(rtf)),
+        (Some(html), _) => html_to_markdown(html),
+        (None, _) => return None,
+    };
+    match markdown {
+        Ok(markdown) if !markdown.is_empty() && markdown != plain.trim() => Some(markdown),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Keeping the plain text of a rich capture: {}", e);
+            None
+        }
+    }
+}
+
+/// Copies from editors and terminals, whose HTML is only syntax highlighting: the
+/// markup is preformatted (VS Code and JetBrains use white-space: pre) or the text
+/// itself is detected as a code language
+fn is_code(plain: &str, html: Option<&str>) -> bool {
+    let preformatted = html.is_some_and(|html| {
+        let lower = html.to_ascii_lowercase();
+        lower.contains("<pre") || lower.contains("white-space: pre") || lower.contains("white-space:pre")
+    });
+    preformatted
+        || (!plain.trim().is_empty()
+            && !matches!(crate::detect_language(plain.to_string()).as_str(), "plaintext" | "markdown"))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardCapture {
+    /// Markdown when `converted`, the plain text otherwise
+    pub text: Option<String>,
+    pub converted: bool,
+    /// Name of the app the copy came from, when known
+    pub source_app: Option<String>,
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// What the clipboard monitor should capture: the plain text, or its Markdown when
+/// rich_to_markdown applies to the copy source
+#[tauri::command]
+pub fn read_clipboard_capture() -> Result<ClipboardCapture, AppError> {
+    if crate::privacy::is_privacy_mode_active() {
+        return Err(AppError::privacy_mode("Capture is paused while privacy mode is on"));
+    }
+    let clipboard = crate::native_clipboard::read_clipboard_rich().map_err(AppError::unsupported)?;
+    let source = crate::frontmost::copy_source();
+    let settings = crate::storage::load_settings()?;
+    let markdown = capture_markdown(&settings.rich_to_markdown, &clipboard, source.as_ref());
+    Ok(ClipboardCapture {
+        converted: markdown.is_some(),
+        text: markdown.or(clipboard.text),
+        source_app: source.map(|app| app.name),
+    })
+}
+
+/// Convert copied HTML or RTF (Word, Pages, Google Docs) to Markdown
+#[tauri::command]
+pub fn convert_rich_to_markdown(html_or_rtf: String) -> Result<String, AppError> {
+    if html_or_rtf.trim().is_empty() {
+        return Err(AppError::invalid_input("Nothing to convert"));
+    }
+    to_markdown(&html_or_rtf).map_err(AppError::internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtf_to_markdown() {
+        let rtf = r#"{\rtf1\ansi\ansicpg1252{\fonttbl{\f0\fswiss Helvetica;}}{\colortbl;\red0\green0\blue0;}
+{\*\generator Riched20;}\f0\fs24 Hello \b bold\b0  and \i italic\i0 .\par
+Caf\'e9 \'805 \u8364?6 \ldblquote q\rdblquote\line next\par
+{\listtext\'b7\tab}First\par
+{\listtext 2.\tab}Second\par
+See {\field{\*\fldinst{HYPERLINK "https://example.com"}}{\fldrslt{docs}}}.\par
+}"#;
+        assert_eq!(
+            rtf_to_markdown(rtf),
+            "Hello **bold** and *italic*.\n\nCafé €5 €6 “q”\nnext\n\n- First\n\n2. Second\n\nSee [docs](https://example.com)."
+        );
+    }
+
+    #[test]
+    fn test_rtf_surrogate_pairs() {
+        assert_eq!(rtf_to_markdown(r"{\rtf1 Hi \u-10179?\u-8704? \u55357?!}"), "Hi 😀 \u{FFFD}!");
+    }
+
+    #[test]
+    fn test_capture_skips_code_and_other_apps() {
+        let settings = RichToMarkdownSettings {
+            enabled: true,
+            apps: vec!["Microsoft Word".to_string()],
+        };
+        let word = FrontmostApp {
+            name: "Microsoft Word".to_string(),
+            bundle_id: None,
+            window_title: None,
+        };
+        let mut clipboard = RichClipboardContent {
+            text: Some("Hello bold".to_string()),
+            rtf: Some(r"{\rtf1 Hello \b bold\b0}".to_string()),
+            html: Some("<p>Hello bold</p>".to_string()),
+            html_from_rtf: true,
+            ..Default::default()
+        };
+        assert_eq!(capture_markdown(&settings, &clipboard, Some(&word)).as_deref(), Some("Hello **bold**"));
+        assert_eq!(capture_markdown(&settings, &clipboard, None), None);
+
+        clipboard.text = Some("const total = 1;\nconsole.log(total);".to_string());
+        clipboard.html = Some("<div><span>const</span> total = 1;</div>".to_string());
+        clipboard.html_from_rtf = false;
+        assert_eq!(capture_markdown(&settings, &clipboard, Some(&word)), None);
+
+        clipboard.text = Some("fn main() {}".to_string());
+        clipboard.html = Some(r#"<div style="white-space: pre;"><span>fn</span> main() {}</div>"#.to_string());
+        assert_eq!(capture_markdown(&settings, &clipboard, Some(&word)), None);
+    }
+
+    #[test]
+    fn test_rtf_emphasis_stays_inside_lines() {
+        let rtf = r"{\rtf1 {\b  one\par two }plain}";
+        assert_eq!(rtf_to_markdown(rtf), "**one**\n\n**two** plain");
+    }
+
+    #[test]
+    fn test_unwrap_google_docs() {
+        let html = r#"<meta charset="utf-8"><b style="font-weight:normal;" id="docs-internal-guid-1a2b"><p>Hi <b>there</b></p></b>"#;
+        assert_eq!(
+            unwrap_google_docs(html),
+            r#"<meta charset="utf-8"><div><p>Hi <b>there</b></p></div>"#
+        );
+        assert_eq!(unwrap_google_docs("<p>plain</p>"), "<p>plain</p>");
+    }
+
+    #[test]
+    fn test_html_to_markdown() {
+        let markdown = to_markdown(r#"<p><b>Hi</b> <a href="https://example.com">x</a></p>"#).unwrap();
+        assert!(markdown.contains("**Hi**"));
+        assert!(markdown.contains("[x](https://example.com)"));
+    }
+
+    #[test]
+    fn test_applies_to() {
+        let mut settings = RichToMarkdownSettings::default();
+        assert!(!applies_to(&settings, Some("Microsoft Word"), None));
+        settings.enabled = true;
+        assert!(applies_to(&settings, None, None));
+        settings.apps = vec!["com.microsoft.Word".to_string(), "Google Chrome".to_string()];
+        assert!(applies_to(&settings, Some("Microsoft Word"), Some("com.microsoft.word")));
+        assert!(applies_to(&settings, Some("google chrome"), None));
+        assert!(!applies_to(&settings, Some("Slack"), None));
+    }
+}
@@ -0,0 +1,949 @@
+// Automation rules: "when X happens, do Y". A rule has one trigger (a new history entry
+// matching a pattern/language, a global hotkey, or a repeating schedule), optional
+// conditions on the entry, and actions run in order (built-in transforms, a webhook,
+// an Obsidian note, tags, an expiry). Hotkey and schedule rules act on the newest history
+// entry. A new-entry rule with an expiry action makes matching captures ephemeral.
+// Rules and their execution log live in the profile database.
+
+use regex::Regex;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use thiserror::Error;
+
+use crate::ai_workflows::parse_transform;
+use crate::clipboard::transform_text;
+use crate::error::AppError;
+use crate::history::{
+    add_entry_tags, get_entries, get_entry, set_entry_expiry, update_entry, HistoryEntry, HistoryError,
+};
+use crate::http_client;
+use crate::premium::{add_to_obsidian_vault, load_obsidian_config};
+use crate::AppState;
+
+/// Older log rows beyond this are pruned after each run
+const MAX_RULE_RUNS: i64 = 1000;
+const SCHEDULER_TICK: Duration = Duration::from_secs(30);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Error, Debug)]
+pub enum RuleError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("Invalid rule data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Rule {0} not found")]
+    NotFound(i64),
+    #[error("Invalid rule: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Trigger {
+    /// A new history entry; both filters are optional
+    NewEntry {
+        #[serde(default)]
+        pattern: Option<String>,
+        #[serde(default)]
+        language: Option<String>,
+    },
+    Hotkey {
+        shortcut: String,
+    },
+    Schedule {
+        interval_minutes: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Condition {
+    Matches { pattern: String },
+    NotMatches { pattern: String },
+    Language { language: String },
+    /// One of classify.rs's smart tags ("url", "json", ...)
+    SmartTag { tag: String },
+    MinLength { chars: usize },
+    MaxLength { chars: usize },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// Built-in TextTransform names, applied in order. The entry is updated in place
+    /// (the old content is kept as a revision) and later actions see the result.
+    Transform { transforms: Vec<String> },
+    /// POST the entry as JSON
+    Webhook { url: String },
+    /// New note in the configured Obsidian vault
+    Obsidian,
+    Tag { tags: Vec<String> },
+    /// Delete the entry this long after the rule runs (see history::set_entry_expiry),
+    /// e.g. for OTPs and tokens
+    Expire { after_minutes: u32 },
+}
+
+impl Action {
+    fn kind(&self) -> &'static str {
+        match self {
+            Action::Transform { .. } => "transform",
+            Action::Webhook { .. } => "webhook",
+            Action::Obsidian => "obsidian",
+            Action::Tag { .. } => "tag",
+            Action::Expire { .. } => "expire",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub id: i64,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: Trigger,
+    pub conditions: Vec<Condition>,
+    pub actions: Vec<Action>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A rule as created or edited from the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleInput {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub trigger: Trigger,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    pub actions: Vec<Action>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionOutcome {
+    pub action: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleRun {
+    pub id: i64,
+    pub rule_id: i64,
+    pub rule_name: String,
+    /// "new_entry", "hotkey" or "schedule"
+    pub trigger: String,
+    pub entry_id: Option<i64>,
+    /// "success", "partial" or "failed"
+    pub status: String,
+    pub outcomes: Vec<ActionOutcome>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConditionCheck {
+    pub condition: Condition,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunResult {
+    /// Always true for hotkey and schedule triggers
+    pub trigger_matched: bool,
+    pub conditions: Vec<ConditionCheck>,
+    pub would_run: bool,
+    /// What each action would do, in order
+    pub actions: Vec<ActionOutcome>,
+    /// Content after the transform actions
+    pub output: String,
+}
+
+/// Global shortcuts currently registered for hotkey rules
+static REGISTERED_HOTKEYS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// When each schedule rule last ran, by rule id
+static LAST_SCHEDULED: Mutex<Option<HashMap<i64, Instant>>> = Mutex::new(None);
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+pub fn init_rules_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            trigger_json TEXT NOT NULL,
+            conditions_json TEXT NOT NULL,
+            actions_json TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS rule_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            rule_id INTEGER NOT NULL,
+            rule_name TEXT NOT NULL,
+            trigger TEXT NOT NULL,
+            entry_id INTEGER,
+            status TEXT NOT NULL,
+            outcomes_json TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_rule_runs_rule ON rule_runs(rule_id, created_at DESC)",
+        [],
+    )?;
+    Ok(())
+}
+
+const RULE_COLUMNS: &str = "id, name, enabled, trigger_json, conditions_json, actions_json, created_at, updated_at";
+
+fn map_rule_row(row: &rusqlite::Row) -> rusqlite::Result<(Rule, [String; 3])> {
+    // JSON columns are decoded after the query so a bad row surfaces as RuleError::Json
+    let rule = Rule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        enabled: row.get(2)?,
+        trigger: Trigger::Schedule { interval_minutes: 0 },
+        conditions: Vec::new(),
+        actions: Vec::new(),
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    };
+    Ok((rule, [row.get(3)?, row.get(4)?, row.get(5)?]))
+}
+
+fn decode_rule((mut rule, [trigger, conditions, actions]): (Rule, [String; 3])) -> Result<Rule, RuleError> {
+    rule.trigger = serde_json::from_str(&trigger)?;
+    rule.conditions = serde_json::from_str(&conditions)?;
+    rule.actions = serde_json::from_str(&actions)?;
+    Ok(rule)
+}
+
+pub fn get_rules(conn: &Connection) -> Result<Vec<Rule>, RuleError> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM rules ORDER BY id", RULE_COLUMNS))?;
+    let rows = stmt.query_map([], map_rule_row)?.collect::<Result<Vec<_>, _>>()?;
+    rows.into_iter().map(decode_rule).collect()
+}
+
+pub fn get_rule(conn: &Connection, id: i64) -> Result<Rule, RuleError> {
+    let sql = format!("SELECT {} FROM rules WHERE id = ?1", RULE_COLUMNS);
+    let row = conn.query_row(&sql, [id], map_rule_row).optional()?;
+    decode_rule(row.ok_or(RuleError::NotFound(id))?)
+}
+
+fn compile(pattern: &str) -> Result<Regex, RuleError> {
+    Regex::new(pattern).map_err(|e| RuleError::Invalid(format!("bad pattern \"{}\": {}", pattern, e)))
+}
+
+pub fn validate_rule(input: &RuleInput) -> Result<(), RuleError> {
+    if input.name.trim().is_empty() {
+        return Err(RuleError::Invalid("name is empty".to_string()));
+    }
+    match &input.trigger {
+        Trigger::NewEntry { pattern: Some(pattern), .. } => {
+            compile(pattern)?;
+        }
+        Trigger::NewEntry { .. } => {}
+        Trigger::Hotkey { shortcut } => {
+            if !crate::hotkey::validate_shortcut(shortcut) {
+                return Err(RuleError::Invalid(format!("invalid hotkey \"{}\"", shortcut)));
+            }
+        }
+        Trigger::Schedule { interval_minutes } => {
+            if *interval_minutes == 0 {
+                return Err(RuleError::Invalid("schedule interval must be at least a minute".to_string()));
+            }
+        }
+    }
+    for condition in &input.conditions {
+        match condition {
+            Condition::Matches { pattern } | Condition::NotMatches { pattern } => {
+                compile(pattern)?;
+            }
+            Condition::SmartTag { tag } if crate::classify::SmartTag::parse(tag).is_none() => {
+                return Err(RuleError::Invalid(format!("unknown smart tag \"{}\"", tag)));
+            }
+            _ => {}
+        }
+    }
+    if input.actions.is_empty() {
+        return Err(RuleError::Invalid("a rule needs at least one action".to_string()));
+    }
+    for action in &input.actions {
+        match action {
+            Action::Transform { transforms } => {
+                for name in transforms {
+                    parse_transform(name).map_err(RuleError::Invalid)?;
+                }
+            }
+            Action::Webhook { url } => {
+                if !(url.starts_with("https://") || url.starts_with("http://")) {
+                    return Err(RuleError::Invalid(format!("webhook URL must be http(s): {}", url)));
+                }
+            }
+            Action::Expire { after_minutes: 0 } => {
+                return Err(RuleError::Invalid("expiry must be at least a minute".to_string()));
+            }
+            Action::Obsidian | Action::Tag { .. } | Action::Expire { .. } => {}
+        }
+    }
+    Ok(())
+}
+
+pub fn insert_rule(conn: &Connection, input: &RuleInput) -> Result<i64, RuleError> {
+    validate_rule(input)?;
+    conn.execute(
+        "INSERT INTO rules (name, enabled, trigger_json, conditions_json, actions_json) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            input.name.trim(),
+            input.enabled,
+            serde_json::to_string(&input.trigger)?,
+            serde_json::to_string(&input.conditions)?,
+            serde_json::to_string(&input.actions)?
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn replace_rule(conn: &Connection, id: i64, input: &RuleInput) -> Result<(), RuleError> {
+    validate_rule(input)?;
+    let updated = conn.execute(
+        "UPDATE rules SET name = ?2, enabled = ?3, trigger_json = ?4, conditions_json = ?5, actions_json = ?6,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1",
+        rusqlite::params![
+            id,
+            input.name.trim(),
+            input.enabled,
+            serde_json::to_string(&input.trigger)?,
+            serde_json::to_string(&input.conditions)?,
+            serde_json::to_string(&input.actions)?
+        ],
+    )?;
+    if updated == 0 {
+        return Err(RuleError::NotFound(id));
+    }
+    Ok(())
+}
+
+fn record_run(conn: &Connection, rule: &Rule, trigger: &str, entry_id: Option<i64>, outcomes: &[ActionOutcome]) -> Result<(), RuleError> {
+    let status = if outcomes.iter().all(|o| o.ok) {
+        "success"
+    } else if outcomes.iter().any(|o| o.ok) {
+        "partial"
+    } else {
+        "failed"
+    };
+    conn.execute(
+        "INSERT INTO rule_runs (rule_id, rule_name, trigger, entry_id, status, outcomes_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![rule.id, rule.name, trigger, entry_id, status, serde_json::to_string(outcomes)?],
+    )?;
+    conn.execute(
+        "DELETE FROM rule_runs WHERE id NOT IN (SELECT id FROM rule_runs ORDER BY id DESC LIMIT ?1)",
+        [MAX_RULE_RUNS],
+    )?;
+    Ok(())
+}
+
+pub fn get_runs(conn: &Connection, rule_id: Option<i64>, limit: u32) -> Result<Vec<RuleRun>, RuleError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, rule_id, rule_name, trigger, entry_id, status, outcomes_json, created_at FROM rule_runs
+         WHERE ?1 IS NULL OR rule_id = ?1
+         ORDER BY id DESC LIMIT ?2",
+    )?;
+    let rows = stmt
+        .query_map(rusqlite::params![rule_id, limit], |row| {
+            Ok((
+                RuleRun {
+                    id: row.get(0)?,
+                    rule_id: row.get(1)?,
+                    rule_name: row.get(2)?,
+                    trigger: row.get(3)?,
+                    entry_id: row.get(4)?,
+                    status: row.get(5)?,
+                    outcomes: Vec::new(),
+                    created_at: row.get(7)?,
+                },
+                row.get::<_, String>(6)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    rows.into_iter()
+        .map(|(mut run, outcomes)| {
+            run.outcomes = serde_json::from_str(&outcomes)?;
+            Ok(run)
+        })
+        .collect()
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+fn trigger_matches(trigger: &Trigger, entry: &HistoryEntry) -> Result<bool, RuleError> {
+    match trigger {
+        Trigger::NewEntry { pattern, language } => {
+            let language_ok = language
+                .as_deref()
+                .map_or(true, |wanted| entry.language.as_deref().is_some_and(|l| l.eq_ignore_ascii_case(wanted)));
+            let pattern_ok = match pattern {
+                Some(pattern) => compile(pattern)?.is_match(&entry.content),
+                None => true,
+            };
+            Ok(language_ok && pattern_ok)
+        }
+        Trigger::Hotkey { .. } | Trigger::Schedule { .. } => Ok(true),
+    }
+}
+
+fn condition_passes(condition: &Condition, entry: &HistoryEntry) -> Result<bool, RuleError> {
+    let chars = || entry.content.chars().count();
+    Ok(match condition {
+        Condition::Matches { pattern } => compile(pattern)?.is_match(&entry.content),
+        Condition::NotMatches { pattern } => !compile(pattern)?.is_match(&entry.content),
+        Condition::Language { language } => entry
+            .language
+            .as_deref()
+            .is_some_and(|l| l.eq_ignore_ascii_case(language)),
+        // New entries reach rules before the indexer has tagged them
+        Condition::SmartTag { tag } => entry
+            .smart_tags
+            .clone()
+            .or_else(|| crate::classify::smart_tags_for(&entry.content))
+            .is_some_and(|tags| tags.split(',').any(|t| t == tag)),
+        Condition::MinLength { chars: min } => chars() >= *min,
+        Condition::MaxLength { chars: max } => chars() <= *max,
+    })
+}
+
+fn apply_transforms(content: &str, transforms: &[String]) -> Result<String, String> {
+    transforms
+        .iter()
+        .try_fold(content.to_string(), |text, name| Ok(transform_text(&text, parse_transform(name)?)))
+}
+
+/// Describe what an action would do without doing it
+fn preview_action(action: &Action, content: &mut String) -> ActionOutcome {
+    let (ok, detail) = match action {
+        Action::Transform { transforms } => match apply_transforms(content, transforms) {
+            Ok(output) => {
+                *content = output;
+                (true, format!("Apply {}", transforms.join(" → ")))
+            }
+            Err(e) => (false, e),
+        },
+        Action::Webhook { url } => (true, format!("POST the entry to {}", url)),
+        Action::Obsidian => match load_obsidian_config() {
+            Ok(config) if !config.vault_path.is_empty() => (true, format!("Create a note in {}", config.vault_path)),
+            _ => (false, "Obsidian vault not configured".to_string()),
+        },
+        Action::Tag { tags } => (true, format!("Tag with {}", tags.join(", "))),
+        Action::Expire { after_minutes } => (true, format!("Delete the entry after {} min", after_minutes)),
+    };
+    ActionOutcome {
+        action: action.kind().to_string(),
+        ok,
+        detail,
+    }
+}
+
+pub fn dry_run(input: &RuleInput, entry: &HistoryEntry) -> Result<DryRunResult, RuleError> {
+    validate_rule(input)?;
+    let trigger_matched = trigger_matches(&input.trigger, entry)?;
+    let conditions = input
+        .conditions
+        .iter()
+        .map(|condition| {
+            Ok(ConditionCheck {
+                condition: condition.clone(),
+                passed: condition_passes(condition, entry)?,
+            })
+        })
+        .collect::<Result<Vec<_>, RuleError>>()?;
+    let would_run = trigger_matched && conditions.iter().all(|c| c.passed);
+
+    let mut output = entry.content.clone();
+    let actions = input
+        .actions
+        .iter()
+        .map(|action| preview_action(action, &mut output))
+        .collect();
+    Ok(DryRunResult {
+        trigger_matched,
+        conditions,
+        would_run,
+        actions,
+        output,
+    })
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+async fn post_webhook(url: &str, rule: &Rule, entry: &HistoryEntry) -> Result<String, String> {
+    if crate::privacy::is_privacy_mode_active() {
+        return Err("Skipped while privacy mode is on".to_string());
+    }
+    let client = http_client::builder()
+        .map_err(|e| e.to_string())?
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let body = serde_json::json!({
+        "rule": rule.name,
+        "entry": {
+            "id": entry.id,
+            "content": entry.content,
+            "language": entry.language,
+            "tags": entry.tags,
+            "created_at": entry.created_at,
+        },
+    });
+    let response = client.post(url).json(&body).send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(format!("HTTP {}", status.as_u16()))
+    } else {
+        Err(format!("HTTP {}", status.as_u16()))
+    }
+}
+
+async fn run_action(app: &AppHandle, rule: &Rule, action: &Action, entry: &mut HistoryEntry) -> Result<String, String> {
+    match action {
+        Action::Transform { transforms } => {
+            let output = apply_transforms(&entry.content, transforms)?;
+            let state = app.state::<AppState>();
+            let conn = state.db.lock().map_err(|e| e.to_string())?;
+            update_entry(&conn, entry.id, &output).map_err(|e| e.to_string())?;
+            entry.content = output;
+            Ok(format!("Applied {}", transforms.join(" → ")))
+        }
+        Action::Webhook { url } => post_webhook(url, rule, entry).await,
+        Action::Obsidian => {
+            let config = load_obsidian_config().map_err(|e| e.to_string())?;
+            if config.vault_path.is_empty() {
+                return Err("Obsidian vault not configured".to_string());
+            }
+            let note = add_to_obsidian_vault(&entry.content, &config).map_err(|e| e.to_string())?;
+            Ok(format!("Created {}", note.note_name))
+        }
+        Action::Tag { tags } => {
+            let state = app.state::<AppState>();
+            let conn = state.db.lock().map_err(|e| e.to_string())?;
+            add_entry_tags(&conn, entry.id, tags).map_err(|e| e.to_string())?;
+            Ok(format!("Tagged with {}", tags.join(", ")))
+        }
+        Action::Expire { after_minutes } => {
+            let expires_at = chrono::Utc::now() + chrono::Duration::minutes(i64::from(*after_minutes));
+            let state = app.state::<AppState>();
+            let conn = state.db.lock().map_err(|e| e.to_string())?;
+            set_entry_expiry(&conn, entry.id, Some(expires_at)).map_err(|e| e.to_string())?;
+            entry.expires_at = Some(expires_at.format("%Y-%m-%d %H:%M:%S").to_string());
+            Ok(format!("Expires in {} min", after_minutes))
+        }
+    }
+}
+
+/// Run a rule against an entry if its trigger and conditions match, then log the run.
+/// Actions run in order; a failing action doesn't stop the ones after it.
+async fn execute(app: &AppHandle, rule: &Rule, trigger: &str, mut entry: HistoryEntry) {
+    // Actions edit history and send it out, both of which privacy mode pauses
+    if crate::privacy::is_privacy_mode_active() {
+        log::info!("Rule \"{}\" skipped while privacy mode is on", rule.name);
+        return;
+    }
+    let matched = trigger_matches(&rule.trigger, &entry).and_then(|matched| {
+        rule.conditions
+            .iter()
+            .try_fold(matched, |ok, c| Ok(ok && condition_passes(c, &entry)?))
+    });
+    match matched {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            log::warn!("Rule \"{}\" could not be evaluated: {}", rule.name, e);
+            return;
+        }
+    }
+
+    let mut outcomes = Vec::with_capacity(rule.actions.len());
+    for action in &rule.actions {
+        let result = run_action(app, rule, action, &mut entry).await;
+        outcomes.push(ActionOutcome {
+            action: action.kind().to_string(),
+            ok: result.is_ok(),
+            detail: result.unwrap_or_else(|e| e),
+        });
+    }
+
+    let state = app.state::<AppState>();
+    let logged = match state.db.lock() {
+        Ok(conn) => record_run(&conn, rule, trigger, Some(entry.id), &outcomes).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = logged {
+        log::warn!("Failed to log run of rule \"{}\": {}", rule.name, e);
+    }
+    if rule.actions.iter().any(|a| matches!(a, Action::Transform { .. } | Action::Tag { .. })) {
+        crate::tray::rebuild(app);
+    }
+    app.emit("rule-executed", rule.id).ok();
+}
+
+fn enabled_rules(app: &AppHandle) -> Vec<Rule> {
+    let state = app.state::<AppState>();
+    let rules = match state.db.lock() {
+        Ok(conn) => get_rules(&conn),
+        Err(_) => return Vec::new(),
+    };
+    match rules {
+        Ok(rules) => rules.into_iter().filter(|r| r.enabled).collect(),
+        Err(e) => {
+            log::warn!("Failed to load rules: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn latest_entry(app: &AppHandle) -> Option<HistoryEntry> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().ok()?;
+    let latest = get_entries(&conn, 1, 0).ok()?.into_iter().next()?;
+    // List queries only carry a preview of the content
+    get_entry(&conn, latest.id).ok()?
+}
+
+/// Run new-entry rules for an entry that was just added to history. Returns at once.
+pub fn on_entry_added(app: &AppHandle, entry: HistoryEntry) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let rules = enabled_rules(&app);
+        for rule in rules.iter().filter(|r| matches!(r.trigger, Trigger::NewEntry { .. })) {
+            execute(&app, rule, "new_entry", entry.clone()).await;
+        }
+    });
+}
+
+fn run_on_latest(app: &AppHandle, rule_ids: Vec<i64>, trigger: &'static str) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(entry) = latest_entry(&app) else {
+            return;
+        };
+        for rule in enabled_rules(&app).iter().filter(|r| rule_ids.contains(&r.id)) {
+            execute(&app, rule, trigger, entry.clone()).await;
+        }
+    });
+}
+
+/// (Re)register global shortcuts for enabled hotkey rules. Called at startup, after
+/// rules change and after switching profiles.
+pub fn register_hotkeys(app: &AppHandle) {
+    let mut registered = REGISTERED_HOTKEYS.lock().unwrap_or_else(|e| e.into_inner());
+    for shortcut in registered.drain(..) {
+        app.global_shortcut().unregister(shortcut.as_str()).ok();
+    }
+
+    let mut by_shortcut: HashMap<String, Vec<i64>> = HashMap::new();
+    for rule in enabled_rules(app) {
+        if let Trigger::Hotkey { shortcut } = &rule.trigger {
+            by_shortcut.entry(shortcut.clone()).or_default().push(rule.id);
+        }
+    }
+    for (shortcut, rule_ids) in by_shortcut {
+        let result = app
+            .global_shortcut()
+            .on_shortcut(shortcut.as_str(), move |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    run_on_latest(app, rule_ids.clone(), "hotkey");
+                }
+            });
+        match result {
+            Ok(()) => registered.push(shortcut),
+            Err(e) => log::warn!("Failed to register rule hotkey {}: {}", shortcut, e),
+        }
+    }
+}
+
+/// Background loop running schedule rules. A rule first runs one interval after the
+/// app starts (or after the rule is created).
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+            let due: Vec<i64> = {
+                let mut last = LAST_SCHEDULED.lock().unwrap_or_else(|e| e.into_inner());
+                let last = last.get_or_insert_with(HashMap::new);
+                let scheduled: Vec<(i64, u32)> = enabled_rules(&app)
+                    .iter()
+                    .filter_map(|rule| match rule.trigger {
+                        Trigger::Schedule { interval_minutes } => Some((rule.id, interval_minutes)),
+                        _ => None,
+                    })
+                    .collect();
+                last.retain(|id, _| scheduled.iter().any(|(rule_id, _)| rule_id == id));
+                scheduled
+                    .into_iter()
+                    .filter(|(id, minutes)| {
+                        let previous = *last.entry(*id).or_insert(now);
+                        let due = now.duration_since(previous) >= Duration::from_secs(*minutes as u64 * 60);
+                        if due {
+                            last.insert(*id, now);
+                        }
+                        due
+                    })
+                    .map(|(id, _)| id)
+                    .collect()
+            };
+            if !due.is_empty() {
+                run_on_latest(&app, due, "schedule");
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn list_rules(state: State<AppState>) -> Result<Vec<Rule>, AppError> {
+    let conn = state.db.lock()?;
+    get_rules(&conn).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn create_rule(app: AppHandle, state: State<AppState>, rule: RuleInput) -> Result<Rule, AppError> {
+    let created = {
+        let conn = state.db.lock()?;
+        let id = insert_rule(&conn, &rule)?;
+        get_rule(&conn, id)?
+    };
+    if matches!(created.trigger, Trigger::Hotkey { .. }) {
+        register_hotkeys(&app);
+    }
+    Ok(created)
+}
+
+#[tauri::command]
+pub fn update_rule(app: AppHandle, state: State<AppState>, id: i64, rule: RuleInput) -> Result<Rule, AppError> {
+    let updated = {
+        let conn = state.db.lock()?;
+        replace_rule(&conn, id, &rule)?;
+        get_rule(&conn, id)?
+    };
+    register_hotkeys(&app);
+    Ok(updated)
+}
+
+#[tauri::command]
+pub fn set_rule_enabled(app: AppHandle, state: State<AppState>, id: i64, enabled: bool) -> Result<(), AppError> {
+    {
+        let conn = state.db.lock()?;
+        let updated = conn
+            .execute(
+                "UPDATE rules SET enabled = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                rusqlite::params![id, enabled],
+            )?;
+        if updated == 0 {
+            return Err(RuleError::NotFound(id).into());
+        }
+    }
+    register_hotkeys(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_rule(app: AppHandle, state: State<AppState>, id: i64) -> Result<(), AppError> {
+    {
+        let conn = state.db.lock()?;
+        conn.execute("DELETE FROM rules WHERE id = ?1", [id])?;
+    }
+    register_hotkeys(&app);
+    Ok(())
+}
+
+/// Evaluate a rule (saved or not) against a history entry, or against `content` when
+/// no entry is given, without running any action
+#[tauri::command]
+pub fn test_rule(
+    state: State<AppState>,
+    rule: RuleInput,
+    entry_id: Option<i64>,
+    content: Option<String>,
+    language: Option<String>,
+) -> Result<DryRunResult, AppError> {
+    let entry = match entry_id {
+        Some(id) => {
+            let conn = state.db.lock()?;
+            get_entry(&conn, id)?
+                .ok_or(HistoryError::EntryNotFound(id))?
+        }
+        None => {
+            let content = content.ok_or_else(|| AppError::invalid_input("Provide an entry or sample content"))?;
+            sample_entry(content, language)
+        }
+    };
+    dry_run(&rule, &entry).map_err(AppError::from)
+}
+
+fn sample_entry(content: String, language: Option<String>) -> HistoryEntry {
+    HistoryEntry {
+        id: 0,
+        character_count: content.chars().count() as i32,
+        word_count: content.split_whitespace().count() as i32,
+        line_count: content.lines().count() as i32,
+        smart_tags: crate::classify::smart_tags_for(&content),
+        content,
+        created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        language,
+        tags: None,
+        images: None,
+        html: None,
+        paste_service: None,
+        paste_url: None,
+        paste_published_at: None,
+        expires_at: None,
+        summary: None,
+        title: None,
+        reminder_at: None,
+        reminder_note: None,
+        content_truncated: false,
+        source_app: None,
+        source_bundle_id: None,
+        source_window_title: None,
+    }
+}
+
+/// Execution log, newest first; all rules when `rule_id` is omitted
+#[tauri::command]
+pub fn get_rule_runs(state: State<AppState>, rule_id: Option<i64>, limit: u32) -> Result<Vec<RuleRun>, AppError> {
+    let conn = state.db.lock()?;
+    get_runs(&conn, rule_id, limit).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn clear_rule_runs(state: State<AppState>) -> Result<(), AppError> {
+    let conn = state.db.lock()?;
+    conn.execute("DELETE FROM rule_runs", [])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_rules_tables(&conn).unwrap();
+        conn
+    }
+
+    fn jira_rule() -> RuleInput {
+        RuleInput {
+            name: "Tag Jira keys".to_string(),
+            enabled: true,
+            trigger: Trigger::NewEntry {
+                pattern: Some(r"\b[A-Z]+-\d+\b".to_string()),
+                language: None,
+            },
+            conditions: vec![Condition::MaxLength { chars: 200 }],
+            actions: vec![
+                Action::Transform {
+                    transforms: vec!["Uppercase".to_string()],
+                },
+                Action::Tag {
+                    tags: vec!["jira".to_string()],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_rule_roundtrip_and_log() {
+        let conn = test_conn();
+        let id = insert_rule(&conn, &jira_rule()).unwrap();
+        let rule = get_rule(&conn, id).unwrap();
+        assert!(matches!(rule.trigger, Trigger::NewEntry { .. }));
+        assert_eq!(rule.actions.len(), 2);
+
+        let outcomes = vec![
+            ActionOutcome {
+                action: "transform".to_string(),
+                ok: true,
+                detail: String::new(),
+            },
+            ActionOutcome {
+                action: "tag".to_string(),
+                ok: false,
+                detail: "boom".to_string(),
+            },
+        ];
+        record_run(&conn, &rule, "new_entry", Some(7), &outcomes).unwrap();
+        let runs = get_runs(&conn, Some(id), 10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, "partial");
+        assert_eq!(runs[0].outcomes[1].detail, "boom");
+        assert!(get_runs(&conn, Some(id + 1), 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let result = dry_run(&jira_rule(), &sample_entry("fix WM-42 today".to_string(), None)).unwrap();
+        assert!(result.trigger_matched);
+        assert!(result.would_run);
+        assert_eq!(result.output, "FIX WM-42 TODAY");
+        assert!(result.actions.iter().all(|a| a.ok));
+
+        let miss = dry_run(&jira_rule(), &sample_entry("no ticket here".to_string(), None)).unwrap();
+        assert!(!miss.trigger_matched);
+        assert!(!miss.would_run);
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut bad_pattern = jira_rule();
+        bad_pattern.trigger = Trigger::NewEntry {
+            pattern: Some("(".to_string()),
+            language: None,
+        };
+        assert!(validate_rule(&bad_pattern).is_err());
+
+        let mut bad_transform = jira_rule();
+        bad_transform.actions = vec![Action::Transform {
+            transforms: vec!["Shout".to_string()],
+        }];
+        assert!(validate_rule(&bad_transform).is_err());
+
+        let mut no_actions = jira_rule();
+        no_actions.actions.clear();
+        assert!(validate_rule(&no_actions).is_err());
+
+        let mut every_zero = jira_rule();
+        every_zero.trigger = Trigger::Schedule { interval_minutes: 0 };
+        assert!(validate_rule(&every_zero).is_err());
+
+        let mut expire_now = jira_rule();
+        expire_now.actions = vec![Action::Expire { after_minutes: 0 }];
+        assert!(validate_rule(&expire_now).is_err());
+    }
+}
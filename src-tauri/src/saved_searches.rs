@@ -0,0 +1,199 @@
+// Saved searches ("smart folders"): a named `HistoryFilter` the sidebar can open in one
+// click. Each keeps a count of matching entries for its badge. Counting runs the whole
+// filter, so it's done lazily: any history write marks every count stale and
+// list_saved_searches recounts the stale ones the next time it's called.
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::State;
+use thiserror::Error;
+
+use crate::error::AppError;
+use crate::history::{HistoryEntry, HistoryError};
+use crate::history_search::{self, HistoryFilter};
+use crate::AppState;
+
+/// Set by history writes; cleared once the counts are refreshed
+static COUNTS_STALE: AtomicBool = AtomicBool::new(true);
+
+#[derive(Error, Debug)]
+pub enum SavedSearchError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("Invalid saved search data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    History(#[from] HistoryError),
+    #[error("Saved search {0} not found")]
+    NotFound(i64),
+    #[error("Invalid saved search: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub name: String,
+    pub filter: HistoryFilter,
+    /// Matching entries as of the last count
+    pub count: Option<i64>,
+    pub created_at: String,
+}
+
+pub fn init_saved_searches_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS saved_searches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            filter_json TEXT NOT NULL,
+            count INTEGER,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Called for every history write; counts are refreshed on the next list
+pub fn mark_counts_stale() {
+    COUNTS_STALE.store(true, Ordering::Relaxed);
+}
+
+fn map_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, String, String, Option<i64>, String)> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+}
+
+fn to_saved_search(
+    (id, name, filter_json, count, created_at): (i64, String, String, Option<i64>, String),
+) -> Result<SavedSearch, SavedSearchError> {
+    Ok(SavedSearch {
+        id,
+        name,
+        filter: serde_json::from_str(&filter_json)?,
+        count,
+        created_at,
+    })
+}
+
+pub fn get_saved_search(conn: &Connection, id: i64) -> Result<SavedSearch, SavedSearchError> {
+    let row = conn
+        .query_row(
+            "SELECT id, name, filter_json, count, created_at FROM saved_searches WHERE id = ?1",
+            [id],
+            map_row,
+        )
+        .optional()?
+        .ok_or(SavedSearchError::NotFound(id))?;
+    to_saved_search(row)
+}
+
+pub fn insert_saved_search(conn: &Connection, name: &str, filter: &HistoryFilter) -> Result<SavedSearch, SavedSearchError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(SavedSearchError::Invalid("A saved search needs a name".to_string()));
+    }
+    let count = history_search::count(conn, filter)?;
+    conn.execute(
+        "INSERT INTO saved_searches (name, filter_json, count) VALUES (?1, ?2, ?3)",
+        rusqlite::params![name, serde_json::to_string(filter)?, count],
+    )?;
+    get_saved_search(conn, conn.last_insert_rowid())
+}
+
+/// Every saved search in the order they were saved, recounting first if history changed
+pub fn get_saved_searches(conn: &Connection) -> Result<Vec<SavedSearch>, SavedSearchError> {
+    let rows = {
+        let mut stmt = conn.prepare(
+            "SELECT id, name, filter_json, count, created_at FROM saved_searches ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], map_row)?.collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+    let mut searches = rows.into_iter().map(to_saved_search).collect::<Result<Vec<_>, _>>()?;
+
+    if COUNTS_STALE.swap(false, Ordering::Relaxed) {
+        for search in &mut searches {
+            let count = history_search::count(conn, &search.filter)?;
+            conn.execute(
+                "UPDATE saved_searches SET count = ?2 WHERE id = ?1",
+                rusqlite::params![search.id, count],
+            )?;
+            search.count = Some(count);
+        }
+    }
+    Ok(searches)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn save_search(state: State<AppState>, name: String, filter: HistoryFilter) -> Result<SavedSearch, AppError> {
+    let conn = state.db.lock()?;
+    insert_saved_search(&conn, &name, &filter).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn list_saved_searches(state: State<AppState>) -> Result<Vec<SavedSearch>, AppError> {
+    let conn = state.db.lock()?;
+    get_saved_searches(&conn).map_err(AppError::from)
+}
+
+/// A page of a saved search's results; pass the last id of a page as `cursor` for the next
+#[tauri::command]
+pub fn run_saved_search(
+    state: State<AppState>,
+    id: i64,
+    limit: u32,
+    cursor: Option<i64>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    let conn = state.db.lock()?;
+    let search = get_saved_search(&conn, id)?;
+    Ok(crate::for_display(history_search::search(&conn, &search.filter, cursor, limit)?))
+}
+
+#[tauri::command]
+pub fn delete_saved_search(state: State<AppState>, id: i64) -> Result<(), AppError> {
+    let conn = state.db.lock()?;
+    if conn.execute("DELETE FROM saved_searches WHERE id = ?1", [id])? == 0 {
+        return Err(SavedSearchError::NotFound(id).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::create_schema;
+
+    #[test]
+    fn test_counts_refresh_after_writes() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        let insert = |language: &str| {
+            conn.execute(
+                "INSERT INTO history (content, character_count, word_count, line_count, language)
+                 VALUES ('x', 1, 1, 1, ?1)",
+                [language],
+            )
+            .unwrap();
+        };
+        insert("sql");
+
+        let filter = HistoryFilter {
+            languages: vec!["sql".to_string()],
+            ..Default::default()
+        };
+        let saved = insert_saved_search(&conn, "SQL", &filter).unwrap();
+        assert_eq!(saved.count, Some(1));
+        assert_eq!(get_saved_search(&conn, saved.id).unwrap().filter, filter);
+        assert!(insert_saved_search(&conn, "  ", &filter).is_err());
+
+        insert("sql");
+        insert("rust");
+        mark_counts_stale();
+        assert_eq!(get_saved_searches(&conn).unwrap()[0].count, Some(2));
+    }
+}
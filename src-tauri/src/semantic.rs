@@ -0,0 +1,384 @@
+// Semantic search over history and snippets. Embeddings are computed locally:
+// words and character trigrams are feature-hashed into a fixed-size vector, which
+// matches reworded or misspelled recollections far better than LIKE queries without
+// shipping a model. Vectors live in the `vectors` table, tagged with the embedding
+// model so a different embedder can re-index later.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use tauri::State;
+use thiserror::Error;
+
+use crate::error::{AppError, ErrorCode};
+use crate::history::full_content;
+use crate::storage::{load_settings, load_snippets, Snippet};
+use crate::AppState;
+
+#[derive(Error, Debug)]
+pub enum SemanticError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("History error: {0}")]
+    History(#[from] crate::history::HistoryError),
+    #[error("Unknown search scope: {0}")]
+    InvalidScope(String),
+}
+
+/// Identifies how vectors were produced; rows from another model are re-embedded
+pub const EMBEDDING_MODEL: &str = "hashed-ngram-v1";
+const DIMENSIONS: usize = 384;
+const WORD_WEIGHT: f32 = 1.0;
+const TRIGRAM_WEIGHT: f32 = 0.5;
+/// Only the start of long clips is embedded; it's what people remember
+const MAX_EMBED_CHARS: usize = 8_000;
+const DEFAULT_RESULTS: usize = 10;
+const MAX_RESULTS: usize = 100;
+const PREVIEW_CHARS: usize = 160;
+
+const SOURCE_HISTORY: &str = "history";
+const SOURCE_SNIPPET: &str = "snippet";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchScope {
+    History,
+    Snippets,
+    All,
+}
+
+impl SearchScope {
+    fn parse(value: Option<&str>) -> Result<Self, SemanticError> {
+        match value.unwrap_or("all") {
+            "history" => Ok(SearchScope::History),
+            "snippets" => Ok(SearchScope::Snippets),
+            "all" => Ok(SearchScope::All),
+            other => Err(SemanticError::InvalidScope(other.to_string())),
+        }
+    }
+
+    fn includes(&self, source: &str) -> bool {
+        match self {
+            SearchScope::History => source == SOURCE_HISTORY,
+            SearchScope::Snippets => source == SOURCE_SNIPPET,
+            SearchScope::All => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticMatch {
+    /// "history" or "snippet"
+    pub source: String,
+    /// History entry id or snippet id
+    pub id: String,
+    /// Cosine similarity, 0.0 to 1.0
+    pub score: f32,
+    pub preview: String,
+}
+
+pub fn init_vectors_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS vectors (
+            source TEXT NOT NULL,
+            source_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            vector BLOB NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (source, source_id)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// FNV-1a; stable across runs and platforms, unlike std's hasher
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+fn add_feature(vector: &mut [f32], feature: &str, weight: f32) {
+    let hash = fnv1a(feature.as_bytes());
+    let index = (hash % DIMENSIONS as u64) as usize;
+    // The top hash bit picks the sign so collisions tend to cancel out
+    let sign = if (hash >> 63) & 1 == 0 { 1.0 } else { -1.0 };
+    vector[index] += sign * weight;
+}
+
+/// L2-normalised embedding of `text`
+pub fn embed(text: &str) -> Vec<f32> {
+    let text: String = text.chars().take(MAX_EMBED_CHARS).collect::<String>().to_lowercase();
+
+    let mut counts: HashMap<&str, u32> = HashMap::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        *counts.entry(word).or_default() += 1;
+    }
+
+    let mut vector = vec![0.0f32; DIMENSIONS];
+    for (word, count) in counts {
+        // Sublinear term frequency so a repeated word doesn't dominate
+        let tf = 1.0 + (count as f32).ln();
+        add_feature(&mut vector, word, WORD_WEIGHT * tf);
+
+        let padded: Vec<char> = format!("<{}>", word).chars().collect();
+        for trigram in padded.windows(3) {
+            let trigram: String = trigram.iter().collect();
+            add_feature(&mut vector, &trigram, TRIGRAM_WEIGHT * tf);
+        }
+    }
+
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in &mut vector {
+            *v /= norm;
+        }
+    }
+    vector
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    // Both sides are normalised
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn vector_to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn blob_to_vector(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn content_hash(content: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    digest[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn preview(content: &str) -> String {
+    let collapsed = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut preview: String = collapsed.chars().take(PREVIEW_CHARS).collect();
+    if collapsed.chars().count() > PREVIEW_CHARS {
+        preview.push('…');
+    }
+    preview
+}
+
+/// Bring the vectors for one source in line with `items` (id, content): embeds new or
+/// changed items and drops vectors whose item is gone. Returns how many were embedded.
+fn sync_source(conn: &Connection, source: &str, items: &[(String, String)]) -> Result<usize, SemanticError> {
+    let existing: HashMap<String, (String, String)> = {
+        let mut stmt = conn.prepare("SELECT source_id, model, content_hash FROM vectors WHERE source = ?1")?;
+        let rows = stmt
+            .query_map([source], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        rows
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    let mut embedded = 0;
+    for (id, content) in items {
+        let hash = content_hash(content);
+        let up_to_date = existing
+            .get(id)
+            .map(|(model, existing_hash)| model == EMBEDDING_MODEL && *existing_hash == hash)
+            .unwrap_or(false);
+        if up_to_date {
+            continue;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO vectors (source, source_id, model, content_hash, vector, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)",
+            rusqlite::params![source, id, EMBEDDING_MODEL, hash, vector_to_blob(&embed(content))],
+        )?;
+        embedded += 1;
+    }
+
+    let current: std::collections::HashSet<&str> = items.iter().map(|(id, _)| id.as_str()).collect();
+    for id in existing.keys().filter(|id| !current.contains(id.as_str())) {
+        tx.execute(
+            "DELETE FROM vectors WHERE source = ?1 AND source_id = ?2",
+            rusqlite::params![source, id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(embedded)
+}
+
+/// Store a history entry's vector, embedded beforehand so the caller needn't hold the
+/// database lock while embedding (see indexer.rs)
+pub fn store_history_vector(conn: &Connection, id: i64, content: &str, vector: &[f32]) -> Result<(), SemanticError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO vectors (source, source_id, model, content_hash, vector, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)",
+        rusqlite::params![SOURCE_HISTORY, id.to_string(), EMBEDDING_MODEL, content_hash(content), vector_to_blob(vector)],
+    )?;
+    Ok(())
+}
+
+/// Embeds each entry's full text; compressed and overflowed rows keep only a head in
+/// `content`, which would leave the rest of the clip unsearchable
+pub fn index_history(conn: &Connection) -> Result<usize, SemanticError> {
+    let rows: Vec<(i64, String, Option<String>, Option<Vec<u8>>)> = {
+        let mut stmt = conn.prepare("SELECT id, content, content_hash, content_zstd FROM history")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+    let items = rows
+        .into_iter()
+        .map(|(id, content, hash, content_zstd)| Ok((id.to_string(), full_content(content, hash, content_zstd)?)))
+        .collect::<Result<Vec<_>, SemanticError>>()?;
+    sync_source(conn, SOURCE_HISTORY, &items)
+}
+
+pub fn index_snippets(conn: &Connection, snippets: &[Snippet]) -> Result<usize, SemanticError> {
+    let items: Vec<(String, String)> = snippets
+        .iter()
+        .map(|s| (s.id.clone(), format!("{}\n{}", s.name, s.content)))
+        .collect();
+    sync_source(conn, SOURCE_SNIPPET, &items)
+}
+
+/// Nearest items to `query` among indexed vectors, best first
+pub fn search(
+    conn: &Connection,
+    query: &str,
+    scope: SearchScope,
+    k: usize,
+    contents: &HashMap<(String, String), String>,
+) -> Result<Vec<SemanticMatch>, SemanticError> {
+    let query_vector = embed(query);
+    let mut stmt = conn.prepare("SELECT source, source_id, vector FROM vectors WHERE model = ?1")?;
+    let rows = stmt.query_map([EMBEDDING_MODEL], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Vec<u8>>(2)?))
+    })?;
+
+    let mut matches = Vec::new();
+    for row in rows {
+        let (source, id, blob) = row?;
+        if !scope.includes(&source) {
+            continue;
+        }
+        let score = cosine(&query_vector, &blob_to_vector(&blob));
+        if score <= 0.0 {
+            continue;
+        }
+        let preview = contents
+            .get(&(source.clone(), id.clone()))
+            .map(|c| preview(c))
+            .unwrap_or_default();
+        matches.push(SemanticMatch { source, id, score, preview });
+    }
+
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(k);
+    Ok(matches)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Find history entries and/or snippets similar in meaning to `query`.
+/// `scope` is "history", "snippets" or "all" (default); `k` defaults to 10.
+#[tauri::command]
+pub fn semantic_search(
+    state: State<AppState>,
+    query: String,
+    scope: Option<String>,
+    k: Option<usize>,
+) -> Result<Vec<SemanticMatch>, AppError> {
+    let settings = load_settings()?;
+    if !settings.semantic_search_enabled {
+        return Err(AppError::new(ErrorCode::Disabled, "Semantic search is turned off in settings"));
+    }
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let scope = SearchScope::parse(scope.as_deref())?;
+    let k = k.unwrap_or(DEFAULT_RESULTS).clamp(1, MAX_RESULTS);
+
+    let conn = state.db.lock()?;
+    let mut contents: HashMap<(String, String), String> = HashMap::new();
+
+    // Indexing is incremental, so keeping it in the search path stays cheap
+    if scope.includes(SOURCE_HISTORY) {
+        index_history(&conn)?;
+    }
+    if scope.includes(SOURCE_SNIPPET) {
+        let snippets = load_snippets()?.snippets;
+        index_snippets(&conn, &snippets)?;
+        for snippet in snippets {
+            contents.insert((SOURCE_SNIPPET.to_string(), snippet.id), snippet.content);
+        }
+    }
+
+    let mut matches = search(&conn, &query, scope, k, &contents)?;
+
+    // Previews for history matches only need the few rows that made the cut
+    for m in matches.iter_mut().filter(|m| m.source == SOURCE_HISTORY) {
+        if let Ok(content) = conn.query_row(
+            "SELECT content FROM history WHERE id = ?1",
+            [&m.id],
+            |row| row.get::<_, String>(0),
+        ) {
+            m.preview = preview(&content);
+        }
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_similarity() {
+        let query = embed("docker compose postgres config");
+        let related = embed("version: '3'\nservices:\n  postgres:\n    image: postgres:16 # docker-compose");
+        let unrelated = embed("Thanks for lunch yesterday, see you on Friday!");
+        assert!(cosine(&query, &related) > cosine(&query, &unrelated));
+
+        // Misspellings still overlap through trigrams
+        assert!(cosine(&embed("kubernetes"), &embed("kubernetis")) > 0.4);
+    }
+
+    #[test]
+    fn test_vector_blob_roundtrip() {
+        let vector = embed("hello world");
+        assert_eq!(vector.len(), DIMENSIONS);
+        assert_eq!(blob_to_vector(&vector_to_blob(&vector)), vector);
+        assert!(embed("").iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn test_sync_and_search() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_vectors_table(&conn).unwrap();
+        let items = vec![
+            ("1".to_string(), "SELECT * FROM users WHERE email = ?".to_string()),
+            ("2".to_string(), "Meeting notes: roadmap planning".to_string()),
+        ];
+        assert_eq!(sync_source(&conn, SOURCE_HISTORY, &items).unwrap(), 2);
+        assert_eq!(sync_source(&conn, SOURCE_HISTORY, &items).unwrap(), 0);
+
+        let results = search(&conn, "sql users query", SearchScope::All, 5, &HashMap::new()).unwrap();
+        assert_eq!(results[0].id, "1");
+        assert!(search(&conn, "sql", SearchScope::Snippets, 5, &HashMap::new()).unwrap().is_empty());
+
+        sync_source(&conn, SOURCE_HISTORY, &items[1..]).unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM vectors", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+    }
+}
@@ -0,0 +1,109 @@
+// macOS Services: "Send selection to Wingman" in every app's Services menu. The entry
+// is declared under NSServices in Info.plist, which names `sendToWingman` as the
+// message; this module registers the object that answers it. Selected text is added
+// to history like any other clip and the panel opens with `service-selection-received`
+// so the frontend can load it into the editor. Users can bind a shortcut to the
+// service in System Settings > Keyboard > Keyboard Shortcuts > Services.
+
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use std::sync::{Once, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::history::get_entry;
+use crate::AppState;
+
+const PROVIDER_CLASS: &str = "WingmanServicesProvider";
+const PLAIN_TEXT_TYPE: &str = "public.utf8-plain-text";
+
+/// The provider is called by AppKit with no context of ours, so it finds the app here
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Add the selection to history and open the panel on it
+fn receive_selection(app: &AppHandle, text: String) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("No text was selected".to_string());
+    }
+    let language = crate::detect_language(text.clone());
+    let id = crate::add_history_entry(app.clone(), app.state::<AppState>(), text, Some(language), None, None, None)?;
+
+    let entry = {
+        let state = app.state::<AppState>();
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        get_entry(&conn, id).map_err(|e| e.to_string())?
+    };
+    crate::tray::show_panel(app, None);
+    if let Some(entry) = entry {
+        app.emit("service-selection-received", &entry).ok();
+    }
+    Ok(())
+}
+
+unsafe fn to_string(ns_string: id) -> Option<String> {
+    if ns_string == nil {
+        return None;
+    }
+    let utf8: *const std::os::raw::c_char = msg_send![ns_string, UTF8String];
+    (!utf8.is_null()).then(|| std::ffi::CStr::from_ptr(utf8).to_string_lossy().into_owned())
+}
+
+/// `sendToWingman:userData:error:`, called on the main thread by the Services menu
+extern "C" fn send_to_wingman(_this: &Object, _cmd: Sel, pasteboard: id, _user_data: id, error: *mut id) {
+    let result = unsafe {
+        let text: id = msg_send![pasteboard, stringForType: NSString::alloc(nil).init_str(PLAIN_TEXT_TYPE)];
+        match (APP.get(), to_string(text)) {
+            (Some(app), Some(text)) => receive_selection(app, text),
+            (None, _) => Err("Wingman isn't ready yet".to_string()),
+            (_, None) => Err("No text was selected".to_string()),
+        }
+    };
+    if let Err(e) = result {
+        log::warn!("Send selection to Wingman failed: {}", e);
+        if !error.is_null() {
+            // AppKit shows this message to the user
+            unsafe { *error = NSString::alloc(nil).init_str(&e) };
+        }
+    }
+}
+
+fn provider_class() -> Option<&'static Class> {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        if let Some(mut decl) = ClassDecl::new(PROVIDER_CLASS, class!(NSObject)) {
+            unsafe {
+                decl.add_method(
+                    sel!(sendToWingman:userData:error:),
+                    send_to_wingman as extern "C" fn(&Object, Sel, id, id, *mut id),
+                );
+            }
+            decl.register();
+        }
+    });
+    Class::get(PROVIDER_CLASS)
+}
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    fn NSUpdateDynamicServices();
+}
+
+/// Register the services provider. Must be called on the main thread during setup.
+pub fn register(app: &AppHandle) {
+    APP.set(app.clone()).ok();
+    let Some(class) = provider_class() else {
+        log::warn!("Couldn't register the services provider class");
+        return;
+    };
+    unsafe {
+        // NSApp keeps the provider for the rest of the run
+        let provider: id = msg_send![class, new];
+        let ns_app: id = msg_send![class!(NSApplication), sharedApplication];
+        let _: () = msg_send![ns_app, setServicesProvider: provider];
+        // Pick up the Info.plist entry without waiting for the next login
+        NSUpdateDynamicServices();
+    }
+    log::info!("Services provider registered");
+}
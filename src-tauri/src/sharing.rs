@@ -0,0 +1,385 @@
+// Native share sheet integration (macOS NSSharingService / NSSharingServicePicker).
+// AppKit objects are created and used entirely on the main thread; only plain
+// Rust data crosses the channel back to the command.
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::AppError;
+
+/// A sharing service available for some content
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareService {
+    /// Localized display title (e.g. "Mail", "AirDrop"); also accepted as a `service_hint`
+    pub title: String,
+}
+
+/// Well-known NSSharingServiceName values for the hints users are most likely to pass
+#[cfg(target_os = "macos")]
+fn service_name_for_hint(hint: &str) -> Option<&'static str> {
+    match hint.to_lowercase().as_str() {
+        "mail" | "email" => Some("com.apple.share.Mail.compose"),
+        "messages" | "message" | "imessage" => Some("com.apple.share.Messages.compose"),
+        "airdrop" => Some("com.apple.share.AirDrop.send"),
+        "notes" => Some("com.apple.Notes.SharingExtension"),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+mod macos {
+    use base64::Engine;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::{NSArray, NSRect, NSString};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    pub(super) unsafe fn ns_string_to_string(ns_string: id) -> String {
+        if ns_string == nil {
+            return String::new();
+        }
+        let ptr: *const i8 = msg_send![ns_string, UTF8String];
+        if ptr.is_null() {
+            return String::new();
+        }
+        std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+
+    /// Build the items array for a share: an NSImage for image data URLs, otherwise the text itself
+    pub(super) unsafe fn share_items(content: &str) -> Result<id, String> {
+        let item: id = if let Some(payload) = content
+            .strip_prefix("data:image/")
+            .and_then(|rest| rest.split_once(";base64,"))
+            .map(|(_, payload)| payload)
+        {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(payload)
+                .map_err(|e| format!("Failed to decode image: {}", e))?;
+            let ns_data: id = msg_send![class!(NSData), dataWithBytes:bytes.as_ptr() length:bytes.len()];
+            let ns_image: id = msg_send![class!(NSImage), alloc];
+            let ns_image: id = msg_send![ns_image, initWithData: ns_data];
+            if ns_image == nil {
+                return Err("Unsupported image data".to_string());
+            }
+            ns_image
+        } else {
+            NSString::alloc(nil).init_str(content)
+        };
+        Ok(NSArray::arrayWithObjects(nil, &[item]))
+    }
+
+    /// Resolve a hint to a service: first by well-known name, then by display title
+    pub(super) unsafe fn find_service(items: id, hint: &str) -> Option<id> {
+        if let Some(name) = super::service_name_for_hint(hint) {
+            let ns_name = NSString::alloc(nil).init_str(name);
+            let service: id = msg_send![class!(NSSharingService), sharingServiceNamed: ns_name];
+            if service != nil {
+                return Some(service);
+            }
+        }
+
+        let services: id = msg_send![class!(NSSharingService), sharingServicesForItems: items];
+        let count: u64 = msg_send![services, count];
+        for i in 0..count {
+            let service: id = msg_send![services, objectAtIndex: i];
+            let title: id = msg_send![service, title];
+            if ns_string_to_string(title).eq_ignore_ascii_case(hint) {
+                return Some(service);
+            }
+        }
+        None
+    }
+
+    pub(super) unsafe fn perform(service: id, items: id) -> Result<(), String> {
+        let can_perform: bool = msg_send![service, canPerformWithItems: items];
+        if !can_perform {
+            return Err("The selected service can't share this content".to_string());
+        }
+        let _: () = msg_send![service, performWithItems: items];
+        Ok(())
+    }
+
+    /// Show the share picker anchored to the top of the window's content view
+    pub(super) unsafe fn show_picker(ns_window: id, items: id) -> Result<(), String> {
+        let content_view: id = msg_send![ns_window, contentView];
+        if content_view == nil {
+            return Err("Window has no content view".to_string());
+        }
+        let bounds: NSRect = msg_send![content_view, bounds];
+        let picker: id = msg_send![class!(NSSharingServicePicker), alloc];
+        let picker: id = msg_send![picker, initWithItems: items];
+        // NSRectEdgeMinY
+        let _: () = msg_send![picker, showRelativeToRect:bounds ofView:content_view preferredEdge:1u64];
+        Ok(())
+    }
+}
+
+/// Share text (or an image data URL) through the macOS share sheet.
+/// With a `service_hint` ("mail", "messages", "airdrop", "notes", or a title from
+/// `list_share_services`) that service is invoked directly; otherwise the picker is shown.
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn share_content(
+    app: AppHandle,
+    content: String,
+    service_hint: Option<String>,
+) -> Result<(), AppError> {
+    use crate::window::MAIN_WINDOW_LABEL;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use tauri::Manager;
+
+    // Must run on main thread to avoid Cocoa exceptions
+    let (tx, rx) = mpsc::channel();
+    let app_inner = app.clone();
+
+    app.run_on_main_thread(move || {
+        let result = unsafe {
+            macos::share_items(&content).and_then(|items| match service_hint.as_deref() {
+                Some(hint) => match macos::find_service(items, hint) {
+                    Some(service) => macos::perform(service, items),
+                    None => Err(format!("Sharing service not available: {}", hint)),
+                },
+                None => match app_inner.get_webview_window(MAIN_WINDOW_LABEL) {
+                    Some(window) => match window.ns_window() {
+                        Ok(ns_window) => macos::show_picker(ns_window as cocoa::base::id, items),
+                        Err(e) => Err(e.to_string()),
+                    },
+                    None => Err("Window not found".to_string()),
+                },
+            })
+        };
+        let _ = tx.send(result);
+    })?;
+
+    rx.recv_timeout(Duration::from_secs(5))
+        .map_err(|e| format!("Timeout waiting for share sheet: {}", e))?
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn share_content(
+    _app: AppHandle,
+    _content: String,
+    _service_hint: Option<String>,
+) -> Result<(), AppError> {
+    Err(AppError::unsupported("Sharing is only supported on macOS"))
+}
+
+/// Services that can share the given content (defaults to sharing plain text)
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn list_share_services(app: AppHandle, content: Option<String>) -> Result<Vec<ShareService>, AppError> {
+    use cocoa::base::id;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let (tx, rx) = mpsc::channel();
+
+    app.run_on_main_thread(move || {
+        let result = unsafe {
+            macos::share_items(content.as_deref().unwrap_or(" ")).map(|items| {
+                let services: id = msg_send![class!(NSSharingService), sharingServicesForItems: items];
+                let count: u64 = msg_send![services, count];
+                (0..count)
+                    .map(|i| {
+                        let service: id = msg_send![services, objectAtIndex: i];
+                        let title: id = msg_send![service, title];
+                        ShareService {
+                            title: macos::ns_string_to_string(title),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        let _ = tx.send(result);
+    })?;
+
+    rx.recv_timeout(Duration::from_secs(5))
+        .map_err(|e| format!("Timeout waiting for share services: {}", e))?
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn list_share_services(_app: AppHandle, _content: Option<String>) -> Result<Vec<ShareService>, AppError> {
+    Ok(Vec::new())
+}
+
+/// mailto: URLs past this length get truncated or rejected by some mail clients
+const MAILTO_MAX_LENGTH: usize = 2000;
+
+/// How an email draft was created
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailDraftResult {
+    /// "mailto" when handed to the default mail client via URL, "mail_app" when composed natively
+    pub method: String,
+    /// Path of the attached file, if the content was attached
+    pub attachment_path: Option<String>,
+}
+
+fn build_mailto_url(to: &str, subject: &str, body: &str) -> String {
+    // Keep addresses readable; mail clients handle raw '@' and ',' in the path
+    let to = urlencoding::encode(to.trim())
+        .replace("%40", "@")
+        .replace("%2C", ",");
+    let mut params = Vec::new();
+    if !subject.is_empty() {
+        params.push(format!("subject={}", urlencoding::encode(subject)));
+    }
+    if !body.is_empty() {
+        params.push(format!("body={}", urlencoding::encode(body)));
+    }
+    if params.is_empty() {
+        format!("mailto:{}", to)
+    } else {
+        format!("mailto:{}?{}", to, params.join("&"))
+    }
+}
+
+/// File name for an attached snippet: the subject if it makes a usable name, else "snippet"
+// Attachments are only composed natively on macOS
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn attachment_file_name(subject: &str, language: &str) -> String {
+    let (_, extensions) = crate::get_file_filter(language);
+    let stem: String = subject
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .chars()
+        .take(60)
+        .collect();
+    let stem = if stem.is_empty() { "snippet".to_string() } else { stem };
+    format!("{}.{}", stem, extensions[0])
+}
+
+/// Write the attachment into its own temp directory so it keeps a clean file name
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn write_attachment_file(subject: &str, body: &str, language: &str) -> Result<std::path::PathBuf, String> {
+    let dir = crate::temp_files::subdir(&format!("email_{}", chrono::Utc::now().timestamp_millis()))?;
+    let path = dir.join(attachment_file_name(subject, language));
+    crate::temp_files::write_private(&path, body)?;
+    Ok(path)
+}
+
+/// Compose a Mail.app draft with the given recipients, subject, body text and optional file
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+unsafe fn compose_mail(to: &str, subject: &str, body: &str, attachment: Option<&std::path::Path>) -> Result<(), String> {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::{NSArray, NSString};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let name = NSString::alloc(nil).init_str("com.apple.share.Mail.compose");
+    let service: id = msg_send![class!(NSSharingService), sharingServiceNamed: name];
+    if service == nil {
+        return Err("Mail sharing service not available".to_string());
+    }
+
+    let recipients: Vec<id> = to
+        .split(',')
+        .map(|r| r.trim())
+        .filter(|r| !r.is_empty())
+        .map(|r| NSString::alloc(nil).init_str(r))
+        .collect();
+    if !recipients.is_empty() {
+        let recipients_array = NSArray::arrayWithObjects(nil, &recipients);
+        let _: () = msg_send![service, setRecipients: recipients_array];
+    }
+    if !subject.is_empty() {
+        let ns_subject = NSString::alloc(nil).init_str(subject);
+        let _: () = msg_send![service, setSubject: ns_subject];
+    }
+
+    let mut items: Vec<id> = vec![NSString::alloc(nil).init_str(body)];
+    if let Some(path) = attachment {
+        let ns_path = NSString::alloc(nil).init_str(&path.to_string_lossy());
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+        items.push(url);
+    }
+    let items_array = NSArray::arrayWithObjects(nil, &items);
+    macos::perform(service, items_array)
+}
+
+/// Create an email draft. Short text goes through a mailto: URL; with `attach_as_file`
+/// (or text too long for mailto:) the content is attached as a file named after the
+/// language, which on macOS is done through Mail's share service.
+#[tauri::command]
+pub async fn create_email_draft(
+    app: AppHandle,
+    to: Option<String>,
+    subject: Option<String>,
+    body: String,
+    attach_as_file: bool,
+    language: Option<String>,
+) -> Result<EmailDraftResult, AppError> {
+    let to = to.unwrap_or_default();
+    let subject = subject.unwrap_or_default();
+    let language = language.unwrap_or_else(|| "plaintext".to_string());
+
+    let mailto = build_mailto_url(&to, &subject, &body);
+    if !attach_as_file && mailto.len() <= MAILTO_MAX_LENGTH {
+        crate::open_with_system_handler(&mailto)?;
+        return Ok(EmailDraftResult {
+            method: "mailto".to_string(),
+            attachment_path: None,
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let path = write_attachment_file(&subject, &body, &language)?;
+        let path_for_mail = path.clone();
+        let (tx, rx) = mpsc::channel();
+
+        app.run_on_main_thread(move || {
+            // The content travels as the attachment, so keep the body itself short
+            let result = unsafe { compose_mail(&to, &subject, "", Some(&path_for_mail)) };
+            let _ = tx.send(result);
+        })?;
+
+        rx.recv_timeout(Duration::from_secs(5))
+            .map_err(|e| format!("Timeout waiting for Mail: {}", e))??;
+
+        Ok(EmailDraftResult {
+            method: "mail_app".to_string(),
+            attachment_path: Some(path.to_string_lossy().to_string()),
+        })
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (app, language);
+        if attach_as_file {
+            Err(AppError::unsupported("Attaching files to email drafts is only supported on macOS"))
+        } else {
+            Err(AppError::invalid_input("Content is too long for a mailto: link. Try attaching it as a file instead."))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_mailto_url() {
+        assert_eq!(
+            build_mailto_url("a@example.com, b@example.com", "Hi there", "line 1\nline 2"),
+            "mailto:a@example.com,%20b@example.com?subject=Hi%20there&body=line%201%0Aline%202"
+        );
+        assert_eq!(build_mailto_url("", "", ""), "mailto:");
+        assert_eq!(build_mailto_url("", "", "a&b=c"), "mailto:?body=a%26b%3Dc");
+    }
+
+    #[test]
+    fn test_attachment_file_name() {
+        assert_eq!(attachment_file_name("Fix login bug", "rust"), "Fix_login_bug.rs");
+        assert_eq!(attachment_file_name("", "python"), "snippet.py");
+        assert_eq!(attachment_file_name("???", "unknown"), "snippet.txt");
+    }
+}
@@ -0,0 +1,274 @@
+// Near-duplicate snippets. Content is normalized (case, whitespace), hashed into word
+// shingles and compared pairwise by Jaccard similarity; pairs over the threshold are
+// clustered, so A~B and B~C end up in one group. Each group suggests a canonical snippet
+// to keep, and merge_snippets folds the others into it.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, State};
+
+use crate::error::AppError;
+use crate::semantic::fnv1a;
+use crate::storage::{load_snippets, save_snippets, Snippet};
+use crate::{tray, AppState};
+
+/// Words per shingle; shorter snippets fall back to character shingles
+const SHINGLE_WORDS: usize = 3;
+const SHINGLE_CHARS: usize = 5;
+const DEFAULT_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateMember {
+    pub id: String,
+    pub name: String,
+    /// Similarity to the canonical snippet, 0.0 to 1.0
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    /// Suggested snippet to keep
+    pub canonical_id: String,
+    /// Why it was suggested, for the UI
+    pub canonical_reason: String,
+    /// Every snippet in the group, canonical first
+    pub members: Vec<DuplicateMember>,
+    /// All members have the same normalized content
+    pub exact: bool,
+}
+
+fn normalize(content: &str) -> String {
+    content.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn shingles(normalized: &str) -> HashSet<u64> {
+    let words: Vec<&str> = normalized.split(' ').collect();
+    if words.len() >= SHINGLE_WORDS * 2 {
+        return words.windows(SHINGLE_WORDS).map(|w| fnv1a(w.join(" ").as_bytes())).collect();
+    }
+    let chars: Vec<char> = normalized.chars().collect();
+    if chars.len() <= SHINGLE_CHARS {
+        return HashSet::from([fnv1a(normalized.as_bytes())]);
+    }
+    chars
+        .windows(SHINGLE_CHARS)
+        .map(|w| fnv1a(w.iter().collect::<String>().as_bytes()))
+        .collect()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let intersection = a.intersection(b).count();
+    let union = a.len() + b.len() - intersection;
+    if union == 0 {
+        1.0
+    } else {
+        intersection as f64 / union as f64
+    }
+}
+
+fn find_root(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    // Path compression
+    let mut node = i;
+    while parents[node] != root {
+        let next = parents[node];
+        parents[node] = root;
+        node = next;
+    }
+    root
+}
+
+/// Favorites first, then snippets synced to a gist, then the most recently updated,
+/// then the longest
+fn pick_canonical<'a>(members: &[&'a Snippet]) -> (&'a Snippet, &'static str) {
+    let canonical = members
+        .iter()
+        .copied()
+        .max_by(|a, b| {
+            (a.favorite, a.github_gist_id.is_some(), &a.updated_at, a.content.len())
+                .cmp(&(b.favorite, b.github_gist_id.is_some(), &b.updated_at, b.content.len()))
+        })
+        .unwrap_or(members[0]);
+    let reason = if canonical.favorite && members.iter().filter(|s| s.favorite).count() == 1 {
+        "favorite"
+    } else if canonical.github_gist_id.is_some() && members.iter().filter(|s| s.github_gist_id.is_some()).count() == 1 {
+        "synced to a gist"
+    } else {
+        "most recently updated"
+    };
+    (canonical, reason)
+}
+
+pub fn find_duplicates(snippets: &[Snippet], threshold: f64) -> Vec<DuplicateGroup> {
+    let normalized: Vec<String> = snippets.iter().map(|s| normalize(&s.content)).collect();
+    let shingled: Vec<HashSet<u64>> = normalized.iter().map(|n| shingles(n)).collect();
+    let mut parents: Vec<usize> = (0..snippets.len()).collect();
+
+    for i in 0..snippets.len() {
+        if normalized[i].is_empty() {
+            continue;
+        }
+        for j in i + 1..snippets.len() {
+            if normalized[j].is_empty() {
+                continue;
+            }
+            // Jaccard can't exceed the ratio of the set sizes
+            let (small, large) = (shingled[i].len().min(shingled[j].len()), shingled[i].len().max(shingled[j].len()));
+            if (small as f64) < threshold * large as f64 {
+                continue;
+            }
+            if normalized[i] == normalized[j] || jaccard(&shingled[i], &shingled[j]) >= threshold {
+                let (a, b) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[a] = b;
+            }
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..snippets.len() {
+        let root = find_root(&mut parents, i);
+        clusters.entry(root).or_default().push(i);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = clusters
+        .into_values()
+        .filter(|indices| indices.len() > 1)
+        .map(|indices| {
+            let members: Vec<&Snippet> = indices.iter().map(|&i| &snippets[i]).collect();
+            let (canonical, reason) = pick_canonical(&members);
+            let canonical_index = indices.iter().copied().find(|&i| snippets[i].id == canonical.id).unwrap_or(indices[0]);
+            let mut members: Vec<DuplicateMember> = indices
+                .iter()
+                .map(|&i| DuplicateMember {
+                    id: snippets[i].id.clone(),
+                    name: snippets[i].name.clone(),
+                    similarity: if normalized[i] == normalized[canonical_index] {
+                        1.0
+                    } else {
+                        (jaccard(&shingled[i], &shingled[canonical_index]) * 1000.0).round() / 1000.0
+                    },
+                })
+                .collect();
+            members.sort_by(|a, b| {
+                (b.id == canonical.id)
+                    .cmp(&(a.id == canonical.id))
+                    .then(b.similarity.total_cmp(&a.similarity))
+            });
+            DuplicateGroup {
+                canonical_id: canonical.id.clone(),
+                canonical_reason: reason.to_string(),
+                exact: indices.iter().all(|&i| normalized[i] == normalized[canonical_index]),
+                members,
+            }
+        })
+        .collect();
+    // Biggest groups first
+    groups.sort_by(|a, b| b.members.len().cmp(&a.members.len()).then(a.canonical_id.cmp(&b.canonical_id)));
+    groups
+}
+
+/// Fold `ids` into `keep_id`: tags are unioned and favorite is kept if any was a favorite.
+/// Returns the kept snippet and the removed ones.
+pub fn merge_into(snippets: &mut Vec<Snippet>, ids: &[String], keep_id: &str) -> Result<(Snippet, Vec<Snippet>), AppError> {
+    let merged: Vec<Snippet> = snippets
+        .iter()
+        .filter(|s| s.id != keep_id && ids.contains(&s.id))
+        .cloned()
+        .collect();
+    let keep = snippets
+        .iter_mut()
+        .find(|s| s.id == keep_id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", keep_id)))?;
+    for snippet in &merged {
+        for tag in &snippet.tags {
+            if !keep.tags.contains(tag) {
+                keep.tags.push(tag.clone());
+            }
+        }
+        keep.favorite |= snippet.favorite;
+    }
+    keep.touch();
+    let kept = keep.clone();
+    snippets.retain(|s| s.id == keep_id || !ids.contains(&s.id));
+    Ok((kept, merged))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Groups of similar snippets; `threshold` is the Jaccard similarity (0.0-1.0, default 0.8)
+#[tauri::command]
+pub fn find_duplicate_snippets(threshold: Option<f64>) -> Result<Vec<DuplicateGroup>, AppError> {
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) || threshold == 0.0 {
+        return Err(AppError::invalid_input("Threshold must be between 0 and 1"));
+    }
+    Ok(find_duplicates(&load_snippets()?.snippets, threshold))
+}
+
+/// Merge duplicates into `keep_id`; the others go to the trash and their links move to
+/// the kept one
+#[tauri::command]
+pub fn merge_snippets(
+    app: AppHandle,
+    state: State<AppState>,
+    ids: Vec<String>,
+    keep_id: String,
+) -> Result<Snippet, AppError> {
+    let mut data = load_snippets()?;
+    let (kept, merged) = merge_into(&mut data.snippets, &ids, &keep_id)?;
+    crate::trash::trash_snippets(merged)?;
+    save_snippets(&data)?;
+
+    let conn = state.db.lock()?;
+    for id in ids.iter().filter(|id| **id != keep_id) {
+        crate::links::retarget_snippet_links(&conn, id, &keep_id)?;
+    }
+    drop(conn);
+    tray::rebuild(&app);
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(id: &str, content: &str, favorite: bool, updated_at: &str) -> Snippet {
+        Snippet {
+            tags: vec![id.to_string()],
+            created_at: updated_at.to_string(),
+            updated_at: updated_at.to_string(),
+            favorite,
+            ..Snippet::for_test(id, content)
+        }
+    }
+
+    #[test]
+    fn test_find_and_merge_duplicates() {
+        let query = "SELECT id, name, email FROM users WHERE active = 1 ORDER BY created_at DESC LIMIT 50";
+        let mut snippets = vec![
+            snippet("a", query, false, "2024-01-01"),
+            snippet("b", &format!("  {}\n", query.to_lowercase()), true, "2023-01-01"),
+            snippet("c", &query.replace("LIMIT 50", "LIMIT 50;"), false, "2024-06-01"),
+            snippet("d", "git log --oneline --graph", false, "2024-01-01"),
+        ];
+        let groups = find_duplicates(&snippets, 0.8);
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.canonical_id, "b");
+        assert_eq!(group.canonical_reason, "favorite");
+        assert_eq!(group.members.len(), 3);
+        assert!(!group.exact);
+        assert_eq!(group.members[1].similarity, 1.0);
+
+        let ids: Vec<String> = group.members.iter().map(|m| m.id.clone()).collect();
+        let (kept, merged) = merge_into(&mut snippets, &ids, "b").unwrap();
+        assert_eq!(kept.tags, vec!["b", "a", "c"]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(snippets.len(), 2);
+    }
+}
@@ -0,0 +1,294 @@
+// Export snippets for use in other tools:
+//
+// - "vscode": a folder of VS Code snippet files, one <language>.json per language, ready
+//   to drop into the user snippets directory. `$` is escaped so bodies insert verbatim.
+// - "alfred": an .alfredsnippets bundle (a zip of one JSON file per snippet) that Alfred
+//   imports as a collection.
+// - "files": a folder with one file per snippet, named after it, with the extension for
+//   its language.
+//
+// Snippets have no language field; it's taken from the linked gist's filename, else from
+// a tag naming a language, else plain text.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::error::AppError;
+use crate::storage::{load_snippets, Snippet};
+use crate::watch_folders::{language_for_path, LANGUAGES};
+
+const ALFRED_EXTENSION: &str = "alfredsnippets";
+
+#[derive(Error, Debug)]
+pub enum SnippetExportError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("Failed to write the Alfred bundle: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("No snippets match the filter")]
+    NothingToExport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetExportFormat {
+    Vscode,
+    Alfred,
+    Files,
+}
+
+/// Which snippets to export; an empty filter exports them all
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SnippetFilter {
+    #[serde(default)]
+    pub ids: Vec<String>,
+    /// Snippets carrying any of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorites_only: bool,
+}
+
+impl SnippetFilter {
+    fn matches(&self, snippet: &Snippet) -> bool {
+        (self.ids.is_empty() || self.ids.contains(&snippet.id))
+            && (self.tags.is_empty()
+                || snippet
+                    .tags
+                    .iter()
+                    .any(|tag| self.tags.iter().any(|wanted| wanted.eq_ignore_ascii_case(tag))))
+            && (!self.favorites_only || snippet.favorite)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnippetExportSummary {
+    /// The folder or bundle written
+    pub path: String,
+    pub snippets: usize,
+    pub files: usize,
+}
+
+/// Wingman language id for a snippet
+fn snippet_language(snippet: &Snippet) -> &'static str {
+    snippet
+        .github_gist_filename
+        .as_deref()
+        .and_then(|name| language_for_path(Path::new(name)))
+        .or_else(|| {
+            snippet.tags.iter().find_map(|tag| {
+                LANGUAGES
+                    .iter()
+                    .copied()
+                    .find(|language| language.eq_ignore_ascii_case(tag))
+            })
+        })
+        .unwrap_or("plaintext")
+}
+
+/// VS Code's id for a Wingman language id, which is also its snippet file name
+fn vscode_language(language: &str) -> &str {
+    match language {
+        "jsx" => "javascriptreact",
+        "tsx" => "typescriptreact",
+        "bash" => "shellscript",
+        other => other,
+    }
+}
+
+/// Lowercase, dash-separated form of a name for prefixes, keywords and file names
+fn slug(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "snippet".to_string()
+    } else {
+        slug.chars().take(60).collect()
+    }
+}
+
+/// `base`, or `base-2`, `base-3`, ... if taken
+fn unique(base: String, taken: &mut HashSet<String>) -> String {
+    let mut candidate = base.clone();
+    let mut n = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{}-{}", base, n);
+        n += 1;
+    }
+    candidate
+}
+
+fn export_vscode(snippets: &[&Snippet], dir: &Path) -> Result<usize, SnippetExportError> {
+    let mut by_language: BTreeMap<&str, serde_json::Map<String, serde_json::Value>> = BTreeMap::new();
+    for snippet in snippets {
+        let entries = by_language
+            .entry(vscode_language(snippet_language(snippet)))
+            .or_default();
+        // Keys are display names and must be unique within a file
+        let mut key = snippet.name.clone();
+        let mut n = 2;
+        while entries.contains_key(&key) {
+            key = format!("{} ({})", snippet.name, n);
+            n += 1;
+        }
+        // `\` is the escape character in snippet syntax; it goes first so the `$` escapes stay single
+        let body: Vec<String> = snippet
+            .content
+            .lines()
+            .map(|line| line.replace('\\', "\\\\").replace('$', "\\$"))
+            .collect();
+        entries.insert(
+            key,
+            json!({
+                "prefix": slug(&snippet.name),
+                "body": body,
+                "description": snippet.name,
+            }),
+        );
+    }
+
+    fs::create_dir_all(dir)?;
+    for (language, entries) in &by_language {
+        let content = serde_json::to_string_pretty(entries)?;
+        fs::write(dir.join(format!("{}.json", language)), content)?;
+    }
+    Ok(by_language.len())
+}
+
+fn export_alfred(snippets: &[&Snippet], path: &Path) -> Result<usize, SnippetExportError> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for snippet in snippets {
+        let entry = json!({
+            "alfredsnippet": {
+                "snippet": snippet.content,
+                "uid": snippet.id,
+                "name": snippet.name,
+                "keyword": slug(&snippet.name),
+            }
+        });
+        // Alfred's own naming; the uid keeps names unique
+        let name = format!("{} [{}].json", snippet.name.replace(['/', '\\'], "-"), snippet.id);
+        zip.start_file(name, options)?;
+        zip.write_all(serde_json::to_string_pretty(&entry)?.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(1)
+}
+
+fn export_files(snippets: &[&Snippet], dir: &Path) -> Result<usize, SnippetExportError> {
+    fs::create_dir_all(dir)?;
+    let mut taken = HashSet::new();
+    for snippet in snippets {
+        let (_, extensions) = crate::get_file_filter(snippet_language(snippet));
+        let stem = unique(slug(&snippet.name), &mut taken);
+        fs::write(dir.join(format!("{}.{}", stem, extensions[0])), &snippet.content)?;
+    }
+    Ok(snippets.len())
+}
+
+fn export(
+    format: SnippetExportFormat,
+    path: &Path,
+    filter: &SnippetFilter,
+) -> Result<SnippetExportSummary, SnippetExportError> {
+    let data = load_snippets()?;
+    let snippets: Vec<&Snippet> = data.snippets.iter().filter(|s| filter.matches(s)).collect();
+    if snippets.is_empty() {
+        return Err(SnippetExportError::NothingToExport);
+    }
+
+    let (path, files) = match format {
+        SnippetExportFormat::Vscode => (path.to_path_buf(), export_vscode(&snippets, path)?),
+        SnippetExportFormat::Alfred => {
+            let path: PathBuf = if path.extension().is_some_and(|ext| ext == ALFRED_EXTENSION) {
+                path.to_path_buf()
+            } else {
+                path.with_extension(ALFRED_EXTENSION)
+            };
+            let files = export_alfred(&snippets, &path)?;
+            (path, files)
+        }
+        SnippetExportFormat::Files => (path.to_path_buf(), export_files(&snippets, path)?),
+    };
+
+    Ok(SnippetExportSummary {
+        path: path.to_string_lossy().to_string(),
+        snippets: snippets.len(),
+        files,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Write snippets to `path`: a folder for "vscode" and "files", a bundle for "alfred"
+#[tauri::command]
+pub fn export_snippets(
+    format: SnippetExportFormat,
+    path: String,
+    filter: Option<SnippetFilter>,
+) -> Result<SnippetExportSummary, AppError> {
+    export(format, Path::new(&path), &filter.unwrap_or_default()).map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, content: &str, tags: &[&str], gist_filename: Option<&str>) -> Snippet {
+        Snippet {
+            name: name.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            github_gist_filename: gist_filename.map(str::to_string),
+            ..Snippet::for_test(&slug(name), content)
+        }
+    }
+
+    #[test]
+    fn test_language_and_names() {
+        assert_eq!(snippet_language(&snippet("a", "", &[], Some("deploy.sh"))), "bash");
+        assert_eq!(snippet_language(&snippet("a", "", &["Python"], None)), "python");
+        assert_eq!(snippet_language(&snippet("a", "", &["misc"], None)), "plaintext");
+        assert_eq!(vscode_language("tsx"), "typescriptreact");
+
+        assert_eq!(slug("Git: Undo last commit!"), "git-undo-last-commit");
+        let mut taken = HashSet::new();
+        assert_eq!(unique("a".to_string(), &mut taken), "a");
+        assert_eq!(unique("a".to_string(), &mut taken), "a-2");
+    }
+
+    #[test]
+    fn test_vscode_export_escapes_body() {
+        let dir = std::env::temp_dir().join(format!("wingman-export-{}", uuid::Uuid::new_v4()));
+        let snippets = [
+            snippet("Echo home", "echo $HOME\nprintf 'done\\n'", &["bash"], None),
+            snippet("Echo home", "echo ~", &["bash"], None),
+        ];
+        let refs: Vec<&Snippet> = snippets.iter().collect();
+        assert_eq!(export_vscode(&refs, &dir).unwrap(), 1);
+
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("shellscript.json")).unwrap()).unwrap();
+        assert_eq!(written["Echo home"]["body"], json!(["echo \\$HOME", "printf 'done\\\\n'"]));
+        assert_eq!(written["Echo home (2)"]["prefix"], "echo-home");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
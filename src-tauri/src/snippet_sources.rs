@@ -0,0 +1,360 @@
+// Snippets whose content lives at a URL, like a shared config. set_snippet_source links a
+// snippet to an https URL; from then on its content comes only from there and edits to it
+// are refused until the link is removed, whether they come from the editor, a whole-file
+// save, a gist pull or a data import. refresh_snippet_from_source fetches it on
+// request, and a background task refetches snippets whose refresh_minutes have passed.
+// Fetches send the last ETag back so unchanged sources cost a 304, go through the same
+// public-address checks as link previews, and give up on bodies over MAX_CONTENT_BYTES
+// rather than saving a cut-off copy. Content that changes is saved as a new snippet
+// version, so earlier copies stay in the history.
+
+use reqwest::{StatusCode, Url};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::AppError;
+use crate::storage::{load_snippets, save_snippets, Snippet, SnippetSource};
+use crate::{http_client, link_preview, offline, snippet_versions, AppState};
+
+/// Largest source accepted; anything bigger fails the refresh
+const MAX_CONTENT_BYTES: usize = 1024 * 1024;
+const MAX_REDIRECTS: usize = 5;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Shortest refresh interval accepted
+const MIN_REFRESH_MINUTES: u32 = 5;
+/// How often the scheduler looks for snippets that are due
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+enum Fetched {
+    NotModified,
+    Content { text: String, etag: Option<String> },
+}
+
+/// Parse and check a source URL before it's saved
+pub fn validate_url(url: &str) -> Result<Url, AppError> {
+    let parsed = Url::parse(url.trim()).map_err(|e| AppError::invalid_input(format!("Invalid URL: {}", e)))?;
+    if parsed.scheme() != "https" {
+        return Err(AppError::invalid_input("Snippet sources must be https URLs"));
+    }
+    if parsed.host_str().is_none() {
+        return Err(AppError::invalid_input("URL has no host"));
+    }
+    Ok(parsed)
+}
+
+/// Whether a scheduled refresh is due at `now`
+pub fn is_due(source: &SnippetSource, now: chrono::DateTime<chrono::Utc>) -> bool {
+    let Some(minutes) = source.refresh_minutes else {
+        return false;
+    };
+    match source
+        .last_fetched
+        .as_deref()
+        .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+    {
+        Some(at) => now.signed_duration_since(at) >= chrono::Duration::minutes(minutes as i64),
+        None => true,
+    }
+}
+
+/// Refuse to change the content of a snippet that's linked to a URL
+pub fn ensure_editable(snippet: &Snippet, content: &str) -> Result<(), AppError> {
+    match snippet.source.as_ref().filter(|_| snippet.content != content) {
+        Some(source) => Err(AppError::invalid_input(format!(
+            "\"{}\" is kept in sync with {}; unlink it to edit the content",
+            snippet.name, source.url
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// For a whole-file save of `incoming` over `current`: linked snippets keep their
+/// content, and links are only made or removed through set_snippet_source
+pub fn keep_sources(current: &[Snippet], incoming: &mut [Snippet]) -> Result<(), AppError> {
+    for snippet in incoming.iter_mut() {
+        let existing = current.iter().find(|s| s.id == snippet.id);
+        if let Some(existing) = existing {
+            ensure_editable(existing, &snippet.content)?;
+        }
+        snippet.source = existing.and_then(|s| s.source.clone());
+    }
+    Ok(())
+}
+
+/// Read the whole body, failing once it passes `MAX_CONTENT_BYTES`
+async fn read_limited(mut response: reqwest::Response) -> Result<Vec<u8>, String> {
+    if response.content_length().is_some_and(|len| len as usize > MAX_CONTENT_BYTES) {
+        return Err(format!("Source is larger than {} KB", MAX_CONTENT_BYTES / 1024));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if body.len() + chunk.len() > MAX_CONTENT_BYTES {
+            return Err(format!("Source is larger than {} KB", MAX_CONTENT_BYTES / 1024));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+async fn fetch(url: &str, etag: Option<&str>) -> Result<Fetched, String> {
+    let mut current = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+
+    for _ in 0..=MAX_REDIRECTS {
+        let addr = link_preview::resolve_public(&current).await?;
+        let host = current.host_str().unwrap_or_default().to_string();
+
+        // Pinned to the checked address, with redirects followed by hand and no proxy,
+        // as in link_preview
+        let client = http_client::builder()
+            .map_err(|e| e.to_string())?
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .resolve(&host, addr)
+            .user_agent("Wingman-Desktop")
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        let mut request = client.get(current.clone());
+        if let Some(etag) = etag {
+            request = request.header("If-None-Match", etag);
+        }
+        let response = request.send().await.map_err(|e| format!("Request failed: {}", e))?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get("Location")
+                .and_then(|v| v.to_str().ok())
+                .ok_or("Redirect without a Location header")?;
+            current = current.join(location).map_err(|e| format!("Invalid redirect: {}", e))?;
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(format!("Request failed with status {}", response.status().as_u16()));
+        }
+
+        let etag = response
+            .headers()
+            .get("ETag")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = read_limited(response).await?;
+        let text = String::from_utf8(body).map_err(|_| "Source isn't UTF-8 text".to_string())?;
+        return Ok(Fetched::Content { text, etag });
+    }
+
+    Err("Too many redirects".to_string())
+}
+
+/// Fetch the snippet's source and save what came back. Failures are recorded on the
+/// source as well as returned.
+pub async fn refresh(app: &AppHandle, id: &str) -> Result<Snippet, AppError> {
+    let source = load_snippets()?
+        .snippets
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", id)))?
+        .source
+        .ok_or_else(|| AppError::invalid_input("Snippet isn't linked to a URL"))?;
+
+    let fetched = fetch(&source.url, source.etag.as_deref()).await;
+
+    // Reload: the snippet may have changed while the request was out
+    let mut data = load_snippets()?;
+    let snippet = data
+        .snippets
+        .iter_mut()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", id)))?;
+    let Some(current) = snippet.source.as_ref().filter(|current| current.url == source.url) else {
+        // Unlinked or pointed elsewhere in the meantime
+        return Ok(snippet.clone());
+    };
+    let mut updated_source = current.clone();
+    updated_source.last_fetched = Some(chrono::Utc::now().to_rfc3339());
+
+    let result = match fetched {
+        Ok(Fetched::NotModified) => {
+            updated_source.last_error = None;
+            Ok(())
+        }
+        Ok(Fetched::Content { text, etag }) => {
+            if text != snippet.content {
+                snippet.content = text;
+                snippet.touch();
+            }
+            updated_source.etag = etag;
+            updated_source.last_error = None;
+            Ok(())
+        }
+        Err(e) => {
+            updated_source.last_error = Some(e.clone());
+            Err(e)
+        }
+    };
+    snippet.source = Some(updated_source);
+    let updated = snippet.clone();
+    let state = app.state::<AppState>();
+    snippet_versions::save_snippets_versioned(&*state.db.lock()?, &mut data)?;
+    app.emit("snippet-refreshed", &updated).ok();
+
+    match result {
+        Ok(()) => Ok(updated),
+        Err(e) => Err(AppError::internal(format!("Failed to refresh \"{}\": {}", updated.name, e))),
+    }
+}
+
+/// Refetch snippets whose refresh interval has passed, once a minute
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            if offline::is_enabled() {
+                continue;
+            }
+            let now = chrono::Utc::now();
+            let due: Vec<String> = match load_snippets() {
+                Ok(data) => data
+                    .snippets
+                    .into_iter()
+                    .filter(|s| s.source.as_ref().is_some_and(|source| is_due(source, now)))
+                    .map(|s| s.id)
+                    .collect(),
+                Err(e) => {
+                    log::warn!("Failed to load snippets for refresh: {}", e);
+                    continue;
+                }
+            };
+            for id in due {
+                if let Err(e) = refresh(&app, &id).await {
+                    log::warn!("{}", e);
+                }
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Link a snippet to an https URL, or with `url` None unlink it so it can be edited
+/// again. The content isn't fetched until the next refresh.
+#[tauri::command]
+pub fn set_snippet_source(
+    app: AppHandle,
+    id: String,
+    url: Option<String>,
+    refresh_minutes: Option<u32>,
+) -> Result<Snippet, AppError> {
+    let source = match url.filter(|url| !url.trim().is_empty()) {
+        Some(url) => {
+            if refresh_minutes.is_some_and(|minutes| minutes < MIN_REFRESH_MINUTES) {
+                return Err(AppError::invalid_input(format!(
+                    "Refresh interval must be at least {} minutes",
+                    MIN_REFRESH_MINUTES
+                )));
+            }
+            Some(SnippetSource {
+                url: validate_url(&url)?.to_string(),
+                refresh_minutes,
+                etag: None,
+                last_fetched: None,
+                last_error: None,
+            })
+        }
+        None => None,
+    };
+
+    let mut data = load_snippets()?;
+    let snippet = data
+        .snippets
+        .iter_mut()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", id)))?;
+    snippet.source = source;
+    snippet.updated_at = chrono::Utc::now().to_rfc3339();
+    let updated = snippet.clone();
+    save_snippets(&data)?;
+    app.emit("snippet-refreshed", &updated).ok();
+    Ok(updated)
+}
+
+/// Fetch a linked snippet's content now
+#[tauri::command]
+pub async fn refresh_snippet_from_source(app: AppHandle, id: String) -> Result<Snippet, AppError> {
+    refresh(&app, &id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_is_due_after_interval() {
+        let now = chrono::Utc::now();
+        let mut source = SnippetSource {
+            url: "https://example.com/config.toml".to_string(),
+            refresh_minutes: Some(30),
+            etag: None,
+            last_fetched: None,
+            last_error: None,
+        };
+        assert!(is_due(&source, now));
+
+        source.last_fetched = Some((now - chrono::Duration::minutes(10)).to_rfc3339());
+        assert!(!is_due(&source, now));
+        source.last_fetched = Some((now - chrono::Duration::minutes(31)).to_rfc3339());
+        assert!(is_due(&source, now));
+
+        source.refresh_minutes = None;
+        assert!(!is_due(&source, now));
+    }
+
+    #[test]
+    fn test_whole_file_saves_keep_sources() {
+        let linked = Snippet {
+            source: Some(SnippetSource {
+                url: "https://example.com/config.toml".to_string(),
+                refresh_minutes: None,
+                etag: None,
+                last_fetched: None,
+                last_error: None,
+            }),
+            ..Snippet::for_test("a", "fetched")
+        };
+        let current = vec![linked.clone(), Snippet::for_test("b", "local")];
+
+        // Renaming a linked snippet is fine; dropping or adding a link here is ignored
+        let mut incoming = vec![
+            Snippet {
+                name: "Renamed".to_string(),
+                source: None,
+                ..linked.clone()
+            },
+            Snippet {
+                source: linked.source.clone(),
+                ..Snippet::for_test("b", "edited")
+            },
+        ];
+        keep_sources(&current, &mut incoming).unwrap();
+        assert_eq!(incoming[0].source, linked.source);
+        assert_eq!(incoming[1].source, None);
+
+        let mut incoming = vec![Snippet::for_test("a", "edited")];
+        assert!(keep_sources(&current, &mut incoming).is_err());
+    }
+
+    #[test]
+    fn test_only_https_sources_are_accepted() {
+        assert!(validate_url("https://example.com/a.json").is_ok());
+        assert!(validate_url("http://example.com/a.json").is_err());
+        assert!(validate_url("file:///etc/hosts").is_err());
+        assert!(validate_url("not a url").is_err());
+    }
+}
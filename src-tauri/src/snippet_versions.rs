@@ -0,0 +1,295 @@
+// Version history for snippets. Before an edit replaces a snippet's name, content or
+// tags, the outgoing copy is saved to snippet_versions in the profile database under
+// the snippet's version number at the time, so every version a user saw can be listed,
+// compared with any other (or the current one) and rolled back to. Rolling back is an
+// edit too, so the copy it replaces is kept as well.
+//
+// Edits reach snippets.json through save_snippets_versioned, which compares the new
+// list with the saved one; that covers the editor, bulk saves from the frontend, gist
+// pulls and source refreshes alike. The diff itself is drawn by the frontend.
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::error::{AppError, ErrorCode};
+use crate::storage::{load_snippets, save_snippets, Snippet, SnippetsData};
+use crate::AppState;
+
+/// Oldest versions beyond this many per snippet are dropped
+const MAX_VERSIONS_PER_SNIPPET: i64 = 50;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnippetVersion {
+    pub snippet_id: String,
+    /// The snippet's `version` while this copy was current
+    pub version: u64,
+    pub name: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    /// When this copy was replaced; for the current snippet, when it was last edited
+    pub created_at: String,
+}
+
+/// Two versions of a snippet for the frontend to diff
+#[derive(Debug, Clone, Serialize)]
+pub struct SnippetComparison {
+    pub from: SnippetVersion,
+    pub to: SnippetVersion,
+}
+
+pub fn init_snippet_versions_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS snippet_versions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            snippet_id TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            content TEXT NOT NULL,
+            tags TEXT NOT NULL DEFAULT '[]',
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(snippet_id, version)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Save `snippet` as it is now, before an edit replaces it
+pub fn record_version(conn: &Connection, snippet: &Snippet) -> rusqlite::Result<()> {
+    let tags = serde_json::to_string(&snippet.tags).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "INSERT OR REPLACE INTO snippet_versions (snippet_id, version, name, content, tags)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![snippet.id, snippet.version as i64, snippet.name, snippet.content, tags],
+    )?;
+    conn.execute(
+        "DELETE FROM snippet_versions WHERE snippet_id = ?1 AND id NOT IN (
+            SELECT id FROM snippet_versions WHERE snippet_id = ?1 ORDER BY version DESC LIMIT ?2
+         )",
+        rusqlite::params![snippet.id, MAX_VERSIONS_PER_SNIPPET],
+    )?;
+    Ok(())
+}
+
+fn changed(old: &Snippet, new: &Snippet) -> bool {
+    old.name != new.name || old.content != new.content || old.tags != new.tags
+}
+
+/// The error for an edit made against an older copy than `current`; its details carry
+/// the current copy as `current`
+pub fn stale_edit(current: &Snippet) -> AppError {
+    AppError::new(
+        ErrorCode::Conflict,
+        format!("\"{}\" was changed somewhere else", current.name),
+    )
+    .with_details(serde_json::json!({ "current": current }))
+}
+
+/// Refuse a save that changes a snippet starting from a different version than the
+/// saved one, rather than silently replacing whatever changed it in between
+pub fn ensure_current(saved: &[Snippet], incoming: &[Snippet]) -> Result<(), AppError> {
+    let saved: HashMap<&str, &Snippet> = saved.iter().map(|s| (s.id.as_str(), s)).collect();
+    for snippet in incoming {
+        if let Some(old) = saved.get(snippet.id.as_str()) {
+            if snippet.version != old.version && changed(old, snippet) {
+                return Err(stale_edit(old));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keep the saved copy of every snippet in `incoming` whose name, content or tags differ
+/// from `saved`. A changed snippet whose version the caller didn't move past the saved
+/// one is touched, so each version number is only ever used for one copy.
+pub fn record_changes(conn: &Connection, saved: &[Snippet], incoming: &mut [Snippet]) -> rusqlite::Result<()> {
+    let saved: HashMap<&str, &Snippet> = saved.iter().map(|s| (s.id.as_str(), s)).collect();
+    for snippet in incoming.iter_mut() {
+        let Some(old) = saved.get(snippet.id.as_str()) else {
+            continue;
+        };
+        if !changed(old, snippet) {
+            continue;
+        }
+        record_version(conn, old)?;
+        if snippet.version <= old.version {
+            snippet.version = old.version;
+            snippet.touch();
+        }
+    }
+    Ok(())
+}
+
+/// save_snippets for edits: versions of the snippets `data` changes are kept first
+pub fn save_snippets_versioned(conn: &Connection, data: &mut SnippetsData) -> Result<(), AppError> {
+    record_changes(conn, &load_snippets()?.snippets, &mut data.snippets)?;
+    save_snippets(data)?;
+    Ok(())
+}
+
+fn map_version_row(row: &rusqlite::Row) -> rusqlite::Result<SnippetVersion> {
+    let tags: String = row.get(4)?;
+    Ok(SnippetVersion {
+        snippet_id: row.get(0)?,
+        version: row.get::<_, i64>(1)? as u64,
+        name: row.get(2)?,
+        content: row.get(3)?,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        created_at: row.get(5)?,
+    })
+}
+
+/// Prior versions of a snippet, newest first
+pub fn list_versions(conn: &Connection, snippet_id: &str) -> rusqlite::Result<Vec<SnippetVersion>> {
+    let mut stmt = conn.prepare(
+        "SELECT snippet_id, version, name, content, tags, created_at FROM snippet_versions
+         WHERE snippet_id = ?1 ORDER BY version DESC",
+    )?;
+    let versions = stmt
+        .query_map([snippet_id], map_version_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(versions)
+}
+
+pub fn get_version(conn: &Connection, snippet_id: &str, version: u64) -> rusqlite::Result<Option<SnippetVersion>> {
+    conn.query_row(
+        "SELECT snippet_id, version, name, content, tags, created_at FROM snippet_versions
+         WHERE snippet_id = ?1 AND version = ?2",
+        rusqlite::params![snippet_id, version as i64],
+        map_version_row,
+    )
+    .optional()
+}
+
+/// Drop the versions of snippets that no longer exist anywhere, e.g. after the trash is
+/// emptied. `keep` lists the ids still in use.
+pub fn delete_orphaned(conn: &Connection, keep: &[String]) -> rusqlite::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("CREATE TEMP TABLE IF NOT EXISTS kept_snippets (id TEXT PRIMARY KEY)", [])?;
+    tx.execute("DELETE FROM kept_snippets", [])?;
+    for id in keep {
+        tx.execute("INSERT OR IGNORE INTO kept_snippets (id) VALUES (?1)", [id])?;
+    }
+    let deleted = tx.execute(
+        "DELETE FROM snippet_versions WHERE snippet_id NOT IN (SELECT id FROM kept_snippets)",
+        [],
+    )?;
+    tx.execute("DELETE FROM kept_snippets", [])?;
+    tx.commit()?;
+    Ok(deleted)
+}
+
+/// A version of the snippet by number: a saved one, or the current snippet
+fn resolve(conn: &Connection, snippet: &Snippet, version: u64) -> Result<SnippetVersion, AppError> {
+    if version == snippet.version {
+        return Ok(SnippetVersion {
+            snippet_id: snippet.id.clone(),
+            version,
+            name: snippet.name.clone(),
+            content: snippet.content.clone(),
+            tags: snippet.tags.clone(),
+            created_at: snippet.updated_at.clone(),
+        });
+    }
+    get_version(conn, &snippet.id, version)?
+        .ok_or_else(|| AppError::not_found(format!("Version {} of \"{}\" not found", version, snippet.name)))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Prior versions of a snippet, newest first. The current one is the snippet itself.
+#[tauri::command]
+pub fn get_snippet_versions(state: State<AppState>, id: String) -> Result<Vec<SnippetVersion>, AppError> {
+    let conn = state.db.lock()?;
+    Ok(list_versions(&conn, &id)?)
+}
+
+/// Two versions of a snippet to diff; pass the snippet's own `version` for its current
+/// content
+#[tauri::command]
+pub fn compare_snippet_versions(
+    state: State<AppState>,
+    id: String,
+    a: u64,
+    b: u64,
+) -> Result<SnippetComparison, AppError> {
+    let snippet = load_snippets()?
+        .snippets
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {} not found", id)))?;
+    let conn = state.db.lock()?;
+    Ok(SnippetComparison {
+        from: resolve(&conn, &snippet, a)?,
+        to: resolve(&conn, &snippet, b)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(version: u64, content: &str) -> Snippet {
+        Snippet {
+            name: format!("v{}", version),
+            tags: vec!["sql".to_string()],
+            version,
+            ..Snippet::for_test("s1", content)
+        }
+    }
+
+    #[test]
+    fn test_versions_are_kept_and_orphans_dropped() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_snippet_versions_table(&conn).unwrap();
+        record_version(&conn, &snippet(0, "one")).unwrap();
+        record_version(&conn, &snippet(1, "two")).unwrap();
+
+        let versions = list_versions(&conn, "s1").unwrap();
+        assert_eq!(versions.iter().map(|v| v.version).collect::<Vec<_>>(), vec![1, 0]);
+        assert_eq!(versions[0].tags, vec!["sql".to_string()]);
+        assert_eq!(get_version(&conn, "s1", 0).unwrap().unwrap().content, "one");
+        assert!(get_version(&conn, "s1", 7).unwrap().is_none());
+
+        assert_eq!(delete_orphaned(&conn, &["s1".to_string()]).unwrap(), 0);
+        assert_eq!(delete_orphaned(&conn, &[]).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_changes_are_recorded_with_new_versions() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_snippet_versions_table(&conn).unwrap();
+        let saved = vec![snippet(3, "old"), Snippet::for_test("s2", "untouched")];
+
+        // A bulk save that sends the snippet back with its version unchanged
+        let mut incoming = vec![snippet(3, "new"), Snippet::for_test("s2", "untouched")];
+        record_changes(&conn, &saved, &mut incoming).unwrap();
+        assert_eq!(incoming[0].version, 4);
+        assert_eq!(incoming[1].version, 0);
+        assert_eq!(get_version(&conn, "s1", 3).unwrap().unwrap().content, "old");
+        assert!(list_versions(&conn, "s2").unwrap().is_empty());
+
+        // An edit that already moved the version on keeps it
+        let mut incoming = vec![snippet(4, "newer")];
+        record_changes(&conn, &[snippet(3, "new")], &mut incoming).unwrap();
+        assert_eq!(incoming[0].version, 4);
+    }
+
+    #[test]
+    fn test_stale_saves_are_refused() {
+        let saved = vec![snippet(5, "current"), Snippet::for_test("s2", "other")];
+
+        // Changed from an older copy
+        let err = ensure_current(&saved, &[snippet(4, "edited")]).unwrap_err();
+        assert_eq!(err.code, ErrorCode::Conflict);
+        assert_eq!(err.details.unwrap()["current"]["content"], "current");
+
+        // Unchanged snippets may be sent back with any version; current ones may change
+        assert!(ensure_current(&saved, &[snippet(4, "current"), Snippet::for_test("s3", "new")]).is_ok());
+        assert!(ensure_current(&saved, &[snippet(5, "edited")]).is_ok());
+    }
+}
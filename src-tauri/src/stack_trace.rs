@@ -0,0 +1,667 @@
+// Stack trace prettifier for JS/TS, Python, Rust and Java traces: parses frames,
+// demangles Rust symbols, folds runtime/library frames (node_modules, asyncio, std,
+// java.*) and re-renders the trace with aligned columns for pasting into chat.
+// Minified JS frames can be mapped back to their sources with a v3 source map.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+
+/// Function names longer than this don't widen the alignment column
+const MAX_FUNCTION_WIDTH: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceLanguage {
+    JavaScript,
+    Python,
+    Rust,
+    Java,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StackTraceOptions {
+    /// Fold node_modules/asyncio/std/JDK frames into a "… N frames hidden" line
+    pub hide_noise: bool,
+    /// Demangle legacy Rust symbols and drop the `::h<hash>` suffix
+    pub demangle: bool,
+    /// Source map (JSON) to resolve minified JS frames with
+    pub source_map: Option<String>,
+}
+
+impl Default for StackTraceOptions {
+    fn default() -> Self {
+        Self {
+            hide_noise: true,
+            demangle: true,
+            source_map: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StackFrame {
+    pub function: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// Source line Python prints under each frame
+    pub code: Option<String>,
+    /// Runtime or library frame rather than application code
+    pub noise: bool,
+    /// Location was rewritten through the source map
+    pub mapped: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormattedTrace {
+    pub language: TraceLanguage,
+    pub frames: Vec<StackFrame>,
+    pub hidden_frames: usize,
+    pub formatted: String,
+}
+
+enum TraceItem {
+    Text(String),
+    Frame(StackFrame),
+}
+
+fn regex(pattern: &'static str, cell: &'static OnceLock<Regex>) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid regex"))
+}
+
+fn v8_frame() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(r"^\s*at\s+(?:(.*?)\s+\()?(.+?):(\d+):(\d+)\)?\s*$", &RE)
+}
+
+fn firefox_frame() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(r"^\s*([^@\s]*)@(.+?):(\d+):(\d+)\s*$", &RE)
+}
+
+fn python_frame() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(r#"^(\s*)File "(.+?)", line (\d+)(?:, in (.+?))?\s*$"#, &RE)
+}
+
+fn rust_frame() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(r"^\s*\d+:\s+(\S.*?)\s*$", &RE)
+}
+
+fn rust_location() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(r"^\s*at\s+(.+?):(\d+)(?::(\d+))?\s*$", &RE)
+}
+
+fn java_frame() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    regex(r"^\s*at\s+([\w$.<>/]+)\(([^():]*)(?::(\d+))?\)\s*$", &RE)
+}
+
+fn detect_language(lines: &[&str]) -> Option<TraceLanguage> {
+    if lines
+        .iter()
+        .any(|l| l.starts_with("Traceback (most recent call last)") || python_frame().is_match(l))
+    {
+        return Some(TraceLanguage::Python);
+    }
+    let rust_header = lines
+        .iter()
+        .any(|l| l.contains("' panicked at ") || l.trim() == "stack backtrace:");
+    let rust_frames = lines
+        .windows(2)
+        .any(|w| rust_frame().is_match(w[0]) && rust_location().is_match(w[1]));
+    if rust_header || rust_frames {
+        return Some(TraceLanguage::Rust);
+    }
+    if lines.iter().any(|l| java_frame().is_match(l)) {
+        return Some(TraceLanguage::Java);
+    }
+    if lines.iter().any(|l| v8_frame().is_match(l) || firefox_frame().is_match(l)) {
+        return Some(TraceLanguage::JavaScript);
+    }
+    None
+}
+
+fn parse_number(value: Option<regex::Match>) -> Option<u32> {
+    value.and_then(|m| m.as_str().parse().ok())
+}
+
+fn frame(function: Option<&str>, file: Option<&str>, line: Option<u32>, column: Option<u32>) -> StackFrame {
+    StackFrame {
+        function: function.filter(|f| !f.is_empty()).map(str::to_string),
+        file: file.filter(|f| !f.is_empty()).map(str::to_string),
+        line,
+        column,
+        code: None,
+        noise: false,
+        mapped: false,
+    }
+}
+
+fn parse_items(lines: &[&str], language: TraceLanguage) -> Vec<TraceItem> {
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+        let parsed = match language {
+            TraceLanguage::JavaScript => v8_frame()
+                .captures(line)
+                .or_else(|| firefox_frame().captures(line))
+                .map(|c| {
+                    frame(
+                        c.get(1).map(|m| m.as_str()),
+                        c.get(2).map(|m| m.as_str()),
+                        parse_number(c.get(3)),
+                        parse_number(c.get(4)),
+                    )
+                }),
+            TraceLanguage::Python => python_frame().captures(line).map(|c| {
+                let mut parsed = frame(c.get(4).map(|m| m.as_str()), Some(&c[2]), parse_number(c.get(3)), None);
+                // The source line follows, indented deeper than "File"
+                let indent = c[1].len();
+                if let Some(next) = lines.get(i) {
+                    let next_indent = next.len() - next.trim_start().len();
+                    if next_indent > indent && !next.trim().is_empty() && !python_frame().is_match(next) {
+                        parsed.code = Some(next.trim().to_string());
+                        i += 1;
+                    }
+                }
+                parsed
+            }),
+            TraceLanguage::Rust => rust_frame().captures(line).map(|c| {
+                let mut parsed = frame(Some(&c[1]), None, None, None);
+                if let Some(location) = lines.get(i).and_then(|next| rust_location().captures(next)) {
+                    parsed.file = Some(location[1].to_string());
+                    parsed.line = parse_number(location.get(2));
+                    parsed.column = parse_number(location.get(3));
+                    i += 1;
+                }
+                parsed
+            }),
+            TraceLanguage::Java => java_frame().captures(line).map(|c| {
+                // "(Native Method)" / "(Unknown Source)" carry no file
+                let file = c.get(3).map(|_| &c[2]).or_else(|| c[2].contains('.').then(|| &c[2]));
+                frame(Some(&c[1]), file, parse_number(c.get(3)), None)
+            }),
+        };
+        match parsed {
+            Some(frame) => items.push(TraceItem::Frame(frame)),
+            None => items.push(TraceItem::Text(line.trim_end().to_string())),
+        }
+    }
+    items
+}
+
+fn is_noise(language: TraceLanguage, frame: &StackFrame) -> bool {
+    let file = frame.file.as_deref().unwrap_or("").replace('\\', "/");
+    let function = frame.function.as_deref().unwrap_or("");
+    match language {
+        TraceLanguage::JavaScript => {
+            file.contains("node_modules/") || file.starts_with("node:") || file.starts_with("internal/")
+        }
+        TraceLanguage::Python => {
+            file.contains("/asyncio/") || file.starts_with("<frozen ") || file.contains("/concurrent/futures/")
+        }
+        TraceLanguage::Rust => {
+            file.starts_with("/rustc/")
+                || [
+                    "std::", "core::", "alloc::", "<std::", "<core::", "<alloc::", "rust_begin_unwind",
+                    "__rust", "_start", "__libc_start", "start_thread", "clone",
+                ]
+                .iter()
+                .any(|prefix| function.starts_with(prefix))
+        }
+        TraceLanguage::Java => ["java.", "javax.", "jdk.internal.", "sun.", "kotlin.coroutines."]
+            .iter()
+            .any(|prefix| function.starts_with(prefix)),
+    }
+}
+
+// ============================================================================
+// Rust symbol demangling
+// ============================================================================
+
+fn is_rust_hash(segment: &str) -> bool {
+    segment.len() == 17 && segment.starts_with('h') && segment[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn unescape_rust_segment(segment: &str) -> String {
+    let segment = segment.strip_prefix("_$").map(|s| format!("${}", s)).unwrap_or_else(|| segment.to_string());
+    let mut out = String::new();
+    let mut rest = segment.as_str();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            out.push_str("::");
+            rest = after;
+        } else if rest.starts_with('$') {
+            let Some(end) = rest[1..].find('$') else {
+                out.push_str(rest);
+                break;
+            };
+            let code = &rest[1..=end];
+            let replacement = match code {
+                "SP" => Some("@".to_string()),
+                "BP" => Some("*".to_string()),
+                "RF" => Some("&".to_string()),
+                "LT" => Some("<".to_string()),
+                "GT" => Some(">".to_string()),
+                "LP" => Some("(".to_string()),
+                "RP" => Some(")".to_string()),
+                "C" => Some(",".to_string()),
+                _ => code
+                    .strip_prefix('u')
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(char::from_u32)
+                    .map(String::from),
+            };
+            match replacement {
+                Some(replacement) => {
+                    out.push_str(&replacement);
+                    rest = &rest[end + 2..];
+                }
+                None => {
+                    out.push('$');
+                    rest = &rest[1..];
+                }
+            }
+        } else {
+            let next = rest.find(['$', '.']).unwrap_or(rest.len()).max(1);
+            out.push_str(&rest[..next]);
+            rest = &rest[next..];
+        }
+    }
+    out
+}
+
+/// Demangle a legacy (`_ZN…E`) Rust symbol, or just drop the hash suffix from an
+/// already-demangled one. Anything else is returned unchanged.
+pub fn demangle_rust(symbol: &str) -> String {
+    let Some(mut rest) = symbol.strip_prefix("_ZN").or_else(|| symbol.strip_prefix("__ZN")) else {
+        static HASH: OnceLock<Regex> = OnceLock::new();
+        return regex(r"::h[0-9a-f]{16}$", &HASH).replace(symbol, "").into_owned();
+    };
+
+    let mut segments = Vec::new();
+    while !rest.starts_with('E') {
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let Ok(len) = rest[..digits].parse::<usize>() else {
+            return symbol.to_string();
+        };
+        let Some(segment) = rest.get(digits..digits + len) else {
+            return symbol.to_string();
+        };
+        segments.push(segment);
+        rest = &rest[digits + len..];
+    }
+    if segments.last().is_some_and(|s| is_rust_hash(s)) {
+        segments.pop();
+    }
+    segments.into_iter().map(unescape_rust_segment).collect::<Vec<_>>().join("::")
+}
+
+// ============================================================================
+// Source maps
+// ============================================================================
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSourceMap {
+    version: u32,
+    file: Option<String>,
+    source_root: Option<String>,
+    sources: Vec<Option<String>>,
+    #[serde(default)]
+    names: Vec<String>,
+    mappings: String,
+}
+
+struct Segment {
+    generated_column: i64,
+    /// (source index, original line, original column, name index), all 0-based
+    original: Option<(i64, i64, i64, Option<i64>)>,
+}
+
+struct SourceMap {
+    file: Option<String>,
+    sources: Vec<Option<String>>,
+    names: Vec<String>,
+    lines: Vec<Vec<Segment>>,
+}
+
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn decode_vlq(segment: &str) -> Result<Vec<i64>, String> {
+    let mut values = Vec::new();
+    let mut value: i64 = 0;
+    let mut shift = 0;
+    for byte in segment.bytes() {
+        let digit = BASE64_CHARS
+            .iter()
+            .position(|&c| c == byte)
+            .ok_or_else(|| format!("Invalid character in source map mappings: {}", byte as char))?
+            as i64;
+        value += (digit & 31) << shift;
+        if digit & 32 != 0 {
+            shift += 5;
+            if shift > 60 {
+                return Err("Source map value out of range".to_string());
+            }
+        } else {
+            let magnitude = value >> 1;
+            values.push(if value & 1 == 1 { -magnitude } else { magnitude });
+            value = 0;
+            shift = 0;
+        }
+    }
+    if shift != 0 {
+        return Err("Truncated value in source map mappings".to_string());
+    }
+    Ok(values)
+}
+
+impl SourceMap {
+    fn parse(json: &str) -> Result<Self, String> {
+        let raw: RawSourceMap = serde_json::from_str(json).map_err(|e| format!("Invalid source map: {}", e))?;
+        if raw.version != 3 {
+            return Err(format!("Unsupported source map version: {}", raw.version));
+        }
+
+        let mut lines = Vec::new();
+        let (mut source, mut line, mut column, mut name) = (0i64, 0i64, 0i64, 0i64);
+        for mapping_line in raw.mappings.split(';') {
+            let mut generated_column = 0i64;
+            let mut segments = Vec::new();
+            for encoded in mapping_line.split(',').filter(|s| !s.is_empty()) {
+                let values = decode_vlq(encoded)?;
+                let Some(&delta) = values.first() else {
+                    continue;
+                };
+                generated_column += delta;
+                let mut segment = Segment {
+                    generated_column,
+                    original: None,
+                };
+                if values.len() >= 4 {
+                    source += values[1];
+                    line += values[2];
+                    column += values[3];
+                    let name_index = values.get(4).map(|delta| {
+                        name += delta;
+                        name
+                    });
+                    segment.original = Some((source, line, column, name_index));
+                }
+                segments.push(segment);
+            }
+            lines.push(segments);
+        }
+
+        let root = raw.source_root.filter(|r| !r.is_empty());
+        let sources = raw
+            .sources
+            .into_iter()
+            .map(|s| {
+                s.map(|s| match &root {
+                    Some(root) => format!("{}/{}", root.trim_end_matches('/'), s),
+                    None => s,
+                })
+            })
+            .collect();
+        Ok(SourceMap {
+            file: raw.file,
+            sources,
+            names: raw.names,
+            lines,
+        })
+    }
+
+    /// Original (file, line, column, name) for a 1-based generated position
+    fn lookup(&self, line: u32, column: u32) -> Option<(String, u32, u32, Option<String>)> {
+        let segments = self.lines.get(line.checked_sub(1)? as usize)?;
+        let column = i64::from(column.saturating_sub(1));
+        let segment = segments
+            .iter()
+            .rev()
+            .find(|s| s.generated_column <= column && s.original.is_some())?;
+        let (source, line, column, name) = segment.original?;
+        let file = self.sources.get(usize::try_from(source).ok()?)?.clone()?;
+        let name = name.and_then(|n| self.names.get(usize::try_from(n).ok()?).cloned());
+        Some((file, u32::try_from(line + 1).ok()?, u32::try_from(column + 1).ok()?, name))
+    }
+
+    /// Whether a frame's file is the bundle this map describes
+    fn covers(&self, file: &str) -> bool {
+        let basename = |path: &str| path.rsplit(['/', '\\']).next().unwrap_or(path).to_string();
+        match &self.file {
+            Some(mapped) => basename(file.split(['?', '#']).next().unwrap_or(file)) == basename(mapped),
+            None => true,
+        }
+    }
+}
+
+fn apply_source_map(items: &mut [TraceItem], map: &SourceMap) {
+    for item in items.iter_mut() {
+        let TraceItem::Frame(frame) = item else {
+            continue;
+        };
+        let (Some(file), Some(line), Some(column)) = (frame.file.as_deref(), frame.line, frame.column) else {
+            continue;
+        };
+        if !map.covers(file) {
+            continue;
+        }
+        if let Some((source, line, column, name)) = map.lookup(line, column) {
+            frame.file = Some(source);
+            frame.line = Some(line);
+            frame.column = Some(column);
+            if name.is_some() {
+                frame.function = name;
+            }
+            frame.mapped = true;
+        }
+    }
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+fn location(frame: &StackFrame) -> String {
+    let mut location = frame.file.clone().unwrap_or_else(|| "<unknown>".to_string());
+    if let Some(line) = frame.line {
+        location.push_str(&format!(":{}", line));
+        if let Some(column) = frame.column {
+            location.push_str(&format!(":{}", column));
+        }
+    }
+    location
+}
+
+fn hidden_line(count: usize) -> String {
+    format!("  … {} internal frame{} hidden", count, if count == 1 { "" } else { "s" })
+}
+
+fn render(items: &[TraceItem], hide_noise: bool) -> (String, usize) {
+    let visible = |frame: &StackFrame| !(hide_noise && frame.noise);
+    let width = items
+        .iter()
+        .filter_map(|item| match item {
+            TraceItem::Frame(frame) if visible(frame) => {
+                Some(frame.function.as_deref().unwrap_or("<anonymous>").chars().count())
+            }
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+        .min(MAX_FUNCTION_WIDTH);
+
+    let mut out = Vec::new();
+    let mut hidden = 0;
+    let mut pending = 0;
+    for item in items {
+        match item {
+            TraceItem::Frame(frame) if !visible(frame) => {
+                pending += 1;
+                hidden += 1;
+            }
+            TraceItem::Frame(frame) => {
+                if pending > 0 {
+                    out.push(hidden_line(pending));
+                    pending = 0;
+                }
+                let function = frame.function.as_deref().unwrap_or("<anonymous>");
+                out.push(format!("  at {:<width$}  {}", function, location(frame), width = width));
+                if let Some(code) = &frame.code {
+                    out.push(format!("       {}", code));
+                }
+            }
+            TraceItem::Text(text) => {
+                if pending > 0 {
+                    out.push(hidden_line(pending));
+                    pending = 0;
+                }
+                out.push(text.clone());
+            }
+        }
+    }
+    if pending > 0 {
+        out.push(hidden_line(pending));
+    }
+    (out.join("\n").trim_end().to_string(), hidden)
+}
+
+pub fn format_trace(text: &str, options: &StackTraceOptions) -> Result<FormattedTrace, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let language = detect_language(&lines).ok_or_else(|| "No stack trace found".to_string())?;
+    let mut items = parse_items(&lines, language);
+
+    if let (TraceLanguage::JavaScript, Some(json)) = (language, options.source_map.as_deref()) {
+        apply_source_map(&mut items, &SourceMap::parse(json)?);
+    }
+
+    for item in items.iter_mut() {
+        if let TraceItem::Frame(frame) = item {
+            if language == TraceLanguage::Rust && options.demangle {
+                frame.function = frame.function.as_deref().map(demangle_rust);
+            }
+            frame.noise = is_noise(language, frame);
+        }
+    }
+
+    let (formatted, hidden_frames) = render(&items, options.hide_noise);
+    let frames = items
+        .into_iter()
+        .filter_map(|item| match item {
+            TraceItem::Frame(frame) => Some(frame),
+            TraceItem::Text(_) => None,
+        })
+        .collect();
+    Ok(FormattedTrace {
+        language,
+        frames,
+        hidden_frames,
+        formatted,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn format_stack_trace(text: String, options: Option<StackTraceOptions>) -> Result<FormattedTrace, AppError> {
+    format_trace(&text, &options.unwrap_or_default()).map_err(AppError::invalid_input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_trace_hides_asyncio() {
+        let trace = "Traceback (most recent call last):\n  \
+            File \"/app/main.py\", line 10, in <module>\n    asyncio.run(main())\n  \
+            File \"/usr/lib/python3.12/asyncio/runners.py\", line 194, in run\n    return runner.run(main)\n  \
+            File \"/app/main.py\", line 6, in main\n    raise ValueError(\"boom\")\nValueError: boom";
+        let result = format_trace(trace, &StackTraceOptions::default()).unwrap();
+        assert_eq!(result.language, TraceLanguage::Python);
+        assert_eq!(result.frames.len(), 3);
+        assert_eq!(result.frames[0].code.as_deref(), Some("asyncio.run(main())"));
+        assert_eq!(result.hidden_frames, 1);
+        assert_eq!(
+            result.formatted,
+            "Traceback (most recent call last):\n  \
+             at <module>  /app/main.py:10\n       asyncio.run(main())\n  \
+             … 1 internal frame hidden\n  \
+             at main      /app/main.py:6\n       raise ValueError(\"boom\")\nValueError: boom"
+        );
+    }
+
+    #[test]
+    fn test_rust_trace_demangles() {
+        let trace = "thread 'main' panicked at src/main.rs:4:5:\nboom\nstack backtrace:\n   \
+            0: rust_begin_unwind\n             at /rustc/abc/library/std/src/panicking.rs:652:5\n   \
+            1: _ZN5myapp4main17h0123456789abcdefE\n             at ./src/main.rs:4:5\n   \
+            2: core::ops::function::FnOnce::call_once::hfedcba9876543210";
+        let result = format_trace(trace, &StackTraceOptions::default()).unwrap();
+        assert_eq!(result.language, TraceLanguage::Rust);
+        assert_eq!(result.frames[1].function.as_deref(), Some("myapp::main"));
+        assert_eq!(result.frames[1].line, Some(4));
+        assert_eq!(result.frames[2].function.as_deref(), Some("core::ops::function::FnOnce::call_once"));
+        assert_eq!(result.hidden_frames, 2);
+    }
+
+    #[test]
+    fn test_demangle_rust() {
+        assert_eq!(
+            demangle_rust("_ZN66_$LT$alloc..vec..Vec$LT$T$GT$$u20$as$u20$core..ops..drop..Drop$GT$4drop17h1234567890abcdefE"),
+            "<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop"
+        );
+        assert_eq!(demangle_rust("not_mangled"), "not_mangled");
+    }
+
+    #[test]
+    fn test_java_and_js_detection() {
+        let java = "java.lang.IllegalStateException: bad\n\tat com.acme.Service.run(Service.java:42)\n\
+                    \tat java.base/java.lang.Thread.run(Thread.java:833)\n\tat jdk.internal.Foo.bar(Native Method)";
+        let result = format_trace(java, &StackTraceOptions::default()).unwrap();
+        assert_eq!(result.language, TraceLanguage::Java);
+        assert_eq!(result.frames[0].file.as_deref(), Some("Service.java"));
+        assert_eq!(result.frames[2].file, None);
+        assert!(result.frames[2].noise);
+
+        let node = "TypeError: x is undefined\n    at handler (/app/src/api.js:12:5)\n    \
+                    at Layer.handle (/app/node_modules/express/lib/router/layer.js:95:5)";
+        let result = format_trace(node, &StackTraceOptions::default()).unwrap();
+        assert_eq!(result.language, TraceLanguage::JavaScript);
+        assert!(result.frames[1].noise);
+        assert!(format_trace("no trace here", &StackTraceOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_source_map() {
+        // Line 1: col 0 -> app.ts 1:1, col 5 -> app.ts 1:6 named "boom"; line 2: col 0 -> app.ts 2:6
+        let map = r#"{"version":3,"file":"bundle.min.js","sources":["app.ts"],"sourceRoot":"src",
+                      "names":["boom"],"mappings":"AAAA,KAAKA;AACA"}"#;
+        let options = StackTraceOptions {
+            source_map: Some(map.to_string()),
+            ..Default::default()
+        };
+        let trace = "Error: x\n    at e (https://cdn.example.com/bundle.min.js:1:7)\n    at other.js:1:1";
+        let result = format_trace(trace, &options).unwrap();
+        let frame = &result.frames[0];
+        assert!(frame.mapped);
+        assert_eq!(frame.file.as_deref(), Some("src/app.ts"));
+        assert_eq!((frame.line, frame.column), (Some(1), Some(6)));
+        assert_eq!(frame.function.as_deref(), Some("boom"));
+        assert!(!result.frames[1].mapped);
+        assert_eq!(decode_vlq("2Hw+B").unwrap(), vec![123, 1000]);
+    }
+}
@@ -0,0 +1,133 @@
+// Startup profiling. `run` and the setup hook wrap each phase in `measure`, and work the
+// hotkey doesn't depend on (tray, indexer, cache warmup, license refresh, ...) goes
+// through `defer`, which runs it on a background thread shortly after setup returns.
+// `ready` marks the point the panel can be summoned; everything is logged and returned
+// by get_startup_metrics.
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// How long deferred work waits, so it doesn't compete with the first frames
+const DEFER_DELAY: Duration = Duration::from_millis(500);
+
+static STARTED: OnceLock<Instant> = OnceLock::new();
+static PHASES: Mutex<Vec<StartupPhase>> = Mutex::new(Vec::new());
+static READY_MS: Mutex<Option<f64>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupPhase {
+    pub name: String,
+    /// Milliseconds after `begin` the phase started
+    pub started_ms: f64,
+    pub duration_ms: f64,
+    /// Ran after setup instead of before the hotkey was registered
+    pub deferred: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupMetrics {
+    pub phases: Vec<StartupPhase>,
+    /// Milliseconds from `begin` until setup finished and the hotkey could open the panel
+    pub ready_ms: Option<f64>,
+}
+
+fn since_start(instant: Instant) -> f64 {
+    let started = *STARTED.get_or_init(Instant::now);
+    instant.saturating_duration_since(started).as_secs_f64() * 1000.0
+}
+
+fn time<T>(name: &str, deferred: bool, work: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = work();
+    let phase = StartupPhase {
+        name: name.to_string(),
+        started_ms: since_start(started),
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        deferred,
+    };
+    log::info!(
+        "Startup: {}{} took {:.1}ms",
+        phase.name,
+        if deferred { " (deferred)" } else { "" },
+        phase.duration_ms
+    );
+    if let Ok(mut phases) = PHASES.lock() {
+        phases.push(phase);
+    }
+    result
+}
+
+/// Start the clock; call first thing in `run`
+pub fn begin() {
+    STARTED.get_or_init(Instant::now);
+}
+
+/// Run a phase that has to finish before the app is ready, recording how long it took
+pub fn measure<T>(name: &str, work: impl FnOnce() -> T) -> T {
+    time(name, false, work)
+}
+
+/// Run non-critical work on a background thread once startup has settled
+pub fn defer(name: &'static str, work: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(move || {
+        std::thread::sleep(DEFER_DELAY);
+        time(name, true, work);
+    });
+}
+
+/// `defer` for work that has to happen on the main thread, like building the tray
+pub fn defer_on_main_thread(app: &AppHandle, name: &'static str, work: impl FnOnce() + Send + 'static) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(DEFER_DELAY);
+        if let Err(e) = app.run_on_main_thread(move || time(name, true, work)) {
+            log::warn!("Failed to run {} on the main thread: {}", name, e);
+        }
+    });
+}
+
+/// Mark the app as ready for the hotkey
+pub fn ready() {
+    let ready_ms = since_start(Instant::now());
+    log::info!("Startup: ready after {:.1}ms", ready_ms);
+    if let Ok(mut ready) = READY_MS.lock() {
+        *ready = Some(ready_ms);
+    }
+}
+
+pub fn metrics() -> StartupMetrics {
+    StartupMetrics {
+        phases: PHASES.lock().map(|phases| phases.clone()).unwrap_or_default(),
+        ready_ms: READY_MS.lock().map(|ready| *ready).unwrap_or_default(),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Phase timings from this launch, including deferred work that has finished so far
+#[tauri::command]
+pub fn get_startup_metrics() -> StartupMetrics {
+    metrics()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_records_phase() {
+        begin();
+        assert_eq!(measure("test phase", || 7), 7);
+        ready();
+
+        let metrics = metrics();
+        let phase = metrics.phases.iter().find(|phase| phase.name == "test phase").unwrap();
+        assert!(!phase.deferred);
+        assert!(phase.started_ms >= 0.0 && phase.duration_ms >= 0.0);
+        assert!(metrics.ready_ms.is_some());
+    }
+}
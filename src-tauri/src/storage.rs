@@ -51,6 +51,9 @@ pub struct AppSettings {
     pub window_position: WindowPosition,
     #[serde(default)]
     pub sticky_mode: bool,
+    /// Which display the panel opens on (macOS)
+    #[serde(default)]
+    pub panel_placement: PanelPlacement,
     #[serde(default)]
     pub show_diff_preview: bool,
     #[serde(default = "default_primary_action")]
@@ -73,6 +76,371 @@ pub struct AppSettings {
     pub show_intelligent_suggestions: bool,
     #[serde(default = "default_toolbar_order")]
     pub toolbar_order: Vec<String>,
+    #[serde(default = "default_update_channel")]
+    pub update_channel: String,
+    #[serde(default)]
+    pub auto_download_updates: bool,
+    /// Global shortcut that cycles through profiles
+    #[serde(default)]
+    pub profile_switch_hotkey: Option<String>,
+    /// Index history and snippets for semantic_search (embeddings are computed locally)
+    #[serde(default)]
+    pub semantic_search_enabled: bool,
+    /// Backend for summarize_text/suggest_title: "ai" (Claude proxy) or "local"
+    #[serde(default = "default_summary_backend")]
+    pub summary_backend: String,
+    /// Discover, pair with and send items to other Wingman devices on the local network
+    #[serde(default)]
+    pub lan_sync_enabled: bool,
+    /// Record AI prompts and responses (see ai_history.rs)
+    #[serde(default)]
+    pub ai_history_enabled: bool,
+    /// Days AI history is kept; 0 keeps it forever
+    #[serde(default = "default_ai_history_retention_days")]
+    pub ai_history_retention_days: u32,
+    /// Days deleted history entries and snippets stay in the trash; 0 keeps them until emptied
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// Which system notifications are shown (see notifications.rs)
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    /// Folder, filename template and overwrite policy for quick_save (see quick_save.rs)
+    #[serde(default)]
+    pub quick_save: QuickSaveSettings,
+    /// Serve launcher queries on localhost for Alfred/Raycast extensions (see local_api.rs)
+    #[serde(default)]
+    pub local_api_enabled: bool,
+    /// Size limit for history entries and what happens above it (see oversize.rs)
+    #[serde(default)]
+    pub history_limits: HistoryLimits,
+    /// Broadcast app activity to dashboards over a localhost WebSocket (see event_bridge.rs)
+    #[serde(default)]
+    pub event_bridge: EventBridgeSettings,
+    /// Append every command invocation to a rotating audit log (see command_middleware.rs)
+    #[serde(default)]
+    pub audit_log_enabled: bool,
+    /// Proxy for every outgoing request (see http_client.rs)
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    /// Extra CA bundle and Supabase certificate pins (see http_client.rs)
+    #[serde(default)]
+    pub tls: TlsSettings,
+    /// Keep every network feature off, for air-gapped machines (see offline.rs)
+    #[serde(default)]
+    pub offline_mode: bool,
+    #[serde(default)]
+    pub command_runner: CommandRunnerSettings,
+    /// Sizes cycle_window_preset steps through (see window_presets.rs)
+    #[serde(default = "default_window_presets")]
+    pub window_presets: Vec<WindowPreset>,
+    /// Global shortcut that cycles through the window presets
+    #[serde(default)]
+    pub window_preset_hotkey: Option<String>,
+    /// How the panel appears and disappears (see panel_animation.rs)
+    #[serde(default)]
+    pub panel_animation: PanelAnimationSettings,
+    /// Actions that need Touch ID or Windows Hello first (see auth_gate.rs)
+    #[serde(default)]
+    pub authentication_gate: AuthenticationGate,
+    /// How timestamps are shown by unix_to_human and format_timestamp (see date_format.rs)
+    #[serde(default)]
+    pub date_time: DateTimeSettings,
+    /// Store rich (HTML/RTF) captures as Markdown (see rich_markdown.rs)
+    #[serde(default)]
+    pub rich_to_markdown: RichToMarkdownSettings,
+}
+
+/// Per-category switches for system notifications. Missing fields take their defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub formatting: bool,
+    pub ai: bool,
+    pub gist_sync: bool,
+    pub export: bool,
+    pub reminders: bool,
+    /// Skip operation notifications while the panel is on screen
+    pub only_when_hidden: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            formatting: true,
+            ai: true,
+            gist_sync: true,
+            export: true,
+            reminders: true,
+            only_when_hidden: true,
+        }
+    }
+}
+
+/// The event bridge switch and which event types it sends. Missing fields take their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventBridgeSettings {
+    pub enabled: bool,
+    pub entry_added: bool,
+    pub transform_run: bool,
+    pub ai_call_completed: bool,
+}
+
+impl Default for EventBridgeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            entry_added: true,
+            transform_run: true,
+            ai_call_completed: true,
+        }
+    }
+}
+
+/// How outgoing HTTP requests reach the network
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    /// Proxy environment variables and the OS configuration
+    #[default]
+    System,
+    /// No proxy, even if the system has one
+    Direct,
+    Manual,
+}
+
+/// Proxy settings. The manual proxy's password is in the keychain. Missing fields take
+/// their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    pub mode: ProxyMode,
+    /// host:port or a full URL, for the manual proxy
+    pub server: String,
+    /// Empty for a proxy without authentication
+    pub username: String,
+    /// Comma-separated hosts, domains (".corp.example") and IP ranges that skip the manual proxy
+    pub bypass: String,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            mode: ProxyMode::System,
+            server: String::new(),
+            username: String::new(),
+            bypass: "localhost,127.0.0.1,::1".to_string(),
+        }
+    }
+}
+
+/// TLS trust settings for outgoing requests
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsSettings {
+    /// PEM file of extra trusted CAs, e.g. a TLS-inspecting proxy's; empty for none
+    pub ca_bundle_path: String,
+    /// "sha256/<base64>" SPKI hashes; when set, Supabase connections must match one
+    pub supabase_pins: Vec<String>,
+}
+
+/// How command snippets run (see command_runner.rs)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandRunnerSettings {
+    /// Shell to run commands with; $SHELL (or /bin/sh, cmd on Windows) when unset
+    pub shell: Option<String>,
+    /// Working directory; a scratch folder in the app data directory when unset
+    pub working_dir: Option<String>,
+    pub timeout_secs: u64,
+    /// Environment variables passed through to the command; everything else is dropped
+    pub env_allowlist: Vec<String>,
+    /// Show the command and ask before running it
+    pub confirm_before_run: bool,
+    /// Add the output to history as a new entry
+    pub save_output_to_history: bool,
+}
+
+impl Default for CommandRunnerSettings {
+    fn default() -> Self {
+        Self {
+            shell: None,
+            working_dir: None,
+            timeout_secs: 30,
+            env_allowlist: ["PATH", "HOME", "USER", "LANG", "LC_ALL", "TERM", "TMPDIR", "SYSTEMROOT", "TEMP"]
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            confirm_before_run: true,
+            save_output_to_history: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelPlacement {
+    /// The display the mouse cursor is on
+    #[default]
+    Cursor,
+    /// The display showing the frontmost app's focused window, falling back to the
+    /// cursor's display when it can't be found
+    ActiveWindow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelAnimation {
+    /// Appear and disappear instantly
+    #[default]
+    None,
+    Fade,
+    /// Fade while sliding down into place (and back up when hiding)
+    SlideFromTop,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelAnimationSettings {
+    pub show: PanelAnimation,
+    pub hide: PanelAnimation,
+    pub duration_ms: u32,
+    /// Use fades instead of slides; the system Reduce Motion preference does the same
+    pub reduce_motion: bool,
+}
+
+impl Default for PanelAnimationSettings {
+    fn default() -> Self {
+        Self {
+            show: PanelAnimation::None,
+            hide: PanelAnimation::None,
+            duration_ms: 150,
+            reduce_motion: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthenticationGate {
+    /// Showing a stored secret with reveal_credential
+    pub reveal_credentials: bool,
+    /// Opening history entries flagged as sensitive (card numbers, private keys, API
+    /// tokens); their listed content is masked while this is on
+    pub sensitive_entries: bool,
+    /// Turning privacy mode off, so capture can't be resumed behind the user's back
+    pub disable_privacy_mode: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RichToMarkdownSettings {
+    pub enabled: bool,
+    /// Source apps by name or bundle id, case-insensitive; empty converts captures from every app
+    pub apps: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DateTimeSettings {
+    /// ICU-style date pattern, e.g. "yyyy-MM-dd" or "dd.MM.yyyy"
+    pub date_pattern: String,
+    /// 24-hour times instead of 12-hour with AM/PM
+    pub clock_24h: bool,
+    /// "local" for the system time zone, an IANA zone like "America/New_York", "UTC", an
+    /// abbreviation like "PST" (DST-aware) or a fixed offset like "+05:30"
+    pub timezone: String,
+}
+
+impl Default for DateTimeSettings {
+    fn default() -> Self {
+        Self {
+            date_pattern: "yyyy-MM-dd".to_string(),
+            clock_24h: true,
+            timezone: "UTC".to_string(),
+        }
+    }
+}
+
+/// Where a window preset puts the window on its screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowAnchor {
+    /// Resize around the window's current center
+    #[default]
+    Center,
+    /// Flush against the right edge of the screen
+    RightEdge,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowPreset {
+    pub name: String,
+    /// Logical pixels; clamped to the screen
+    pub width: u32,
+    /// None fills the screen's visible height (below the menu bar, above the Dock)
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub anchor: WindowAnchor,
+}
+
+/// What quick save does when the target file already exists
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverwritePolicy {
+    /// Save as "name (2).ext", "name (3).ext", ...
+    #[default]
+    Rename,
+    Overwrite,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuickSaveSettings {
+    /// Target folder; the Downloads folder when unset. A leading `~/` is expanded.
+    pub directory: Option<String>,
+    /// Filename with `{{date}}`, `{{time}}`, `{{datetime}}`, `{{language}}` and `{{ext}}`
+    pub filename_template: String,
+    pub overwrite: OverwritePolicy,
+}
+
+impl Default for QuickSaveSettings {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            filename_template: "{{date}}-{{language}}.{{ext}}".to_string(),
+            overwrite: OverwritePolicy::Rename,
+        }
+    }
+}
+
+/// What happens to history entries larger than `HistoryLimits::max_entry_bytes`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizedPolicy {
+    /// Keep the full content in a file; the database holds the first `max_entry_bytes`
+    #[default]
+    Overflow,
+    /// Cut the content at `max_entry_bytes` and append a notice saying so
+    Truncate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryLimits {
+    pub max_entry_bytes: u64,
+    pub oversized: OversizedPolicy,
+}
+
+impl Default for HistoryLimits {
+    fn default() -> Self {
+        Self {
+            max_entry_bytes: 1024 * 1024,
+            oversized: OversizedPolicy::Overflow,
+        }
+    }
 }
 
 fn default_primary_action() -> String {
@@ -118,6 +486,37 @@ fn default_show_intelligent_suggestions() -> bool {
     true
 }
 
+fn default_update_channel() -> String {
+    "stable".to_string()
+}
+
+fn default_summary_backend() -> String {
+    "ai".to_string()
+}
+
+fn default_ai_history_retention_days() -> u32 {
+    30
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_window_presets() -> Vec<WindowPreset> {
+    let preset = |name: &str, width, height, anchor| WindowPreset {
+        name: name.to_string(),
+        width,
+        height,
+        anchor,
+    };
+    vec![
+        preset("Compact", 720, Some(140), WindowAnchor::Center),
+        preset("Normal", 800, Some(500), WindowAnchor::Center),
+        preset("Tall", 800, Some(820), WindowAnchor::Center),
+        preset("Right edge", 520, None, WindowAnchor::RightEdge),
+    ]
+}
+
 fn default_toolbar_order() -> Vec<String> {
     vec![
         "uppercase".to_string(),
@@ -162,6 +561,7 @@ impl Default for AppSettings {
             default_language: "markdown".to_string(),
             window_position: WindowPosition::default(),
             sticky_mode: false,
+            panel_placement: PanelPlacement::default(),
             show_diff_preview: false,
             primary_action: default_primary_action(),
             export_action: default_export_action(),
@@ -173,11 +573,36 @@ impl Default for AppSettings {
             show_tips: default_show_tips(),
             show_intelligent_suggestions: default_show_intelligent_suggestions(),
             toolbar_order: default_toolbar_order(),
+            update_channel: default_update_channel(),
+            auto_download_updates: false,
+            profile_switch_hotkey: None,
+            semantic_search_enabled: false,
+            summary_backend: default_summary_backend(),
+            lan_sync_enabled: false,
+            ai_history_enabled: false,
+            ai_history_retention_days: default_ai_history_retention_days(),
+            trash_retention_days: default_trash_retention_days(),
+            notifications: NotificationSettings::default(),
+            quick_save: QuickSaveSettings::default(),
+            local_api_enabled: false,
+            history_limits: HistoryLimits::default(),
+            event_bridge: EventBridgeSettings::default(),
+            audit_log_enabled: false,
+            proxy: ProxySettings::default(),
+            tls: TlsSettings::default(),
+            offline_mode: false,
+            command_runner: CommandRunnerSettings::default(),
+            window_presets: default_window_presets(),
+            window_preset_hotkey: None,
+            panel_animation: PanelAnimationSettings::default(),
+            authentication_gate: AuthenticationGate::default(),
+            date_time: DateTimeSettings::default(),
+            rich_to_markdown: RichToMarkdownSettings::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Snippet {
     pub id: String,
     pub name: String,
@@ -195,6 +620,67 @@ pub struct Snippet {
     pub github_synced_at: Option<String>,
     #[serde(default)]
     pub github_source: Option<String>,
+    #[serde(default)]
+    pub favorite: bool, // Shown in the tray's Snippets submenu
+    #[serde(default)]
+    pub kind: SnippetKind,
+    /// Bumped whenever the name, content or tags change; update_snippet rejects edits
+    /// made against an older version
+    #[serde(default)]
+    pub version: u64,
+    /// Content is kept in sync with this URL and can't be edited locally
+    #[serde(default)]
+    pub source: Option<SnippetSource>,
+}
+
+impl Snippet {
+    /// Record an edit to the name, content or tags
+    pub fn touch(&mut self) {
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+        self.version += 1;
+    }
+
+    /// Content can change from outside Wingman: fetched from a URL or synced with a gist
+    pub fn is_remote(&self) -> bool {
+        self.source.is_some() || self.github_gist_id.is_some()
+    }
+
+    /// A plain text snippet with everything else at its default, for tests to adjust
+    #[cfg(test)]
+    pub fn for_test(id: &str, content: &str) -> Self {
+        Snippet {
+            id: id.to_string(),
+            name: id.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Where a snippet's content is fetched from (see snippet_sources.rs)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnippetSource {
+    pub url: String,
+    /// Re-fetch this often; None refreshes only on request
+    #[serde(default)]
+    pub refresh_minutes: Option<u32>,
+    /// From the last successful fetch, sent back as If-None-Match
+    #[serde(default)]
+    pub etag: Option<String>,
+    /// Last fetch attempt, successful or not
+    #[serde(default)]
+    pub last_fetched: Option<String>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetKind {
+    #[default]
+    Text,
+    /// A shell command, run with run_snippet_command instead of pasted
+    Command,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -202,6 +688,32 @@ pub struct SnippetsData {
     pub snippets: Vec<Snippet>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptVariableType {
+    Text,
+    Multiline,
+    Number,
+    Boolean,
+    /// One of `PromptVariable::options`
+    Select,
+}
+
+/// A `{{name}}` placeholder in a custom prompt, filled in from a form at call time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptVariable {
+    pub name: String,
+    pub label: String,
+    #[serde(rename = "type")]
+    pub var_type: PromptVariableType,
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomAIPrompt {
     pub id: String,
@@ -211,6 +723,12 @@ pub struct CustomAIPrompt {
     pub enabled: bool,
     pub created_at: String,
     pub updated_at: String,
+    /// Template for the user message; `{{text}}` is the input. Without one the input
+    /// is sent as-is.
+    #[serde(default)]
+    pub user_prompt: Option<String>,
+    #[serde(default)]
+    pub variables: Vec<PromptVariable>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -232,6 +750,28 @@ pub fn ensure_app_data_dir() -> Result<PathBuf, StorageError> {
     Ok(dir)
 }
 
+/// Data directory of the active profile (see profiles.rs)
+pub fn get_profile_data_dir() -> Result<PathBuf, StorageError> {
+    profile_data_dir(&crate::profiles::active_profile_id())
+}
+
+/// Data directory of any profile
+pub fn profile_data_dir(profile_id: &str) -> Result<PathBuf, StorageError> {
+    let dir = get_app_data_dir()?;
+    Ok(match crate::profiles::profile_subdir(profile_id) {
+        Some(subdir) => dir.join(subdir),
+        None => dir,
+    })
+}
+
+pub fn ensure_profile_data_dir() -> Result<PathBuf, StorageError> {
+    let dir = get_profile_data_dir()?;
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+    }
+    Ok(dir)
+}
+
 pub fn load_settings() -> Result<AppSettings, StorageError> {
     let dir = ensure_app_data_dir()?;
     let path = dir.join("settings.json");
@@ -255,7 +795,7 @@ pub fn save_settings(settings: &AppSettings) -> Result<(), StorageError> {
 }
 
 pub fn load_snippets() -> Result<SnippetsData, StorageError> {
-    let dir = ensure_app_data_dir()?;
+    let dir = ensure_profile_data_dir()?;
     let path = dir.join("snippets.json");
 
     if path.exists() {
@@ -269,7 +809,7 @@ pub fn load_snippets() -> Result<SnippetsData, StorageError> {
 }
 
 pub fn save_snippets(data: &SnippetsData) -> Result<(), StorageError> {
-    let dir = ensure_app_data_dir()?;
+    let dir = ensure_profile_data_dir()?;
     let path = dir.join("snippets.json");
     let content = serde_json::to_string_pretty(data)?;
     fs::write(path, content)?;
@@ -277,7 +817,7 @@ pub fn save_snippets(data: &SnippetsData) -> Result<(), StorageError> {
 }
 
 pub fn load_custom_ai_prompts() -> Result<CustomAIPromptsData, StorageError> {
-    let dir = ensure_app_data_dir()?;
+    let dir = ensure_profile_data_dir()?;
     let path = dir.join("custom_ai_prompts.json");
 
     if path.exists() {
@@ -291,7 +831,7 @@ pub fn load_custom_ai_prompts() -> Result<CustomAIPromptsData, StorageError> {
 }
 
 pub fn save_custom_ai_prompts(data: &CustomAIPromptsData) -> Result<(), StorageError> {
-    let dir = ensure_app_data_dir()?;
+    let dir = ensure_profile_data_dir()?;
     let path = dir.join("custom_ai_prompts.json");
     let content = serde_json::to_string_pretty(data)?;
     fs::write(path, content)?;
@@ -388,6 +928,9 @@ pub struct ChainStep {
     #[serde(rename = "transformId")]
     pub transform_id: String,
     pub name: String,
+    /// Separator, prefix and suffix for the parameterized built-in transforms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<crate::clipboard::TransformParams>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -425,3 +968,56 @@ pub fn save_transformation_chains(data: &TransformationChainsData) -> Result<(),
     fs::write(path, content)?;
     Ok(())
 }
+
+// AI workflows: like transformation chains, but steps can also run AI presets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AIWorkflowStep {
+    pub id: String,
+    /// "ai" (an AI preset or custom AI prompt) or "transform" (a built-in text transform)
+    #[serde(rename = "type")]
+    pub step_type: String,
+    /// Preset/custom prompt id for "ai" steps, TextTransform name for "transform" steps
+    #[serde(rename = "targetId")]
+    pub target_id: String,
+    pub name: String,
+    /// Snapshot of the preset's system prompt, used when the preset only exists as a
+    /// frontend default and was never saved
+    #[serde(default, rename = "systemPrompt")]
+    pub system_prompt: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AIWorkflow {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<AIWorkflowStep>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AIWorkflowsData {
+    pub workflows: Vec<AIWorkflow>,
+}
+
+pub fn load_ai_workflows() -> Result<AIWorkflowsData, StorageError> {
+    let dir = ensure_profile_data_dir()?;
+    let path = dir.join("ai_workflows.json");
+
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        Ok(AIWorkflowsData::default())
+    }
+}
+
+pub fn save_ai_workflows(data: &AIWorkflowsData) -> Result<(), StorageError> {
+    let dir = ensure_profile_data_dir()?;
+    let path = dir.join("ai_workflows.json");
+    let content = serde_json::to_string_pretty(data)?;
+    fs::write(path, content)?;
+    Ok(())
+}
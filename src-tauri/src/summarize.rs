@@ -0,0 +1,301 @@
+// Summaries and titles for long clips. The "ai" backend goes through the Claude proxy;
+// "local" is an extractive summary (highest-scoring sentences by word frequency) and a
+// title taken from the first meaningful line, for people without Pro or offline.
+// Results can be stored on the history entry so the history list shows the title.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::command_middleware::track;
+use crate::error::AppError;
+use crate::history::{set_entry_summary, set_entry_title};
+use crate::license::get_cached_license_key;
+use crate::premium::call_ai_feature;
+use crate::storage::load_settings;
+use crate::AppState;
+
+const MAX_TITLE_CHARS: usize = 60;
+
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was", "one", "our",
+    "out", "has", "have", "his", "how", "its", "may", "who", "did", "get", "him", "she", "too", "use", "that",
+    "with", "this", "from", "they", "will", "would", "there", "their", "what", "about", "which", "when",
+    "were", "been", "than", "then", "them", "these", "into", "also", "just", "more", "some", "such", "only",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryBackend {
+    Ai,
+    Local,
+}
+
+impl SummaryBackend {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "ai" => Ok(SummaryBackend::Ai),
+            "local" => Ok(SummaryBackend::Local),
+            other => Err(format!("Unknown summary backend: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryLength {
+    Short,
+    Medium,
+    Long,
+}
+
+impl SummaryLength {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "short" => Ok(SummaryLength::Short),
+            "medium" => Ok(SummaryLength::Medium),
+            "long" => Ok(SummaryLength::Long),
+            other => Err(format!("Unknown summary length: {}", other)),
+        }
+    }
+
+    /// Sentences kept by the local backend
+    fn sentences(&self) -> usize {
+        match self {
+            SummaryLength::Short => 1,
+            SummaryLength::Medium => 3,
+            SummaryLength::Long => 6,
+        }
+    }
+
+    fn instructions(&self) -> &'static str {
+        match self {
+            SummaryLength::Short => "Summarize the user's text in one sentence.",
+            SummaryLength::Medium => "Summarize the user's text in two to four sentences.",
+            SummaryLength::Long => "Summarize the user's text in a short paragraph followed by the key points as a bulleted list.",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryResult {
+    pub text: String,
+    pub backend: SummaryBackend,
+    /// Only set for the AI backend
+    pub tokens_used: Option<i64>,
+}
+
+// ============================================================================
+// Local backend
+// ============================================================================
+
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    for line in text.lines() {
+        let mut start = 0;
+        let bytes = line.as_bytes();
+        for (i, &b) in bytes.iter().enumerate() {
+            let at_boundary = matches!(b, b'.' | b'!' | b'?') && bytes.get(i + 1).map_or(true, |n| *n == b' ');
+            if at_boundary {
+                sentences.push(line[start..=i].trim());
+                start = i + 1;
+            }
+        }
+        sentences.push(line[start..].trim());
+    }
+    sentences.retain(|s| s.chars().any(char::is_alphanumeric));
+    sentences
+}
+
+fn words(sentence: &str) -> impl Iterator<Item = String> + '_ {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 2)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+}
+
+/// Pick the `count` sentences whose words are most frequent across the text, in
+/// their original order
+pub fn extractive_summary(text: &str, count: usize) -> String {
+    let sentences = split_sentences(text);
+    if sentences.len() <= count {
+        return sentences.join(" ");
+    }
+
+    let mut frequency: HashMap<String, usize> = HashMap::new();
+    for word in sentences.iter().flat_map(|s| words(s)) {
+        *frequency.entry(word).or_default() += 1;
+    }
+
+    let mut scored: Vec<(usize, f64)> = sentences
+        .iter()
+        .enumerate()
+        .map(|(i, sentence)| {
+            let counts: Vec<usize> = words(sentence).map(|w| frequency[&w]).collect();
+            // Normalise so long sentences don't win by length alone
+            let score = counts.iter().sum::<usize>() as f64 / (counts.len().max(1) as f64).sqrt();
+            (i, score)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut chosen: Vec<usize> = scored.into_iter().take(count).map(|(i, _)| i).collect();
+    chosen.sort_unstable();
+    chosen.into_iter().map(|i| sentences[i]).collect::<Vec<_>>().join(" ")
+}
+
+fn truncate_title(title: &str) -> String {
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+    let cut: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > MAX_TITLE_CHARS / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end_matches([' ', ',', ';', ':', '-']))
+}
+
+/// First line with some text in it, minus markdown/comment markers
+pub fn heuristic_title(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(|l| l.trim().trim_start_matches(['#', '/', '*', '-', '>', ' ']).trim())
+        .find(|l| l.chars().any(char::is_alphanumeric))
+        .unwrap_or("");
+    truncate_title(line)
+}
+
+/// Strip the quotes and trailing period models like to add
+fn clean_title(reply: &str) -> String {
+    let line = reply.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    let line = line.strip_prefix("Title:").unwrap_or(line).trim();
+    let line = line.trim_matches(['"', '\'', '“', '”', '*', '`']).trim_end_matches('.').trim();
+    truncate_title(line)
+}
+
+// ============================================================================
+// AI backend
+// ============================================================================
+
+async fn ask_ai(text: &str, feature: &str, instructions: &str) -> Result<(String, i64), String> {
+    let license_key = get_cached_license_key().ok_or_else(|| "No active license key found".to_string())?;
+    let response = call_ai_feature(&license_key, text, feature, Some(instructions))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok((response.result.trim().to_string(), response.tokens_used_this_request))
+}
+
+fn configured_backend() -> Result<SummaryBackend, String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    SummaryBackend::parse(&settings.summary_backend)
+}
+
+pub async fn summarize(text: &str, length: SummaryLength, backend: SummaryBackend) -> Result<SummaryResult, String> {
+    let (summary, tokens_used) = match backend {
+        SummaryBackend::Ai => {
+            let instructions = format!(
+                "{} Write in the same language as the text. Reply with only the summary.",
+                length.instructions()
+            );
+            let (summary, tokens) = ask_ai(text, "summarize", &instructions).await?;
+            (summary, Some(tokens))
+        }
+        SummaryBackend::Local => (extractive_summary(text, length.sentences()), None),
+    };
+    Ok(SummaryResult {
+        text: summary,
+        backend,
+        tokens_used,
+    })
+}
+
+pub async fn title(text: &str, backend: SummaryBackend) -> Result<SummaryResult, String> {
+    let (title, tokens_used) = match backend {
+        SummaryBackend::Ai => {
+            let instructions = "Suggest a short descriptive title (at most eight words) for the user's text, \
+                                in the same language as the text. Reply with only the title.";
+            let (reply, tokens) = ask_ai(text, "title", instructions).await?;
+            (clean_title(&reply), Some(tokens))
+        }
+        SummaryBackend::Local => (heuristic_title(text), None),
+    };
+    Ok(SummaryResult {
+        text: title,
+        backend,
+        tokens_used,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Summarize `text` ("short", "medium" (default) or "long") with the backend from
+/// settings. Pass `entry_id` to store the summary on that history entry.
+#[tauri::command]
+pub async fn summarize_text(
+    state: State<'_, AppState>,
+    text: String,
+    length: Option<String>,
+    entry_id: Option<i64>,
+) -> Result<SummaryResult, AppError> {
+    track("summarize_text", async move {
+        if text.trim().is_empty() {
+            return Err(AppError::invalid_input("Nothing to summarize"));
+        }
+        let length = SummaryLength::parse(length.as_deref().unwrap_or("medium"))?;
+        let result = summarize(&text, length, configured_backend()?).await?;
+        if let Some(id) = entry_id {
+            let conn = state.db.lock()?;
+            set_entry_summary(&conn, id, Some(&result.text))?;
+        }
+        Ok(result)
+    })
+    .await
+}
+
+/// Suggest a title for `text`. Pass `entry_id` to store it on that history entry.
+#[tauri::command]
+pub async fn suggest_title(
+    state: State<'_, AppState>,
+    text: String,
+    entry_id: Option<i64>,
+) -> Result<SummaryResult, AppError> {
+    if text.trim().is_empty() {
+        return Err(AppError::invalid_input("Nothing to title"));
+    }
+    let result = title(&text, configured_backend()?).await?;
+    if let Some(id) = entry_id {
+        let conn = state.db.lock()?;
+        set_entry_title(&conn, id, Some(&result.text))?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extractive_summary_keeps_order() {
+        let text = "Rust makes memory safety practical. The weather was nice today. \
+                    Rust memory safety comes from ownership. Lunch was pasta.";
+        let summary = extractive_summary(text, 2);
+        assert_eq!(
+            summary,
+            "Rust makes memory safety practical. Rust memory safety comes from ownership."
+        );
+        assert_eq!(extractive_summary("Just one line", 3), "Just one line");
+    }
+
+    #[test]
+    fn test_titles() {
+        assert_eq!(heuristic_title("\n\n## Release notes\nFixed bugs"), "Release notes");
+        assert_eq!(heuristic_title("// TODO: tidy up"), "TODO: tidy up");
+        let long = "word ".repeat(30);
+        assert!(heuristic_title(&long).ends_with('…'));
+        assert!(heuristic_title(&long).chars().count() <= MAX_TITLE_CHARS + 1);
+        assert_eq!(clean_title("Title: \"Quarterly Budget Review.\"\n"), "Quarterly Budget Review");
+    }
+}
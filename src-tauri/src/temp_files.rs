@@ -0,0 +1,239 @@
+// Temp files holding clipboard content: copy_file_to_clipboard, the image files
+// write_native_clipboard references, and the drag-out, print and mail attachment files.
+// The dir is owner-only (0700, files 0600; %TEMP% is already per-user on Windows).
+//
+// Files put on the pasteboard are tracked with the pasteboard change count (NSPasteboard
+// changeCount, the Windows clipboard sequence number) right after the copy, and deleted
+// as soon as the count moves on, since nothing references them any more. Linux has no
+// change count, so there and for everything untracked the old one-hour limit applies.
+// The whole dir is purged on quit and through purge_temp_files.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::error::AppError;
+
+const DIR_NAME: &str = "wingman_clipboard";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Untracked files, and tracked ones where the change count isn't available
+const MAX_AGE: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, PartialEq)]
+struct TrackedFile {
+    path: PathBuf,
+    /// Pasteboard change count right after the copy that referenced the file
+    change_count: i64,
+}
+
+static TRACKED: Mutex<Vec<TrackedFile>> = Mutex::new(Vec::new());
+
+/// Pasteboard change count; None where the platform has none (Linux)
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+pub fn change_count() -> Option<i64> {
+    use cocoa::base::id;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        if pasteboard.is_null() {
+            return None;
+        }
+        let count: i64 = msg_send![pasteboard, changeCount];
+        Some(count)
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn change_count() -> Option<i64> {
+    // 0 when the process lacks access to the window station
+    match unsafe { windows::Win32::System::DataExchange::GetClipboardSequenceNumber() } {
+        0 => None,
+        count => Some(count as i64),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn change_count() -> Option<i64> {
+    None
+}
+
+/// Owner-only permissions: 0700 for dirs, 0600 for files
+#[cfg(unix)]
+pub fn restrict(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = if path.is_dir() { 0o700 } else { 0o600 };
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+pub fn restrict(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// The temp dir, created owner-only. Permissions are reapplied each time in case an
+/// older build created it world-readable.
+pub fn dir() -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join(DIR_NAME);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    restrict(&dir).map_err(|e| format!("Failed to restrict temp directory: {}", e))?;
+    Ok(dir)
+}
+
+/// A subdirectory of the temp dir, for files that need a clean name
+pub fn subdir(name: &str) -> Result<PathBuf, String> {
+    let dir = dir()?.join(name);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    restrict(&dir).map_err(|e| format!("Failed to restrict temp directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Write a file no other user can read
+pub fn write_private(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        file.write_all(contents.as_ref()).map_err(|e| format!("Failed to write temp file: {}", e))?;
+    }
+    #[cfg(not(unix))]
+    fs::write(path, contents).map_err(|e| format!("Failed to write temp file: {}", e))?;
+    // mode only applies to new files
+    restrict(path).map_err(|e| format!("Failed to restrict temp file: {}", e))
+}
+
+/// Remember that the pasteboard now references `paths`. Call right after the copy.
+pub fn track_pasteboard_files(paths: &[PathBuf]) {
+    let Some(change_count) = change_count() else {
+        return;
+    };
+    let mut tracked = TRACKED.lock().unwrap_or_else(|e| e.into_inner());
+    tracked.retain(|file| !paths.contains(&file.path));
+    tracked.extend(paths.iter().map(|path| TrackedFile {
+        path: path.clone(),
+        change_count,
+    }));
+}
+
+/// Tracked files the pasteboard has moved past, removed from `tracked`
+fn take_released(tracked: &mut Vec<TrackedFile>, current: i64) -> Vec<PathBuf> {
+    let (released, kept): (Vec<_>, Vec<_>) = tracked.drain(..).partition(|file| file.change_count != current);
+    *tracked = kept;
+    released.into_iter().map(|file| file.path).collect()
+}
+
+fn remove(path: &Path) -> bool {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.is_ok()
+}
+
+/// Delete released pasteboard files and untracked entries older than MAX_AGE
+fn sweep() {
+    let tracked_paths: Vec<PathBuf> = {
+        let mut tracked = TRACKED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(current) = change_count() {
+            for path in take_released(&mut tracked, current) {
+                if remove(&path) {
+                    log::debug!("Deleted released clipboard file {}", path.display());
+                }
+            }
+        }
+        tracked.iter().map(|file| file.path.clone()).collect()
+    };
+
+    let Ok(entries) = fs::read_dir(std::env::temp_dir().join(DIR_NAME)) else {
+        return;
+    };
+    let cutoff = SystemTime::now().checked_sub(MAX_AGE).unwrap_or(SystemTime::UNIX_EPOCH);
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let old = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified < cutoff);
+        if old && !tracked_paths.contains(&path) {
+            remove(&path);
+        }
+    }
+}
+
+/// Delete everything in the temp dir; returns how many files and dirs went
+pub fn purge() -> usize {
+    TRACKED.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    let Ok(entries) = fs::read_dir(std::env::temp_dir().join(DIR_NAME)) else {
+        return 0;
+    };
+    let removed = entries.flatten().filter(|entry| remove(&entry.path())).count();
+    if removed > 0 {
+        log::info!("Purged {} clipboard temp files", removed);
+    }
+    removed
+}
+
+/// Poll the pasteboard change count and sweep the temp dir
+pub fn start_monitor() {
+    std::thread::spawn(|| loop {
+        sweep();
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Delete all clipboard temp files now. Files still on the pasteboard stop pasting.
+#[tauri::command]
+pub fn purge_temp_files() -> Result<usize, AppError> {
+    Ok(purge())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracked(path: &str, change_count: i64) -> TrackedFile {
+        TrackedFile {
+            path: PathBuf::from(path),
+            change_count,
+        }
+    }
+
+    #[test]
+    fn test_take_released_keeps_files_of_current_copy() {
+        let mut files = vec![tracked("/tmp/a", 3), tracked("/tmp/b", 5), tracked("/tmp/c", 5)];
+        assert_eq!(take_released(&mut files, 5), vec![PathBuf::from("/tmp/a")]);
+        assert_eq!(files, vec![tracked("/tmp/b", 5), tracked("/tmp/c", 5)]);
+        assert_eq!(take_released(&mut files, 6).len(), 2);
+        assert!(files.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("temp-files-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("secret.txt");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,407 @@
+// Translation through one of three backends: the AI proxy (uses the Pro license),
+// DeepL or Google Cloud Translation (user-supplied API keys stored via credentials.rs).
+// The source language is detected by the backend unless one is given.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::command_middleware::track;
+use crate::credentials::get_credential;
+use crate::error::AppError;
+use crate::http_client::{self, HttpClientError};
+use crate::license::get_cached_license_key;
+use crate::premium::call_ai_feature;
+
+#[derive(Error, Debug)]
+pub enum TranslateError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("{0}")]
+    HttpClient(#[from] HttpClientError),
+    #[error("{0} API key not set")]
+    MissingApiKey(&'static str),
+    #[error("Unsupported target language for {0}: {1}")]
+    UnsupportedLanguage(&'static str, String),
+    #[error("Translation failed: {0}")]
+    Service(String),
+    #[error("Translation is paused while privacy mode is on")]
+    PrivacyModeActive,
+}
+
+/// Keychain entries holding the provider API keys
+pub const DEEPL_API_KEY: &str = "deepl_api_key";
+pub const GOOGLE_API_KEY: &str = "google_translate_api_key";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationBackend {
+    Ai,
+    Deepl,
+    Google,
+}
+
+impl TranslationBackend {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "ai" => Ok(TranslationBackend::Ai),
+            "deepl" => Ok(TranslationBackend::Deepl),
+            "google" => Ok(TranslationBackend::Google),
+            other => Err(format!("Unknown translation backend: {}", other)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            TranslationBackend::Ai => "AI",
+            TranslationBackend::Deepl => "DeepL",
+            TranslationBackend::Google => "Google",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Language {
+    /// BCP 47 style code accepted as `target_lang` ("en", "pt-BR", "zh-CN", ...)
+    pub code: &'static str,
+    pub name: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranslationResult {
+    pub text: String,
+    /// Detected (or given) source language code, when the backend reports it
+    pub source_lang: Option<String>,
+    pub target_lang: String,
+    pub backend: TranslationBackend,
+    /// AI backend only
+    pub tokens_used: Option<i64>,
+}
+
+/// (code, name, DeepL target code). DeepL supports a subset; `None` means unsupported.
+const LANGUAGES: &[(&str, &str, Option<&str>)] = &[
+    ("ar", "Arabic", Some("AR")),
+    ("bg", "Bulgarian", Some("BG")),
+    ("cs", "Czech", Some("CS")),
+    ("da", "Danish", Some("DA")),
+    ("de", "German", Some("DE")),
+    ("el", "Greek", Some("EL")),
+    ("en", "English", Some("EN-US")),
+    ("en-GB", "English (UK)", Some("EN-GB")),
+    ("es", "Spanish", Some("ES")),
+    ("et", "Estonian", Some("ET")),
+    ("fi", "Finnish", Some("FI")),
+    ("fr", "French", Some("FR")),
+    ("he", "Hebrew", None),
+    ("hi", "Hindi", None),
+    ("hu", "Hungarian", Some("HU")),
+    ("id", "Indonesian", Some("ID")),
+    ("it", "Italian", Some("IT")),
+    ("ja", "Japanese", Some("JA")),
+    ("ko", "Korean", Some("KO")),
+    ("lt", "Lithuanian", Some("LT")),
+    ("lv", "Latvian", Some("LV")),
+    ("nb", "Norwegian", Some("NB")),
+    ("nl", "Dutch", Some("NL")),
+    ("pl", "Polish", Some("PL")),
+    ("pt", "Portuguese", Some("PT-PT")),
+    ("pt-BR", "Portuguese (Brazil)", Some("PT-BR")),
+    ("ro", "Romanian", Some("RO")),
+    ("ru", "Russian", Some("RU")),
+    ("sk", "Slovak", Some("SK")),
+    ("sl", "Slovenian", Some("SL")),
+    ("sv", "Swedish", Some("SV")),
+    ("th", "Thai", None),
+    ("tr", "Turkish", Some("TR")),
+    ("uk", "Ukrainian", Some("UK")),
+    ("vi", "Vietnamese", None),
+    ("zh-CN", "Chinese (Simplified)", Some("ZH-HANS")),
+    ("zh-TW", "Chinese (Traditional)", Some("ZH-HANT")),
+];
+
+fn find_language(code: &str) -> Option<&'static (&'static str, &'static str, Option<&'static str>)> {
+    LANGUAGES.iter().find(|(c, _, _)| c.eq_ignore_ascii_case(code)).or_else(|| {
+        // "zh" and "en-us" style codes fall back to their primary entry
+        let primary = code.split(['-', '_']).next().unwrap_or(code);
+        match primary.to_ascii_lowercase().as_str() {
+            "zh" => LANGUAGES.iter().find(|(c, _, _)| *c == "zh-CN"),
+            other => LANGUAGES.iter().find(|(c, _, _)| c.eq_ignore_ascii_case(other)),
+        }
+    })
+}
+
+fn supported_languages(backend: TranslationBackend) -> Vec<Language> {
+    LANGUAGES
+        .iter()
+        .filter(|(_, _, deepl)| backend != TranslationBackend::Deepl || deepl.is_some())
+        .map(|(code, name, _)| Language { code, name })
+        .collect()
+}
+
+fn api_key(key: &str, provider: &'static str) -> Result<String, TranslateError> {
+    get_credential(key.to_string())
+        .map_err(|e| TranslateError::Service(e.message))?
+        .filter(|k| !k.trim().is_empty())
+        .ok_or(TranslateError::MissingApiKey(provider))
+}
+
+fn http_client() -> Result<reqwest::Client, TranslateError> {
+    Ok(http_client::builder()?
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?)
+}
+
+#[derive(Deserialize)]
+struct DeeplResponse {
+    translations: Vec<DeeplTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeeplTranslation {
+    detected_source_language: Option<String>,
+    text: String,
+}
+
+async fn translate_deepl(
+    text: &str,
+    target: &str,
+    source: Option<&str>,
+) -> Result<(String, Option<String>), TranslateError> {
+    let key = api_key(DEEPL_API_KEY, "DeepL")?;
+    let target_code = find_language(target)
+        .and_then(|(_, _, deepl)| *deepl)
+        .ok_or_else(|| TranslateError::UnsupportedLanguage("DeepL", target.to_string()))?;
+    // Free-plan keys end in ":fx" and use a separate host
+    let host = if key.ends_with(":fx") {
+        "https://api-free.deepl.com"
+    } else {
+        "https://api.deepl.com"
+    };
+
+    let mut body = serde_json::json!({ "text": [text], "target_lang": target_code });
+    if let Some(source) = source {
+        // Source codes have no regional variants
+        let primary = source.split(['-', '_']).next().unwrap_or(source);
+        body["source_lang"] = serde_json::json!(primary.to_ascii_uppercase());
+    }
+
+    let response = http_client()?
+        .post(format!("{}/v2/translate", host))
+        .header("Authorization", format!("DeepL-Auth-Key {}", key))
+        .json(&body)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let message = response.text().await.unwrap_or_default();
+        return Err(TranslateError::Service(format!("DeepL returned {}: {}", status, message.trim())));
+    }
+    let mut parsed: DeeplResponse = response.json().await?;
+    let translation = parsed
+        .translations
+        .pop()
+        .ok_or_else(|| TranslateError::Service("DeepL returned no translation".to_string()))?;
+    Ok((
+        translation.text,
+        translation.detected_source_language.map(|l| l.to_ascii_lowercase()),
+    ))
+}
+
+#[derive(Deserialize)]
+struct GoogleResponse {
+    data: GoogleData,
+}
+
+#[derive(Deserialize)]
+struct GoogleData {
+    translations: Vec<GoogleTranslation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleTranslation {
+    translated_text: String,
+    detected_source_language: Option<String>,
+}
+
+async fn translate_google(
+    text: &str,
+    target: &str,
+    source: Option<&str>,
+) -> Result<(String, Option<String>), TranslateError> {
+    let key = api_key(GOOGLE_API_KEY, "Google Translate")?;
+    let target_code = find_language(target)
+        .map(|(code, _, _)| *code)
+        .ok_or_else(|| TranslateError::UnsupportedLanguage("Google", target.to_string()))?;
+
+    let mut body = serde_json::json!({ "q": text, "target": target_code, "format": "text" });
+    if let Some(source) = source {
+        body["source"] = serde_json::json!(source);
+    }
+
+    let response = http_client()?
+        .post("https://translation.googleapis.com/language/translate/v2")
+        .header("X-Goog-Api-Key", key)
+        .json(&body)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let message = response.text().await.unwrap_or_default();
+        return Err(TranslateError::Service(format!("Google returned {}: {}", status, message.trim())));
+    }
+    let mut parsed: GoogleResponse = response.json().await?;
+    let translation = parsed
+        .data
+        .translations
+        .pop()
+        .ok_or_else(|| TranslateError::Service("Google returned no translation".to_string()))?;
+    Ok((translation.translated_text, translation.detected_source_language))
+}
+
+#[derive(Deserialize)]
+struct AiTranslation {
+    source_lang: Option<String>,
+    translation: String,
+}
+
+/// The AI is asked for JSON so it can report the detected language; a reply that
+/// isn't JSON is taken as the translation itself
+fn parse_ai_reply(reply: &str) -> (String, Option<String>) {
+    let trimmed = reply.trim();
+    let json = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|s| s.strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+    match serde_json::from_str::<AiTranslation>(json) {
+        Ok(parsed) => (parsed.translation, parsed.source_lang.filter(|l| !l.is_empty())),
+        Err(_) => (reply.to_string(), None),
+    }
+}
+
+async fn translate_ai(
+    text: &str,
+    target: &str,
+    source: Option<&str>,
+) -> Result<(String, Option<String>, i64), TranslateError> {
+    let license_key =
+        get_cached_license_key().ok_or_else(|| TranslateError::Service("No active license key found".to_string()))?;
+    let target_name = find_language(target).map(|(_, name, _)| *name).unwrap_or(target);
+    let source_hint = match source {
+        Some(source) => format!("The text is in {}.", find_language(source).map(|(_, n, _)| *n).unwrap_or(source)),
+        None => "Detect the language of the text.".to_string(),
+    };
+    let instructions = format!(
+        "You are a translator. Translate the user's text into {}. {} Preserve formatting, \
+         line breaks, code and placeholders. Reply with only a JSON object of the form \
+         {{\"source_lang\": \"<ISO 639-1 code of the original>\", \"translation\": \"<translated text>\"}}.",
+        target_name, source_hint
+    );
+
+    let response = call_ai_feature(&license_key, text, "translate", Some(&instructions))
+        .await
+        .map_err(|e| TranslateError::Service(e.to_string()))?;
+    let (translation, detected) = parse_ai_reply(&response.result);
+    Ok((translation, detected, response.tokens_used_this_request))
+}
+
+pub async fn translate(
+    text: &str,
+    target_lang: &str,
+    source_lang: Option<&str>,
+    backend: TranslationBackend,
+) -> Result<TranslationResult, TranslateError> {
+    if crate::privacy::is_privacy_mode_active() {
+        return Err(TranslateError::PrivacyModeActive);
+    }
+    let source_lang = source_lang.filter(|s| !s.is_empty() && *s != "auto");
+
+    let (translated, detected, tokens_used) = match backend {
+        TranslationBackend::Ai => {
+            let (text, detected, tokens) = translate_ai(text, target_lang, source_lang).await?;
+            (text, detected, Some(tokens))
+        }
+        TranslationBackend::Deepl => {
+            let (text, detected) = translate_deepl(text, target_lang, source_lang).await?;
+            (text, detected, None)
+        }
+        TranslationBackend::Google => {
+            let (text, detected) = translate_google(text, target_lang, source_lang).await?;
+            (text, detected, None)
+        }
+    };
+
+    Ok(TranslationResult {
+        text: translated,
+        source_lang: detected.or_else(|| source_lang.map(str::to_string)),
+        target_lang: target_lang.to_string(),
+        backend,
+        tokens_used,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Translate `text` into `target_lang`. `backend` is "ai" (default), "deepl" or
+/// "google"; leave `source_lang` empty to auto-detect.
+#[tauri::command]
+pub async fn translate_text(
+    text: String,
+    target_lang: String,
+    backend: Option<String>,
+    source_lang: Option<String>,
+) -> Result<TranslationResult, AppError> {
+    track("translate_text", async move {
+        if text.trim().is_empty() {
+            return Err(AppError::invalid_input("Nothing to translate"));
+        }
+        let backend = TranslationBackend::parse(backend.as_deref().unwrap_or("ai"))?;
+        log::info!("Translating {} chars to {} via {}", text.len(), target_lang, backend.name());
+        translate(&text, &target_lang, source_lang.as_deref(), backend)
+            .await
+            .map_err(AppError::from)
+    })
+    .await
+}
+
+/// Target languages the backend can translate into
+#[tauri::command]
+pub fn list_supported_languages(backend: Option<String>) -> Result<Vec<Language>, AppError> {
+    let backend = TranslationBackend::parse(backend.as_deref().unwrap_or("ai"))?;
+    Ok(supported_languages(backend))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_language() {
+        assert_eq!(find_language("pt-br").unwrap().0, "pt-BR");
+        assert_eq!(find_language("zh").unwrap().0, "zh-CN");
+        assert_eq!(find_language("en_US").unwrap().2, Some("EN-US"));
+        assert!(find_language("tlh").is_none());
+    }
+
+    #[test]
+    fn test_deepl_languages_are_subset() {
+        let all = supported_languages(TranslationBackend::Google).len();
+        let deepl = supported_languages(TranslationBackend::Deepl);
+        assert!(deepl.len() < all);
+        assert!(!deepl.iter().any(|l| l.code == "hi"));
+    }
+
+    #[test]
+    fn test_parse_ai_reply() {
+        let (text, lang) = parse_ai_reply("```json\n{\"source_lang\": \"de\", \"translation\": \"Hello\"}\n```");
+        assert_eq!(text, "Hello");
+        assert_eq!(lang.as_deref(), Some("de"));
+
+        let (text, lang) = parse_ai_reply("Bonjour");
+        assert_eq!(text, "Bonjour");
+        assert!(lang.is_none());
+    }
+}
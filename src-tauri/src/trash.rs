@@ -0,0 +1,389 @@
+// Trash for deleted history entries and snippets. Deleting an entry or clearing history
+// moves rows into history_trash (same columns as history plus deleted_at); revisions and
+// attachments stay until the entry is purged. Deleted snippets go to snippets_trash.json.
+// restore_deleted puts an item back under its original id, empty_trash purges for good,
+// and the trash_retention_days setting purges old items at startup. Self-destructing
+// entries and the history size cap still delete outright, and so does deleting an entry
+// that has an expiry: the trash isn't swept, so it would outlive its expiry there.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::{AppHandle, State};
+
+use crate::error::AppError;
+use crate::history::{self, HistoryEntry, HistoryError};
+use crate::links::{ItemKind, ItemRef};
+use crate::storage::{ensure_profile_data_dir, load_snippets, save_snippets, Snippet, StorageError};
+use crate::{snippet_versions, tray, AppState};
+
+/// Trashed history entries listed at once
+const LIST_LIMIT: u32 = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedEntry {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    pub deleted_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedSnippet {
+    #[serde(flatten)]
+    pub snippet: Snippet,
+    /// RFC 3339
+    pub deleted_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SnippetTrashData {
+    pub snippets: Vec<TrashedSnippet>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashContents {
+    /// Newest deletions first, with content previews
+    pub history: Vec<TrashedEntry>,
+    pub snippets: Vec<TrashedSnippet>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PurgedCounts {
+    pub history: u32,
+    pub snippets: u32,
+}
+
+fn table_columns(conn: &Connection, table: &str) -> rusqlite::Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
+/// Create history_trash as a copy of history's shape, adding any columns history has
+/// gained since. Called after the history migrations in create_schema.
+pub fn init_trash_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute("CREATE TABLE IF NOT EXISTS history_trash AS SELECT * FROM history WHERE 0", [])?;
+    let existing: Vec<String> = table_columns(conn, "history_trash")?.into_iter().map(|(name, _)| name).collect();
+    for (name, column_type) in table_columns(conn, "history")? {
+        if !existing.contains(&name) {
+            conn.execute(&format!("ALTER TABLE history_trash ADD COLUMN \"{}\" {}", name, column_type), [])?;
+        }
+    }
+    if !existing.iter().any(|name| name == "deleted_at") {
+        conn.execute("ALTER TABLE history_trash ADD COLUMN deleted_at DATETIME", [])?;
+    }
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_history_trash_deleted_at ON history_trash(deleted_at)", [])?;
+    Ok(())
+}
+
+/// history's columns, quoted and comma-separated, for copying rows between the tables
+fn history_column_list(conn: &Connection) -> rusqlite::Result<String> {
+    Ok(table_columns(conn, "history")?
+        .into_iter()
+        .map(|(name, _)| format!("\"{}\"", name))
+        .collect::<Vec<_>>()
+        .join(", "))
+}
+
+/// Copy the rows matching `filter` (with ?1 bound to `id`) into the trash and delete them
+/// from history. Entries with an expiry are deleted outright along with their revisions.
+/// Returns (moved, deleted outright). Callers run this inside a transaction.
+fn move_to_trash(conn: &Connection, columns: &str, filter: &str, id: Option<i64>) -> rusqlite::Result<(usize, usize)> {
+    // Ids aren't reused, but an entry could have been restored and deleted again
+    conn.execute(&format!("DELETE FROM history_trash WHERE id IN (SELECT id FROM history {})", filter), [id])?;
+    conn.execute(
+        &format!(
+            "DELETE FROM history_versions WHERE entry_id IN (SELECT id FROM history {} AND expires_at IS NOT NULL)",
+            filter
+        ),
+        [id],
+    )?;
+    let dropped = conn.execute(&format!("DELETE FROM history {} AND expires_at IS NOT NULL", filter), [id])?;
+    let moved = conn.execute(
+        &format!(
+            "INSERT INTO history_trash ({columns}, deleted_at) SELECT {columns}, CURRENT_TIMESTAMP FROM history {filter}",
+            columns = columns,
+            filter = filter
+        ),
+        [id],
+    )?;
+    conn.execute(&format!("DELETE FROM history {}", filter), [id])?;
+    Ok((moved, dropped))
+}
+
+/// Move one entry, or every entry with `None`, into the trash. Returns how many were
+/// removed from history, whether trashed or (with an expiry) deleted outright.
+pub fn trash_entries(conn: &Connection, id: Option<i64>) -> Result<u32, HistoryError> {
+    let columns = history_column_list(conn)?;
+    let filter = if id.is_some() { "WHERE id = ?1" } else { "WHERE ?1 IS NULL" };
+    let tx = conn.unchecked_transaction()?;
+    let (moved, dropped) = move_to_trash(&tx, &columns, filter, id)?;
+    tx.commit()?;
+    if dropped > 0 {
+        history::collect_orphaned_attachments(conn)?;
+    }
+    Ok((moved + dropped) as u32)
+}
+
+/// Move several entries into the trash in one transaction, like `trash_entries`.
+/// Returns how many were removed; ids that don't exist are skipped.
+pub fn trash_entry_ids(conn: &Connection, ids: &[i64]) -> Result<u32, HistoryError> {
+    let columns = history_column_list(conn)?;
+    let tx = conn.unchecked_transaction()?;
+    let (mut moved, mut dropped) = (0, 0);
+    for &id in ids {
+        let (entry_moved, entry_dropped) = move_to_trash(&tx, &columns, "WHERE id = ?1", Some(id))?;
+        moved += entry_moved;
+        dropped += entry_dropped;
+    }
+    tx.commit()?;
+    if dropped > 0 {
+        history::collect_orphaned_attachments(conn)?;
+    }
+    Ok((moved + dropped) as u32)
+}
+
+/// Move a trashed entry back into history under its original id
+pub fn restore_entry(conn: &Connection, id: i64) -> Result<bool, HistoryError> {
+    let columns = history_column_list(conn)?;
+    let tx = conn.unchecked_transaction()?;
+    let restored = tx.execute(
+        &format!(
+            "INSERT OR IGNORE INTO history ({columns}) SELECT {columns} FROM history_trash WHERE id = ?1",
+            columns = columns
+        ),
+        [id],
+    )?;
+    tx.execute("DELETE FROM history_trash WHERE id = ?1", [id])?;
+    tx.commit()?;
+    Ok(restored > 0)
+}
+
+pub fn list_trashed_entries(conn: &Connection, limit: u32) -> Result<Vec<TrashedEntry>, HistoryError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, deleted_at FROM history_trash ORDER BY deleted_at DESC, id DESC LIMIT ?1",
+        history::preview_columns()
+    ))?;
+    let entries = stmt
+        .query_map([limit], |row| {
+            Ok(TrashedEntry {
+                entry: history::map_entry_row(row)?,
+                deleted_at: row.get("deleted_at")?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+/// Permanently delete trashed entries deleted more than `older_than_days` ago, or all of
+/// them with `None`, along with their revisions. Attachments are collected by `purge`.
+pub fn purge_entries(conn: &Connection, older_than_days: Option<u32>) -> Result<u32, HistoryError> {
+    let cutoff = older_than_days.map(|days| format!("-{} days", days));
+    let filter = if cutoff.is_some() { "WHERE deleted_at < datetime('now', ?1)" } else { "WHERE ?1 IS NULL" };
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        &format!("DELETE FROM history_versions WHERE entry_id IN (SELECT id FROM history_trash {})", filter),
+        [&cutoff],
+    )?;
+    let purged = tx.execute(&format!("DELETE FROM history_trash {}", filter), [&cutoff])?;
+    tx.commit()?;
+    Ok(purged as u32)
+}
+
+pub fn load_snippet_trash() -> Result<SnippetTrashData, StorageError> {
+    let path = ensure_profile_data_dir()?.join("snippets_trash.json");
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        Ok(SnippetTrashData::default())
+    }
+}
+
+pub fn save_snippet_trash(data: &SnippetTrashData) -> Result<(), StorageError> {
+    let path = ensure_profile_data_dir()?.join("snippets_trash.json");
+    fs::write(path, serde_json::to_string_pretty(data)?)?;
+    Ok(())
+}
+
+/// Add removed snippets to the trash file; a snippet trashed again replaces its old copy
+pub fn trash_snippets(snippets: Vec<Snippet>) -> Result<(), StorageError> {
+    if snippets.is_empty() {
+        return Ok(());
+    }
+    let mut trash = load_snippet_trash()?;
+    let deleted_at = chrono::Utc::now().to_rfc3339();
+    trash.snippets.retain(|trashed| !snippets.iter().any(|s| s.id == trashed.snippet.id));
+    trash.snippets.extend(snippets.into_iter().map(|snippet| TrashedSnippet {
+        snippet,
+        deleted_at: deleted_at.clone(),
+    }));
+    save_snippet_trash(&trash)
+}
+
+/// Remove trashed snippets deleted before the cutoff (all of them with `None`)
+fn purge_snippet_list(trash: &mut SnippetTrashData, older_than_days: Option<u32>) -> u32 {
+    let before = trash.snippets.len();
+    match older_than_days {
+        Some(days) => {
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(days as i64);
+            trash.snippets.retain(|trashed| {
+                chrono::DateTime::parse_from_rfc3339(&trashed.deleted_at)
+                    .map(|deleted_at| deleted_at >= cutoff)
+                    .unwrap_or(true)
+            });
+        }
+        None => trash.snippets.clear(),
+    }
+    (before - trash.snippets.len()) as u32
+}
+
+pub fn purge(conn: &Connection, older_than_days: Option<u32>) -> Result<PurgedCounts, AppError> {
+    let history = purge_entries(conn, older_than_days)?;
+    if history > 0 {
+        history::collect_orphaned_attachments(conn)?;
+    }
+    let mut trash = load_snippet_trash()?;
+    let snippets = purge_snippet_list(&mut trash, older_than_days);
+    if snippets > 0 {
+        save_snippet_trash(&trash)?;
+        // Versions of purged snippets go with them
+        let keep: Vec<String> = load_snippets()?
+            .snippets
+            .into_iter()
+            .map(|s| s.id)
+            .chain(trash.snippets.iter().map(|t| t.snippet.id.clone()))
+            .collect();
+        snippet_versions::delete_orphaned(conn, &keep)?;
+    }
+    Ok(PurgedCounts { history, snippets })
+}
+
+/// Apply the trash_retention_days setting; 0 keeps trashed items until emptied
+pub fn apply_retention(conn: &Connection, retention_days: u32) -> Result<PurgedCounts, AppError> {
+    if retention_days == 0 {
+        return Ok(PurgedCounts::default());
+    }
+    purge(conn, Some(retention_days))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn list_trash(state: State<AppState>) -> Result<TrashContents, AppError> {
+    let conn = state.db.lock()?;
+    let trashed = list_trashed_entries(&conn, LIST_LIMIT)?;
+    drop(conn);
+    let (entries, deleted_at): (Vec<_>, Vec<_>) = trashed.into_iter().map(|t| (t.entry, t.deleted_at)).unzip();
+    let history = crate::for_display(entries)
+        .into_iter()
+        .zip(deleted_at)
+        .map(|(entry, deleted_at)| TrashedEntry { entry, deleted_at })
+        .collect();
+    let mut snippets = load_snippet_trash()?.snippets;
+    snippets.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(TrashContents { history, snippets })
+}
+
+/// Put a trashed history entry or snippet back where it was
+#[tauri::command]
+pub fn restore_deleted(app: AppHandle, state: State<AppState>, item: ItemRef) -> Result<(), AppError> {
+    match item.kind {
+        ItemKind::History => {
+            let id = item
+                .id
+                .parse::<i64>()
+                .map_err(|_| AppError::invalid_input(format!("Invalid history id: {}", item.id)))?;
+            crate::privacy::ensure_history_writable()?;
+            let conn = state.db.lock()?;
+            if !restore_entry(&conn, id)? {
+                return Err(AppError::not_found(format!("History entry {} is not in the trash", id)));
+            }
+        }
+        ItemKind::Snippet => {
+            let mut trash = load_snippet_trash()?;
+            let index = trash
+                .snippets
+                .iter()
+                .position(|trashed| trashed.snippet.id == item.id)
+                .ok_or_else(|| AppError::not_found(format!("Snippet {} is not in the trash", item.id)))?;
+            let mut data = load_snippets()?;
+            let restored = trash.snippets.remove(index).snippet;
+            data.snippets.retain(|s| s.id != restored.id);
+            data.snippets.push(restored);
+            save_snippets(&data)?;
+            save_snippet_trash(&trash)?;
+        }
+        ItemKind::Ai => return Err(AppError::invalid_input("AI history isn't kept in the trash")),
+    }
+    tray::rebuild(&app);
+    Ok(())
+}
+
+/// Permanently delete trashed items older than `older_than_days`, or everything
+#[tauri::command]
+pub fn empty_trash(state: State<AppState>, older_than_days: Option<u32>) -> Result<PurgedCounts, AppError> {
+    let conn = state.db.lock()?;
+    purge(&conn, older_than_days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trash_restore_and_purge() {
+        let conn = Connection::open_in_memory().unwrap();
+        history::create_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO history (id, content, character_count, word_count, line_count, source_app)
+             VALUES (1, 'one', 3, 1, 1, 'Code'), (2, 'two', 3, 1, 1, NULL)",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO history_versions (entry_id, version, content) VALUES (1, 1, 'on')", []).unwrap();
+
+        assert_eq!(trash_entries(&conn, Some(1)).unwrap(), 1);
+        let trashed = list_trashed_entries(&conn, 10).unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].entry.source_app.as_deref(), Some("Code"));
+
+        assert!(restore_entry(&conn, 1).unwrap());
+        assert!(!restore_entry(&conn, 1).unwrap());
+        let count = |table: &str| conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0)).unwrap();
+        assert_eq!(count("history"), 2);
+
+        // Clearing moves everything; recent deletions survive a retention purge
+        assert_eq!(trash_entries(&conn, None).unwrap(), 2);
+        assert_eq!(count("history"), 0);
+        assert_eq!(purge_entries(&conn, Some(30)).unwrap(), 0);
+        assert_eq!(count("history_versions"), 1);
+        assert_eq!(purge_entries(&conn, None).unwrap(), 2);
+        assert_eq!(count("history_versions"), 0);
+    }
+
+    #[test]
+    fn test_expiring_entries_skip_the_trash() {
+        let conn = Connection::open_in_memory().unwrap();
+        history::create_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO history (id, content, character_count, word_count, line_count, expires_at)
+             VALUES (1, '123456', 6, 1, 1, datetime('now', '+5 minutes')), (2, 'keep', 4, 1, 1, NULL)",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO history_versions (entry_id, version, content) VALUES (1, 1, '12345')", []).unwrap();
+
+        // Through move_to_trash, since trash_entries then sweeps the real attachment store
+        let columns = history_column_list(&conn).unwrap();
+        assert_eq!(move_to_trash(&conn, &columns, "WHERE ?1 IS NULL", None).unwrap(), (1, 1));
+        let trashed = list_trashed_entries(&conn, 10).unwrap();
+        assert_eq!(trashed.iter().map(|t| t.entry.id).collect::<Vec<_>>(), vec![2]);
+        let versions: i64 = conn.query_row("SELECT COUNT(*) FROM history_versions", [], |row| row.get(0)).unwrap();
+        assert_eq!(versions, 0);
+    }
+}
@@ -0,0 +1,329 @@
+// Menu bar tray. The menu is rebuilt whenever history, favorite snippets, the profile
+// or privacy mode change, so recent clips and snippets can be pasted without opening the panel.
+// Clip labels come from the same masked view as the panel, and pasting a sensitive
+// clip goes through the authentication gate first. While presenting, clips are listed by
+// title only, unnamed snippets are hidden, and nothing is pasted.
+
+use tauri::menu::{CheckMenuItem, Menu, MenuBuilder, MenuEvent, MenuItem, PredefinedMenuItem, SubmenuBuilder};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+#[cfg(target_os = "macos")]
+use tauri_nspanel::ManagerExt;
+
+#[cfg(target_os = "macos")]
+use crate::window::{WebviewWindowExt, MAIN_WINDOW_LABEL};
+use crate::history::{get_entries, get_entry};
+use crate::privacy::{is_privacy_mode_active, toggle_privacy};
+use crate::profiles::{load_profiles, switch_to};
+use crate::storage::load_snippets;
+use crate::AppState;
+
+/// Id of the menu bar icon, for updating it after creation
+pub const TRAY_ID: &str = "main";
+
+const RECENT_CLIPS_LIMIT: u32 = 10;
+const FAVORITE_SNIPPETS_LIMIT: usize = 20;
+const LABEL_MAX_CHARS: usize = 40;
+const HIDDEN_LABEL: &str = "Hidden while presenting";
+
+const CLIP_ID_PREFIX: &str = "clip:";
+const SNIPPET_ID_PREFIX: &str = "snippet:";
+const PROFILE_ID_PREFIX: &str = "profile:";
+
+/// Menu bar icon; dimmed while privacy mode has capture paused
+pub fn tray_icon(paused: bool) -> tauri::image::Image<'static> {
+    let png_bytes = include_bytes!("../icons/tray/icon.png");
+    let img = image::load_from_memory(png_bytes).expect("Failed to decode tray icon");
+    let mut rgba = img.to_rgba8();
+    if paused {
+        for pixel in rgba.pixels_mut() {
+            pixel[3] = (pixel[3] as u16 * 2 / 5) as u8;
+        }
+    }
+    let (width, height) = rgba.dimensions();
+    tauri::image::Image::new_owned(rgba.into_raw(), width, height)
+}
+
+/// First non-empty line, shortened to fit a menu
+fn menu_label(content: &str) -> String {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("(empty)");
+    let mut label: String = line.chars().take(LABEL_MAX_CHARS).collect();
+    if line.chars().count() > LABEL_MAX_CHARS {
+        label.push('…');
+    }
+    // '&' marks a mnemonic in menu labels
+    label.replace('&', "&&")
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let mut recent = SubmenuBuilder::new(app, "Recent Clips");
+    let entries = match app.state::<AppState>().db.lock() {
+        Ok(conn) => get_entries(&conn, RECENT_CLIPS_LIMIT, 0).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    if entries.is_empty() {
+        recent = recent.item(&MenuItem::with_id(app, "clip_none", "No Recent Clips", false, None::<&str>)?);
+    }
+    let presenting = crate::presentation::is_active();
+    for entry in &crate::for_display(entries) {
+        let label = match (presenting, &entry.title) {
+            (false, _) => menu_label(&entry.content),
+            (true, Some(title)) => menu_label(title),
+            (true, None) => HIDDEN_LABEL.to_string(),
+        };
+        recent = recent.text(format!("{}{}", CLIP_ID_PREFIX, entry.id), label);
+    }
+
+    let mut snippets = SubmenuBuilder::new(app, "Snippets");
+    let favorites: Vec<_> = load_snippets()
+        .map(|data| data.snippets.into_iter().filter(|s| s.favorite).take(FAVORITE_SNIPPETS_LIMIT).collect())
+        .unwrap_or_default();
+    if favorites.is_empty() {
+        snippets = snippets.item(&MenuItem::with_id(app, "snippet_none", "No Favorite Snippets", false, None::<&str>)?);
+    }
+    for snippet in &favorites {
+        let name: &str = if !snippet.name.trim().is_empty() {
+            &snippet.name
+        } else if presenting {
+            HIDDEN_LABEL
+        } else {
+            &snippet.content
+        };
+        snippets = snippets.text(format!("{}{}", SNIPPET_ID_PREFIX, snippet.id), menu_label(name));
+    }
+
+    let profiles = load_profiles().unwrap_or_default();
+    let active_name = profiles
+        .profiles
+        .iter()
+        .find(|p| p.id == profiles.active)
+        .map(|p| menu_label(&p.name))
+        .unwrap_or_default();
+    let mut profile_menu = SubmenuBuilder::new(app, format!("Profile: {}", active_name));
+    for profile in &profiles.profiles {
+        profile_menu = profile_menu.item(&CheckMenuItem::with_id(
+            app,
+            format!("{}{}", PROFILE_ID_PREFIX, profile.id),
+            menu_label(&profile.name),
+            true,
+            profile.id == profiles.active,
+            None::<&str>,
+        )?);
+    }
+
+    let privacy_item = CheckMenuItem::with_id(
+        app,
+        "privacy_mode",
+        "Pause Clipboard Capture",
+        true,
+        is_privacy_mode_active(),
+        None::<&str>,
+    )?;
+
+    MenuBuilder::new(app)
+        .text("show", "Show Wingman")
+        .item(&PredefinedMenuItem::separator(app)?)
+        .item(&recent.build()?)
+        .item(&snippets.build()?)
+        .item(&PredefinedMenuItem::separator(app)?)
+        .item(&profile_menu.build()?)
+        .text("hotkeys", "Hotkeys...")
+        .text("settings", "Settings...")
+        .item(&privacy_item)
+        .item(&PredefinedMenuItem::separator(app)?)
+        .text("check_updates", "Check for Updates...")
+        .item(&PredefinedMenuItem::separator(app)?)
+        .text("quit", "Quit Wingman")
+        .build()
+}
+
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
+
+    // Build tray icon - menu shows on left-click
+    // Use custom tray icon (light version with colors for menu bar)
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(tray_icon(is_privacy_mode_active()))
+        .menu(&menu)
+        .show_menu_on_left_click(true)
+        .on_menu_event(handle_menu_event)
+        .build(app)?;
+
+    Ok(())
+}
+
+/// Replace the tray menu with a freshly built one. Must not be called while holding the DB lock.
+pub fn rebuild(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            tray.set_menu(Some(menu)).ok();
+        }
+        Err(e) => log::warn!("Failed to rebuild tray menu: {}", e),
+    }
+}
+
+/// Show the panel on the cursor's monitor, optionally telling the frontend which view to open
+pub fn show_panel(app: &AppHandle, event: Option<&str>) {
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(window) = app.get_webview_window("main") {
+            let panel = app
+                .get_webview_panel(MAIN_WINDOW_LABEL)
+                .or_else(|_| window.to_wingman_panel());
+
+            if let Ok(panel) = panel {
+                // Move to the cursor's or active window's monitor with saved position
+                window.move_to_placement_monitor().ok();
+                crate::window::show_panel_animated(&panel);
+                if let Some(event) = event {
+                    window.emit(event, ()).ok();
+                }
+            }
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        if let Some(window) = app.get_webview_window("main") {
+            window.center().ok();
+            window.show().ok();
+            window.set_focus().ok();
+            crate::panel_animation::notify_shown(app);
+            if let Some(event) = event {
+                window.emit(event, ()).ok();
+            }
+        }
+    }
+}
+
+/// Simulate Cmd+V in the frontmost app. Needs Accessibility permission; without it
+/// the item is still on the clipboard for a manual paste.
+#[cfg(target_os = "macos")]
+fn send_paste_keystroke() {
+    std::thread::spawn(|| {
+        std::thread::sleep(std::time::Duration::from_millis(150));
+        let output = std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(r#"tell application "System Events" to keystroke "v" using command down"#)
+            .output();
+        if let Ok(output) = output {
+            if !output.status.success() {
+                log::warn!(
+                    "Paste keystroke failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+        }
+    });
+}
+
+#[cfg(not(target_os = "macos"))]
+fn send_paste_keystroke() {}
+
+/// Put content on the clipboard with its HTML when present, falling back to plain text
+pub fn copy_content(app: &AppHandle, text: &str, html: Option<&str>) -> bool {
+    match crate::native_clipboard::write_formats_to_clipboard(text, html, None) {
+        Ok(()) => true,
+        Err(e) => {
+            log::debug!("Native clipboard write failed ({}), using plain text", e);
+            use tauri_plugin_clipboard_manager::ClipboardExt;
+            app.clipboard()
+                .write_text(text)
+                .map_err(|e| log::error!("Failed to copy to clipboard: {}", e))
+                .is_ok()
+        }
+    }
+}
+
+/// Put content on the clipboard and paste it into the frontmost app. Opening the tray
+/// menu doesn't activate Wingman, so the app the user was in still has focus.
+pub fn paste_content(app: &AppHandle, text: &str, html: Option<&str>) {
+    if copy_content(app, text, html) {
+        send_paste_keystroke();
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id.as_ref();
+
+    let is_content = id.starts_with(CLIP_ID_PREFIX) || id.starts_with(SNIPPET_ID_PREFIX);
+    if is_content && crate::presentation::is_active() {
+        log::info!("Tray paste skipped in presentation mode");
+        return;
+    }
+
+    if let Some(entry_id) = id.strip_prefix(CLIP_ID_PREFIX).and_then(|v| v.parse::<i64>().ok()) {
+        let entry = match app.state::<AppState>().db.lock() {
+            Ok(conn) => get_entry(&conn, entry_id).ok().flatten(),
+            Err(_) => None,
+        };
+        match entry {
+            Some(entry) if crate::auth_gate::has_sensitive_tag(entry.smart_tags.as_deref()) => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    match crate::auth_gate::ensure(crate::auth_gate::GatedAction::ViewSensitiveEntry).await {
+                        Ok(()) => paste_content(&app, &entry.content, entry.html.as_deref()),
+                        Err(e) => log::info!("Tray paste of entry {} not authenticated: {}", entry.id, e),
+                    }
+                });
+            }
+            Some(entry) => paste_content(app, &entry.content, entry.html.as_deref()),
+            None => rebuild(app), // Entry is gone; the menu is stale
+        }
+        return;
+    }
+
+    if let Some(snippet_id) = id.strip_prefix(SNIPPET_ID_PREFIX) {
+        let snippet = load_snippets()
+            .ok()
+            .and_then(|data| data.snippets.into_iter().find(|s| s.id == snippet_id));
+        match snippet {
+            Some(snippet) => paste_content(app, &snippet.content, None),
+            None => rebuild(app),
+        }
+        return;
+    }
+
+    if let Some(profile_id) = id.strip_prefix(PROFILE_ID_PREFIX) {
+        if let Err(e) = switch_to(app, profile_id) {
+            log::error!("Failed to switch profile: {}", e);
+            rebuild(app);
+        }
+        return;
+    }
+
+    match id {
+        "show" => show_panel(app, None),
+        "hotkeys" => show_panel(app, Some("open-hotkeys")),
+        "settings" => show_panel(app, Some("open-settings")),
+        "check_updates" => show_panel(app, Some("check-updates")),
+        "privacy_mode" => toggle_privacy(app),
+        "quit" => app.exit(0),
+        _ => {}
+    }
+}
+
+#[tauri::command]
+pub fn rebuild_tray_menu(app: AppHandle) {
+    rebuild(&app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_label() {
+        assert_eq!(menu_label("\n  first line  \nsecond"), "first line");
+        assert_eq!(menu_label("Q&A"), "Q&&A");
+        assert_eq!(menu_label("   "), "(empty)");
+        let long = "x".repeat(60);
+        assert_eq!(menu_label(&long).chars().count(), LABEL_MAX_CHARS + 1);
+    }
+}
@@ -0,0 +1,255 @@
+// Removing everything Wingman stored, for users who are uninstalling: the history
+// databases and config files of every profile (current and legacy dirs), the Tauri
+// cache, log and webview dirs, the keychain entries, temp files and the login item.
+//
+// Two steps so nothing is wiped by a stray call: `prepare_remove_all_app_data` lists
+// what would go and hands out a single-use confirm token, which
+// `remove_all_app_data` needs within a few minutes. The open database is swapped for
+// an in-memory one before its file is deleted, and Wingman quits right after returning
+// the report so nothing writes a settings file back.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::AppError;
+use crate::AppState;
+
+/// How long a confirm token from prepare_remove_all_app_data stays valid
+const CONFIRM_TTL: Duration = Duration::from_secs(300);
+
+/// Temp files and dirs Wingman creates all start with one of these
+const TEMP_PREFIXES: &[&str] = &["wingman_", "wingman-"];
+
+/// Time for the report to reach the frontend before quitting
+const QUIT_DELAY: Duration = Duration::from_millis(1500);
+
+static PENDING_CONFIRM: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemovalPlan {
+    /// Pass to remove_all_app_data to go ahead
+    pub confirm_token: String,
+    pub expires_in_secs: u64,
+    /// Directories and temp files that exist and would be deleted
+    pub paths: Vec<String>,
+    /// Keychain accounts that may hold a secret; only existing ones are reported as removed
+    pub credentials: Vec<String>,
+    pub launch_at_login: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RemovalReport {
+    /// Directories deleted with everything in them
+    pub directories: Vec<String>,
+    pub temp_files: usize,
+    /// Keychain accounts that held a secret
+    pub credentials: Vec<String>,
+    pub login_item_removed: bool,
+    /// What couldn't be removed and why; the rest was still removed
+    pub failures: Vec<String>,
+}
+
+/// Every directory Wingman or its plugins write to, existing or not, without duplicates
+fn data_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let resolver = app.path();
+    let mut dirs: Vec<PathBuf> = [
+        crate::storage::get_app_data_dir().ok(),
+        // license cache, AI and Obsidian configs, the store plugin
+        dirs::data_dir().map(|dir| dir.join("com.wingman.app")),
+        // pre-profile GitHub config, the sql plugin
+        dirs::config_dir().map(|dir| dir.join("com.wingman.app")),
+        resolver.app_local_data_dir().ok(),
+        resolver.app_cache_dir().ok(),
+        resolver.app_log_dir().ok(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Wingman's files and dirs directly inside `temp_dir`
+fn temp_entries(temp_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(temp_dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            TEMP_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Consume the pending token if `token` matches it and it hasn't expired
+fn take_confirm(token: &str, now: Instant) -> Result<(), AppError> {
+    let mut pending = PENDING_CONFIRM.lock().unwrap_or_else(|e| e.into_inner());
+    match pending.as_ref() {
+        Some((expected, issued)) if expected == token => {
+            let expired = now.duration_since(*issued) > CONFIRM_TTL;
+            *pending = None;
+            if expired {
+                Err(AppError::invalid_input("The confirmation expired; start over"))
+            } else {
+                Ok(())
+            }
+        }
+        _ => Err(AppError::invalid_input("Invalid confirmation token")),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// What remove_all_app_data would delete, with the token it needs
+#[tauri::command]
+pub fn prepare_remove_all_app_data(app: AppHandle) -> Result<RemovalPlan, AppError> {
+    let mut paths: Vec<String> = data_dirs(&app)
+        .into_iter()
+        .filter(|dir| dir.exists())
+        .map(|dir| dir.to_string_lossy().to_string())
+        .collect();
+    paths.extend(
+        temp_entries(&std::env::temp_dir())
+            .into_iter()
+            .map(|path| path.to_string_lossy().to_string()),
+    );
+    let launch_at_login = crate::autostart::get_launch_at_login().map(|s| s.enabled).unwrap_or(false);
+
+    let confirm_token = uuid::Uuid::new_v4().to_string();
+    *PENDING_CONFIRM.lock().unwrap_or_else(|e| e.into_inner()) = Some((confirm_token.clone(), Instant::now()));
+
+    Ok(RemovalPlan {
+        confirm_token,
+        expires_in_secs: CONFIRM_TTL.as_secs(),
+        paths,
+        credentials: crate::credentials::all_accounts(),
+        launch_at_login,
+    })
+}
+
+/// Delete all of Wingman's data, secrets and login item, then quit. Needs the token
+/// from prepare_remove_all_app_data. Failures are collected, not fatal, so one locked
+/// file doesn't leave the tokens behind.
+#[tauri::command]
+pub fn remove_all_app_data(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    confirm_token: String,
+) -> Result<RemovalReport, AppError> {
+    take_confirm(&confirm_token, Instant::now())?;
+    log::warn!("Removing all Wingman data");
+    let mut report = RemovalReport::default();
+
+    // Close the database file; anything still running writes to memory from here on
+    match Connection::open_in_memory() {
+        Ok(memory) => *state.db.lock().map_err(|e| AppError::internal(e.to_string()))? = memory,
+        Err(e) => report.failures.push(format!("Closing the database: {}", e)),
+    }
+
+    // Before the dirs go, since the registry file lists the accounts
+    for account in crate::credentials::all_accounts() {
+        match crate::credentials::erase(&account) {
+            Ok(true) => report.credentials.push(account),
+            Ok(false) => {}
+            Err(e) => report.failures.push(format!("Keychain entry {}: {}", account, e.message)),
+        }
+    }
+
+    match crate::autostart::get_launch_at_login() {
+        Ok(status) if status.enabled => match crate::autostart::set_enabled(false) {
+            Ok(()) => report.login_item_removed = true,
+            Err(e) => report.failures.push(format!("Login item: {}", e)),
+        },
+        Ok(_) => {}
+        Err(e) => report.failures.push(format!("Login item: {}", e.message)),
+    }
+
+    for path in temp_entries(&std::env::temp_dir()) {
+        match remove_path(&path) {
+            Ok(()) => report.temp_files += 1,
+            Err(e) => report.failures.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    for dir in data_dirs(&app).into_iter().filter(|dir| dir.exists()) {
+        match fs::remove_dir_all(&dir) {
+            Ok(()) => report.directories.push(dir.to_string_lossy().to_string()),
+            Err(e) => report.failures.push(format!("{}: {}", dir.display(), e)),
+        }
+    }
+
+    log::warn!(
+        "Removed {} directories, {} temp files and {} keychain entries; {} failures",
+        report.directories.len(),
+        report.temp_files,
+        report.credentials.len(),
+        report.failures.len()
+    );
+
+    std::thread::spawn(move || {
+        std::thread::sleep(QUIT_DELAY);
+        app.exit(0);
+    });
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_token_is_single_use_and_expires() {
+        let now = Instant::now();
+        *PENDING_CONFIRM.lock().unwrap() = Some(("token".to_string(), now));
+        assert!(take_confirm("wrong", now).is_err());
+        assert!(take_confirm("token", now).is_ok());
+        assert!(take_confirm("token", now).is_err());
+
+        *PENDING_CONFIRM.lock().unwrap() = Some(("token".to_string(), now));
+        assert!(take_confirm("token", now + CONFIRM_TTL + Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn test_temp_entries_only_match_wingman_prefixes() {
+        let dir = std::env::temp_dir().join(format!("uninstall-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("wingman_clipboard")).unwrap();
+        fs::write(dir.join("wingman-bundle-x.db"), b"").unwrap();
+        fs::write(dir.join("wingmanish.txt"), b"").unwrap();
+        fs::write(dir.join("other.txt"), b"").unwrap();
+
+        let names: Vec<String> = temp_entries(&dir)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["wingman-bundle-x.db", "wingman_clipboard"]);
+
+        for path in temp_entries(&dir) {
+            remove_path(&path).unwrap();
+        }
+        assert!(temp_entries(&dir).is_empty());
+        fs::remove_dir_all(dir).ok();
+    }
+}
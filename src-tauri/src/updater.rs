@@ -1,6 +1,53 @@
+use base64::Engine;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
-use tauri_plugin_updater::UpdaterExt;
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::http_client;
+use crate::storage::{load_settings, save_settings};
+
+const STABLE_ENDPOINT: &str = "https://github.com/csteamengine/wingman/releases/latest/download/latest.json";
+/// Rolling `beta` release that CI re-points at each pre-release
+const BETA_ENDPOINT: &str = "https://github.com/csteamengine/wingman/releases/download/beta/latest.json";
+
+/// Delay before the first background check, so startup isn't competing with a download
+const BACKGROUND_CHECK_DELAY: Duration = Duration::from_secs(60);
+const BACKGROUND_CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "stable" => Ok(UpdateChannel::Stable),
+            "beta" => Ok(UpdateChannel::Beta),
+            other => Err(format!("Unknown update channel: {}", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+
+    fn endpoint(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => STABLE_ENDPOINT,
+            UpdateChannel::Beta => BETA_ENDPOINT,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfo {
@@ -9,6 +56,10 @@ pub struct UpdateInfo {
     pub has_update: bool,
     pub release_notes: Option<String>,
     pub download_url: Option<String>,
+    pub channel: String,
+    pub release_date: Option<String>,
+    /// Already downloaded in the background; only a restart is needed
+    pub ready_to_install: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -18,106 +69,443 @@ pub struct DownloadProgress {
     pub percent: Option<f64>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseNotes {
+    pub version: String,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+}
+
+/// An update downloaded in the background, waiting for a restart
+struct ReadyUpdate {
+    update: Update,
+    bytes: Vec<u8>,
+}
+
+static READY_UPDATE: Mutex<Option<ReadyUpdate>> = Mutex::new(None);
+
+/// Install the ready update when the app quits instead of restarting now
+static INSTALL_ON_QUIT: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadState {
+    Idle,
+    Downloading,
+    Paused,
+    Cancelled,
+}
+
+static DOWNLOAD_STATE: Mutex<DownloadState> = Mutex::new(DownloadState::Idle);
+
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+fn download_state() -> DownloadState {
+    DOWNLOAD_STATE.lock().map(|s| *s).unwrap_or(DownloadState::Cancelled)
+}
+
+/// Resets the download state however the download ends
+struct DownloadGuard;
+
+impl DownloadGuard {
+    fn start() -> Result<Self, String> {
+        let mut state = DOWNLOAD_STATE.lock().map_err(|e| e.to_string())?;
+        if matches!(*state, DownloadState::Downloading | DownloadState::Paused) {
+            return Err("An update download is already in progress".to_string());
+        }
+        *state = DownloadState::Downloading;
+        Ok(DownloadGuard)
+    }
+}
+
+impl Drop for DownloadGuard {
+    fn drop(&mut self) {
+        if let Ok(mut state) = DOWNLOAD_STATE.lock() {
+            *state = DownloadState::Idle;
+        }
+    }
+}
+
 fn get_current_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
-/// Check for available updates using Tauri's built-in updater
+fn current_channel() -> UpdateChannel {
+    load_settings()
+        .ok()
+        .and_then(|s| UpdateChannel::parse(&s.update_channel).ok())
+        .unwrap_or(UpdateChannel::Stable)
+}
+
+fn ready_version() -> Option<String> {
+    READY_UPDATE
+        .lock()
+        .ok()
+        .and_then(|ready| ready.as_ref().map(|r| r.update.version.clone()))
+}
+
+/// Stable 0-99 bucket for this device and release, so each release rolls out to a
+/// different slice of users
+fn rollout_bucket(device_id: &str, version: &str) -> u8 {
+    let digest = Sha256::digest(format!("{}:{}", device_id, version).as_bytes());
+    (u16::from_be_bytes([digest[0], digest[1]]) % 100) as u8
+}
+
+/// Stable releases can carry a `rollout_percentage` in latest.json to stage the rollout.
+/// Beta users always get everything.
+fn is_in_rollout(channel: UpdateChannel, update: &Update) -> bool {
+    if channel == UpdateChannel::Beta {
+        return true;
+    }
+    let Some(percentage) = update.raw_json.get("rollout_percentage").and_then(|v| v.as_u64()) else {
+        return true;
+    };
+    if percentage >= 100 {
+        return true;
+    }
+    // Without a device id there's nothing stable to bucket on; wait for the full rollout
+    match crate::license::get_device_id() {
+        Ok(device_id) => (rollout_bucket(&device_id, &update.version) as u64) < percentage,
+        Err(_) => false,
+    }
+}
+
+/// Latest release on the selected channel that this device is eligible for
+async fn find_update(app: &AppHandle) -> Result<(UpdateChannel, Option<Update>), String> {
+    let channel = current_channel();
+    let endpoint = Url::parse(channel.endpoint()).map_err(|e| e.to_string())?;
+    // Same proxy and CA settings as every other request; offline mode stops here
+    let settings = crate::http_client::settings().map_err(|e| e.to_string())?;
+    let updater = app
+        .updater_builder()
+        .configure_client(settings)
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Updater not available: {}", e))?;
+
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+
+    match update {
+        Some(update) if !is_in_rollout(channel, &update) => {
+            log::info!("Update {} is staged and not yet rolled out to this device", update.version);
+            Ok((channel, None))
+        }
+        update => Ok((channel, update)),
+    }
+}
+
+/// Check the selected channel for an update
 pub async fn check_for_updates(app: AppHandle) -> Result<UpdateInfo, String> {
     let current_version = get_current_version();
+    let (channel, update) = find_update(&app).await?;
 
-    // Check for updates using the Tauri plugin
-    match app.updater() {
-        Ok(updater) => {
-            match updater.check().await {
-                Ok(Some(update)) => {
-                    // Update is available
-                    Ok(UpdateInfo {
-                        current_version: current_version.clone(),
-                        latest_version: Some(update.version.clone()),
-                        has_update: true,
-                        release_notes: update.body.clone(),
-                        download_url: Some(update.download_url.to_string()),
-                    })
-                }
-                Ok(None) => {
-                    // No update available
-                    Ok(UpdateInfo {
-                        current_version: current_version.clone(),
-                        latest_version: Some(current_version),
-                        has_update: false,
-                        release_notes: None,
-                        download_url: None,
-                    })
-                }
-                Err(e) => {
-                    // Error checking for updates
-                    Err(format!("Failed to check for updates: {}", e))
+    match update {
+        Some(update) => Ok(UpdateInfo {
+            current_version,
+            latest_version: Some(update.version.clone()),
+            has_update: true,
+            release_notes: update.body.clone(),
+            download_url: Some(update.download_url.to_string()),
+            channel: channel.as_str().to_string(),
+            release_date: update.date.map(|d| d.to_string()),
+            ready_to_install: ready_version().as_deref() == Some(update.version.as_str()),
+        }),
+        None => Ok(UpdateInfo {
+            current_version: current_version.clone(),
+            latest_version: Some(current_version),
+            has_update: false,
+            release_notes: None,
+            download_url: None,
+            channel: channel.as_str().to_string(),
+            release_date: None,
+            ready_to_install: false,
+        }),
+    }
+}
+
+/// Notes for the pending update, to show before installing
+pub async fn get_release_notes(app: AppHandle) -> Result<Option<ReleaseNotes>, String> {
+    let (_, update) = find_update(&app).await?;
+    Ok(update.map(|update| ReleaseNotes {
+        version: update.version.clone(),
+        notes: update.body.clone(),
+        date: update.date.map(|d| d.to_string()),
+    }))
+}
+
+/// Switch channels. A download from the previous channel is discarded.
+pub fn set_update_channel(channel: &str) -> Result<(), String> {
+    let channel = UpdateChannel::parse(channel)?;
+    let mut settings = load_settings().map_err(|e| e.to_string())?;
+    if settings.update_channel != channel.as_str() {
+        settings.update_channel = channel.as_str().to_string();
+        save_settings(&settings).map_err(|e| e.to_string())?;
+        if let Ok(mut ready) = READY_UPDATE.lock() {
+            *ready = None;
+        }
+        INSTALL_ON_QUIT.store(false, Ordering::SeqCst);
+        log::info!("Update channel set to {}", channel.as_str());
+    }
+    Ok(())
+}
+
+/// minisign public key from the updater section of tauri.conf.json
+fn updater_pubkey(app: &AppHandle) -> Result<String, String> {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|config| config.get("pubkey"))
+        .and_then(|key| key.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| "Updater public key is not configured".to_string())
+}
+
+/// Check the release signature before the bytes are allowed anywhere near the installer
+fn verify_signature(data: &[u8], signature: &str, pubkey: &str) -> Result<(), String> {
+    let decode = |value: &str| -> Result<String, String> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(|e| e.to_string())?;
+        String::from_utf8(bytes).map_err(|e| e.to_string())
+    };
+    let public_key = minisign_verify::PublicKey::decode(&decode(pubkey)?).map_err(|e| e.to_string())?;
+    let signature = minisign_verify::Signature::decode(&decode(signature)?).map_err(|e| e.to_string())?;
+    public_key
+        .verify(data, &signature, true)
+        .map_err(|e| format!("Update signature is invalid: {}", e))
+}
+
+/// Wait out a pause. Returns an error if the download is cancelled meanwhile.
+async fn wait_while_paused() -> Result<(), String> {
+    loop {
+        match download_state() {
+            DownloadState::Paused => tokio::time::sleep(PAUSE_POLL_INTERVAL).await,
+            DownloadState::Cancelled => return Err("Update download cancelled".to_string()),
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Stream the update, honouring pause and cancel. Pausing drops the connection;
+/// resuming continues with a Range request where the server supports it.
+async fn fetch_update_bytes(app: &AppHandle, update: &Update, emit_progress: bool) -> Result<Vec<u8>, String> {
+    let client = http_client::builder()
+        .map_err(|e| e.to_string())?
+        .user_agent(format!("Wingman/{}", get_current_version()))
+        .connect_timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut total: Option<u64> = None;
+
+    'download: loop {
+        let mut request = client
+            .get(update.download_url.as_str())
+            .headers(update.headers.clone())
+            .header(reqwest::header::ACCEPT, "application/octet-stream");
+        if !bytes.is_empty() {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", bytes.len()));
+        }
+
+        let mut response = request
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to download update: {}", e))?;
+
+        if !bytes.is_empty() && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            // Server ignored the Range header; start over
+            bytes.clear();
+        }
+        if total.is_none() {
+            total = response.content_length().map(|len| len + bytes.len() as u64);
+        }
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to download update: {}", e))?
+        {
+            bytes.extend_from_slice(&chunk);
+
+            if emit_progress {
+                let downloaded = bytes.len() as u64;
+                app.emit(
+                    "update-download-progress",
+                    DownloadProgress {
+                        downloaded,
+                        total,
+                        percent: total.map(|t| (downloaded as f64 / t as f64) * 100.0),
+                    },
+                )
+                .ok();
+            }
+
+            match download_state() {
+                DownloadState::Cancelled => return Err("Update download cancelled".to_string()),
+                DownloadState::Paused => {
+                    drop(response);
+                    if emit_progress {
+                        app.emit("update-download-paused", ()).ok();
+                    }
+                    wait_while_paused().await?;
+                    if emit_progress {
+                        app.emit("update-download-resumed", ()).ok();
+                    }
+                    continue 'download;
                 }
+                _ => {}
             }
         }
-        Err(e) => Err(format!("Updater not available: {}", e)),
+        break;
+    }
+
+    if let Some(total) = total {
+        if bytes.len() as u64 != total {
+            return Err(format!("Update download incomplete ({} of {} bytes)", bytes.len(), total));
+        }
     }
+    Ok(bytes)
+}
+
+/// Download and verify the pending update, keeping it ready for install.
+/// `emit_progress` is off for background downloads so the UI isn't disturbed.
+async fn download_update(app: &AppHandle, emit_progress: bool) -> Result<Option<UpdateInfo>, String> {
+    let (channel, Some(update)) = find_update(app).await? else {
+        return Ok(None);
+    };
+    let info = UpdateInfo {
+        current_version: get_current_version(),
+        latest_version: Some(update.version.clone()),
+        has_update: true,
+        release_notes: update.body.clone(),
+        download_url: Some(update.download_url.to_string()),
+        channel: channel.as_str().to_string(),
+        release_date: update.date.map(|d| d.to_string()),
+        ready_to_install: true,
+    };
+    if ready_version().as_deref() == Some(update.version.as_str()) {
+        return Ok(Some(info));
+    }
+
+    let _guard = DownloadGuard::start()?;
+    if emit_progress {
+        app.emit("update-download-started", ()).ok();
+    }
+    log::info!("Downloading update {}", update.version);
+
+    let result = async {
+        let bytes = fetch_update_bytes(app, &update, emit_progress).await?;
+        verify_signature(&bytes, &update.signature, &updater_pubkey(app)?)?;
+        Ok::<_, String>(bytes)
+    }
+    .await;
+
+    let bytes = match result {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            if emit_progress {
+                app.emit("update-error", e.clone()).ok();
+            }
+            return Err(e);
+        }
+    };
+
+    *READY_UPDATE.lock().map_err(|e| e.to_string())? = Some(ReadyUpdate { update, bytes });
+    app.emit("update-ready", &info).ok();
+    Ok(Some(info))
+}
+
+/// Periodically fetch updates in the background when auto-download is enabled
+pub fn start_background_updates(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(BACKGROUND_CHECK_DELAY).await;
+        loop {
+            let enabled = load_settings().map(|s| s.auto_download_updates).unwrap_or(false);
+            if enabled && !crate::offline::is_enabled() {
+                if let Err(e) = download_update(&app, false).await {
+                    log::warn!("Background update failed: {}", e);
+                }
+            }
+            tokio::time::sleep(BACKGROUND_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn take_ready_update() -> Result<ReadyUpdate, String> {
+    READY_UPDATE
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or_else(|| "No downloaded update to install".to_string())
+}
+
+/// Install an update that was downloaded in the background and restart
+pub fn install_ready_update(app: AppHandle) -> Result<(), String> {
+    let ready = take_ready_update()?;
+
+    app.emit("update-install-started", ()).ok();
+    ready
+        .update
+        .install(&ready.bytes)
+        .map_err(|e| format!("Failed to install update: {}", e))?;
+    app.emit("update-installed", ()).ok();
+    app.restart();
 }
 
 /// Download and install an update
 pub async fn download_and_install_update(app: AppHandle) -> Result<(), String> {
-    match app.updater() {
-        Ok(updater) => {
-            match updater.check().await {
-                Ok(Some(update)) => {
-                    // Emit download started event
-                    app.emit("update-download-started", ()).ok();
-
-                    // Download with progress tracking
-                    let mut downloaded = 0u64;
-
-                    match update
-                        .download_and_install(
-                            |chunk_length, content_length| {
-                                downloaded += chunk_length as u64;
-
-                                let percent = if let Some(total) = content_length {
-                                    Some((downloaded as f64 / total as f64) * 100.0)
-                                } else {
-                                    None
-                                };
-
-                                // Emit progress event
-                                let progress = DownloadProgress {
-                                    downloaded,
-                                    total: content_length,
-                                    percent,
-                                };
-
-                                app.emit("update-download-progress", progress).ok();
-                            },
-                            || {
-                                // Installation started
-                                app.emit("update-install-started", ()).ok();
-                            },
-                        )
-                        .await
-                    {
-                        Ok(_) => {
-                            // Update installed successfully
-                            app.emit("update-installed", ()).ok();
-
-                            // Restart the app to apply the update
-                            app.restart();
-                        }
-                        Err(e) => {
-                            let error_msg = format!("Failed to download/install update: {}", e);
-                            app.emit("update-error", error_msg.clone()).ok();
-                            Err(error_msg)
-                        }
-                    }
-                }
-                Ok(None) => Err("No update available".to_string()),
-                Err(e) => Err(format!("Failed to check for updates: {}", e)),
+    if download_update(&app, true).await?.is_none() {
+        return Err("No update available".to_string());
+    }
+    install_ready_update(app)
+}
+
+pub fn set_download_paused(paused: bool) -> Result<DownloadState, String> {
+    let mut state = DOWNLOAD_STATE.lock().map_err(|e| e.to_string())?;
+    *state = match (*state, paused) {
+        (DownloadState::Downloading, true) => DownloadState::Paused,
+        (DownloadState::Paused, false) => DownloadState::Downloading,
+        (current, _) => current,
+    };
+    Ok(*state)
+}
+
+pub fn cancel_download() -> Result<(), String> {
+    let mut state = DOWNLOAD_STATE.lock().map_err(|e| e.to_string())?;
+    if matches!(*state, DownloadState::Downloading | DownloadState::Paused) {
+        *state = DownloadState::Cancelled;
+    }
+    Ok(())
+}
+
+/// Download now (if needed) and install silently when Wingman next quits
+pub async fn defer_until_quit(app: AppHandle) -> Result<UpdateInfo, String> {
+    let info = download_update(&app, true)
+        .await?
+        .ok_or_else(|| "No update available".to_string())?;
+    INSTALL_ON_QUIT.store(true, Ordering::SeqCst);
+    log::info!("Update {} will be installed on quit", info.latest_version.as_deref().unwrap_or("?"));
+    Ok(info)
+}
+
+/// Called on exit: installs a deferred update without restarting
+pub fn install_on_quit() {
+    if !INSTALL_ON_QUIT.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    match take_ready_update() {
+        Ok(ready) => {
+            if let Err(e) = ready.update.install(&ready.bytes) {
+                log::error!("Failed to install deferred update: {}", e);
             }
         }
-        Err(e) => Err(format!("Updater not available: {}", e)),
+        Err(e) => log::warn!("Deferred update not installed: {}", e),
     }
 }
 
@@ -133,4 +521,22 @@ mod tests {
         let parts: Vec<&str> = version.split('.').collect();
         assert!(parts.len() >= 3);
     }
+
+    #[test]
+    fn test_rollout_bucket() {
+        let bucket = rollout_bucket("device-a", "1.2.0");
+        assert!(bucket < 100);
+        assert_eq!(bucket, rollout_bucket("device-a", "1.2.0"));
+        // Different devices should spread across buckets
+        let buckets: std::collections::HashSet<u8> =
+            (0..50).map(|i| rollout_bucket(&format!("device-{}", i), "1.2.0")).collect();
+        assert!(buckets.len() > 20);
+    }
+
+    #[test]
+    fn test_channel_parse() {
+        assert_eq!(UpdateChannel::parse("beta").unwrap(), UpdateChannel::Beta);
+        assert_eq!(UpdateChannel::parse("stable").unwrap().as_str(), "stable");
+        assert!(UpdateChannel::parse("nightly").is_err());
+    }
 }
@@ -0,0 +1,413 @@
+// Watch folders: new text and code files dropped into a configured directory are
+// ingested as history entries or snippets. Folders are listed in watch_folders.json;
+// one notify watcher covers all of them and is rebuilt whenever the list changes.
+//
+// Editors and downloads often create a file and write it in several steps, so a new
+// file is only read once it has been quiet for SETTLE_DELAY.
+
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use thiserror::Error;
+
+use crate::error::AppError;
+use crate::history::{add_entry, get_entry};
+use crate::storage::{ensure_app_data_dir, load_snippets, save_snippets, Snippet};
+use crate::AppState;
+
+/// Files larger than this are skipped unless the folder says otherwise
+const DEFAULT_MAX_BYTES: u64 = 256 * 1024;
+
+/// How long a new file must go without changes before it's read
+const SETTLE_DELAY: Duration = Duration::from_secs(1);
+
+/// Language ids with a known file extension (see `get_file_filter`)
+pub(crate) const LANGUAGES: &[&str] = &[
+    "javascript", "typescript", "jsx", "tsx", "html", "css", "json", "sql", "yaml", "xml", "bash",
+    "python", "java", "go", "php", "c", "cpp", "rust", "ruby", "swift", "kotlin", "csharp", "markdown",
+];
+
+#[derive(Error, Debug)]
+pub enum WatchFolderError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+    #[error("Watcher error: {0}")]
+    Watcher(#[from] notify::Error),
+    #[error("{0} is not a directory")]
+    NotADirectory(String),
+    #[error("{0} is already watched")]
+    AlreadyWatched(String),
+    #[error("Invalid pattern \"{0}\": {1}")]
+    InvalidPattern(String, String),
+    #[error("Watch folder {0} not found")]
+    NotFound(String),
+}
+
+/// Where a watched folder's files end up
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchTarget {
+    History,
+    Snippet,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchFolder {
+    pub id: String,
+    pub path: String,
+    pub target: WatchTarget,
+    /// Glob patterns matched against file names ("*.md"); empty accepts every file
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: u64,
+    #[serde(default)]
+    pub recursive: bool,
+    pub created_at: String,
+}
+
+fn default_max_bytes() -> u64 {
+    DEFAULT_MAX_BYTES
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct WatchFoldersData {
+    folders: Vec<WatchFolder>,
+}
+
+/// Sent to the frontend for every ingested file
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestedFile {
+    pub folder_id: String,
+    pub path: String,
+    pub target: WatchTarget,
+    /// History entry id or snippet id
+    pub item_id: String,
+}
+
+/// The active watcher; replacing it stops the previous one and its event thread
+static WATCHER: Mutex<Option<RecommendedWatcher>> = Mutex::new(None);
+
+fn folders_path() -> Result<PathBuf, WatchFolderError> {
+    Ok(ensure_app_data_dir()?.join("watch_folders.json"))
+}
+
+fn load_folders() -> Result<WatchFoldersData, WatchFolderError> {
+    let path = folders_path()?;
+    if !path.exists() {
+        return Ok(WatchFoldersData::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_folders(data: &WatchFoldersData) -> Result<(), WatchFolderError> {
+    fs::write(folders_path()?, serde_json::to_string_pretty(data)?)?;
+    Ok(())
+}
+
+fn validate_patterns(patterns: &[String]) -> Result<(), WatchFolderError> {
+    for pattern in patterns {
+        glob::Pattern::new(pattern).map_err(|e| WatchFolderError::InvalidPattern(pattern.clone(), e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Whether a file of `size` bytes at `path` should be ingested by `folder`.
+/// Hidden files (editor swap files, .DS_Store) never are.
+fn accepts(folder: &WatchFolder, path: &Path, size: u64) -> bool {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        return false;
+    };
+    if name.starts_with('.') || size == 0 || size > folder.max_bytes {
+        return false;
+    }
+    let in_scope = if folder.recursive {
+        path.starts_with(&folder.path)
+    } else {
+        path.parent() == Some(Path::new(&folder.path))
+    };
+    in_scope
+        && (folder.patterns.is_empty()
+            || folder
+                .patterns
+                .iter()
+                .filter_map(|p| glob::Pattern::new(p).ok())
+                .any(|p| p.matches(&name)))
+}
+
+/// Language id for a file from its extension
+pub(crate) fn language_for_path(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    LANGUAGES
+        .iter()
+        .copied()
+        .find(|language| crate::get_file_filter(language).1.contains(&ext.as_str()))
+}
+
+/// Text contents of a file, or `None` for binary files
+fn read_text(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn ingest(app: &AppHandle, folder: &WatchFolder, path: &Path) -> Result<Option<String>, String> {
+    let Some(content) = read_text(path) else {
+        return Ok(None);
+    };
+    let language = language_for_path(path);
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    match folder.target {
+        WatchTarget::History => {
+            if crate::privacy::is_privacy_mode_active() {
+                return Err("History is paused while privacy mode is on".to_string());
+            }
+            let state = app.state::<AppState>();
+            let conn = state.db.lock().map_err(|e| e.to_string())?;
+            let id = add_entry(&conn, &content, language, None, None, None).map_err(|e| e.to_string())?;
+            if let Ok(Some(entry)) = get_entry(&conn, id) {
+                app.emit("history-entry-added", &entry).ok();
+            }
+            drop(conn);
+            crate::tray::rebuild(app);
+            Ok(Some(id.to_string()))
+        }
+        WatchTarget::Snippet => {
+            let mut data = load_snippets().map_err(|e| e.to_string())?;
+            let now = chrono::Utc::now().to_rfc3339();
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or(file_name);
+            let snippet = Snippet {
+                id: uuid::Uuid::new_v4().to_string(),
+                name,
+                content,
+                tags: language.map(|l| vec![l.to_string()]).unwrap_or_default(),
+                created_at: now.clone(),
+                updated_at: now,
+                ..Default::default()
+            };
+            let id = snippet.id.clone();
+            data.snippets.push(snippet);
+            save_snippets(&data).map_err(|e| e.to_string())?;
+            crate::tray::rebuild(app);
+            Ok(Some(id))
+        }
+    }
+}
+
+/// Most specific watched folder containing `path`
+fn folder_for<'a>(folders: &'a [WatchFolder], path: &Path) -> Option<&'a WatchFolder> {
+    folders
+        .iter()
+        .filter(|f| path.starts_with(&f.path))
+        .max_by_key(|f| f.path.len())
+}
+
+fn process(app: &AppHandle, folders: &[WatchFolder], path: &Path) {
+    let Some(folder) = folder_for(folders, path) else {
+        return;
+    };
+    let Ok(metadata) = fs::metadata(path) else {
+        return; // Deleted or moved away before it settled
+    };
+    if !metadata.is_file() || !accepts(folder, path, metadata.len()) {
+        return;
+    }
+    match ingest(app, folder, path) {
+        Ok(Some(item_id)) => {
+            log::info!("Ingested {} from watch folder {}", path.display(), folder.path);
+            let ingested = IngestedFile {
+                folder_id: folder.id.clone(),
+                path: path.to_string_lossy().to_string(),
+                target: folder.target,
+                item_id,
+            };
+            app.emit("watch-folder-ingested", &ingested).ok();
+        }
+        Ok(None) => log::debug!("Skipped non-text file {}", path.display()),
+        Err(e) => log::warn!("Failed to ingest {}: {}", path.display(), e),
+    }
+}
+
+/// Watch every configured folder, replacing any previous watcher
+pub fn restart(app: &AppHandle) -> Result<(), WatchFolderError> {
+    let folders = load_folders()?.folders;
+    let mut current = WATCHER.lock().unwrap_or_else(|e| e.into_inner());
+    *current = None;
+    if folders.is_empty() {
+        return Ok(());
+    }
+
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for folder in &folders {
+        let mode = if folder.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        if let Err(e) = watcher.watch(Path::new(&folder.path), mode) {
+            log::warn!("Can't watch {}: {}", folder.path, e);
+        }
+    }
+    *current = Some(watcher);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        // New files waiting to settle, with when they last changed
+        let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+        loop {
+            match rx.recv_timeout(SETTLE_DELAY / 2) {
+                Ok(Ok(event)) => match event.kind {
+                    EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_)) => {
+                        for path in event.paths {
+                            pending.insert(path, Instant::now());
+                        }
+                    }
+                    EventKind::Modify(_) => {
+                        for path in event.paths {
+                            if let Some(changed) = pending.get_mut(&path) {
+                                *changed = Instant::now();
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                Ok(Err(e)) => log::warn!("Watch folder error: {}", e),
+                Err(RecvTimeoutError::Timeout) => {}
+                // The watcher was replaced or removed
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            let settled: Vec<PathBuf> = pending
+                .iter()
+                .filter(|(_, changed)| changed.elapsed() >= SETTLE_DELAY)
+                .map(|(path, _)| path.clone())
+                .collect();
+            for path in settled {
+                pending.remove(&path);
+                process(&app, &folders, &path);
+            }
+        }
+    });
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn list_watch_folders() -> Result<Vec<WatchFolder>, AppError> {
+    load_folders().map(|data| data.folders).map_err(AppError::from)
+}
+
+/// Start ingesting new files in `path`. Existing files are left alone.
+#[tauri::command]
+pub fn add_watch_folder(
+    app: AppHandle,
+    path: String,
+    target: WatchTarget,
+    patterns: Option<Vec<String>>,
+    max_bytes: Option<u64>,
+    recursive: Option<bool>,
+) -> Result<WatchFolder, AppError> {
+    let add = || -> Result<WatchFolder, WatchFolderError> {
+        let canonical = fs::canonicalize(&path).map_err(|_| WatchFolderError::NotADirectory(path.clone()))?;
+        if !canonical.is_dir() {
+            return Err(WatchFolderError::NotADirectory(path.clone()));
+        }
+        let canonical = canonical.to_string_lossy().to_string();
+        let patterns = patterns.unwrap_or_default();
+        validate_patterns(&patterns)?;
+
+        let mut data = load_folders()?;
+        if data.folders.iter().any(|f| f.path == canonical) {
+            return Err(WatchFolderError::AlreadyWatched(canonical));
+        }
+        let folder = WatchFolder {
+            id: uuid::Uuid::new_v4().to_string(),
+            path: canonical,
+            target,
+            patterns,
+            max_bytes: max_bytes.unwrap_or(DEFAULT_MAX_BYTES),
+            recursive: recursive.unwrap_or(false),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        data.folders.push(folder.clone());
+        save_folders(&data)?;
+        restart(&app)?;
+        Ok(folder)
+    };
+    add().map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn remove_watch_folder(app: AppHandle, id: String) -> Result<(), AppError> {
+    let mut data = load_folders()?;
+    let before = data.folders.len();
+    data.folders.retain(|f| f.id != id);
+    if data.folders.len() == before {
+        return Err(WatchFolderError::NotFound(id).into());
+    }
+    save_folders(&data)?;
+    restart(&app).map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(patterns: &[&str], recursive: bool) -> WatchFolder {
+        WatchFolder {
+            id: "f".to_string(),
+            path: "/inbox".to_string(),
+            target: WatchTarget::History,
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            max_bytes: 1024,
+            recursive,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_accepts() {
+        let flat = folder(&["*.md", "*.txt"], false);
+        assert!(accepts(&flat, Path::new("/inbox/notes.md"), 10));
+        assert!(!accepts(&flat, Path::new("/inbox/photo.png"), 10));
+        assert!(!accepts(&flat, Path::new("/inbox/.notes.md.swp"), 10));
+        assert!(!accepts(&flat, Path::new("/inbox/huge.md"), 4096));
+        assert!(!accepts(&flat, Path::new("/inbox/empty.md"), 0));
+        assert!(!accepts(&flat, Path::new("/inbox/sub/notes.md"), 10));
+
+        let deep = folder(&[], true);
+        assert!(accepts(&deep, Path::new("/inbox/sub/anything.rs"), 10));
+        assert!(!accepts(&deep, Path::new("/elsewhere/anything.rs"), 10));
+    }
+
+    #[test]
+    fn test_language_and_patterns() {
+        assert_eq!(language_for_path(Path::new("/a/main.rs")), Some("rust"));
+        assert_eq!(language_for_path(Path::new("/a/Notes.MD")), Some("markdown"));
+        assert_eq!(language_for_path(Path::new("/a/README")), None);
+
+        assert!(validate_patterns(&["*.md".to_string(), "notes-??.txt".to_string()]).is_ok());
+        assert!(validate_patterns(&["[".to_string()]).is_err());
+    }
+}
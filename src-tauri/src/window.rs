@@ -5,10 +5,16 @@ use tauri_nspanel::{
     WebviewWindowExt as WebviewPanelExt,
 };
 use thiserror::Error;
-use crate::storage::{load_settings, get_position_for_monitor, save_position_for_monitor, WindowPosition};
+use crate::storage::{load_settings, get_position_for_monitor, save_position_for_monitor, WindowPosition, WindowPreset};
+use crate::panel_animation;
+use crate::storage::{PanelAnimation, PanelPlacement};
+use crate::window_presets::{preset_frame, Frame};
 
 pub const MAIN_WINDOW_LABEL: &str = "main";
 
+/// How far the panel travels in a slide animation, in points
+const SLIDE_DISTANCE: f64 = 24.0;
+
 /// Global flag to prevent panel from hiding during dialog operations
 pub static DIALOG_OPEN: AtomicBool = AtomicBool::new(false);
 
@@ -167,7 +173,10 @@ pub trait WebviewWindowExt<R: Runtime> {
     #[allow(dead_code)]
     fn center_at_cursor_monitor(&self) -> tauri::Result<()>;
     fn move_to_cursor_monitor(&self) -> tauri::Result<()>;
+    fn move_to_placement_monitor(&self) -> tauri::Result<()>;
     fn move_to_monitor_animated(&self, monitor_name: &str) -> tauri::Result<()>;
+    fn set_frame_animated(&self, frame: NSRect) -> tauri::Result<()>;
+    fn apply_size_preset(&self, preset: &WindowPreset) -> tauri::Result<()>;
     fn save_position_for_current_monitor(&self, monitor_name: &str) -> tauri::Result<()>;
     fn update_panel_behavior(&self, sticky_mode: bool) -> tauri::Result<()>;
 }
@@ -273,7 +282,7 @@ impl<R: Runtime> WebviewWindowExt<R> for WebviewWindow<R> {
                                 }
                             }
 
-                            panel.hide();
+                            hide_panel_animated(&app_handle3, &panel);
                             let _ = app_handle3.emit("panel-hidden", ());
                         }
                     }
@@ -364,13 +373,58 @@ impl<R: Runtime> WebviewWindowExt<R> for WebviewWindow<R> {
         Ok(())
     }
 
+    /// Move window to the display picked by settings.panel_placement, restoring saved
+    /// position if available. Falls back to the cursor's monitor when the active window's
+    /// display can't be found.
+    fn move_to_placement_monitor(&self) -> tauri::Result<()> {
+        let placement = load_settings().map(|s| s.panel_placement).unwrap_or_default();
+        if placement == PanelPlacement::Cursor {
+            return self.move_to_cursor_monitor();
+        }
+        let Some((monitor_name, screen_frame)) = active_window_screen() else {
+            log::info!("Active window's display not found, using the cursor's");
+            return self.move_to_cursor_monitor();
+        };
+
+        let panel = self
+            .get_webview_panel(self.label())
+            .map_err(|_| TauriError::Anyhow(Error::PanelNotFound(self.label().into()).into()))?;
+
+        let panel = panel.as_panel();
+        let panel_frame = panel.frame();
+
+        let rect = if let Some(saved_pos) = get_position_for_monitor(&monitor_name) {
+            log::info!("Restoring saved position for monitor: {}", monitor_name);
+            NSRect {
+                origin: NSPoint {
+                    x: saved_pos.x as f64,
+                    y: saved_pos.y as f64,
+                },
+                size: NSSize {
+                    width: saved_pos.width as f64,
+                    height: saved_pos.height as f64,
+                },
+            }
+        } else {
+            log::info!("No saved position for {}, centering", monitor_name);
+            NSRect {
+                origin: NSPoint {
+                    x: (screen_frame.origin.x + (screen_frame.size.width / 2.0))
+                        - (panel_frame.size.width / 2.0),
+                    y: (screen_frame.origin.y + (screen_frame.size.height / 2.0))
+                        - (panel_frame.size.height / 2.0),
+                },
+                size: panel_frame.size,
+            }
+        };
+
+        panel.setFrame_display(rect, true);
+        Ok(())
+    }
+
     /// Move window to a specific monitor with animation, restoring saved position
     /// Note: This is called when cursor has already moved to the target monitor
-    #[allow(deprecated)]
     fn move_to_monitor_animated(&self, monitor_name: &str) -> tauri::Result<()> {
-        use cocoa::base::id;
-        use objc::{class, msg_send, sel, sel_impl};
-
         // Get the monitor where cursor currently is (should be the target)
         let target_monitor = monitor::get_monitor_with_cursor()
             .ok_or(TauriError::Anyhow(Error::MonitorNotFound.into()))?;
@@ -413,6 +467,21 @@ impl<R: Runtime> WebviewWindowExt<R> for WebviewWindow<R> {
             }
         };
 
+        self.set_frame_animated(target_rect)
+    }
+
+    /// Animate the panel to a new frame over 0.2s
+    #[allow(deprecated)]
+    fn set_frame_animated(&self, frame: NSRect) -> tauri::Result<()> {
+        use cocoa::base::id;
+        use objc::{class, msg_send, sel, sel_impl};
+
+        let panel = self
+            .get_webview_panel(self.label())
+            .map_err(|_| TauriError::Anyhow(Error::PanelNotFound(self.label().into()).into()))?;
+
+        let ns_panel = panel.as_panel();
+
         // Animate the frame change using NSWindow's animator
         unsafe {
             // Get the raw NSPanel pointer
@@ -425,7 +494,7 @@ impl<R: Runtime> WebviewWindowExt<R> for WebviewWindow<R> {
 
             // Get animator proxy and set frame
             let animator: id = msg_send![raw_panel, animator];
-            let _: () = msg_send![animator, setFrame: target_rect display: true];
+            let _: () = msg_send![animator, setFrame: frame display: true];
 
             let _: () = msg_send![class!(NSAnimationContext), endGrouping];
         }
@@ -433,6 +502,51 @@ impl<R: Runtime> WebviewWindowExt<R> for WebviewWindow<R> {
         Ok(())
     }
 
+    /// Resize to a window preset within the visible area of the panel's screen
+    /// (below the menu bar, beside the Dock), animated
+    #[allow(deprecated)]
+    fn apply_size_preset(&self, preset: &WindowPreset) -> tauri::Result<()> {
+        use cocoa::base::id;
+        use objc::{msg_send, sel, sel_impl};
+
+        let panel = self
+            .get_webview_panel(self.label())
+            .map_err(|_| TauriError::Anyhow(Error::PanelNotFound(self.label().into()).into()))?;
+
+        let ns_panel = panel.as_panel();
+        let panel_frame = ns_panel.frame();
+
+        let visible: cocoa::foundation::NSRect = unsafe {
+            let raw_panel: id = std::mem::transmute_copy(&ns_panel);
+            let screen: id = msg_send![raw_panel, screen];
+            if screen.is_null() {
+                return Err(TauriError::Anyhow(Error::MonitorNotFound.into()));
+            }
+            msg_send![screen, visibleFrame]
+        };
+
+        let target = preset_frame(
+            preset,
+            Frame {
+                x: visible.origin.x,
+                y: visible.origin.y,
+                width: visible.size.width,
+                height: visible.size.height,
+            },
+            Frame {
+                x: panel_frame.origin.x,
+                y: panel_frame.origin.y,
+                width: panel_frame.size.width,
+                height: panel_frame.size.height,
+            },
+        );
+
+        self.set_frame_animated(NSRect {
+            origin: NSPoint { x: target.x, y: target.y },
+            size: NSSize { width: target.width, height: target.height },
+        })
+    }
+
     /// Save the current window position for a specific monitor
     fn save_position_for_current_monitor(&self, monitor_name: &str) -> tauri::Result<()> {
         let panel = self
@@ -489,6 +603,104 @@ impl<R: Runtime> WebviewWindowExt<R> for WebviewWindow<R> {
     }
 }
 
+/// Show the panel and make it key, playing the configured show animation
+#[allow(deprecated)]
+pub fn show_panel_animated<R: Runtime>(panel: &PanelHandle<R>) {
+    use cocoa::base::id;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    panel_animation::begin_show();
+    let animation = panel_animation::show_animation();
+    let ns_panel = panel.as_panel();
+    let raw_panel: id = unsafe { std::mem::transmute_copy(&ns_panel) };
+
+    if animation.is_none() {
+        unsafe {
+            let _: () = msg_send![raw_panel, setAlphaValue: 1.0_f64];
+        }
+        panel.show_and_make_key();
+        return;
+    }
+
+    let frame = ns_panel.frame();
+    let slide = animation.style == PanelAnimation::SlideFromTop;
+    unsafe {
+        let _: () = msg_send![raw_panel, setAlphaValue: 0.0_f64];
+        if slide {
+            let start = NSRect {
+                origin: NSPoint { x: frame.origin.x, y: frame.origin.y + SLIDE_DISTANCE },
+                size: frame.size,
+            };
+            let _: () = msg_send![raw_panel, setFrame: start display: false];
+        }
+    }
+
+    panel.show_and_make_key();
+
+    unsafe {
+        let _: () = msg_send![class!(NSAnimationContext), beginGrouping];
+        let context: id = msg_send![class!(NSAnimationContext), currentContext];
+        let _: () = msg_send![context, setDuration: animation.duration().as_secs_f64()];
+
+        let animator: id = msg_send![raw_panel, animator];
+        let _: () = msg_send![animator, setAlphaValue: 1.0_f64];
+        if slide {
+            let _: () = msg_send![animator, setFrame: frame display: true];
+        }
+
+        let _: () = msg_send![class!(NSAnimationContext), endGrouping];
+    }
+}
+
+/// Hide the panel, playing the configured hide animation first. Callers save the
+/// panel's position beforehand; the frame is put back once it's hidden.
+#[allow(deprecated)]
+pub fn hide_panel_animated<R: Runtime>(app: &tauri::AppHandle<R>, panel: &PanelHandle<R>) {
+    use cocoa::base::id;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let animation = panel_animation::hide_animation();
+    if animation.is_none() {
+        panel.hide();
+        return;
+    }
+
+    let ns_panel = panel.as_panel();
+    let frame = ns_panel.frame();
+    unsafe {
+        let raw_panel: id = std::mem::transmute_copy(&ns_panel);
+
+        let _: () = msg_send![class!(NSAnimationContext), beginGrouping];
+        let context: id = msg_send![class!(NSAnimationContext), currentContext];
+        let _: () = msg_send![context, setDuration: animation.duration().as_secs_f64()];
+
+        let animator: id = msg_send![raw_panel, animator];
+        let _: () = msg_send![animator, setAlphaValue: 0.0_f64];
+        if animation.style == PanelAnimation::SlideFromTop {
+            let end = NSRect {
+                origin: NSPoint { x: frame.origin.x, y: frame.origin.y + SLIDE_DISTANCE },
+                size: frame.size,
+            };
+            let _: () = msg_send![animator, setFrame: end display: true];
+        }
+
+        let _: () = msg_send![class!(NSAnimationContext), endGrouping];
+    }
+
+    let app_for_hide = app.clone();
+    panel_animation::hide_after(app, animation, move || {
+        if let Ok(panel) = app_for_hide.get_webview_panel(MAIN_WINDOW_LABEL) {
+            panel.hide();
+            let ns_panel = panel.as_panel();
+            unsafe {
+                let raw_panel: id = std::mem::transmute_copy(&ns_panel);
+                let _: () = msg_send![raw_panel, setFrame: frame display: false];
+                let _: () = msg_send![raw_panel, setAlphaValue: 1.0_f64];
+            }
+        }
+    });
+}
+
 /// Get the name of the monitor where the cursor is located
 #[allow(dead_code)]
 pub fn get_cursor_monitor_name() -> Option<String> {
@@ -499,8 +711,14 @@ pub fn get_cursor_monitor_name() -> Option<String> {
 /// Get the name of the monitor that contains a given point
 /// Uses macOS NSScreen APIs to find which screen contains the point
 #[cfg(target_os = "macos")]
-#[allow(deprecated)]
 pub fn get_monitor_name_for_point(x: f64, y: f64) -> Option<String> {
+    screen_containing_point(x, y).map(|(name, _)| name)
+}
+
+/// The name and frame of the screen containing a point, in AppKit coordinates
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+fn screen_containing_point(x: f64, y: f64) -> Option<(String, cocoa::foundation::NSRect)> {
     use cocoa::base::id;
     use cocoa::foundation::{NSPoint, NSRect};
     use objc::{class, msg_send, sel, sel_impl};
@@ -534,7 +752,7 @@ pub fn get_monitor_name_for_point(x: f64, y: f64) -> Option<String> {
                 if !name.is_null() {
                     let name_str: *const i8 = msg_send![name, UTF8String];
                     if !name_str.is_null() {
-                        return Some(std::ffi::CStr::from_ptr(name_str).to_string_lossy().into_owned());
+                        return Some((std::ffi::CStr::from_ptr(name_str).to_string_lossy().into_owned(), frame));
                     }
                 }
             }
@@ -544,6 +762,92 @@ pub fn get_monitor_name_for_point(x: f64, y: f64) -> Option<String> {
     }
 }
 
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    // Declared here rather than going through core_graphics::window::copy_window_info so
+    // the result can be read as an NSArray (toll-free bridged) like the rest of this file
+    fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> cocoa::base::id;
+}
+
+/// The screen showing the frontmost app's front window: the first normal-level window
+/// owned by that app in the on-screen window list, which is ordered front to back. None
+/// when Wingman itself is frontmost or the app has no windows on screen.
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+pub fn active_window_screen() -> Option<(String, cocoa::foundation::NSRect)> {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::{NSAutoreleasePool, NSRect, NSString};
+    use core_graphics::window::{kCGNullWindowID, kCGWindowListExcludeDesktopElements, kCGWindowListOptionOnScreenOnly};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let frontmost: id = msg_send![workspace, frontmostApplication];
+        if frontmost.is_null() {
+            return None;
+        }
+        let pid: i32 = msg_send![frontmost, processIdentifier];
+        if pid as u32 == std::process::id() {
+            return None;
+        }
+
+        let windows = CGWindowListCopyWindowInfo(
+            kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
+            kCGNullWindowID,
+        );
+        if windows.is_null() {
+            return None;
+        }
+
+        let key = |name: &str| -> id { NSString::alloc(nil).init_str(name).autorelease() };
+        let number = |dict: id, name: &str| -> Option<f64> {
+            let value: id = msg_send![dict, objectForKey: key(name)];
+            if value.is_null() {
+                None
+            } else {
+                Some(msg_send![value, doubleValue])
+            }
+        };
+
+        // Window bounds are in Core Graphics coordinates: origin at the top left of the
+        // primary screen, y growing down
+        let mut center = None;
+        let count: usize = msg_send![windows, count];
+        for i in 0..count {
+            let info: id = msg_send![windows, objectAtIndex: i];
+            if number(info, "kCGWindowOwnerPID") != Some(pid as f64) || number(info, "kCGWindowLayer") != Some(0.0) {
+                continue;
+            }
+            let bounds: id = msg_send![info, objectForKey: key("kCGWindowBounds")];
+            if bounds.is_null() {
+                continue;
+            }
+            if let (Some(x), Some(y), Some(width), Some(height)) = (
+                number(bounds, "X"),
+                number(bounds, "Y"),
+                number(bounds, "Width"),
+                number(bounds, "Height"),
+            ) {
+                center = Some((x + width / 2.0, y + height / 2.0));
+                break;
+            }
+        }
+        let () = msg_send![windows, release];
+
+        // Flip into AppKit coordinates, where y grows up from the primary screen's bottom
+        let (x, y) = center?;
+        let screens: id = msg_send![class!(NSScreen), screens];
+        let count: usize = msg_send![screens, count];
+        if count == 0 {
+            return None;
+        }
+        let primary: id = msg_send![screens, objectAtIndex: 0usize];
+        let primary_frame: NSRect = msg_send![primary, frame];
+        screen_containing_point(x, primary_frame.size.height - y)
+    }
+}
+
 #[cfg(not(target_os = "macos"))]
 pub fn get_monitor_name_for_point(_x: f64, _y: f64) -> Option<String> {
     None
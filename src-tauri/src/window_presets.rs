@@ -0,0 +1,195 @@
+// Window size presets. settings.window_presets lists them (compact, normal, tall and a
+// full-height strip on the right edge by default); cycle_window_preset steps through them
+// and can be bound to a global shortcut with settings.window_preset_hotkey. On macOS the
+// frame change is animated like the sticky-mode monitor move (see window.rs); elsewhere
+// the window is resized in place.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::error::AppError;
+use crate::storage::{load_settings, WindowAnchor, WindowPreset};
+
+/// Index of the preset applied last; usize::MAX until one is, so cycling starts at the first
+static CURRENT_PRESET: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Shortcut currently registered for cycling, so it can be swapped out
+static PRESET_HOTKEY: Mutex<Option<String>> = Mutex::new(None);
+
+/// A window frame or screen area in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Where `preset` puts a window currently at `current` on a screen whose usable area is
+/// `area`. Nothing depends on which way y grows, so AppKit and top-left frames both work.
+pub fn preset_frame(preset: &WindowPreset, area: Frame, current: Frame) -> Frame {
+    let width = (preset.width as f64).min(area.width);
+    let height = preset.height.map_or(area.height, |height| (height as f64).min(area.height));
+    let (x, y) = match preset.anchor {
+        WindowAnchor::Center => (
+            current.x + (current.width - width) / 2.0,
+            current.y + (current.height - height) / 2.0,
+        ),
+        WindowAnchor::RightEdge => (area.x + area.width - width, area.y + (area.height - height) / 2.0),
+    };
+    Frame {
+        x: x.clamp(area.x, area.x + area.width - width),
+        y: y.clamp(area.y, area.y + area.height - height),
+        width,
+        height,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn resize_window(app: &AppHandle, preset: &WindowPreset) -> Result<(), AppError> {
+    use crate::window::{WebviewWindowExt, MAIN_WINDOW_LABEL};
+    use tauri::Manager;
+
+    let app_inner = app.clone();
+    let preset = preset.clone();
+    // AppKit frames can only be changed on the main thread
+    app.run_on_main_thread(move || {
+        if let Some(window) = app_inner.get_webview_window(MAIN_WINDOW_LABEL) {
+            if let Err(e) = window.apply_size_preset(&preset) {
+                log::warn!("Failed to apply window preset {}: {:?}", preset.name, e);
+            }
+        }
+    })?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn resize_window(app: &AppHandle, preset: &WindowPreset) -> Result<(), AppError> {
+    use tauri::{LogicalPosition, LogicalSize, Manager};
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| AppError::not_found("Main window not found"))?;
+    let monitor = window
+        .current_monitor()?
+        .ok_or_else(|| AppError::not_found("The window isn't on a screen"))?;
+    let scale = monitor.scale_factor();
+    let area_position = monitor.work_area().position.to_logical::<f64>(scale);
+    let area_size = monitor.work_area().size.to_logical::<f64>(scale);
+    let position = window.outer_position()?.to_logical::<f64>(scale);
+    let size = window.outer_size()?.to_logical::<f64>(scale);
+
+    let frame = preset_frame(
+        preset,
+        Frame {
+            x: area_position.x,
+            y: area_position.y,
+            width: area_size.width,
+            height: area_size.height,
+        },
+        Frame {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        },
+    );
+    window.set_size(LogicalSize::new(frame.width, frame.height))?;
+    window.set_position(LogicalPosition::new(frame.x, frame.y))?;
+    Ok(())
+}
+
+fn apply_preset_at(app: &AppHandle, presets: &[WindowPreset], index: usize) -> Result<WindowPreset, AppError> {
+    let preset = presets
+        .get(index)
+        .cloned()
+        .ok_or_else(|| AppError::not_found("No window presets are configured"))?;
+    resize_window(app, &preset)?;
+    CURRENT_PRESET.store(index, Ordering::SeqCst);
+    app.emit("window-preset-changed", &preset).ok();
+    Ok(preset)
+}
+
+fn cycle(app: &AppHandle) -> Result<WindowPreset, AppError> {
+    let presets = load_settings()?.window_presets;
+    if presets.is_empty() {
+        return Err(AppError::not_found("No window presets are configured"));
+    }
+    let next = CURRENT_PRESET.load(Ordering::SeqCst).wrapping_add(1) % presets.len();
+    apply_preset_at(app, &presets, next)
+}
+
+/// Bind (or with `None`, unbind) the shortcut that cycles window presets
+pub fn register_hotkey(app: &AppHandle, shortcut: Option<&str>) -> Result<(), String> {
+    let mut current = PRESET_HOTKEY.lock().map_err(|e| e.to_string())?;
+    if current.as_deref() == shortcut {
+        return Ok(());
+    }
+    if let Some(previous) = current.take() {
+        app.global_shortcut().unregister(previous.as_str()).ok();
+    }
+    if let Some(shortcut) = shortcut.filter(|s| !s.trim().is_empty()) {
+        app.global_shortcut()
+            .on_shortcut(shortcut, |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    if let Err(e) = cycle(app) {
+                        log::warn!("Failed to cycle window presets: {}", e);
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to register window preset hotkey: {}", e))?;
+        *current = Some(shortcut.to_string());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Resize the window to the next preset, wrapping around; returns the preset applied
+#[tauri::command]
+pub fn cycle_window_preset(app: AppHandle) -> Result<WindowPreset, AppError> {
+    cycle(&app)
+}
+
+/// Resize the window to the preset called `name`
+#[tauri::command]
+pub fn apply_window_preset(app: AppHandle, name: String) -> Result<WindowPreset, AppError> {
+    let presets = load_settings()?.window_presets;
+    let index = presets
+        .iter()
+        .position(|preset| preset.name.eq_ignore_ascii_case(&name))
+        .ok_or_else(|| AppError::not_found(format!("No window preset named \"{}\"", name)))?;
+    apply_preset_at(&app, &presets, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_frame() {
+        let area = Frame { x: 0.0, y: 25.0, width: 1440.0, height: 875.0 };
+        let current = Frame { x: 100.0, y: 100.0, width: 800.0, height: 500.0 };
+        let preset = |width, height, anchor| WindowPreset {
+            name: String::new(),
+            width,
+            height,
+            anchor,
+        };
+
+        // Shrinks around the current center
+        let compact = preset_frame(&preset(720, Some(140), WindowAnchor::Center), area, current);
+        assert_eq!(compact, Frame { x: 140.0, y: 280.0, width: 720.0, height: 140.0 });
+
+        // Grows but stays on screen
+        let tall = preset_frame(&preset(800, Some(2000), WindowAnchor::Center), area, current);
+        assert_eq!(tall, Frame { x: 100.0, y: 25.0, width: 800.0, height: 875.0 });
+
+        let right = preset_frame(&preset(520, None, WindowAnchor::RightEdge), area, current);
+        assert_eq!(right, Frame { x: 920.0, y: 25.0, width: 520.0, height: 875.0 });
+    }
+}
@@ -31,6 +31,7 @@ import {
 import { useGlobalHotkey, useKeyboardShortcuts, useSettings, useLicense } from './hooks';
 import { useEditorStore } from './stores/editorStore';
 import { usePremiumStore } from './stores/premiumStore';
+import type { PanelAnimation } from './types';
 
 function App() {
   const { settings } = useSettings();
@@ -108,6 +109,22 @@ function App() {
     };
   }, []);
 
+  // Play the show animation in the page where the window itself can't be animated
+  // (macOS animates the panel natively and never sends this)
+  useEffect(() => {
+    const unlisten = listen<{ phase: 'show'; style: PanelAnimation; duration_ms: number }>('panel-animation', (event) => {
+      const { style, duration_ms } = event.payload;
+      const keyframes = style === 'slide_from_top'
+        ? [{ opacity: 0, transform: 'translateY(-24px)' }, { opacity: 1, transform: 'none' }]
+        : [{ opacity: 0 }, { opacity: 1 }];
+      document.documentElement.animate(keyframes, { duration: duration_ms, easing: 'ease-out' });
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   // Apply theme mode - controls vibrancy material, UI styling, and theme-specific colors
   useEffect(() => {
     const lightThemes = ['light', 'solarized-light'];
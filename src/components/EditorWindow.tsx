@@ -61,12 +61,17 @@ import {
     AILoadingOverlay,
     searchPanelExtension,
     TipsBar,
+    SuggestionBar,
+    canRunSuggestion,
+    runSuggestedCommand,
+    EDITOR_HANDLED,
 } from './editor';
 
-import type {ObsidianResult, GistResult, AIPreset} from '../types';
+import type {ObsidianResult, GistResult, AIPreset, SuggestedAction} from '../types';
 import {maskSecrets} from '../utils/maskSecrets';
 import {detectContent} from '../detectors';
 import type {DetectorResult, DetectorAction, DetectorActionResult} from '../detectors';
+import {errorMessage} from '../lib/appError';
 
 // Parse error line/column from formatter error messages
 function parseErrorPosition(errorMsg: string): { line: number; column: number } | null {
@@ -152,6 +157,7 @@ export function EditorWindow() {
     const [selectedPresetId, setSelectedPresetId] = useState<string | null>(null);
     const [selectedCustomPromptId, setSelectedCustomPromptId] = useState<string | null>(null);
     const [contextDetection, setContextDetection] = useState<DetectorResult | null>(null);
+    const [suggestedActions, setSuggestedActions] = useState<SuggestedAction[]>([]);
     const contextDismissedRef = useRef<string | null>(null); // tracks dismissed detector id
     const pasteOrDropRef = useRef(false); // tracks if content change came from paste/drag
 
@@ -189,7 +195,15 @@ export function EditorWindow() {
         snippetEditSession,
         clearSnippetEditSession,
     } = useEditorStore();
-    const { snippets, updateSnippet, syncSnippetToGitHub, addSnippetLinkedToGist } = useSnippetsStore();
+    const {
+        snippets,
+        updateSnippet,
+        forceUpdateSnippet,
+        conflict: snippetConflict,
+        dismissConflict,
+        syncSnippetToGitHub,
+        addSnippetLinkedToGist,
+    } = useSnippetsStore();
 
     const {settings, updateSettings} = useSettingsStore();
     const {tier, devTierOverride} = useLicenseStore();
@@ -351,6 +365,28 @@ export function EditorWindow() {
         return () => clearTimeout(timer);
     }, [content, settings?.auto_detect_language, language, setLanguage]);
 
+    // Action bar suggestions for the whole text, ranked by the backend from past use
+    useEffect(() => {
+        if (settings?.show_intelligent_suggestions === false || !content.trim()) {
+            setSuggestedActions([]);
+            return;
+        }
+        let cancelled = false;
+        const timer = setTimeout(() => {
+            invoke<SuggestedAction[]>('get_suggested_actions', { text: content, language })
+                .then(actions => {
+                    if (!cancelled) setSuggestedActions(actions.filter(action => canRunSuggestion(action.id)));
+                })
+                .catch(() => {
+                    if (!cancelled) setSuggestedActions([]);
+                });
+        }, 500);
+        return () => {
+            cancelled = true;
+            clearTimeout(timer);
+        };
+    }, [content, language, settings?.show_intelligent_suggestions]);
+
     // Auto-dismiss context detection after 8 seconds
     useEffect(() => {
         if (contextDetection) {
@@ -565,7 +601,7 @@ export function EditorWindow() {
             });
             setValidationToast({ type: 'success', message: 'File copied to clipboard' });
         } catch (error) {
-            setValidationToast({ type: 'error', message: `Failed to copy as file: ${error}` });
+            setValidationToast({ type: 'error', message: `Failed to copy as file: ${errorMessage(error)}` });
         }
     }, [content, language, setValidationToast]);
 
@@ -580,12 +616,14 @@ export function EditorWindow() {
     const handleSaveSnippetEdit = useCallback(async (syncToGitHub = false) => {
         if (!snippetEditSession) return;
         const existingSnippet = snippets.find(s => s.id === snippetEditSession.snippetId);
-        await updateSnippet(
+        const saved = await updateSnippet(
             snippetEditSession.snippetId,
             snippetEditSession.snippetName,
             content,
             existingSnippet?.tags || []
         );
+        // A conflict keeps the session open with the choice to overwrite
+        if (!saved) return;
         if (syncToGitHub && existingSnippet?.github_gist_id) {
             await syncSnippetToGitHub(snippetEditSession.snippetId);
         }
@@ -601,6 +639,13 @@ export function EditorWindow() {
         clearSnippetEditSession,
     ]);
 
+    // Save the edit over a copy that changed since the session started
+    const handleOverwriteSnippet = useCallback(async () => {
+        if (!(await forceUpdateSnippet())) return;
+        setValidationToast({ type: 'success', message: 'Snippet updated' });
+        clearSnippetEditSession();
+    }, [forceUpdateSnippet, setValidationToast, clearSnippetEditSession]);
+
     // Format handler
     const handleFormat = useCallback(async () => {
         const view = viewRef.current;
@@ -661,7 +706,7 @@ export function EditorWindow() {
                 }
             }
         } catch (error) {
-            const errorMsg = String(error);
+            const errorMsg = errorMessage(error);
             setValidationToast({ type: 'error', message: errorMsg });
 
             // Highlight the error position using CodeMirror diagnostics
@@ -737,7 +782,7 @@ export function EditorWindow() {
                 }
             }
         } catch (error) {
-            const errorMsg = String(error);
+            const errorMsg = errorMessage(error);
             setValidationToast({ type: 'error', message: errorMsg });
 
             const errorPos = parseErrorPosition(errorMsg);
@@ -921,6 +966,31 @@ export function EditorWindow() {
         }
     }, [content, setContent, setValidationToast, setLanguage, setContextDetection]);
 
+    // Run an action bar suggestion and count it towards future rankings
+    const handleSuggestedAction = useCallback(async (action: SuggestedAction) => {
+        if (action.locked) {
+            setValidationToast({ type: 'error', message: `${action.title} requires a Pro license` });
+            return;
+        }
+        try {
+            if (action.id === 'format_code') {
+                await handleFormat();
+            } else if (action.id === 'minify_code') {
+                await handleMinify();
+            } else if (EDITOR_HANDLED.has(action.id)) {
+                const detectorAction = detectContent(content)?.actions.find(a => a.id === action.id);
+                if (!detectorAction) return;
+                handleContextAction(detectorAction);
+            } else {
+                const text = await runSuggestedCommand(action.id, content);
+                if (text !== null && text !== content) setContent(text);
+            }
+            await invoke('record_action_use', { action: action.id, kind: action.kind });
+        } catch (error) {
+            setValidationToast({ type: 'error', message: `${action.title} failed: ${errorMessage(error)}` });
+        }
+    }, [content, setContent, handleFormat, handleMinify, handleContextAction, setValidationToast]);
+
     // Handle paste for file attachments
     const handlePaste = useCallback(async (e: ClipboardEvent) => {
         const items = e.clipboardData?.items;
@@ -1221,18 +1291,34 @@ export function EditorWindow() {
                         <p className="text-xs font-medium text-[var(--ui-text)] truncate">
                             Editing snippet: {snippetEditSession.snippetName}
                         </p>
-                        <p className="text-[10px] text-[var(--ui-text-muted)]">
-                            Save updates to apply changes, or cancel to keep text in the editor without snippet mode.
-                        </p>
+                        {snippetConflict?.id === snippetEditSession.snippetId ? (
+                            <p className="text-[10px] text-amber-400">
+                                This snippet changed since you opened it. Overwrite it with your edit, or cancel to keep the saved copy.
+                            </p>
+                        ) : (
+                            <p className="text-[10px] text-[var(--ui-text-muted)]">
+                                Save updates to apply changes, or cancel to keep text in the editor without snippet mode.
+                            </p>
+                        )}
                     </div>
                     <div className="flex items-center gap-2 flex-shrink-0">
-                        <button
-                            onClick={() => handleSaveSnippetEdit(false)}
-                            className="text-xs px-2.5 py-1.5 rounded-md bg-[var(--ui-accent)] text-white hover:brightness-110 flex items-center gap-1"
-                        >
-                            <Check size={12} />
-                            Save
-                        </button>
+                        {snippetConflict?.id === snippetEditSession.snippetId ? (
+                            <button
+                                onClick={handleOverwriteSnippet}
+                                className="text-xs px-2.5 py-1.5 rounded-md bg-[var(--ui-accent)] text-white hover:brightness-110 flex items-center gap-1"
+                            >
+                                <Check size={12} />
+                                Overwrite Anyway
+                            </button>
+                        ) : (
+                            <button
+                                onClick={() => handleSaveSnippetEdit(false)}
+                                className="text-xs px-2.5 py-1.5 rounded-md bg-[var(--ui-accent)] text-white hover:brightness-110 flex items-center gap-1"
+                            >
+                                <Check size={12} />
+                                Save
+                            </button>
+                        )}
                         {snippets.find(s => s.id === snippetEditSession.snippetId)?.github_gist_id && (
                             <button
                                 onClick={() => handleSaveSnippetEdit(true)}
@@ -1243,7 +1329,10 @@ export function EditorWindow() {
                             </button>
                         )}
                         <button
-                            onClick={clearSnippetEditSession}
+                            onClick={() => {
+                                dismissConflict();
+                                clearSnippetEditSession();
+                            }}
                             className="text-xs px-2.5 py-1.5 rounded-md bg-[var(--ui-surface)] border border-[var(--ui-border)] text-[var(--ui-text-muted)] hover:text-[var(--ui-text)] hover:bg-[var(--ui-hover)] flex items-center gap-1"
                         >
                             <X size={12} />
@@ -1281,6 +1370,8 @@ export function EditorWindow() {
                 />
             </div>
 
+            <SuggestionBar suggestions={suggestedActions} onRun={handleSuggestedAction} />
+
             <AttachmentsBar
                 images={images}
                 removeImage={removeImage}
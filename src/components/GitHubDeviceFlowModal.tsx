@@ -4,6 +4,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { useGitHubStore } from '../stores/githubStore';
 import { Github, ExternalLink, X, Loader2, CheckCircle, Copy, Check } from 'lucide-react';
 import type { DeviceFlowStart } from '../types';
+import { errorCode, errorMessage, isAppError } from '../lib/appError';
 
 interface GitHubDeviceFlowModalProps {
   flowStart: DeviceFlowStart;
@@ -91,16 +92,16 @@ export function GitHubDeviceFlowModal({
         setPolling(false);
         scheduleNextPoll(currentIntervalMs.current);
       } catch (err) {
-        const errorStr = String(err);
+        const errorStr = errorMessage(err);
+        const code = errorCode(err);
         console.error('[Modal] Poll error caught:', errorStr);
 
         // Check if it's a slow_down error - if so, use the interval from GitHub
-        if (errorStr.includes('slow_down')) {
-          // Parse interval from error message (format: "slow_down:N")
-          const match = errorStr.match(/slow_down:(\d+)/);
-          console.log('[Modal] slow_down detected, regex match:', match);
-          if (match) {
-            const newInterval = (parseInt(match[1], 10) + 1) * 1000;
+        if (code === 'quota_exceeded') {
+          const details = isAppError(err) ? (err.details as { interval?: number } | undefined) : undefined;
+          console.log('[Modal] slow_down detected, interval:', details?.interval);
+          if (typeof details?.interval === 'number') {
+            const newInterval = (details.interval + 1) * 1000;
             console.log('[Modal] Setting new interval from GitHub:', newInterval / 1000, 's');
             currentIntervalMs.current = newInterval;
           } else {
@@ -118,7 +119,7 @@ export function GitHubDeviceFlowModal({
         setPolling(false);
 
         // Schedule next poll even on error (except for fatal errors)
-        if (!errorStr.includes('expired') && !errorStr.includes('timeout')) {
+        if (code !== 'unauthenticated') {
           scheduleNextPoll(currentIntervalMs.current);
         } else {
           setError(errorStr);
@@ -299,14 +300,6 @@ export function GitHubDeviceFlowModal({
                   className="mt-4 bg-red-500/10 border border-red-500/30 rounded-lg p-3 text-red-400 text-sm"
                 >
                   <p className="mb-2">{error}</p>
-                  {(error.includes('timeout') || error.includes('connection')) && (
-                    <button
-                      onClick={() => setError(null)}
-                      className="text-xs text-red-300 hover:text-red-200 underline"
-                    >
-                      Try again
-                    </button>
-                  )}
                 </motion.div>
               )}
 
@@ -1,6 +1,5 @@
 import {useState, useMemo, useRef, useEffect, useCallback} from 'react';
 import {invoke} from '@tauri-apps/api/core';
-import {formatCode, minifyCode} from '../lib/formatters';
 import {useEditorStore} from '../stores/editorStore';
 import {useLicenseStore} from '../stores/licenseStore';
 import {useClipboardStore} from '../stores/clipboardStore';
@@ -9,228 +8,64 @@ import {useDiffStore} from '../stores/diffStore';
 import {useSettingsStore} from '../stores/settingsStore';
 import {useCustomTransformationsStore} from '../stores/customTransformationsStore';
 import {useTransformationChainsStore} from '../stores/transformationChainsStore';
+import {errorMessage} from '../lib/appError';
+import type {ActionInfo, ActionOutput, ActionResult} from '../types';
 
 type TabType = 'clipboard' | 'actions';
 
 interface ActionSection {
     title: string;
     actions: Action[];
-    proFeature?: 'json_xml_formatting' | 'encode_decode';
+    proFeature?: string;
 }
 
 interface Action {
     id: string;
     label: string;
     description: string;
+    keywords?: string[];
     requiresInput?: boolean;
-    handler?: 'transform' | 'format' | 'encode' | 'generate' | 'custom' | 'chain' | 'validate';
+    handler?: 'registry' | 'custom' | 'chain';
+    output?: ActionOutput;
     section?: string;
-    proFeature?: 'json_xml_formatting' | 'encode_decode' | 'custom_transformations';
+    proFeature?: string;
 }
 
-const actionSections: ActionSection[] = [
-    {
-        title: 'Case Conversion',
-        actions: [
-            {
-                id: 'camelcase',
-                label: 'camelCase',
-                description: 'Convert to camelCase',
-                handler: 'transform',
-                requiresInput: true
-            },
-            {
-                id: 'snakecase',
-                label: 'snake_case',
-                description: 'Convert to snake_case',
-                handler: 'transform',
-                requiresInput: true
-            },
-            {
-                id: 'kebabcase',
-                label: 'kebab-case',
-                description: 'Convert to kebab-case',
-                handler: 'transform',
-                requiresInput: true
-            },
-        ],
-    },
-    {
-        title: 'Timestamps',
-        actions: [
-            {
-                id: 'unix_to_human',
-                label: 'Unix → Human',
-                description: 'Convert Unix timestamp to readable date',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'human_to_unix',
-                label: 'Human → Unix',
-                description: 'Convert datetime to Unix timestamp',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'get_current_timestamp',
-                label: 'Current Timestamp',
-                description: 'Insert current Unix timestamp',
-                handler: 'generate'
-            },
-        ],
-    },
-    {
-        title: 'Encode/Decode',
-        proFeature: 'encode_decode',
-        actions: [
-            {
-                id: 'encode_base64',
-                label: 'Base64 Encode',
-                description: 'Encode text to Base64',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'decode_base64',
-                label: 'Base64 Decode',
-                description: 'Decode Base64 to text',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'encode_url',
-                label: 'URL Encode',
-                description: 'Percent-encode for URLs',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'decode_url',
-                label: 'URL Decode',
-                description: 'Decode percent-encoded text',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'encode_html',
-                label: 'HTML Encode',
-                description: 'Escape HTML entities',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'decode_html',
-                label: 'HTML Decode',
-                description: 'Unescape HTML entities',
-                handler: 'encode',
-                requiresInput: true
-            },
-        ],
-    },
-    {
-        title: 'ID Generators',
-        actions: [
-            {
-                id: 'generate_uuid',
-                label: 'UUID v4',
-                description: 'Random UUID (standard)',
-                handler: 'generate'
-            },
-            {
-                id: 'generate_uuid_v7',
-                label: 'UUID v7',
-                description: 'Time-sortable UUID',
-                handler: 'generate'
-            },
-            {
-                id: 'generate_nanoid',
-                label: 'NanoID',
-                description: 'Compact URL-safe ID (21 chars)',
-                handler: 'generate'
-            },
-            {
-                id: 'generate_short_hash',
-                label: 'Short Hash',
-                description: '8-char random hash',
-                handler: 'generate'
-            },
-            {
-                id: 'generate_prefixed_id',
-                label: 'Prefixed ID (usr_)',
-                description: 'ID with usr_ prefix',
-                handler: 'generate'
-            },
-            {
-                id: 'generate_bulk_uuid',
-                label: 'Bulk UUIDs (10)',
-                description: 'Generate 10 UUIDs at once',
-                handler: 'generate'
-            },
-        ],
-    },
-    {
-        title: 'Lorem Ipsum',
-        actions: [
-            {id: 'lorem_1', label: '1 Paragraph', description: 'Generate 1 paragraph', handler: 'generate'},
-            {id: 'lorem_3', label: '3 Paragraphs', description: 'Generate 3 paragraphs', handler: 'generate'},
-            {id: 'lorem_5', label: '5 Paragraphs', description: 'Generate 5 paragraphs', handler: 'generate'},
-        ],
-    },
-    {
-        title: 'Hash Generators',
-        actions: [
-            {
-                id: 'generate_md5',
-                label: 'MD5 Hash',
-                description: 'Generate MD5 hash of text',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'generate_sha1',
-                label: 'SHA-1 Hash',
-                description: 'Generate SHA-1 hash of text',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'generate_sha256',
-                label: 'SHA-256 Hash',
-                description: 'Generate SHA-256 hash of text',
-                handler: 'encode',
-                requiresInput: true
-            },
-            {
-                id: 'generate_sha512',
-                label: 'SHA-512 Hash',
-                description: 'Generate SHA-512 hash of text',
-                handler: 'encode',
-                requiresInput: true
-            },
-        ],
-    },
-];
-
-// Flatten all static actions with section info for search
-const staticActions: Action[] = actionSections.flatMap(section =>
-    section.actions.map(action => ({
-        ...action,
-        section: section.title,
-        proFeature: section.proFeature,
-    }))
-);
+// Built-in actions come from the backend registry (list_actions), grouped in list order
+function groupActions(infos: ActionInfo[]): ActionSection[] {
+    const sections: ActionSection[] = [];
+    for (const info of infos) {
+        let section = sections.find(s => s.title === info.section);
+        if (!section) {
+            section = {title: info.section, actions: []};
+            sections.push(section);
+        }
+        section.actions.push({
+            id: info.id,
+            label: info.title,
+            description: info.description,
+            keywords: info.keywords,
+            requiresInput: info.needs_input,
+            handler: 'registry',
+            output: info.output,
+            proFeature: info.pro_feature ?? undefined,
+        });
+    }
+    // A section whose actions all need the same Pro feature is gated as a whole
+    for (const section of sections) {
+        const features = new Set(section.actions.map(action => action.proFeature));
+        if (features.size === 1) {
+            section.proFeature = section.actions[0].proFeature;
+        }
+    }
+    return sections;
+}
 
 export function QuickActionsPanel() {
     const {
         setActivePanel,
-        transformText,
         content,
         setContent,
-        language,
-        setLanguage,
-        applyBulletList,
-        applyNumberedList,
         editorView
     } = useEditorStore();
     const { getEffectiveTier } = useLicenseStore();
@@ -278,9 +113,27 @@ export function QuickActionsPanel() {
     const [selectedIndex, setSelectedIndex] = useState(0);
     const searchInputRef = useRef<HTMLInputElement>(null);
     const listRef = useRef<HTMLDivElement>(null);
-    const [expandedSections, setExpandedSections] = useState<Set<string>>(
-        new Set([...actionSections.map(s => s.title), 'Chains', 'Custom', 'Case Conversion', 'Timestamps', 'ID Generators', 'Lorem Ipsum'])
-    );
+    const [expandedSections, setExpandedSections] = useState<Set<string>>(new Set(['Chains', 'Custom']));
+    const [actionSections, setActionSections] = useState<ActionSection[]>([]);
+
+    // Load the built-in actions; re-listed when the tier changes so snippets and locks stay current
+    useEffect(() => {
+        invoke<ActionInfo[]>('list_actions')
+            .then(infos => {
+                const sections = groupActions(infos);
+                setActionSections(sections);
+                setExpandedSections(prev => new Set([...prev, ...sections.map(s => s.title)]));
+            })
+            .catch(err => setError(errorMessage(err)));
+    }, [effectiveTier]);
+
+    const staticActions: Action[] = useMemo(() => actionSections.flatMap(section =>
+        section.actions.map(action => ({
+            ...action,
+            section: section.title,
+            proFeature: section.proFeature ?? action.proFeature,
+        }))
+    ), [actionSections]);
     const [draggedItem, setDraggedItem] = useState<string | null>(null);
 
     // Start clipboard monitoring when panel opens
@@ -341,7 +194,7 @@ export function QuickActionsPanel() {
     // All actions including custom transformations and chains
     const allActions = useMemo(() => {
         return [...staticActions, ...customTransformationActions, ...chainActions];
-    }, [customTransformationActions, chainActions]);
+    }, [staticActions, customTransformationActions, chainActions]);
 
     const filteredActions = useMemo(() => {
         if (!searchQuery.trim()) return null;
@@ -349,7 +202,8 @@ export function QuickActionsPanel() {
         return allActions.filter(
             action =>
                 action.label.toLowerCase().includes(query) ||
-                action.description.toLowerCase().includes(query)
+                action.description.toLowerCase().includes(query) ||
+                action.keywords?.some(keyword => keyword.toLowerCase().includes(query))
         );
     }, [searchQuery, allActions]);
 
@@ -364,10 +218,10 @@ export function QuickActionsPanel() {
             return section.actions.map(action => ({
                 ...action,
                 section: section.title,
-                proFeature: section.proFeature,
+                proFeature: section.proFeature ?? action.proFeature,
             }));
         });
-    }, [filteredActions, expandedSections]);
+    }, [filteredActions, expandedSections, actionSections]);
 
     // Reset selection when search changes
     useEffect(() => {
@@ -557,102 +411,48 @@ export function QuickActionsPanel() {
 
         try {
             switch (action.handler) {
-                case 'transform':
-                    // Special handling for lists - uses editor selection
-                    if (action.id === 'bulletlist') {
-                        applyBulletList();
-                    } else if (action.id === 'numberedlist') {
-                        applyNumberedList();
-                    } else {
-                        await transformText(action.id);
-                    }
-                    break;
-
-                case 'format': {
-                    const {text, hasSelection, from, to, cursorPos} = getTextToProcess();
-                    if (!text.trim()) {
-                        setError('No text to format');
-                        return;
-                    }
-
-                    let detectedLang = language;
-
-                    // Auto-detect language for plaintext
-                    if (language === 'plaintext') {
-                        detectedLang = await invoke<string>('detect_language', { text });
-
-                        if (detectedLang === 'plaintext') {
-                            setError('Cannot detect language. Please select a language mode first.');
-                            return;
-                        }
-
-                        // Switch to detected language
-                        setLanguage(detectedLang);
-                    }
-
-                    let formatted: string;
-                    if (action.id === 'format') {
-                        formatted = await formatCode(text, detectedLang);
-                    } else if (action.id === 'minify') {
-                        formatted = await minifyCode(text, detectedLang);
-                    } else {
-                        setError('Unknown format action');
-                        return;
+                case 'registry': {
+                    if (action.output === 'side_effect') {
+                        await invoke<ActionResult>('execute_action', {id: action.id});
+                        break;
                     }
 
-                    // Check if diff preview is enabled (and user has PRO)
-                    const showDiffPreview = useSettingsStore.getState().settings?.show_diff_preview;
-                    const hasDiffPreview = isPro;
-                    if (showDiffPreview && hasDiffPreview && text !== formatted) {
-                        useDiffStore.getState().setPendingDiff({
-                            originalText: text,
-                            transformedText: formatted,
-                            transformationType: action.label,
-                            selectionRange: hasSelection ? { from, to } : null,
-                            cursorPos,
-                            applyCallback: () => applyProcessedText(formatted, hasSelection, from, to, cursorPos),
-                        });
-                        useDiffStore.getState().openPreviewModal();
-                    } else {
-                        applyProcessedText(formatted, hasSelection, from, to, cursorPos);
-                        if (text !== formatted && hasDiffPreview) {
-                            useDiffStore.getState().addTransformationRecord({
-                                originalText: text,
-                                transformedText: formatted,
-                                transformationType: action.label,
-                            });
-                        }
+                    if (action.output === 'append') {
+                        const result = await invoke<ActionResult>('execute_action', {id: action.id});
+                        const generated = result.text ?? '';
+                        // Lorem ipsum paragraphs go in as their own block
+                        const separator = action.id.startsWith('lorem:') ? '\n\n' : '\n';
+                        setContent(content ? content + separator + generated : generated);
+                        break;
                     }
-                    break;
-                }
 
-                case 'encode': {
                     const {text, hasSelection, from, to, cursorPos} = getTextToProcess();
                     if (!text.trim()) {
-                        setError('No text to encode/decode');
+                        setError('No text to transform');
                         return;
                     }
-                    const encoded = await invoke<string>(action.id, {text});
+                    const result = await invoke<ActionResult>('execute_action', {id: action.id, payload: {text}});
+                    const transformed = result.text ?? text;
 
                     // Check if diff preview is enabled (and user has PRO)
                     const showDiffPreview = useSettingsStore.getState().settings?.show_diff_preview;
                     const hasDiffPreview = isPro;
-                    if (showDiffPreview && hasDiffPreview && text !== encoded) {
+                    if (showDiffPreview && hasDiffPreview && text !== transformed) {
                         useDiffStore.getState().setPendingDiff({
                             originalText: text,
-                            transformedText: encoded,
+                            transformedText: transformed,
                             transformationType: action.label,
                             selectionRange: hasSelection ? { from, to } : null,
                             cursorPos,
-                            applyCallback: () => applyProcessedText(encoded, hasSelection, from, to, cursorPos),
+                            applyCallback: () => applyProcessedText(transformed, hasSelection, from, to, cursorPos),
                         });
                         useDiffStore.getState().openPreviewModal();
                     } else {
-                        applyProcessedText(encoded, hasSelection, from, to, cursorPos);
-                        if (text !== encoded && hasDiffPreview) {
+                        applyProcessedText(transformed, hasSelection, from, to, cursorPos);
+                        if (text !== transformed && hasDiffPreview) {
                             useDiffStore.getState().addTransformationRecord({
                                 originalText: text,
-                                transformedText: encoded,
+                                transformedText: transformed,
                                 transformationType: action.label,
                             });
                         }
@@ -660,45 +460,6 @@ export function QuickActionsPanel() {
                     break;
                 }
 
-                case 'generate': {
-                    let generated: string;
-
-                    if (action.id === 'generate_uuid') {
-                        generated = await invoke<string>('generate_uuid');
-                    } else if (action.id === 'generate_uuid_v7') {
-                        generated = await invoke<string>('generate_uuid_v7');
-                    } else if (action.id === 'generate_nanoid') {
-                        generated = await invoke<string>('generate_nanoid', {length: 21});
-                    } else if (action.id === 'generate_short_hash') {
-                        generated = await invoke<string>('generate_short_hash', {length: 8});
-                    } else if (action.id === 'generate_prefixed_id') {
-                        generated = await invoke<string>('generate_prefixed_id', {
-                            prefix: 'usr_',
-                            idType: 'nanoid'
-                        });
-                    } else if (action.id === 'generate_bulk_uuid') {
-                        generated = await invoke<string>('generate_bulk', {
-                            generator: 'uuid',
-                            count: 10
-                        });
-                    } else if (action.id === 'get_current_timestamp') {
-                        generated = await invoke<string>('get_current_timestamp', {asMs: false});
-                    } else if (action.id.startsWith('lorem_')) {
-                        const paragraphs = parseInt(action.id.split('_')[1], 10);
-                        generated = await invoke<string>('generate_lorem_ipsum', {
-                            paragraphs,
-                            format: 'plain',
-                        });
-                        setContent(content ? content + '\n\n' + generated : generated);
-                        break;
-                    } else {
-                        break;
-                    }
-
-                    setContent(content ? content + '\n' + generated : generated);
-                    break;
-                }
-
                 case 'custom': {
                     const {text, hasSelection, from, to, cursorPos} = getTextToProcess();
                     if (!text.trim()) {
@@ -788,9 +549,9 @@ export function QuickActionsPanel() {
                 }
             }
         } catch (err) {
-            setError(String(err));
+            setError(errorMessage(err));
         }
-    }, [content, language, isPro, setContent, setLanguage, transformText, applyBulletList, applyNumberedList, getTextToProcess, applyProcessedText, executeTransformation, executeChain, editorView]);
+    }, [content, isPro, setContent, getTextToProcess, applyProcessedText, executeTransformation, executeChain]);
 
     // Keyboard navigation
     const handleKeyDown = useCallback((e: React.KeyboardEvent) => {
@@ -1220,7 +981,7 @@ export function QuickActionsPanel() {
                                                                         {
                                                                             ...action,
                                                                             section: section.title,
-                                                                            proFeature: section.proFeature
+                                                                            proFeature: section.proFeature ?? action.proFeature
                                                                         },
                                                                         actionIndex
                                                                     );
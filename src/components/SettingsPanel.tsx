@@ -14,6 +14,7 @@ import {ProBadge} from './ProFeatureGate';
 import {getLicenseKey} from '../lib/secureStorage';
 import { openExternalUrl } from '../utils/openExternalUrl';
 import type {ThemeType} from '../types';
+import {errorMessage} from '../lib/appError';
 
 const THEMES: { value: ThemeType; label: string; isPro: boolean }[] = [
     {value: 'dark', label: 'Dark', isPro: false},
@@ -41,6 +42,9 @@ interface UpdateInfo {
     has_update: boolean;
     release_notes: string | null;
     download_url: string | null;
+    channel: 'stable' | 'beta';
+    release_date: string | null;
+    ready_to_install: boolean;
 }
 
 interface DownloadProgress {
@@ -284,7 +288,7 @@ export function SettingsPanel() {
             const info = await invoke<UpdateInfo>('check_for_app_updates');
             setUpdateInfo(info);
         } catch (err) {
-            setUpdateError(err instanceof Error ? err.message : String(err));
+            setUpdateError(errorMessage(err));
         } finally {
             setIsCheckingUpdate(false);
         }
@@ -297,7 +301,7 @@ export function SettingsPanel() {
             await invoke('download_and_install_update');
             // App will restart automatically after installation
         } catch (err) {
-            setUpdateError(err instanceof Error ? err.message : String(err));
+            setUpdateError(errorMessage(err));
         }
     };
 
@@ -952,7 +956,7 @@ export function SettingsPanel() {
                                                 await openExternalUrl(portalUrl, ['billing.stripe.com', 'stripe.com']);
                                             } catch (error) {
                                                 console.error('Failed to open customer portal:', error);
-                                                alert(`Failed to open customer portal: ${error}`);
+                                                alert(`Failed to open customer portal: ${errorMessage(error)}`);
                                             }
                                         }}
                                         className="w-full px-4 py-2 text-sm bg-[var(--ui-accent)] text-white rounded-md hover:opacity-90 transition-opacity"
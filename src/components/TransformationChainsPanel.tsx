@@ -4,7 +4,7 @@ import { useTransformationChainsStore } from '../stores/transformationChainsStor
 import { useCustomTransformationsStore } from '../stores/customTransformationsStore';
 import { useLicenseStore } from '../stores/licenseStore';
 import { ProFeatureGate } from './ProFeatureGate';
-import type { TransformationChain, ChainStep } from '../types';
+import type { TransformationChain, ChainStep, TransformParams } from '../types';
 
 type ViewMode = 'list' | 'edit';
 
@@ -18,8 +18,24 @@ const BUILTIN_TRANSFORMATIONS = [
   { id: 'sort', name: 'Sort Lines', description: 'Sort lines alphabetically' },
   { id: 'deduplicate', name: 'Deduplicate', description: 'Remove duplicate lines' },
   { id: 'reverse', name: 'Reverse', description: 'Reverse line order' },
+  { id: 'numberlines', name: 'Number Lines', description: 'Prefix lines with 1., 2., ...' },
+  { id: 'joinlines', name: 'Join Lines', description: 'Join lines with a separator' },
+  { id: 'splitlines', name: 'Split to Lines', description: 'One line per separated item' },
+  { id: 'prefixsuffix', name: 'Prefix / Suffix', description: 'Wrap each line in a prefix and suffix' },
+  { id: 'shufflelines', name: 'Shuffle Lines', description: 'Random line order' },
 ];
 
+// Arguments of the parameterized built-ins, edited on the step. A typed \n or \t means a
+// newline or tab. An empty separator uses the default shown as its placeholder.
+const BUILTIN_PARAMS: Record<string, { key: keyof TransformParams; label: string; placeholder: string }[]> = {
+  joinlines: [{ key: 'separator', label: 'Separator', placeholder: ', ' }],
+  splitlines: [{ key: 'separator', label: 'Separator', placeholder: ',' }],
+  prefixsuffix: [
+    { key: 'prefix', label: 'Prefix', placeholder: "'" },
+    { key: 'suffix', label: 'Suffix', placeholder: "'," },
+  ],
+};
+
 export function TransformationChainsPanel() {
   return (
     <ProFeatureGate feature="transformation_chains">
@@ -145,6 +161,21 @@ function TransformationChainsPanelContent() {
     });
   }, [editingChain]);
 
+  const handleStepParamChange = useCallback((stepId: string, key: keyof TransformParams, value: string) => {
+    if (!editingChain) return;
+
+    setEditingChain({
+      ...editingChain,
+      steps: editingChain.steps.map((s) => {
+        if (s.id !== stepId) return s;
+        const params = { ...s.params };
+        if (value) params[key] = value;
+        else delete params[key];
+        return { ...s, params };
+      }),
+    });
+  }, [editingChain]);
+
   const handleMoveStepUp = useCallback((stepId: string) => {
     if (!editingChain) return;
 
@@ -504,6 +535,23 @@ function TransformationChainsPanelContent() {
                       <span className="ml-2 text-[10px] text-[var(--ui-text-muted)]">
                         {step.type === 'builtin' ? 'Built-in' : 'Custom'}
                       </span>
+                      {step.type === 'builtin' && BUILTIN_PARAMS[step.transformId] && (
+                        <div className="flex items-center gap-2 mt-1">
+                          {BUILTIN_PARAMS[step.transformId].map((field) => (
+                            <label key={field.key} className="flex items-center gap-1 text-[10px] text-[var(--ui-text-muted)]">
+                              {field.label}
+                              <input
+                                type="text"
+                                value={step.params?.[field.key] ?? ''}
+                                placeholder={field.placeholder}
+                                onMouseDown={(e) => e.stopPropagation()}
+                                onChange={(e) => handleStepParamChange(step.id, field.key, e.target.value)}
+                                className="w-20 px-1.5 py-0.5 text-xs rounded bg-[var(--ui-bg)] border border-[var(--ui-border)] text-[var(--ui-text)] focus:outline-none focus:border-[var(--ui-accent)]"
+                              />
+                            </label>
+                          ))}
+                        </div>
+                      )}
                     </div>
 
                     {/* Move buttons */}
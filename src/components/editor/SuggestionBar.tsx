@@ -0,0 +1,57 @@
+import {invoke} from '@tauri-apps/api/core';
+import {Diamond, Sparkles} from 'lucide-react';
+import type {ActionResult, SuggestedAction} from '../../types';
+
+// Suggestions the editor runs itself rather than through a command
+export const EDITOR_HANDLED = new Set(['format_code', 'minify_code', 'decode-jwt', 'csv-to-json', 'csv-pretty-table']);
+
+// Palette actions (execute_action) the backend suggests, besides "transform:<name>"
+const REGISTRY_ACTIONS = new Set(['decode_base64', 'decode_url', 'unix_to_human']);
+
+// Editor tools that take the text and hand back its replacement. Tools that need more
+// input (query_json) or return something to inspect (parse_url) aren't offered here.
+const TEXT_TOOLS: Record<string, (text: string) => Promise<string>> = {
+    sort_json_keys: text => invoke<string>('sort_json_keys', {text, recursive: true}),
+    json_to_typescript_interface: text => invoke<string>('json_to_typescript_interface', {text}),
+    infer_json_schema: text => invoke<string>('infer_json_schema', {text}),
+    url_to_markdown_link: text => invoke<string>('url_to_markdown_link', {url: text.trim()}),
+    format_stack_trace: async text => (await invoke<{formatted: string}>('format_stack_trace', {text})).formatted,
+};
+
+export function canRunSuggestion(id: string): boolean {
+    return EDITOR_HANDLED.has(id) || REGISTRY_ACTIONS.has(id) || id.startsWith('transform:') || id in TEXT_TOOLS;
+}
+
+/** Run a suggested command on `text`, returning the replacement text */
+export async function runSuggestedCommand(id: string, text: string): Promise<string | null> {
+    const tool = TEXT_TOOLS[id];
+    if (tool) return tool(text);
+    const result = await invoke<ActionResult>('execute_action', {id, payload: {text}});
+    return result.output === 'side_effect' ? null : result.text;
+}
+
+interface SuggestionBarProps {
+    suggestions: SuggestedAction[];
+    onRun: (action: SuggestedAction) => void;
+}
+
+export function SuggestionBar({suggestions, onRun}: SuggestionBarProps) {
+    if (suggestions.length === 0) return null;
+
+    return (
+        <div className="flex items-center gap-1.5 px-3 py-1.5 border-t border-[var(--ui-border)] overflow-x-auto">
+            <Sparkles className="w-3.5 h-3.5 flex-shrink-0 text-[var(--ui-text-muted)]"/>
+            {suggestions.map(action => (
+                <button
+                    key={action.id}
+                    onClick={() => onRun(action)}
+                    title={action.locked ? `${action.title} (Pro)` : action.title}
+                    className={`flex-shrink-0 flex items-center gap-1 text-xs px-2 py-0.5 rounded-md border border-[var(--ui-border)] bg-[var(--ui-surface)] text-[var(--ui-text)] hover:bg-[var(--ui-hover)] ${action.locked ? 'opacity-60' : ''}`}
+                >
+                    {action.title}
+                    {action.locked && <Diamond className="w-2.5 h-2.5 text-[var(--ui-accent)]"/>}
+                </button>
+            ))}
+        </div>
+    );
+}
@@ -33,3 +33,6 @@ export { preserveCase } from './casePreservation';
 
 // Tips bar
 export { TipsBar } from './TipsBar';
+
+// Action bar suggestions
+export { SuggestionBar, canRunSuggestion, runSuggestedCommand, EDITOR_HANDLED } from './SuggestionBar';
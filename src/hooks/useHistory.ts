@@ -1,4 +1,5 @@
 import { useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { useHistoryStore } from '../stores/historyStore';
 import { useEditorStore, type EditorAttachment } from '../stores/editorStore';
 import type { HistoryEntry } from '../types';
@@ -21,8 +22,12 @@ export function useHistory() {
   );
 
   const handleSelect = useCallback(
-    (entry: HistoryEntry) => {
-      setContent(entry.content);
+    async (entry: HistoryEntry) => {
+      // Listed entries only carry a preview of long content
+      const content = entry.content_truncated
+        ? await invoke<string>('get_entry_content', { id: entry.id })
+        : entry.content;
+      setContent(content);
       // Load attachments if present
       if (entry.images) {
         try {
@@ -1,5 +1,7 @@
 import { useEffect, useRef } from 'react';
 import { register, unregister, isRegistered } from '@tauri-apps/plugin-global-shortcut';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { closeSearchPanel, searchPanelOpen } from '@codemirror/search';
 import { useSettingsStore } from '../stores/settingsStore';
 import { useEditorStore } from '../stores/editorStore';
@@ -15,8 +17,18 @@ export function useGlobalHotkey() {
     const shortcut = settings.hotkey;
     let registered = false;
 
+    // Double taps ("DoubleTap+Command") and chords ("Control+K Control+S") are watched
+    // by the backend, which emits summon-hotkey
+    const unlistenSummon = listen('summon-hotkey', () => toggleWindow());
+
     const setupHotkey = async () => {
       try {
+        const handledByBackend = await invoke<boolean>('set_summon_hotkey', { hotkey: shortcut });
+        if (handledByBackend) {
+          console.log(`Hotkey registered: ${shortcut}`);
+          return;
+        }
+
         // Check if already registered
         const alreadyRegistered = await isRegistered(shortcut);
         if (alreadyRegistered) {
@@ -61,6 +73,7 @@ export function useGlobalHotkey() {
 
     return () => {
       clearInterval(wakeDetectorId);
+      unlistenSummon.then((unlisten) => unlisten());
       if (registered) {
         unregister(shortcut).catch(console.error);
       }
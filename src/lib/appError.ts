@@ -0,0 +1,59 @@
+/**
+ * Errors returned by Tauri commands
+ *
+ * Commands reject with a serialized AppError (see src-tauri/src/error.rs) so the
+ * UI can react to the code instead of matching on message text. A few plugin
+ * commands still reject with a plain string, so everything here accepts both.
+ */
+
+export type ErrorCode =
+  | 'pro_required'
+  | 'license_invalid'
+  | 'subscription_expired'
+  | 'quota_exceeded'
+  | 'network'
+  | 'offline'
+  | 'external_service'
+  | 'unauthenticated'
+  | 'permission_denied'
+  | 'privacy_mode'
+  | 'disabled'
+  | 'unsupported'
+  | 'cancelled'
+  | 'invalid_input'
+  | 'not_found'
+  | 'conflict'
+  | 'storage'
+  | 'internal';
+
+export interface AppError {
+  code: ErrorCode;
+  message: string;
+  details?: unknown;
+}
+
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as AppError).code === 'string' &&
+    typeof (error as AppError).message === 'string'
+  );
+}
+
+/** Human-readable message for anything a command (or JS) can throw */
+export function errorMessage(error: unknown): string {
+  if (isAppError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
+
+export function errorCode(error: unknown): ErrorCode | null {
+  return isAppError(error) ? error.code : null;
+}
+
+/** Errors that the license screen can resolve (activate, renew or upgrade) */
+export function needsLicense(error: unknown): boolean {
+  const code = errorCode(error);
+  return code === 'pro_required' || code === 'license_invalid' || code === 'subscription_expired';
+}
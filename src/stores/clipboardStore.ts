@@ -1,7 +1,9 @@
 import { create } from 'zustand';
 import { persist, createJSONStorage } from 'zustand/middleware';
 import { readText } from '@tauri-apps/plugin-clipboard-manager';
-import type { ClipboardItem } from '../types';
+import { invoke } from '@tauri-apps/api/core';
+import type { ClipboardCapture, ClipboardItem } from '../types';
+import { errorCode } from '../lib/appError';
 
 const MAX_CLIPBOARD_ITEMS = 50;
 
@@ -28,6 +30,20 @@ const createPreview = (content: string): string => {
 };
 
 let monitoringInterval: ReturnType<typeof setInterval> | null = null;
+// Plain text seen on the last poll; stored items may be its Markdown version
+let lastPlainText: string | null = null;
+
+// The text to keep for a new clipboard value: Markdown when rich_to_markdown applies to
+// the app it was copied from, the plain text otherwise. Null while privacy mode is on.
+const captureText = async (plain: string): Promise<string | null> => {
+  try {
+    const capture = await invoke<ClipboardCapture>('read_clipboard_capture');
+    return capture.text ?? plain;
+  } catch (error) {
+    if (errorCode(error) === 'privacy_mode') return null;
+    return plain;
+  }
+};
 
 export const useClipboardStore = create<ClipboardState>()(
   persist(
@@ -87,8 +103,11 @@ export const useClipboardStore = create<ClipboardState>()(
         monitoringInterval = setInterval(async () => {
           try {
             const text = await readText();
-            if (text && text !== get().lastContent) {
-              get().addItem(text);
+            if (!text || text === lastPlainText) return;
+            lastPlainText = text;
+            const captured = await captureText(text);
+            if (captured && captured !== get().lastContent) {
+              get().addItem(captured);
             }
           } catch (error) {
             // Clipboard might be empty or contain non-text data
@@ -2,6 +2,7 @@ import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { CustomAIPrompt, CustomAIPromptsData } from '../types';
 import { usePremiumStore } from './premiumStore';
+import { errorMessage } from '../lib/appError';
 
 interface CustomAIPromptsState {
   prompts: CustomAIPrompt[];
@@ -33,7 +34,7 @@ export const useCustomAIPromptsStore = create<CustomAIPromptsState>((set, get) =
       set({ prompts: data.prompts, loading: false });
     } catch (error) {
       console.error('Failed to load custom AI prompts:', error);
-      set({ loading: false, error: String(error) });
+      set({ loading: false, error: errorMessage(error) });
     }
   },
 
@@ -50,7 +51,7 @@ export const useCustomAIPromptsStore = create<CustomAIPromptsState>((set, get) =
       return prompt;
     } catch (error) {
       console.error('Failed to add custom AI prompt:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return null;
     }
   },
@@ -67,7 +68,7 @@ export const useCustomAIPromptsStore = create<CustomAIPromptsState>((set, get) =
       }));
     } catch (error) {
       console.error('Failed to update custom AI prompt:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -79,7 +80,7 @@ export const useCustomAIPromptsStore = create<CustomAIPromptsState>((set, get) =
       }));
     } catch (error) {
       console.error('Failed to delete custom AI prompt:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -93,7 +94,7 @@ export const useCustomAIPromptsStore = create<CustomAIPromptsState>((set, get) =
       }));
     } catch (error) {
       console.error('Failed to toggle custom AI prompt:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -133,7 +134,7 @@ export const useCustomAIPromptsStore = create<CustomAIPromptsState>((set, get) =
       set({ prompts: data.prompts });
     } catch (error) {
       console.error('Failed to import custom AI prompts:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 }));
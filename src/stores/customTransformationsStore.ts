@@ -2,6 +2,7 @@ import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { CustomTransformation, CustomTransformationsData } from '../types';
 import { executeUserTransformation } from '../lib/secureTransformExecution';
+import { errorMessage } from '../lib/appError';
 
 interface TransformationResult {
   success: boolean;
@@ -43,7 +44,7 @@ export const useCustomTransformationsStore = create<CustomTransformationsState>(
       set({
         transformations: [],
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
     }
   },
@@ -56,7 +57,7 @@ export const useCustomTransformationsStore = create<CustomTransformationsState>(
       });
     } catch (error) {
       console.error('Failed to save custom transformations:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
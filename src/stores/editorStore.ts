@@ -6,6 +6,7 @@ import type { Snippet, TextStats, PanelType } from '../types';
 import { useDiffStore } from './diffStore';
 import { useSettingsStore } from './settingsStore';
 import { useLicenseStore } from './licenseStore';
+import { errorMessage } from '../lib/appError';
 
 export type SettingsTab = 'settings' | 'hotkeys' | 'license' | 'obsidian';
 
@@ -368,8 +369,11 @@ export const useEditorStore = create<EditorState>((set, get) => ({
           images: images.length > 0 ? JSON.stringify(images) : null,
         });
 
-        // Hide window and auto-paste to previous app
-        await invoke('hide_and_paste');
+        // Hide window and auto-paste to previous app. Text-only content is passed along so
+        // the target app's paste profile can reformat it; attachments are left as written.
+        await invoke('hide_and_paste', images.length > 0
+          ? {}
+          : { content: content.replace(/\n+$/, ''), language: get().language });
         set({ content: '', images: [], nextImageId: 1, activePanel: 'editor', isQuickActionsOpen: true, isVisible: false });
         return;
       } catch (error) {
@@ -943,7 +947,7 @@ export const useEditorStore = create<EditorState>((set, get) => ({
         return { success: false };
       }
     } catch (error) {
-      return { success: false, error: String(error) };
+      return { success: false, error: errorMessage(error) };
     }
   },
 }));
@@ -8,6 +8,7 @@ import type {
   GistResult,
   WingmanGist,
 } from '../types';
+import { errorCode, errorMessage } from '../lib/appError';
 
 interface GitHubState {
   // Auth
@@ -108,16 +109,12 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       });
     } catch (error) {
       console.error('Failed to load GitHub auth status:', error);
-      const errorMessage = String(error);
+      const message = errorMessage(error);
 
       // Provide user-friendly error messages
-      let friendlyError = errorMessage;
-      if (errorMessage.includes('connection') || errorMessage.includes('network')) {
+      let friendlyError = message;
+      if (errorCode(error) === 'network') {
         friendlyError = 'Unable to connect to GitHub. Please check your internet connection.';
-      } else if (errorMessage.includes('timeout')) {
-        friendlyError = 'Request timed out. Please try again.';
-      } else if (errorMessage.includes('revoked')) {
-        friendlyError = 'Your GitHub authorization has been revoked. Please re-authorize.';
       }
 
       set({
@@ -135,17 +132,21 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       return flowStart;
     } catch (error) {
       console.error('Failed to start GitHub device flow:', error);
-      const errorMessage = String(error);
-
-      let friendlyError = errorMessage;
-      if (errorMessage.includes('Client ID not configured')) {
-        friendlyError = 'GitHub integration not properly configured. Please contact support.';
-      } else if (errorMessage.includes('connection') || errorMessage.includes('connect')) {
-        friendlyError = 'Failed to connect to GitHub. Please check your internet connection.';
-      } else if (errorMessage.includes('timeout')) {
-        friendlyError = 'Request timed out. Please try again.';
-      } else if (!errorMessage.includes('Failed')) {
-        friendlyError = `Failed to start authorization: ${errorMessage}`;
+      const message = errorMessage(error);
+
+      let friendlyError: string;
+      switch (errorCode(error)) {
+        case 'disabled':
+          friendlyError = 'GitHub integration not properly configured. Please contact support.';
+          break;
+        case 'network':
+          friendlyError = 'Failed to connect to GitHub. Please check your internet connection.';
+          break;
+        case 'offline':
+          friendlyError = message;
+          break;
+        default:
+          friendlyError = `Failed to start authorization: ${message}`;
       }
 
       set({
@@ -181,23 +182,25 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       return false;
     } catch (error) {
       console.error('[GitHub Store] Poll caught error:', error);
-      const errorMessage = String(error);
-      console.log('[GitHub Store] Error message string:', errorMessage);
-      console.log('[GitHub Store] Contains slow_down?', errorMessage.includes('slow_down'));
+      const message = errorMessage(error);
+      const code = errorCode(error);
 
       // Re-throw slow_down errors so the modal can adjust its polling interval
-      if (errorMessage.includes('slow_down')) {
+      if (code === 'quota_exceeded') {
         console.log('[GitHub Store] Re-throwing slow_down error');
         throw error;
       }
 
-      let friendlyError = errorMessage;
-      if (errorMessage.includes('timeout') || errorMessage.includes('expired')) {
-        friendlyError = 'Authorization timed out. Please try again.';
-      } else if (errorMessage.includes('connection')) {
-        friendlyError = 'Connection lost. Please check your internet connection.';
-      } else if (!errorMessage.includes('Failed')) {
-        friendlyError = `Authorization failed: ${errorMessage}`;
+      let friendlyError: string;
+      switch (code) {
+        case 'unauthenticated':
+          friendlyError = 'Authorization timed out. Please try again.';
+          break;
+        case 'network':
+          friendlyError = 'Connection lost. Please check your internet connection.';
+          break;
+        default:
+          friendlyError = `Authorization failed: ${message}`;
       }
 
       set({
@@ -219,10 +222,10 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       });
     } catch (error) {
       console.error('Failed to logout from GitHub:', error);
-      const errorMessage = String(error);
+      const message = errorMessage(error);
       set({
         loading: false,
-        error: `Failed to logout: ${errorMessage}`,
+        error: `Failed to logout: ${message}`,
       });
     }
   },
@@ -282,29 +285,34 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       return result;
     } catch (error) {
       console.error('Failed to create GitHub gist:', error);
-      const errorMessage = String(error);
+      const message = errorMessage(error);
 
       // Provide user-friendly error messages
-      let friendlyError = errorMessage;
-      if (errorMessage.includes('Not authenticated')) {
-        friendlyError = 'Not authenticated with GitHub. Please authorize in Settings.';
-      } else if (errorMessage.includes('revoked')) {
-        friendlyError = 'Your GitHub authorization has been revoked. Please re-authorize in Settings.';
-      } else if (errorMessage.includes('rate limit')) {
-        friendlyError = 'GitHub rate limit reached. Please try again in a few minutes.';
-      } else if (errorMessage.includes('connection') || errorMessage.includes('network')) {
-        friendlyError = 'Failed to connect to GitHub. Please check your internet connection.';
-      } else if (errorMessage.includes('timeout')) {
-        friendlyError = 'Request timed out. Please try again.';
-      } else if (errorMessage.includes('too large')) {
-        friendlyError = errorMessage; // Use the specific size error
-      } else if (errorMessage.includes('Client ID not configured')) {
-        friendlyError = 'GitHub integration not configured. Please contact support.';
-      } else if (errorMessage.includes('forbidden') || errorMessage.includes('permissions')) {
-        friendlyError = 'Permission denied. Your GitHub token may lack gist permissions.';
-      } else if (!errorMessage.includes('Failed')) {
-        // Wrap raw errors in a more user-friendly message
-        friendlyError = `Failed to create gist: ${errorMessage}`;
+      let friendlyError: string;
+      switch (errorCode(error)) {
+        case 'unauthenticated':
+          // Already says whether to authorize or re-authorize in Settings
+          friendlyError = message;
+          break;
+        case 'quota_exceeded':
+          friendlyError = 'GitHub rate limit reached. Please try again in a few minutes.';
+          break;
+        case 'network':
+          friendlyError = 'Failed to connect to GitHub. Please check your internet connection.';
+          break;
+        case 'invalid_input':
+        case 'offline':
+          friendlyError = message; // Use the specific error
+          break;
+        case 'disabled':
+          friendlyError = 'GitHub integration not configured. Please contact support.';
+          break;
+        case 'permission_denied':
+          friendlyError = 'Permission denied. Your GitHub token may lack gist permissions.';
+          break;
+        default:
+          // Wrap raw errors in a more user-friendly message
+          friendlyError = `Failed to create gist: ${message}`;
       }
 
       set({
@@ -323,8 +331,8 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       set({ loading: false, error: null });
       return gists;
     } catch (error) {
-      const errorMessage = String(error);
-      set({ loading: false, error: `Failed to load gists: ${errorMessage}` });
+      const message = errorMessage(error);
+      set({ loading: false, error: `Failed to load gists: ${message}` });
       return [];
     }
   },
@@ -346,8 +354,8 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       set({ gistLoading: false, error: null, lastGistResult: result });
       return result;
     } catch (error) {
-      const errorMessage = String(error);
-      set({ gistLoading: false, error: `Failed to sync gist: ${errorMessage}` });
+      const message = errorMessage(error);
+      set({ gistLoading: false, error: `Failed to sync gist: ${message}` });
       return null;
     }
   },
@@ -359,8 +367,8 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       set({ gistLoading: false, error: null });
       return true;
     } catch (error) {
-      const errorMessage = String(error);
-      set({ gistLoading: false, error: `Failed to delete gist: ${errorMessage}` });
+      const message = errorMessage(error);
+      set({ gistLoading: false, error: `Failed to delete gist: ${message}` });
       return false;
     }
   },
@@ -380,7 +388,7 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       console.error('Failed to load GitHub config:', error);
       set({
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
     }
   },
@@ -413,7 +421,7 @@ export const useGitHubStore = create<GitHubState>((set, get) => ({
       console.error('Failed to save GitHub config:', error);
       set({
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
       return false;
     }
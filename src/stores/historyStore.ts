@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { HistoryEntry, HistoryStats } from '../types';
+import { errorMessage } from '../lib/appError';
 
 interface HistoryState {
   entries: HistoryEntry[];
@@ -31,7 +32,7 @@ export const useHistoryStore = create<HistoryState>((set, get) => ({
       set({ entries, loading: false });
     } catch (error) {
       console.error('Failed to load history:', error);
-      set({ loading: false, error: String(error) });
+      set({ loading: false, error: errorMessage(error) });
     }
   },
 
@@ -46,7 +47,7 @@ export const useHistoryStore = create<HistoryState>((set, get) => ({
       }
     } catch (error) {
       console.error('Failed to search history:', error);
-      set({ loading: false, error: String(error) });
+      set({ loading: false, error: errorMessage(error) });
     }
   },
 
@@ -59,7 +60,7 @@ export const useHistoryStore = create<HistoryState>((set, get) => ({
       get().loadStats();
     } catch (error) {
       console.error('Failed to delete entry:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -69,7 +70,7 @@ export const useHistoryStore = create<HistoryState>((set, get) => ({
       set({ entries: [], stats: { total_entries: 0, total_characters: 0, total_words: 0 } });
     } catch (error) {
       console.error('Failed to clear history:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { LicenseTier, LicenseStatus, LicenseStatusInfo, ProFeature } from '../types';
+import { errorMessage } from '../lib/appError';
 
 // Check if running in development mode
 export const isDev = import.meta.env.DEV;
@@ -65,7 +66,7 @@ export const useLicenseStore = create<LicenseState>((set, get) => ({
       set({
         ...defaultLicenseState,
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
     }
   },
@@ -92,7 +93,7 @@ export const useLicenseStore = create<LicenseState>((set, get) => ({
       console.error('Failed to activate license:', error);
       set({
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
       return false;
     }
@@ -112,7 +113,7 @@ export const useLicenseStore = create<LicenseState>((set, get) => ({
       console.error('Failed to deactivate license:', error);
       set({
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
       return false;
     }
@@ -137,7 +138,7 @@ export const useLicenseStore = create<LicenseState>((set, get) => ({
       console.error('Failed to refresh license:', error);
       set({
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
       return false;
     }
@@ -13,6 +13,8 @@ import type {
 } from '../types';
 import { DEFAULT_AI_PRESETS } from '../data/aiPresets';
 import { useLicenseStore, isDev } from './licenseStore';
+import { useEditorStore } from './editorStore';
+import { errorMessage, needsLicense } from '../lib/appError';
 
 // Token limit for Premium tier
 const MONTHLY_TOKEN_LIMIT = 1_000_000;
@@ -125,7 +127,7 @@ export const usePremiumStore = create<PremiumState>((set, get) => ({
         aiConfig,
         aiPresets,
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
     }
   },
@@ -179,7 +181,7 @@ export const usePremiumStore = create<PremiumState>((set, get) => ({
       return true;
     } catch (error) {
       console.error('Failed to save Obsidian config:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return false;
     }
   },
@@ -209,7 +211,7 @@ export const usePremiumStore = create<PremiumState>((set, get) => ({
       return true;
     } catch (error) {
       console.error('Failed to save AI config:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return false;
     }
   },
@@ -242,7 +244,7 @@ export const usePremiumStore = create<PremiumState>((set, get) => ({
       return true;
     } catch (error) {
       console.error('Failed to save AI presets:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return false;
     }
   },
@@ -299,7 +301,10 @@ export const usePremiumStore = create<PremiumState>((set, get) => ({
       return response;
     } catch (error) {
       console.error('AI feature call failed:', error);
-      set({ aiLoading: false, error: String(error) });
+      set({ aiLoading: false, error: errorMessage(error) });
+      if (needsLicense(error)) {
+        useEditorStore.getState().openSettingsTab('license');
+      }
       return null;
     }
   },
@@ -310,7 +315,7 @@ export const usePremiumStore = create<PremiumState>((set, get) => ({
       return isValid;
     } catch (error) {
       console.error('Obsidian vault validation failed:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return false;
     }
   },
@@ -369,7 +374,10 @@ export const usePremiumStore = create<PremiumState>((set, get) => ({
       return response;
     } catch (error) {
       console.error('AI feature call failed:', error);
-      set({ aiLoading: false, error: String(error) });
+      set({ aiLoading: false, error: errorMessage(error) });
+      if (needsLicense(error)) {
+        useEditorStore.getState().openSettingsTab('license');
+      }
       return null;
     }
   },
@@ -386,7 +394,7 @@ export const usePremiumStore = create<PremiumState>((set, get) => ({
       return result;
     } catch (error) {
       console.error('Failed to add to Obsidian:', error);
-      set({ error: String(error), lastObsidianResult: null });
+      set({ error: errorMessage(error), lastObsidianResult: null });
       return null;
     }
   },
@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { AppSettings } from '../types';
+import { errorMessage } from '../lib/appError';
 
 interface SettingsState {
   settings: AppSettings | null;
@@ -71,6 +72,58 @@ const defaultSettings: AppSettings = {
   show_tips: true,
   show_intelligent_suggestions: true,
   toolbar_order: DEFAULT_TOOLBAR_ORDER,
+  update_channel: 'stable',
+  auto_download_updates: false,
+  profile_switch_hotkey: null,
+  semantic_search_enabled: false,
+  summary_backend: 'ai',
+  lan_sync_enabled: false,
+  ai_history_enabled: false,
+  ai_history_retention_days: 30,
+  notifications: {
+    formatting: true,
+    ai: true,
+    gist_sync: true,
+    export: true,
+    reminders: true,
+    only_when_hidden: true,
+  },
+  quick_save: {
+    directory: null,
+    filename_template: '{{date}}-{{language}}.{{ext}}',
+    overwrite: 'rename',
+  },
+  local_api_enabled: false,
+  history_limits: {
+    max_entry_bytes: 1024 * 1024,
+    oversized: 'overflow',
+  },
+  event_bridge: {
+    enabled: false,
+    entry_added: true,
+    transform_run: true,
+    ai_call_completed: true,
+  },
+  audit_log_enabled: false,
+  proxy: {
+    mode: 'system',
+    server: '',
+    username: '',
+    bypass: 'localhost,127.0.0.1,::1',
+  },
+  tls: {
+    ca_bundle_path: '',
+    supabase_pins: [],
+  },
+  offline_mode: false,
+  command_runner: {
+    shell: null,
+    working_dir: null,
+    timeout_secs: 30,
+    env_allowlist: ['PATH', 'HOME', 'USER', 'LANG', 'LC_ALL', 'TERM', 'TMPDIR', 'SYSTEMROOT', 'TEMP'],
+    confirm_before_run: true,
+    save_output_to_history: true,
+  },
 };
 
 export const useSettingsStore = create<SettingsState>((set, get) => ({
@@ -86,7 +139,7 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     } catch (error) {
       console.error('Failed to load settings:', error);
       // Keep using defaults, just log the error
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -109,7 +162,7 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
       }
     } catch (error) {
       console.error('Failed to save settings:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -128,7 +181,7 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
       }
     } catch (error) {
       console.error('Failed to reset settings:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 }));
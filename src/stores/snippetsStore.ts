@@ -1,11 +1,39 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { GitHubAuthStatus, GitHubConfig, Snippet, SnippetsData, WingmanGist } from '../types';
+import { diffLines, type Change } from 'diff';
+import type {
+  CommandRunResult,
+  GistConflictResolution,
+  GistSyncReport,
+  GitHubAuthStatus,
+  GitHubConfig,
+  Snippet,
+  SnippetExportFormat,
+  SnippetExportSummary,
+  SnippetFilter,
+  SnippetKind,
+  SnippetComparison,
+  SnippetsData,
+  SnippetVersion,
+  WingmanGist,
+} from '../types';
+import { errorCode, errorMessage, isAppError } from '../lib/appError';
+import { useEditorStore } from './editorStore';
+
+// An edit refused because the snippet changed after it was opened
+export interface SnippetConflict {
+  id: string;
+  name: string;
+  content: string;
+  tags: string[];
+  current: Snippet; // The copy that's saved now
+}
 
 interface SnippetsState {
   snippets: Snippet[];
   loading: boolean;
   error: string | null;
+  conflict: SnippetConflict | null;
   searchQuery: string;
   loadSnippets: () => Promise<void>;
   addSnippet: (name: string, content: string, tags?: string[]) => Promise<Snippet | null>;
@@ -18,19 +46,40 @@ interface SnippetsState {
     tags?: string[],
     githubSource?: string
   ) => Promise<Snippet | null>;
-  updateSnippet: (id: string, name: string, content: string, tags: string[]) => Promise<void>;
+  updateSnippet: (id: string, name: string, content: string, tags: string[]) => Promise<boolean>;
+  forceUpdateSnippet: () => Promise<boolean>;
+  dismissConflict: () => void;
+  getSnippetVersions: (id: string) => Promise<SnippetVersion[]>;
+  compareSnippetVersions: (id: string, a: number, b: number) => Promise<SnippetVersionDiff | null>;
+  rollbackSnippet: (id: string, version: number) => Promise<boolean>;
   renameSnippet: (id: string, name: string) => Promise<boolean>;
   deleteSnippet: (id: string) => Promise<void>;
+  setSnippetKind: (id: string, kind: SnippetKind) => Promise<void>;
+  runSnippetCommand: (id: string) => Promise<CommandRunResult | null>;
   createGistFromSnippet: (id: string) => Promise<boolean>;
   syncSnippetToGitHub: (id: string) => Promise<boolean>;
   importWingmanGists: () => Promise<number>;
+  syncAllGists: () => Promise<GistSyncReport | null>;
+  resolveGistConflict: (id: string, resolution: GistConflictResolution) => Promise<boolean>;
   disconnectSnippetFromGitHub: (id: string) => Promise<boolean>;
   setSearchQuery: (query: string) => void;
   getFilteredSnippets: () => Snippet[];
   exportSnippets: () => SnippetsData;
+  exportSnippetsAs: (
+    format: SnippetExportFormat,
+    path: string,
+    filter?: SnippetFilter
+  ) => Promise<SnippetExportSummary | null>;
   importSnippets: (data: SnippetsData) => Promise<void>;
 }
 
+// Two versions of a snippet with the line changes from the first to the second
+export interface SnippetVersionDiff extends SnippetComparison {
+  changes: Change[];
+  added: number;
+  removed: number;
+}
+
 const sanitizeFilename = (name: string): string => {
   const base = name
     .toLowerCase()
@@ -46,6 +95,7 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
   snippets: [],
   loading: false,
   error: null,
+  conflict: null,
   searchQuery: '',
 
   loadSnippets: async () => {
@@ -55,7 +105,7 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
       set({ snippets: data.snippets, loading: false });
     } catch (error) {
       console.error('Failed to load snippets:', error);
-      set({ loading: false, error: String(error) });
+      set({ loading: false, error: errorMessage(error) });
     }
   },
 
@@ -68,7 +118,7 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
       return snippet;
     } catch (error) {
       console.error('Failed to add snippet:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return null;
     }
   },
@@ -109,22 +159,97 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
       return linkedSnippet;
     } catch (error) {
       console.error('Failed to link snippet to gist:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return snippet;
     }
   },
 
   updateSnippet: async (id: string, name: string, content: string, tags: string[]) => {
+    const version = get().snippets.find((s) => s.id === id)?.version ?? 0;
     try {
-      await invoke('update_snippet', { id, name, content, tags });
+      const updated = await invoke<Snippet>('update_snippet', { id, name, content, tags, version });
       set((state) => ({
-        snippets: state.snippets.map((s) =>
-          s.id === id ? { ...s, name, content, tags, updated_at: nowIso() } : s
-        ),
+        snippets: state.snippets.map((s) => (s.id === id ? updated : s)),
+        conflict: null,
       }));
+      return true;
     } catch (error) {
       console.error('Failed to update snippet:', error);
-      set({ error: String(error) });
+      // Edited elsewhere since it was loaded: show the current copy and keep the edit so
+      // it can still be saved over it
+      const current = isAppError(error) && error.code === 'conflict'
+        ? (error.details as { current?: Snippet } | undefined)?.current
+        : undefined;
+      set((state) => ({
+        error: errorMessage(error),
+        snippets: current ? state.snippets.map((s) => (s.id === id ? current : s)) : state.snippets,
+        conflict: current ? { id, name, content, tags, current } : null,
+      }));
+      return false;
+    }
+  },
+
+  forceUpdateSnippet: async () => {
+    const conflict = get().conflict;
+    if (!conflict) return false;
+    const { id, name, content, tags } = conflict;
+    try {
+      const updated = await invoke<Snippet>('force_update_snippet', { id, name, content, tags });
+      set((state) => ({
+        snippets: state.snippets.map((s) => (s.id === id ? updated : s)),
+        conflict: null,
+        error: null,
+      }));
+      return true;
+    } catch (error) {
+      console.error('Failed to overwrite snippet:', error);
+      set({ error: errorMessage(error) });
+      return false;
+    }
+  },
+
+  dismissConflict: () => set({ conflict: null }),
+
+  getSnippetVersions: async (id: string) => {
+    try {
+      return await invoke<SnippetVersion[]>('get_snippet_versions', { id });
+    } catch (error) {
+      console.error('Failed to load snippet versions:', error);
+      set({ error: errorMessage(error) });
+      return [];
+    }
+  },
+
+  compareSnippetVersions: async (id: string, a: number, b: number) => {
+    try {
+      const comparison = await invoke<SnippetComparison>('compare_snippet_versions', { id, a, b });
+      const changes = diffLines(comparison.from.content, comparison.to.content);
+      const count = (pick: (change: Change) => boolean) =>
+        changes.filter(pick).reduce((total, change) => total + (change.count ?? 0), 0);
+      return {
+        ...comparison,
+        changes,
+        added: count((change) => change.added),
+        removed: count((change) => change.removed),
+      };
+    } catch (error) {
+      console.error('Failed to compare snippet versions:', error);
+      set({ error: errorMessage(error) });
+      return null;
+    }
+  },
+
+  rollbackSnippet: async (id: string, version: number) => {
+    try {
+      const updated = await invoke<Snippet>('rollback_snippet', { id, version });
+      set((state) => ({
+        snippets: state.snippets.map((s) => (s.id === id ? updated : s)),
+      }));
+      return true;
+    } catch (error) {
+      console.error('Failed to roll back snippet:', error);
+      set({ error: errorMessage(error) });
+      return false;
     }
   },
 
@@ -136,11 +261,12 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
     const nextFilename = sanitizeFilename(trimmedName);
 
     try {
-      await invoke('update_snippet', {
+      const updated = await invoke<Snippet>('update_snippet', {
         id,
         name: trimmedName,
         content: snippet.content,
         tags: snippet.tags,
+        version: snippet.version ?? 0,
       });
 
       if (snippet.github_gist_id) {
@@ -164,8 +290,7 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
           snippets: state.snippets.map((s) =>
             s.id === id
               ? {
-                  ...s,
-                  name: trimmedName,
+                  ...updated,
                   github_gist_url: gistResult.html_url,
                   github_gist_filename: nextFilename,
                   github_synced_at: syncedAt,
@@ -176,16 +301,14 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
         }));
       } else {
         set((state) => ({
-          snippets: state.snippets.map((s) =>
-            s.id === id ? { ...s, name: trimmedName, updated_at: nowIso() } : s
-          ),
+          snippets: state.snippets.map((s) => (s.id === id ? updated : s)),
         }));
       }
 
       return true;
     } catch (error) {
       console.error('Failed to rename snippet:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return false;
     }
   },
@@ -204,7 +327,37 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
       }));
     } catch (error) {
       console.error('Failed to delete snippet:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
+    }
+  },
+
+  setSnippetKind: async (id: string, kind: SnippetKind) => {
+    try {
+      await invoke('set_snippet_kind', { id, kind });
+      set((state) => ({
+        snippets: state.snippets.map((s) => (s.id === id ? { ...s, kind, updated_at: nowIso() } : s)),
+      }));
+    } catch (error) {
+      console.error('Failed to change snippet type:', error);
+      set({ error: errorMessage(error) });
+    }
+  },
+
+  runSnippetCommand: async (id: string) => {
+    try {
+      const result = await invoke<CommandRunResult>('run_snippet_command', { id });
+      // Output goes to the editor; stderr is shown when there's nothing else
+      const output = result.stdout || result.stderr;
+      if (output) {
+        useEditorStore.getState().setContent(output);
+      }
+      return result;
+    } catch (error) {
+      if (errorCode(error) !== 'cancelled') {
+        console.error('Failed to run command snippet:', error);
+        set({ error: errorMessage(error) });
+      }
+      return null;
     }
   },
 
@@ -255,11 +408,10 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
       return true;
     } catch (error) {
       console.error('Failed to create gist from snippet:', error);
-      const message = String(error);
       set({
-        error: message.includes('Not authenticated')
+        error: errorCode(error) === 'unauthenticated'
           ? 'Not authenticated with GitHub. Please authorize in Settings.'
-          : message,
+          : errorMessage(error),
       });
       return false;
     }
@@ -307,7 +459,7 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
       return true;
     } catch (error) {
       console.error('Failed to sync snippet to GitHub:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return false;
     }
   },
@@ -366,11 +518,36 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
       return importedCount;
     } catch (error) {
       console.error('Failed to import Wingman gists:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return 0;
     }
   },
 
+  syncAllGists: async () => {
+    try {
+      const report = await invoke<GistSyncReport>('sync_all_gist_snippets');
+      await get().loadSnippets();
+      return report;
+    } catch (error) {
+      console.error('Failed to sync gists:', error);
+      set({ error: errorMessage(error) });
+      return null;
+    }
+  },
+
+  resolveGistConflict: async (id: string, resolution: GistConflictResolution) => {
+    try {
+      await invoke<Snippet>('resolve_gist_conflict', { snippetId: id, resolution });
+      // Duplicating adds a snippet, so reload rather than patch one entry
+      await get().loadSnippets();
+      return true;
+    } catch (error) {
+      console.error('Failed to resolve gist conflict:', error);
+      set({ error: errorMessage(error) });
+      return false;
+    }
+  },
+
   disconnectSnippetFromGitHub: async (id: string) => {
     const snippet = get().snippets.find((s) => s.id === id);
     if (!snippet || !snippet.github_gist_id) return false;
@@ -396,7 +573,7 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
       return true;
     } catch (error) {
       console.error('Failed to disconnect snippet from GitHub:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
       return false;
     }
   },
@@ -422,13 +599,23 @@ export const useSnippetsStore = create<SnippetsState>((set, get) => ({
     return { snippets: get().snippets };
   },
 
+  exportSnippetsAs: async (format: SnippetExportFormat, path: string, filter?: SnippetFilter) => {
+    try {
+      return await invoke<SnippetExportSummary>('export_snippets', { format, path, filter });
+    } catch (error) {
+      console.error('Failed to export snippets:', error);
+      set({ error: errorMessage(error) });
+      return null;
+    }
+  },
+
   importSnippets: async (data: SnippetsData) => {
     try {
       await invoke('save_snippets_data', { data });
       set({ snippets: data.snippets });
     } catch (error) {
       console.error('Failed to import snippets:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 }));
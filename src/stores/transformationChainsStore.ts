@@ -2,6 +2,7 @@ import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { TransformationChain, ChainStep, TransformationChainsData } from '../types';
 import { useCustomTransformationsStore } from './customTransformationsStore';
+import { errorMessage } from '../lib/appError';
 
 interface TransformationChainsState {
   chains: TransformationChain[];
@@ -34,6 +35,11 @@ const BUILTIN_TRANSFORMS = [
   'sort',
   'deduplicate',
   'reverse',
+  'numberlines',
+  'joinlines',
+  'splitlines',
+  'prefixsuffix',
+  'shufflelines',
 ];
 
 export const useTransformationChainsStore = create<TransformationChainsState>((set, get) => ({
@@ -54,7 +60,7 @@ export const useTransformationChainsStore = create<TransformationChainsState>((s
       set({
         chains: [],
         loading: false,
-        error: String(error),
+        error: errorMessage(error),
       });
     }
   },
@@ -67,7 +73,7 @@ export const useTransformationChainsStore = create<TransformationChainsState>((s
       });
     } catch (error) {
       console.error('Failed to save transformation chains:', error);
-      set({ error: String(error) });
+      set({ error: errorMessage(error) });
     }
   },
 
@@ -143,6 +149,7 @@ export const useTransformationChainsStore = create<TransformationChainsState>((s
           currentText = await invoke<string>('transform_text_cmd', {
             text: currentText,
             transform: step.transformId,
+            params: step.params,
           });
         } else if (step.type === 'custom') {
           // Execute custom transformation
@@ -154,7 +161,7 @@ export const useTransformationChainsStore = create<TransformationChainsState>((s
           currentText = result.result!;
         }
       } catch (error) {
-        return { success: false, error: `Step "${step.name}" failed: ${String(error)}` };
+        return { success: false, error: `Step "${step.name}" failed: ${errorMessage(error)}` };
       }
     }
 
@@ -25,6 +25,7 @@ export interface AppSettings {
   default_language: string;
   window_position: WindowPosition;
   sticky_mode: boolean;
+  panel_placement: PanelPlacement; // Display the panel opens on (macOS)
   show_diff_preview: boolean;
   primary_action: PrimaryAction;
   export_action: ExportAction;
@@ -36,6 +37,158 @@ export interface AppSettings {
   show_tips: boolean;
   show_intelligent_suggestions: boolean;
   toolbar_order: string[]; // Array of toolbar item IDs in display order
+  update_channel: 'stable' | 'beta';
+  auto_download_updates: boolean; // Download in the background, prompt only to restart
+  profile_switch_hotkey: string | null; // Global shortcut cycling through profiles
+  semantic_search_enabled: boolean;
+  summary_backend: SummaryBackend;
+  lan_sync_enabled: boolean;
+  ai_history_enabled: boolean; // Record AI prompts and responses
+  ai_history_retention_days: number; // 0 = keep forever
+  trash_retention_days: number; // 0 = keep until the trash is emptied
+  notifications: NotificationSettings;
+  quick_save: QuickSaveSettings;
+  local_api_enabled: boolean; // Localhost API for launcher extensions
+  history_limits: HistoryLimits;
+  event_bridge: EventBridgeSettings;
+  audit_log_enabled: boolean; // Append command invocations to command_audit.log
+  proxy: ProxySettings;
+  tls: TlsSettings;
+  offline_mode: boolean; // Keep every network feature off (air-gapped machines)
+  command_runner: CommandRunnerSettings;
+  window_presets: WindowPreset[]; // Sizes cycle_window_preset steps through
+  window_preset_hotkey: string | null; // Global shortcut for cycle_window_preset
+  panel_animation: PanelAnimationSettings;
+  authentication_gate: AuthenticationGate; // Actions that need Touch ID / Windows Hello first
+  date_time: DateTimeSettings; // How unix_to_human and format_timestamp show times
+  rich_to_markdown: RichToMarkdownSettings; // Keep HTML/RTF clipboard captures as Markdown
+}
+
+export interface RichToMarkdownSettings {
+  enabled: boolean;
+  apps: string[]; // Source app names or bundle ids; empty means every app
+}
+
+export interface DateTimeSettings {
+  date_pattern: string; // ICU-style, e.g. 'yyyy-MM-dd' or 'dd.MM.yyyy'
+  clock_24h: boolean;
+  timezone: string; // 'local', an IANA zone like 'America/New_York', 'UTC', 'PST' (DST-aware) or an offset like '+05:30'
+}
+
+// format_timestamp: a named style built from DateTimeSettings, or any ICU-style pattern
+export type TimestampStyle = 'date' | 'time' | 'datetime' | 'iso' | string;
+
+export interface AuthenticationGate {
+  reveal_credentials: boolean; // reveal_credential
+  sensitive_entries: boolean; // Card numbers, keys and tokens are listed masked until get_entry_content authenticates
+  disable_privacy_mode: boolean;
+}
+
+// 'active_window' falls back to the cursor's display when the focused window can't be found
+export type PanelPlacement = 'cursor' | 'active_window';
+
+export type PanelAnimation = 'none' | 'fade' | 'slide_from_top';
+
+// check_hotkey_conflicts: what a shortcut would clash with
+export interface HotkeyConflict {
+  kind: 'system' | 'app' | 'wingman' | 'unavailable'; // 'app' is a popular app's default, maybe not installed
+  owner: string; // e.g. 'Spotlight'
+  message: string; // e.g. 'Command+Space is taken by Spotlight'
+}
+
+// get_accessibility_prefs and the accessibility-changed event
+export interface AccessibilityPrefs {
+  reduce_motion: boolean;
+  increase_contrast: boolean;
+  reduce_transparency: boolean; // Reason to drop the vibrancy
+  screen_reader: boolean; // VoiceOver, Narrator/NVDA or Orca is running
+}
+
+export interface PanelAnimationSettings {
+  show: PanelAnimation;
+  hide: PanelAnimation;
+  duration_ms: number;
+  reduce_motion: boolean; // Fades instead of slides (the system Reduce Motion setting does the same)
+}
+
+// 'system' follows proxy environment variables and the OS settings
+export type ProxyMode = 'system' | 'direct' | 'manual';
+
+// The manual proxy's password is stored in the keychain with set_proxy_password
+export interface ProxySettings {
+  mode: ProxyMode;
+  server: string; // host:port or a full URL
+  username: string; // Empty for no authentication
+  bypass: string; // Comma-separated hosts, domains and IP ranges
+}
+
+export interface TlsSettings {
+  ca_bundle_path: string; // PEM file of extra trusted CAs; empty for none
+  supabase_pins: string[]; // "sha256/<base64>" SPKI hashes; empty disables pinning
+}
+
+// How command snippets run; only allowlisted environment variables reach the command
+export interface CommandRunnerSettings {
+  shell: string | null; // $SHELL (cmd on Windows) when null
+  working_dir: string | null; // Scratch folder in app data when null
+  timeout_secs: number;
+  env_allowlist: string[];
+  confirm_before_run: boolean;
+  save_output_to_history: boolean;
+}
+
+// 'center' resizes around the window's center; 'right_edge' docks it to the screen edge
+export type WindowAnchor = 'center' | 'right_edge';
+
+export interface WindowPreset {
+  name: string;
+  width: number;
+  height?: number | null; // null fills the screen's visible height
+  anchor?: WindowAnchor;
+}
+
+// Localhost WebSocket broadcasting app activity; each flag enables one event type
+export interface EventBridgeSettings {
+  enabled: boolean;
+  entry_added: boolean;
+  transform_run: boolean;
+  ai_call_completed: boolean;
+}
+
+// Entries over max_entry_bytes are overflowed to a file or truncated with a notice
+export interface HistoryLimits {
+  max_entry_bytes: number;
+  oversized: 'overflow' | 'truncate';
+}
+
+export type OverwritePolicy = 'rename' | 'overwrite' | 'fail';
+
+// Where quick_save writes without a dialog
+export interface QuickSaveSettings {
+  directory: string | null; // Downloads folder when null
+  filename_template: string; // {{date}}, {{time}}, {{datetime}}, {{language}}, {{ext}}
+  overwrite: OverwritePolicy;
+}
+
+// Per-category switches for system notifications
+export interface NotificationSettings {
+  formatting: boolean;
+  ai: boolean;
+  gist_sync: boolean;
+  export: boolean;
+  reminders: boolean;
+  only_when_hidden: boolean; // Skip operation notifications while the panel is visible
+}
+
+export type NotificationCategory = 'formatting' | 'ai' | 'gist_sync' | 'export' | 'reminder';
+
+// Payload of the notification-clicked event
+export interface AppNotification {
+  id: string;
+  category: NotificationCategory;
+  title: string;
+  body: string;
+  entry_id: number | null;
 }
 
 export interface HistoryEntry {
@@ -48,6 +201,76 @@ export interface HistoryEntry {
   language: string | null;
   tags: string | null;
   images: string | null; // JSON array of EditorImage objects
+  html: string | null; // Rich HTML variant, if captured with formatting
+  paste_service: string | null; // Set once published to a paste service
+  paste_url: string | null;
+  paste_published_at: string | null;
+  expires_at: string | null; // UTC; the entry self-destructs after this
+  summary: string | null; // Generated by summarize_text
+  title: string | null; // Generated by suggest_title
+  smart_tags: string | null; // Comma-separated SmartTag values detected on add
+  reminder_at: string | null; // UTC; a notification fires and the panel opens on this entry
+  reminder_note: string | null;
+  content_truncated: boolean; // content is a preview; fetch the rest with get_entry_content
+  source_app: string | null; // App Wingman was opened over when the entry was saved (macOS)
+  source_bundle_id: string | null;
+  source_window_title: string | null;
+}
+
+export type AnalyticsRange = 'day' | 'week' | 'month' | 'quarter' | 'year' | 'all';
+export type AnalyticsGranularity = 'hour' | 'day' | 'week' | 'month';
+
+export interface HistoryAnalytics {
+  range: AnalyticsRange;
+  granularity: AnalyticsGranularity;
+  total_entries: number;
+  total_characters: number;
+  total_words: number;
+  total_bytes: number;
+  overflowed_entries: number;
+  timeline: { bucket: string; entries: number; characters: number }[];
+  top_languages: { name: string; count: number }[];
+  top_source_apps: { name: string; count: number }[];
+  busiest_hours: number[]; // 24 local hours
+  busiest_weekdays: number[]; // Sunday first
+}
+
+export interface SourceAppCount {
+  name: string;
+  bundle_id: string | null;
+  count: number;
+}
+
+export type SmartTag = 'url' | 'email' | 'json' | 'sql' | 'stack_trace' | 'credit_card' | 'address' | 'secret';
+
+// search_history_advanced: every field that's set narrows the results
+export interface HistoryFilter {
+  query?: string;
+  languages?: string[]; // Any of
+  tags?: string[]; // All of
+  smart_tags?: SmartTag[]; // Any of
+  from?: string; // RFC 3339, inclusive
+  to?: string; // RFC 3339, exclusive
+  has_images?: boolean;
+  min_length?: number; // Characters, inclusive
+  max_length?: number;
+  source_app?: string; // Name or bundle id
+}
+
+// A named HistoryFilter shown as a smart folder (save_search, list_saved_searches)
+export interface SavedSearch {
+  id: number;
+  name: string;
+  filter: HistoryFilter;
+  count: number | null; // Matching entries, refreshed lazily after history changes
+  created_at: string;
+}
+
+export interface HistoryEntryVersion {
+  entry_id: number;
+  version: number;
+  content: string;
+  created_at: string;
 }
 
 export interface HistoryStats {
@@ -68,12 +291,104 @@ export interface Snippet {
   github_gist_filename?: string | null;
   github_synced_at?: string | null;
   github_source?: string | null;
+  favorite?: boolean; // Listed in the tray's Snippets submenu
+  kind?: SnippetKind;
+  version?: number; // Bumped on each edit; update_snippet rejects edits against an older version
+  source?: SnippetSource | null; // Content comes from this URL and can't be edited locally
+}
+
+// Set with set_snippet_source; refresh_snippet_from_source fetches it now
+export interface SnippetSource {
+  url: string;
+  refresh_minutes?: number | null; // null refreshes only on request
+  etag?: string | null;
+  last_fetched?: string | null;
+  last_error?: string | null;
+}
+
+// 'command' snippets are shell commands, run with run_snippet_command instead of pasted
+export type SnippetKind = 'text' | 'command';
+
+export interface CommandRunResult {
+  stdout: string;
+  stderr: string;
+  exit_code: number | null; // null when killed
+  timed_out: boolean;
+  truncated: boolean;
+  duration_ms: number;
+  history_id: number | null;
 }
 
 export interface SnippetsData {
   snippets: Snippet[];
 }
 
+export interface GistSyncConflict {
+  snippet_id: string;
+  snippet_name: string;
+  gist_id: string;
+  local_content: string;
+  remote_content: string;
+  local_updated_at: string;
+  remote_updated_at: string;
+  synced_at: string | null;
+}
+
+export interface GistSyncFailure {
+  snippet_id: string;
+  snippet_name: string;
+  gist_id: string;
+  error: string;
+}
+
+export interface GistSyncReport {
+  pushed: string[]; // Snippet ids
+  pulled: string[];
+  unchanged: number;
+  conflicts: GistSyncConflict[];
+  failures: GistSyncFailure[];
+}
+
+export type GistConflictResolution = 'keep_local' | 'keep_remote' | 'duplicate';
+
+export type SnippetExportFormat = 'vscode' | 'alfred' | 'files';
+
+export interface SnippetFilter {
+  ids?: string[];
+  tags?: string[]; // Any of these
+  favorites_only?: boolean;
+}
+
+export interface SnippetExportSummary {
+  path: string; // Folder for vscode/files, bundle for alfred
+  snippets: number;
+  files: number;
+}
+
+export interface AIInteraction {
+  id: number;
+  feature: string; // Preset id for preset calls
+  preset_name: string | null;
+  prompt: string;
+  system_instructions: string | null;
+  response: string;
+  tokens_used: number;
+  rerun_of: number | null;
+  created_at: string;
+}
+
+export type PromptVariableType = 'text' | 'multiline' | 'number' | 'boolean' | 'select';
+
+// Referenced as {{name}} in system_prompt / user_prompt
+export interface PromptVariable {
+  name: string;
+  label: string;
+  type: PromptVariableType;
+  default?: string | null;
+  required?: boolean;
+  options?: string[]; // For 'select'
+}
+
 export interface CustomAIPrompt {
   id: string;
   name: string;
@@ -82,6 +397,22 @@ export interface CustomAIPrompt {
   enabled: boolean;
   created_at: string;
   updated_at: string;
+  user_prompt: string | null; // {{text}} is the input; null sends the input as-is
+  variables: PromptVariable[];
+}
+
+// Counts from import_ai_prompts; duplicates matched an installed item by content
+export interface PromptLibraryKindCounts {
+  added: number;
+  duplicates: number;
+}
+
+export interface PromptLibraryImportSummary {
+  ai_presets: PromptLibraryKindCounts;
+  custom_prompts: PromptLibraryKindCounts;
+  transformations: PromptLibraryKindCounts;
+  chains: PromptLibraryKindCounts;
+  workflows: PromptLibraryKindCounts;
 }
 
 export interface CustomAIPromptsData {
@@ -95,6 +426,35 @@ export interface TextStats {
   paragraph_count: number;
 }
 
+// Quick actions palette entries from the backend registry (list_actions / execute_action)
+export type ActionOutput = 'replace' | 'append' | 'side_effect';
+
+export interface ActionInfo {
+  id: string; // "transform:<name>", a command name, "lorem:<n>", "window:<command>" or "snippet:<id>"
+  title: string;
+  description: string;
+  section: string;
+  keywords: string[];
+  pro_feature: string | null;
+  locked: boolean; // Needs a Pro license the user doesn't have
+  needs_input: boolean;
+  output: ActionOutput;
+}
+
+export interface ActionResult {
+  output: ActionOutput;
+  text: string | null;
+}
+
+// A tool from get_suggested_actions, ranked by content kind and past use
+export interface SuggestedAction {
+  id: string; // Command name, detector action id, or "transform:<name>"
+  title: string;
+  kind: string; // Content kind it was suggested for; pass back to record_action_use
+  score: number;
+  locked: boolean; // Needs a Pro license
+}
+
 export type TextTransform =
   | 'uppercase'
   | 'lowercase'
@@ -106,7 +466,20 @@ export type TextTransform =
   | 'trim'
   | 'sort'
   | 'deduplicate'
-  | 'reverse';
+  | 'reverse'
+  | 'bulletlist'
+  | 'numberlines'
+  | 'joinlines'
+  | 'splitlines'
+  | 'prefixsuffix'
+  | 'shufflelines';
+
+// Arguments of the parameterized transforms; a typed \n or \t means a newline or tab
+export interface TransformParams {
+  separator?: string; // joinlines (default ', ') and splitlines (default ',')
+  prefix?: string; // prefixsuffix
+  suffix?: string;
+}
 
 export type PanelType = 'editor' | 'settings' | 'history' | 'snippets' | 'customTransformations' | 'chains' | 'customAIPrompts' | 'regexPlayground';
 
@@ -227,6 +600,753 @@ export interface WingmanGist {
   is_public: boolean;
 }
 
+export interface UrlMetadata {
+  url: string; // Final URL after redirects
+  title: string | null;
+  description: string | null;
+  favicon_url: string | null;
+  content_type: string | null;
+  site_name: string | null;
+}
+
+export interface PrivacyStatus {
+  enabled: boolean;
+  resumes_at: string | null; // RFC 3339; null when paused indefinitely
+}
+
+export type PermissionKind = 'accessibility' | 'automation' | 'screen_recording';
+
+export type PermissionState = 'granted' | 'denied' | 'not_determined' | 'unknown' | 'not_applicable';
+
+export interface PermissionStatus {
+  kind: PermissionKind;
+  state: PermissionState;
+  description: string;
+}
+
+export interface GrammarConfig {
+  server_url: string; // LanguageTool server; self-hosted or https://api.languagetool.org
+  username: string | null; // LanguageTool Premium; API key is stored in the keychain
+  mother_tongue: string | null;
+  disabled_rules: string[];
+  picky: boolean;
+}
+
+export type GrammarIssueKind = 'spelling' | 'grammar' | 'style' | 'punctuation' | 'other';
+
+export interface GrammarIssue {
+  offset: number; // UTF-16 offset (JS string index)
+  length: number;
+  message: string;
+  short_message: string | null;
+  replacements: string[];
+  kind: GrammarIssueKind;
+  rule_id: string;
+  category: string;
+}
+
+export interface GrammarCheckResult {
+  issues: GrammarIssue[];
+  language: string | null;
+}
+
+export interface CodeImageOptions {
+  padding?: number; // Points around the window (default 48)
+  background?: string; // '#rrggbb' or 'transparent'
+  window_chrome?: boolean;
+  title?: string | null;
+  line_numbers?: boolean;
+  font_size?: number;
+  font_path?: string | null; // Defaults to the system monospace font
+  scale?: number; // Pixels per point (default 2)
+  copy_to_clipboard?: boolean; // Default true
+}
+
+export interface CodeImage {
+  width: number;
+  height: number;
+  data_url: string;
+  copied: boolean;
+}
+
+export interface LanPeer {
+  id: string;
+  name: string;
+  address: string | null; // "ip:port" while visible on the network
+  paired: boolean;
+  online: boolean;
+  sync: PeerSyncSettings | null; // null for unpaired devices
+}
+
+export interface PeerSyncSettings {
+  auto_sync: boolean; // Push new clips to this device and accept its clips into history
+  include_images: boolean; // Off = text only
+  max_bytes: number; // Larger clips are not synced automatically (default 1 MB)
+}
+
+export interface PairedPeer {
+  id: string;
+  name: string;
+  paired_at: string;
+  sync: PeerSyncSettings;
+}
+
+export interface PairingCode {
+  peer_id: string;
+  peer_name: string;
+  code: string; // Six digits shown on both devices
+}
+
+export interface LanItem {
+  content: string;
+  html: string | null;
+  images: string | null;
+  language: string | null;
+  expires_at: string | null; // UTC "YYYY-MM-DD HH:MM:SS"
+  sent_at: number; // Unix seconds on the sender
+}
+
+export type TransferDirection = 'sent' | 'received';
+export type TransferStatus = 'delivered' | 'skipped' | 'rejected' | 'failed';
+
+export interface TransferRecord {
+  timestamp: string;
+  peer_id: string;
+  peer_name: string;
+  direction: TransferDirection;
+  automatic: boolean;
+  bytes: number;
+  has_images: boolean;
+  status: TransferStatus;
+  detail: string | null;
+}
+
+// Payload of the "lan-item-received" event
+export interface ReceivedLanItem {
+  peer_id: string;
+  peer_name: string;
+  item: LanItem;
+}
+
+export type RuleTrigger =
+  | { type: 'new_entry'; pattern?: string | null; language?: string | null }
+  | { type: 'hotkey'; shortcut: string }
+  | { type: 'schedule'; interval_minutes: number };
+
+export type RuleCondition =
+  | { type: 'matches'; pattern: string }
+  | { type: 'not_matches'; pattern: string }
+  | { type: 'language'; language: string }
+  | { type: 'smart_tag'; tag: SmartTag }
+  | { type: 'min_length'; chars: number }
+  | { type: 'max_length'; chars: number };
+
+export type RuleAction =
+  | { type: 'transform'; transforms: string[] } // Built-in TextTransform names
+  | { type: 'webhook'; url: string }
+  | { type: 'obsidian' }
+  | { type: 'tag'; tags: string[] }
+  | { type: 'expire'; after_minutes: number }; // Makes matching entries ephemeral
+
+export interface RuleInput {
+  name: string;
+  enabled?: boolean;
+  trigger: RuleTrigger;
+  conditions?: RuleCondition[];
+  actions: RuleAction[];
+}
+
+export interface Rule extends Required<RuleInput> {
+  id: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface RuleActionOutcome {
+  action: RuleAction['type'];
+  ok: boolean;
+  detail: string;
+}
+
+export interface RuleRun {
+  id: number;
+  rule_id: number;
+  rule_name: string;
+  trigger: RuleTrigger['type'];
+  entry_id: number | null;
+  status: 'success' | 'partial' | 'failed';
+  outcomes: RuleActionOutcome[];
+  created_at: string;
+}
+
+export interface RuleDryRun {
+  trigger_matched: boolean;
+  conditions: { condition: RuleCondition; passed: boolean }[];
+  would_run: boolean;
+  actions: RuleActionOutcome[]; // What each action would do
+  output: string; // Content after transform actions
+}
+
+// Launcher extensions (Alfred/Raycast). Action ids are "<copy|paste|open>:<snippet|history>:<id>"
+export interface LauncherItem {
+  title: string;
+  subtitle: string;
+  icon: string; // 'snippet', 'image', or the entry's language
+  action_id: string;
+}
+
+// Payload of `launcher-open`
+export interface LauncherOpen {
+  kind: 'snippet' | 'history';
+  id: string;
+}
+
+export interface LocalApiInfo {
+  running: boolean;
+  port: number | null;
+  token: string;
+}
+
+// Event bridge status; clients authenticate with LocalApiInfo.token
+export interface EventBridgeInfo {
+  running: boolean;
+  port: number | null;
+  clients: number;
+}
+
+// Messages sent to event bridge clients (activity only, never content)
+export type BridgeEvent = { at: string } & (
+  | { type: 'entry_added'; id: number; language: string | null; characters: number; lines: number }
+  | { type: 'transform_run'; transform: string; input_characters: number; output_characters: number }
+  | { type: 'ai_call_completed'; feature: string; tokens_used: number; tokens_remaining: number }
+);
+
+// Per-command counts and timings for the diagnostics view (current session only)
+export interface CommandStat {
+  command: string;
+  calls: number;
+  failures: number;
+  rate_limited: number;
+  total_ms: number;
+  max_ms: number;
+  last_called_at: string;
+}
+
+export interface CommandStats {
+  since: string;
+  audit_log_path: string | null; // Set while the audit log is on
+  commands: CommandStat[]; // Busiest first
+}
+
+// SSH key tools (nothing is saved unless save_to is given)
+export type SshKeyAlgorithm = 'ed25519' | 'rsa';
+export type SshKeyFormat = 'openssh' | 'pem'; // PEM means PKCS#8 / SubjectPublicKeyInfo
+
+export interface SshKeyFingerprint {
+  key_type: string; // 'ssh-ed25519' or 'ssh-rsa'
+  bits: number;
+  comment: string | null;
+  sha256: string; // "SHA256:..." as printed by ssh-keygen -l
+  md5: string; // "MD5:aa:bb:..."
+}
+
+export interface SshKeyPair {
+  algorithm: SshKeyAlgorithm;
+  format: SshKeyFormat;
+  private_key: string;
+  public_key: string; // authorized_keys line
+  fingerprint: SshKeyFingerprint;
+  saved_to: string | null;
+}
+
+// Certificate decoding (decode_certificate returns one entry per cert in a PEM chain)
+export interface PublicKeySummary {
+  algorithm: string; // 'RSA' | 'EC' | 'Ed25519' | ...
+  bits: number | null;
+  curve: string | null;
+}
+
+export interface CertificateInfo {
+  subject: string;
+  subject_common_name: string | null;
+  issuer: string;
+  issuer_common_name: string | null;
+  self_signed: boolean;
+  version: number;
+  serial_number: string;
+  subject_alt_names: string[]; // "DNS:example.com", "IP:10.0.0.1", ...
+  not_before: string;
+  not_after: string;
+  expires_in_seconds: number; // negative once expired
+  days_remaining: number;
+  expired: boolean;
+  not_yet_valid: boolean;
+  public_key: PublicKeySummary;
+  signature_algorithm: string;
+  is_ca: boolean;
+  key_usage: string[];
+  extended_key_usage: string[];
+  sha256_fingerprint: string;
+  sha1_fingerprint: string;
+}
+
+export interface CsrInfo {
+  subject: string;
+  subject_common_name: string | null;
+  subject_alt_names: string[];
+  public_key: PublicKeySummary;
+  signature_algorithm: string;
+  key_usage: string[];
+  extended_key_usage: string[];
+}
+
+// Network helpers (analyze_ip, expand_cidr, dns_lookup)
+export type IpScope =
+  | 'public'
+  | 'private'
+  | 'loopback'
+  | 'link_local'
+  | 'shared'
+  | 'multicast'
+  | 'broadcast'
+  | 'documentation'
+  | 'unspecified'
+  | 'reserved';
+
+export interface IpAnalysis {
+  address: string;
+  version: 4 | 6;
+  scope: IpScope;
+  is_public: boolean;
+  ipv4_mapped: string | null;
+  reverse_pointer: string;
+  reverse_dns: string | null; // null without a PTR record or in offline mode
+}
+
+export interface CidrInfo {
+  version: 4 | 6;
+  prefix_len: number;
+  network: string;
+  broadcast: string | null; // IPv4 only
+  netmask: string;
+  wildcard: string;
+  first_host: string;
+  last_host: string;
+  total_addresses: string; // decimal string; IPv6 counts overflow JS numbers
+  usable_hosts: string;
+  host_bits_set: boolean;
+  addresses: string[]; // first 1024 at most
+  truncated: boolean;
+}
+
+export type DnsRecordType = 'A' | 'AAAA' | 'CNAME' | 'MX' | 'TXT' | 'NS' | 'SOA' | 'SRV' | 'CAA' | 'PTR';
+
+export interface DnsRecord {
+  name: string;
+  record_type: string;
+  ttl: number;
+  value: string;
+}
+
+export interface DnsLookupResult {
+  host: string;
+  record_type: DnsRecordType;
+  records: DnsRecord[];
+  duration_ms: number;
+}
+
+// URL and user-agent inspection
+export interface QueryParam {
+  key: string;
+  value: string; // percent-decoded
+}
+
+export interface ParsedUrl {
+  href: string;
+  scheme: string;
+  scheme_assumed: boolean; // no scheme in the input; https was assumed
+  username: string | null;
+  password: string | null;
+  host: string | null;
+  port: number | null;
+  effective_port: number | null;
+  path: string;
+  path_segments: string[];
+  query: string | null;
+  query_params: QueryParam[];
+  fragment: string | null;
+}
+
+export interface UrlComponents {
+  scheme: string;
+  host: string;
+  username?: string | null;
+  password?: string | null;
+  port?: number | null;
+  path?: string | null;
+  query_params?: QueryParam[];
+  fragment?: string | null;
+}
+
+export interface UserAgentInfo {
+  browser: string | null;
+  browser_version: string | null;
+  engine: string | null;
+  os: string | null;
+  os_version: string | null;
+  device_type: 'desktop' | 'mobile' | 'tablet' | 'bot' | 'other';
+  is_bot: boolean;
+}
+
+// JSON tools (query_json)
+export interface JsonMatch {
+  path: string; // normalized JSONPath, e.g. $['items'][0]
+  pointer: string; // JSON Pointer, e.g. /items/0
+  value: unknown;
+}
+
+export interface JsonQueryResult {
+  matches: JsonMatch[];
+  output: string; // pretty-printed value (single match) or array of values
+}
+
+// Binary payload decoding (decode_base64_binary)
+export type BinaryFormat = 'msgpack' | 'cbor' | 'protobuf';
+
+export interface BinaryDecodeResult {
+  format: BinaryFormat;
+  value: unknown; // bytes as "0x..." hex; protobuf fields keyed by number
+  output: string;
+  bytes: number;
+  framing: 'length_prefix' | 'grpc' | null;
+  alternatives: BinaryFormat[]; // other formats that also decoded the whole payload
+}
+
+export type HexDumpInput = 'text' | 'base64' | 'hex';
+
+export interface HexDumpOptions {
+  input?: HexDumpInput;
+  bytes_per_line?: number;
+  group_size?: number;
+  uppercase?: boolean;
+  ascii?: boolean;
+}
+
+export interface DecodedBytes {
+  text: string | null;
+  base64: string;
+  hex: string;
+  bytes: number;
+}
+
+export type CharacterCategory =
+  | 'smileys_and_emotion'
+  | 'people_and_body'
+  | 'animals_and_nature'
+  | 'food_and_drink'
+  | 'travel_and_places'
+  | 'activities'
+  | 'objects'
+  | 'symbols'
+  | 'flags'
+  | 'arrows'
+  | 'math'
+  | 'currency'
+  | 'punctuation'
+  | 'latin'
+  | 'greek'
+  | 'technical'
+  | 'shapes'
+  | 'box_drawing';
+
+export interface CharacterEntry {
+  character: string;
+  name: string;
+  category: CharacterCategory;
+  keywords: string[];
+  codepoints: string[];
+}
+
+export interface BulkTemplateOptions {
+  format?: 'auto' | 'csv' | 'tsv' | 'json';
+  separator?: string;
+  has_header?: boolean;
+}
+
+export interface BulkTemplateResult {
+  output: string;
+  rows: number;
+  columns: string[];
+  missing_fields: string[];
+}
+
+export type FakeDataLocale = 'en_US' | 'en_GB' | 'de_DE' | 'fr_FR' | 'es_ES';
+
+/** Options for generate_bulk's fake data generators (name, email, address, json, ...) */
+export interface FakeDataOptions {
+  locale?: FakeDataLocale;
+  seed?: number;
+  date_from?: string;
+  date_to?: string;
+  schema?: unknown;
+}
+
+// Links between history entries, snippets and AI interactions (provenance)
+export type ItemKind = 'history' | 'snippet' | 'ai';
+
+export interface ItemRef {
+  kind: ItemKind;
+  id: string;
+}
+
+export interface ItemLink {
+  id: number;
+  source: ItemRef;
+  target: ItemRef;
+  relation: string;
+  created_at: string;
+}
+
+export interface LinkedItem {
+  link_id: number;
+  relation: string;
+  direction: 'outgoing' | 'incoming';
+  item: ItemRef;
+  label: string | null;
+  created_at: string;
+}
+
+export interface DuplicateMember {
+  id: string;
+  name: string;
+  similarity: number; // to the canonical snippet, 0-1
+}
+
+export interface DuplicateGroup {
+  canonical_id: string;
+  canonical_reason: string;
+  members: DuplicateMember[]; // canonical first
+  exact: boolean;
+}
+
+// Trash: deleted history entries and snippets, restorable until purged
+export interface TrashedEntry extends HistoryEntry {
+  deleted_at: string;
+}
+
+export interface TrashedSnippet extends Snippet {
+  deleted_at: string;
+}
+
+export interface TrashContents {
+  history: TrashedEntry[];
+  snippets: TrashedSnippet[];
+}
+
+export interface PurgedCounts {
+  history: number;
+  snippets: number;
+}
+
+// Bulk history actions
+export type BulkExportFormat = 'json' | 'csv' | 'markdown' | 'text';
+
+export interface BulkExportSummary {
+  path: string;
+  entries: number;
+}
+
+// What list_credentials returns about a keychain secret; never the secret itself
+export interface CredentialRecord {
+  service: string; // 'github', 'translate', 'grammar', 'lan_sync', ...
+  account: string; // Pass to rotate_credential / revoke_credential
+  label: string;
+  created_at: string;
+  rotated_at: string | null;
+}
+
+// A snippet as it was before an edit; the current version is the snippet itself
+export interface SnippetVersion {
+  snippet_id: string;
+  version: number; // The snippet's version while this copy was current
+  name: string;
+  content: string;
+  tags: string[];
+  created_at: string; // When this copy was replaced; for the current snippet, its last edit
+}
+
+// Two versions from compare_snippet_versions; the store diffs them
+export interface SnippetComparison {
+  from: SnippetVersion;
+  to: SnippetVersion;
+}
+
+// Result of compact_database: large entries compressed, then the database VACUUMed
+export interface CompactReport {
+  compressed_entries: number;
+  size_before: number;
+  size_after: number;
+  saved: string; // e.g. "12.4 MB"
+}
+
+// Encrypted everything bundle (export_all_data / import_all_data)
+export interface ExportReport {
+  path: string;
+  files: number;
+  databases: number;
+  secrets: number; // Keychain entries, only with include_secrets
+  bytes: number;
+}
+
+export interface ImportReport {
+  files_written: number;
+  files_kept: number; // Merge mode keeps local copies
+  history_entries: number; // Restored (replace) or added (merge)
+  snippets_added: number;
+  secrets_restored: number;
+  restart_recommended: boolean;
+}
+
+// Everything on the clipboard right now (read_clipboard_inventory)
+export type ClipboardFlavorKind = 'text' | 'image' | 'files' | 'data';
+
+export interface ClipboardFlavor {
+  item: number;
+  type_id: string; // UTI on macOS, MIME type or atom on Linux
+  size: number;
+  kind: ClipboardFlavorKind;
+  preview: string | null;
+  image_width: number | null;
+  image_height: number | null;
+  file_names: string[];
+}
+
+export interface ClipboardInventory {
+  items: number;
+  flavors: ClipboardFlavor[];
+  total_size: number;
+  concealed: boolean; // Marked by a password manager; no previews
+}
+
+// Removing all app data before uninstalling (prepare_remove_all_app_data, then remove_all_app_data)
+export interface RemovalPlan {
+  confirm_token: string;
+  expires_in_secs: number;
+  paths: string[];
+  credentials: string[]; // Keychain accounts that may hold a secret
+  launch_at_login: boolean;
+}
+
+export interface RemovalReport {
+  directories: string[];
+  temp_files: number;
+  credentials: string[]; // Keychain accounts that held a secret
+  login_item_removed: boolean;
+  failures: string[];
+}
+
+// Watch folders: new files are ingested as history entries or snippets
+export type WatchTarget = 'history' | 'snippet';
+
+export interface WatchFolder {
+  id: string;
+  path: string;
+  target: WatchTarget;
+  patterns: string[]; // Globs matched against file names; empty accepts every file
+  max_bytes: number;
+  recursive: boolean;
+  created_at: string;
+}
+
+// Payload of the "watch-folder-ingested" event
+export interface IngestedFile {
+  folder_id: string;
+  path: string;
+  target: WatchTarget;
+  item_id: string; // History entry id or snippet id
+}
+
+export type TraceLanguage = 'javascript' | 'python' | 'rust' | 'java';
+
+export interface StackTraceOptions {
+  hide_noise?: boolean; // Default true: fold node_modules/asyncio/std/JDK frames
+  demangle?: boolean; // Default true
+  source_map?: string | null; // v3 source map JSON for minified JS traces
+}
+
+export interface StackFrame {
+  function: string | null;
+  file: string | null;
+  line: number | null;
+  column: number | null;
+  code: string | null; // Python source line
+  noise: boolean;
+  mapped: boolean; // Resolved through the source map
+}
+
+export interface FormattedTrace {
+  language: TraceLanguage;
+  frames: StackFrame[];
+  hidden_frames: number;
+  formatted: string;
+}
+
+export type SummaryBackend = 'ai' | 'local';
+
+export type SummaryLength = 'short' | 'medium' | 'long';
+
+export interface SummaryResult {
+  text: string;
+  backend: SummaryBackend;
+  tokens_used: number | null; // Only for the AI backend
+}
+
+export type TranslationBackend = 'ai' | 'deepl' | 'google';
+
+export interface TranslationLanguage {
+  code: string; // e.g. 'en', 'pt-BR', 'zh-CN'
+  name: string;
+}
+
+export interface TranslationResult {
+  text: string;
+  source_lang: string | null; // Detected when not given
+  target_lang: string;
+  backend: TranslationBackend;
+  tokens_used: number | null; // AI backend only
+}
+
+export interface SemanticMatch {
+  source: 'history' | 'snippet';
+  id: string; // History entry id (as a string) or snippet id
+  score: number; // Cosine similarity, 0-1
+  preview: string;
+}
+
+export interface Profile {
+  id: string;
+  name: string;
+  created_at: string;
+}
+
+export interface ProfilesData {
+  active: string;
+  profiles: Profile[];
+}
+
+export interface Draft {
+  id: number;
+  content: string;
+  metadata: Record<string, unknown> | null; // Editor state saved with the text
+  created_at: string;
+  updated_at: string;
+}
+
+export interface LaunchAtLoginStatus {
+  enabled: boolean;
+  requires_approval: boolean; // macOS: must be allowed in Login Items
+}
+
 export interface AIConfig {
   system_instructions: string;
 }
@@ -269,6 +1389,13 @@ export interface ClipboardItem {
   preview: string; // First 100 chars for display
 }
 
+// read_clipboard_capture
+export interface ClipboardCapture {
+  text: string | null; // Markdown when converted, the plain text otherwise
+  converted: boolean;
+  source_app: string | null; // App the copy came from, when known (macOS)
+}
+
 // Custom text transformation types
 export interface CustomTransformation {
   id: string;
@@ -293,6 +1420,7 @@ export interface ChainStep {
   type: 'builtin' | 'custom';
   transformId: string;  // e.g., 'uppercase' or custom UUID
   name: string;         // Display name
+  params?: TransformParams; // joinlines, splitlines and prefixsuffix
 }
 
 export interface TransformationChain {
@@ -308,3 +1436,92 @@ export interface TransformationChain {
 export interface TransformationChainsData {
   chains: TransformationChain[];
 }
+
+// Background indexer (smart tags, semantic vectors)
+export interface IndexStatus {
+  queued: number;
+  failed: number; // Gave up after repeated errors; rebuild_index retries them
+  indexing: boolean;
+}
+
+// Payload of `index-progress`, emitted after each batch
+export interface IndexProgress {
+  indexed: number;
+  remaining: number;
+  failed: number;
+}
+
+// Dev tools: timings from benchmark_history_ops, in milliseconds
+export interface HistoryBenchmark {
+  entries: number;
+  insert_ms: number;
+  list_ms: number;
+  first_page_ms: number; // The page the panel opens with, from SQLite
+  first_page_cached_ms: number; // The same page from a warm cache
+  search_ms: number;
+  export_ms: number;
+}
+
+// get_startup_metrics: how long each startup phase took this launch, in milliseconds
+export interface StartupPhase {
+  name: string;
+  started_ms: number; // Since the process started
+  duration_ms: number;
+  deferred: boolean; // Ran in the background after the app was ready
+}
+
+export interface StartupMetrics {
+  phases: StartupPhase[];
+  ready_ms: number | null; // When the hotkey could first open the panel
+}
+
+// Per-app paste profiles, applied by hide_and_paste and copy_as('auto')
+export interface PasteProfile {
+  id: string;
+  app: string; // Bundle id or app name
+  format: 'Markdown' | 'Rtf' | 'Plain' | 'Html' | null;
+  transforms: string[]; // Backend TextTransform names, e.g. 'TrimWhitespace'
+  enabled: boolean;
+}
+
+export interface PasteProfilesData {
+  profiles: PasteProfile[];
+}
+
+// AI workflows: steps run in order, each feeding the next
+export interface AIWorkflowStep {
+  id: string;
+  type: 'ai' | 'transform';
+  targetId: string;       // AI preset / custom prompt id, or a TextTransform name
+  name: string;
+  systemPrompt?: string;  // Snapshot used if the preset was never saved
+}
+
+export interface AIWorkflow {
+  id: string;
+  name: string;
+  description: string;
+  steps: AIWorkflowStep[];
+  created_at: string;
+  updated_at: string;
+  enabled: boolean;
+}
+
+export interface AIWorkflowsData {
+  workflows: AIWorkflow[];
+}
+
+export interface WorkflowStepResult {
+  step_id: string;
+  name: string;
+  output: string;
+  tokens_used: number;
+}
+
+export interface WorkflowRunResult {
+  workflow_id: string;
+  output: string;
+  steps: WorkflowStepResult[];
+  total_tokens: number;
+  tokens_remaining: number | null;
+}